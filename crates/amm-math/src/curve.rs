@@ -0,0 +1,241 @@
+use fixed::types::I64F64;
+
+use crate::{math, MathError, Result};
+
+/// 权重的总和（基点）
+pub const TOTAL_WEIGHT: u16 = 10000;
+
+/// 稳定币曲线中的代币数量
+const N_COINS: u128 = 2;
+
+/// 牛顿迭代的最大次数
+const MAX_ITERATIONS: usize = 255;
+
+/// 不随时间变化的定价曲线，LBP按当前时间的权重换算为加权曲线后计算
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Curve {
+    /// 恒定乘积曲线 x * y = k
+    ConstantProduct,
+    /// Curve风格的稳定币曲线
+    StableSwap { amp: u64 },
+    /// Balancer风格的加权曲线 x^w_a * y^w_b = k
+    Weighted { weight_a: u16 },
+}
+
+/// 按给定时间线性插值LBP的代币A权重
+pub fn lbp_weight_a(start_weight_a: u16, end_weight_a: u16, start_time: i64, end_time: i64, timestamp: i64) -> u16 {
+    let elapsed = timestamp.clamp(start_time, end_time) - start_time;
+    let duration = end_time - start_time;
+    (start_weight_a as i64 + (end_weight_a as i64 - start_weight_a as i64) * elapsed / duration) as u16
+}
+
+impl Curve {
+    /// 给定扣费后的输入数量，计算输出数量（向下取整，对池子有利）
+    /// swap_a 表示输入为代币A，只有加权曲线需要区分方向
+    pub fn swap_output(&self, swap_a: bool, taxed_input: u64, reserve_in: u64, reserve_out: u64) -> Result<u64> {
+        match *self {
+            Curve::ConstantProduct => {
+                let output = taxed_input as u128 * reserve_out as u128
+                    / (reserve_in as u128 + taxed_input as u128);
+                Ok(output as u64)
+            }
+            Curve::StableSwap { amp } => {
+                let d = compute_d(amp, reserve_in as u128, reserve_out as u128)?;
+                let new_reserve_in = reserve_in as u128 + taxed_input as u128;
+                let new_reserve_out = compute_y(amp, new_reserve_in, d)?;
+                // 迭代求得的储备与实际储备有误差，同时按迭代求得的交换前储备计算，取较小的输出，
+                // 再多扣1个单位以抵消舍入误差，输入为0时不会有输出
+                let model_reserve_out = compute_y(amp, reserve_in as u128, d)?;
+                let output = (reserve_out as u128).min(model_reserve_out).saturating_sub(new_reserve_out);
+                Ok(output.saturating_sub(1) as u64)
+            }
+            Curve::Weighted { weight_a } => {
+                // out = R_out * (1 - (R_in / (R_in + x))^(w_in / w_out))
+                let (weight_in, weight_out) = weights(weight_a, swap_a);
+                let base = I64F64::from_num(reserve_in) / (I64F64::from_num(reserve_in) + I64F64::from_num(taxed_input));
+                let factor = math::pow(base, weight_in / weight_out).ok_or(MathError::Overflow)?;
+                let output = I64F64::from_num(reserve_out)
+                    .checked_mul(I64F64::ONE - factor.min(I64F64::ONE))
+                    .ok_or(MathError::Overflow)?;
+                // 多扣1个单位以抵消幂运算的近似误差
+                Ok(output.to_num::<u64>().saturating_sub(1))
+            }
+        }
+    }
+
+    /// 给定输出数量，反推所需的扣费后输入数量（向上取整，对池子有利）
+    pub fn swap_input(&self, swap_a: bool, output: u64, reserve_in: u64, reserve_out: u64) -> Result<u64> {
+        if output >= reserve_out {
+            return Err(MathError::InsufficientLiquidity);
+        }
+        match *self {
+            Curve::ConstantProduct => {
+                let input = (output as u128 * reserve_in as u128).div_ceil((reserve_out - output) as u128);
+                u64::try_from(input).map_err(|_| MathError::Overflow)
+            }
+            Curve::StableSwap { amp } => {
+                let d = compute_d(amp, reserve_in as u128, reserve_out as u128)?;
+                let new_reserve_out = (reserve_out - output) as u128;
+                let new_reserve_in = compute_y(amp, new_reserve_out, d)?;
+                // 与 swap_output 对称，取迭代误差下较大的输入
+                let model_reserve_in = compute_y(amp, reserve_out as u128, d)?;
+                let input = new_reserve_in.saturating_sub((reserve_in as u128).min(model_reserve_in)) + 1;
+                u64::try_from(input).map_err(|_| MathError::Overflow)
+            }
+            Curve::Weighted { weight_a } => {
+                // x = R_in * ((R_out / (R_out - out))^(w_out / w_in) - 1)
+                let (weight_in, weight_out) = weights(weight_a, swap_a);
+                let base = I64F64::from_num(reserve_out) / I64F64::from_num(reserve_out - output);
+                let factor = math::pow(base, weight_out / weight_in).ok_or(MathError::Overflow)?;
+                let input = I64F64::from_num(reserve_in)
+                    .checked_mul(factor.max(I64F64::ONE) - I64F64::ONE)
+                    .and_then(|v| v.ceil().checked_to_num::<u64>())
+                    .ok_or(MathError::Overflow)?;
+                Ok(input + 1)
+            }
+        }
+    }
+
+    /// 计算池子的不变量，交换后不变量不得减少
+    pub fn invariant(&self, reserve_a: u64, reserve_b: u64) -> Result<u128> {
+        match *self {
+            Curve::ConstantProduct => Ok(reserve_a as u128 * reserve_b as u128),
+            Curve::StableSwap { amp } => compute_d(amp, reserve_a as u128, reserve_b as u128),
+            Curve::Weighted { weight_a } => Ok(weighted_mean(weight_a, reserve_a, reserve_b)? as u128),
+        }
+    }
+
+    /// 不变量自身的计算误差：稳定币曲线的D由牛顿迭代求得，误差不超过1
+    pub fn invariant_tolerance(&self) -> u128 {
+        match self {
+            Curve::StableSwap { .. } => 1,
+            _ => 0,
+        }
+    }
+
+    /// 校验交换后不变量的增长并返回增量：只按扣除LP手续费后的输入计算的储备已不得使不变量减少
+    /// （向有利于池子的方向放宽计算误差），计入留在池中的手续费后的实际储备同样不得低于交换前
+    pub fn check_invariant_growth(
+        &self,
+        invariant_before: u128,
+        (fee_excluded_a, fee_excluded_b): (u64, u64),
+        (reserve_a_after, reserve_b_after): (u64, u64),
+    ) -> Result<u128> {
+        let invariant_without_fee = self.invariant(fee_excluded_a, fee_excluded_b)?;
+        let invariant_after = self.invariant(reserve_a_after, reserve_b_after)?;
+        if invariant_without_fee + self.invariant_tolerance() < invariant_before || invariant_after < invariant_before {
+            return Err(MathError::InvariantViolated);
+        }
+
+        Ok(invariant_after - invariant_before)
+    }
+
+    /// 计算一笔存款对应的流动性数量
+    pub fn deposit_liquidity(&self, amount_a: u64, amount_b: u64) -> Result<u64> {
+        match *self {
+            Curve::ConstantProduct => Ok(math::isqrt(amount_a as u128 * amount_b as u128) as u64),
+            // 平衡状态下 D/2 与 sqrt(a * b) 相等，保证两种曲线的LP单位一致
+            Curve::StableSwap { amp } => {
+                let liquidity = compute_d(amp, amount_a as u128, amount_b as u128)? / N_COINS;
+                u64::try_from(liquidity).map_err(|_| MathError::Overflow)
+            }
+            // 加权几何平均，50/50权重时与 sqrt(a * b) 相同
+            Curve::Weighted { weight_a } => weighted_mean(weight_a, amount_a, amount_b),
+        }
+    }
+}
+
+/// 按交换方向返回 (输入侧权重, 输出侧权重)
+fn weights(weight_a: u16, swap_a: bool) -> (I64F64, I64F64) {
+    let weight_b = TOTAL_WEIGHT - weight_a;
+    if swap_a {
+        (I64F64::from_num(weight_a), I64F64::from_num(weight_b))
+    } else {
+        (I64F64::from_num(weight_b), I64F64::from_num(weight_a))
+    }
+}
+
+/// 加权几何平均 a^w_a * b^w_b，在对数空间中计算避免溢出
+fn weighted_mean(weight_a: u16, amount_a: u64, amount_b: u64) -> Result<u64> {
+    if amount_a == 0 || amount_b == 0 {
+        return Ok(0);
+    }
+    let total = I64F64::from_num(TOTAL_WEIGHT);
+    let weight_a = I64F64::from_num(weight_a) / total;
+    let weight_b = I64F64::ONE - weight_a;
+    let ln_a = math::ln(I64F64::from_num(amount_a)).ok_or(MathError::Overflow)?;
+    let ln_b = math::ln(I64F64::from_num(amount_b)).ok_or(MathError::Overflow)?;
+    math::exp(ln_a * weight_a + ln_b * weight_b)
+        .and_then(|v| v.checked_to_num::<u64>())
+        .ok_or(MathError::Overflow)
+}
+
+/// 牛顿迭代求解稳定币不变量 D
+fn compute_d(amp: u64, x: u128, y: u128) -> Result<u128> {
+    let sum = x + y;
+    if sum == 0 {
+        return Ok(0);
+    }
+    if x == 0 || y == 0 {
+        return Err(MathError::InsufficientLiquidity);
+    }
+
+    let ann = amp as u128 * N_COINS;
+    let mut d = sum;
+    for _ in 0..MAX_ITERATIONS {
+        // d_p = D^3 / (4 * x * y)
+        let d_p = d
+            .checked_mul(d)
+            .and_then(|v| v.checked_div(x * N_COINS))
+            .and_then(|v| v.checked_mul(d))
+            .and_then(|v| v.checked_div(y * N_COINS))
+            .ok_or(MathError::Overflow)?;
+        let prev = d;
+        // D = (Ann * S + 2 * d_p) * D / ((Ann - 1) * D + 3 * d_p)
+        let numerator = ann
+            .checked_mul(sum)
+            .and_then(|v| v.checked_add(d_p * N_COINS))
+            .and_then(|v| v.checked_mul(d))
+            .ok_or(MathError::Overflow)?;
+        let denominator = (ann - 1)
+            .checked_mul(d)
+            .and_then(|v| v.checked_add((N_COINS + 1) * d_p))
+            .ok_or(MathError::Overflow)?;
+        d = numerator / denominator;
+        if d.abs_diff(prev) <= 1 {
+            return Ok(d);
+        }
+    }
+    Err(MathError::Overflow)
+}
+
+/// 已知一侧储备和不变量 D，牛顿迭代求解另一侧储备
+fn compute_y(amp: u64, x: u128, d: u128) -> Result<u128> {
+    let ann = amp as u128 * N_COINS;
+    // c = D^3 / (4 * x * Ann)
+    let c = d
+        .checked_mul(d)
+        .and_then(|v| v.checked_div(x * N_COINS))
+        .and_then(|v| v.checked_mul(d))
+        .and_then(|v| v.checked_div(ann * N_COINS))
+        .ok_or(MathError::Overflow)?;
+    let b = x + d / ann;
+
+    let mut y = d;
+    for _ in 0..MAX_ITERATIONS {
+        let prev = y;
+        // y = (y^2 + c) / (2y + b - D)
+        let numerator = y
+            .checked_mul(y)
+            .and_then(|v| v.checked_add(c))
+            .ok_or(MathError::Overflow)?;
+        let denominator = (2 * y + b)
+            .checked_sub(d)
+            .ok_or(MathError::Overflow)?;
+        y = numerator / denominator;
+        if y.abs_diff(prev) <= 1 {
+            return Ok(y);
+        }
+    }
+    Err(MathError::Overflow)
+}
@@ -0,0 +1,98 @@
+use fixed::types::I64F64;
+
+/// 成交量折扣档位：(30天成交量下限, 手续费折扣基点)，成交量以代币B的最小单位计，按下限从高到低排列
+pub const VOLUME_DISCOUNT_TIERS: [(u64, u16); 3] = [
+    (100_000_000_000, 5000), // 成交量 >= 10^11，手续费减半
+    (10_000_000_000, 2500),  // 成交量 >= 10^10，手续费减25%
+    (1_000_000_000, 1000),   // 成交量 >= 10^9，手续费减10%
+];
+
+/// 按费率（基点）计算费用，向下取整
+pub fn fee_amount(amount: u64, fee_bps: u16) -> u64 {
+    (amount as u128 * fee_bps as u128 / 10000) as u64
+}
+
+/// 费用中按比例（基点）划出的部分，如协议分成和无常损失补偿分成
+pub fn fee_share(fee_amount: u64, share_bps: u16) -> u64 {
+    (fee_amount as u128 * share_bps as u128 / 10000) as u64
+}
+
+/// 交易者30天成交量对应的折扣档位（基点）
+pub fn volume_discount_bps(volume: u64) -> u16 {
+    VOLUME_DISCOUNT_TIERS
+        .iter()
+        .find(|(min_volume, _)| volume >= *min_volume)
+        .map_or(0, |(_, discount_bps)| *discount_bps)
+}
+
+/// 对费率应用折扣，10000表示完全免除手续费
+pub fn apply_discount(fee_rate_bps: u16, discount_bps: u16) -> u16 {
+    (fee_rate_bps as u32 * (10000 - discount_bps.min(10000) as u32) / 10000) as u16
+}
+
+/// 动态费率（基于池子深度和交易量）：base_fee + adjustment * (input / reserve)^2，限制在 [min, max] 内
+pub fn dynamic_fee_bps(
+    (min_fee_bps, base_fee_bps, max_fee_bps): (u16, u16, u16),
+    adjustment_factor: u16,
+    input_amount: u64,
+    reserve: u64,
+) -> u16 {
+    // 计算交易量占池子的比例
+    let ratio = if reserve == 0 {
+        I64F64::from_num(1) // 防止除以0
+    } else {
+        I64F64::saturating_from_num(input_amount) / I64F64::saturating_from_num(reserve)
+    };
+
+    // 用二次曲线调整费率，调整系数放大1000倍
+    let adjustment = I64F64::from_num(adjustment_factor) / I64F64::from_num(1000);
+    let base_fee = I64F64::from_num(base_fee_bps);
+    let fee_adjustment = adjustment.saturating_mul(ratio).saturating_mul(ratio);
+
+    // 计算最终费率，确保在min和max之间；远超储备的输入按饱和值计算，不会溢出
+    let calculated_fee = base_fee.saturating_add(fee_adjustment.saturating_mul(I64F64::from_num(10000)));
+    let fee_bps = calculated_fee.saturating_to_num::<u16>();
+
+    fee_bps.clamp(min_fee_bps, max_fee_bps)
+}
+
+/// 分层费率（基于交易量大小）：小额交易使用最高费率，大额交易使用最低费率
+pub fn tiered_fee_bps((min_fee_bps, base_fee_bps, max_fee_bps): (u16, u16, u16), input_amount: u64) -> u16 {
+    // 定义几个交易量分层阈值
+    let tier1 = 1_000 * 10u64.pow(6); // 1,000 tokens (假设6位小数)
+    let tier2 = 10_000 * 10u64.pow(6); // 10,000 tokens
+    let tier3 = 100_000 * 10u64.pow(6); // 100,000 tokens
+
+    // 根据交易量确定费率
+    if input_amount < tier1 {
+        max_fee_bps // 小额交易，使用最高费率
+    } else if input_amount < tier2 {
+        // 线性插值第一层和第二层之间
+        (max_fee_bps + base_fee_bps) / 2
+    } else if input_amount < tier3 {
+        base_fee_bps // 中等交易，使用基础费率
+    } else {
+        min_fee_bps // 大额交易，使用最低费率
+    }
+}
+
+/// 按波动率调整的费率：低波动率使用最低费率，高波动率使用最高费率，中间线性插值
+pub fn volatility_adjusted_fee_bps(min_fee_bps: u16, max_fee_bps: u16, volatility: u16) -> u16 {
+    // 波动率门槛
+    let low_threshold = 50; // 波动率低于5%
+    let high_threshold = 200; // 波动率高于20%
+
+    // 根据波动率调整费率
+    if volatility < low_threshold {
+        min_fee_bps // 低波动率，使用最低费率
+    } else if volatility > high_threshold {
+        max_fee_bps // 高波动率，使用最高费率
+    } else {
+        // 线性插值波动率与费率
+        let volatility_range = high_threshold - low_threshold;
+        let fee_range = max_fee_bps - min_fee_bps;
+        let vol_position = volatility - low_threshold;
+
+        min_fee_bps + (vol_position as u32 * fee_range as u32 / volatility_range as u32) as u16
+    }
+}
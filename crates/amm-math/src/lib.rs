@@ -0,0 +1,24 @@
+//! anchor-spl-amm 的定价数学库
+//!
+//! 定价曲线、手续费和价格影响的计算与链上程序共用，机器人和前端可以用读取到的账户状态
+//! 算出与链上一致的报价和最少输出。只依赖定点数库，不依赖标准库和Solana。
+
+#![no_std]
+
+pub mod curve;
+pub mod fee;
+pub mod math;
+pub mod price_impact;
+
+/// 计算失败的原因，链上程序映射为对应的程序错误
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MathError {
+    /// 数值溢出或迭代不收敛
+    Overflow,
+    /// 储备不足以完成计算
+    InsufficientLiquidity,
+    /// 交换后不变量减少
+    InvariantViolated,
+}
+
+pub type Result<T> = core::result::Result<T, MathError>;
@@ -0,0 +1,95 @@
+use fixed::types::I64F64;
+
+/// 对数级数展开的项数，s ≤ 1/3 时截断误差小于 1e-18
+const LN_SERIES_TERMS: u32 = 20;
+
+/// 指数泰勒展开的项数，r < ln2 时截断误差小于 1e-18
+const EXP_SERIES_TERMS: u32 = 24;
+
+/// 定点数自然对数，不依赖浮点运算以保证各验证节点结果一致
+///
+/// 先把 x 归一化为 m·2^k（m ∈ [1, 2)），再用 ln(m) = 2·atanh((m-1)/(m+1)) 的级数展开计算。
+/// x 不为正数时返回 None
+pub fn ln(x: I64F64) -> Option<I64F64> {
+    if x <= I64F64::ZERO {
+        return None;
+    }
+
+    // 1. 归一化：I64F64 有64位小数，最高有效位在第64位时 m ∈ [1, 2)
+    let bits = x.to_bits();
+    let exponent = 127 - bits.leading_zeros() as i32 - 64;
+    let mantissa = if exponent >= 0 {
+        I64F64::from_bits(bits >> exponent)
+    } else {
+        I64F64::from_bits(bits << -exponent)
+    };
+
+    // 2. 级数展开：atanh(s) = s + s^3/3 + s^5/5 + ...
+    let s = (mantissa - I64F64::ONE) / (mantissa + I64F64::ONE);
+    let s_squared = s * s;
+    let mut term = s;
+    let mut sum = I64F64::ZERO;
+    for n in 0..LN_SERIES_TERMS {
+        sum += term / I64F64::from_num(2 * n + 1);
+        term *= s_squared;
+    }
+
+    Some(sum * I64F64::from_num(2) + I64F64::LN_2 * I64F64::from_num(exponent))
+}
+
+/// 定点数自然指数，结果超出 I64F64 范围时返回 None
+///
+/// 把 x 拆成 k·ln2 + r（r ∈ [0, ln2)），用泰勒展开计算 e^r 后再乘以 2^k
+pub fn exp(x: I64F64) -> Option<I64F64> {
+    let k = x
+        .checked_div(I64F64::LN_2)
+        .and_then(|quotient| quotient.floor().checked_to_num::<i32>());
+    let Some(k) = k else {
+        // |x| 过大：负数的结果小于最小精度，正数的结果溢出
+        return x.is_negative().then_some(I64F64::ZERO);
+    };
+    // 结果小于最小精度，直接视为0
+    if k < -64 {
+        return Some(I64F64::ZERO);
+    }
+    if k >= 63 {
+        return None;
+    }
+    let r = x - I64F64::LN_2 * I64F64::from_num(k);
+
+    let mut term = I64F64::ONE;
+    let mut sum = I64F64::ONE;
+    for n in 1..EXP_SERIES_TERMS {
+        term = term * r / I64F64::from_num(n);
+        sum += term;
+    }
+
+    let bits = sum.to_bits();
+    if k >= 0 {
+        bits.checked_shl(k as u32)
+            .filter(|shifted| shifted >> k == bits)
+            .map(I64F64::from_bits)
+    } else {
+        Some(I64F64::from_bits(bits >> -k))
+    }
+}
+
+/// 定点数幂运算 base^exponent = e^(exponent·ln(base))，base 必须为正数
+pub fn pow(base: I64F64, exponent: I64F64) -> Option<I64F64> {
+    exp(ln(base)?.checked_mul(exponent)?)
+}
+
+/// 整数平方根（向下取整），牛顿迭代，不经过定点数因此两侧数量的乘积不会溢出
+pub fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    let mut x = 1u128 << ((128 - n.leading_zeros()).div_ceil(2));
+    loop {
+        let next = (x + n / x) / 2;
+        if next >= x {
+            return x;
+        }
+        x = next;
+    }
+}
@@ -0,0 +1,58 @@
+use fixed::types::I64F64;
+
+/// 交易前后价格的相对变化（1 - 交易后价格 / 交易前价格）
+pub fn price_impact(input_amount: u64, output_amount: u64, reserve_in: u64, reserve_out: u64) -> I64F64 {
+    let price_before = I64F64::from_num(reserve_out) / I64F64::from_num(reserve_in);
+    let price_after = I64F64::from_num(reserve_out - output_amount) / I64F64::from_num(reserve_in + input_amount);
+
+    I64F64::from_num(1) - (price_after / price_before)
+}
+
+/// 将价格影响转换为基点，负值按0计，不超过10000
+pub fn to_bps(price_impact: I64F64) -> u16 {
+    (price_impact.max(I64F64::ZERO) * I64F64::from_num(10000))
+        .min(I64F64::from_num(10000))
+        .to_num::<u16>()
+}
+
+/// 价格影响是否不超过最大滑点（基点）
+pub fn is_within_slippage(price_impact: I64F64, max_slippage_bps: u16) -> bool {
+    price_impact * I64F64::from_num(10000) <= I64F64::from_num(max_slippage_bps)
+}
+
+/// 成交价格（每单位输出支付的输入，Q64.64）是否不高于交易者给出的最差价格，未给出时不限制
+pub fn is_within_price_limit(input_amount: u64, output_amount: u64, max_price_x64: Option<u128>) -> bool {
+    let Some(max_price_x64) = max_price_x64 else {
+        return true;
+    };
+    // input / output <= max_price / 2^64，乘积溢出说明限价远高于成交价格
+    max_price_x64
+        .checked_mul(output_amount as u128)
+        .is_none_or(|limit| (input_amount as u128) << 64 <= limit)
+}
+
+/// 单笔交易的输入是否不超过输入端储备的最大比例（基点），0表示不限制
+pub fn is_trade_size_acceptable(max_trade_pct_bps: u16, input_amount: u64, reserve_in: u64) -> bool {
+    max_trade_pct_bps == 0 || input_amount as u128 * 10000 <= reserve_in as u128 * max_trade_pct_bps as u128
+}
+
+/// 根据价格影响动态调整输出金额，调整系数（放大1000倍）越大扣减越多，最多扣减10%
+pub fn adjust_output_for_slippage(dynamic_adjustment_factor: u16, output_amount: u64, price_impact: I64F64) -> u64 {
+    let adjustment_factor = I64F64::from_num(1)
+        - (price_impact * I64F64::from_num(dynamic_adjustment_factor) / I64F64::from_num(1000));
+
+    // 确保调整系数不会低于0.9
+    let final_adjustment = adjustment_factor.max(I64F64::from_num(0.9));
+
+    (I64F64::from_num(output_amount) * final_adjustment).to_num::<u64>()
+}
+
+/// 输出价值是否高于输入价值加费用
+pub fn is_trade_beneficial(input_value: I64F64, output_value: I64F64, fee_percentage: I64F64) -> bool {
+    output_value > input_value * (I64F64::from_num(1) + fee_percentage)
+}
+
+/// 按滑点容忍度（基点）计算最少输出，供客户端设置交换的 min_output_amount
+pub fn min_output(expected_output: u64, slippage_bps: u16) -> u64 {
+    (expected_output as u128 * (10000 - slippage_bps.min(10000) as u128) / 10000) as u64
+}
@@ -0,0 +1,150 @@
+//! 定点数 ln、exp、pow 的精度测试：与 f64 的结果比较，误差不超过下面给出的上限
+
+use amm_math::math::{exp, ln, pow};
+use fixed::types::I64F64;
+
+/// ln 的绝对误差上限，级数截断误差小于 1e-18，其余来自定点数的舍入
+const LN_ABS_TOLERANCE: f64 = 1e-15;
+
+/// exp 的相对误差上限，结果按 2^k 放大时绝对误差随之放大
+const EXP_REL_TOLERANCE: f64 = 1e-14;
+
+/// exp 的绝对误差上限，结果很小时受最小精度 2^-64 限制，相对误差没有意义
+const EXP_ABS_TOLERANCE: f64 = 1e-18;
+
+fn fixed(value: f64) -> I64F64 {
+    I64F64::from_num(value)
+}
+
+fn assert_ln(x: I64F64) {
+    let actual = ln(x).unwrap().to_num::<f64>();
+    let expected = x.to_num::<f64>().ln();
+    assert!((actual - expected).abs() <= LN_ABS_TOLERANCE, "ln({x}) = {actual}, expected {expected}");
+}
+
+/// |actual - expected| ≤ rel_tolerance·|expected| + EXP_ABS_TOLERANCE
+fn assert_close(actual: Option<I64F64>, expected: f64, rel_tolerance: f64, what: &str) {
+    let actual = actual.unwrap_or_else(|| panic!("{what} overflowed")).to_num::<f64>();
+    let error = (actual - expected).abs();
+    let bound = rel_tolerance * expected.abs() + EXP_ABS_TOLERANCE;
+    assert!(error <= bound, "{what} = {actual}, expected {expected}, error {error:e} > {bound:e}");
+}
+
+fn assert_exp(x: f64) {
+    assert_close(exp(fixed(x)), x.exp(), EXP_REL_TOLERANCE, &format!("exp({x})"));
+}
+
+/// pow 先计算 exponent·ln(base)，ln 的绝对误差按指数放大为结果的相对误差
+fn assert_pow(base: f64, exponent: f64) {
+    let rel_tolerance = EXP_REL_TOLERANCE + exponent.abs() * LN_ABS_TOLERANCE;
+    assert_close(pow(fixed(base), fixed(exponent)), base.powf(exponent), rel_tolerance, &format!("{base}^{exponent}"));
+}
+
+#[test]
+fn ln_known_values() {
+    assert_eq!(ln(I64F64::ONE), Some(I64F64::ZERO));
+    assert_eq!(ln(fixed(2.0)), Some(I64F64::LN_2));
+    assert_eq!(ln(fixed(0.5)), Some(-I64F64::LN_2));
+    for x in [core::f64::consts::E, 3.0, 10.0, 1234.5678, 1e9] {
+        assert_ln(fixed(x));
+    }
+}
+
+#[test]
+fn ln_near_one() {
+    // ln(1 + d) ≈ d，比较 ln_1p 以避免 f64 在 1 附近的抵消误差
+    for d in [1e-3, 1e-6, 1e-9, -1e-9, -1e-6, -1e-3] {
+        let x = I64F64::ONE + fixed(d);
+        let actual = ln(x).unwrap().to_num::<f64>();
+        let expected = (x - I64F64::ONE).to_num::<f64>().ln_1p();
+        assert!((actual - expected).abs() <= LN_ABS_TOLERANCE, "ln(1 + {d}) = {actual}, expected {expected}");
+    }
+    // 最小的步长
+    let x = I64F64::ONE + I64F64::DELTA;
+    assert!(ln(x).unwrap() >= I64F64::ZERO);
+    assert!(ln(x).unwrap() <= I64F64::DELTA * 2);
+}
+
+#[test]
+fn ln_near_zero_and_large() {
+    for x in [1e-3, 1e-9, 1e-15] {
+        assert_ln(fixed(x));
+    }
+    // 最小的正数是 2^-64
+    let expected = -64.0 * core::f64::consts::LN_2;
+    assert!((ln(I64F64::DELTA).unwrap().to_num::<f64>() - expected).abs() <= LN_ABS_TOLERANCE);
+
+    assert_ln(fixed(2f64.powi(62)));
+    assert_ln(I64F64::MAX);
+}
+
+#[test]
+fn ln_rejects_non_positive() {
+    assert_eq!(ln(I64F64::ZERO), None);
+    assert_eq!(ln(-I64F64::ONE), None);
+    assert_eq!(ln(I64F64::MIN), None);
+}
+
+#[test]
+fn exp_known_values() {
+    assert_eq!(exp(I64F64::ZERO), Some(I64F64::ONE));
+    assert_close(exp(I64F64::LN_2), 2.0, EXP_REL_TOLERANCE, "exp(ln2)");
+    for x in [1.0, -1.0, 1e-9, -1e-9, 0.5, 2.5, -7.25] {
+        assert_exp(x);
+    }
+}
+
+#[test]
+fn exp_large_exponents() {
+    // I64F64 的最大值约为 2^63，ln(2^63) ≈ 43.668
+    for x in [20.0, 40.0, 43.0, 43.6, -20.0, -40.0] {
+        assert_exp(x);
+    }
+    assert_eq!(exp(fixed(43.7)), None);
+    assert_eq!(exp(fixed(1000.0)), None);
+    assert_eq!(exp(I64F64::MAX), None);
+
+    // 结果小于最小精度时为0，不会溢出
+    assert_eq!(exp(fixed(-50.0)), Some(I64F64::ZERO));
+    assert_eq!(exp(fixed(-1e12)), Some(I64F64::ZERO));
+    assert_eq!(exp(I64F64::MIN), Some(I64F64::ZERO));
+}
+
+#[test]
+fn exp_inverts_ln() {
+    for x in [1e-6, 0.3, 1.0, 7.0, 1e6, 1e12] {
+        // ln 的绝对误差成为结果的相对误差
+        let rel_tolerance = EXP_REL_TOLERANCE + LN_ABS_TOLERANCE;
+        assert_close(exp(ln(fixed(x)).unwrap()), x, rel_tolerance, &format!("exp(ln({x}))"));
+    }
+}
+
+#[test]
+fn pow_known_values() {
+    assert_pow(2.0, 10.0);
+    assert_pow(4.0, 0.5);
+    assert_pow(10.0, -3.0);
+    assert_eq!(pow(fixed(123.0), I64F64::ZERO), Some(I64F64::ONE));
+    // 加权曲线中常见的权重比
+    assert_pow(1.05, 0.8 / 0.2);
+    assert_pow(0.95, 0.2 / 0.8);
+}
+
+#[test]
+fn pow_large_exponents() {
+    // 接近1的底数和很大的指数
+    assert_pow(1.0001, 10_000.0);
+    assert_pow(0.9999, 100_000.0);
+    assert_pow(2.0, 62.5);
+}
+
+#[test]
+fn pow_overflow_returns_none() {
+    assert_eq!(pow(fixed(2.0), fixed(63.0)), None);
+    assert_eq!(pow(fixed(10.0), fixed(20.0)), None);
+    // ln(base)·exponent 本身溢出
+    assert_eq!(pow(fixed(2f64.powi(62)), fixed(2f64.powi(60))), None);
+    // 底数必须为正数
+    assert_eq!(pow(I64F64::ZERO, fixed(2.0)), None);
+    assert_eq!(pow(fixed(-2.0), fixed(2.0)), None);
+}
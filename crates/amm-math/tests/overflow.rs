@@ -0,0 +1,44 @@
+//! 大额储备下的曲线计算：乘积在u128中计算不会溢出，结果超出u64时返回 MathError::Overflow 而不是panic
+
+use amm_math::{curve::Curve, MathError};
+
+const HALF: u64 = u64::MAX / 2;
+
+#[test]
+fn invariant_of_max_reserves_fits_in_u128() {
+    let invariant = Curve::ConstantProduct.invariant(u64::MAX, u64::MAX).unwrap();
+    assert_eq!(invariant, u64::MAX as u128 * u64::MAX as u128);
+    assert_eq!(Curve::ConstantProduct.deposit_liquidity(u64::MAX, u64::MAX), Ok(u64::MAX));
+}
+
+#[test]
+fn large_pools_can_trade() {
+    let curve = Curve::ConstantProduct;
+    let input = HALF / 2;
+    let output = curve.swap_output(true, input, HALF, HALF).unwrap();
+    // x·y = k：输入为储备的一半时输出为储备的三分之一
+    assert_eq!(output, HALF / 3);
+
+    let before = curve.invariant(HALF, HALF).unwrap();
+    let after = (HALF + input, HALF - output);
+    assert!(curve.check_invariant_growth(before, after, after).is_ok());
+
+    // 输出向下取整，按输出反推的输入不会超过实际输入
+    assert!(curve.swap_input(true, output, HALF, HALF).unwrap() <= input + 1);
+}
+
+#[test]
+fn input_above_u64_is_an_error() {
+    // 从很小的输出端储备中几乎取空，所需的输入超出u64
+    assert_eq!(Curve::ConstantProduct.swap_input(true, 3, u64::MAX, 4), Err(MathError::Overflow));
+    assert!(Curve::StableSwap { amp: 100 }.swap_input(true, 3, u64::MAX, 4).is_err());
+}
+
+#[test]
+fn stable_swap_overflow_is_an_error() {
+    // D² 超出u128时牛顿迭代返回错误
+    let curve = Curve::StableSwap { amp: 10_000 };
+    assert_eq!(curve.invariant(u64::MAX, u64::MAX), Err(MathError::Overflow));
+    assert_eq!(curve.swap_output(true, 1, u64::MAX, u64::MAX), Err(MathError::Overflow));
+    assert!(curve.swap_output(true, 1_000, 1 << 40, 1 << 40).is_ok());
+}
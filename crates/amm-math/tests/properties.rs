@@ -0,0 +1,211 @@
+//! 定价曲线和手续费的性质测试：在随机的储备和数量上检查不变量不减少、没有免费的输出、
+//! 舍入总是对池子有利，以及费率随输入单调变化
+
+use amm_math::{
+    curve::{Curve, TOTAL_WEIGHT},
+    fee, MathError,
+};
+use proptest::prelude::*;
+
+/// 储备在1000到10^15之间，覆盖小池子和18位小数以外的常见代币
+fn reserve() -> impl Strategy<Value = u64> {
+    1_000u64..1_000_000_000_000_000
+}
+
+fn curve() -> impl Strategy<Value = Curve> {
+    prop_oneof![
+        Just(Curve::ConstantProduct),
+        (1u64..=10_000).prop_map(|amp| Curve::StableSwap { amp }),
+        (1_000u16..=9_000).prop_map(|weight_a| Curve::Weighted { weight_a }),
+    ]
+}
+
+/// 交换后的储备 (A, B)
+fn reserves_after(swap_a: bool, reserve_in: u64, reserve_out: u64, input: u64, output: u64) -> (u64, u64) {
+    let (new_in, new_out) = (reserve_in + input, reserve_out - output);
+    if swap_a {
+        (new_in, new_out)
+    } else {
+        (new_out, new_in)
+    }
+}
+
+/// 交换前的储备 (A, B)
+fn reserves_before(swap_a: bool, reserve_in: u64, reserve_out: u64) -> (u64, u64) {
+    if swap_a {
+        (reserve_in, reserve_out)
+    } else {
+        (reserve_out, reserve_in)
+    }
+}
+
+proptest! {
+    #[test]
+    fn invariant_never_decreases(
+        curve in curve(),
+        swap_a: bool,
+        reserve_in in reserve(),
+        reserve_out in reserve(),
+        input_pct in 1u64..=5_000,
+    ) {
+        let input = (reserve_in as u128 * input_pct as u128 / 10_000).max(1) as u64;
+        let Ok(output) = curve.swap_output(swap_a, input, reserve_in, reserve_out) else {
+            return Ok(());
+        };
+        prop_assert!(output < reserve_out);
+
+        let (before_a, before_b) = reserves_before(swap_a, reserve_in, reserve_out);
+        let before = curve.invariant(before_a, before_b).unwrap();
+        let (after_a, after_b) = reserves_after(swap_a, reserve_in, reserve_out, input, output);
+        let growth = curve.check_invariant_growth(before, (after_a, after_b), (after_a, after_b));
+        prop_assert!(growth.is_ok(), "invariant decreased: {:?}", growth);
+    }
+
+    #[test]
+    fn no_free_output(
+        curve in curve(),
+        swap_a: bool,
+        reserve_in in reserve(),
+        reserve_out in reserve(),
+    ) {
+        prop_assert_eq!(curve.swap_output(swap_a, 0, reserve_in, reserve_out).unwrap_or(0), 0);
+    }
+
+    #[test]
+    fn exact_output_costs_at_least_exact_input(
+        curve in curve(),
+        swap_a: bool,
+        reserve_in in reserve(),
+        reserve_out in reserve(),
+        output_pct in 1u64..=5_000,
+    ) {
+        // 按精确输出反推的输入再按精确输入交换，得到的输出不少于请求的输出
+        let output = (reserve_out as u128 * output_pct as u128 / 10_000).max(1) as u64;
+        let Ok(input) = curve.swap_input(swap_a, output, reserve_in, reserve_out) else {
+            return Ok(());
+        };
+        let Some(new_reserve_in) = reserve_in.checked_add(input) else {
+            return Ok(());
+        };
+        let Ok(round_trip) = curve.swap_output(swap_a, input, reserve_in, reserve_out) else {
+            return Ok(());
+        };
+        // 加权曲线的幂运算是近似值，允许相对误差 1e-12，链上另有不变量检查兜底
+        let tolerance = match curve {
+            Curve::Weighted { .. } => output / 1_000_000_000_000 + 1,
+            _ => 1,
+        };
+        prop_assert!(round_trip + tolerance >= output, "paid {} for {}, got back {}", input, output, round_trip);
+        prop_assert!(new_reserve_in > reserve_in);
+    }
+
+    #[test]
+    fn constant_product_rounds_down(
+        reserve_in in reserve(),
+        reserve_out in reserve(),
+        input in 1u64..1_000_000_000_000,
+    ) {
+        // 输出向下取整：output * (R_in + x) <= x * R_out
+        let output = Curve::ConstantProduct.swap_output(true, input, reserve_in, reserve_out).unwrap();
+        prop_assert!(output as u128 * (reserve_in as u128 + input as u128) <= input as u128 * reserve_out as u128);
+
+        if output > 0 && output < reserve_out {
+            // 输入向上取整：x * (R_out - out) >= out * R_in
+            let required = Curve::ConstantProduct.swap_input(true, output, reserve_in, reserve_out).unwrap();
+            prop_assert!(required as u128 * (reserve_out - output) as u128 >= output as u128 * reserve_in as u128);
+            prop_assert!(required <= input);
+        }
+    }
+
+    #[test]
+    fn output_is_monotonic_in_input(
+        curve in curve(),
+        swap_a: bool,
+        reserve_in in reserve(),
+        reserve_out in reserve(),
+        input in 1u64..1_000_000_000_000,
+        extra in 1u64..1_000_000_000,
+    ) {
+        let (Ok(smaller), Ok(larger)) = (
+            curve.swap_output(swap_a, input, reserve_in, reserve_out),
+            curve.swap_output(swap_a, input + extra, reserve_in, reserve_out),
+        ) else {
+            return Ok(());
+        };
+        prop_assert!(larger >= smaller);
+    }
+
+    #[test]
+    fn output_exhausting_the_pool_is_rejected(
+        curve in curve(),
+        swap_a: bool,
+        reserve_in in reserve(),
+        reserve_out in reserve(),
+        excess in 0u64..1_000,
+    ) {
+        prop_assert_eq!(
+            curve.swap_input(swap_a, reserve_out + excess, reserve_in, reserve_out),
+            Err(MathError::InsufficientLiquidity)
+        );
+    }
+
+    #[test]
+    fn fee_is_monotonic(amount in 0u64..u64::MAX / 2, extra in 0u64..u64::MAX / 2, bps in 0u16..=10_000, extra_bps in 0u16..=10_000) {
+        let higher_bps = bps.saturating_add(extra_bps).min(10_000);
+        prop_assert!(fee::fee_amount(amount, bps) <= fee::fee_amount(amount + extra, bps));
+        prop_assert!(fee::fee_amount(amount, bps) <= fee::fee_amount(amount, higher_bps));
+        prop_assert!(fee::fee_amount(amount, bps) <= amount);
+        prop_assert!(fee::fee_share(fee::fee_amount(amount, bps), bps) <= fee::fee_amount(amount, bps));
+    }
+
+    #[test]
+    fn discount_never_raises_the_fee(fee_bps in 0u16..=10_000, volume: u64, extra_volume: u64) {
+        let discount = fee::volume_discount_bps(volume);
+        let larger_discount = fee::volume_discount_bps(volume.saturating_add(extra_volume));
+        prop_assert!(larger_discount >= discount);
+        prop_assert!(fee::apply_discount(fee_bps, larger_discount) <= fee::apply_discount(fee_bps, discount));
+        prop_assert!(fee::apply_discount(fee_bps, discount) <= fee_bps);
+    }
+
+    #[test]
+    fn dynamic_fee_is_bounded_and_monotonic(
+        min in 0u16..=100,
+        base in 0u16..=100,
+        max in 100u16..=1_000,
+        adjustment_factor in 0u16..=10_000,
+        reserve in reserve(),
+        input: u64,
+        extra: u64,
+    ) {
+        let range = (min, min.max(base), max);
+        let fee_bps = fee::dynamic_fee_bps(range, adjustment_factor, input, reserve);
+        let larger_fee_bps = fee::dynamic_fee_bps(range, adjustment_factor, input.saturating_add(extra), reserve);
+        prop_assert!((min..=max).contains(&fee_bps));
+        prop_assert!(larger_fee_bps >= fee_bps);
+    }
+
+    #[test]
+    fn tiered_fee_decreases_with_size(min in 0u16..=100, base in 100u16..=300, max in 300u16..=1_000, input: u64, extra: u64) {
+        let range = (min, base, max);
+        let fee_bps = fee::tiered_fee_bps(range, input);
+        prop_assert!((min..=max).contains(&fee_bps));
+        prop_assert!(fee::tiered_fee_bps(range, input.saturating_add(extra)) <= fee_bps);
+    }
+
+    #[test]
+    fn volatility_fee_increases_with_volatility(min in 0u16..=100, max in 100u16..=1_000, volatility: u16, extra: u16) {
+        let fee_bps = fee::volatility_adjusted_fee_bps(min, max, volatility);
+        prop_assert!((min..=max).contains(&fee_bps));
+        prop_assert!(fee::volatility_adjusted_fee_bps(min, max, volatility.saturating_add(extra)) >= fee_bps);
+    }
+
+    #[test]
+    fn balanced_deposits_agree_across_curves(amount in 1_000u64..1_000_000_000_000) {
+        // 平衡存款时三种曲线铸造的流动性一致，切换曲线不改变LP单位
+        let constant_product = Curve::ConstantProduct.deposit_liquidity(amount, amount).unwrap();
+        let stable = Curve::StableSwap { amp: 100 }.deposit_liquidity(amount, amount).unwrap();
+        let weighted = Curve::Weighted { weight_a: TOTAL_WEIGHT / 2 }.deposit_liquidity(amount, amount).unwrap();
+        prop_assert!(constant_product.abs_diff(stable) <= 1);
+        prop_assert!(constant_product.abs_diff(weighted) <= constant_product / 1_000_000 + 1);
+    }
+}
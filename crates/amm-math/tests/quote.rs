@@ -0,0 +1,67 @@
+//! 链下报价：按链上交换的顺序组合手续费、曲线和价格影响，从读取到的储备算出确定的输出和最少输出
+
+use amm_math::{curve::Curve, fee, price_impact, MathError};
+
+const RESERVE_IN: u64 = 1_000_000;
+const RESERVE_OUT: u64 = 4_000_000;
+
+/// 与链上精确输入交换相同的步骤：协议分成不进入池子，LP手续费留在池中但不参与定价
+fn quote(input: u64, fee_bps: u16, protocol_share_bps: u16) -> (u64, u64, u64) {
+    let fee_amount = fee::fee_amount(input, fee_bps);
+    let protocol_fee = fee::fee_share(fee_amount, protocol_share_bps);
+    let pool_received = input - protocol_fee;
+    let taxed_input = pool_received - (fee_amount - protocol_fee);
+    let output = Curve::ConstantProduct.swap_output(true, taxed_input, RESERVE_IN, RESERVE_OUT).unwrap();
+    (pool_received, taxed_input, output)
+}
+
+#[test]
+fn exact_input_quote() {
+    let (pool_received, taxed_input, output) = quote(10_000, 30, 2000);
+    assert_eq!((pool_received, taxed_input), (9_994, 9_970));
+    // 9970 · 4·10^6 / (10^6 + 9970)，向下取整
+    assert_eq!(output, 39_486);
+    // 0.5% 的滑点容忍度
+    assert_eq!(price_impact::min_output(output, 50), 39_288);
+
+    let impact = price_impact::price_impact(pool_received, output, RESERVE_IN, RESERVE_OUT);
+    assert_eq!(price_impact::to_bps(impact), 196);
+    assert!(price_impact::is_within_slippage(impact, 200));
+    assert!(!price_impact::is_within_slippage(impact, 150));
+}
+
+#[test]
+fn exact_output_quote() {
+    // 39000 · 10^6 / (4·10^6 - 39000)，向上取整
+    let input = Curve::ConstantProduct.swap_input(true, 39_000, RESERVE_IN, RESERVE_OUT).unwrap();
+    assert_eq!(input, 9_846);
+    assert!(Curve::ConstantProduct.swap_output(true, input, RESERVE_IN, RESERVE_OUT).unwrap() >= 39_000);
+}
+
+#[test]
+fn discounts_lower_the_fee() {
+    assert_eq!(fee::volume_discount_bps(0), 0);
+    assert_eq!(fee::volume_discount_bps(100_000_000_000), 5000);
+    assert_eq!(fee::apply_discount(30, fee::volume_discount_bps(100_000_000_000)), 15);
+    assert!(quote(10_000, 15, 0).2 > quote(10_000, 30, 0).2);
+}
+
+#[test]
+fn impossible_quotes_are_rejected() {
+    // 不能取走输出端的全部储备
+    assert_eq!(
+        Curve::ConstantProduct.swap_input(true, RESERVE_OUT, RESERVE_IN, RESERVE_OUT),
+        Err(MathError::InsufficientLiquidity),
+    );
+    // 稳定币曲线的一侧没有储备
+    assert_eq!(
+        Curve::StableSwap { amp: 100 }.swap_output(true, 1_000, 0, RESERVE_OUT),
+        Err(MathError::InsufficientLiquidity),
+    );
+    // 超过输入端储备10%的交易
+    assert!(price_impact::is_trade_size_acceptable(1000, RESERVE_IN / 10, RESERVE_IN));
+    assert!(!price_impact::is_trade_size_acceptable(1000, RESERVE_IN / 10 + 1, RESERVE_IN));
+    // 成交价格高于给出的最差价格：每单位输出最多支付0.25个输入
+    assert!(price_impact::is_within_price_limit(10_000, 40_000, Some(1u128 << 62)));
+    assert!(!price_impact::is_within_price_limit(10_000, 39_486, Some(1u128 << 62)));
+}
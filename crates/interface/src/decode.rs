@@ -0,0 +1,230 @@
+use anchor_lang::prelude::*;
+use anchor_spl_amm::{
+    constants::{AMM_VERSION, POOL_VERSION},
+    state::{Amm, FeePosition, IlPosition, Pool, Position, TraderStats},
+};
+
+/// Q64.64 定点数中的1
+const ONE_X64: f64 = 18_446_744_073_709_551_616.0;
+
+/// 按当前布局解析账户数据，兼容其他版本的账户
+///
+/// 账户布局只在末尾追加字段：旧版本账户比当前布局短，缺少的字段按零值补齐，与迁移后的结果一致；
+/// 新版本账户比当前布局长，末尾本库不认识的字段被忽略
+pub fn account<T: AccountDeserialize>(data: &[u8], len: usize) -> Result<T> {
+    if data.len() >= len {
+        return T::try_deserialize(&mut &data[..]);
+    }
+    let mut padded = data.to_vec();
+    padded.resize(len, 0);
+    T::try_deserialize(&mut padded.as_slice())
+}
+
+pub fn amm(data: &[u8]) -> Result<Amm> {
+    account(data, Amm::LEN)
+}
+
+pub fn pool(data: &[u8]) -> Result<Pool> {
+    account(data, Pool::LEN)
+}
+
+pub fn position(data: &[u8]) -> Result<Position> {
+    account(data, Position::LEN)
+}
+
+pub fn il_position(data: &[u8]) -> Result<IlPosition> {
+    account(data, IlPosition::LEN)
+}
+
+pub fn fee_position(data: &[u8]) -> Result<FeePosition> {
+    account(data, FeePosition::LEN)
+}
+
+pub fn trader_stats(data: &[u8]) -> Result<TraderStats> {
+    account(data, TraderStats::LEN)
+}
+
+/// AMM账户的版本比本库新，末尾新增的字段没有被解析
+pub fn is_newer_amm(amm: &Amm) -> bool {
+    amm.version > AMM_VERSION
+}
+
+/// 池子账户的版本比本库新，末尾新增的字段没有被解析
+pub fn is_newer_pool(pool: &Pool) -> bool {
+    pool.version > POOL_VERSION
+}
+
+/// Q64.64 定点数（如 `*_price_x64`、`fee_growth_checkpoint`）转换为小数
+pub fn x64_to_f64(value: u128) -> f64 {
+    value as f64 / ONE_X64
+}
+
+/// 以原始位存储的 I64F64（如 `IlPosition::entry_price`）转换为小数
+pub fn i64f64_bits_to_f64(bits: u128) -> f64 {
+    bits as i128 as f64 / ONE_X64
+}
+
+/// 最小单位的数量按小数位数转换为整币数量
+pub fn ui_amount(amount: u128, decimals: u8) -> f64 {
+    amount as f64 / 10f64.powi(decimals as i32)
+}
+
+/// 代币A以代币B计价的原始数量之比换算为整币之比，迁移前未记录小数位数的池子不换算
+pub fn ui_price(pool: &Pool, raw_price: f64) -> f64 {
+    raw_price * 10f64.powi(pool.decimals_a as i32 - pool.decimals_b as i32)
+}
+
+/// 池子的可读视图，数量均为整币
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PoolView {
+    pub version: u8,
+    pub reserve_a: f64,
+    pub reserve_b: f64,
+    /// 以代币B计价的代币A价格，池子没有储备时为0
+    pub price_a: f64,
+    pub volume_a: f64,
+    pub volume_b: f64,
+    pub fees_a: f64,
+    pub fees_b: f64,
+    pub swap_count: u64,
+    pub deposit_count: u64,
+    pub withdraw_count: u64,
+}
+
+impl PoolView {
+    pub fn new(pool: &Pool) -> Self {
+        let (decimals_a, decimals_b) = (pool.decimals_a, pool.decimals_b);
+        let price_a = if pool.reserve_a == 0 {
+            0.0
+        } else {
+            ui_price(pool, pool.reserve_b as f64 / pool.reserve_a as f64)
+        };
+        Self {
+            version: pool.version,
+            reserve_a: ui_amount(pool.reserve_a as u128, decimals_a),
+            reserve_b: ui_amount(pool.reserve_b as u128, decimals_b),
+            price_a,
+            volume_a: ui_amount(pool.stats.volume_a, decimals_a),
+            volume_b: ui_amount(pool.stats.volume_b, decimals_b),
+            fees_a: ui_amount(pool.stats.fees_a, decimals_a),
+            fees_b: ui_amount(pool.stats.fees_b, decimals_b),
+            swap_count: pool.stats.swap_count,
+            deposit_count: pool.stats.deposit_count,
+            withdraw_count: pool.stats.withdraw_count,
+        }
+    }
+}
+
+/// 聚合流动性头寸的可读视图，流动性按流动性代币的小数位数换算，价格为整币之比
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PositionView {
+    pub liquidity: f64,
+    pub lower_price: f64,
+    pub upper_price: f64,
+    pub fee_growth_checkpoint: f64,
+    pub fees_owed: f64,
+    pub range_order: Option<bool>,
+}
+
+impl PositionView {
+    pub fn new(position: &Position, pool: &Pool) -> Self {
+        let lp_decimals = Pool::lp_decimals(pool.decimals_a, pool.decimals_b);
+        Self {
+            liquidity: ui_amount(position.liquidity as u128, lp_decimals),
+            lower_price: ui_price(pool, x64_to_f64(position.lower_price_x64)),
+            upper_price: ui_price(pool, x64_to_f64(position.upper_price_x64)),
+            fee_growth_checkpoint: x64_to_f64(position.fee_growth_checkpoint),
+            fees_owed: ui_amount(position.fees_owed as u128, lp_decimals),
+            range_order: position.range_order,
+        }
+    }
+}
+
+/// 无常损失补偿头寸的可读视图，登记价格在登记时已按小数位数换算
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct IlPositionView {
+    pub liquidity: f64,
+    pub entry_price: f64,
+    pub last_compensated: i64,
+}
+
+impl IlPositionView {
+    pub fn new(position: &IlPosition, pool: &Pool) -> Self {
+        Self {
+            liquidity: ui_amount(position.liquidity as u128, Pool::lp_decimals(pool.decimals_a, pool.decimals_b)),
+            entry_price: i64f64_bits_to_f64(position.entry_price),
+            last_compensated: position.last_compensated,
+        }
+    }
+}
+
+/// 交易者成交量的可读视图，成交量以代币B计
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TraderStatsView {
+    pub total_volume: f64,
+    pub period_volume: f64,
+    pub previous_period_volume: f64,
+    pub period_start: i64,
+}
+
+impl TraderStatsView {
+    pub fn new(stats: &TraderStats, pool: &Pool) -> Self {
+        Self {
+            total_volume: ui_amount(stats.total_volume, pool.decimals_b),
+            period_volume: ui_amount(stats.period_volume as u128, pool.decimals_b),
+            previous_period_volume: ui_amount(stats.previous_period_volume as u128, pool.decimals_b),
+            period_start: stats.period_start,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn serialized_pool(version: u8) -> Vec<u8> {
+        let pool = Pool {
+            reserve_a: 2_000_000_000,
+            reserve_b: 500_000,
+            decimals_a: 9,
+            decimals_b: 6,
+            version,
+            ..Default::default()
+        };
+        let mut data = Vec::new();
+        pool.try_serialize(&mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn older_layout_is_zero_padded() {
+        // 去掉最后追加的两个小数位数字段和池子状态，相当于迁移前的账户
+        let data = serialized_pool(POOL_VERSION);
+        let pool = pool(&data[..data.len() - 3]).unwrap();
+        assert_eq!((pool.reserve_a, pool.decimals_a, pool.decimals_b), (2_000_000_000, 0, 0));
+    }
+
+    #[test]
+    fn newer_layout_ignores_trailing_fields() {
+        let mut data = serialized_pool(POOL_VERSION + 1);
+        data.extend_from_slice(&[7; 64]);
+        let pool = pool(&data).unwrap();
+        assert!(is_newer_pool(&pool));
+
+        let view = PoolView::new(&pool);
+        assert_eq!((view.reserve_a, view.reserve_b), (2.0, 0.5));
+        assert_eq!(view.price_a, 0.25);
+    }
+
+    #[test]
+    fn rejects_other_accounts() {
+        assert!(amm(&serialized_pool(POOL_VERSION)).is_err());
+    }
+
+    #[test]
+    fn fixed_point_conversions() {
+        assert_eq!(x64_to_f64(3 << 63), 1.5);
+        assert_eq!(i64f64_bits_to_f64((-(1i128 << 62)) as u128), -0.25);
+        assert_eq!(ui_amount(1_500_000, 6), 1.5);
+    }
+}
@@ -0,0 +1,244 @@
+use anchor_lang::{prelude::*, solana_program::instruction::Instruction, system_program, InstructionData};
+use anchor_spl::{associated_token, associated_token::get_associated_token_address_with_program_id, token};
+use anchor_spl_amm::{accounts, instruction, models::swap_options::SwapOptions, state::Pool, ID};
+
+use crate::pda;
+
+/// 构建指令需要的池子地址
+///
+/// 代币程序是各代币所属的程序（Token或Token-2022），池代币账户是池子权限的关联代币账户
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PoolKeys {
+    pub amm: Pubkey,
+    pub pool: Pubkey,
+    pub pool_authority: Pubkey,
+    pub mint_liquidity: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub token_program_a: Pubkey,
+    pub token_program_b: Pubkey,
+    pub pool_account_a: Pubkey,
+    pub pool_account_b: Pubkey,
+}
+
+impl PoolKeys {
+    /// 由交易对及其代币程序推导全部地址，代币顺序任意
+    pub fn new(amm: Pubkey, (mint_x, token_program_x): (Pubkey, Pubkey), (mint_y, token_program_y): (Pubkey, Pubkey), fee_tier: u16) -> Self {
+        let (mint_a, mint_b) = Pool::sort_mints(mint_x, mint_y);
+        let (token_program_a, token_program_b) = if mint_a == mint_x {
+            (token_program_x, token_program_y)
+        } else {
+            (token_program_y, token_program_x)
+        };
+        let pool_authority = pda::pool_authority(&amm, &mint_a, &mint_b, fee_tier);
+        Self {
+            amm,
+            pool: pda::pool(&amm, &mint_a, &mint_b, fee_tier),
+            pool_authority,
+            mint_liquidity: pda::mint_liquidity(&amm, &mint_a, &mint_b, fee_tier),
+            mint_a,
+            mint_b,
+            token_program_a,
+            token_program_b,
+            pool_account_a: get_associated_token_address_with_program_id(&pool_authority, &mint_a, &token_program_a),
+            pool_account_b: get_associated_token_address_with_program_id(&pool_authority, &mint_b, &token_program_b),
+        }
+    }
+
+    /// 指定所有者在池子两侧代币上的关联代币账户
+    pub fn user_accounts(&self, owner: &Pubkey) -> (Pubkey, Pubkey) {
+        (
+            get_associated_token_address_with_program_id(owner, &self.mint_a, &self.token_program_a),
+            get_associated_token_address_with_program_id(owner, &self.mint_b, &self.token_program_b),
+        )
+    }
+}
+
+/// swap_exact_tokens_for_tokens 的账户：传入池子预言机，其他可选账户均省略，需要时可以在返回值上修改
+pub fn swap_exact_tokens_for_tokens_accounts(keys: &PoolKeys, trader: &Pubkey) -> accounts::SwapExactTokensForTokens {
+    let (trader_account_a, trader_account_b) = keys.user_accounts(trader);
+    accounts::SwapExactTokensForTokens {
+        amm: keys.amm,
+        pool: keys.pool,
+        pool_oracle: Some(pda::pool_oracle(&keys.pool)),
+        pool_authority: keys.pool_authority,
+        trader: *trader,
+        mint_a: keys.mint_a,
+        mint_b: keys.mint_b,
+        pool_token_accounts: accounts::PoolTokenAccounts {
+            pool_account_a: keys.pool_account_a,
+            pool_account_b: keys.pool_account_b,
+            protocol_fee_account_a: pda::protocol_fee_account(&keys.pool_authority, &keys.mint_a),
+            protocol_fee_account_b: pda::protocol_fee_account(&keys.pool_authority, &keys.mint_b),
+            il_compensation_account_a: None,
+            il_compensation_account_b: None,
+            mint_a: keys.mint_a,
+            mint_b: keys.mint_b,
+            pool_authority: keys.pool_authority,
+            token_program_a: keys.token_program_a,
+            token_program_b: keys.token_program_b,
+        },
+        trader_token_accounts: accounts::TraderTokenAccounts {
+            trader_account_a,
+            trader_account_b,
+            mint_a: keys.mint_a,
+            mint_b: keys.mint_b,
+            trader: *trader,
+            payer: *trader,
+            token_program_a: keys.token_program_a,
+            token_program_b: keys.token_program_b,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        price_feed: None,
+        trader_stats: None,
+        hook_program: None,
+        fee_exemption: None,
+        relayer_account: None,
+        swap_commitment: None,
+        host_fee_account: None,
+        denylist: None,
+        token_program_a: keys.token_program_a,
+        token_program_b: keys.token_program_b,
+        associated_token_program: associated_token::ID,
+        system_program: system_program::ID,
+    }
+}
+
+/// 精确输入交换，交易者同时支付代币账户的租金
+pub fn swap_exact_tokens_for_tokens(
+    keys: &PoolKeys,
+    trader: &Pubkey,
+    input_mint: Pubkey,
+    input_amount: u64,
+    min_output_amount: u64,
+) -> Instruction {
+    build(
+        swap_exact_tokens_for_tokens_accounts(keys, trader),
+        instruction::SwapExactTokensForTokens {
+            input_mint,
+            input_amount,
+            min_output_amount,
+            deadline: None,
+            options: SwapOptions::default(),
+        },
+    )
+}
+
+/// 按池子比例存入流动性，存款人同时支付流动性代币账户的租金
+pub fn deposit_liquidity(
+    keys: &PoolKeys,
+    depositor: &Pubkey,
+    amount_a: u64,
+    amount_b: u64,
+    min_lp_tokens_out: u64,
+) -> Instruction {
+    let (depositor_account_a, depositor_account_b) = keys.user_accounts(depositor);
+    build(
+        accounts::DepositLiquidity {
+            amm: keys.amm,
+            pool: keys.pool,
+            pool_authority: keys.pool_authority,
+            depositor: *depositor,
+            mint_liquidity: keys.mint_liquidity,
+            mint_a: keys.mint_a,
+            mint_b: keys.mint_b,
+            pool_account_a: keys.pool_account_a,
+            pool_account_b: keys.pool_account_b,
+            depositor_account_liquidity: get_associated_token_address_with_program_id(
+                depositor,
+                &keys.mint_liquidity,
+                &token::ID,
+            ),
+            depositor_account_a,
+            depositor_account_b,
+            deposit_record: None,
+            denylist: None,
+            payer: *depositor,
+            token_program: token::ID,
+            token_program_a: keys.token_program_a,
+            token_program_b: keys.token_program_b,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::DepositLiquidity {
+            amount_a,
+            amount_b,
+            min_lp_tokens_out,
+            deadline: None,
+            max_ratio_deviation_bps: None,
+            clamp_to_balance: false,
+        },
+    )
+}
+
+/// 销毁流动性代币按比例提取，存款人同时支付代币账户的租金
+pub fn withdraw_liquidity(
+    keys: &PoolKeys,
+    depositor: &Pubkey,
+    amount: u64,
+    min_amount_a: u64,
+    min_amount_b: u64,
+) -> Instruction {
+    let (depositor_account_a, depositor_account_b) = keys.user_accounts(depositor);
+    build(
+        accounts::WithdrawLiquidity {
+            amm: keys.amm,
+            pool: keys.pool,
+            pool_authority: keys.pool_authority,
+            depositor: *depositor,
+            mint_liquidity: keys.mint_liquidity,
+            mint_a: keys.mint_a,
+            mint_b: keys.mint_b,
+            pool_token_accounts: accounts::WithdrawPoolAccounts {
+                pool_account_a: keys.pool_account_a,
+                pool_account_b: keys.pool_account_b,
+                mint_a: keys.mint_a,
+                mint_b: keys.mint_b,
+                pool_authority: keys.pool_authority,
+                token_program_a: keys.token_program_a,
+                token_program_b: keys.token_program_b,
+            },
+            depositor_token_accounts: accounts::DepositorTokenAccounts {
+                depositor_account_liquidity: get_associated_token_address_with_program_id(
+                    depositor,
+                    &keys.mint_liquidity,
+                    &token::ID,
+                ),
+                depositor_account_a,
+                depositor_account_b,
+                mint_liquidity: keys.mint_liquidity,
+                mint_a: keys.mint_a,
+                mint_b: keys.mint_b,
+                depositor: *depositor,
+                payer: *depositor,
+                token_program: token::ID,
+                token_program_a: keys.token_program_a,
+                token_program_b: keys.token_program_b,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            },
+            deposit_record: None,
+            token_program: token::ID,
+            token_program_a: keys.token_program_a,
+            token_program_b: keys.token_program_b,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::WithdrawLiquidity {
+            amount,
+            min_amount_a,
+            min_amount_b,
+            deadline: None,
+        },
+    )
+}
+
+/// 组合账户和指令参数
+pub fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
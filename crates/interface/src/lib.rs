@@ -0,0 +1,12 @@
+//! anchor-spl-amm 的接口库
+//!
+//! 导出账户结构、指令构建函数、PDA推导和事件类型，其他Anchor程序通过 `cpi` 模块组合本AMM时
+//! 不需要手动复制种子。依赖的程序库启用了 `cpi` 特性，不会编译入口点。
+//!
+//! `decode` 模块从原始账户数据解析各版本的账户，并把定点数和最小单位换算为可读的小数，供索引器使用。
+
+pub mod decode;
+pub mod ix;
+pub mod pda;
+
+pub use anchor_spl_amm::{accounts, constants, cpi, events, instruction, models, program::AnchorSplAmm, state, ID};
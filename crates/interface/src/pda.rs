@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+use anchor_spl_amm::{
+    constants::{
+        AUTHORITY_SEED, DENYLIST_SEED, DEPOSIT_RECORD_SEED, IL_COMPENSATION_SEED, LIQUIDITY_SEED, POOL_ORACLE_SEED, PROGRAM_CONFIG_SEED,
+        PROTOCOL_FEE_SEED,
+    },
+    state::Pool,
+    ID,
+};
+
+/// 全局程序配置地址
+pub fn program_config() -> Pubkey {
+    Pubkey::find_program_address(&[PROGRAM_CONFIG_SEED], &ID).0
+}
+
+/// AMM账户地址
+pub fn amm(id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[id.as_ref()], &ID).0
+}
+
+/// AMM的地址黑名单地址
+pub fn denylist(amm: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[amm.as_ref(), DENYLIST_SEED], &ID).0
+}
+
+/// 池子地址，与传入的代币顺序无关
+pub fn pool(amm: &Pubkey, mint_x: &Pubkey, mint_y: &Pubkey, fee_tier: u16) -> Pubkey {
+    Pool::find_address(amm, mint_x, mint_y, fee_tier).0
+}
+
+/// 池子权限地址，持有池代币账户并签署转出，与传入的代币顺序无关
+pub fn pool_authority(amm: &Pubkey, mint_x: &Pubkey, mint_y: &Pubkey, fee_tier: u16) -> Pubkey {
+    pair_address(amm, mint_x, mint_y, fee_tier, AUTHORITY_SEED)
+}
+
+/// 流动性代币地址，与传入的代币顺序无关
+pub fn mint_liquidity(amm: &Pubkey, mint_x: &Pubkey, mint_y: &Pubkey, fee_tier: u16) -> Pubkey {
+    pair_address(amm, mint_x, mint_y, fee_tier, LIQUIDITY_SEED)
+}
+
+/// 池子的波动率预言机地址
+pub fn pool_oracle(pool: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[pool.as_ref(), POOL_ORACLE_SEED], &ID).0
+}
+
+/// 池子一侧代币的协议费用账户地址
+pub fn protocol_fee_account(pool_authority: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[pool_authority.as_ref(), mint.as_ref(), PROTOCOL_FEE_SEED], &ID).0
+}
+
+/// 池子一侧代币的无常损失补偿金库地址
+pub fn il_compensation_account(pool_authority: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[pool_authority.as_ref(), mint.as_ref(), IL_COMPENSATION_SEED], &ID).0
+}
+
+/// LP在池子中的存款记录地址
+pub fn deposit_record(pool: &Pubkey, depositor: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[pool.as_ref(), depositor.as_ref(), DEPOSIT_RECORD_SEED], &ID).0
+}
+
+// 以规范顺序的交易对和费率档位为种子的地址
+fn pair_address(amm: &Pubkey, mint_x: &Pubkey, mint_y: &Pubkey, fee_tier: u16, seed: &[u8]) -> Pubkey {
+    let (mint_a, mint_b) = Pool::sort_mints(*mint_x, *mint_y);
+    Pubkey::find_program_address(
+        &[amm.as_ref(), mint_a.as_ref(), mint_b.as_ref(), &fee_tier.to_le_bytes(), seed],
+        &ID,
+    )
+    .0
+}
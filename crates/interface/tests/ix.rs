@@ -0,0 +1,104 @@
+//! 指令构建函数和PDA推导：地址与程序的种子一致且与代币顺序无关，指令数据可以被程序解析
+
+use anchor_lang::{prelude::*, AnchorDeserialize, Discriminator};
+use anchor_spl::{associated_token::get_associated_token_address_with_program_id, token, token_2022};
+use anchor_spl_amm_interface::{
+    constants::{AUTHORITY_SEED, LIQUIDITY_SEED},
+    instruction,
+    ix::{self, PoolKeys},
+    pda, ID,
+};
+
+struct Fixture {
+    amm: Pubkey,
+    mint_a: Pubkey,
+    mint_b: Pubkey,
+}
+
+impl Fixture {
+    /// mint_a 按字节序小于 mint_b，与程序中代币A、B的顺序一致
+    fn new() -> Self {
+        Self {
+            amm: pda::amm(&Pubkey::new_unique()),
+            mint_a: Pubkey::new_from_array([1; 32]),
+            mint_b: Pubkey::new_from_array([2; 32]),
+        }
+    }
+
+    fn seeds_address(&self, fee_tier: u16, seed: &[u8]) -> Pubkey {
+        Pubkey::find_program_address(
+            &[self.amm.as_ref(), self.mint_a.as_ref(), self.mint_b.as_ref(), &fee_tier.to_le_bytes(), seed],
+            &ID,
+        )
+        .0
+    }
+}
+
+#[test]
+fn pool_addresses_match_program_seeds() {
+    let f = Fixture::new();
+    assert_eq!(pda::pool(&f.amm, &f.mint_a, &f.mint_b, 1), f.seeds_address(1, &[]));
+    assert_eq!(pda::pool_authority(&f.amm, &f.mint_a, &f.mint_b, 1), f.seeds_address(1, AUTHORITY_SEED));
+    assert_eq!(pda::mint_liquidity(&f.amm, &f.mint_a, &f.mint_b, 1), f.seeds_address(1, LIQUIDITY_SEED));
+}
+
+#[test]
+fn pool_addresses_ignore_mint_order() {
+    let f = Fixture::new();
+    assert_eq!(pda::pool(&f.amm, &f.mint_b, &f.mint_a, 0), pda::pool(&f.amm, &f.mint_a, &f.mint_b, 0));
+    assert_eq!(
+        pda::pool_authority(&f.amm, &f.mint_b, &f.mint_a, 0),
+        pda::pool_authority(&f.amm, &f.mint_a, &f.mint_b, 0),
+    );
+    // 费率档位不同的池子地址不同
+    assert_ne!(pda::pool(&f.amm, &f.mint_a, &f.mint_b, 0), pda::pool(&f.amm, &f.mint_a, &f.mint_b, 1));
+}
+
+#[test]
+fn pool_keys_follow_sorted_mints() {
+    let f = Fixture::new();
+    // 按相反的顺序传入，代币程序跟随各自的代币
+    let keys = PoolKeys::new(f.amm, (f.mint_b, token_2022::ID), (f.mint_a, token::ID), 0);
+    assert_eq!((keys.mint_a, keys.mint_b), (f.mint_a, f.mint_b));
+    assert_eq!((keys.token_program_a, keys.token_program_b), (token::ID, token_2022::ID));
+    assert_eq!(
+        keys.pool_account_b,
+        get_associated_token_address_with_program_id(&keys.pool_authority, &f.mint_b, &token_2022::ID),
+    );
+}
+
+#[test]
+fn swap_instruction_round_trips() {
+    let f = Fixture::new();
+    let keys = PoolKeys::new(f.amm, (f.mint_a, token::ID), (f.mint_b, token::ID), 0);
+    let trader = Pubkey::new_unique();
+    let ix = ix::swap_exact_tokens_for_tokens(&keys, &trader, f.mint_a, 1_000, 900);
+
+    assert_eq!(ix.program_id, ID);
+    assert_eq!(&ix.data[..8], instruction::SwapExactTokensForTokens::DISCRIMINATOR);
+    let args = instruction::SwapExactTokensForTokens::try_from_slice(&ix.data[8..]).unwrap();
+    assert_eq!((args.input_mint, args.input_amount, args.min_output_amount), (f.mint_a, 1_000, 900));
+    assert_eq!(args.deadline, None);
+
+    // 交易者签名，池子可写，省略的可选账户以程序ID占位
+    let meta = |key: &Pubkey| ix.accounts.iter().find(|meta| meta.pubkey == *key).unwrap();
+    assert!(meta(&trader).is_signer);
+    assert!(meta(&keys.pool).is_writable);
+    assert!(ix.accounts.iter().any(|meta| meta.pubkey == ID && !meta.is_writable));
+    assert!(ix.accounts.iter().any(|meta| meta.pubkey == pda::pool_oracle(&keys.pool)));
+}
+
+#[test]
+fn instruction_data_is_not_interchangeable() {
+    let f = Fixture::new();
+    let keys = PoolKeys::new(f.amm, (f.mint_a, token::ID), (f.mint_b, token::ID), 0);
+    let depositor = Pubkey::new_unique();
+    let deposit = ix::deposit_liquidity(&keys, &depositor, 1_000, 2_000, 0);
+    let withdraw = ix::withdraw_liquidity(&keys, &depositor, 500, 0, 0);
+
+    // 判别符区分指令，另一条指令的参数无法按本指令解析
+    assert_ne!(deposit.data[..8], withdraw.data[..8]);
+    assert!(instruction::WithdrawLiquidity::try_from_slice(&deposit.data[8..]).is_err());
+    let args = instruction::DepositLiquidity::try_from_slice(&deposit.data[8..]).unwrap();
+    assert_eq!((args.amount_a, args.amount_b), (1_000, 2_000));
+}
@@ -0,0 +1,98 @@
+//! `jupiter_amm_interface::Amm` 的实现，各方法委托给 `SplAmmPool` 的同名方法
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{bail, Result};
+use jupiter_amm_interface::{
+    AccountMap, Amm, AmmContext, KeyedAccount, Quote, QuoteParams, Swap, SwapAndAccountMetas, SwapMode, SwapParams,
+};
+use rust_decimal::Decimal;
+
+use crate::{AccountData, SplAmmPool};
+
+impl Amm for SplAmmPool {
+    fn from_keyed_account(keyed_account: &KeyedAccount, _amm_context: &AmmContext) -> Result<Self> {
+        let account = &keyed_account.account;
+        let account = AccountData {
+            owner: account.owner,
+            lamports: account.lamports,
+            data: account.data.clone(),
+        };
+        Ok(SplAmmPool::from_keyed_account(keyed_account.key, &account)?)
+    }
+
+    fn label(&self) -> String {
+        SplAmmPool::label(self)
+    }
+
+    fn program_id(&self) -> Pubkey {
+        SplAmmPool::program_id(self)
+    }
+
+    fn key(&self) -> Pubkey {
+        SplAmmPool::key(self)
+    }
+
+    fn get_reserve_mints(&self) -> Vec<Pubkey> {
+        SplAmmPool::get_reserve_mints(self)
+    }
+
+    fn get_accounts_to_update(&self) -> Vec<Pubkey> {
+        SplAmmPool::get_accounts_to_update(self)
+    }
+
+    /// Clock系统变量在需要刷新的账户中，报价使用其中的时间
+    fn update(&mut self, account_map: &AccountMap) -> Result<()> {
+        let accounts = SplAmmPool::get_accounts_to_update(self)
+            .into_iter()
+            .filter_map(|key| {
+                let account = account_map.get(&key)?;
+                Some((
+                    key,
+                    AccountData {
+                        owner: account.owner,
+                        lamports: account.lamports,
+                        data: account.data.clone(),
+                    },
+                ))
+            })
+            .collect();
+        Ok(SplAmmPool::update(self, &accounts)?)
+    }
+
+    fn quote(&self, quote_params: &QuoteParams) -> Result<Quote> {
+        if matches!(quote_params.swap_mode, SwapMode::ExactOut) {
+            bail!("anchor-spl-amm only quotes exact input swaps");
+        }
+        let quote = SplAmmPool::quote(self, &quote_params.input_mint, quote_params.amount)?;
+        // 手续费以输出代币收取时，按扣费前的输出计算比例
+        let fee_base = if quote.fee_on_output { quote.output_amount + quote.fee_amount } else { quote.input_amount };
+        Ok(Quote {
+            in_amount: quote.input_amount,
+            out_amount: quote.net_output_amount,
+            fee_amount: quote.fee_amount,
+            fee_mint: self.fee_mint(&quote_params.input_mint)?,
+            fee_pct: Decimal::from(quote.fee_amount)
+                .checked_div(Decimal::from(fee_base))
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Jupiter按 `Swap` 变体构建指令数据，这里只提供账户列表
+    fn get_swap_and_account_metas(&self, swap_params: &SwapParams) -> Result<SwapAndAccountMetas> {
+        let instruction = self.get_swap_instruction(&crate::SwapParams {
+            source_mint: swap_params.source_mint,
+            in_amount: swap_params.in_amount,
+            minimum_out_amount: swap_params.out_amount,
+            user: swap_params.token_transfer_authority,
+        })?;
+        Ok(SwapAndAccountMetas {
+            // Jupiter为本程序分配专用的变体之前，沿用SPL token-swap的变体
+            swap: Swap::TokenSwap,
+            account_metas: instruction.accounts,
+        })
+    }
+
+    fn clone_amm(&self) -> Box<dyn Amm + Send + Sync> {
+        Box::new(self.clone())
+    }
+}
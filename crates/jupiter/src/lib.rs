@@ -0,0 +1,377 @@
+//! anchor-spl-amm 的Jupiter聚合器适配器
+//!
+//! 按Jupiter `Amm` trait的流程组织：从池子账户创建、列出需要刷新的账户、用账户数据更新状态、
+//! 报价并构建交换指令。报价直接调用程序的 `quote_exact_input`，与链上交换共用同一套计算，
+//! 程序的费用、曲线或价格影响逻辑变化时适配器的报价随之变化。
+//!
+//! 启用 `amm-interface` 特性后，`SplAmmPool` 实现 `jupiter_amm_interface::Amm`。
+
+#[cfg(feature = "amm-interface")]
+mod amm;
+
+use std::collections::HashMap;
+
+use anchor_lang::{
+    prelude::*,
+    solana_program::{instruction::Instruction, sysvar},
+};
+use anchor_spl_amm::{
+    models::{quote::SwapQuote, swap_options::SwapOptions},
+    state::{Amm, Pool, PoolOracle},
+};
+use anchor_spl_amm_interface::{
+    decode,
+    ix::{self, PoolKeys},
+    pda,
+};
+
+/// 账户数据快照，对应Jupiter `AccountMap` 中的账户
+#[derive(Clone, Debug, Default)]
+pub struct AccountData {
+    pub owner: Pubkey,
+    pub lamports: u64,
+    pub data: Vec<u8>,
+}
+
+pub type AccountMap = HashMap<Pubkey, AccountData>;
+
+/// 交换参数，对应Jupiter的 `SwapParams`
+#[derive(Clone, Copy, Debug)]
+pub struct SwapParams {
+    /// 输入代币
+    pub source_mint: Pubkey,
+    /// 输入数量
+    pub in_amount: u64,
+    /// 最少输出数量
+    pub minimum_out_amount: u64,
+    /// 交易者，同时支付代币账户的租金；程序要求交易者使用关联代币账户
+    pub user: Pubkey,
+}
+
+/// 一个池子的报价状态
+#[derive(Clone)]
+pub struct SplAmmPool {
+    key: Pubkey,
+    pool: Pool,
+    pool_authority: Pubkey,
+    amm: Option<Amm>,
+    pool_oracle: Option<PoolOracle>,
+    mint_a: Option<AccountData>,
+    mint_b: Option<AccountData>,
+    il_compensation_vaults: (bool, bool),
+    clock: Clock,
+}
+
+impl SplAmmPool {
+    /// 从池子账户创建
+    pub fn from_keyed_account(key: Pubkey, account: &AccountData) -> Result<Self> {
+        let pool = decode::pool(&account.data)?;
+        let pool_authority = pda::pool_authority(&pool.amm, &pool.mint_a, &pool.mint_b, pool.fee_tier);
+        Ok(Self {
+            key,
+            pool,
+            pool_authority,
+            amm: None,
+            pool_oracle: None,
+            mint_a: None,
+            mint_b: None,
+            il_compensation_vaults: (false, false),
+            clock: Clock::default(),
+        })
+    }
+
+    pub fn label(&self) -> String {
+        "anchor-spl-amm".to_string()
+    }
+
+    pub fn program_id(&self) -> Pubkey {
+        anchor_spl_amm::ID
+    }
+
+    pub fn key(&self) -> Pubkey {
+        self.key
+    }
+
+    pub fn get_reserve_mints(&self) -> Vec<Pubkey> {
+        vec![self.pool.mint_a, self.pool.mint_b]
+    }
+
+    /// 需要刷新的账户。报价使用池子记录的储备，不需要读取池代币账户
+    pub fn get_accounts_to_update(&self) -> Vec<Pubkey> {
+        vec![
+            self.key,
+            self.pool.amm,
+            pda::pool_oracle(&self.key),
+            self.pool.mint_a,
+            self.pool.mint_b,
+            pda::il_compensation_account(&self.pool_authority, &self.pool.mint_a),
+            pda::il_compensation_account(&self.pool_authority, &self.pool.mint_b),
+            sysvar::clock::ID,
+        ]
+    }
+
+    /// 用刷新得到的账户数据更新状态，缺少的账户保持原状。
+    /// 报价使用账户中的Clock系统变量判断熔断、转账费的epoch和LBP权重
+    pub fn update(&mut self, accounts: &AccountMap) -> Result<()> {
+        if let Some(account) = accounts.get(&self.key) {
+            self.pool = decode::pool(&account.data)?;
+        }
+        if let Some(account) = accounts.get(&self.pool.amm) {
+            self.amm = Some(decode::amm(&account.data)?);
+        }
+        if let Some(account) = accounts.get(&pda::pool_oracle(&self.key)) {
+            self.pool_oracle = Some(PoolOracle::try_deserialize(&mut account.data.as_slice())?);
+        }
+        if let Some(account) = accounts.get(&self.pool.mint_a) {
+            self.mint_a = Some(account.clone());
+        }
+        if let Some(account) = accounts.get(&self.pool.mint_b) {
+            self.mint_b = Some(account.clone());
+        }
+        self.il_compensation_vaults = (
+            accounts.contains_key(&pda::il_compensation_account(&self.pool_authority, &self.pool.mint_a)),
+            accounts.contains_key(&pda::il_compensation_account(&self.pool_authority, &self.pool.mint_b)),
+        );
+        if let Some(account) = accounts.get(&sysvar::clock::ID) {
+            self.clock = parse_clock(&account.data)?;
+        }
+        Ok(())
+    }
+
+    /// 精确输入报价，与 swap_exact_tokens_for_tokens 的执行结果一致（不含交易者的手续费折扣）
+    pub fn quote(&self, input_mint: &Pubkey, amount: u64) -> Result<SwapQuote> {
+        let (Some(amm), Some(mint_a), Some(mint_b)) = (&self.amm, &self.mint_a, &self.mint_b) else {
+            return Err(ErrorCode::AccountNotInitialized.into());
+        };
+        let swap_a = self.pool.swap_direction(input_mint)?;
+
+        // 程序按 AccountInfo 读取代币的转账费配置
+        let (mut lamports_a, mut data_a) = (mint_a.lamports, mint_a.data.clone());
+        let (mut lamports_b, mut data_b) = (mint_b.lamports, mint_b.data.clone());
+        let mint_a_info = AccountInfo::new(&self.pool.mint_a, false, false, &mut lamports_a, &mut data_a, &mint_a.owner, false, 0);
+        let mint_b_info = AccountInfo::new(&self.pool.mint_b, false, false, &mut lamports_b, &mut data_b, &mint_b.owner, false, 0);
+        let mints = if swap_a { (&mint_a_info, &mint_b_info) } else { (&mint_b_info, &mint_a_info) };
+
+        anchor_spl_amm::quote_exact_input(amm, &self.pool, self.pool_oracle.as_ref(), mints, amount, 0, &self.clock)
+    }
+
+    /// 手续费以哪种代币收取
+    pub fn fee_mint(&self, input_mint: &Pubkey) -> Result<Pubkey> {
+        let swap_a = self.pool.swap_direction(input_mint)?;
+        let fee_on_output = self.amm.as_ref().is_some_and(|amm| amm.fee_on_output);
+        Ok(if swap_a == fee_on_output { self.pool.mint_b } else { self.pool.mint_a })
+    }
+
+    /// 构建 swap_exact_tokens_for_tokens 指令
+    ///
+    /// 配置了交换钩子的池子只传入钩子程序，钩子需要的其他账户无法从池子状态得知
+    pub fn get_swap_instruction(&self, params: &SwapParams) -> Result<Instruction> {
+        let keys = self.pool_keys().ok_or(ErrorCode::AccountNotInitialized)?;
+        self.pool.swap_direction(&params.source_mint)?;
+        let optional = |enabled: bool, address: Pubkey| enabled.then_some(address);
+
+        let mut accounts = ix::swap_exact_tokens_for_tokens_accounts(&keys, &params.user);
+        accounts.pool_oracle = accounts.pool_oracle.filter(|_| self.pool_oracle.is_some());
+        accounts.pool_token_accounts.il_compensation_account_a = optional(
+            self.il_compensation_vaults.0,
+            pda::il_compensation_account(&self.pool_authority, &self.pool.mint_a),
+        );
+        accounts.pool_token_accounts.il_compensation_account_b = optional(
+            self.il_compensation_vaults.1,
+            pda::il_compensation_account(&self.pool_authority, &self.pool.mint_b),
+        );
+        accounts.price_feed = optional(self.pool.oracle_guard.is_enabled(), self.pool.oracle_guard.price_feed);
+        accounts.hook_program = optional(self.pool.swap_hook != Pubkey::default(), self.pool.swap_hook);
+        accounts.denylist = self.amm.as_ref().map(|amm| amm.denylist).filter(|denylist| *denylist != Pubkey::default());
+
+        Ok(ix::build(
+            accounts,
+            anchor_spl_amm::instruction::SwapExactTokensForTokens {
+                input_mint: params.source_mint,
+                input_amount: params.in_amount,
+                min_output_amount: params.minimum_out_amount,
+                deadline: None,
+                options: SwapOptions::default(),
+            },
+        ))
+    }
+
+    /// 读取到两种代币账户（确定了代币程序）后才能推导池代币账户等地址
+    fn pool_keys(&self) -> Option<PoolKeys> {
+        let (mint_a, mint_b) = (self.mint_a.as_ref()?, self.mint_b.as_ref()?);
+        Some(PoolKeys::new(
+            self.pool.amm,
+            (self.pool.mint_a, mint_a.owner),
+            (self.pool.mint_b, mint_b.owner),
+            self.pool.fee_tier,
+        ))
+    }
+}
+
+/// Clock系统变量账户的数据是各字段按顺序排列的小端整数
+fn parse_clock(data: &[u8]) -> Result<Clock> {
+    let field = |index: usize| {
+        data.get(index * 8..index * 8 + 8)
+            .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+            .ok_or(ErrorCode::AccountDidNotDeserialize)
+    };
+    Ok(Clock {
+        slot: field(0)?,
+        epoch_start_timestamp: field(1)? as i64,
+        epoch: field(2)?,
+        leader_schedule_epoch: field(3)?,
+        unix_timestamp: field(4)? as i64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::solana_program::program_pack::Pack;
+    use anchor_spl::token::spl_token;
+
+    use super::*;
+
+    const RESERVE: u64 = 1_000_000_000;
+
+    struct Fixture {
+        key: Pubkey,
+        pool: Pool,
+        amm: Amm,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            let pool = Pool {
+                amm: Pubkey::new_unique(),
+                mint_a: Pubkey::new_unique(),
+                mint_b: Pubkey::new_unique(),
+                // 代币A的价格为2，卖出A的交换才对交易者有利
+                reserve_a: RESERVE,
+                reserve_b: 2 * RESERVE,
+                decimals_a: 6,
+                decimals_b: 6,
+                ..Default::default()
+            };
+            let amm = Amm {
+                fee: 30,
+                ..Default::default()
+            };
+            Self {
+                key: Pubkey::new_unique(),
+                pool,
+                amm,
+            }
+        }
+
+        fn accounts(&self, unix_timestamp: i64) -> AccountMap {
+            let mut accounts = AccountMap::new();
+            accounts.insert(self.key, program_account(&self.pool));
+            accounts.insert(self.pool.amm, program_account(&self.amm));
+            accounts.insert(self.pool.mint_a, mint_account());
+            accounts.insert(self.pool.mint_b, mint_account());
+            accounts.insert(sysvar::clock::ID, clock_account(unix_timestamp));
+            accounts
+        }
+
+        fn updated(&self, unix_timestamp: i64) -> SplAmmPool {
+            let mut pool = SplAmmPool::from_keyed_account(self.key, &program_account(&self.pool)).unwrap();
+            pool.update(&self.accounts(unix_timestamp)).unwrap();
+            pool
+        }
+    }
+
+    fn program_account<T: AccountSerialize>(account: &T) -> AccountData {
+        let mut data = Vec::new();
+        account.try_serialize(&mut data).unwrap();
+        AccountData {
+            owner: anchor_spl_amm::ID,
+            lamports: 1,
+            data,
+        }
+    }
+
+    fn mint_account() -> AccountData {
+        let mint = spl_token::state::Mint {
+            decimals: 6,
+            is_initialized: true,
+            ..Default::default()
+        };
+        let mut data = vec![0; spl_token::state::Mint::LEN];
+        mint.pack_into_slice(&mut data);
+        AccountData {
+            owner: spl_token::ID,
+            lamports: 1,
+            data,
+        }
+    }
+
+    fn clock_account(unix_timestamp: i64) -> AccountData {
+        let data = [100, 0, 1, 1, unix_timestamp as u64]
+            .iter()
+            .flat_map(|field| field.to_le_bytes())
+            .collect();
+        AccountData {
+            owner: sysvar::ID,
+            lamports: 1,
+            data,
+        }
+    }
+
+    #[test]
+    fn quote_needs_update() {
+        let fixture = Fixture::new();
+        let pool = SplAmmPool::from_keyed_account(fixture.key, &program_account(&fixture.pool)).unwrap();
+        assert!(pool.quote(&fixture.pool.mint_a, 1_000_000).is_err());
+    }
+
+    #[test]
+    fn quote_matches_constant_product() {
+        let fixture = Fixture::new();
+        let pool = fixture.updated(1_700_000_000);
+
+        let input = 1_000_000;
+        let quote = pool.quote(&fixture.pool.mint_a, input).unwrap();
+        assert_eq!(quote.fee_amount, input * 30 / 10000);
+        let expected = (quote.taxed_input as u128 * 2 * RESERVE as u128 / (RESERVE + quote.taxed_input) as u128) as u64;
+        assert_eq!(quote.output_amount, expected);
+        assert_eq!(quote.net_output_amount, expected);
+        assert_eq!(pool.fee_mint(&fixture.pool.mint_a).unwrap(), fixture.pool.mint_a);
+
+        assert!(pool.quote(&Pubkey::new_unique(), input).is_err());
+    }
+
+    #[test]
+    fn update_reads_clock_from_accounts() {
+        let mut fixture = Fixture::new();
+        fixture.pool.circuit_breaker_until = 1_000;
+
+        let error = fixture.updated(999).quote(&fixture.pool.mint_a, 1_000_000).unwrap_err();
+        assert!(matches!(error, Error::AnchorError(error) if error.error_name == "ExcessiveVolatility"));
+        assert!(fixture.updated(1_000).quote(&fixture.pool.mint_a, 1_000_000).is_ok());
+    }
+
+    #[test]
+    fn update_keeps_missing_accounts() {
+        let fixture = Fixture::new();
+        let mut pool = fixture.updated(1_000);
+        let before = pool.quote(&fixture.pool.mint_a, 1_000_000).unwrap();
+
+        // 只刷新池子账户：储备变化，AMM、代币和时钟保持上次的状态
+        let mut changed = fixture.pool.clone();
+        changed.reserve_b = 4 * RESERVE;
+        let accounts = AccountMap::from([(fixture.key, program_account(&changed))]);
+        pool.update(&accounts).unwrap();
+        let after = pool.quote(&fixture.pool.mint_a, 1_000_000).unwrap();
+        assert!(after.output_amount > before.output_amount);
+        assert_eq!(after.fee_amount, before.fee_amount);
+    }
+
+    #[test]
+    fn rejects_malformed_clock() {
+        let fixture = Fixture::new();
+        let mut pool = SplAmmPool::from_keyed_account(fixture.key, &program_account(&fixture.pool)).unwrap();
+        let mut accounts = fixture.accounts(0);
+        accounts.get_mut(&sysvar::clock::ID).unwrap().data.truncate(16);
+        assert!(pool.update(&accounts).is_err());
+    }
+}
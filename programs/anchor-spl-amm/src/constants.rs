@@ -3,8 +3,176 @@ use anchor_lang::prelude::*;
 #[constant]
 pub const MINIMUM_LIQUIDITY: u64 = 100;
 
+/// 首次存款至少需要的流动性，其中MINIMUM_LIQUIDITY永久锁定
+#[constant]
+pub const MINIMUM_INITIAL_LIQUIDITY: u64 = 10 * MINIMUM_LIQUIDITY;
+
 #[constant]
 pub const AUTHORITY_SEED: &[u8] = b"authority";
 
 #[constant]
-pub const LIQUIDITY_SEED: &[u8] = b"liquidity";
\ No newline at end of file
+pub const LIQUIDITY_SEED: &[u8] = b"liquidity";
+
+#[constant]
+pub const PROTOCOL_FEE_SEED: &[u8] = b"protocol_fee";
+
+#[constant]
+pub const IL_COMPENSATION_SEED: &[u8] = b"il_compensation";
+
+#[constant]
+pub const IL_POSITION_SEED: &[u8] = b"il_position";
+
+#[constant]
+pub const FEE_POSITION_SEED: &[u8] = b"fee_position";
+
+#[constant]
+pub const LIMIT_ORDER_SEED: &[u8] = b"limit_order";
+
+#[constant]
+pub const DCA_SEED: &[u8] = b"dca";
+
+#[constant]
+pub const ESCROW_SEED: &[u8] = b"escrow";
+
+#[constant]
+pub const TRADER_STATS_SEED: &[u8] = b"trader_stats";
+
+#[constant]
+pub const FEE_EXEMPTION_SEED: &[u8] = b"fee_exemption";
+
+#[constant]
+pub const DEPOSIT_RECORD_SEED: &[u8] = b"deposit_record";
+
+#[constant]
+pub const LIQUIDITY_LOCK_SEED: &[u8] = b"liquidity_lock";
+
+#[constant]
+pub const FARM_SEED: &[u8] = b"farm";
+
+#[constant]
+pub const REWARD_VAULT_SEED: &[u8] = b"reward_vault";
+
+#[constant]
+pub const STAKE_POSITION_SEED: &[u8] = b"stake_position";
+
+#[constant]
+pub const VAULT_SEED: &[u8] = b"vault";
+
+#[constant]
+pub const VAULT_SHARE_SEED: &[u8] = b"vault_share";
+
+#[constant]
+pub const POOL_ORACLE_SEED: &[u8] = b"pool_oracle";
+
+#[constant]
+pub const POOL_REF_SEED: &[u8] = b"pool_ref";
+
+#[constant]
+pub const POSITION_SEED: &[u8] = b"position";
+
+/// 头寸NFT的Metaplex元数据名称
+#[constant]
+pub const POSITION_NFT_NAME: &str = "AMM Position";
+
+/// 头寸NFT的Metaplex元数据符号
+#[constant]
+pub const POSITION_NFT_SYMBOL: &str = "AMM-POS";
+
+/// 自动复投金库两次复投之间的最短间隔（秒）
+#[constant]
+pub const VAULT_COMPOUND_INTERVAL: i64 = 60 * 60;
+
+/// 每个农场可以同时发放的奖励代币数量上限
+#[constant]
+pub const MAX_FARM_REWARDS: usize = 3;
+
+/// 流动性锁的最长期限（秒），锁满该期限时获得最大加成
+#[constant]
+pub const MAX_LOCK_DURATION: i64 = 4 * 365 * 24 * 60 * 60;
+
+/// 流动性锁的最大加成倍数（基点），随到期临近线性衰减至1倍
+#[constant]
+pub const MAX_BOOST_BPS: u16 = 25000;
+
+#[constant]
+pub const FLASH_LOAN_FEE_BPS: u64 = 9;
+
+/// 定投每次执行时付给调用者的小费（基点），从当期投入的代币中扣除
+#[constant]
+pub const DCA_KEEPER_TIP_BPS: u64 = 10;
+
+/// 池子预言机可选的价格样本数量
+#[constant]
+pub const OBSERVATION_WINDOWS: [u16; 3] = [24, 128, 1024];
+
+/// 无常损失补偿登记和领取时使用的时间加权平均价格窗口（秒），同一笔交易中的交换无法影响登记和补偿价格
+#[constant]
+pub const IL_COMPENSATION_TWAP_WINDOW: u32 = 30 * 60;
+
+/// 池子可选的费率档位（基点），0表示使用AMM的默认费率
+#[constant]
+pub const FEE_TIERS: [u16; 5] = [0, 1, 5, 30, 100];
+
+/// 池子滚动成交量的小时桶数量和每个桶的时长（秒）
+#[constant]
+pub const VOLUME_BUCKETS: usize = 24;
+
+#[constant]
+pub const VOLUME_BUCKET_SECS: i64 = 60 * 60;
+
+/// 交易者成交量统计周期（秒），手续费折扣按最近一个完整周期或当前周期的成交量计算
+#[constant]
+pub const VOLUME_PERIOD_SECS: i64 = 30 * 24 * 60 * 60;
+
+/// Amm账户的当前布局版本
+#[constant]
+pub const AMM_VERSION: u8 = 9;
+
+/// Pool账户的当前布局版本
+#[constant]
+pub const POOL_VERSION: u8 = 7;
+
+#[constant]
+pub const MINT_LIST_SEED: &[u8] = b"mint_list";
+
+/// 代币名单最多容纳的代币数量，账户大小保持在单次创建的10KB限制以内
+#[constant]
+pub const MAX_MINT_LIST_LEN: usize = 300;
+
+#[constant]
+pub const SWAP_COMMITMENT_SEED: &[u8] = b"swap_commitment";
+
+/// 提交交换承诺后必须在这么多个slot内揭示，超时后只能取消
+#[constant]
+pub const REVEAL_WINDOW_SLOTS: u64 = 150;
+
+#[constant]
+pub const PROGRAM_CONFIG_SEED: &[u8] = b"program_config";
+
+#[constant]
+pub const PENDING_CHANGE_SEED: &[u8] = b"pending_change";
+
+/// 时间锁延迟的上限（秒）
+#[constant]
+pub const MAX_TIMELOCK_DELAY: i64 = 30 * 24 * 60 * 60;
+
+#[constant]
+pub const GOVERNANCE_PROPOSAL_SEED: &[u8] = b"governance_proposal";
+
+#[constant]
+pub const VOTE_RECORD_SEED: &[u8] = b"vote_record";
+
+/// LP治理提案的投票期（秒）
+#[constant]
+pub const GOVERNANCE_VOTING_PERIOD: i64 = 3 * 24 * 60 * 60;
+
+/// 提案通过所需的赞成票占创建提案时流动性代币供应量的比例（基点）
+#[constant]
+pub const GOVERNANCE_QUORUM_BPS: u16 = 1000;
+
+#[constant]
+pub const DENYLIST_SEED: &[u8] = b"denylist";
+
+/// 地址黑名单最多容纳的地址数量，与代币名单一样保持在单次创建的10KB限制以内
+#[constant]
+pub const MAX_DENYLIST_LEN: usize = 300;
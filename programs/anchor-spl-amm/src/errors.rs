@@ -1,3 +1,4 @@
+use amm_math::MathError;
 use anchor_lang::prelude::*;
 
 #[error_code]
@@ -31,4 +32,279 @@ pub enum TutorialError {
     
     #[msg("Trade is not beneficial to the user")]
     TradeNotBeneficial,
-}
\ No newline at end of file
+
+    #[msg("Invalid fee configuration")]
+    InvalidFeeConfig,
+
+    #[msg("Pool still has outstanding liquidity")]
+    PoolNotEmpty,
+
+    #[msg("Protocol fees must be collected first")]
+    UncollectedProtocolFees,
+
+    #[msg("Input is above the maximum allowed")]
+    InputTooLarge,
+
+    #[msg("Not enough liquidity in the pool")]
+    InsufficientLiquidity,
+
+    #[msg("Invalid swap route")]
+    InvalidRoute,
+
+    #[msg("Transaction deadline has passed")]
+    TransactionExpired,
+
+    #[msg("Pool does not contain native SOL")]
+    NotNativeMintPool,
+
+    #[msg("Math overflow")]
+    MathOverflow,
+
+    #[msg("Invalid curve configuration")]
+    InvalidCurveConfig,
+
+    #[msg("Not enough oracle history for the requested window")]
+    InsufficientOracleHistory,
+
+    #[msg("A flash loan is in progress on this pool")]
+    FlashLoanActive,
+
+    #[msg("No flash loan is in progress on this pool")]
+    FlashLoanNotActive,
+
+    #[msg("The flash loan is not repaid within the transaction")]
+    FlashLoanNotRepaid,
+
+    #[msg("Deposit mints fewer liquidity tokens than the minimum")]
+    LiquidityOutputTooSmall,
+
+    #[msg("Withdrawal returns less token A than the minimum")]
+    WithdrawAmountATooSmall,
+
+    #[msg("Withdrawal returns less token B than the minimum")]
+    WithdrawAmountBTooSmall,
+
+    #[msg("The impermanent loss compensation vault is required")]
+    MissingCompensationVault,
+
+    #[msg("No impermanent loss compensation is due")]
+    NoCompensationDue,
+
+    #[msg("Mint A must sort before mint B")]
+    InvalidMintOrder,
+
+    #[msg("Pool mints must be different")]
+    IdenticalMints,
+
+    #[msg("Volatility was updated too recently")]
+    VolatilityUpdateTooSoon,
+
+    #[msg("Fee tier is not supported")]
+    InvalidFeeTier,
+
+    #[msg("Pool price has not reached the limit price")]
+    LimitPriceNotReached,
+
+    #[msg("The DCA schedule is not due yet")]
+    DcaNotDue,
+
+    #[msg("Invalid DCA schedule parameters")]
+    InvalidDcaSchedule,
+
+    #[msg("The pool's price oracle account is required")]
+    OracleAccountRequired,
+
+    #[msg("Invalid price oracle account")]
+    InvalidOracleAccount,
+
+    #[msg("The oracle price is stale")]
+    StaleOraclePrice,
+
+    #[msg("Execution price deviates too far from the oracle price")]
+    OraclePriceDeviation,
+
+    #[msg("Invalid oracle guard configuration")]
+    InvalidOracleGuard,
+
+    #[msg("Trade exceeds the maximum share of pool reserves")]
+    TradeTooLarge,
+
+    #[msg("Fee exemption discount must not exceed 100%")]
+    InvalidFeeExemption,
+
+    #[msg("Invalid withdrawal fee configuration")]
+    InvalidWithdrawalFeeConfig,
+
+    #[msg("Liquidity is still locked")]
+    LiquidityLocked,
+
+    #[msg("Invalid lock duration")]
+    InvalidLockDuration,
+
+    #[msg("Farm emission is still active")]
+    FarmActive,
+
+    #[msg("Invalid farm emission")]
+    InvalidEmission,
+
+    #[msg("Insufficient staked liquidity")]
+    InsufficientStake,
+
+    #[msg("Invalid farm reward index")]
+    InvalidRewardIndex,
+
+    #[msg("Farm reward slots are full")]
+    TooManyRewards,
+
+    #[msg("Vault compounded too recently")]
+    CompoundTooSoon,
+
+    #[msg("Insufficient vault shares")]
+    InsufficientShares,
+
+    #[msg("Invalid position price range")]
+    InvalidPriceRange,
+
+    #[msg("Position is not a range order")]
+    NotRangeOrder,
+
+    #[msg("Range order has not been filled")]
+    RangeOrderNotFilled,
+
+    #[msg("Trade would move the price outside the pool's range")]
+    PriceOutOfRange,
+    #[msg("Invalid concentrated liquidity config")]
+    InvalidConcentratedLiquidityConfig,
+    #[msg("Pool oracle account is required")]
+    MissingPoolOracle,
+    #[msg("Unsupported observation window")]
+    InvalidObservationWindow,
+    #[msg("Invalid volatility config")]
+    InvalidVolatilityConfig,
+    #[msg("Invalid price impact config")]
+    InvalidPriceImpactConfig,
+    #[msg("Account is already at the current version")]
+    AlreadyMigrated,
+    #[msg("Mint has a freeze authority")]
+    MintHasFreezeAuthority,
+    #[msg("Mint charges a transfer fee")]
+    MintHasTransferFee,
+    #[msg("Nested account does not match the top-level account")]
+    NestedAccountMismatch,
+    #[msg("Pool is locked by an instruction in progress")]
+    PoolLocked,
+    #[msg("Pool swap hook program missing or mismatched")]
+    SwapHookRequired,
+    #[msg("Mint is not allowed by the AMM mint list")]
+    MintNotAllowed,
+    #[msg("AMM mint list must be provided")]
+    MintListRequired,
+    #[msg("Mint list is full")]
+    MintListFull,
+    #[msg("Mint is already in the list")]
+    MintAlreadyListed,
+    #[msg("Mint is not in the list")]
+    MintNotListed,
+    #[msg("Relayer tip account missing or not owned by the relayer")]
+    InvalidRelayerAccount,
+    #[msg("Split proportions must cover the pools of one pair and sum to 10000 bps")]
+    InvalidSplit,
+    #[msg("Swap commitment missing or does not match the revealed parameters")]
+    CommitmentMismatch,
+    #[msg("Swap must be revealed after the commitment slot")]
+    RevealTooEarly,
+    #[msg("Swap reveal window has expired")]
+    RevealWindowExpired,
+    #[msg("Execution price is worse than the limit price")]
+    PriceLimitExceeded,
+    #[msg("Fees charged on the output token are not supported by this instruction")]
+    FeeOnOutputNotSupported,
+    #[msg("Host fee exceeds the cap set by the AMM admin")]
+    HostFeeTooHigh,
+    #[msg("Host fee account missing or not for the input token")]
+    InvalidHostFeeAccount,
+    #[msg("Input mint is neither token of the pool")]
+    InvalidInputMint,
+    #[msg("Input amount exceeds the trader's balance")]
+    InsufficientInputBalance,
+    #[msg("Deposit amounts deviate from the pool ratio by more than the tolerance")]
+    DepositRatioDeviation,
+    #[msg("Withdraw percentage must be between 1 and 10000 bps")]
+    InvalidWithdrawPct,
+    #[msg("Pool reserves have not been recorded, sync the pool first")]
+    ReservesNotRecorded,
+    #[msg("Liquidity can only be migrated into a different pool")]
+    SamePoolMigration,
+    #[msg("Deposit would exceed the pool's deposit cap")]
+    DepositCapExceeded,
+    #[msg("Only the pool creator or the AMM admin can perform this action")]
+    NotPoolCreatorOrAdmin,
+    #[msg("LP metadata requires the metadata account and the token metadata program")]
+    LpMetadataAccountsRequired,
+    #[msg("Only the program's upgrade authority can perform this action")]
+    NotUpgradeAuthority,
+    #[msg("Flash loans are disabled by the program config")]
+    FlashLoansDisabled,
+    #[msg("Token-2022 pools are disabled by the program config")]
+    Token2022Disabled,
+    #[msg("AMM creation is restricted to the program config authority")]
+    AmmCreationRestricted,
+    #[msg("Signer does not hold the role required for this action")]
+    MissingRole,
+    #[msg("The owner role can only be transferred, not revoked")]
+    CannotRevokeOwner,
+    #[msg("This change must go through the timelock")]
+    TimelockRequired,
+    #[msg("The parameter change is not queued")]
+    ChangeNotQueued,
+    #[msg("The parameter change is already queued")]
+    ChangeAlreadyQueued,
+    #[msg("The timelock delay has not elapsed")]
+    TimelockNotElapsed,
+    #[msg("Invalid timelock delay")]
+    InvalidTimelockDelay,
+    #[msg("A curve change cannot switch the curve kind")]
+    CurveKindMismatch,
+    #[msg("Voting on the proposal has ended")]
+    VotingClosed,
+    #[msg("Voting on the proposal has not ended")]
+    VotingNotEnded,
+    #[msg("The proposal did not pass")]
+    ProposalNotPassed,
+    #[msg("The proposal has already been executed")]
+    ProposalAlreadyExecuted,
+    #[msg("No locked liquidity to vote with")]
+    NoVotingWeight,
+    #[msg("The liquidity lock expires before voting ends")]
+    LockExpiresBeforeVoteEnds,
+    #[msg("The AMM is in exit-only mode")]
+    ExitOnly,
+    #[msg("The pool status does not allow this operation")]
+    PoolStatusRestricted,
+    #[msg("Address is on the AMM denylist")]
+    AddressDenied,
+    #[msg("The AMM denylist account is required")]
+    DenylistRequired,
+    #[msg("Address is already on the denylist")]
+    AddressAlreadyDenied,
+    #[msg("Address is not on the denylist")]
+    AddressNotDenied,
+    #[msg("The denylist is full")]
+    DenylistFull,
+    #[msg("A deposit record is required while the withdrawal fee is enabled")]
+    DepositRecordRequired,
+    #[msg("An admin token account is required to receive the compensation vault balance")]
+    AdminAccountRequired,
+    #[msg("An IL position must hold some liquidity")]
+    EmptyIlPosition,
+}
+
+impl From<MathError> for TutorialError {
+    fn from(error: MathError) -> Self {
+        match error {
+            MathError::Overflow => TutorialError::MathOverflow,
+            MathError::InsufficientLiquidity => TutorialError::InsufficientLiquidity,
+            MathError::InvariantViolated => TutorialError::InvariantViolated,
+        }
+    }
+}
@@ -0,0 +1,565 @@
+use anchor_lang::prelude::*;
+
+use crate::models::{
+    concentrated_liquidity::{ConcentratedLiquidityConfig, PriceRange},
+    fee_strategy::FeeConfig,
+    governance::GovernanceAction,
+    mint_policy::MintPolicy,
+    oracle_guard::OracleGuardConfig,
+    pool_status::PoolStatus,
+    price_impact::PriceImpactConfig,
+    program_config::FeatureFlags,
+    roles::Role,
+    timelock::ParameterChange,
+    volatility::VolatilityConfig,
+    withdrawal_fee::WithdrawalFeeConfig,
+};
+
+/// 交换完成事件
+#[event]
+pub struct SwapEvent {
+    pub pool: Pubkey,
+    pub trader: Pubkey,
+    /// true表示用代币A换代币B
+    pub swap_a: bool,
+    pub input_amount: u64,
+    pub output_amount: u64,
+    /// 交易者支付的总手续费（含协议分成）
+    pub fee_amount: u64,
+    pub protocol_fee: u64,
+    pub price_impact_bps: u16,
+    /// 交易后的池子储备
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+    /// 交换前后不变量的增量，LP可据此核对每笔交换都没有使不变量减少
+    pub invariant_delta: u128,
+}
+
+/// 添加流动性事件
+#[event]
+pub struct DepositEvent {
+    pub pool: Pubkey,
+    pub depositor: Pubkey,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub liquidity: u64,
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+}
+
+/// 提取流动性事件
+#[event]
+pub struct WithdrawEvent {
+    pub pool: Pubkey,
+    pub depositor: Pubkey,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub liquidity: u64,
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+    /// 实际收取的提取费率（基点）
+    pub withdrawal_fee_bps: u16,
+}
+
+/// 费率配置变更事件
+#[event]
+pub struct FeeConfigUpdatedEvent {
+    pub amm: Pubkey,
+    pub fee_config: FeeConfig,
+}
+
+/// 协议费用接收者变更事件
+#[event]
+pub struct FeeRecipientUpdatedEvent {
+    pub amm: Pubkey,
+    pub old_fee_recipient: Pubkey,
+    pub new_fee_recipient: Pubkey,
+}
+
+/// 协议费用提取事件
+#[event]
+pub struct ProtocolFeesCollectedEvent {
+    pub pool: Pubkey,
+    pub fee_recipient: Pubkey,
+    pub amount_a: u64,
+    pub amount_b: u64,
+}
+
+/// 闪电贷归还事件
+#[event]
+pub struct FlashLoanEvent {
+    pub pool: Pubkey,
+    pub borrower: Pubkey,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub fee_a: u64,
+    pub fee_b: u64,
+}
+
+/// 无常损失补偿领取事件
+#[event]
+pub struct IlCompensationClaimedEvent {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub amount_a: u64,
+    pub amount_b: u64,
+}
+
+/// LP手续费领取事件
+#[event]
+pub struct FeesClaimedEvent {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub liquidity: u64,
+    pub amount_a: u64,
+    pub amount_b: u64,
+}
+
+/// 波动率重新计算事件
+#[event]
+pub struct VolatilityUpdatedEvent {
+    pub pool: Pubkey,
+    pub volatility_raw: i128,
+}
+
+/// 限价单成交事件
+#[event]
+pub struct LimitOrderFilledEvent {
+    pub order: Pubkey,
+    pub owner: Pubkey,
+    pub filler: Pubkey,
+    pub input_amount: u64,
+    pub output_amount: u64,
+}
+
+/// 定投执行事件
+#[event]
+pub struct DcaExecutedEvent {
+    pub schedule: Pubkey,
+    pub owner: Pubkey,
+    pub keeper: Pubkey,
+    pub input_amount: u64,
+    pub output_amount: u64,
+    pub tip: u64,
+}
+
+/// 预言机保护配置更新事件
+#[event]
+pub struct OracleGuardUpdatedEvent {
+    pub pool: Pubkey,
+    pub oracle_guard: OracleGuardConfig,
+}
+
+/// 聚合流动性配置更新事件
+#[event]
+pub struct ConcentratedLiquidityConfigUpdatedEvent {
+    pub amm: Pubkey,
+    pub config: ConcentratedLiquidityConfig,
+}
+
+/// 池子价格区间迁移事件
+#[event]
+pub struct PoolPriceRangeUpdatedEvent {
+    pub pool: Pubkey,
+    pub price_range: PriceRange,
+}
+
+/// 熔断状态变化事件，由波动率超过阈值或管理员手动设置触发
+#[event]
+pub struct CircuitBreakerUpdatedEvent {
+    pub pool: Pubkey,
+    pub circuit_breaker_until: i64,
+}
+
+/// 费用豁免更新事件，移除豁免时折扣为0
+#[event]
+pub struct FeeExemptionUpdatedEvent {
+    pub amm: Pubkey,
+    pub address: Pubkey,
+    pub discount_bps: u16,
+}
+
+/// LP提取费配置变更事件
+#[event]
+pub struct WithdrawalFeeConfigUpdatedEvent {
+    pub amm: Pubkey,
+    pub withdrawal_fee_config: WithdrawalFeeConfig,
+}
+
+/// 流动性锁定事件
+#[event]
+pub struct LiquidityLockedEvent {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    /// 本次新增锁定的数量
+    pub amount: u64,
+    /// 锁中的总数量
+    pub total_locked: u64,
+    pub unlock_at: i64,
+}
+
+/// 锁定流动性领取加成手续费事件
+#[event]
+pub struct LockedFeesClaimedEvent {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    /// 手续费对应的流动性代币数量，从锁中销毁
+    pub liquidity: u64,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    /// 从协议费中支付的加成部分
+    pub bonus_a: u64,
+    pub bonus_b: u64,
+    pub boost_bps: u16,
+}
+
+/// 农场开始新一期奖励发放事件
+#[event]
+pub struct FarmFundedEvent {
+    pub farm: Pubkey,
+    pub reward_index: u8,
+    pub amount: u64,
+    pub emission_rate: u64,
+    pub end_time: i64,
+}
+
+/// 质押或取消质押流动性代币事件
+#[event]
+pub struct StakeUpdatedEvent {
+    pub farm: Pubkey,
+    pub owner: Pubkey,
+    /// 质押后的数量
+    pub amount: u64,
+    /// 质押后的权重
+    pub weight: u64,
+}
+
+/// 领取挖矿奖励事件
+#[event]
+pub struct RewardsClaimedEvent {
+    pub farm: Pubkey,
+    pub owner: Pubkey,
+    pub reward_index: u8,
+    pub amount: u64,
+}
+
+/// 农场新增奖励代币事件
+#[event]
+pub struct FarmRewardAddedEvent {
+    pub farm: Pubkey,
+    pub reward_index: u8,
+    pub mint: Pubkey,
+}
+
+/// 存入或取出自动复投金库事件
+#[event]
+pub struct VaultSharesUpdatedEvent {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    /// 存入为true，取出为false
+    pub deposit: bool,
+    pub liquidity: u64,
+    pub shares: u64,
+}
+
+/// 自动复投事件
+#[event]
+pub struct VaultCompoundedEvent {
+    pub vault: Pubkey,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub liquidity: u64,
+    /// 复投后金库持有的流动性代币数量
+    pub total_liquidity: u64,
+}
+
+/// 开立聚合流动性头寸事件
+#[event]
+pub struct PositionOpenedEvent {
+    pub pool: Pubkey,
+    pub position_mint: Pubkey,
+    pub owner: Pubkey,
+    pub liquidity: u64,
+    pub lower_price_x64: u128,
+    pub upper_price_x64: u128,
+    pub range_order: Option<bool>,
+}
+
+/// 关闭聚合流动性头寸事件
+#[event]
+pub struct PositionClosedEvent {
+    pub pool: Pubkey,
+    pub position_mint: Pubkey,
+    pub owner: Pubkey,
+    pub liquidity: u64,
+}
+
+/// 调整头寸流动性事件
+#[event]
+pub struct PositionLiquidityUpdatedEvent {
+    pub pool: Pubkey,
+    pub position_mint: Pubkey,
+    /// 增加为true，减少为false
+    pub increase: bool,
+    pub amount: u64,
+    /// 调整后的本金流动性
+    pub liquidity: u64,
+    /// 已结算的待领取手续费
+    pub fees_owed: u64,
+}
+
+/// 领取头寸手续费事件
+#[event]
+pub struct PositionFeesCollectedEvent {
+    pub pool: Pubkey,
+    pub position_mint: Pubkey,
+    /// 手续费对应的流动性代币数量，从头寸中销毁
+    pub liquidity: u64,
+    pub amount_a: u64,
+    pub amount_b: u64,
+}
+
+/// 区间订单成交关闭事件
+#[event]
+pub struct RangeOrderFilledEvent {
+    pub pool: Pubkey,
+    pub position_mint: Pubkey,
+    pub owner: Pubkey,
+    /// true表示卖出代币A换成代币B
+    pub swap_a: bool,
+    pub liquidity: u64,
+    pub output: u64,
+}
+
+/// 波动率配置变更事件
+#[event]
+pub struct VolatilityConfigUpdatedEvent {
+    pub amm: Pubkey,
+    pub volatility_config: VolatilityConfig,
+}
+
+/// 价格影响配置变更事件
+#[event]
+pub struct PriceImpactConfigUpdatedEvent {
+    pub amm: Pubkey,
+    pub price_impact_config: PriceImpactConfig,
+}
+
+/// 账户布局升级事件
+#[event]
+pub struct AccountMigratedEvent {
+    pub account: Pubkey,
+    pub version: u8,
+}
+
+/// 代币准入策略变更事件
+#[event]
+pub struct MintPolicyUpdatedEvent {
+    pub amm: Pubkey,
+    pub mint_policy: MintPolicy,
+}
+
+/// 交换钩子变更事件
+#[event]
+pub struct SwapHookUpdatedEvent {
+    pub pool: Pubkey,
+    pub swap_hook: Pubkey,
+}
+
+/// 代币名单变更事件，listed为false表示移出名单
+#[event]
+pub struct MintListUpdatedEvent {
+    pub amm: Pubkey,
+    pub mint: Pubkey,
+    pub listed: bool,
+}
+
+/// 中继交换事件，relayer为支付交易费和租金的中继方
+#[event]
+pub struct SwapRelayedEvent {
+    pub pool: Pubkey,
+    pub trader: Pubkey,
+    pub relayer: Pubkey,
+    pub relayer_tip: u64,
+}
+
+/// 部分成交事件：完整输入超出价格影响上限时只成交了filled_amount，其余留在交易者账户
+#[event]
+pub struct PartialFillEvent {
+    pub pool: Pubkey,
+    pub trader: Pubkey,
+    pub requested_amount: u64,
+    pub filled_amount: u64,
+}
+
+/// 交换承诺提交事件
+#[event]
+pub struct SwapCommittedEvent {
+    pub pool: Pubkey,
+    pub trader: Pubkey,
+    pub committed_slot: u64,
+}
+
+/// 手续费收取端变更事件
+#[event]
+pub struct FeeSideUpdatedEvent {
+    pub amm: Pubkey,
+    pub fee_on_output: bool,
+}
+
+/// 平台费上限变更事件
+#[event]
+pub struct MaxHostFeeUpdatedEvent {
+    pub amm: Pubkey,
+    pub max_host_fee_bps: u16,
+}
+
+/// 交换向前端托管方支付平台费事件
+#[event]
+pub struct HostFeePaidEvent {
+    pub pool: Pubkey,
+    pub trader: Pubkey,
+    pub host_fee_account: Pubkey,
+    pub host_fee: u64,
+}
+
+/// 池子储备同步事件
+#[event]
+pub struct ReservesSyncedEvent {
+    pub pool: Pubkey,
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+}
+
+/// 池子盈余转入协议费用账户事件
+#[event]
+pub struct ReservesSkimmedEvent {
+    pub pool: Pubkey,
+    pub amount_a: u64,
+    pub amount_b: u64,
+}
+
+/// 流动性在同一交易对的两个池子之间迁移事件
+#[event]
+pub struct LiquidityMigratedEvent {
+    pub from_pool: Pubkey,
+    pub to_pool: Pubkey,
+    pub depositor: Pubkey,
+    pub lp_amount: u64,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub liquidity: u64,
+    pub withdrawal_fee_bps: u16,
+}
+
+/// 池子存款上限变更事件
+#[event]
+pub struct DepositCapUpdatedEvent {
+    pub pool: Pubkey,
+    pub max_reserve_a: u64,
+    pub max_reserve_b: u64,
+}
+
+/// 功能开关变更事件
+#[event]
+pub struct ProgramConfigUpdatedEvent {
+    pub authority: Pubkey,
+    pub features: FeatureFlags,
+}
+
+/// 角色变更事件，撤销时holder为默认地址
+#[event]
+pub struct RoleUpdatedEvent {
+    pub amm: Pubkey,
+    pub role: Role,
+    pub holder: Pubkey,
+}
+
+/// 参数变更提议事件
+#[event]
+pub struct ParameterChangeProposedEvent {
+    pub amm: Pubkey,
+    pub pending_change: Pubkey,
+    pub proposer: Pubkey,
+    pub change: ParameterChange,
+}
+
+/// 参数变更排队事件，eta之后可以执行
+#[event]
+pub struct ParameterChangeQueuedEvent {
+    pub amm: Pubkey,
+    pub pending_change: Pubkey,
+    pub eta: i64,
+}
+
+/// 参数变更执行事件
+#[event]
+pub struct ParameterChangeExecutedEvent {
+    pub amm: Pubkey,
+    pub pending_change: Pubkey,
+    pub change: ParameterChange,
+}
+
+/// 参数变更取消事件
+#[event]
+pub struct ParameterChangeCancelledEvent {
+    pub amm: Pubkey,
+    pub pending_change: Pubkey,
+}
+
+/// 时间锁延迟变更事件
+#[event]
+pub struct TimelockDelayUpdatedEvent {
+    pub amm: Pubkey,
+    pub delay: i64,
+}
+
+/// LP治理提案创建事件
+#[event]
+pub struct GovernanceProposalCreatedEvent {
+    pub pool: Pubkey,
+    pub proposal: Pubkey,
+    pub proposer: Pubkey,
+    pub action: GovernanceAction,
+    pub voting_ends_at: i64,
+    pub quorum: u64,
+}
+
+/// LP治理投票事件
+#[event]
+pub struct VoteCastEvent {
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub support: bool,
+    pub weight: u64,
+}
+
+/// LP治理提案执行事件
+#[event]
+pub struct GovernanceProposalExecutedEvent {
+    pub pool: Pubkey,
+    pub proposal: Pubkey,
+    pub action: GovernanceAction,
+}
+
+/// 仅退出模式变更事件
+#[event]
+pub struct ExitOnlyUpdatedEvent {
+    pub amm: Pubkey,
+    pub exit_only: bool,
+}
+
+/// 池子状态变更事件
+#[event]
+pub struct PoolStatusUpdatedEvent {
+    pub pool: Pubkey,
+    pub status: PoolStatus,
+}
+
+/// 地址黑名单变更事件
+#[event]
+pub struct DenylistUpdatedEvent {
+    pub amm: Pubkey,
+    pub address: Pubkey,
+    pub denied: bool,
+}
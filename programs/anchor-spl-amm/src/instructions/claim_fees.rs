@@ -0,0 +1,430 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token::Token,
+    token_interface::{self, Burn, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    constants::{AUTHORITY_SEED, DEPOSIT_RECORD_SEED, ESCROW_SEED, FEE_POSITION_SEED, LIQUIDITY_SEED, MINIMUM_LIQUIDITY},
+    errors::TutorialError,
+    events::FeesClaimedEvent,
+    models::{lp_fees::LpFeeCalculator, withdrawal_fee::WithdrawalFeeConfig},
+    state::{Amm, DepositRecord, FeePosition, Pool},
+};
+
+/// 把流动性代币转入手续费头寸的托管账户，之后的手续费按托管的数量累计；
+/// 已有头寸时追加数量，检查点按流动性加权合并，已累计的手续费保持不变
+pub fn deposit_fee_position(ctx: Context<DepositFeePosition>, amount: u64) -> Result<()> {
+    let total_liquidity = ctx.accounts.mint_liquidity.supply + MINIMUM_LIQUIDITY;
+    let (reserve_a, reserve_b) = ctx.accounts.pool.reserves();
+    let growth = LpFeeCalculator::growth_per_share(&ctx.accounts.pool.curve_type, reserve_a, reserve_b, total_liquidity)?;
+
+    token_interface::transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.owner_account_liquidity.to_account_info(),
+                mint: ctx.accounts.mint_liquidity.to_account_info(),
+                to: ctx.accounts.escrow.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            },
+        ),
+        amount,
+        ctx.accounts.mint_liquidity.decimals,
+    )?;
+
+    let position = &mut ctx.accounts.fee_position;
+    position.pool = ctx.accounts.pool.key();
+    position.owner = ctx.accounts.owner.key();
+    position.deposit(amount, growth)?;
+
+    Ok(())
+}
+
+/// 取回托管的全部流动性代币并关闭头寸，未领取的手续费仍包含在流动性代币的价值中
+pub fn close_fee_position(ctx: Context<CloseFeePosition>) -> Result<()> {
+    let position = &ctx.accounts.fee_position;
+    let position_seeds = &[
+        position.pool.as_ref(),
+        position.owner.as_ref(),
+        FEE_POSITION_SEED,
+        &[ctx.bumps.fee_position],
+    ];
+    let signer_seeds = &[&position_seeds[..]];
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.escrow.to_account_info(),
+                mint: ctx.accounts.mint_liquidity.to_account_info(),
+                to: ctx.accounts.owner_account_liquidity.to_account_info(),
+                authority: ctx.accounts.fee_position.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        ctx.accounts.escrow.amount,
+        ctx.accounts.mint_liquidity.decimals,
+    )?;
+    token_interface::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.escrow.to_account_info(),
+            destination: ctx.accounts.owner.to_account_info(),
+            authority: ctx.accounts.fee_position.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
+
+    Ok(())
+}
+
+/// 领取LP手续费：只兑现托管的流动性自上次领取以来手续费带来的不变量增长，本金头寸保持不变
+///
+/// 领取时同样销毁流动性代币，因此与提取流动性一样按持有时间收取提取费并更新存款记录
+pub fn claim_fees(ctx: Context<ClaimFees>) -> Result<()> {
+    let (pool_a, pool_b) = ctx.accounts.pool.reserves();
+    let total_liquidity = ctx.accounts.mint_liquidity.supply + MINIMUM_LIQUIDITY;
+    let growth = LpFeeCalculator::growth_per_share(&ctx.accounts.pool.curve_type, pool_a, pool_b, total_liquidity)?;
+
+    // 1. 手续费以流动性代币计，只按托管的数量计算，按比例兑换为两种代币
+    let position = &mut ctx.accounts.fee_position;
+    let liquidity = LpFeeCalculator::fee_liquidity(position.liquidity, position.fee_growth_checkpoint, growth)?;
+    position.fee_growth_checkpoint = growth;
+    if liquidity == 0 {
+        return Ok(());
+    }
+    position.liquidity -= liquidity;
+    let share = |reserve: u64| {
+        (liquidity as u128)
+            .checked_mul(reserve as u128)
+            .and_then(|v| v.checked_div(total_liquidity as u128))
+            .and_then(|v| u64::try_from(v).ok())
+            .ok_or(TutorialError::MathOverflow)
+    };
+    let now = Clock::get()?.unix_timestamp;
+    let withdrawal_fee_bps = DepositRecord::withdrawal_fee_bps(
+        ctx.accounts.deposit_record.as_deref().map(|record| &**record),
+        &ctx.accounts.amm.withdrawal_fee_config,
+        liquidity,
+        now,
+    );
+    let amount_a = WithdrawalFeeConfig::apply(share(pool_a)?, withdrawal_fee_bps);
+    let amount_b = WithdrawalFeeConfig::apply(share(pool_b)?, withdrawal_fee_bps);
+
+    // 2. 转出手续费
+    let authority_bump = ctx.bumps.pool_authority;
+    let authority_seeds = &[
+        &ctx.accounts.pool.amm.to_bytes(),
+        &ctx.accounts.mint_a.key().to_bytes(),
+        &ctx.accounts.mint_b.key().to_bytes(),
+        &ctx.accounts.pool.fee_tier.to_le_bytes()[..],
+        AUTHORITY_SEED,
+        &[authority_bump],
+    ];
+    let signer_seeds = &[&authority_seeds[..]];
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program_a.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.pool_account_a.to_account_info(),
+                mint: ctx.accounts.mint_a.to_account_info(),
+                to: ctx.accounts.owner_account_a.to_account_info(),
+                authority: ctx.accounts.pool_authority.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount_a,
+        ctx.accounts.mint_a.decimals,
+    )?;
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program_b.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.pool_account_b.to_account_info(),
+                mint: ctx.accounts.mint_b.to_account_info(),
+                to: ctx.accounts.owner_account_b.to_account_info(),
+                authority: ctx.accounts.pool_authority.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount_b,
+        ctx.accounts.mint_b.decimals,
+    )?;
+
+    // 3. 从托管账户中销毁手续费对应的流动性代币，剩余代币对应的不变量与上次领取时相同
+    let position_seeds = &[
+        ctx.accounts.fee_position.pool.as_ref(),
+        ctx.accounts.fee_position.owner.as_ref(),
+        FEE_POSITION_SEED,
+        &[ctx.bumps.fee_position],
+    ];
+    token_interface::burn(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.mint_liquidity.to_account_info(),
+                from: ctx.accounts.escrow.to_account_info(),
+                authority: ctx.accounts.fee_position.to_account_info(),
+            },
+            &[&position_seeds[..]],
+        ),
+        liquidity,
+    )?;
+
+    if let Some(deposit_record) = ctx.accounts.deposit_record.as_mut() {
+        deposit_record.record_withdrawal(liquidity);
+    }
+
+    // 储备只减少转出的数量，提取费留在储备中
+    ctx.accounts.pool.remove_reserves(amount_a, amount_b)?;
+
+    emit!(FeesClaimedEvent {
+        pool: ctx.accounts.pool.key(),
+        owner: ctx.accounts.owner.key(),
+        liquidity,
+        amount_a,
+        amount_b,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimFees<'info> {
+    #[account(
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+    )]
+    pub amm: Box<Account<'info, Amm>>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.key().as_ref(),
+            pool.mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+        ],
+        bump,
+        has_one = amm,
+        has_one = mint_a,
+        has_one = mint_b,
+        constraint = !pool.flash_loan.active @ TutorialError::FlashLoanActive,
+        constraint = !pool.locked @ TutorialError::PoolLocked,
+        constraint = !pool.status.is_frozen() @ TutorialError::PoolStatusRestricted,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// CHECK: Read only authority
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            mint_a.key().as_ref(),
+            mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+            AUTHORITY_SEED,
+        ],
+        bump,
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool.key().as_ref(),
+            owner.key().as_ref(),
+            FEE_POSITION_SEED,
+        ],
+        bump,
+        has_one = owner,
+    )]
+    pub fee_position: Box<Account<'info, FeePosition>>,
+
+    #[account(
+        mut,
+        seeds = [
+            fee_position.key().as_ref(),
+            ESCROW_SEED,
+        ],
+        bump,
+    )]
+    pub escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The liquidity provider claiming the fees
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool.amm.as_ref(),
+            mint_a.key().as_ref(),
+            mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+            LIQUIDITY_SEED,
+        ],
+        bump,
+    )]
+    pub mint_liquidity: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_program_a)]
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_program_b)]
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_a,
+    )]
+    pub pool_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_b,
+        associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_b,
+    )]
+    pub pool_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint_a,
+        token::authority = owner,
+        token::token_program = token_program_a,
+    )]
+    pub owner_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint_b,
+        token::authority = owner,
+        token::token_program = token_program_b,
+    )]
+    pub owner_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// LP的存款记录，提供时按持有时间计算提取费
+    #[account(
+        mut,
+        seeds = [
+            pool.key().as_ref(),
+            owner.key().as_ref(),
+            DEPOSIT_RECORD_SEED,
+        ],
+        bump,
+    )]
+    pub deposit_record: Option<Box<Account<'info, DepositRecord>>>,
+
+    /// Solana ecosystem accounts
+    pub token_program: Program<'info, Token>,
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct DepositFeePosition<'info> {
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.as_ref(),
+            pool.mint_b.as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+            LIQUIDITY_SEED,
+        ],
+        bump,
+    )]
+    pub mint_liquidity: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = FeePosition::LEN,
+        seeds = [
+            pool.key().as_ref(),
+            owner.key().as_ref(),
+            FEE_POSITION_SEED,
+        ],
+        bump,
+    )]
+    pub fee_position: Box<Account<'info, FeePosition>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [
+            fee_position.key().as_ref(),
+            ESCROW_SEED,
+        ],
+        bump,
+        token::mint = mint_liquidity,
+        token::authority = fee_position,
+        token::token_program = token_program,
+    )]
+    pub escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The liquidity provider depositing into the position
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = mint_liquidity,
+        token::authority = owner,
+    )]
+    pub owner_account_liquidity: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The account paying for all rents
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Solana ecosystem accounts
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseFeePosition<'info> {
+    #[account(
+        mut,
+        seeds = [
+            fee_position.pool.as_ref(),
+            owner.key().as_ref(),
+            FEE_POSITION_SEED,
+        ],
+        bump,
+        has_one = owner,
+        close = owner,
+    )]
+    pub fee_position: Box<Account<'info, FeePosition>>,
+
+    #[account(
+        mut,
+        seeds = [
+            fee_position.key().as_ref(),
+            ESCROW_SEED,
+        ],
+        bump,
+    )]
+    pub escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = escrow.mint)]
+    pub mint_liquidity: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The owner of the position
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = mint_liquidity,
+        token::authority = owner,
+    )]
+    pub owner_account_liquidity: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Solana ecosystem accounts
+    pub token_program: Program<'info, Token>,
+}
@@ -0,0 +1,285 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Burn, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::{
+    constants::{AUTHORITY_SEED, IL_COMPENSATION_SEED, LIQUIDITY_SEED, POOL_ORACLE_SEED, PROTOCOL_FEE_SEED},
+    errors::TutorialError,
+    state::{Amm, Pool, PoolOracle},
+};
+
+/// 关闭没有流动性的池子，退还池子、预言机和各代币账户的租金
+///
+/// LP代币的mint无法关闭：旧版SPL Token程序不支持关闭mint，创建池子时也没有为
+/// Token-2022的mint启用MintCloseAuthority扩展。mint的供应量为0，权限归池子的PDA，
+/// 池子关闭后不会再有人能铸造LP代币
+pub fn close_pool(ctx: Context<ClosePool>) -> Result<()> {
+    let authority_bump = ctx.bumps.pool_authority;
+    let authority_seeds = &[
+        &ctx.accounts.pool.amm.to_bytes(),
+        &ctx.accounts.mint_a.key().to_bytes(),
+        &ctx.accounts.mint_b.key().to_bytes(),
+        &ctx.accounts.pool.fee_tier.to_le_bytes()[..],
+        AUTHORITY_SEED,
+        &[authority_bump],
+    ];
+    let signer_seeds = &[&authority_seeds[..]];
+
+    // 销毁锁定的MINIMUM_LIQUIDITY对应的残留代币，之后才能关闭代币账户
+    for (pool_account, mint, token_program) in [
+        (&ctx.accounts.pool_account_a, &ctx.accounts.mint_a, &ctx.accounts.token_program_a),
+        (&ctx.accounts.pool_account_b, &ctx.accounts.mint_b, &ctx.accounts.token_program_b),
+    ] {
+        if pool_account.amount > 0 {
+            token_interface::burn(
+                CpiContext::new_with_signer(
+                    token_program.to_account_info(),
+                    Burn {
+                        mint: mint.to_account_info(),
+                        from: pool_account.to_account_info(),
+                        authority: ctx.accounts.pool_authority.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                pool_account.amount,
+            )?;
+        }
+    }
+
+    // 补偿金库中剩余的资金转给管理员，之后与其他代币账户一起关闭；金库是可选的，未创建时跳过
+    let mut compensation_vaults = Vec::with_capacity(2);
+    for (vault, admin_account, mint, token_program) in [
+        (
+            &ctx.accounts.il_compensation_account_a,
+            &ctx.accounts.admin_account_a,
+            &ctx.accounts.mint_a,
+            &ctx.accounts.token_program_a,
+        ),
+        (
+            &ctx.accounts.il_compensation_account_b,
+            &ctx.accounts.admin_account_b,
+            &ctx.accounts.mint_b,
+            &ctx.accounts.token_program_b,
+        ),
+    ] {
+        if vault.data_is_empty() {
+            continue;
+        }
+        let amount = TokenAccount::try_deserialize(&mut &vault.try_borrow_data()?[..])?.amount;
+        if amount > 0 {
+            let admin_account = admin_account.as_ref().ok_or(TutorialError::AdminAccountRequired)?;
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    token_program.to_account_info(),
+                    TransferChecked {
+                        from: vault.to_account_info(),
+                        mint: mint.to_account_info(),
+                        to: admin_account.to_account_info(),
+                        authority: ctx.accounts.pool_authority.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                amount,
+                mint.decimals,
+            )?;
+        }
+        compensation_vaults.push((vault.to_account_info(), token_program));
+    }
+
+    // 关闭池代币账户、协议费用账户和补偿金库，租金退还给接收者
+    for (token_account, token_program) in [
+        (ctx.accounts.pool_account_a.to_account_info(), &ctx.accounts.token_program_a),
+        (ctx.accounts.pool_account_b.to_account_info(), &ctx.accounts.token_program_b),
+        (ctx.accounts.protocol_fee_account_a.to_account_info(), &ctx.accounts.token_program_a),
+        (ctx.accounts.protocol_fee_account_b.to_account_info(), &ctx.accounts.token_program_b),
+    ]
+    .into_iter()
+    .chain(compensation_vaults)
+    {
+        token_interface::close_account(CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            CloseAccount {
+                account: token_account,
+                destination: ctx.accounts.receiver.to_account_info(),
+                authority: ctx.accounts.pool_authority.to_account_info(),
+            },
+            signer_seeds,
+        ))?;
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClosePool<'info> {
+    #[account(
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+        has_one = admin,
+    )]
+    pub amm: Box<Account<'info, Amm>>,
+
+    /// The admin of the AMM
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        close = receiver,
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.key().as_ref(),
+            pool.mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+        ],
+        bump,
+        has_one = amm,
+        has_one = mint_a,
+        has_one = mint_b,
+        constraint = !pool.locked @ TutorialError::PoolLocked,
+        constraint = !pool.flash_loan.active @ TutorialError::FlashLoanActive,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        mut,
+        close = receiver,
+        seeds = [
+            pool.key().as_ref(),
+            POOL_ORACLE_SEED,
+        ],
+        bump,
+    )]
+    pub pool_oracle: Box<Account<'info, PoolOracle>>,
+
+    /// CHECK: Read only authority
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            mint_a.key().as_ref(),
+            mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+            AUTHORITY_SEED,
+        ],
+        bump,
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            mint_a.key().as_ref(),
+            mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+            LIQUIDITY_SEED,
+        ],
+        bump,
+        constraint = mint_liquidity.supply == 0 @ TutorialError::PoolNotEmpty,
+    )]
+    pub mint_liquidity: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        mint::token_program = token_program_a,
+    )]
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        mint::token_program = token_program_b,
+    )]
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_a,
+    )]
+    pub pool_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_b,
+        associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_b,
+    )]
+    pub pool_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool_authority.key().as_ref(),
+            mint_a.key().as_ref(),
+            PROTOCOL_FEE_SEED,
+        ],
+        bump,
+        token::token_program = token_program_a,
+        constraint = protocol_fee_account_a.amount == 0 @ TutorialError::UncollectedProtocolFees,
+    )]
+    pub protocol_fee_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool_authority.key().as_ref(),
+            mint_b.key().as_ref(),
+            PROTOCOL_FEE_SEED,
+        ],
+        bump,
+        token::token_program = token_program_b,
+        constraint = protocol_fee_account_b.amount == 0 @ TutorialError::UncollectedProtocolFees,
+    )]
+    pub protocol_fee_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// 无常损失补偿金库，可能尚未创建，因此按地址校验，在指令中判断是否存在
+    /// CHECK: Derived from the pool authority, deserialized as a token account when it exists
+    #[account(
+        mut,
+        seeds = [
+            pool_authority.key().as_ref(),
+            mint_a.key().as_ref(),
+            IL_COMPENSATION_SEED,
+        ],
+        bump,
+    )]
+    pub il_compensation_account_a: UncheckedAccount<'info>,
+
+    /// CHECK: Derived from the pool authority, deserialized as a token account when it exists
+    #[account(
+        mut,
+        seeds = [
+            pool_authority.key().as_ref(),
+            mint_b.key().as_ref(),
+            IL_COMPENSATION_SEED,
+        ],
+        bump,
+    )]
+    pub il_compensation_account_b: UncheckedAccount<'info>,
+
+    /// 接收补偿金库剩余资金的管理员代币账户，金库有余额时必须提供
+    #[account(
+        mut,
+        token::mint = mint_a,
+        token::authority = admin,
+        token::token_program = token_program_a,
+    )]
+    pub admin_account_a: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(
+        mut,
+        token::mint = mint_b,
+        token::authority = admin,
+        token::token_program = token_program_b,
+    )]
+    pub admin_account_b: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// The account receiving the reclaimed rent
+    /// CHECK: Any account can receive lamports
+    #[account(mut)]
+    pub receiver: AccountInfo<'info>,
+
+    /// Solana ecosystem accounts
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,103 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::SWAP_COMMITMENT_SEED,
+    errors::TutorialError,
+    events::SwapCommittedEvent,
+    models::swap_options::SwapOptions,
+    state::{Pool, SwapCommitment},
+};
+
+use super::swap_exact_tokens_for_tokens::{swap_exact_tokens_for_tokens_process, SwapExactTokensForTokens};
+
+/// 提交交换承诺：只公开参数哈希，搜索者无法根据具体参数抢跑或夹击
+pub fn commit_swap(ctx: Context<CommitSwap>, hash: [u8; 32]) -> Result<()> {
+    let swap_commitment = &mut ctx.accounts.swap_commitment;
+    swap_commitment.pool = ctx.accounts.pool.key();
+    swap_commitment.trader = ctx.accounts.trader.key();
+    swap_commitment.hash = hash;
+    swap_commitment.committed_slot = Clock::get()?.slot;
+
+    emit!(SwapCommittedEvent {
+        pool: swap_commitment.pool,
+        trader: swap_commitment.trader,
+        committed_slot: swap_commitment.committed_slot,
+    });
+
+    Ok(())
+}
+
+/// 揭示并执行交换：参数和盐值必须与承诺一致，并且在揭示窗口内，执行后关闭承诺账户
+pub fn reveal_swap<'info>(
+    mut ctx: Context<'_, '_, 'info, 'info, SwapExactTokensForTokens<'info>>,
+    input_mint: Pubkey,
+    input_amount: u64,
+    min_output_amount: u64,
+    salt: [u8; 32],
+) -> Result<()> {
+    let swap_commitment = ctx.accounts.swap_commitment.as_ref().ok_or(TutorialError::CommitmentMismatch)?;
+    if swap_commitment.hash != SwapCommitment::hash_params(&input_mint, input_amount, min_output_amount, &salt) {
+        return err!(TutorialError::CommitmentMismatch);
+    }
+    swap_commitment.check_reveal_slot(Clock::get()?.slot)?;
+    let swap_a = ctx.accounts.pool.swap_direction(&input_mint)?;
+
+    swap_exact_tokens_for_tokens_process(&mut ctx, swap_a, input_amount, min_output_amount, SwapOptions::default())?;
+
+    // 承诺只能使用一次，租金退还给交易者
+    let trader = ctx.accounts.trader.to_account_info();
+    if let Some(swap_commitment) = ctx.accounts.swap_commitment.as_ref() {
+        swap_commitment.close(trader)?;
+    }
+
+    Ok(())
+}
+
+/// 取消未揭示或已过期的交换承诺，租金退还给交易者
+pub fn cancel_swap_commitment(_ctx: Context<CancelSwapCommitment>) -> Result<()> {
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CommitSwap<'info> {
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        init,
+        payer = trader,
+        space = SwapCommitment::LEN,
+        seeds = [
+            pool.key().as_ref(),
+            trader.key().as_ref(),
+            SWAP_COMMITMENT_SEED,
+        ],
+        bump,
+    )]
+    pub swap_commitment: Box<Account<'info, SwapCommitment>>,
+
+    /// The account committing to the swap
+    #[account(mut)]
+    pub trader: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelSwapCommitment<'info> {
+    #[account(
+        mut,
+        seeds = [
+            swap_commitment.pool.as_ref(),
+            trader.key().as_ref(),
+            SWAP_COMMITMENT_SEED,
+        ],
+        bump,
+        has_one = trader,
+        close = trader,
+    )]
+    pub swap_commitment: Box<Account<'info, SwapCommitment>>,
+
+    /// The account that made the commitment
+    #[account(mut)]
+    pub trader: Signer<'info>,
+}
@@ -1,21 +1,30 @@
 use anchor_lang::prelude::*;
 
 use crate::{
+    constants::{AMM_VERSION, PROGRAM_CONFIG_SEED},
     errors::*,
-    state::Amm,
+    state::{Amm, ProgramConfig},
     models::{
         concentrated_liquidity::ConcentratedLiquidityConfig,
         price_impact::PriceImpactConfig,
         volatility::VolatilityConfig,
         fee_strategy::{FeeConfig, FeeStrategy},
+        withdrawal_fee::WithdrawalFeeConfig,
     },
 };
 
 pub fn create_amm(ctx: Context<CreateAmm>, id: Pubkey, fee: u16) -> Result<()> {
+    // 关闭无需许可创建后，只有配置权限可以创建AMM
+    let program_config = ProgramConfig::load(&ctx.accounts.program_config)?;
+    if !program_config.features.permissionless_amms && ctx.accounts.payer.key() != program_config.authority {
+        return err!(TutorialError::AmmCreationRestricted);
+    }
+
     let amm = &mut ctx.accounts.amm;
     amm.id = id;
     amm.admin = ctx.accounts.admin.key();
     amm.fee = fee;
+    amm.fee_recipient = ctx.accounts.admin.key();
     
     // 初始化默认配置
     amm.fee_config = FeeConfig {
@@ -24,11 +33,17 @@ pub fn create_amm(ctx: Context<CreateAmm>, id: Pubkey, fee: u16) -> Result<()> {
         max_fee_bps: fee * 2,         // 最高费率为设定的两倍
         base_fee_bps: fee,            // 基础费率即为设定值
         adjustment_factor: 500,       // 默认调整因子0.5
+        protocol_fee_bps: program_config.features.default_protocol_fee_bps, // 按全局配置的默认协议分成
+        il_compensation_fee_bps: 0,   // 默认不提取补偿资金
+        fee_a_to_b_bps: None,         // 默认两个方向费率相同
+        fee_b_to_a_bps: None,
     };
     
     amm.price_impact_config = PriceImpactConfig::default();
     amm.volatility_config = VolatilityConfig::default();
     amm.concentrated_liquidity_config = ConcentratedLiquidityConfig::default();
+    amm.withdrawal_fee_config = WithdrawalFeeConfig::default();
+    amm.version = AMM_VERSION;
     
     Ok(())
 }
@@ -52,6 +67,11 @@ pub struct CreateAmm<'info> {
     /// CHECK: Read only, delegatable creation
     pub admin: AccountInfo<'info>,
 
+    /// 全局程序配置，尚未创建时使用默认配置
+    /// CHECK: Address checked by seeds, loaded by ProgramConfig::load
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
+
     /// The account paying for all rents
     #[account(mut)]
     pub payer: Signer<'info>,
@@ -1,40 +1,109 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{prelude::*, solana_program::entrypoint::MAX_PERMITTED_DATA_INCREASE};
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{self, Mint, Token, TokenAccount},
+    metadata::{self, mpl_token_metadata::types::DataV2, CreateMetadataAccountsV3, Metadata},
+    token::Token,
+    token_2022,
+    token_interface::{Mint, TokenAccount, TokenInterface},
 };
 use fixed::types::I64F64;
 
 use crate::{
-    constants::{AUTHORITY_SEED, LIQUIDITY_SEED},
-    state::{Amm, Pool},
-    models::volatility::VolatilityTracker,
+    constants::{
+        AUTHORITY_SEED, FEE_TIERS, LIQUIDITY_SEED, OBSERVATION_WINDOWS, POOL_ORACLE_SEED, POOL_REF_SEED, POOL_VERSION,
+        PROGRAM_CONFIG_SEED, PROTOCOL_FEE_SEED,
+    },
+    errors::TutorialError,
+    state::{Amm, MintList, Pool, PoolOracle, PoolRef, ProgramConfig},
+    models::{concentrated_liquidity::PriceRange, curve::CurveType, lp_metadata::LpMetadata, volatility::VolatilityTracker},
 };
 
 // 分为两部分的指令实现
-pub fn create_pool(ctx: Context<CreatePool>, initial_price: u64) -> Result<()> {
+pub fn create_pool(
+    ctx: Context<CreatePool>,
+    initial_price: u64,
+    curve_type: CurveType,
+    fee_tier: u16,
+    observation_count: u16,
+    lp_metadata: Option<LpMetadata>,
+) -> Result<()> {
+    // 校验曲线参数
+    if !curve_type.is_valid() {
+        return err!(TutorialError::InvalidCurveConfig);
+    }
+
+    // 只允许预设的费率档位，避免同一交易对的流动性过度分散
+    if !FEE_TIERS.contains(&fee_tier) {
+        return err!(TutorialError::InvalidFeeTier);
+    }
+
+    // 预言机的价格样本数量只能从预设档位中选择
+    if !OBSERVATION_WINDOWS.contains(&observation_count) {
+        return err!(TutorialError::InvalidObservationWindow);
+    }
+
+    // 按AMM的准入策略检查两种代币
+    let mint_policy = &ctx.accounts.amm.mint_policy;
+    mint_policy.check(&ctx.accounts.mint_a)?;
+    mint_policy.check(&ctx.accounts.mint_b)?;
+
+    // AMM设置了代币名单时，两种代币都必须通过名单检查
+    if ctx.accounts.amm.mint_list != Pubkey::default() {
+        let mint_list = ctx.accounts.mint_list.as_ref().ok_or(TutorialError::MintListRequired)?;
+        mint_list.check(&ctx.accounts.mint_a.key())?;
+        mint_list.check(&ctx.accounts.mint_b.key())?;
+    }
+
+    // 全局配置关闭Token-2022后，两种代币都必须属于Token程序
+    let uses_token_2022 = [&ctx.accounts.token_program_a, &ctx.accounts.token_program_b]
+        .iter()
+        .any(|token_program| token_program.key() == token_2022::ID);
+    if uses_token_2022 && !ProgramConfig::load(&ctx.accounts.program_config)?.features.token_2022_enabled {
+        return err!(TutorialError::Token2022Disabled);
+    }
+
     // 首先初始化池
     let pool = &mut ctx.accounts.pool;
     pool.amm = ctx.accounts.amm.key();
     pool.mint_a = ctx.accounts.mint_a.key();
     pool.mint_b = ctx.accounts.mint_b.key();
+    pool.fee_tier = fee_tier;
+    pool.version = POOL_VERSION;
+    pool.creator = ctx.accounts.payer.key();
+    pool.decimals_a = ctx.accounts.mint_a.decimals;
+    pool.decimals_b = ctx.accounts.mint_b.decimals;
     
     // 设置初始价格
     pool.initial_price = initial_price;
+
+    // 设置定价曲线
+    pool.curve_type = curve_type;
     
-    // 初始化波动率追踪器
-    pool.volatility_tracker = VolatilityTracker::default();
-    
-    // 如果开启了集中流动性，计算价格范围
-    if ctx.accounts.amm.concentrated_liquidity_config.enabled {
-        let current_price = I64F64::from_num(initial_price);
-        let range_percentage = I64F64::from_num(ctx.accounts.amm.concentrated_liquidity_config.range_percentage) / I64F64::from_num(100);
-        
-        // 计算下限和上限价格
-        let _lower_price = current_price * (I64F64::from_num(1) - range_percentage);
-        let _upper_price = current_price * (I64F64::from_num(1) + range_percentage);
-        
-        // 未来可以将这些价格存储在池中，用于集中流动性范围的验证
+    // 如果开启了集中流动性，按初始价格计算价格区间并保存，交易时强制执行
+    let cl_config = &ctx.accounts.amm.concentrated_liquidity_config;
+    if cl_config.enabled && initial_price > 0 {
+        pool.price_range = PriceRange::around(I64F64::from_num(initial_price), cl_config.range_percentage);
+    }
+
+    // 初始化池子的波动率预言机
+    let pool_oracle = &mut ctx.accounts.pool_oracle;
+    pool_oracle.pool = pool.key();
+    pool_oracle.volatility_tracker = VolatilityTracker {
+        observation_count,
+        ..Default::default()
+    };
+
+    // 登记到AMM的池子注册表
+    let amm = &mut ctx.accounts.amm;
+    let pool_ref = &mut ctx.accounts.pool_ref;
+    pool_ref.amm = amm.key();
+    pool_ref.pool = pool.key();
+    pool_ref.index = amm.pool_count;
+    amm.pool_count += 1;
+
+    // 提供了元数据时为流动性代币创建Metaplex元数据，钱包不再显示为未知代币
+    if let Some(lp_metadata) = lp_metadata {
+        ctx.accounts.create_lp_metadata(lp_metadata, ctx.bumps.pool_authority)?;
     }
 
     Ok(())
@@ -42,9 +111,10 @@ pub fn create_pool(ctx: Context<CreatePool>, initial_price: u64) -> Result<()> {
 
 // 分割成两个更小的上下文结构体以减少堆栈使用
 #[derive(Accounts)]
-#[instruction(initial_price: u64)]
+#[instruction(initial_price: u64, curve_type: CurveType, fee_tier: u16, observation_count: u16, lp_metadata: Option<LpMetadata>)]
 pub struct CreatePool<'info> {
     #[account(
+        mut,
         seeds = [
             amm.id.as_ref()
         ],
@@ -60,17 +130,59 @@ pub struct CreatePool<'info> {
             amm.key().as_ref(),
             mint_a.key().as_ref(),
             mint_b.key().as_ref(),
+            fee_tier.to_le_bytes().as_ref(),
         ],
         bump,
+        // 交易对按规范顺序排列，每个交易对在每个费率档位下只能有一个池子
+        constraint = mint_a.key() != mint_b.key() @ TutorialError::IdenticalMints,
+        constraint = mint_a.key() < mint_b.key() @ TutorialError::InvalidMintOrder,
     )]
     pub pool: Box<Account<'info, Pool>>,
 
+    /// 池子的波动率预言机，与池子分开存储；单条指令最多分配10KB，
+    /// 超出的样本缓冲区通过 grow_pool_oracle 继续分配
+    #[account(
+        init,
+        payer = payer,
+        space = PoolOracle::space(observation_count).min(MAX_PERMITTED_DATA_INCREASE),
+        seeds = [
+            pool.key().as_ref(),
+            POOL_ORACLE_SEED,
+        ],
+        bump,
+    )]
+    pub pool_oracle: Box<Account<'info, PoolOracle>>,
+
+    /// 池子注册表条目，序号为AMM当前的池子数量
+    #[account(
+        init,
+        payer = payer,
+        space = PoolRef::LEN,
+        seeds = [
+            amm.key().as_ref(),
+            amm.pool_count.to_le_bytes().as_ref(),
+            POOL_REF_SEED,
+        ],
+        bump,
+    )]
+    pub pool_ref: Box<Account<'info, PoolRef>>,
+
+    /// AMM的代币名单，AMM未设置名单时可以不提供
+    #[account(address = amm.mint_list @ TutorialError::MintListRequired)]
+    pub mint_list: Option<Box<Account<'info, MintList>>>,
+
+    /// 全局程序配置，尚未创建时使用默认配置
+    /// CHECK: Address checked by seeds, loaded by ProgramConfig::load
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
+
     /// CHECK: Read only authority
     #[account(
         seeds = [
             amm.key().as_ref(),
             mint_a.key().as_ref(),
             mint_b.key().as_ref(),
+            fee_tier.to_le_bytes().as_ref(),
             AUTHORITY_SEED,
         ],
         bump,
@@ -84,20 +196,32 @@ pub struct CreatePool<'info> {
             amm.key().as_ref(),
             mint_a.key().as_ref(),
             mint_b.key().as_ref(),
+            fee_tier.to_le_bytes().as_ref(),
             LIQUIDITY_SEED,
         ],
         bump,
-        mint::decimals = 6,
+        mint::decimals = Pool::lp_decimals(mint_a.decimals, mint_b.decimals),
         mint::authority = pool_authority,
+        mint::token_program = token_program,
     )]
-    pub mint_liquidity: Box<Account<'info, Mint>>,
+    pub mint_liquidity: Box<InterfaceAccount<'info, Mint>>,
 
-    pub mint_a: Box<Account<'info, Mint>>,
+    #[account(mint::token_program = token_program_a)]
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
 
-    pub mint_b: Box<Account<'info, Mint>>,
+    #[account(mint::token_program = token_program_b)]
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
 
-    // 拆分账户减少同一时间验证的账户数量
+    // 拆分账户减少同一时间验证的账户数量，其中用于约束的账户必须与上面已校验的账户一致
     /// The liquidity pools
+    #[account(
+        constraint = token_accounts.mint_a.key() == mint_a.key() @ TutorialError::NestedAccountMismatch,
+        constraint = token_accounts.mint_b.key() == mint_b.key() @ TutorialError::NestedAccountMismatch,
+        constraint = token_accounts.pool_authority.key() == pool_authority.key() @ TutorialError::NestedAccountMismatch,
+        constraint = token_accounts.payer.key() == payer.key() @ TutorialError::NestedAccountMismatch,
+        constraint = token_accounts.token_program_a.key() == token_program_a.key() @ TutorialError::NestedAccountMismatch,
+        constraint = token_accounts.token_program_b.key() == token_program_b.key() @ TutorialError::NestedAccountMismatch,
+    )]
     pub token_accounts: TokenAccounts<'info>,
 
     /// The account paying for all rents
@@ -106,8 +230,63 @@ pub struct CreatePool<'info> {
 
     /// Solana ecosystem accounts
     pub token_program: Program<'info, Token>,
+    /// 代币A和代币B各自所属的代币程序（Token或Token-2022）
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
+
+    /// 流动性代币的元数据账户，由元数据程序校验地址，创建元数据时必须提供
+    /// CHECK: Initialized by the token metadata program
+    #[account(mut)]
+    pub lp_metadata: Option<UncheckedAccount<'info>>,
+
+    pub token_metadata_program: Option<Program<'info, Metadata>>,
+}
+
+impl<'info> CreatePool<'info> {
+    /// 以池子权限（流动性代币的铸造权限）创建流动性代币的元数据，更新权限同为池子权限
+    fn create_lp_metadata(&self, lp_metadata: LpMetadata, authority_bump: u8) -> Result<()> {
+        let (Some(metadata_account), Some(metadata_program)) = (&self.lp_metadata, &self.token_metadata_program) else {
+            return err!(TutorialError::LpMetadataAccountsRequired);
+        };
+        let authority_seeds = &[
+            &self.amm.key().to_bytes(),
+            &self.mint_a.key().to_bytes(),
+            &self.mint_b.key().to_bytes(),
+            &self.pool.fee_tier.to_le_bytes()[..],
+            AUTHORITY_SEED,
+            &[authority_bump],
+        ];
+        metadata::create_metadata_accounts_v3(
+            CpiContext::new_with_signer(
+                metadata_program.to_account_info(),
+                CreateMetadataAccountsV3 {
+                    metadata: metadata_account.to_account_info(),
+                    mint: self.mint_liquidity.to_account_info(),
+                    mint_authority: self.pool_authority.to_account_info(),
+                    payer: self.payer.to_account_info(),
+                    update_authority: self.pool_authority.to_account_info(),
+                    system_program: self.system_program.to_account_info(),
+                    // 元数据程序的v3指令已不再读取rent账户
+                    rent: self.system_program.to_account_info(),
+                },
+                &[&authority_seeds[..]],
+            ),
+            DataV2 {
+                name: lp_metadata.name,
+                symbol: lp_metadata.symbol,
+                uri: lp_metadata.uri,
+                seller_fee_basis_points: 0,
+                creators: None,
+                collection: None,
+                uses: None,
+            },
+            true,
+            true,
+            None,
+        )
+    }
 }
 
 // 单独的结构体持有池代币账户
@@ -118,16 +297,49 @@ pub struct TokenAccounts<'info> {
         payer = payer,
         associated_token::mint = mint_a,
         associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_a,
     )]
-    pub pool_account_a: Box<Account<'info, TokenAccount>>,
+    pub pool_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         init,
         payer = payer,
         associated_token::mint = mint_b,
         associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_b,
+    )]
+    pub pool_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// 协议费用累积账户
+    #[account(
+        init,
+        payer = payer,
+        seeds = [
+            pool_authority.key().as_ref(),
+            mint_a.key().as_ref(),
+            PROTOCOL_FEE_SEED,
+        ],
+        bump,
+        token::mint = mint_a,
+        token::authority = pool_authority,
+        token::token_program = token_program_a,
+    )]
+    pub protocol_fee_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [
+            pool_authority.key().as_ref(),
+            mint_b.key().as_ref(),
+            PROTOCOL_FEE_SEED,
+        ],
+        bump,
+        token::mint = mint_b,
+        token::authority = pool_authority,
+        token::token_program = token_program_b,
     )]
-    pub pool_account_b: Box<Account<'info, TokenAccount>>,
+    pub protocol_fee_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
 
     /// CHECK: Used in constraints
     pub mint_a: AccountInfo<'info>,
@@ -144,6 +356,7 @@ pub struct TokenAccounts<'info> {
     
     // 必须添加这些程序账户以实现init约束
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
\ No newline at end of file
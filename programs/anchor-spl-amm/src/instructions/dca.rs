@@ -0,0 +1,462 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::{
+    constants::{AUTHORITY_SEED, DCA_KEEPER_TIP_BPS, DCA_SEED, ESCROW_SEED, POOL_ORACLE_SEED, PROTOCOL_FEE_SEED},
+    errors::TutorialError,
+    events::DcaExecutedEvent,
+    state::{Amm, DcaSchedule, Denylist, Pool, PoolOracle},
+};
+
+use super::escrow_swap::EscrowSwap;
+
+/// 创建定投计划：托管全部输入代币，之后每个周期按固定数量换成输出代币
+pub fn create_dca_schedule(
+    ctx: Context<CreateDcaSchedule>,
+    id: u64,
+    total_amount: u64,
+    amount_per_interval: u64,
+    interval: i64,
+    min_price: u128,
+) -> Result<()> {
+    if amount_per_interval == 0 || interval <= 0 || total_amount < amount_per_interval {
+        return err!(TutorialError::InvalidDcaSchedule);
+    }
+
+    token_interface::transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program_in.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.owner_account_in.to_account_info(),
+                mint: ctx.accounts.mint_in.to_account_info(),
+                to: ctx.accounts.escrow.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            },
+        ),
+        total_amount,
+        ctx.accounts.mint_in.decimals,
+    )?;
+
+    // 第一期可以立即执行
+    let schedule = &mut ctx.accounts.schedule;
+    schedule.pool = ctx.accounts.pool.key();
+    schedule.owner = ctx.accounts.owner.key();
+    schedule.id = id;
+    schedule.swap_a = ctx.accounts.mint_in.key() == ctx.accounts.pool.mint_a;
+    schedule.amount_per_interval = amount_per_interval;
+    schedule.interval = interval;
+    schedule.min_price = min_price;
+    schedule.next_execution = Clock::get()?.unix_timestamp;
+
+    Ok(())
+}
+
+/// 执行一期定投：到期后任何人都可以调用，调用者从当期投入中获得小费
+pub fn execute_dca(ctx: Context<ExecuteDca>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let schedule = &ctx.accounts.schedule;
+    if now < schedule.next_execution {
+        return err!(TutorialError::DcaNotDue);
+    }
+
+    let (schedule_key, owner, swap_a) = (schedule.key(), schedule.owner, schedule.swap_a);
+    let id_bytes = schedule.id.to_le_bytes();
+    let schedule_seeds = &[
+        schedule.pool.as_ref(),
+        owner.as_ref(),
+        &id_bytes[..],
+        DCA_SEED,
+        &[ctx.bumps.schedule],
+    ];
+    let signer_seeds = &[&schedule_seeds[..]];
+
+    // 1. 计算本期投入、小费和可接受的最低输出
+    let amount = schedule.amount_per_interval.min(ctx.accounts.escrow.amount);
+    let tip = (amount as u128 * DCA_KEEPER_TIP_BPS as u128 / 10000) as u64;
+    let input = amount - tip;
+    let min_output = u64::try_from((input as u128).checked_mul(schedule.min_price).ok_or(TutorialError::MathOverflow)? >> 64)
+        .map_err(|_| error!(TutorialError::MathOverflow))?;
+
+    // 2. 通过池子交换，价格低于最低价格时本期不执行
+    let quote = EscrowSwap {
+        amm: &ctx.accounts.amm,
+        pool: &mut ctx.accounts.pool,
+        pool_oracle: ctx.accounts.pool_oracle.as_deref_mut(),
+        pool_authority: &ctx.accounts.pool_authority,
+        authority_bump: ctx.bumps.pool_authority,
+        mint_a: &ctx.accounts.mint_a,
+        mint_b: &ctx.accounts.mint_b,
+        pool_account_a: &mut ctx.accounts.pool_account_a,
+        pool_account_b: &mut ctx.accounts.pool_account_b,
+        protocol_fee_account_a: ctx.accounts.protocol_fee_account_a.to_account_info(),
+        protocol_fee_account_b: ctx.accounts.protocol_fee_account_b.to_account_info(),
+        token_program_a: ctx.accounts.token_program_a.to_account_info(),
+        token_program_b: ctx.accounts.token_program_b.to_account_info(),
+        escrow: ctx.accounts.escrow.to_account_info(),
+        escrow_authority: ctx.accounts.schedule.to_account_info(),
+        destination: ctx.accounts.owner_account_out.to_account_info(),
+        trader: owner,
+        denylist: ctx.accounts.denylist.as_deref().map(|denylist| &**denylist),
+        price_feed: ctx.accounts.price_feed.as_ref().map(|feed| feed.to_account_info()),
+    }
+    .execute(swap_a, input, min_output, signer_seeds)?;
+
+    // 3. 支付调用者小费
+    let (mint_in, token_program_in) = if swap_a {
+        (&ctx.accounts.mint_a, &ctx.accounts.token_program_a)
+    } else {
+        (&ctx.accounts.mint_b, &ctx.accounts.token_program_b)
+    };
+    if tip > 0 {
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                token_program_in.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.escrow.to_account_info(),
+                    mint: mint_in.to_account_info(),
+                    to: ctx.accounts.keeper_account.to_account_info(),
+                    authority: ctx.accounts.schedule.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            tip,
+            mint_in.decimals,
+        )?;
+    }
+
+    // 4. 托管代币用完后关闭计划，租金退还给创建者
+    ctx.accounts.escrow.reload()?;
+    if ctx.accounts.escrow.amount == 0 {
+        token_interface::close_account(CpiContext::new_with_signer(
+            token_program_in.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.escrow.to_account_info(),
+                destination: ctx.accounts.owner.to_account_info(),
+                authority: ctx.accounts.schedule.to_account_info(),
+            },
+            signer_seeds,
+        ))?;
+        ctx.accounts.schedule.close(ctx.accounts.owner.to_account_info())?;
+    } else {
+        ctx.accounts.schedule.next_execution = now + ctx.accounts.schedule.interval;
+    }
+
+    emit!(DcaExecutedEvent {
+        schedule: schedule_key,
+        owner,
+        keeper: ctx.accounts.keeper.key(),
+        input_amount: input,
+        output_amount: quote.net_output_amount,
+        tip,
+    });
+
+    Ok(())
+}
+
+/// 取消定投计划：退回剩余的托管代币并关闭计划
+pub fn cancel_dca_schedule(ctx: Context<CancelDcaSchedule>) -> Result<()> {
+    let schedule = &ctx.accounts.schedule;
+    let id_bytes = schedule.id.to_le_bytes();
+    let schedule_seeds = &[
+        schedule.pool.as_ref(),
+        schedule.owner.as_ref(),
+        &id_bytes[..],
+        DCA_SEED,
+        &[ctx.bumps.schedule],
+    ];
+    let signer_seeds = &[&schedule_seeds[..]];
+
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program_in.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.escrow.to_account_info(),
+                mint: ctx.accounts.mint_in.to_account_info(),
+                to: ctx.accounts.owner_account_in.to_account_info(),
+                authority: ctx.accounts.schedule.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        ctx.accounts.escrow.amount,
+        ctx.accounts.mint_in.decimals,
+    )?;
+    token_interface::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program_in.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.escrow.to_account_info(),
+            destination: ctx.accounts.owner.to_account_info(),
+            authority: ctx.accounts.schedule.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct CreateDcaSchedule<'info> {
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.key().as_ref(),
+            pool.mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = DcaSchedule::LEN,
+        seeds = [
+            pool.key().as_ref(),
+            owner.key().as_ref(),
+            id.to_le_bytes().as_ref(),
+            DCA_SEED,
+        ],
+        bump,
+    )]
+    pub schedule: Box<Account<'info, DcaSchedule>>,
+
+    #[account(
+        init,
+        payer = owner,
+        seeds = [
+            schedule.key().as_ref(),
+            ESCROW_SEED,
+        ],
+        bump,
+        token::mint = mint_in,
+        token::authority = schedule,
+        token::token_program = token_program_in,
+    )]
+    pub escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The account creating the schedule and paying for all rents
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mint::token_program = token_program_in,
+        constraint = mint_in.key() == pool.mint_a || mint_in.key() == pool.mint_b @ TutorialError::InvalidMint,
+    )]
+    pub mint_in: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = mint_in,
+        token::authority = owner,
+        token::token_program = token_program_in,
+    )]
+    pub owner_account_in: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Solana ecosystem accounts
+    pub token_program_in: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteDca<'info> {
+    #[account(
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+    )]
+    pub amm: Box<Account<'info, Amm>>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.key().as_ref(),
+            pool.mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+        ],
+        bump,
+        has_one = amm,
+        has_one = mint_a,
+        has_one = mint_b,
+        constraint = !pool.flash_loan.active @ TutorialError::FlashLoanActive,
+        constraint = !pool.locked @ TutorialError::PoolLocked,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// 池子的波动率预言机，启用波动率跟踪或动态费率时必须提供
+    #[account(
+        mut,
+        seeds = [
+            pool.key().as_ref(),
+            POOL_ORACLE_SEED,
+        ],
+        bump,
+    )]
+    pub pool_oracle: Option<Box<Account<'info, PoolOracle>>>,
+
+    /// CHECK: Read only authority
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            mint_a.key().as_ref(),
+            mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+            AUTHORITY_SEED,
+        ],
+        bump,
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool.key().as_ref(),
+            owner.key().as_ref(),
+            schedule.id.to_le_bytes().as_ref(),
+            DCA_SEED,
+        ],
+        bump,
+        has_one = pool,
+        has_one = owner,
+    )]
+    pub schedule: Box<Account<'info, DcaSchedule>>,
+
+    #[account(
+        mut,
+        seeds = [
+            schedule.key().as_ref(),
+            ESCROW_SEED,
+        ],
+        bump,
+    )]
+    pub escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: The schedule owner, receives the rent back once the schedule completes
+    #[account(mut)]
+    pub owner: AccountInfo<'info>,
+
+    /// The owner's account receiving the output token
+    #[account(
+        mut,
+        token::authority = owner,
+        constraint = owner_account_out.mint == if schedule.swap_a { pool.mint_b } else { pool.mint_a } @ TutorialError::InvalidMint,
+    )]
+    pub owner_account_out: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The account executing the schedule
+    pub keeper: Signer<'info>,
+
+    /// The keeper's account receiving the tip in the input token
+    #[account(
+        mut,
+        token::authority = keeper,
+        constraint = keeper_account.mint == escrow.mint @ TutorialError::InvalidMint,
+    )]
+    pub keeper_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mint::token_program = token_program_a)]
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_program_b)]
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_a,
+    )]
+    pub pool_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_b,
+        associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_b,
+    )]
+    pub pool_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool_authority.key().as_ref(),
+            mint_a.key().as_ref(),
+            PROTOCOL_FEE_SEED,
+        ],
+        bump,
+    )]
+    pub protocol_fee_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool_authority.key().as_ref(),
+            mint_b.key().as_ref(),
+            PROTOCOL_FEE_SEED,
+        ],
+        bump,
+    )]
+    pub protocol_fee_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// 池子配置的Pyth价格账户，未启用预言机保护时可以省略
+    /// CHECK: 地址和数据在预言机保护检查中校验
+    pub price_feed: Option<UncheckedAccount<'info>>,
+
+    /// AMM的地址黑名单，未设置黑名单时可以省略
+    #[account(address = amm.denylist @ TutorialError::DenylistRequired)]
+    pub denylist: Option<Box<Account<'info, Denylist>>>,
+
+    /// Solana ecosystem accounts
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CancelDcaSchedule<'info> {
+    #[account(
+        mut,
+        seeds = [
+            schedule.pool.as_ref(),
+            owner.key().as_ref(),
+            schedule.id.to_le_bytes().as_ref(),
+            DCA_SEED,
+        ],
+        bump,
+        has_one = owner,
+        close = owner,
+    )]
+    pub schedule: Box<Account<'info, DcaSchedule>>,
+
+    #[account(
+        mut,
+        seeds = [
+            schedule.key().as_ref(),
+            ESCROW_SEED,
+        ],
+        bump,
+    )]
+    pub escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The account that created the schedule
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        address = escrow.mint,
+        mint::token_program = token_program_in,
+    )]
+    pub mint_in: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = mint_in,
+        token::authority = owner,
+        token::token_program = token_program_in,
+    )]
+    pub owner_account_in: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Solana ecosystem accounts
+    pub token_program_in: Interface<'info, TokenInterface>,
+}
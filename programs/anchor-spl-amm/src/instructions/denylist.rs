@@ -0,0 +1,138 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{DENYLIST_SEED, MAX_DENYLIST_LEN},
+    errors::TutorialError,
+    events::DenylistUpdatedEvent,
+    models::roles::Role,
+    state::{Amm, Denylist},
+};
+
+/// 为AMM创建地址黑名单，之后交换和存款必须提供名单并通过检查
+pub fn create_denylist(ctx: Context<CreateDenylist>) -> Result<()> {
+    let denylist = &mut ctx.accounts.denylist;
+    denylist.amm = ctx.accounts.amm.key();
+    ctx.accounts.amm.denylist = denylist.key();
+
+    Ok(())
+}
+
+/// 关闭地址黑名单，取消交换和存款时的检查，租金退还给管理员
+pub fn close_denylist(ctx: Context<CloseDenylist>) -> Result<()> {
+    ctx.accounts.amm.denylist = Pubkey::default();
+
+    Ok(())
+}
+
+/// 将地址加入黑名单
+pub fn add_to_denylist(ctx: Context<UpdateDenylist>, address: Pubkey) -> Result<()> {
+    let denylist = &mut ctx.accounts.denylist;
+    if denylist.addresses.contains(&address) {
+        return err!(TutorialError::AddressAlreadyDenied);
+    }
+    if denylist.addresses.len() >= MAX_DENYLIST_LEN {
+        return err!(TutorialError::DenylistFull);
+    }
+    denylist.addresses.push(address);
+
+    emit!(DenylistUpdatedEvent {
+        amm: denylist.amm,
+        address,
+        denied: true,
+    });
+
+    Ok(())
+}
+
+/// 将地址移出黑名单
+pub fn remove_from_denylist(ctx: Context<UpdateDenylist>, address: Pubkey) -> Result<()> {
+    let denylist = &mut ctx.accounts.denylist;
+    let index = denylist
+        .addresses
+        .iter()
+        .position(|denied| *denied == address)
+        .ok_or(TutorialError::AddressNotDenied)?;
+    denylist.addresses.swap_remove(index);
+
+    emit!(DenylistUpdatedEvent {
+        amm: denylist.amm,
+        address,
+        denied: false,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateDenylist<'info> {
+    #[account(
+        mut,
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+        constraint = amm.has_role(Role::RiskManager, &admin.key()) @ TutorialError::MissingRole,
+    )]
+    pub amm: Box<Account<'info, Amm>>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = Denylist::LEN,
+        seeds = [
+            amm.key().as_ref(),
+            DENYLIST_SEED,
+        ],
+        bump,
+    )]
+    pub denylist: Box<Account<'info, Denylist>>,
+
+    /// The owner or the risk manager of the AMM
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseDenylist<'info> {
+    #[account(
+        mut,
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+        constraint = amm.has_role(Role::RiskManager, &admin.key()) @ TutorialError::MissingRole,
+        has_one = denylist,
+    )]
+    pub amm: Box<Account<'info, Amm>>,
+
+    #[account(
+        mut,
+        close = admin,
+    )]
+    pub denylist: Box<Account<'info, Denylist>>,
+
+    /// The owner or the risk manager of the AMM
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateDenylist<'info> {
+    #[account(
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+        constraint = amm.has_role(Role::RiskManager, &admin.key()) @ TutorialError::MissingRole,
+        has_one = denylist,
+    )]
+    pub amm: Box<Account<'info, Amm>>,
+
+    #[account(mut)]
+    pub denylist: Box<Account<'info, Denylist>>,
+
+    /// The owner or the risk manager of the AMM
+    pub admin: Signer<'info>,
+}
@@ -1,61 +1,75 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{self, Mint, MintTo, Token, TokenAccount, Transfer},
+    token::Token,
+    token_interface::{self, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked},
 };
 use fixed::types::I64F64;
 
 use crate::{
-    constants::{AUTHORITY_SEED, LIQUIDITY_SEED, MINIMUM_LIQUIDITY},
+    constants::{AUTHORITY_SEED, DEPOSIT_RECORD_SEED, LIQUIDITY_SEED, MINIMUM_INITIAL_LIQUIDITY, MINIMUM_LIQUIDITY},
     errors::TutorialError,
-    state::{Pool, Amm},
+    events::DepositEvent,
+    models::curve::CurveCalculator,
+    state::{DepositRecord, Denylist, Pool, Amm},
+    utils::{check_deadline, get_transfer_fee, input_within_balance},
 };
 
 // 分为两部分的指令实现
 pub fn deposit_liquidity(
-    ctx: Context<DepositLiquidity>,
+    mut ctx: Context<DepositLiquidity>,
     amount_a: u64,
     amount_b: u64,
+    min_lp_tokens_out: u64,
+    deadline: Option<i64>,
+    max_ratio_deviation_bps: Option<u16>,
+    clamp_to_balance: bool,
 ) -> Result<()> {
-    deposit_liquidity_process(ctx, amount_a, amount_b)
+    // 拒绝已过期的交易，避免以过时的价格执行
+    check_deadline(deadline)?;
+
+    deposit_liquidity_process(&mut ctx, amount_a, amount_b, min_lp_tokens_out, max_ratio_deviation_bps, clamp_to_balance)
 }
 
-// 处理实际的存款逻辑
-fn deposit_liquidity_process(
-    ctx: Context<DepositLiquidity>,
+/// 存款数量比例 amount_a/amount_b 相对池子比例 pool_a/pool_b 的偏离（基点）
+fn ratio_deviation_bps(amount_a: u64, amount_b: u64, pool_a: u64, pool_b: u64) -> Option<u128> {
+    let provided = (amount_a as u128).checked_mul(pool_b as u128)?;
+    let expected = (amount_b as u128).checked_mul(pool_a as u128)?;
+    if expected == 0 {
+        return if provided == 0 { Some(0) } else { Some(u128::MAX) };
+    }
+    provided.abs_diff(expected).checked_mul(10000)?.checked_div(expected)
+}
+
+/// 按池子比例调整的存款数量，首次存款按原样存入
+pub(crate) fn deposit_amounts(
     amount_a: u64,
     amount_b: u64,
-) -> Result<()> {
-    // Prevent depositing assets the depositor does not own
-    let mut amount_a = if amount_a > ctx.accounts.depositor_account_a.amount {
-        ctx.accounts.depositor_account_a.amount
-    } else {
-        amount_a
-    };
-    let mut amount_b = if amount_b > ctx.accounts.depositor_account_b.amount {
-        ctx.accounts.depositor_account_b.amount
-    } else {
-        amount_b
-    };
-
-    // Making sure they are provided in the same proportion as existing liquidity
-    let pool_a = &ctx.accounts.pool_account_a;
-    let pool_b = &ctx.accounts.pool_account_b;
-    // Defining pool creation like this allows attackers to frontrun pool creation with bad ratios
-    let pool_creation = pool_a.amount == 0 && pool_b.amount == 0;
-    (amount_a, amount_b) = if pool_creation {
-        // Add as is if there is no liquidity
+    (pool_a, pool_b): (u64, u64),
+    pool_creation: bool,
+    max_ratio_deviation_bps: Option<u16>,
+) -> Result<(u64, u64)> {
+    let amounts = if pool_creation {
+        // Add as is if there is no liquidity, tokens sent to the pool beforehand stay surplus
         (amount_a, amount_b)
     } else {
-        let ratio = I64F64::from_num(pool_a.amount)
-            .checked_mul(I64F64::from_num(pool_b.amount))
-            .unwrap();
-        if pool_a.amount > pool_b.amount {
+        // 设置了容差时，提供的数量比例偏离池子比例过多则拒绝，而不是按池子比例重新计算
+        if let Some(max_deviation_bps) = max_ratio_deviation_bps {
+            let deviation_bps = ratio_deviation_bps(amount_a, amount_b, pool_a, pool_b)
+                .ok_or(TutorialError::MathOverflow)?;
+            if deviation_bps > max_deviation_bps as u128 {
+                return err!(TutorialError::DepositRatioDeviation);
+            }
+        }
+        let ratio = I64F64::from_num(pool_a)
+            .checked_div(I64F64::from_num(pool_b))
+            .ok_or(TutorialError::MathOverflow)?;
+        if pool_a > pool_b {
             (
                 I64F64::from_num(amount_b)
                     .checked_mul(ratio)
-                    .unwrap()
-                    .to_num::<u64>(),
+                    .and_then(|v| v.checked_to_num::<u64>())
+                    .ok_or(TutorialError::MathOverflow)?,
                 amount_b,
             )
         } else {
@@ -63,50 +77,108 @@ fn deposit_liquidity_process(
                 amount_a,
                 I64F64::from_num(amount_a)
                     .checked_div(ratio)
-                    .unwrap()
-                    .to_num::<u64>(),
+                    .and_then(|v| v.checked_to_num::<u64>())
+                    .ok_or(TutorialError::MathOverflow)?,
             )
         }
     };
+    Ok(amounts)
+}
 
-    // Computing the amount of liquidity about to be deposited
-    let mut liquidity = I64F64::from_num(amount_a)
-        .checked_mul(I64F64::from_num(amount_b))
-        .unwrap()
-        .sqrt()
-        .to_num::<u64>();
-
+/// 池子实际收到的数量对应铸造的流动性代币数量，reserves为存入前的储备，supply为流动性代币的供应量
+///
+/// 首次存款按曲线计算流动性；之后按两侧占储备的较小比例铸造，
+/// 与提取时的份额计算一致，存入后立即提取不能带走已累计的手续费
+pub(crate) fn minted_liquidity(pool: &Pool, received_a: u64, received_b: u64, reserves: (u64, u64), supply: u64) -> Result<u64> {
     // Lock some minimum liquidity on the first deposit
-    if pool_creation {
-        if liquidity < MINIMUM_LIQUIDITY {
+    // 锁定部分永不铸造，但计入所有份额计算的总量，首个LP无法以极少的份额抬高单位份额价格
+    if supply == 0 {
+        let liquidity = CurveCalculator::deposit_liquidity(&pool.curve_type, received_a, received_b)?;
+        if liquidity < MINIMUM_INITIAL_LIQUIDITY {
             return err!(TutorialError::DepositTooSmall);
         }
 
-        liquidity -= MINIMUM_LIQUIDITY;
+        return Ok(liquidity - MINIMUM_LIQUIDITY);
+    }
+
+    let (reserve_a, reserve_b) = reserves;
+    if reserve_a == 0 || reserve_b == 0 {
+        return err!(TutorialError::InsufficientLiquidity);
+    }
+    let total_liquidity = supply as u128 + MINIMUM_LIQUIDITY as u128;
+    let share = |received: u64, reserve: u64| received as u128 * total_liquidity / reserve as u128;
+    u64::try_from(share(received_a, reserve_a).min(share(received_b, reserve_b)))
+        .map_err(|_| error!(TutorialError::MathOverflow))
+}
+
+// 处理实际的存款逻辑
+pub(crate) fn deposit_liquidity_process(
+    ctx: &mut Context<DepositLiquidity>,
+    amount_a: u64,
+    amount_b: u64,
+    min_lp_tokens_out: u64,
+    max_ratio_deviation_bps: Option<u16>,
+    clamp_to_balance: bool,
+) -> Result<()> {
+    Denylist::check(&ctx.accounts.amm, ctx.accounts.denylist.as_deref().map(|denylist| &**denylist), &ctx.accounts.depositor.key())?;
+    DepositRecord::check_required(&ctx.accounts.amm.withdrawal_fee_config, ctx.accounts.deposit_record.as_deref().map(|record| &**record))?;
+    Pool::lock(&mut ctx.accounts.pool)?;
+
+    // Prevent depositing assets the depositor does not own
+    // 超出余额时只有clamp_to_balance才按余额存入，否则拒绝
+    let mut amount_a = input_within_balance(amount_a, ctx.accounts.depositor_account_a.amount, clamp_to_balance)?;
+    let mut amount_b = input_within_balance(amount_b, ctx.accounts.depositor_account_b.amount, clamp_to_balance)?;
+
+    // Making sure they are provided in the same proportion as existing liquidity
+    let (pool_a, pool_b) = ctx.accounts.pool.reserves();
+    // 按流动性代币供应量判断首次存款，直接向池代币账户转入少量代币不能绕过最低流动性锁定
+    let pool_creation = ctx.accounts.mint_liquidity.supply == 0;
+    (amount_a, amount_b) = deposit_amounts(amount_a, amount_b, (pool_a, pool_b), pool_creation, max_ratio_deviation_bps)?;
+
+    // Token-2022的转账费用会在转入时扣除，按池子实际收到的数量计算流动性
+    let received_a = amount_a - get_transfer_fee(&ctx.accounts.mint_a.to_account_info(), amount_a)?;
+    let received_b = amount_b - get_transfer_fee(&ctx.accounts.mint_b.to_account_info(), amount_b)?;
+
+    // Computing the amount of liquidity about to be deposited
+    let liquidity = minted_liquidity(
+        &ctx.accounts.pool,
+        received_a,
+        received_b,
+        (pool_a, pool_b),
+        ctx.accounts.mint_liquidity.supply,
+    )?;
+
+    // 滑点保护：防止存款被夹击后以不利的比例铸造流动性代币
+    if liquidity < min_lp_tokens_out {
+        return err!(TutorialError::LiquidityOutputTooSmall);
     }
 
     // Transfer tokens to the pool
-    token::transfer(
+    token_interface::transfer_checked(
         CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            ctx.accounts.token_program_a.to_account_info(),
+            TransferChecked {
                 from: ctx.accounts.depositor_account_a.to_account_info(),
+                mint: ctx.accounts.mint_a.to_account_info(),
                 to: ctx.accounts.pool_account_a.to_account_info(),
                 authority: ctx.accounts.depositor.to_account_info(),
             },
         ),
         amount_a,
+        ctx.accounts.mint_a.decimals,
     )?;
-    token::transfer(
+    token_interface::transfer_checked(
         CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            ctx.accounts.token_program_b.to_account_info(),
+            TransferChecked {
                 from: ctx.accounts.depositor_account_b.to_account_info(),
+                mint: ctx.accounts.mint_b.to_account_info(),
                 to: ctx.accounts.pool_account_b.to_account_info(),
                 authority: ctx.accounts.depositor.to_account_info(),
             },
         ),
         amount_b,
+        ctx.accounts.mint_b.decimals,
     )?;
 
     // Mint the liquidity to user
@@ -115,11 +187,12 @@ fn deposit_liquidity_process(
         &ctx.accounts.pool.amm.to_bytes(),
         &ctx.accounts.mint_a.key().to_bytes(),
         &ctx.accounts.mint_b.key().to_bytes(),
+        &ctx.accounts.pool.fee_tier.to_le_bytes()[..],
         AUTHORITY_SEED,
         &[authority_bump],
     ];
     let signer_seeds = &[&authority_seeds[..]];
-    token::mint_to(
+    token_interface::mint_to(
         CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
             MintTo {
@@ -132,6 +205,29 @@ fn deposit_liquidity_process(
         liquidity,
     )?;
 
+    // 更新存款记录的加权存入时间，已有流动性按存款前实际持有的数量计算
+    let held_liquidity = ctx.accounts.depositor_account_liquidity.amount;
+    if let Some(deposit_record) = ctx.accounts.deposit_record.as_mut() {
+        deposit_record.record_deposit(held_liquidity, liquidity, Clock::get()?.unix_timestamp);
+    }
+
+    ctx.accounts.pool.stats.record_deposit();
+
+    // 储备只增加池子实际收到的数量，直接转入池子的代币不会并入储备
+    ctx.accounts.pool.add_reserves(received_a, received_b)?;
+    let (reserve_a, reserve_b) = ctx.accounts.pool.reserves();
+    ctx.accounts.pool.check_deposit_cap(reserve_a, reserve_b)?;
+    emit!(DepositEvent {
+        pool: ctx.accounts.pool.key(),
+        depositor: ctx.accounts.depositor.key(),
+        amount_a,
+        amount_b,
+        liquidity,
+        reserve_a,
+        reserve_b,
+    });
+    ctx.accounts.pool.unlock();
+
     Ok(())
 }
 
@@ -142,18 +238,25 @@ pub struct DepositLiquidity<'info> {
             amm.id.as_ref()
         ],
         bump,
+        constraint = !amm.exit_only @ TutorialError::ExitOnly,
     )]
     pub amm: Box<Account<'info, Amm>>,
 
     #[account(
+        mut,
         seeds = [
             pool.amm.as_ref(),
             pool.mint_a.key().as_ref(),
             pool.mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
         ],
         bump,
+        has_one = amm,
         has_one = mint_a,
         has_one = mint_b,
+        constraint = !pool.flash_loan.active @ TutorialError::FlashLoanActive,
+        constraint = !pool.locked @ TutorialError::PoolLocked,
+        constraint = pool.status.allows_deposits() @ TutorialError::PoolStatusRestricted,
     )]
     pub pool: Box<Account<'info, Pool>>,
 
@@ -163,6 +266,7 @@ pub struct DepositLiquidity<'info> {
             pool.amm.as_ref(),
             mint_a.key().as_ref(),
             mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
             AUTHORITY_SEED,
         ],
         bump,
@@ -172,57 +276,81 @@ pub struct DepositLiquidity<'info> {
     /// The account paying for all rents
     pub depositor: Signer<'info>,
 
+    /// AMM的地址黑名单，未设置黑名单时可以省略
+    #[account(address = amm.denylist @ TutorialError::DenylistRequired)]
+    pub denylist: Option<Box<Account<'info, Denylist>>>,
+
     #[account(
         mut,
         seeds = [
             pool.amm.as_ref(),
             mint_a.key().as_ref(),
             mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
             LIQUIDITY_SEED,
         ],
         bump,
     )]
-    pub mint_liquidity: Box<Account<'info, Mint>>,
+    pub mint_liquidity: Box<InterfaceAccount<'info, Mint>>,
 
-    pub mint_a: Box<Account<'info, Mint>>,
+    #[account(mint::token_program = token_program_a)]
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
 
-    pub mint_b: Box<Account<'info, Mint>>,
+    #[account(mint::token_program = token_program_b)]
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
         mut,
         associated_token::mint = mint_a,
         associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_a,
     )]
-    pub pool_account_a: Box<Account<'info, TokenAccount>>,
+    pub pool_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
         associated_token::mint = mint_b,
         associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_b,
     )]
-    pub pool_account_b: Box<Account<'info, TokenAccount>>,
+    pub pool_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         init_if_needed,
         payer = payer,
         associated_token::mint = mint_liquidity,
         associated_token::authority = depositor,
+        associated_token::token_program = token_program,
     )]
-    pub depositor_account_liquidity: Box<Account<'info, TokenAccount>>,
+    pub depositor_account_liquidity: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
         associated_token::mint = mint_a,
         associated_token::authority = depositor,
+        associated_token::token_program = token_program_a,
     )]
-    pub depositor_account_a: Box<Account<'info, TokenAccount>>,
+    pub depositor_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
         associated_token::mint = mint_b,
         associated_token::authority = depositor,
+        associated_token::token_program = token_program_b,
+    )]
+    pub depositor_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// LP的存款记录，记录按流动性加权的存入时间，用于计算提取费；启用提取费时必须提供
+    #[account(
+        mut,
+        seeds = [
+            pool.key().as_ref(),
+            depositor.key().as_ref(),
+            DEPOSIT_RECORD_SEED,
+        ],
+        bump,
     )]
-    pub depositor_account_b: Box<Account<'info, TokenAccount>>,
+    pub deposit_record: Option<Box<Account<'info, DepositRecord>>>,
 
     /// The account paying for all rents
     #[account(mut)]
@@ -230,6 +358,8 @@ pub struct DepositLiquidity<'info> {
 
     /// Solana ecosystem accounts
     pub token_program: Program<'info, Token>,
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
\ No newline at end of file
@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::DEPOSIT_RECORD_SEED,
+    state::{DepositRecord, Pool},
+};
+
+/// 创建LP在池子中的存款记录，之后的存款和提取传入该账户即可按持有时间减免提取费
+///
+/// 记录创建前已持有的流动性不计入记录，按刚存入计算提取费
+pub fn create_deposit_record(ctx: Context<CreateDepositRecord>) -> Result<()> {
+    let deposit_record = &mut ctx.accounts.deposit_record;
+    deposit_record.pool = ctx.accounts.pool.key();
+    deposit_record.owner = ctx.accounts.owner.key();
+    deposit_record.deposited_at = Clock::get()?.unix_timestamp;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateDepositRecord<'info> {
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = DepositRecord::LEN,
+        seeds = [
+            pool.key().as_ref(),
+            owner.key().as_ref(),
+            DEPOSIT_RECORD_SEED,
+        ],
+        bump,
+    )]
+    pub deposit_record: Box<Account<'info, DepositRecord>>,
+
+    /// LP，同时支付账户租金
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
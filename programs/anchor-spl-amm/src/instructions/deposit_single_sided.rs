@@ -0,0 +1,296 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::Token,
+    token_interface::{self, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    constants::{AUTHORITY_SEED, DEPOSIT_RECORD_SEED, LIQUIDITY_SEED, POOL_ORACLE_SEED},
+    errors::TutorialError,
+    events::DepositEvent,
+    instructions::{minted_liquidity, swap_fee_rate_bps},
+    models::curve::CurveCalculator,
+    state::{Amm, DepositRecord, Denylist, Pool, PoolOracle},
+    utils::{check_deadline, get_transfer_fee, input_within_balance},
+};
+
+/// 单边存款：只提供一种代币，其中约一半按池子价格（扣除手续费）换成另一种代币后再存入
+pub fn deposit_single_sided(
+    ctx: Context<DepositSingleSided>,
+    deposit_a: bool,
+    amount: u64,
+    min_lp_tokens_out: u64,
+    deadline: Option<i64>,
+    clamp_to_balance: bool,
+) -> Result<()> {
+    check_deadline(deadline)?;
+    Denylist::check(&ctx.accounts.amm, ctx.accounts.denylist.as_deref().map(|denylist| &**denylist), &ctx.accounts.depositor.key())?;
+    DepositRecord::check_required(&ctx.accounts.amm.withdrawal_fee_config, ctx.accounts.deposit_record.as_deref().map(|record| &**record))?;
+    Pool::lock(&mut ctx.accounts.pool)?;
+
+    // 1. 校验来源账户的代币种类，超出余额时只有clamp_to_balance才按余额存入
+    let mint_in = if deposit_a { &ctx.accounts.mint_a } else { &ctx.accounts.mint_b };
+    if ctx.accounts.depositor_account.mint != mint_in.key() {
+        return err!(TutorialError::InvalidMint);
+    }
+    let amount = input_within_balance(amount, ctx.accounts.depositor_account.amount, clamp_to_balance)?;
+
+    // 2. 空池子没有价格，无法进行单边存款
+    let (pool_a, pool_b) = ctx.accounts.pool.reserves();
+    if pool_a == 0 || pool_b == 0 {
+        return err!(TutorialError::InsufficientLiquidity);
+    }
+    let (reserve_in, reserve_out) = if deposit_a { (pool_a, pool_b) } else { (pool_b, pool_a) };
+
+    // 3. 将池子实际收到的数量拆成两半，一半在池内按曲线虚拟交换
+    let received = amount
+        .checked_sub(get_transfer_fee(&mint_in.to_account_info(), amount)?)
+        .ok_or(TutorialError::MathOverflow)?;
+    let swap_amount = received / 2;
+    let deposit_amount = received - swap_amount;
+    let fee_rate_bps = swap_fee_rate_bps(
+        &ctx.accounts.amm,
+        &ctx.accounts.pool,
+        ctx.accounts.pool_oracle.as_deref().map(|oracle| &**oracle),
+        deposit_a,
+        swap_amount,
+        (reserve_in, reserve_out),
+    );
+    let fee_amount = (swap_amount as u128 * fee_rate_bps as u128 / 10000) as u64;
+    let swap_output = CurveCalculator::swap_output(
+        &ctx.accounts.pool.curve_type,
+        deposit_a,
+        swap_amount - fee_amount,
+        reserve_in,
+        reserve_out,
+    )?;
+
+    // 4. 用剩余的一半和交换所得按虚拟交换后的储备比例计算流动性，交换手续费留在池子里归LP所有
+    let (liquidity_a, liquidity_b) = if deposit_a {
+        (deposit_amount, swap_output)
+    } else {
+        (swap_output, deposit_amount)
+    };
+    let swapped_in = reserve_in.checked_add(swap_amount).ok_or(TutorialError::MathOverflow)?;
+    let swapped_out = reserve_out.checked_sub(swap_output).ok_or(TutorialError::MathOverflow)?;
+    let swapped_reserves = if deposit_a { (swapped_in, swapped_out) } else { (swapped_out, swapped_in) };
+    let liquidity = minted_liquidity(
+        &ctx.accounts.pool,
+        liquidity_a,
+        liquidity_b,
+        swapped_reserves,
+        ctx.accounts.mint_liquidity.supply,
+    )?;
+    if liquidity < min_lp_tokens_out {
+        return err!(TutorialError::LiquidityOutputTooSmall);
+    }
+
+    // 5. 转入全部代币
+    let token_program = if deposit_a { &ctx.accounts.token_program_a } else { &ctx.accounts.token_program_b };
+    let pool_account = if deposit_a { &ctx.accounts.pool_account_a } else { &ctx.accounts.pool_account_b };
+    token_interface::transfer_checked(
+        CpiContext::new(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.depositor_account.to_account_info(),
+                mint: mint_in.to_account_info(),
+                to: pool_account.to_account_info(),
+                authority: ctx.accounts.depositor.to_account_info(),
+            },
+        ),
+        amount,
+        mint_in.decimals,
+    )?;
+
+    // 6. 铸造流动性代币
+    let authority_bump = ctx.bumps.pool_authority;
+    let authority_seeds = &[
+        &ctx.accounts.pool.amm.to_bytes(),
+        &ctx.accounts.mint_a.key().to_bytes(),
+        &ctx.accounts.mint_b.key().to_bytes(),
+        &ctx.accounts.pool.fee_tier.to_le_bytes()[..],
+        AUTHORITY_SEED,
+        &[authority_bump],
+    ];
+    let signer_seeds = &[&authority_seeds[..]];
+    token_interface::mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.mint_liquidity.to_account_info(),
+                to: ctx.accounts.depositor_account_liquidity.to_account_info(),
+                authority: ctx.accounts.pool_authority.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        liquidity,
+    )?;
+
+    // 7. 单边存款会改变池子价格，交易后的价格不能超出聚合流动性区间，同步更新预言机
+    //    储备只增加池子实际收到的数量，直接转入池子的代币不会并入储备
+    let now = Clock::get()?.unix_timestamp;
+    let held_liquidity = ctx.accounts.depositor_account_liquidity.amount;
+    if let Some(deposit_record) = ctx.accounts.deposit_record.as_mut() {
+        deposit_record.record_deposit(held_liquidity, liquidity, now);
+    }
+    let pool = &mut ctx.accounts.pool;
+    if deposit_a {
+        pool.add_reserves(received, 0)?;
+    } else {
+        pool.add_reserves(0, received)?;
+    }
+    let (reserve_a, reserve_b) = pool.reserves();
+    pool.price_range.check(reserve_a, reserve_b)?;
+    pool.oracle.update(reserve_a, reserve_b, now);
+    pool.check_deposit_cap(reserve_a, reserve_b)?;
+    pool.stats.record_volume(deposit_a, swap_amount, swap_output, fee_amount, now);
+    pool.stats.record_deposit();
+
+    emit!(DepositEvent {
+        pool: ctx.accounts.pool.key(),
+        depositor: ctx.accounts.depositor.key(),
+        amount_a: if deposit_a { amount } else { 0 },
+        amount_b: if deposit_a { 0 } else { amount },
+        liquidity,
+        reserve_a,
+        reserve_b,
+    });
+    ctx.accounts.pool.unlock();
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct DepositSingleSided<'info> {
+    #[account(
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+        constraint = !amm.exit_only @ TutorialError::ExitOnly,
+    )]
+    pub amm: Box<Account<'info, Amm>>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.key().as_ref(),
+            pool.mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+        ],
+        bump,
+        has_one = amm,
+        has_one = mint_a,
+        has_one = mint_b,
+        constraint = !pool.flash_loan.active @ TutorialError::FlashLoanActive,
+        constraint = !pool.locked @ TutorialError::PoolLocked,
+        constraint = pool.status.allows_deposits() @ TutorialError::PoolStatusRestricted,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// 池子的波动率预言机，动态费率按其中的波动率计算，未提供时按无波动处理
+    #[account(
+        seeds = [
+            pool.key().as_ref(),
+            POOL_ORACLE_SEED,
+        ],
+        bump,
+    )]
+    pub pool_oracle: Option<Box<Account<'info, PoolOracle>>>,
+
+    /// CHECK: Read only authority
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            mint_a.key().as_ref(),
+            mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+            AUTHORITY_SEED,
+        ],
+        bump,
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    /// The account depositing the single token
+    pub depositor: Signer<'info>,
+
+    /// AMM的地址黑名单，未设置黑名单时可以省略
+    #[account(address = amm.denylist @ TutorialError::DenylistRequired)]
+    pub denylist: Option<Box<Account<'info, Denylist>>>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool.amm.as_ref(),
+            mint_a.key().as_ref(),
+            mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+            LIQUIDITY_SEED,
+        ],
+        bump,
+    )]
+    pub mint_liquidity: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_program_a)]
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_program_b)]
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_a,
+    )]
+    pub pool_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_b,
+        associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_b,
+    )]
+    pub pool_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = mint_liquidity,
+        associated_token::authority = depositor,
+        associated_token::token_program = token_program,
+    )]
+    pub depositor_account_liquidity: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The depositor's account holding the token being deposited
+    #[account(
+        mut,
+        token::authority = depositor,
+    )]
+    pub depositor_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// LP的存款记录，记录按流动性加权的存入时间，用于计算提取费；启用提取费时必须提供
+    #[account(
+        mut,
+        seeds = [
+            pool.key().as_ref(),
+            depositor.key().as_ref(),
+            DEPOSIT_RECORD_SEED,
+        ],
+        bump,
+    )]
+    pub deposit_record: Option<Box<Account<'info, DepositRecord>>>,
+
+    /// The account paying for all rents
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Solana ecosystem accounts
+    pub token_program: Program<'info, Token>,
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,234 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TransferChecked};
+
+use crate::{
+    constants::AUTHORITY_SEED,
+    errors::TutorialError,
+    events::SwapEvent,
+    models::{curve::CurveCalculator, quote::SwapQuote},
+    state::{Amm, Denylist, Pool, PoolOracle},
+    utils::get_transfer_fee,
+};
+
+use super::swap_exact_tokens_for_tokens::{check_oracle_guard, quote_exact_input};
+
+/// 由程序托管账户发起的精确输入交换，供限价单、定投等代替用户执行交换的指令共用
+///
+/// 托管账户不携带补偿金库账户，补偿分成与路由交换一样留在池子里归LP所有
+pub(crate) struct EscrowSwap<'a, 'info> {
+    pub amm: &'a Amm,
+    pub pool: &'a mut Account<'info, Pool>,
+    /// 池子的波动率预言机，启用波动率跟踪或动态费率时必须提供
+    pub pool_oracle: Option<&'a mut Account<'info, PoolOracle>>,
+    pub pool_authority: &'a AccountInfo<'info>,
+    pub authority_bump: u8,
+    pub mint_a: &'a InterfaceAccount<'info, Mint>,
+    pub mint_b: &'a InterfaceAccount<'info, Mint>,
+    pub pool_account_a: &'a mut InterfaceAccount<'info, TokenAccount>,
+    pub pool_account_b: &'a mut InterfaceAccount<'info, TokenAccount>,
+    pub protocol_fee_account_a: AccountInfo<'info>,
+    pub protocol_fee_account_b: AccountInfo<'info>,
+    pub token_program_a: AccountInfo<'info>,
+    pub token_program_b: AccountInfo<'info>,
+    /// 托管输入代币的账户及其权限账户（PDA）
+    pub escrow: AccountInfo<'info>,
+    pub escrow_authority: AccountInfo<'info>,
+    /// 接收输出代币的账户
+    pub destination: AccountInfo<'info>,
+    /// 交换的受益人，记录在交换事件中，并按AMM的地址黑名单检查
+    pub trader: Pubkey,
+    /// AMM的地址黑名单，未设置黑名单时为None
+    pub denylist: Option<&'a Denylist>,
+    /// 池子配置的Pyth价格账户
+    pub price_feed: Option<AccountInfo<'info>>,
+}
+
+impl<'a, 'info> EscrowSwap<'a, 'info> {
+    /// 以托管账户的代币执行交换，输出低于 min_output 时拒绝执行
+    pub fn execute(
+        self,
+        swap_a: bool,
+        input: u64,
+        min_output: u64,
+        escrow_signer_seeds: &[&[&[u8]]],
+    ) -> Result<SwapQuote> {
+        Denylist::check(self.amm, self.denylist, &self.trader)?;
+        Pool::lock(self.pool)?;
+        // 托管交换不携带钩子账户，配置了交换钩子的池子不能代为交换
+        if self.pool.swap_hook != Pubkey::default() {
+            return err!(TutorialError::SwapHookRequired);
+        }
+
+        // 1. 按与报价相同的路径计算费用和输出
+        let (mint_in, mint_out) = if swap_a { (self.mint_a, self.mint_b) } else { (self.mint_b, self.mint_a) };
+        let (reserve_a, reserve_b) = self.pool.reserves();
+        let (reserve_in, reserve_out) = if swap_a { (reserve_a, reserve_b) } else { (reserve_b, reserve_a) };
+        let quote = quote_exact_input(
+            self.amm,
+            self.pool,
+            self.pool_oracle.as_deref().map(|oracle| &**oracle),
+            (&mint_in.to_account_info(), &mint_out.to_account_info()),
+            input,
+            0,
+            &Clock::get()?,
+        )?;
+        if quote.net_output_amount < min_output {
+            return err!(TutorialError::LimitPriceNotReached);
+        }
+        check_oracle_guard(
+            self.pool,
+            self.price_feed.as_ref(),
+            swap_a,
+            quote.taxed_input,
+            quote.output_amount,
+            self.mint_a.decimals,
+            self.mint_b.decimals,
+        )?;
+
+        let invariant = CurveCalculator::invariant(&self.pool.curve_type, reserve_a, reserve_b)?;
+
+        // 2. 托管账户转入池子和协议费用账户，池子转出到接收账户
+        let (token_program_in, token_program_out) = if swap_a {
+            (self.token_program_a.clone(), self.token_program_b.clone())
+        } else {
+            (self.token_program_b.clone(), self.token_program_a.clone())
+        };
+        let (pool_account_in, pool_account_out, protocol_fee_account_in, protocol_fee_account_out) = if swap_a {
+            (
+                self.pool_account_a.to_account_info(),
+                self.pool_account_b.to_account_info(),
+                self.protocol_fee_account_a,
+                self.protocol_fee_account_b,
+            )
+        } else {
+            (
+                self.pool_account_b.to_account_info(),
+                self.pool_account_a.to_account_info(),
+                self.protocol_fee_account_b,
+                self.protocol_fee_account_a,
+            )
+        };
+        // 手续费以输出代币收取时输入全部进入池子，协议分成由池子从输出端转出
+        let input_side_protocol_fee = if quote.fee_on_output { 0 } else { quote.protocol_fee };
+        let output_side_protocol_fee = if quote.fee_on_output { quote.protocol_fee } else { 0 };
+        // 补偿分成留在池子里，池子收到的输入按实际转入的数量计算
+        let pool_transfer = input.checked_sub(input_side_protocol_fee).ok_or(TutorialError::MathOverflow)?;
+        let pool_received = pool_transfer
+            .checked_sub(get_transfer_fee(&mint_in.to_account_info(), pool_transfer)?)
+            .ok_or(TutorialError::MathOverflow)?;
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                token_program_in.clone(),
+                TransferChecked {
+                    from: self.escrow.clone(),
+                    mint: mint_in.to_account_info(),
+                    to: pool_account_in,
+                    authority: self.escrow_authority.clone(),
+                },
+                escrow_signer_seeds,
+            ),
+            pool_transfer,
+            mint_in.decimals,
+        )?;
+        if input_side_protocol_fee > 0 {
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    token_program_in,
+                    TransferChecked {
+                        from: self.escrow,
+                        mint: mint_in.to_account_info(),
+                        to: protocol_fee_account_in,
+                        authority: self.escrow_authority,
+                    },
+                    escrow_signer_seeds,
+                ),
+                quote.protocol_fee,
+                mint_in.decimals,
+            )?;
+        }
+        let authority_seeds = &[
+            &self.pool.amm.to_bytes(),
+            &self.pool.mint_a.to_bytes(),
+            &self.pool.mint_b.to_bytes(),
+            &self.pool.fee_tier.to_le_bytes()[..],
+            AUTHORITY_SEED,
+            &[self.authority_bump],
+        ];
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                token_program_out.clone(),
+                TransferChecked {
+                    from: pool_account_out.clone(),
+                    mint: mint_out.to_account_info(),
+                    to: self.destination,
+                    authority: self.pool_authority.clone(),
+                },
+                &[&authority_seeds[..]],
+            ),
+            quote.output_amount,
+            mint_out.decimals,
+        )?;
+        if output_side_protocol_fee > 0 {
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    token_program_out,
+                    TransferChecked {
+                        from: pool_account_out,
+                        mint: mint_out.to_account_info(),
+                        to: protocol_fee_account_out,
+                        authority: self.pool_authority.clone(),
+                    },
+                    &[&authority_seeds[..]],
+                ),
+                quote.protocol_fee,
+                mint_out.decimals,
+            )?;
+        }
+
+        // 3. 校验不变量，更新波动率追踪器和TWAP预言机
+        //    交易后的储备按实际转入转出的数量计算，直接转入池子的代币不会并入储备
+        let reserve_in_after = reserve_in.checked_add(pool_received).ok_or(TutorialError::MathOverflow)?;
+        let reserve_out_after = reserve_out
+            .checked_sub(quote.output_amount)
+            .and_then(|reserve| reserve.checked_sub(output_side_protocol_fee))
+            .ok_or(TutorialError::MathOverflow)?;
+        let (reserve_a, reserve_b) =
+            if swap_a { (reserve_in_after, reserve_out_after) } else { (reserve_out_after, reserve_in_after) };
+        let fee_excluded = quote.fee_excluded_reserves(reserve_in, reserve_out);
+        let invariant_delta = CurveCalculator::check_invariant_growth(
+            &self.pool.curve_type,
+            invariant,
+            if swap_a { fee_excluded } else { (fee_excluded.1, fee_excluded.0) },
+            (reserve_a, reserve_b),
+        )?;
+        let current_price = if swap_a {
+            self.pool.normalized_price(reserve_a, reserve_b, true)
+        } else {
+            self.pool.normalized_price(reserve_b, reserve_a, false)
+        };
+        let now = Clock::get()?.unix_timestamp;
+        if let Some(pool_oracle) = self.pool_oracle {
+            PoolOracle::record_price(pool_oracle, current_price, now, &self.amm.volatility_config)?;
+        }
+        self.pool.oracle.update(reserve_a, reserve_b, now);
+        self.pool.swap_reserves(swap_a, pool_received, quote.output_amount + output_side_protocol_fee)?;
+        self.pool.stats.record_swap(swap_a, input, quote.output_amount, quote.fee_amount, now);
+        self.pool.unlock();
+
+        emit!(SwapEvent {
+            pool: self.pool.key(),
+            trader: self.trader,
+            swap_a,
+            input_amount: input,
+            output_amount: quote.net_output_amount,
+            fee_amount: quote.fee_amount,
+            protocol_fee: quote.protocol_fee,
+            price_impact_bps: quote.price_impact_bps,
+            reserve_a,
+            reserve_b,
+            invariant_delta,
+        });
+
+        Ok(quote)
+    }
+}
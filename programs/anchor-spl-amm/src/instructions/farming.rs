@@ -0,0 +1,675 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token::Token,
+    token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    constants::{ESCROW_SEED, FARM_SEED, LIQUIDITY_LOCK_SEED, LIQUIDITY_SEED, REWARD_VAULT_SEED, STAKE_POSITION_SEED},
+    errors::TutorialError,
+    events::{FarmFundedEvent, FarmRewardAddedEvent, RewardsClaimedEvent, StakeUpdatedEvent},
+    state::{Amm, Farm, LiquidityLock, Pool, StakePosition},
+};
+
+/// 创建池子的农场，第一种奖励代币托管在农场的金库中
+pub fn create_farm(ctx: Context<CreateFarm>) -> Result<()> {
+    let farm = &mut ctx.accounts.farm;
+    farm.pool = ctx.accounts.pool.key();
+    farm.lp_vault = ctx.accounts.lp_vault.key();
+    farm.rewards[0].mint = ctx.accounts.reward_mint.key();
+    farm.rewards[0].vault = ctx.accounts.reward_vault.key();
+
+    emit!(FarmRewardAddedEvent {
+        farm: farm.key(),
+        reward_index: 0,
+        mint: farm.rewards[0].mint,
+    });
+
+    Ok(())
+}
+
+/// 为农场新增一种奖励代币（如合作方代币），各奖励独立发放和领取
+pub fn add_farm_reward(ctx: Context<AddFarmReward>) -> Result<()> {
+    let farm = &mut ctx.accounts.farm;
+    let reward_mint = ctx.accounts.reward_mint.key();
+    if farm.rewards.iter().any(|reward| reward.mint == reward_mint) {
+        return err!(TutorialError::InvalidRewardIndex);
+    }
+    let index = farm.next_reward_index().ok_or(TutorialError::TooManyRewards)?;
+
+    // 新奖励从首次注入时开始累计，现有头寸的检查点从0开始即可
+    farm.rewards[index].mint = reward_mint;
+    farm.rewards[index].vault = ctx.accounts.reward_vault.key();
+
+    emit!(FarmRewardAddedEvent {
+        farm: farm.key(),
+        reward_index: index as u8,
+        mint: reward_mint,
+    });
+
+    Ok(())
+}
+
+/// 注入奖励并开始新一期发放：转入 emission_rate * duration 的奖励代币，该奖励上一期结束后才能开始新一期
+pub fn fund_farm(ctx: Context<FundFarm>, reward_index: u8, emission_rate: u64, duration: i64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let farm = &mut ctx.accounts.farm;
+    if farm.rewards[reward_index as usize].is_active(now) {
+        return err!(TutorialError::FarmActive);
+    }
+    if emission_rate == 0 || duration <= 0 {
+        return err!(TutorialError::InvalidEmission);
+    }
+    let amount = emission_rate
+        .checked_mul(duration as u64)
+        .ok_or(TutorialError::MathOverflow)?;
+
+    farm.update_rewards(now)?;
+    farm.rewards[reward_index as usize].start(emission_rate, duration, now);
+
+    token_interface::transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program_reward.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.admin_reward_account.to_account_info(),
+                mint: ctx.accounts.reward_mint.to_account_info(),
+                to: ctx.accounts.reward_vault.to_account_info(),
+                authority: ctx.accounts.admin.to_account_info(),
+            },
+        ),
+        amount,
+        ctx.accounts.reward_mint.decimals,
+    )?;
+
+    emit!(FarmFundedEvent {
+        farm: ctx.accounts.farm.key(),
+        reward_index,
+        amount,
+        emission_rate,
+        end_time: ctx.accounts.farm.rewards[reward_index as usize].end_time,
+    });
+
+    Ok(())
+}
+
+/// 质押流动性代币，数量为0时仅按锁的当前加成刷新质押权重；其他人可以通过refresh_stake_weight刷新
+pub fn stake_lp(ctx: Context<StakeLp>, amount: u64) -> Result<()> {
+    if amount > 0 {
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.owner_account_liquidity.to_account_info(),
+                    mint: ctx.accounts.mint_liquidity.to_account_info(),
+                    to: ctx.accounts.lp_vault.to_account_info(),
+                    authority: ctx.accounts.owner.to_account_info(),
+                },
+            ),
+            amount,
+            ctx.accounts.mint_liquidity.decimals,
+        )?;
+    }
+
+    let position = &mut ctx.accounts.position;
+    position.farm = ctx.accounts.farm.key();
+    position.owner = ctx.accounts.owner.key();
+    let new_amount = position.amount.checked_add(amount).ok_or(TutorialError::MathOverflow)?;
+    update_stake(
+        &mut ctx.accounts.farm,
+        position,
+        new_amount,
+        ctx.accounts.lock.as_deref().map(|lock| &**lock),
+    )?;
+
+    emit!(StakeUpdatedEvent {
+        farm: ctx.accounts.farm.key(),
+        owner: position.owner,
+        amount: position.amount,
+        weight: position.weight,
+    });
+
+    Ok(())
+}
+
+/// 取消质押流动性代币，未领取的奖励保留在头寸中
+pub fn unstake_lp(ctx: Context<UnstakeLp>, amount: u64) -> Result<()> {
+    let position = &mut ctx.accounts.position;
+    if amount > position.amount {
+        return err!(TutorialError::InsufficientStake);
+    }
+    let new_amount = position.amount - amount;
+    update_stake(
+        &mut ctx.accounts.farm,
+        position,
+        new_amount,
+        ctx.accounts.lock.as_deref().map(|lock| &**lock),
+    )?;
+
+    let farm_seeds = &[
+        ctx.accounts.farm.pool.as_ref(),
+        FARM_SEED,
+        &[ctx.bumps.farm],
+    ];
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.lp_vault.to_account_info(),
+                mint: ctx.accounts.mint_liquidity.to_account_info(),
+                to: ctx.accounts.owner_account_liquidity.to_account_info(),
+                authority: ctx.accounts.farm.to_account_info(),
+            },
+            &[&farm_seeds[..]],
+        ),
+        amount,
+        ctx.accounts.mint_liquidity.decimals,
+    )?;
+
+    emit!(StakeUpdatedEvent {
+        farm: ctx.accounts.farm.key(),
+        owner: ctx.accounts.position.owner,
+        amount: ctx.accounts.position.amount,
+        weight: ctx.accounts.position.weight,
+    });
+
+    Ok(())
+}
+
+/// 按锁的当前加成重新计算质押权重，任何人都可以调用：加成随到期临近线性衰减，锁到期或关闭后只剩质押数量本身
+pub fn refresh_stake_weight(ctx: Context<RefreshStakeWeight>) -> Result<()> {
+    // 锁账户按地址派生，调用者无法省略仍然存在的锁；关闭后的锁没有数据，按没有锁计算
+    let lock = if ctx.accounts.lock.data_is_empty() {
+        None
+    } else {
+        Some(LiquidityLock::try_deserialize(&mut &ctx.accounts.lock.data.borrow()[..])?)
+    };
+    let position = &mut ctx.accounts.position;
+    let amount = position.amount;
+    update_stake(&mut ctx.accounts.farm, position, amount, lock.as_ref())?;
+
+    emit!(StakeUpdatedEvent {
+        farm: ctx.accounts.farm.key(),
+        owner: position.owner,
+        amount: position.amount,
+        weight: position.weight,
+    });
+
+    Ok(())
+}
+
+/// 领取一种奖励代币的挖矿奖励
+pub fn claim_rewards(ctx: Context<ClaimRewards>, reward_index: u8) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let farm = &mut ctx.accounts.farm;
+    let position = &mut ctx.accounts.position;
+    farm.update_rewards(now)?;
+    position.settle(&farm.rewards)?;
+
+    let amount = position.pending_rewards[reward_index as usize];
+    if amount == 0 {
+        return Ok(());
+    }
+    position.pending_rewards[reward_index as usize] = 0;
+
+    let farm_seeds = &[
+        ctx.accounts.farm.pool.as_ref(),
+        FARM_SEED,
+        &[ctx.bumps.farm],
+    ];
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program_reward.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.reward_vault.to_account_info(),
+                mint: ctx.accounts.reward_mint.to_account_info(),
+                to: ctx.accounts.owner_reward_account.to_account_info(),
+                authority: ctx.accounts.farm.to_account_info(),
+            },
+            &[&farm_seeds[..]],
+        ),
+        amount,
+        ctx.accounts.reward_mint.decimals,
+    )?;
+
+    emit!(RewardsClaimedEvent {
+        farm: ctx.accounts.farm.key(),
+        owner: ctx.accounts.owner.key(),
+        reward_index,
+        amount,
+    });
+
+    Ok(())
+}
+
+/// 累计奖励并结算头寸后，按新的质押数量重新计算权重
+fn update_stake(farm: &mut Farm, position: &mut StakePosition, new_amount: u64, lock: Option<&LiquidityLock>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    farm.update_rewards(now)?;
+    position.settle(&farm.rewards)?;
+
+    let new_weight = StakePosition::compute_weight(new_amount, lock, now);
+    farm.total_staked = farm.total_staked - position.amount + new_amount;
+    farm.total_weight = farm.total_weight - position.weight + new_weight;
+    position.amount = new_amount;
+    position.weight = new_weight;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateFarm<'info> {
+    #[account(
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+        has_one = admin,
+    )]
+    pub amm: Box<Account<'info, Amm>>,
+
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.key().as_ref(),
+            pool.mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+        ],
+        bump,
+        has_one = amm,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = Farm::LEN,
+        seeds = [
+            pool.key().as_ref(),
+            FARM_SEED,
+        ],
+        bump,
+    )]
+    pub farm: Box<Account<'info, Farm>>,
+
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.as_ref(),
+            pool.mint_b.as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+            LIQUIDITY_SEED,
+        ],
+        bump,
+    )]
+    pub mint_liquidity: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [
+            farm.key().as_ref(),
+            ESCROW_SEED,
+        ],
+        bump,
+        token::mint = mint_liquidity,
+        token::authority = farm,
+        token::token_program = token_program,
+    )]
+    pub lp_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mint::token_program = token_program_reward)]
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [
+            farm.key().as_ref(),
+            reward_mint.key().as_ref(),
+            REWARD_VAULT_SEED,
+        ],
+        bump,
+        token::mint = reward_mint,
+        token::authority = farm,
+        token::token_program = token_program_reward,
+    )]
+    pub reward_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The admin of the AMM
+    pub admin: Signer<'info>,
+
+    /// The account paying for all rents
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Solana ecosystem accounts
+    pub token_program: Program<'info, Token>,
+    pub token_program_reward: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AddFarmReward<'info> {
+    #[account(
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+        has_one = admin,
+    )]
+    pub amm: Box<Account<'info, Amm>>,
+
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.key().as_ref(),
+            pool.mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+        ],
+        bump,
+        has_one = amm,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool.key().as_ref(),
+            FARM_SEED,
+        ],
+        bump,
+    )]
+    pub farm: Box<Account<'info, Farm>>,
+
+    #[account(mint::token_program = token_program_reward)]
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [
+            farm.key().as_ref(),
+            reward_mint.key().as_ref(),
+            REWARD_VAULT_SEED,
+        ],
+        bump,
+        token::mint = reward_mint,
+        token::authority = farm,
+        token::token_program = token_program_reward,
+    )]
+    pub reward_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The admin of the AMM
+    pub admin: Signer<'info>,
+
+    /// The account paying for all rents
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Solana ecosystem accounts
+    pub token_program_reward: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(reward_index: u8)]
+pub struct FundFarm<'info> {
+    #[account(
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+        has_one = admin,
+    )]
+    pub amm: Box<Account<'info, Amm>>,
+
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.key().as_ref(),
+            pool.mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+        ],
+        bump,
+        has_one = amm,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool.key().as_ref(),
+            FARM_SEED,
+        ],
+        bump,
+        constraint = farm.has_reward(reward_index) @ TutorialError::InvalidRewardIndex,
+    )]
+    pub farm: Box<Account<'info, Farm>>,
+
+    #[account(address = farm.rewards[reward_index as usize].mint)]
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        address = farm.rewards[reward_index as usize].vault,
+    )]
+    pub reward_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The admin of the AMM
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = reward_mint,
+        token::authority = admin,
+        token::token_program = token_program_reward,
+    )]
+    pub admin_reward_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Solana ecosystem accounts
+    pub token_program_reward: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct StakeLp<'info> {
+    #[account(
+        mut,
+        seeds = [
+            farm.pool.as_ref(),
+            FARM_SEED,
+        ],
+        bump,
+    )]
+    pub farm: Box<Account<'info, Farm>>,
+
+    #[account(
+        mut,
+        address = farm.lp_vault,
+    )]
+    pub lp_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = lp_vault.mint)]
+    pub mint_liquidity: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = StakePosition::LEN,
+        seeds = [
+            farm.key().as_ref(),
+            owner.key().as_ref(),
+            STAKE_POSITION_SEED,
+        ],
+        bump,
+    )]
+    pub position: Box<Account<'info, StakePosition>>,
+
+    /// The LP staking its liquidity and paying for all rents
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = mint_liquidity,
+        token::authority = owner,
+    )]
+    pub owner_account_liquidity: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// LP的流动性锁，提供时按锁的ve加成放大质押权重
+    #[account(
+        seeds = [
+            farm.pool.as_ref(),
+            owner.key().as_ref(),
+            LIQUIDITY_LOCK_SEED,
+        ],
+        bump,
+    )]
+    pub lock: Option<Box<Account<'info, LiquidityLock>>>,
+
+    /// Solana ecosystem accounts
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UnstakeLp<'info> {
+    #[account(
+        mut,
+        seeds = [
+            farm.pool.as_ref(),
+            FARM_SEED,
+        ],
+        bump,
+    )]
+    pub farm: Box<Account<'info, Farm>>,
+
+    #[account(
+        mut,
+        address = farm.lp_vault,
+    )]
+    pub lp_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = lp_vault.mint)]
+    pub mint_liquidity: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [
+            farm.key().as_ref(),
+            owner.key().as_ref(),
+            STAKE_POSITION_SEED,
+        ],
+        bump,
+        has_one = owner,
+    )]
+    pub position: Box<Account<'info, StakePosition>>,
+
+    /// The owner of the stake position
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = mint_liquidity,
+        token::authority = owner,
+    )]
+    pub owner_account_liquidity: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// LP的流动性锁，提供时按锁的ve加成放大质押权重
+    #[account(
+        seeds = [
+            farm.pool.as_ref(),
+            owner.key().as_ref(),
+            LIQUIDITY_LOCK_SEED,
+        ],
+        bump,
+    )]
+    pub lock: Option<Box<Account<'info, LiquidityLock>>>,
+
+    /// Solana ecosystem accounts
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct RefreshStakeWeight<'info> {
+    #[account(
+        mut,
+        seeds = [
+            farm.pool.as_ref(),
+            FARM_SEED,
+        ],
+        bump,
+    )]
+    pub farm: Box<Account<'info, Farm>>,
+
+    #[account(
+        mut,
+        seeds = [
+            farm.key().as_ref(),
+            position.owner.as_ref(),
+            STAKE_POSITION_SEED,
+        ],
+        bump,
+    )]
+    pub position: Box<Account<'info, StakePosition>>,
+
+    /// CHECK: 头寸所有者的流动性锁，可能已经关闭，存在时在处理函数中反序列化
+    #[account(
+        seeds = [
+            farm.pool.as_ref(),
+            position.owner.as_ref(),
+            LIQUIDITY_LOCK_SEED,
+        ],
+        bump,
+    )]
+    pub lock: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(reward_index: u8)]
+pub struct ClaimRewards<'info> {
+    #[account(
+        mut,
+        seeds = [
+            farm.pool.as_ref(),
+            FARM_SEED,
+        ],
+        bump,
+        constraint = farm.has_reward(reward_index) @ TutorialError::InvalidRewardIndex,
+    )]
+    pub farm: Box<Account<'info, Farm>>,
+
+    #[account(
+        mut,
+        seeds = [
+            farm.key().as_ref(),
+            owner.key().as_ref(),
+            STAKE_POSITION_SEED,
+        ],
+        bump,
+        has_one = owner,
+    )]
+    pub position: Box<Account<'info, StakePosition>>,
+
+    #[account(address = farm.rewards[reward_index as usize].mint)]
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        address = farm.rewards[reward_index as usize].vault,
+    )]
+    pub reward_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The owner of the stake position
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = reward_mint,
+        token::authority = owner,
+        token::token_program = token_program_reward,
+    )]
+    pub owner_reward_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Solana ecosystem accounts
+    pub token_program_reward: Interface<'info, TokenInterface>,
+}
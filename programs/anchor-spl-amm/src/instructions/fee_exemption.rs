@@ -0,0 +1,98 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::FEE_EXEMPTION_SEED,
+    errors::*,
+    events::FeeExemptionUpdatedEvent,
+    models::roles::Role,
+    state::{Amm, FeeExemption},
+};
+
+/// 将地址加入费用豁免名单，或修改其折扣
+pub fn set_fee_exemption(ctx: Context<SetFeeExemption>, discount_bps: u16) -> Result<()> {
+    if discount_bps > 10000 {
+        return err!(TutorialError::InvalidFeeExemption);
+    }
+
+    let fee_exemption = &mut ctx.accounts.fee_exemption;
+    fee_exemption.amm = ctx.accounts.amm.key();
+    fee_exemption.address = ctx.accounts.address.key();
+    fee_exemption.discount_bps = discount_bps;
+
+    emit!(FeeExemptionUpdatedEvent {
+        amm: fee_exemption.amm,
+        address: fee_exemption.address,
+        discount_bps,
+    });
+
+    Ok(())
+}
+
+/// 将地址移出费用豁免名单，租金退还给管理员
+pub fn remove_fee_exemption(ctx: Context<RemoveFeeExemption>) -> Result<()> {
+    emit!(FeeExemptionUpdatedEvent {
+        amm: ctx.accounts.amm.key(),
+        address: ctx.accounts.fee_exemption.address,
+        discount_bps: 0,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetFeeExemption<'info> {
+    #[account(
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+        constraint = amm.has_role(Role::FeeManager, &admin.key()) @ TutorialError::MissingRole,
+    )]
+    pub amm: Box<Account<'info, Amm>>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = FeeExemption::LEN,
+        seeds = [
+            amm.key().as_ref(),
+            address.key().as_ref(),
+            FEE_EXEMPTION_SEED,
+        ],
+        bump,
+    )]
+    pub fee_exemption: Box<Account<'info, FeeExemption>>,
+
+    /// 享受豁免的地址
+    /// CHECK: Read only, any address can be exempted
+    pub address: AccountInfo<'info>,
+
+    /// The owner or the fee manager of the AMM
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveFeeExemption<'info> {
+    #[account(
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+        constraint = amm.has_role(Role::FeeManager, &admin.key()) @ TutorialError::MissingRole,
+    )]
+    pub amm: Box<Account<'info, Amm>>,
+
+    #[account(
+        mut,
+        close = admin,
+        has_one = amm,
+    )]
+    pub fee_exemption: Box<Account<'info, FeeExemption>>,
+
+    /// The owner or the fee manager of the AMM
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
@@ -0,0 +1,271 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::sysvar::instructions::{self as instructions_sysvar, load_current_index_checked, load_instruction_at_checked},
+    Discriminator,
+};
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::{
+    constants::{AUTHORITY_SEED, PROGRAM_CONFIG_SEED},
+    errors::TutorialError,
+    events::FlashLoanEvent,
+    instruction::FlashRepay,
+    models::flash_loan::FlashLoanState,
+    state::{Amm, Pool, ProgramConfig},
+    utils::get_transfer_inverse_fee,
+};
+
+pub fn flash_borrow(ctx: Context<FlashLoan>, amount_a: u64, amount_b: u64) -> Result<()> {
+    if !ProgramConfig::load(&ctx.accounts.program_config)?.features.flash_loans_enabled {
+        return err!(TutorialError::FlashLoansDisabled);
+    }
+    if ctx.accounts.pool.flash_loan.active {
+        return err!(TutorialError::FlashLoanActive);
+    }
+    if !ctx.accounts.pool.status.allows_swaps() {
+        return err!(TutorialError::PoolStatusRestricted);
+    }
+    Pool::lock(&mut ctx.accounts.pool)?;
+    if amount_a >= ctx.accounts.pool_account_a.amount || amount_b >= ctx.accounts.pool_account_b.amount {
+        return err!(TutorialError::InsufficientLiquidity);
+    }
+
+    // 1. 通过指令内省确认同一交易中稍后会针对同一池子调用flash_repay
+    let instructions = ctx.accounts.instructions.to_account_info();
+    let current_index = load_current_index_checked(&instructions)? as usize;
+    let pool_key = ctx.accounts.pool.key();
+    let mut index = current_index + 1;
+    let mut repaid = false;
+    while let Ok(ix) = load_instruction_at_checked(index, &instructions) {
+        if ix.program_id == crate::ID
+            && ix.data.get(..8) == Some(&FlashRepay::DISCRIMINATOR[..])
+            && ix.accounts.get(1).map(|meta| meta.pubkey) == Some(pool_key)
+        {
+            repaid = true;
+            break;
+        }
+        index += 1;
+    }
+    if !repaid {
+        return err!(TutorialError::FlashLoanNotRepaid);
+    }
+
+    // 2. 记录借出前的余额，归还时据此校验
+    ctx.accounts.pool.flash_loan = FlashLoanState {
+        active: true,
+        amount_a,
+        amount_b,
+        balance_a_before: ctx.accounts.pool_account_a.amount,
+        balance_b_before: ctx.accounts.pool_account_b.amount,
+    };
+
+    // 3. 将储备借给调用者
+    let authority_bump = ctx.bumps.pool_authority;
+    let authority_seeds = &[
+        &ctx.accounts.pool.amm.to_bytes(),
+        &ctx.accounts.mint_a.key().to_bytes(),
+        &ctx.accounts.mint_b.key().to_bytes(),
+        &ctx.accounts.pool.fee_tier.to_le_bytes()[..],
+        AUTHORITY_SEED,
+        &[authority_bump],
+    ];
+    let signer_seeds = &[&authority_seeds[..]];
+    if amount_a > 0 {
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program_a.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.pool_account_a.to_account_info(),
+                    mint: ctx.accounts.mint_a.to_account_info(),
+                    to: ctx.accounts.borrower_account_a.to_account_info(),
+                    authority: ctx.accounts.pool_authority.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount_a,
+            ctx.accounts.mint_a.decimals,
+        )?;
+    }
+    if amount_b > 0 {
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program_b.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.pool_account_b.to_account_info(),
+                    mint: ctx.accounts.mint_b.to_account_info(),
+                    to: ctx.accounts.borrower_account_b.to_account_info(),
+                    authority: ctx.accounts.pool_authority.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount_b,
+            ctx.accounts.mint_b.decimals,
+        )?;
+    }
+    ctx.accounts.pool.unlock();
+
+    Ok(())
+}
+
+pub fn flash_repay(ctx: Context<FlashLoan>) -> Result<()> {
+    let loan = ctx.accounts.pool.flash_loan;
+    if !loan.active {
+        return err!(TutorialError::FlashLoanNotActive);
+    }
+    Pool::lock(&mut ctx.accounts.pool)?;
+
+    // 1. 归还本金和手续费，手续费留在池子里归LP所有
+    let fee_a = FlashLoanState::fee(loan.amount_a);
+    let fee_b = FlashLoanState::fee(loan.amount_b);
+    let repay_a = loan.amount_a + fee_a;
+    let repay_b = loan.amount_b + fee_b;
+    if repay_a > 0 {
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program_a.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.borrower_account_a.to_account_info(),
+                    mint: ctx.accounts.mint_a.to_account_info(),
+                    to: ctx.accounts.pool_account_a.to_account_info(),
+                    authority: ctx.accounts.borrower.to_account_info(),
+                },
+            ),
+            repay_a + get_transfer_inverse_fee(&ctx.accounts.mint_a.to_account_info(), repay_a)?,
+            ctx.accounts.mint_a.decimals,
+        )?;
+    }
+    if repay_b > 0 {
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program_b.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.borrower_account_b.to_account_info(),
+                    mint: ctx.accounts.mint_b.to_account_info(),
+                    to: ctx.accounts.pool_account_b.to_account_info(),
+                    authority: ctx.accounts.borrower.to_account_info(),
+                },
+            ),
+            repay_b + get_transfer_inverse_fee(&ctx.accounts.mint_b.to_account_info(), repay_b)?,
+            ctx.accounts.mint_b.decimals,
+        )?;
+    }
+
+    // 2. 校验池子余额不低于借出前的余额加手续费
+    ctx.accounts.pool_account_a.reload()?;
+    ctx.accounts.pool_account_b.reload()?;
+    if ctx.accounts.pool_account_a.amount < loan.balance_a_before + fee_a
+        || ctx.accounts.pool_account_b.amount < loan.balance_b_before + fee_b
+    {
+        return err!(TutorialError::FlashLoanNotRepaid);
+    }
+
+    ctx.accounts.pool.flash_loan = FlashLoanState::default();
+    // 借出期间记录的储备保持不变，归还后只并入手续费，多归还的部分属于盈余
+    ctx.accounts.pool.add_reserves(fee_a, fee_b)?;
+
+    emit!(FlashLoanEvent {
+        pool: ctx.accounts.pool.key(),
+        borrower: ctx.accounts.borrower.key(),
+        amount_a: loan.amount_a,
+        amount_b: loan.amount_b,
+        fee_a,
+        fee_b,
+    });
+    ctx.accounts.pool.unlock();
+
+    Ok(())
+}
+
+// 借出和归还共用同一账户结构，池子固定在第二个位置以便内省校验
+#[derive(Accounts)]
+pub struct FlashLoan<'info> {
+    #[account(
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+    )]
+    pub amm: Box<Account<'info, Amm>>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.key().as_ref(),
+            pool.mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+        ],
+        bump,
+        has_one = amm,
+        has_one = mint_a,
+        has_one = mint_b,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// CHECK: Read only authority
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            mint_a.key().as_ref(),
+            mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+            AUTHORITY_SEED,
+        ],
+        bump,
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    #[account(mint::token_program = token_program_a)]
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_program_b)]
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_a,
+    )]
+    pub pool_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_b,
+        associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_b,
+    )]
+    pub pool_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The account borrowing and repaying the loan
+    pub borrower: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = mint_a,
+        token::authority = borrower,
+        token::token_program = token_program_a,
+    )]
+    pub borrower_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint_b,
+        token::authority = borrower,
+        token::token_program = token_program_b,
+    )]
+    pub borrower_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// 全局程序配置，借出时检查闪电贷开关，尚未创建时使用默认配置
+    /// CHECK: Address checked by seeds, loaded by ProgramConfig::load
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
+
+    /// CHECK: The instructions sysvar, used to find the matching repayment
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions: AccountInfo<'info>,
+
+    /// Solana ecosystem accounts
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::{
+    constants::AUTHORITY_SEED,
+    models::apr::PoolApr,
+    state::{Amm, Pool},
+};
+
+pub fn get_pool_apr(ctx: Context<GetPoolApr>) -> Result<PoolApr> {
+    // 按最近24小时的手续费和当前锁仓价值估算年化收益率，通过返回数据提供给钱包展示
+    let rolling = ctx.accounts.pool.stats.rolling_volume(Clock::get()?.unix_timestamp);
+    let (reserve_a, reserve_b) = ctx.accounts.pool.reserves();
+    PoolApr::estimate(&ctx.accounts.amm.fee_config, &rolling, reserve_a, reserve_b)
+}
+
+#[derive(Accounts)]
+pub struct GetPoolApr<'info> {
+    #[account(
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+    )]
+    pub amm: Box<Account<'info, Amm>>,
+
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.key().as_ref(),
+            pool.mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+        ],
+        bump,
+        has_one = amm,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// CHECK: Read only authority
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.as_ref(),
+            pool.mint_b.as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+            AUTHORITY_SEED,
+        ],
+        bump,
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    #[account(
+        token::mint = pool.mint_a,
+        token::authority = pool_authority,
+    )]
+    pub pool_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        token::mint = pool.mint_b,
+        token::authority = pool_authority,
+    )]
+    pub pool_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+}
@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::TutorialError,
+    models::oracle::TwapPrice,
+    state::Pool,
+};
+
+pub fn get_twap(ctx: Context<GetTwap>, window_secs: u32) -> Result<TwapPrice> {
+    // 返回至少覆盖window_secs的时间加权平均价格，供其他程序通过CPI读取
+    ctx.accounts
+        .pool
+        .oracle
+        .get_twap(window_secs, Clock::get()?.unix_timestamp)
+        .ok_or(error!(TutorialError::InsufficientOracleHistory))
+}
+
+#[derive(Accounts)]
+pub struct GetTwap<'info> {
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.key().as_ref(),
+            pool.mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+}
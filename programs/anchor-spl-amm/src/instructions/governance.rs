@@ -0,0 +1,235 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::{
+    constants::{
+        GOVERNANCE_PROPOSAL_SEED, GOVERNANCE_QUORUM_BPS, GOVERNANCE_VOTING_PERIOD, LIQUIDITY_LOCK_SEED, LIQUIDITY_SEED,
+        MINIMUM_LIQUIDITY, VOTE_RECORD_SEED,
+    },
+    errors::TutorialError,
+    events::{DepositCapUpdatedEvent, GovernanceProposalCreatedEvent, GovernanceProposalExecutedEvent, VoteCastEvent},
+    models::governance::GovernanceAction,
+    state::{Amm, GovernanceProposal, LiquidityLock, Pool, VoteRecord},
+};
+
+/// 锁定了流动性的LP发起治理提案，法定票数按当前的流动性代币供应量计算
+pub fn create_governance_proposal(
+    ctx: Context<CreateGovernanceProposal>,
+    id: u64,
+    action: GovernanceAction,
+) -> Result<()> {
+    if ctx.accounts.lock.amount == 0 {
+        return err!(TutorialError::NoVotingWeight);
+    }
+    if let GovernanceAction::Curve(curve_type) = action {
+        if !curve_type.is_valid() {
+            return err!(TutorialError::InvalidCurveConfig);
+        }
+    }
+
+    let total_liquidity = ctx.accounts.mint_liquidity.supply + MINIMUM_LIQUIDITY;
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.pool = ctx.accounts.pool.key();
+    proposal.proposer = ctx.accounts.proposer.key();
+    proposal.id = id;
+    proposal.action = action;
+    proposal.voting_ends_at = Clock::get()?.unix_timestamp + GOVERNANCE_VOTING_PERIOD;
+    proposal.quorum = (total_liquidity as u128 * GOVERNANCE_QUORUM_BPS as u128 / 10000) as u64;
+
+    emit!(GovernanceProposalCreatedEvent {
+        pool: proposal.pool,
+        proposal: proposal.key(),
+        proposer: proposal.proposer,
+        action,
+        voting_ends_at: proposal.voting_ends_at,
+        quorum: proposal.quorum,
+    });
+
+    Ok(())
+}
+
+/// 按锁定的流动性代币数量投票，每个流动性锁对每个提案只能投一次。
+/// 锁必须在投票截止后才到期，避免取回后转给其他账户重复投票
+pub fn cast_vote(ctx: Context<CastVote>, support: bool) -> Result<()> {
+    let lock = &ctx.accounts.lock;
+    let proposal = &mut ctx.accounts.proposal;
+    if Clock::get()?.unix_timestamp >= proposal.voting_ends_at {
+        return err!(TutorialError::VotingClosed);
+    }
+    if lock.amount == 0 {
+        return err!(TutorialError::NoVotingWeight);
+    }
+    if lock.unlock_at < proposal.voting_ends_at {
+        return err!(TutorialError::LockExpiresBeforeVoteEnds);
+    }
+
+    let weight = lock.amount;
+    if support {
+        proposal.votes_for += weight;
+    } else {
+        proposal.votes_against += weight;
+    }
+
+    let vote_record = &mut ctx.accounts.vote_record;
+    vote_record.proposal = proposal.key();
+    vote_record.lock = lock.key();
+    vote_record.support = support;
+    vote_record.weight = weight;
+
+    emit!(VoteCastEvent {
+        proposal: proposal.key(),
+        voter: ctx.accounts.voter.key(),
+        support,
+        weight,
+    });
+
+    Ok(())
+}
+
+/// 执行通过的提案，任何人都可以调用。投票截止后还需等待AMM的时间锁延迟
+pub fn execute_governance_proposal(ctx: Context<ExecuteGovernanceProposal>) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+    if proposal.executed {
+        return err!(TutorialError::ProposalAlreadyExecuted);
+    }
+    let now = Clock::get()?.unix_timestamp;
+    if now < proposal.voting_ends_at {
+        return err!(TutorialError::VotingNotEnded);
+    }
+    if now < proposal.voting_ends_at + ctx.accounts.amm.timelock_delay {
+        return err!(TutorialError::TimelockNotElapsed);
+    }
+    if !proposal.passed() {
+        return err!(TutorialError::ProposalNotPassed);
+    }
+
+    let pool = &mut ctx.accounts.pool;
+    match proposal.action {
+        GovernanceAction::Curve(curve_type) => pool.update_curve_params(curve_type)?,
+        GovernanceAction::DepositCap { max_reserve_a, max_reserve_b } => {
+            pool.max_reserve_a = max_reserve_a;
+            pool.max_reserve_b = max_reserve_b;
+
+            emit!(DepositCapUpdatedEvent {
+                pool: pool.key(),
+                max_reserve_a,
+                max_reserve_b,
+            });
+        }
+    }
+    proposal.executed = true;
+
+    emit!(GovernanceProposalExecutedEvent {
+        pool: pool.key(),
+        proposal: proposal.key(),
+        action: proposal.action,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct CreateGovernanceProposal<'info> {
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.as_ref(),
+            pool.mint_b.as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+            LIQUIDITY_SEED,
+        ],
+        bump,
+    )]
+    pub mint_liquidity: Box<InterfaceAccount<'info, Mint>>,
+
+    /// 提议者在该池子的流动性锁
+    #[account(
+        seeds = [
+            pool.key().as_ref(),
+            proposer.key().as_ref(),
+            LIQUIDITY_LOCK_SEED,
+        ],
+        bump,
+    )]
+    pub lock: Box<Account<'info, LiquidityLock>>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = GovernanceProposal::LEN,
+        seeds = [
+            pool.key().as_ref(),
+            proposer.key().as_ref(),
+            id.to_le_bytes().as_ref(),
+            GOVERNANCE_PROPOSAL_SEED,
+        ],
+        bump,
+    )]
+    pub proposal: Box<Account<'info, GovernanceProposal>>,
+
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CastVote<'info> {
+    #[account(mut)]
+    pub proposal: Box<Account<'info, GovernanceProposal>>,
+
+    /// 投票者在提案池子的流动性锁
+    #[account(
+        seeds = [
+            proposal.pool.as_ref(),
+            voter.key().as_ref(),
+            LIQUIDITY_LOCK_SEED,
+        ],
+        bump,
+    )]
+    pub lock: Box<Account<'info, LiquidityLock>>,
+
+    #[account(
+        init,
+        payer = voter,
+        space = VoteRecord::LEN,
+        seeds = [
+            proposal.key().as_ref(),
+            lock.key().as_ref(),
+            VOTE_RECORD_SEED,
+        ],
+        bump,
+    )]
+    pub vote_record: Box<Account<'info, VoteRecord>>,
+
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteGovernanceProposal<'info> {
+    #[account(
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+    )]
+    pub amm: Box<Account<'info, Amm>>,
+
+    #[account(
+        mut,
+        has_one = amm,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        mut,
+        has_one = pool,
+    )]
+    pub proposal: Box<Account<'info, GovernanceProposal>>,
+}
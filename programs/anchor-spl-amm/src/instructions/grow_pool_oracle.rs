@@ -0,0 +1,33 @@
+use anchor_lang::{prelude::*, solana_program::entrypoint::MAX_PERMITTED_DATA_INCREASE};
+
+use crate::{constants::POOL_ORACLE_SEED, state::PoolOracle};
+
+/// 继续分配池子预言机的样本缓冲区，每次最多增加10KB，任何人都可以支付租金调用；
+/// 已分配完成时不做任何改变，缓冲区分配完成前交换不记录价格样本
+pub fn grow_pool_oracle(_ctx: Context<GrowPoolOracle>) -> Result<()> {
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct GrowPoolOracle<'info> {
+    #[account(
+        mut,
+        seeds = [
+            pool_oracle.pool.as_ref(),
+            POOL_ORACLE_SEED,
+        ],
+        bump,
+        realloc = PoolOracle::space(pool_oracle.volatility_tracker.observation_count)
+            .min(pool_oracle.to_account_info().data_len() + MAX_PERMITTED_DATA_INCREASE),
+        realloc::payer = payer,
+        realloc::zero = false,
+    )]
+    pub pool_oracle: Box<Account<'info, PoolOracle>>,
+
+    /// The account paying for the additional rent
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Solana ecosystem accounts
+    pub system_program: Program<'info, System>,
+}
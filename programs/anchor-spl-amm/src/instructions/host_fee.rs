@@ -0,0 +1,103 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, TransferChecked};
+
+use crate::{
+    errors::TutorialError,
+    events::{HostFeePaidEvent, MaxHostFeeUpdatedEvent},
+    models::{swap_options::SwapOptions, roles::Role},
+    state::Amm,
+    utils::check_deadline,
+};
+
+use super::swap_exact_tokens_for_tokens::{swap_exact_tokens_for_tokens_process, SwapExactTokensForTokens};
+
+/// 更新前端托管方可收取的平台费上限，设为0即禁止收取平台费
+pub fn update_max_host_fee(ctx: Context<UpdateMaxHostFee>, max_host_fee_bps: u16) -> Result<()> {
+    if max_host_fee_bps >= 10000 {
+        return err!(TutorialError::InvalidFee);
+    }
+
+    let amm = &mut ctx.accounts.amm;
+    amm.max_host_fee_bps = max_host_fee_bps;
+
+    emit!(MaxHostFeeUpdatedEvent {
+        amm: amm.key(),
+        max_host_fee_bps,
+    });
+
+    Ok(())
+}
+
+/// 带平台费的交换：先按host_fee_bps从输入中向前端托管方支付平台费，剩余部分再进行交换，
+/// 平台费与协议费用、LP手续费相互独立，费率不能超过AMM管理员设置的上限
+pub fn swap_exact_tokens_for_tokens_with_host_fee<'info>(
+    mut ctx: Context<'_, '_, 'info, 'info, SwapExactTokensForTokens<'info>>,
+    input_mint: Pubkey,
+    input_amount: u64,
+    min_output_amount: u64,
+    deadline: Option<i64>,
+    host_fee_bps: u16,
+) -> Result<()> {
+    check_deadline(deadline)?;
+    let swap_a = ctx.accounts.pool.swap_direction(&input_mint)?;
+    if host_fee_bps > ctx.accounts.amm.max_host_fee_bps {
+        return err!(TutorialError::HostFeeTooHigh);
+    }
+
+    let host_fee = (input_amount as u128 * host_fee_bps as u128 / 10000) as u64;
+    let accounts = &ctx.accounts;
+    let host_fee_account = accounts.host_fee_account.as_ref().ok_or(TutorialError::InvalidHostFeeAccount)?;
+    let (trader_account, mint, token_program) = if swap_a {
+        (&accounts.trader_token_accounts.trader_account_a, &accounts.mint_a, &accounts.token_program_a)
+    } else {
+        (&accounts.trader_token_accounts.trader_account_b, &accounts.mint_b, &accounts.token_program_b)
+    };
+    if host_fee_account.mint != mint.key() {
+        return err!(TutorialError::InvalidHostFeeAccount);
+    }
+    let host_fee_account_key = host_fee_account.key();
+    if host_fee > 0 {
+        token_interface::transfer_checked(
+            CpiContext::new(
+                token_program.to_account_info(),
+                TransferChecked {
+                    from: trader_account.to_account_info(),
+                    mint: mint.to_account_info(),
+                    to: host_fee_account.to_account_info(),
+                    authority: accounts.trader.to_account_info(),
+                },
+            ),
+            host_fee,
+            mint.decimals,
+        )?;
+        ctx.accounts.trader_token_accounts.trader_account_a.reload()?;
+        ctx.accounts.trader_token_accounts.trader_account_b.reload()?;
+    }
+
+    swap_exact_tokens_for_tokens_process(&mut ctx, swap_a, input_amount - host_fee, min_output_amount, SwapOptions::default())?;
+
+    emit!(HostFeePaidEvent {
+        pool: ctx.accounts.pool.key(),
+        trader: ctx.accounts.trader.key(),
+        host_fee_account: host_fee_account_key,
+        host_fee,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateMaxHostFee<'info> {
+    #[account(
+        mut,
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+        constraint = amm.has_role(Role::FeeManager, &admin.key()) @ TutorialError::MissingRole,
+    )]
+    pub amm: Account<'info, Amm>,
+
+    /// The owner or the fee manager of the AMM
+    pub admin: Signer<'info>,
+}
@@ -0,0 +1,560 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token::Token,
+    token_interface::{self, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+use fixed::types::I64F64;
+
+use crate::{
+    constants::{
+        AUTHORITY_SEED, ESCROW_SEED, IL_COMPENSATION_SEED, IL_COMPENSATION_TWAP_WINDOW, IL_POSITION_SEED, LIQUIDITY_SEED,
+        MINIMUM_LIQUIDITY, POOL_ORACLE_SEED,
+    },
+    errors::TutorialError,
+    events::IlCompensationClaimedEvent,
+    state::{Amm, IlPosition, Pool, PoolOracle},
+};
+
+/// 创建池子的无常损失补偿金库，任何人都可以支付租金创建
+pub fn create_il_compensation_vault(_ctx: Context<CreateIlCompensationVault>) -> Result<()> {
+    Ok(())
+}
+
+/// 以预言机的几何平均价格作为登记和补偿价格，同一笔交易中操纵储备无法影响补偿
+fn twap_price(pool: &Pool, now: i64) -> Result<I64F64> {
+    let twap = pool
+        .oracle
+        .get_twap(IL_COMPENSATION_TWAP_WINDOW, now)
+        .ok_or(TutorialError::InsufficientOracleHistory)?;
+    Ok(pool.normalize_price(I64F64::from_bits(twap.geometric_price_a_x64 as i128), false))
+}
+
+/// 登记LP头寸：把流动性代币转入头寸的托管账户，并记录当前的时间加权平均价格，之后的补偿以此为基准；
+/// 已有头寸时追加数量，登记价格按流动性加权合并，补偿周期重新计算
+pub fn register_il_position(ctx: Context<RegisterIlPosition>, amount: u64) -> Result<()> {
+    let (pool_a, pool_b) = ctx.accounts.pool.reserves();
+    if pool_a == 0 || pool_b == 0 {
+        return err!(TutorialError::InsufficientLiquidity);
+    }
+    let now = Clock::get()?.unix_timestamp;
+    let price = twap_price(&ctx.accounts.pool, now)?;
+
+    token_interface::transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.owner_account_liquidity.to_account_info(),
+                mint: ctx.accounts.mint_liquidity.to_account_info(),
+                to: ctx.accounts.escrow.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            },
+        ),
+        amount,
+        ctx.accounts.mint_liquidity.decimals,
+    )?;
+
+    let position = &mut ctx.accounts.il_position;
+    position.pool = ctx.accounts.pool.key();
+    position.owner = ctx.accounts.owner.key();
+    position.register(amount, price)?;
+    position.last_compensated = now;
+
+    Ok(())
+}
+
+/// 取回托管的全部流动性代币并关闭头寸
+pub fn close_il_position(ctx: Context<CloseIlPosition>) -> Result<()> {
+    let position = &ctx.accounts.il_position;
+    let position_seeds = &[
+        position.pool.as_ref(),
+        position.owner.as_ref(),
+        IL_POSITION_SEED,
+        &[ctx.bumps.il_position],
+    ];
+    let signer_seeds = &[&position_seeds[..]];
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.escrow.to_account_info(),
+                mint: ctx.accounts.mint_liquidity.to_account_info(),
+                to: ctx.accounts.owner_account_liquidity.to_account_info(),
+                authority: ctx.accounts.il_position.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        ctx.accounts.escrow.amount,
+        ctx.accounts.mint_liquidity.decimals,
+    )?;
+    token_interface::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.escrow.to_account_info(),
+            destination: ctx.accounts.owner.to_account_info(),
+            authority: ctx.accounts.il_position.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
+
+    Ok(())
+}
+
+/// 按登记价格与当前的时间加权平均价格计算无常损失，从补偿金库向LP支付补偿
+pub fn claim_il_compensation(ctx: Context<ClaimIlCompensation>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let (pool_a, pool_b) = ctx.accounts.pool.reserves();
+    if pool_a == 0 || pool_b == 0 {
+        return err!(TutorialError::InsufficientLiquidity);
+    }
+
+    // 1. 只按托管的流动性补偿，登记后新增的流动性需要追加登记
+    let position = &ctx.accounts.il_position;
+    let liquidity = position.liquidity;
+    let total_liquidity = ctx.accounts.mint_liquidity.supply as u128 + MINIMUM_LIQUIDITY as u128;
+    let share = |reserve: u64| {
+        (liquidity as u128)
+            .checked_mul(reserve as u128)
+            .and_then(|v| v.checked_div(total_liquidity))
+            .and_then(|v| u64::try_from(v).ok())
+            .ok_or(TutorialError::MathOverflow)
+    };
+    let (share_a, share_b) = (share(pool_a)?, share(pool_b)?);
+
+    // 2. 两侧分别按头寸价值计算补偿，不超过金库余额
+    let entry_price = I64F64::from_bits(position.entry_price as i128);
+    let current_price = twap_price(&ctx.accounts.pool, now)?;
+    let config = &ctx.accounts.amm.volatility_config;
+    let tracker = &ctx.accounts.pool_oracle.volatility_tracker;
+    let compensation = |value: u64| {
+        tracker.calculate_il_compensation(entry_price, current_price, value, config, now, position.last_compensated)
+    };
+    let amount_a = compensation(share_a).min(ctx.accounts.il_compensation_account_a.amount);
+    let amount_b = compensation(share_b).min(ctx.accounts.il_compensation_account_b.amount);
+    if amount_a == 0 && amount_b == 0 {
+        return err!(TutorialError::NoCompensationDue);
+    }
+
+    // 3. 从金库转出补偿
+    let authority_bump = ctx.bumps.pool_authority;
+    let authority_seeds = &[
+        &ctx.accounts.pool.amm.to_bytes(),
+        &ctx.accounts.mint_a.key().to_bytes(),
+        &ctx.accounts.mint_b.key().to_bytes(),
+        &ctx.accounts.pool.fee_tier.to_le_bytes()[..],
+        AUTHORITY_SEED,
+        &[authority_bump],
+    ];
+    let signer_seeds = &[&authority_seeds[..]];
+    if amount_a > 0 {
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program_a.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.il_compensation_account_a.to_account_info(),
+                    mint: ctx.accounts.mint_a.to_account_info(),
+                    to: ctx.accounts.owner_account_a.to_account_info(),
+                    authority: ctx.accounts.pool_authority.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount_a,
+            ctx.accounts.mint_a.decimals,
+        )?;
+    }
+    if amount_b > 0 {
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program_b.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.il_compensation_account_b.to_account_info(),
+                    mint: ctx.accounts.mint_b.to_account_info(),
+                    to: ctx.accounts.owner_account_b.to_account_info(),
+                    authority: ctx.accounts.pool_authority.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount_b,
+            ctx.accounts.mint_b.decimals,
+        )?;
+    }
+
+    // 4. 更新头寸和池子的补偿时间
+    ctx.accounts.il_position.last_compensated = now;
+    ctx.accounts.pool_oracle.volatility_tracker.last_compensated = now;
+
+    emit!(IlCompensationClaimedEvent {
+        pool: ctx.accounts.pool.key(),
+        owner: ctx.accounts.owner.key(),
+        amount_a,
+        amount_b,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateIlCompensationVault<'info> {
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.key().as_ref(),
+            pool.mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+        ],
+        bump,
+        has_one = mint_a,
+        has_one = mint_b,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// CHECK: Read only authority
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            mint_a.key().as_ref(),
+            mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+            AUTHORITY_SEED,
+        ],
+        bump,
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    #[account(mint::token_program = token_program_a)]
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_program_b)]
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [
+            pool_authority.key().as_ref(),
+            mint_a.key().as_ref(),
+            IL_COMPENSATION_SEED,
+        ],
+        bump,
+        token::mint = mint_a,
+        token::authority = pool_authority,
+        token::token_program = token_program_a,
+    )]
+    pub il_compensation_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [
+            pool_authority.key().as_ref(),
+            mint_b.key().as_ref(),
+            IL_COMPENSATION_SEED,
+        ],
+        bump,
+        token::mint = mint_b,
+        token::authority = pool_authority,
+        token::token_program = token_program_b,
+    )]
+    pub il_compensation_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The account paying for all rents
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Solana ecosystem accounts
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterIlPosition<'info> {
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.key().as_ref(),
+            pool.mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+        ],
+        bump,
+        has_one = mint_a,
+        has_one = mint_b,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// CHECK: Read only authority
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            mint_a.key().as_ref(),
+            mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+            AUTHORITY_SEED,
+        ],
+        bump,
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = IlPosition::LEN,
+        seeds = [
+            pool.key().as_ref(),
+            owner.key().as_ref(),
+            IL_POSITION_SEED,
+        ],
+        bump,
+    )]
+    pub il_position: Box<Account<'info, IlPosition>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [
+            il_position.key().as_ref(),
+            ESCROW_SEED,
+        ],
+        bump,
+        token::mint = mint_liquidity,
+        token::authority = il_position,
+        token::token_program = token_program,
+    )]
+    pub escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The liquidity provider registering the position
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            mint_a.key().as_ref(),
+            mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+            LIQUIDITY_SEED,
+        ],
+        bump,
+    )]
+    pub mint_liquidity: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_program_a)]
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_program_b)]
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        associated_token::mint = mint_a,
+        associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_a,
+    )]
+    pub pool_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        associated_token::mint = mint_b,
+        associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_b,
+    )]
+    pub pool_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint_liquidity,
+        token::authority = owner,
+    )]
+    pub owner_account_liquidity: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The account paying for all rents
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Solana ecosystem accounts
+    pub token_program: Program<'info, Token>,
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseIlPosition<'info> {
+    #[account(
+        mut,
+        seeds = [
+            il_position.pool.as_ref(),
+            owner.key().as_ref(),
+            IL_POSITION_SEED,
+        ],
+        bump,
+        has_one = owner,
+        close = owner,
+    )]
+    pub il_position: Box<Account<'info, IlPosition>>,
+
+    #[account(
+        mut,
+        seeds = [
+            il_position.key().as_ref(),
+            ESCROW_SEED,
+        ],
+        bump,
+    )]
+    pub escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = escrow.mint)]
+    pub mint_liquidity: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The owner of the position
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = mint_liquidity,
+        token::authority = owner,
+    )]
+    pub owner_account_liquidity: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Solana ecosystem accounts
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimIlCompensation<'info> {
+    #[account(
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+    )]
+    pub amm: Box<Account<'info, Amm>>,
+
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.key().as_ref(),
+            pool.mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+        ],
+        bump,
+        has_one = amm,
+        has_one = mint_a,
+        has_one = mint_b,
+        constraint = !pool.status.is_frozen() @ TutorialError::PoolStatusRestricted,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// 池子的波动率预言机
+    #[account(
+        mut,
+        seeds = [
+            pool.key().as_ref(),
+            POOL_ORACLE_SEED,
+        ],
+        bump,
+    )]
+    pub pool_oracle: Box<Account<'info, PoolOracle>>,
+
+    /// CHECK: Read only authority
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            mint_a.key().as_ref(),
+            mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+            AUTHORITY_SEED,
+        ],
+        bump,
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool.key().as_ref(),
+            owner.key().as_ref(),
+            IL_POSITION_SEED,
+        ],
+        bump,
+        has_one = pool,
+        has_one = owner,
+    )]
+    pub il_position: Box<Account<'info, IlPosition>>,
+
+    /// The liquidity provider claiming the compensation
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            mint_a.key().as_ref(),
+            mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+            LIQUIDITY_SEED,
+        ],
+        bump,
+    )]
+    pub mint_liquidity: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_program_a)]
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_program_b)]
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        associated_token::mint = mint_a,
+        associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_a,
+    )]
+    pub pool_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        associated_token::mint = mint_b,
+        associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_b,
+    )]
+    pub pool_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool_authority.key().as_ref(),
+            mint_a.key().as_ref(),
+            IL_COMPENSATION_SEED,
+        ],
+        bump,
+        token::token_program = token_program_a,
+    )]
+    pub il_compensation_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool_authority.key().as_ref(),
+            mint_b.key().as_ref(),
+            IL_COMPENSATION_SEED,
+        ],
+        bump,
+        token::token_program = token_program_b,
+    )]
+    pub il_compensation_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint_a,
+        token::authority = owner,
+        token::token_program = token_program_a,
+    )]
+    pub owner_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint_b,
+        token::authority = owner,
+        token::token_program = token_program_b,
+    )]
+    pub owner_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Solana ecosystem accounts
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
+}
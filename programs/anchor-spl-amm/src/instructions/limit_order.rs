@@ -0,0 +1,408 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::{
+    constants::{AUTHORITY_SEED, ESCROW_SEED, LIMIT_ORDER_SEED, POOL_ORACLE_SEED, PROTOCOL_FEE_SEED},
+    errors::TutorialError,
+    events::LimitOrderFilledEvent,
+    state::{Amm, Denylist, LimitOrder, Pool, PoolOracle},
+};
+
+use super::escrow_swap::EscrowSwap;
+
+/// 挂限价单：把输入代币转入订单的托管账户
+pub fn place_limit_order(
+    ctx: Context<PlaceLimitOrder>,
+    id: u64,
+    input_amount: u64,
+    min_output_amount: u64,
+) -> Result<()> {
+    token_interface::transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program_in.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.owner_account_in.to_account_info(),
+                mint: ctx.accounts.mint_in.to_account_info(),
+                to: ctx.accounts.escrow.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            },
+        ),
+        input_amount,
+        ctx.accounts.mint_in.decimals,
+    )?;
+
+    // 按托管账户实际收到的数量记录，Token-2022转账费已扣除
+    ctx.accounts.escrow.reload()?;
+    let order = &mut ctx.accounts.order;
+    order.pool = ctx.accounts.pool.key();
+    order.owner = ctx.accounts.owner.key();
+    order.id = id;
+    order.swap_a = ctx.accounts.mint_in.key() == ctx.accounts.pool.mint_a;
+    order.input_amount = ctx.accounts.escrow.amount;
+    order.min_output_amount = min_output_amount;
+
+    Ok(())
+}
+
+/// 撤销限价单：退回托管的代币并关闭订单
+pub fn cancel_limit_order(ctx: Context<CancelLimitOrder>) -> Result<()> {
+    let order = &ctx.accounts.order;
+    let id_bytes = order.id.to_le_bytes();
+    let order_seeds = &[
+        order.pool.as_ref(),
+        order.owner.as_ref(),
+        &id_bytes[..],
+        LIMIT_ORDER_SEED,
+        &[ctx.bumps.order],
+    ];
+    let signer_seeds = &[&order_seeds[..]];
+
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program_in.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.escrow.to_account_info(),
+                mint: ctx.accounts.mint_in.to_account_info(),
+                to: ctx.accounts.owner_account_in.to_account_info(),
+                authority: ctx.accounts.order.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        ctx.accounts.escrow.amount,
+        ctx.accounts.mint_in.decimals,
+    )?;
+    token_interface::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program_in.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.escrow.to_account_info(),
+            destination: ctx.accounts.owner.to_account_info(),
+            authority: ctx.accounts.order.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
+
+    Ok(())
+}
+
+/// 成交限价单：池子价格达到限价时任何人都可以调用，托管的代币通过池子换成输出代币发给下单者
+pub fn fill_limit_order(ctx: Context<FillLimitOrder>) -> Result<()> {
+    let order = &ctx.accounts.order;
+    let (order_key, owner, swap_a) = (order.key(), order.owner, order.swap_a);
+    let (input_amount, min_output_amount) = (order.input_amount, order.min_output_amount);
+    let id_bytes = order.id.to_le_bytes();
+    let order_seeds = &[
+        order.pool.as_ref(),
+        owner.as_ref(),
+        &id_bytes[..],
+        LIMIT_ORDER_SEED,
+        &[ctx.bumps.order],
+    ];
+    let signer_seeds = &[&order_seeds[..]];
+
+    // 1. 通过池子交换，输出不足限价时拒绝成交
+    let quote = EscrowSwap {
+        amm: &ctx.accounts.amm,
+        pool: &mut ctx.accounts.pool,
+        pool_oracle: ctx.accounts.pool_oracle.as_deref_mut(),
+        pool_authority: &ctx.accounts.pool_authority,
+        authority_bump: ctx.bumps.pool_authority,
+        mint_a: &ctx.accounts.mint_a,
+        mint_b: &ctx.accounts.mint_b,
+        pool_account_a: &mut ctx.accounts.pool_account_a,
+        pool_account_b: &mut ctx.accounts.pool_account_b,
+        protocol_fee_account_a: ctx.accounts.protocol_fee_account_a.to_account_info(),
+        protocol_fee_account_b: ctx.accounts.protocol_fee_account_b.to_account_info(),
+        token_program_a: ctx.accounts.token_program_a.to_account_info(),
+        token_program_b: ctx.accounts.token_program_b.to_account_info(),
+        escrow: ctx.accounts.escrow.to_account_info(),
+        escrow_authority: ctx.accounts.order.to_account_info(),
+        destination: ctx.accounts.owner_account_out.to_account_info(),
+        trader: owner,
+        denylist: ctx.accounts.denylist.as_deref().map(|denylist| &**denylist),
+        price_feed: ctx.accounts.price_feed.as_ref().map(|feed| feed.to_account_info()),
+    }
+    .execute(swap_a, input_amount, min_output_amount, signer_seeds)?;
+
+    // 2. 关闭托管账户，租金退还给下单者
+    let token_program_in = if swap_a { &ctx.accounts.token_program_a } else { &ctx.accounts.token_program_b };
+    token_interface::close_account(CpiContext::new_with_signer(
+        token_program_in.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.escrow.to_account_info(),
+            destination: ctx.accounts.owner.to_account_info(),
+            authority: ctx.accounts.order.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
+
+    emit!(LimitOrderFilledEvent {
+        order: order_key,
+        owner,
+        filler: ctx.accounts.filler.key(),
+        input_amount,
+        output_amount: quote.net_output_amount,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct PlaceLimitOrder<'info> {
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.key().as_ref(),
+            pool.mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = LimitOrder::LEN,
+        seeds = [
+            pool.key().as_ref(),
+            owner.key().as_ref(),
+            id.to_le_bytes().as_ref(),
+            LIMIT_ORDER_SEED,
+        ],
+        bump,
+    )]
+    pub order: Box<Account<'info, LimitOrder>>,
+
+    #[account(
+        init,
+        payer = owner,
+        seeds = [
+            order.key().as_ref(),
+            ESCROW_SEED,
+        ],
+        bump,
+        token::mint = mint_in,
+        token::authority = order,
+        token::token_program = token_program_in,
+    )]
+    pub escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The account placing the order and paying for all rents
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mint::token_program = token_program_in,
+        constraint = mint_in.key() == pool.mint_a || mint_in.key() == pool.mint_b @ TutorialError::InvalidMint,
+    )]
+    pub mint_in: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = mint_in,
+        token::authority = owner,
+        token::token_program = token_program_in,
+    )]
+    pub owner_account_in: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Solana ecosystem accounts
+    pub token_program_in: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelLimitOrder<'info> {
+    #[account(
+        mut,
+        seeds = [
+            order.pool.as_ref(),
+            owner.key().as_ref(),
+            order.id.to_le_bytes().as_ref(),
+            LIMIT_ORDER_SEED,
+        ],
+        bump,
+        has_one = owner,
+        close = owner,
+    )]
+    pub order: Box<Account<'info, LimitOrder>>,
+
+    #[account(
+        mut,
+        seeds = [
+            order.key().as_ref(),
+            ESCROW_SEED,
+        ],
+        bump,
+    )]
+    pub escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The account that placed the order
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        address = escrow.mint,
+        mint::token_program = token_program_in,
+    )]
+    pub mint_in: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = mint_in,
+        token::authority = owner,
+        token::token_program = token_program_in,
+    )]
+    pub owner_account_in: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Solana ecosystem accounts
+    pub token_program_in: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct FillLimitOrder<'info> {
+    #[account(
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+    )]
+    pub amm: Box<Account<'info, Amm>>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.key().as_ref(),
+            pool.mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+        ],
+        bump,
+        has_one = amm,
+        has_one = mint_a,
+        has_one = mint_b,
+        constraint = !pool.flash_loan.active @ TutorialError::FlashLoanActive,
+        constraint = !pool.locked @ TutorialError::PoolLocked,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// 池子的波动率预言机，启用波动率跟踪或动态费率时必须提供
+    #[account(
+        mut,
+        seeds = [
+            pool.key().as_ref(),
+            POOL_ORACLE_SEED,
+        ],
+        bump,
+    )]
+    pub pool_oracle: Option<Box<Account<'info, PoolOracle>>>,
+
+    /// CHECK: Read only authority
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            mint_a.key().as_ref(),
+            mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+            AUTHORITY_SEED,
+        ],
+        bump,
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool.key().as_ref(),
+            owner.key().as_ref(),
+            order.id.to_le_bytes().as_ref(),
+            LIMIT_ORDER_SEED,
+        ],
+        bump,
+        has_one = pool,
+        has_one = owner,
+        close = owner,
+    )]
+    pub order: Box<Account<'info, LimitOrder>>,
+
+    #[account(
+        mut,
+        seeds = [
+            order.key().as_ref(),
+            ESCROW_SEED,
+        ],
+        bump,
+    )]
+    pub escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: The order owner, receives the rent back
+    #[account(mut)]
+    pub owner: AccountInfo<'info>,
+
+    /// The owner's account receiving the output token
+    #[account(
+        mut,
+        token::authority = owner,
+        constraint = owner_account_out.mint == if order.swap_a { pool.mint_b } else { pool.mint_a } @ TutorialError::InvalidMint,
+    )]
+    pub owner_account_out: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The account filling the order
+    pub filler: Signer<'info>,
+
+    #[account(mint::token_program = token_program_a)]
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_program_b)]
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_a,
+    )]
+    pub pool_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_b,
+        associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_b,
+    )]
+    pub pool_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool_authority.key().as_ref(),
+            mint_a.key().as_ref(),
+            PROTOCOL_FEE_SEED,
+        ],
+        bump,
+    )]
+    pub protocol_fee_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool_authority.key().as_ref(),
+            mint_b.key().as_ref(),
+            PROTOCOL_FEE_SEED,
+        ],
+        bump,
+    )]
+    pub protocol_fee_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// 池子配置的Pyth价格账户，未启用预言机保护时可以省略
+    /// CHECK: 地址和数据在预言机保护检查中校验
+    pub price_feed: Option<UncheckedAccount<'info>>,
+
+    /// AMM的地址黑名单，未设置黑名单时可以省略
+    #[account(address = amm.denylist @ TutorialError::DenylistRequired)]
+    pub denylist: Option<Box<Account<'info, Denylist>>>,
+
+    /// Solana ecosystem accounts
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,553 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token::Token,
+    token_interface::{self, Burn, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    constants::{
+        AUTHORITY_SEED, ESCROW_SEED, LIQUIDITY_LOCK_SEED, LIQUIDITY_SEED, MAX_LOCK_DURATION, MINIMUM_LIQUIDITY,
+        PROTOCOL_FEE_SEED,
+    },
+    errors::TutorialError,
+    events::{LiquidityLockedEvent, LockedFeesClaimedEvent},
+    models::{liquidity_lock::LiquidityBalance, lp_fees::LpFeeCalculator, ve_boost::VeBoost},
+    state::{LiquidityLock, Pool},
+};
+
+/// 锁定流动性代币：转入锁的托管账户，到期前无法提取；已有锁时追加数量，到期时间取较晚者
+/// 锁定期限越长，ve加成越高
+pub fn lock_liquidity(ctx: Context<LockLiquidity>, amount: u64, duration: i64) -> Result<()> {
+    if duration <= 0 || duration > MAX_LOCK_DURATION {
+        return err!(TutorialError::InvalidLockDuration);
+    }
+
+    // 追加锁定前合并手续费检查点，已累计的手续费保持不变
+    let total_liquidity = ctx.accounts.mint_liquidity.supply + MINIMUM_LIQUIDITY;
+    let (reserve_a, reserve_b) = ctx.accounts.pool.reserves();
+    let growth = LpFeeCalculator::growth_per_share(&ctx.accounts.pool.curve_type, reserve_a, reserve_b, total_liquidity)?;
+
+    token_interface::transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.owner_account_liquidity.to_account_info(),
+                mint: ctx.accounts.mint_liquidity.to_account_info(),
+                to: ctx.accounts.escrow.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            },
+        ),
+        amount,
+        ctx.accounts.mint_liquidity.decimals,
+    )?;
+
+    let unlock_at = Clock::get()?
+        .unix_timestamp
+        .checked_add(duration)
+        .ok_or(TutorialError::MathOverflow)?;
+    let lock = &mut ctx.accounts.lock;
+    lock.pool = ctx.accounts.pool.key();
+    lock.owner = ctx.accounts.owner.key();
+    lock.fee_growth_checkpoint = LpFeeCalculator::merge_checkpoint(lock.amount, lock.fee_growth_checkpoint, amount, growth)?;
+    lock.amount += amount;
+    lock.unlock_at = lock.unlock_at.max(unlock_at);
+
+    emit!(LiquidityLockedEvent {
+        pool: lock.pool,
+        owner: lock.owner,
+        amount,
+        total_locked: lock.amount,
+        unlock_at: lock.unlock_at,
+    });
+
+    Ok(())
+}
+
+/// 到期后取回全部锁定的流动性代币并关闭锁
+pub fn unlock_liquidity(ctx: Context<UnlockLiquidity>) -> Result<()> {
+    let lock = &ctx.accounts.lock;
+    if Clock::get()?.unix_timestamp < lock.unlock_at {
+        return err!(TutorialError::LiquidityLocked);
+    }
+
+    let lock_seeds = &[
+        lock.pool.as_ref(),
+        lock.owner.as_ref(),
+        LIQUIDITY_LOCK_SEED,
+        &[ctx.bumps.lock],
+    ];
+    let signer_seeds = &[&lock_seeds[..]];
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.escrow.to_account_info(),
+                mint: ctx.accounts.mint_liquidity.to_account_info(),
+                to: ctx.accounts.owner_account_liquidity.to_account_info(),
+                authority: ctx.accounts.lock.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        ctx.accounts.escrow.amount,
+        ctx.accounts.mint_liquidity.decimals,
+    )?;
+    token_interface::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.escrow.to_account_info(),
+            destination: ctx.accounts.owner.to_account_info(),
+            authority: ctx.accounts.lock.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
+
+    Ok(())
+}
+
+/// 查询LP可自由提取和锁定中的流动性代币数量
+pub fn get_liquidity_balance(ctx: Context<GetLiquidityBalance>) -> Result<LiquidityBalance> {
+    let now = Clock::get()?.unix_timestamp;
+    let (locked, unlock_at, boost_bps) = ctx
+        .accounts
+        .lock
+        .as_ref()
+        .map_or((0, 0, 10000), |lock| (lock.amount, lock.unlock_at, lock.boost_bps(now)));
+
+    Ok(LiquidityBalance {
+        free: ctx.accounts.owner_account_liquidity.amount,
+        locked,
+        unlock_at,
+        boost_bps,
+    })
+}
+
+/// 领取锁定流动性的手续费：基础部分与claim_fees相同，从锁中销毁手续费对应的流动性代币；
+/// 加成部分按ve加成倍数从协议费中支付，协议费不足时以余额为限
+pub fn claim_locked_fees(ctx: Context<ClaimLockedFees>) -> Result<()> {
+    let (pool_a, pool_b) = ctx.accounts.pool.reserves();
+    let total_liquidity = ctx.accounts.mint_liquidity.supply + MINIMUM_LIQUIDITY;
+    let growth = LpFeeCalculator::growth_per_share(&ctx.accounts.pool.curve_type, pool_a, pool_b, total_liquidity)?;
+
+    let lock = &mut ctx.accounts.lock;
+    let checkpoint = lock.fee_growth_checkpoint;
+    lock.fee_growth_checkpoint = growth;
+
+    // 1. 手续费以流动性代币计，按比例兑换为两种代币
+    let liquidity = LpFeeCalculator::fee_liquidity(lock.amount, checkpoint, growth)?;
+    if liquidity == 0 {
+        return Ok(());
+    }
+    lock.amount -= liquidity;
+    let share = |reserve: u64| {
+        (liquidity as u128)
+            .checked_mul(reserve as u128)
+            .and_then(|v| v.checked_div(total_liquidity as u128))
+            .and_then(|v| u64::try_from(v).ok())
+            .ok_or(TutorialError::MathOverflow)
+    };
+    let amount_a = share(pool_a)?;
+    let amount_b = share(pool_b)?;
+
+    // 2. 加成部分从协议费中支付
+    let boost_bps = lock.boost_bps(Clock::get()?.unix_timestamp);
+    let bonus_a = VeBoost::bonus(amount_a, boost_bps).min(ctx.accounts.protocol_fee_account_a.amount);
+    let bonus_b = VeBoost::bonus(amount_b, boost_bps).min(ctx.accounts.protocol_fee_account_b.amount);
+
+    // 3. 转出手续费和加成
+    let authority_bump = ctx.bumps.pool_authority;
+    let authority_seeds = &[
+        &ctx.accounts.pool.amm.to_bytes(),
+        &ctx.accounts.mint_a.key().to_bytes(),
+        &ctx.accounts.mint_b.key().to_bytes(),
+        &ctx.accounts.pool.fee_tier.to_le_bytes()[..],
+        AUTHORITY_SEED,
+        &[authority_bump],
+    ];
+    let signer_seeds = &[&authority_seeds[..]];
+    let transfers = [
+        (
+            &ctx.accounts.pool_account_a,
+            &ctx.accounts.mint_a,
+            &ctx.accounts.owner_account_a,
+            &ctx.accounts.token_program_a,
+            amount_a,
+        ),
+        (
+            &ctx.accounts.pool_account_b,
+            &ctx.accounts.mint_b,
+            &ctx.accounts.owner_account_b,
+            &ctx.accounts.token_program_b,
+            amount_b,
+        ),
+        (
+            &ctx.accounts.protocol_fee_account_a,
+            &ctx.accounts.mint_a,
+            &ctx.accounts.owner_account_a,
+            &ctx.accounts.token_program_a,
+            bonus_a,
+        ),
+        (
+            &ctx.accounts.protocol_fee_account_b,
+            &ctx.accounts.mint_b,
+            &ctx.accounts.owner_account_b,
+            &ctx.accounts.token_program_b,
+            bonus_b,
+        ),
+    ];
+    for (from, mint, to, token_program, amount) in transfers {
+        if amount == 0 {
+            continue;
+        }
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                TransferChecked {
+                    from: from.to_account_info(),
+                    mint: mint.to_account_info(),
+                    to: to.to_account_info(),
+                    authority: ctx.accounts.pool_authority.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+            mint.decimals,
+        )?;
+    }
+
+    // 4. 从锁的托管账户中销毁手续费对应的流动性代币
+    let lock_seeds = &[
+        ctx.accounts.lock.pool.as_ref(),
+        ctx.accounts.lock.owner.as_ref(),
+        LIQUIDITY_LOCK_SEED,
+        &[ctx.bumps.lock],
+    ];
+    token_interface::burn(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.mint_liquidity.to_account_info(),
+                from: ctx.accounts.escrow.to_account_info(),
+                authority: ctx.accounts.lock.to_account_info(),
+            },
+            &[&lock_seeds[..]],
+        ),
+        liquidity,
+    )?;
+
+    ctx.accounts.pool.remove_reserves(amount_a, amount_b)?;
+
+    emit!(LockedFeesClaimedEvent {
+        pool: ctx.accounts.pool.key(),
+        owner: ctx.accounts.owner.key(),
+        liquidity,
+        amount_a,
+        amount_b,
+        bonus_a,
+        bonus_b,
+        boost_bps,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct LockLiquidity<'info> {
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// CHECK: Read only authority
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.as_ref(),
+            pool.mint_b.as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+            AUTHORITY_SEED,
+        ],
+        bump,
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    #[account(
+        token::mint = pool.mint_a,
+        token::authority = pool_authority,
+    )]
+    pub pool_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        token::mint = pool.mint_b,
+        token::authority = pool_authority,
+    )]
+    pub pool_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.as_ref(),
+            pool.mint_b.as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+            LIQUIDITY_SEED,
+        ],
+        bump,
+    )]
+    pub mint_liquidity: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = LiquidityLock::LEN,
+        seeds = [
+            pool.key().as_ref(),
+            owner.key().as_ref(),
+            LIQUIDITY_LOCK_SEED,
+        ],
+        bump,
+    )]
+    pub lock: Box<Account<'info, LiquidityLock>>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        seeds = [
+            lock.key().as_ref(),
+            ESCROW_SEED,
+        ],
+        bump,
+        token::mint = mint_liquidity,
+        token::authority = lock,
+        token::token_program = token_program,
+    )]
+    pub escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The LP locking its liquidity and paying for all rents
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = mint_liquidity,
+        token::authority = owner,
+    )]
+    pub owner_account_liquidity: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Solana ecosystem accounts
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UnlockLiquidity<'info> {
+    #[account(
+        mut,
+        seeds = [
+            lock.pool.as_ref(),
+            owner.key().as_ref(),
+            LIQUIDITY_LOCK_SEED,
+        ],
+        bump,
+        has_one = owner,
+        close = owner,
+    )]
+    pub lock: Box<Account<'info, LiquidityLock>>,
+
+    #[account(
+        mut,
+        seeds = [
+            lock.key().as_ref(),
+            ESCROW_SEED,
+        ],
+        bump,
+    )]
+    pub escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = escrow.mint)]
+    pub mint_liquidity: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The owner of the lock
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = mint_liquidity,
+        token::authority = owner,
+    )]
+    pub owner_account_liquidity: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Solana ecosystem accounts
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct GetLiquidityBalance<'info> {
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.as_ref(),
+            pool.mint_b.as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+            LIQUIDITY_SEED,
+        ],
+        bump,
+    )]
+    pub mint_liquidity: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: Read only, the LP whose balance is queried
+    pub owner: AccountInfo<'info>,
+
+    #[account(
+        token::mint = mint_liquidity,
+        token::authority = owner,
+    )]
+    pub owner_account_liquidity: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// LP的流动性锁，没有锁时可以省略
+    #[account(
+        seeds = [
+            pool.key().as_ref(),
+            owner.key().as_ref(),
+            LIQUIDITY_LOCK_SEED,
+        ],
+        bump,
+    )]
+    pub lock: Option<Box<Account<'info, LiquidityLock>>>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimLockedFees<'info> {
+    #[account(
+        mut,
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.key().as_ref(),
+            pool.mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+        ],
+        bump,
+        has_one = mint_a,
+        has_one = mint_b,
+        constraint = !pool.flash_loan.active @ TutorialError::FlashLoanActive,
+        constraint = !pool.locked @ TutorialError::PoolLocked,
+        constraint = !pool.status.is_frozen() @ TutorialError::PoolStatusRestricted,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// CHECK: Read only authority
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            mint_a.key().as_ref(),
+            mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+            AUTHORITY_SEED,
+        ],
+        bump,
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool.key().as_ref(),
+            owner.key().as_ref(),
+            LIQUIDITY_LOCK_SEED,
+        ],
+        bump,
+        has_one = owner,
+    )]
+    pub lock: Box<Account<'info, LiquidityLock>>,
+
+    #[account(
+        mut,
+        seeds = [
+            lock.key().as_ref(),
+            ESCROW_SEED,
+        ],
+        bump,
+    )]
+    pub escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The owner of the lock
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool.amm.as_ref(),
+            mint_a.key().as_ref(),
+            mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+            LIQUIDITY_SEED,
+        ],
+        bump,
+    )]
+    pub mint_liquidity: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_program_a)]
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_program_b)]
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_a,
+    )]
+    pub pool_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_b,
+        associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_b,
+    )]
+    pub pool_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool_authority.key().as_ref(),
+            mint_a.key().as_ref(),
+            PROTOCOL_FEE_SEED,
+        ],
+        bump,
+        token::token_program = token_program_a,
+    )]
+    pub protocol_fee_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool_authority.key().as_ref(),
+            mint_b.key().as_ref(),
+            PROTOCOL_FEE_SEED,
+        ],
+        bump,
+        token::token_program = token_program_b,
+    )]
+    pub protocol_fee_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint_a,
+        token::authority = owner,
+        token::token_program = token_program_a,
+    )]
+    pub owner_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint_b,
+        token::authority = owner,
+        token::token_program = token_program_b,
+    )]
+    pub owner_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Solana ecosystem accounts
+    pub token_program: Program<'info, Token>,
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
+}
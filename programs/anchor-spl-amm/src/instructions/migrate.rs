@@ -0,0 +1,155 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{AMM_VERSION, POOL_VERSION},
+    errors::TutorialError,
+    events::AccountMigratedEvent,
+    state::{Amm, FeePosition, Pool},
+    utils::grow_account,
+};
+
+/// 把旧布局的AMM账户扩容到当前大小并升级版本，新增字段以零值初始化
+///
+/// 旧账户无法按当前布局反序列化，因此先扩容再加载校验
+pub fn migrate_amm(ctx: Context<MigrateAmm>) -> Result<()> {
+    let amm_info = ctx.accounts.amm.to_account_info();
+    grow_account(
+        &amm_info,
+        Amm::LEN,
+        &ctx.accounts.admin.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+    )?;
+
+    let mut data = amm_info.try_borrow_mut_data()?;
+    let mut amm = Amm::try_deserialize(&mut &data[..])?;
+    if amm.admin != ctx.accounts.admin.key() {
+        return Err(ErrorCode::ConstraintHasOne.into());
+    }
+    if amm.version >= AMM_VERSION {
+        return err!(TutorialError::AlreadyMigrated);
+    }
+
+    amm.version = AMM_VERSION;
+    amm.try_serialize(&mut &mut data[..])?;
+
+    emit!(AccountMigratedEvent {
+        account: amm_info.key(),
+        version: AMM_VERSION,
+    });
+
+    Ok(())
+}
+
+/// 把旧布局的池子账户扩容到当前大小并升级版本，需要先升级所属的AMM
+pub fn migrate_pool(ctx: Context<MigratePool>) -> Result<()> {
+    let pool_info = ctx.accounts.pool.to_account_info();
+    grow_account(
+        &pool_info,
+        Pool::LEN,
+        &ctx.accounts.admin.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+    )?;
+
+    let mut data = pool_info.try_borrow_mut_data()?;
+    let mut pool = Pool::try_deserialize(&mut &data[..])?;
+    if pool.amm != ctx.accounts.amm.key() {
+        return Err(ErrorCode::ConstraintHasOne.into());
+    }
+    if pool.version >= POOL_VERSION {
+        return err!(TutorialError::AlreadyMigrated);
+    }
+
+    pool.version = POOL_VERSION;
+    pool.try_serialize(&mut &mut data[..])?;
+
+    emit!(AccountMigratedEvent {
+        account: pool_info.key(),
+        version: POOL_VERSION,
+    });
+
+    Ok(())
+}
+
+/// 把托管前布局的手续费头寸扩容到当前大小，由头寸所有者支付新增的租金
+///
+/// 旧头寸没有托管流动性代币，托管数量以零值初始化，之后通过追加托管重新累计手续费
+pub fn migrate_fee_position(ctx: Context<MigrateFeePosition>) -> Result<()> {
+    let position_info = ctx.accounts.fee_position.to_account_info();
+    if position_info.data_len() >= FeePosition::LEN {
+        return err!(TutorialError::AlreadyMigrated);
+    }
+    grow_account(
+        &position_info,
+        FeePosition::LEN,
+        &ctx.accounts.owner.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+    )?;
+
+    let data = position_info.try_borrow_data()?;
+    let position = FeePosition::try_deserialize(&mut &data[..])?;
+    if position.owner != ctx.accounts.owner.key() {
+        return Err(ErrorCode::ConstraintHasOne.into());
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateAmm<'info> {
+    /// CHECK: Deserialized and checked after the realloc, old layouts are too short to load
+    #[account(
+        mut,
+        owner = crate::ID,
+    )]
+    pub amm: UncheckedAccount<'info>,
+
+    /// The admin of the AMM, paying for the additional rent
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// Solana ecosystem accounts
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigratePool<'info> {
+    #[account(
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+        has_one = admin,
+    )]
+    pub amm: Box<Account<'info, Amm>>,
+
+    /// CHECK: Deserialized and checked after the realloc, old layouts are too short to load
+    #[account(
+        mut,
+        owner = crate::ID,
+    )]
+    pub pool: UncheckedAccount<'info>,
+
+    /// The admin of the AMM, paying for the additional rent
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// Solana ecosystem accounts
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateFeePosition<'info> {
+    /// CHECK: Deserialized and checked after the realloc, old layouts are too short to load
+    #[account(
+        mut,
+        owner = crate::ID,
+    )]
+    pub fee_position: UncheckedAccount<'info>,
+
+    /// The owner of the position, paying for the additional rent
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Solana ecosystem accounts
+    pub system_program: Program<'info, System>,
+}
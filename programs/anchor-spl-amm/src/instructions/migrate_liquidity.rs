@@ -0,0 +1,430 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::Token,
+    token_interface::{self, Burn, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    constants::{AUTHORITY_SEED, DEPOSIT_RECORD_SEED, LIQUIDITY_SEED},
+    errors::TutorialError,
+    events::LiquidityMigratedEvent,
+    instructions::{minted_liquidity, withdraw_amounts},
+    state::{Amm, DepositRecord, Denylist, Pool},
+    utils::{check_deadline, get_transfer_fee},
+};
+
+/// 按新池比例存入，以不足的一侧为准，多出的代币留在存款人账户
+fn fitting_amounts(amount_a: u64, amount_b: u64, pool_a: u64, pool_b: u64) -> Result<(u64, u64)> {
+    let needed_b = (amount_a as u128)
+        .checked_mul(pool_b as u128)
+        .and_then(|v| v.checked_div(pool_a as u128))
+        .ok_or(TutorialError::MathOverflow)?;
+    if needed_b <= amount_b as u128 {
+        return Ok((amount_a, needed_b as u64));
+    }
+    let needed_a = (amount_b as u128)
+        .checked_mul(pool_a as u128)
+        .and_then(|v| v.checked_div(pool_b as u128))
+        .ok_or(TutorialError::MathOverflow)?;
+    Ok((needed_a as u64, amount_b))
+}
+
+/// 在同一笔交易中从旧池提取流动性并存入同一交易对的新池（例如更换费率档位或曲线），
+/// 避免分两笔交易时中间暴露在价格变动下
+pub fn migrate_liquidity(
+    ctx: Context<MigrateLiquidity>,
+    lp_amount: u64,
+    min_lp_out: u64,
+    deadline: Option<i64>,
+) -> Result<()> {
+    check_deadline(deadline)?;
+    Denylist::check(&ctx.accounts.amm, ctx.accounts.denylist.as_deref().map(|denylist| &**denylist), &ctx.accounts.depositor.key())?;
+    DepositRecord::check_required(&ctx.accounts.amm.withdrawal_fee_config, ctx.accounts.to_deposit_record.as_deref().map(|record| &**record))?;
+    Pool::lock(&mut ctx.accounts.from_pool)?;
+    Pool::lock(&mut ctx.accounts.to_pool)?;
+
+    // 1. 按份额从旧池提取，与withdraw_liquidity一样按持有时间收取提取费
+    let (from_a, from_b) = ctx.accounts.from_pool.reserves();
+    let now = Clock::get()?.unix_timestamp;
+    let withdrawal_fee_bps = DepositRecord::withdrawal_fee_bps(
+        ctx.accounts.from_deposit_record.as_deref().map(|record| &**record),
+        &ctx.accounts.amm.withdrawal_fee_config,
+        lp_amount,
+        now,
+    );
+    let (amount_a, amount_b) =
+        withdraw_amounts(lp_amount, (from_a, from_b), ctx.accounts.from_mint_liquidity.supply, withdrawal_fee_bps)?;
+
+    let from_seeds = &[
+        &ctx.accounts.from_pool.amm.to_bytes(),
+        &ctx.accounts.mint_a.key().to_bytes(),
+        &ctx.accounts.mint_b.key().to_bytes(),
+        &ctx.accounts.from_pool.fee_tier.to_le_bytes()[..],
+        AUTHORITY_SEED,
+        &[ctx.bumps.from_pool_authority],
+    ];
+    let withdrawals = [
+        (
+            amount_a,
+            &ctx.accounts.from_pool_account_a,
+            &ctx.accounts.mint_a,
+            &ctx.accounts.depositor_account_a,
+            &ctx.accounts.token_program_a,
+        ),
+        (
+            amount_b,
+            &ctx.accounts.from_pool_account_b,
+            &ctx.accounts.mint_b,
+            &ctx.accounts.depositor_account_b,
+            &ctx.accounts.token_program_b,
+        ),
+    ];
+    for (amount, from, mint, to, token_program) in withdrawals {
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                TransferChecked {
+                    from: from.to_account_info(),
+                    mint: mint.to_account_info(),
+                    to: to.to_account_info(),
+                    authority: ctx.accounts.from_pool_authority.to_account_info(),
+                },
+                &[&from_seeds[..]],
+            ),
+            amount,
+            mint.decimals,
+        )?;
+    }
+    token_interface::burn(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.from_mint_liquidity.to_account_info(),
+                from: ctx.accounts.depositor_account_from_liquidity.to_account_info(),
+                authority: ctx.accounts.depositor.to_account_info(),
+            },
+        ),
+        lp_amount,
+    )?;
+    if let Some(deposit_record) = ctx.accounts.from_deposit_record.as_mut() {
+        deposit_record.record_withdrawal(lp_amount);
+    }
+
+    // 2. 按新池比例存入存款人实际收到的数量
+    let received_a = amount_a - get_transfer_fee(&ctx.accounts.mint_a.to_account_info(), amount_a)?;
+    let received_b = amount_b - get_transfer_fee(&ctx.accounts.mint_b.to_account_info(), amount_b)?;
+    let to_supply = ctx.accounts.to_mint_liquidity.supply;
+    let to_reserves = ctx.accounts.to_pool.reserves();
+    let (deposit_a, deposit_b) = if to_supply == 0 {
+        (received_a, received_b)
+    } else {
+        fitting_amounts(received_a, received_b, to_reserves.0, to_reserves.1)?
+    };
+    let liquidity = minted_liquidity(
+        &ctx.accounts.to_pool,
+        deposit_a - get_transfer_fee(&ctx.accounts.mint_a.to_account_info(), deposit_a)?,
+        deposit_b - get_transfer_fee(&ctx.accounts.mint_b.to_account_info(), deposit_b)?,
+        to_reserves,
+        to_supply,
+    )?;
+
+    // 滑点保护：两个池子的价格差异过大时拒绝
+    if liquidity < min_lp_out {
+        return err!(TutorialError::LiquidityOutputTooSmall);
+    }
+
+    let deposits = [
+        (
+            deposit_a,
+            &ctx.accounts.depositor_account_a,
+            &ctx.accounts.mint_a,
+            &ctx.accounts.to_pool_account_a,
+            &ctx.accounts.token_program_a,
+        ),
+        (
+            deposit_b,
+            &ctx.accounts.depositor_account_b,
+            &ctx.accounts.mint_b,
+            &ctx.accounts.to_pool_account_b,
+            &ctx.accounts.token_program_b,
+        ),
+    ];
+    for (amount, from, mint, to, token_program) in deposits {
+        token_interface::transfer_checked(
+            CpiContext::new(
+                token_program.to_account_info(),
+                TransferChecked {
+                    from: from.to_account_info(),
+                    mint: mint.to_account_info(),
+                    to: to.to_account_info(),
+                    authority: ctx.accounts.depositor.to_account_info(),
+                },
+            ),
+            amount,
+            mint.decimals,
+        )?;
+    }
+
+    let to_seeds = &[
+        &ctx.accounts.to_pool.amm.to_bytes(),
+        &ctx.accounts.mint_a.key().to_bytes(),
+        &ctx.accounts.mint_b.key().to_bytes(),
+        &ctx.accounts.to_pool.fee_tier.to_le_bytes()[..],
+        AUTHORITY_SEED,
+        &[ctx.bumps.to_pool_authority],
+    ];
+    token_interface::mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.to_mint_liquidity.to_account_info(),
+                to: ctx.accounts.depositor_account_to_liquidity.to_account_info(),
+                authority: ctx.accounts.to_pool_authority.to_account_info(),
+            },
+            &[&to_seeds[..]],
+        ),
+        liquidity,
+    )?;
+    let held_liquidity = ctx.accounts.depositor_account_to_liquidity.amount;
+    if let Some(deposit_record) = ctx.accounts.to_deposit_record.as_mut() {
+        deposit_record.record_deposit(held_liquidity, liquidity, now);
+    }
+
+    // 3. 两个池子的储备按实际转出和收到的数量更新
+    ctx.accounts.from_pool.remove_reserves(amount_a, amount_b)?;
+    ctx.accounts.to_pool.add_reserves(
+        deposit_a - get_transfer_fee(&ctx.accounts.mint_a.to_account_info(), deposit_a)?,
+        deposit_b - get_transfer_fee(&ctx.accounts.mint_b.to_account_info(), deposit_b)?,
+    )?;
+    let (to_a, to_b) = ctx.accounts.to_pool.reserves();
+    ctx.accounts.to_pool.check_deposit_cap(to_a, to_b)?;
+    ctx.accounts.from_pool.stats.record_withdraw();
+    ctx.accounts.to_pool.stats.record_deposit();
+
+    emit!(LiquidityMigratedEvent {
+        from_pool: ctx.accounts.from_pool.key(),
+        to_pool: ctx.accounts.to_pool.key(),
+        depositor: ctx.accounts.depositor.key(),
+        lp_amount,
+        amount_a: deposit_a,
+        amount_b: deposit_b,
+        liquidity,
+        withdrawal_fee_bps,
+    });
+    ctx.accounts.from_pool.unlock();
+    ctx.accounts.to_pool.unlock();
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateLiquidity<'info> {
+    #[account(
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+        constraint = !amm.exit_only @ TutorialError::ExitOnly,
+    )]
+    pub amm: Box<Account<'info, Amm>>,
+
+    #[account(
+        mut,
+        seeds = [
+            from_pool.amm.as_ref(),
+            from_pool.mint_a.key().as_ref(),
+            from_pool.mint_b.key().as_ref(),
+            from_pool.fee_tier.to_le_bytes().as_ref(),
+        ],
+        bump,
+        has_one = amm,
+        has_one = mint_a,
+        has_one = mint_b,
+        constraint = !from_pool.flash_loan.active @ TutorialError::FlashLoanActive,
+        constraint = !from_pool.locked @ TutorialError::PoolLocked,
+        constraint = from_pool.status.allows_withdrawals() @ TutorialError::PoolStatusRestricted,
+    )]
+    pub from_pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        mut,
+        seeds = [
+            to_pool.amm.as_ref(),
+            to_pool.mint_a.key().as_ref(),
+            to_pool.mint_b.key().as_ref(),
+            to_pool.fee_tier.to_le_bytes().as_ref(),
+        ],
+        bump,
+        has_one = amm,
+        has_one = mint_a,
+        has_one = mint_b,
+        constraint = to_pool.key() != from_pool.key() @ TutorialError::SamePoolMigration,
+        constraint = !to_pool.flash_loan.active @ TutorialError::FlashLoanActive,
+        constraint = !to_pool.locked @ TutorialError::PoolLocked,
+        constraint = to_pool.status.allows_deposits() @ TutorialError::PoolStatusRestricted,
+    )]
+    pub to_pool: Box<Account<'info, Pool>>,
+
+    /// CHECK: Read only authority
+    #[account(
+        seeds = [
+            from_pool.amm.as_ref(),
+            mint_a.key().as_ref(),
+            mint_b.key().as_ref(),
+            from_pool.fee_tier.to_le_bytes().as_ref(),
+            AUTHORITY_SEED,
+        ],
+        bump,
+    )]
+    pub from_pool_authority: AccountInfo<'info>,
+
+    /// CHECK: Read only authority
+    #[account(
+        seeds = [
+            to_pool.amm.as_ref(),
+            mint_a.key().as_ref(),
+            mint_b.key().as_ref(),
+            to_pool.fee_tier.to_le_bytes().as_ref(),
+            AUTHORITY_SEED,
+        ],
+        bump,
+    )]
+    pub to_pool_authority: AccountInfo<'info>,
+
+    /// The liquidity provider moving the position
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+
+    /// AMM的地址黑名单，未设置黑名单时可以省略
+    #[account(address = amm.denylist @ TutorialError::DenylistRequired)]
+    pub denylist: Option<Box<Account<'info, Denylist>>>,
+
+    #[account(
+        mut,
+        seeds = [
+            from_pool.amm.as_ref(),
+            mint_a.key().as_ref(),
+            mint_b.key().as_ref(),
+            from_pool.fee_tier.to_le_bytes().as_ref(),
+            LIQUIDITY_SEED,
+        ],
+        bump,
+    )]
+    pub from_mint_liquidity: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [
+            to_pool.amm.as_ref(),
+            mint_a.key().as_ref(),
+            mint_b.key().as_ref(),
+            to_pool.fee_tier.to_le_bytes().as_ref(),
+            LIQUIDITY_SEED,
+        ],
+        bump,
+    )]
+    pub to_mint_liquidity: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_program_a)]
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_program_b)]
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = from_pool_authority,
+        associated_token::token_program = token_program_a,
+    )]
+    pub from_pool_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_b,
+        associated_token::authority = from_pool_authority,
+        associated_token::token_program = token_program_b,
+    )]
+    pub from_pool_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = to_pool_authority,
+        associated_token::token_program = token_program_a,
+    )]
+    pub to_pool_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_b,
+        associated_token::authority = to_pool_authority,
+        associated_token::token_program = token_program_b,
+    )]
+    pub to_pool_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = from_mint_liquidity,
+        associated_token::authority = depositor,
+        associated_token::token_program = token_program,
+    )]
+    pub depositor_account_from_liquidity: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = depositor,
+        associated_token::mint = to_mint_liquidity,
+        associated_token::authority = depositor,
+        associated_token::token_program = token_program,
+    )]
+    pub depositor_account_to_liquidity: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = depositor,
+        associated_token::token_program = token_program_a,
+    )]
+    pub depositor_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_b,
+        associated_token::authority = depositor,
+        associated_token::token_program = token_program_b,
+    )]
+    pub depositor_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// LP在旧池的存款记录，用于计算提取费
+    #[account(
+        mut,
+        seeds = [
+            from_pool.key().as_ref(),
+            depositor.key().as_ref(),
+            DEPOSIT_RECORD_SEED,
+        ],
+        bump,
+    )]
+    pub from_deposit_record: Option<Box<Account<'info, DepositRecord>>>,
+
+    /// LP在新池的存款记录，按迁入的流动性更新加权存入时间；启用提取费时必须提供
+    #[account(
+        mut,
+        seeds = [
+            to_pool.key().as_ref(),
+            depositor.key().as_ref(),
+            DEPOSIT_RECORD_SEED,
+        ],
+        bump,
+    )]
+    pub to_deposit_record: Option<Box<Account<'info, DepositRecord>>>,
+
+    /// Solana ecosystem accounts
+    pub token_program: Program<'info, Token>,
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
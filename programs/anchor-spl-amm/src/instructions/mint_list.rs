@@ -0,0 +1,139 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{MAX_MINT_LIST_LEN, MINT_LIST_SEED},
+    errors::TutorialError,
+    events::MintListUpdatedEvent,
+    models::{mint_policy::MintListMode, roles::Role},
+    state::{Amm, MintList},
+};
+
+/// 为AMM创建代币名单，之后创建池子必须提供名单并通过检查
+pub fn create_mint_list(ctx: Context<CreateMintList>, mode: MintListMode) -> Result<()> {
+    let mint_list = &mut ctx.accounts.mint_list;
+    mint_list.amm = ctx.accounts.amm.key();
+    mint_list.mode = mode;
+    ctx.accounts.amm.mint_list = mint_list.key();
+
+    Ok(())
+}
+
+/// 关闭代币名单，取消创建池子时的名单检查，租金退还给管理员
+pub fn close_mint_list(ctx: Context<CloseMintList>) -> Result<()> {
+    ctx.accounts.amm.mint_list = Pubkey::default();
+
+    Ok(())
+}
+
+/// 将代币加入名单
+pub fn add_mint_to_list(ctx: Context<UpdateMintList>, mint: Pubkey) -> Result<()> {
+    let mint_list = &mut ctx.accounts.mint_list;
+    if mint_list.mints.contains(&mint) {
+        return err!(TutorialError::MintAlreadyListed);
+    }
+    if mint_list.mints.len() >= MAX_MINT_LIST_LEN {
+        return err!(TutorialError::MintListFull);
+    }
+    mint_list.mints.push(mint);
+
+    emit!(MintListUpdatedEvent {
+        amm: mint_list.amm,
+        mint,
+        listed: true,
+    });
+
+    Ok(())
+}
+
+/// 将代币移出名单，已创建的池子不受影响
+pub fn remove_mint_from_list(ctx: Context<UpdateMintList>, mint: Pubkey) -> Result<()> {
+    let mint_list = &mut ctx.accounts.mint_list;
+    let index = mint_list
+        .mints
+        .iter()
+        .position(|listed| *listed == mint)
+        .ok_or(TutorialError::MintNotListed)?;
+    mint_list.mints.swap_remove(index);
+
+    emit!(MintListUpdatedEvent {
+        amm: mint_list.amm,
+        mint,
+        listed: false,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateMintList<'info> {
+    #[account(
+        mut,
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+        constraint = amm.has_role(Role::RiskManager, &admin.key()) @ TutorialError::MissingRole,
+    )]
+    pub amm: Box<Account<'info, Amm>>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = MintList::LEN,
+        seeds = [
+            amm.key().as_ref(),
+            MINT_LIST_SEED,
+        ],
+        bump,
+    )]
+    pub mint_list: Box<Account<'info, MintList>>,
+
+    /// The owner or the risk manager of the AMM
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseMintList<'info> {
+    #[account(
+        mut,
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+        constraint = amm.has_role(Role::RiskManager, &admin.key()) @ TutorialError::MissingRole,
+        has_one = mint_list,
+    )]
+    pub amm: Box<Account<'info, Amm>>,
+
+    #[account(
+        mut,
+        close = admin,
+    )]
+    pub mint_list: Box<Account<'info, MintList>>,
+
+    /// The owner or the risk manager of the AMM
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateMintList<'info> {
+    #[account(
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+        constraint = amm.has_role(Role::RiskManager, &admin.key()) @ TutorialError::MissingRole,
+        has_one = mint_list,
+    )]
+    pub amm: Box<Account<'info, Amm>>,
+
+    #[account(mut)]
+    pub mint_list: Box<Account<'info, MintList>>,
+
+    /// The owner or the risk manager of the AMM
+    pub admin: Signer<'info>,
+}
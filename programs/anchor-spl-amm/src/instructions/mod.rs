@@ -1,11 +1,121 @@
+mod claim_fees;
+mod close_pool;
+mod collect_protocol_fees;
+mod commit_reveal;
 mod create_amm;
 mod create_pool;
+mod dca;
+mod denylist;
 mod deposit_liquidity;
+mod deposit_record;
+mod deposit_single_sided;
+mod escrow_swap;
+mod farming;
+mod fee_exemption;
+mod flash_loan;
+mod get_pool_apr;
+mod get_twap;
+mod governance;
+mod grow_pool_oracle;
+mod host_fee;
+mod il_compensation;
+mod limit_order;
+mod liquidity_lock;
+mod migrate;
+mod migrate_liquidity;
+mod mint_list;
+mod native_sol;
+mod position;
+mod preview_liquidity;
+mod program_config;
+mod quote_swap;
+mod range_order;
+mod relayed_swap;
+mod reserves;
+mod roles;
+mod set_circuit_breaker;
+mod set_deposit_cap;
+mod set_exit_only;
+mod set_fee_recipient;
+mod set_oracle_guard;
+mod set_pool_status;
+mod set_swap_hook;
+mod swap_batch;
 mod swap_exact_tokens_for_tokens;
+mod swap_hook;
+mod swap_route;
+mod swap_split;
+mod swap_tokens_for_exact_tokens;
+mod timelock;
+mod trader_stats;
+mod update_concentrated_liquidity_config;
+mod update_fee_config;
+mod update_fee_side;
+mod update_mint_policy;
+mod update_price_impact_config;
+mod update_volatility;
+mod update_volatility_config;
+mod update_withdrawal_fee_config;
+mod vault;
 mod withdraw_liquidity;
+mod withdraw_single_token;
 
+pub use claim_fees::*;
+pub use close_pool::*;
+pub use collect_protocol_fees::*;
+pub use commit_reveal::*;
 pub use create_amm::*;
 pub use create_pool::*;
+pub use dca::*;
+pub use denylist::*;
 pub use deposit_liquidity::*;
+pub use deposit_record::*;
+pub use deposit_single_sided::*;
+pub use farming::*;
+pub use fee_exemption::*;
+pub use flash_loan::*;
+pub use get_pool_apr::*;
+pub use get_twap::*;
+pub use governance::*;
+pub use grow_pool_oracle::*;
+pub use host_fee::*;
+pub use il_compensation::*;
+pub use limit_order::*;
+pub use liquidity_lock::*;
+pub use migrate::*;
+pub use migrate_liquidity::*;
+pub use mint_list::*;
+pub use native_sol::*;
+pub use position::*;
+pub use preview_liquidity::*;
+pub use program_config::*;
+pub use quote_swap::*;
+pub use range_order::*;
+pub use relayed_swap::*;
+pub use reserves::*;
+pub use roles::*;
+pub use set_circuit_breaker::*;
+pub use set_deposit_cap::*;
+pub use set_exit_only::*;
+pub use set_fee_recipient::*;
+pub use set_oracle_guard::*;
+pub use set_pool_status::*;
+pub use set_swap_hook::*;
+pub use swap_batch::*;
 pub use swap_exact_tokens_for_tokens::*;
-pub use withdraw_liquidity::*;
\ No newline at end of file
+pub use swap_route::*;
+pub use swap_split::*;
+pub use swap_tokens_for_exact_tokens::*;
+pub use timelock::*;
+pub use trader_stats::*;
+pub use update_concentrated_liquidity_config::*;
+pub use update_fee_config::*;
+pub use update_fee_side::*;
+pub use update_mint_policy::*;
+pub use update_price_impact_config::*;
+pub use update_volatility::*;
+pub use update_volatility_config::*;
+pub use update_withdrawal_fee_config::*;
+pub use vault::*;
+pub use withdraw_liquidity::*;
+pub use withdraw_single_token::*;
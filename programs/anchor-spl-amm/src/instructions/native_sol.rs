@@ -0,0 +1,201 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{self, Transfer as SystemTransfer},
+};
+use anchor_spl::{
+    token::spl_token::native_mint,
+    token_interface::{self, CloseAccount, SyncNative},
+};
+
+use crate::{
+    errors::TutorialError,
+    models::swap_options::SwapOptions,
+    utils::check_deadline,
+};
+
+use super::{
+    deposit_liquidity::{deposit_liquidity_process, DepositLiquidity},
+    swap_exact_tokens_for_tokens::{swap_exact_tokens_for_tokens_process, SwapExactTokensForTokens},
+    withdraw_liquidity::{withdraw_liquidity_process, WithdrawLiquidity},
+};
+
+// 将原生SOL转入wSOL账户并同步余额
+fn wrap_sol<'info>(
+    owner: AccountInfo<'info>,
+    wsol_account: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    system_program::transfer(
+        CpiContext::new(
+            system_program,
+            SystemTransfer {
+                from: owner,
+                to: wsol_account.clone(),
+            },
+        ),
+        amount,
+    )?;
+    token_interface::sync_native(CpiContext::new(
+        token_program,
+        SyncNative {
+            account: wsol_account,
+        },
+    ))
+}
+
+// 关闭wSOL账户，将全部余额和租金以原生SOL返还给所有者
+fn unwrap_sol<'info>(
+    owner: AccountInfo<'info>,
+    wsol_account: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+) -> Result<()> {
+    token_interface::close_account(CpiContext::new(
+        token_program,
+        CloseAccount {
+            account: wsol_account,
+            destination: owner.clone(),
+            authority: owner,
+        },
+    ))
+}
+
+// 判断池中哪一侧是原生SOL，true表示代币A
+fn native_side(mint_a: &Pubkey, mint_b: &Pubkey) -> Result<bool> {
+    if *mint_a == native_mint::ID {
+        Ok(true)
+    } else if *mint_b == native_mint::ID {
+        Ok(false)
+    } else {
+        err!(TutorialError::NotNativeMintPool)
+    }
+}
+
+pub fn swap_exact_tokens_for_tokens_native<'info>(
+    mut ctx: Context<'_, '_, 'info, 'info, SwapExactTokensForTokens<'info>>,
+    input_mint: Pubkey,
+    input_amount: u64,
+    min_output_amount: u64,
+    deadline: Option<i64>,
+) -> Result<()> {
+    check_deadline(deadline)?;
+    let swap_a = ctx.accounts.pool.swap_direction(&input_mint)?;
+
+    let native_a = native_side(&ctx.accounts.mint_a.key(), &ctx.accounts.mint_b.key())?;
+    let (wsol_account, native_token_program) = if native_a {
+        (
+            ctx.accounts.trader_token_accounts.trader_account_a.to_account_info(),
+            ctx.accounts.token_program_a.to_account_info(),
+        )
+    } else {
+        (
+            ctx.accounts.trader_token_accounts.trader_account_b.to_account_info(),
+            ctx.accounts.token_program_b.to_account_info(),
+        )
+    };
+
+    // 输入为SOL时先包装
+    if swap_a == native_a {
+        wrap_sol(
+            ctx.accounts.trader.to_account_info(),
+            wsol_account.clone(),
+            ctx.accounts.system_program.to_account_info(),
+            native_token_program.clone(),
+            input_amount,
+        )?;
+        if native_a {
+            ctx.accounts.trader_token_accounts.trader_account_a.reload()?;
+        } else {
+            ctx.accounts.trader_token_accounts.trader_account_b.reload()?;
+        }
+    }
+
+    swap_exact_tokens_for_tokens_process(&mut ctx, swap_a, input_amount, min_output_amount, SwapOptions::default())?;
+
+    // 无论SOL是输入还是输出，最后都解包为原生SOL
+    unwrap_sol(
+        ctx.accounts.trader.to_account_info(),
+        wsol_account,
+        native_token_program,
+    )
+}
+
+pub fn deposit_liquidity_native(
+    mut ctx: Context<DepositLiquidity>,
+    amount_a: u64,
+    amount_b: u64,
+    min_lp_tokens_out: u64,
+    deadline: Option<i64>,
+    clamp_to_balance: bool,
+) -> Result<()> {
+    check_deadline(deadline)?;
+
+    let native_a = native_side(&ctx.accounts.mint_a.key(), &ctx.accounts.mint_b.key())?;
+    let (wsol_account, native_token_program, native_amount) = if native_a {
+        (
+            ctx.accounts.depositor_account_a.to_account_info(),
+            ctx.accounts.token_program_a.to_account_info(),
+            amount_a,
+        )
+    } else {
+        (
+            ctx.accounts.depositor_account_b.to_account_info(),
+            ctx.accounts.token_program_b.to_account_info(),
+            amount_b,
+        )
+    };
+
+    wrap_sol(
+        ctx.accounts.depositor.to_account_info(),
+        wsol_account.clone(),
+        ctx.accounts.system_program.to_account_info(),
+        native_token_program.clone(),
+        native_amount,
+    )?;
+    if native_a {
+        ctx.accounts.depositor_account_a.reload()?;
+    } else {
+        ctx.accounts.depositor_account_b.reload()?;
+    }
+
+    deposit_liquidity_process(&mut ctx, amount_a, amount_b, min_lp_tokens_out, None, clamp_to_balance)?;
+
+    // 按池子比例未用完的SOL退还给存款人
+    unwrap_sol(
+        ctx.accounts.depositor.to_account_info(),
+        wsol_account,
+        native_token_program,
+    )
+}
+
+pub fn withdraw_liquidity_native(
+    mut ctx: Context<WithdrawLiquidity>,
+    amount: u64,
+    min_amount_a: u64,
+    min_amount_b: u64,
+    deadline: Option<i64>,
+) -> Result<()> {
+    check_deadline(deadline)?;
+
+    let native_a = native_side(&ctx.accounts.mint_a.key(), &ctx.accounts.mint_b.key())?;
+
+    withdraw_liquidity_process(&mut ctx, amount, min_amount_a, min_amount_b)?;
+
+    let (wsol_account, native_token_program) = if native_a {
+        (
+            ctx.accounts.depositor_token_accounts.depositor_account_a.to_account_info(),
+            ctx.accounts.token_program_a.to_account_info(),
+        )
+    } else {
+        (
+            ctx.accounts.depositor_token_accounts.depositor_account_b.to_account_info(),
+            ctx.accounts.token_program_b.to_account_info(),
+        )
+    };
+    unwrap_sol(
+        ctx.accounts.depositor.to_account_info(),
+        wsol_account,
+        native_token_program,
+    )
+}
@@ -0,0 +1,833 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    metadata::{
+        self, mpl_token_metadata::types::DataV2, BurnNft, CreateMasterEditionV3, CreateMetadataAccountsV3, Metadata,
+    },
+    token::Token,
+    token_interface::{self, Burn, CloseAccount, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    constants::{
+        AUTHORITY_SEED, ESCROW_SEED, LIQUIDITY_SEED, MINIMUM_LIQUIDITY, POSITION_NFT_NAME, POSITION_NFT_SYMBOL,
+        POSITION_SEED,
+    },
+    errors::TutorialError,
+    events::{PositionClosedEvent, PositionFeesCollectedEvent, PositionLiquidityUpdatedEvent, PositionOpenedEvent},
+    models::{concentrated_liquidity::ConcentratedLiquidityPricing, lp_fees::LpFeeCalculator},
+    state::{Amm, Denylist, Pool, Position},
+};
+
+/// 当前每个流动性代币对应的不变量和池子价格，用于头寸的手续费结算
+pub(crate) fn pool_snapshot(pool: &Pool, mint_liquidity: &Mint) -> Result<(u128, u128)> {
+    let (reserve_a, reserve_b) = pool.reserves();
+    let growth =
+        LpFeeCalculator::growth_per_share(&pool.curve_type, reserve_a, reserve_b, mint_liquidity.supply + MINIMUM_LIQUIDITY)?;
+    Ok((growth, ConcentratedLiquidityPricing::price_x64(reserve_a, reserve_b)))
+}
+
+/// 通过元数据程序销毁头寸NFT，同时关闭NFT的代币账户、元数据和主版本账户，租金退还给持有者
+pub(crate) fn burn_position_nft<'info>(
+    metadata_program: &Program<'info, Metadata>,
+    metadata: &AccountInfo<'info>,
+    edition: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    token: &AccountInfo<'info>,
+    owner: &AccountInfo<'info>,
+    token_program: &Program<'info, Token>,
+) -> Result<()> {
+    metadata::burn_nft(
+        CpiContext::new(
+            metadata_program.to_account_info(),
+            BurnNft {
+                metadata: metadata.clone(),
+                owner: owner.clone(),
+                mint: mint.clone(),
+                token: token.clone(),
+                edition: edition.clone(),
+                spl_token: token_program.to_account_info(),
+            },
+        ),
+        None,
+    )
+}
+
+/// 开立聚合流动性头寸：流动性代币托管在头寸中，并向所有者铸造唯一的头寸NFT，
+/// 头寸以NFT为键，NFT可以转让或作为抵押品
+pub fn open_position(
+    ctx: Context<OpenPosition>,
+    lower_price_x64: u128,
+    upper_price_x64: u128,
+    liquidity: u64,
+) -> Result<()> {
+    if lower_price_x64 >= upper_price_x64 {
+        return err!(TutorialError::InvalidPriceRange);
+    }
+    if liquidity == 0 {
+        return err!(TutorialError::DepositTooSmall);
+    }
+
+    // 1. 转入流动性代币
+    token_interface::transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.owner_account_liquidity.to_account_info(),
+                mint: ctx.accounts.mint_liquidity.to_account_info(),
+                to: ctx.accounts.escrow.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            },
+        ),
+        liquidity,
+        ctx.accounts.mint_liquidity.decimals,
+    )?;
+
+    // 2. 铸造头寸NFT，创建Metaplex元数据和主版本，主版本接管铸币权限并把供应量固定为1
+    let position_mint = ctx.accounts.position_mint.key();
+    let position_seeds = &[
+        position_mint.as_ref(),
+        POSITION_SEED,
+        &[ctx.bumps.position],
+    ];
+    let signer_seeds = &[&position_seeds[..]];
+    token_interface::mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.position_mint.to_account_info(),
+                to: ctx.accounts.owner_account_nft.to_account_info(),
+                authority: ctx.accounts.position.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        1,
+    )?;
+    metadata::create_metadata_accounts_v3(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_metadata_program.to_account_info(),
+            CreateMetadataAccountsV3 {
+                metadata: ctx.accounts.position_metadata.to_account_info(),
+                mint: ctx.accounts.position_mint.to_account_info(),
+                mint_authority: ctx.accounts.position.to_account_info(),
+                payer: ctx.accounts.owner.to_account_info(),
+                update_authority: ctx.accounts.position.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                // 元数据程序的v3指令已不再读取rent账户
+                rent: ctx.accounts.system_program.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        DataV2 {
+            name: POSITION_NFT_NAME.to_string(),
+            symbol: POSITION_NFT_SYMBOL.to_string(),
+            uri: String::new(),
+            seller_fee_basis_points: 0,
+            creators: None,
+            collection: None,
+            uses: None,
+        },
+        false,
+        true,
+        None,
+    )?;
+    metadata::create_master_edition_v3(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_metadata_program.to_account_info(),
+            CreateMasterEditionV3 {
+                edition: ctx.accounts.position_edition.to_account_info(),
+                mint: ctx.accounts.position_mint.to_account_info(),
+                update_authority: ctx.accounts.position.to_account_info(),
+                mint_authority: ctx.accounts.position.to_account_info(),
+                payer: ctx.accounts.owner.to_account_info(),
+                metadata: ctx.accounts.position_metadata.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                rent: ctx.accounts.system_program.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        Some(0),
+    )?;
+
+    let position = &mut ctx.accounts.position;
+    position.pool = ctx.accounts.pool.key();
+    position.position_mint = position_mint;
+    position.liquidity = liquidity;
+    position.lower_price_x64 = lower_price_x64;
+    position.upper_price_x64 = upper_price_x64;
+    let price_x64;
+    (position.fee_growth_checkpoint, price_x64) = pool_snapshot(&ctx.accounts.pool, &ctx.accounts.mint_liquidity)?;
+    // 区间不包含当前价格的头寸作为区间订单
+    position.range_order = if lower_price_x64 > price_x64 {
+        Some(true)
+    } else if upper_price_x64 < price_x64 {
+        Some(false)
+    } else {
+        None
+    };
+
+    emit!(PositionOpenedEvent {
+        pool: position.pool,
+        position_mint,
+        owner: ctx.accounts.owner.key(),
+        liquidity,
+        lower_price_x64,
+        upper_price_x64,
+        range_order: position.range_order,
+    });
+
+    Ok(())
+}
+
+/// 向已有头寸追加流动性，追加前先结算手续费，价格区间不变
+pub fn increase_liquidity(ctx: Context<ModifyPosition>, amount: u64) -> Result<()> {
+    if amount == 0 {
+        return err!(TutorialError::DepositTooSmall);
+    }
+    Denylist::check(&ctx.accounts.amm, ctx.accounts.denylist.as_deref().map(|denylist| &**denylist), &ctx.accounts.owner.key())?;
+
+    let (growth, _) = pool_snapshot(&ctx.accounts.pool, &ctx.accounts.mint_liquidity)?;
+    let position = &mut ctx.accounts.position;
+    position.settle_fees(growth)?;
+    position.liquidity = position.liquidity.checked_add(amount).ok_or(TutorialError::MathOverflow)?;
+
+    token_interface::transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.owner_account_liquidity.to_account_info(),
+                mint: ctx.accounts.mint_liquidity.to_account_info(),
+                to: ctx.accounts.escrow.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            },
+        ),
+        amount,
+        ctx.accounts.mint_liquidity.decimals,
+    )?;
+
+    emit!(PositionLiquidityUpdatedEvent {
+        pool: position.pool,
+        position_mint: position.position_mint,
+        increase: true,
+        amount,
+        liquidity: position.liquidity,
+        fees_owed: position.fees_owed,
+    });
+
+    Ok(())
+}
+
+/// 从头寸中取回部分本金流动性，取回前先结算手续费，已结算的手续费留在头寸中待领取
+pub fn decrease_liquidity(ctx: Context<ModifyPosition>, amount: u64) -> Result<()> {
+    let (growth, _) = pool_snapshot(&ctx.accounts.pool, &ctx.accounts.mint_liquidity)?;
+    let position = &mut ctx.accounts.position;
+    position.settle_fees(growth)?;
+    if amount == 0 || amount > position.liquidity {
+        return err!(TutorialError::InsufficientLiquidity);
+    }
+    position.liquidity -= amount;
+
+    let position_seeds = &[
+        position.position_mint.as_ref(),
+        POSITION_SEED,
+        &[ctx.bumps.position],
+    ];
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.escrow.to_account_info(),
+                mint: ctx.accounts.mint_liquidity.to_account_info(),
+                to: ctx.accounts.owner_account_liquidity.to_account_info(),
+                authority: position.to_account_info(),
+            },
+            &[&position_seeds[..]],
+        ),
+        amount,
+        ctx.accounts.mint_liquidity.decimals,
+    )?;
+
+    emit!(PositionLiquidityUpdatedEvent {
+        pool: position.pool,
+        position_mint: position.position_mint,
+        increase: false,
+        amount,
+        liquidity: position.liquidity,
+        fees_owed: position.fees_owed,
+    });
+
+    Ok(())
+}
+
+/// 领取头寸的手续费：结算后销毁待领取手续费对应的流动性代币并兑换为两种代币
+pub fn collect_position_fees(ctx: Context<CollectPositionFees>) -> Result<()> {
+    let (pool_a, pool_b) = ctx.accounts.pool.reserves();
+    let total_liquidity = ctx.accounts.mint_liquidity.supply + MINIMUM_LIQUIDITY;
+    let (growth, _) = pool_snapshot(&ctx.accounts.pool, &ctx.accounts.mint_liquidity)?;
+    let position = &mut ctx.accounts.position;
+    position.settle_fees(growth)?;
+
+    // 1. 手续费以流动性代币计，按比例兑换为两种代币
+    let liquidity = position.fees_owed;
+    if liquidity == 0 {
+        return Ok(());
+    }
+    position.fees_owed = 0;
+    let share = |reserve: u64| {
+        (liquidity as u128)
+            .checked_mul(reserve as u128)
+            .and_then(|v| v.checked_div(total_liquidity as u128))
+            .and_then(|v| u64::try_from(v).ok())
+            .ok_or(TutorialError::MathOverflow)
+    };
+    let amount_a = share(pool_a)?;
+    let amount_b = share(pool_b)?;
+
+    // 2. 转出手续费
+    let authority_bump = ctx.bumps.pool_authority;
+    let authority_seeds = &[
+        &ctx.accounts.pool.amm.to_bytes(),
+        &ctx.accounts.mint_a.key().to_bytes(),
+        &ctx.accounts.mint_b.key().to_bytes(),
+        &ctx.accounts.pool.fee_tier.to_le_bytes()[..],
+        AUTHORITY_SEED,
+        &[authority_bump],
+    ];
+    let signer_seeds = &[&authority_seeds[..]];
+    let transfers = [
+        (
+            &ctx.accounts.pool_account_a,
+            &ctx.accounts.mint_a,
+            &ctx.accounts.owner_account_a,
+            &ctx.accounts.token_program_a,
+            amount_a,
+        ),
+        (
+            &ctx.accounts.pool_account_b,
+            &ctx.accounts.mint_b,
+            &ctx.accounts.owner_account_b,
+            &ctx.accounts.token_program_b,
+            amount_b,
+        ),
+    ];
+    for (from, mint, to, token_program, amount) in transfers {
+        if amount == 0 {
+            continue;
+        }
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                TransferChecked {
+                    from: from.to_account_info(),
+                    mint: mint.to_account_info(),
+                    to: to.to_account_info(),
+                    authority: ctx.accounts.pool_authority.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+            mint.decimals,
+        )?;
+    }
+
+    // 3. 从头寸的托管账户中销毁手续费对应的流动性代币
+    let position_seeds = &[
+        ctx.accounts.position.position_mint.as_ref(),
+        POSITION_SEED,
+        &[ctx.bumps.position],
+    ];
+    token_interface::burn(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.mint_liquidity.to_account_info(),
+                from: ctx.accounts.escrow.to_account_info(),
+                authority: ctx.accounts.position.to_account_info(),
+            },
+            &[&position_seeds[..]],
+        ),
+        liquidity,
+    )?;
+
+    ctx.accounts.pool.remove_reserves(amount_a, amount_b)?;
+
+    emit!(PositionFeesCollectedEvent {
+        pool: ctx.accounts.pool.key(),
+        position_mint: ctx.accounts.position.position_mint,
+        liquidity,
+        amount_a,
+        amount_b,
+    });
+
+    Ok(())
+}
+
+/// 关闭头寸：销毁头寸NFT及其元数据，取回全部流动性代币（包含未领取的手续费）
+pub fn close_position(ctx: Context<ClosePosition>) -> Result<()> {
+    let position_mint = ctx.accounts.position_mint.key();
+    let position_seeds = &[
+        position_mint.as_ref(),
+        POSITION_SEED,
+        &[ctx.bumps.position],
+    ];
+    let signer_seeds = &[&position_seeds[..]];
+    let liquidity = ctx.accounts.escrow.amount;
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.escrow.to_account_info(),
+                mint: ctx.accounts.mint_liquidity.to_account_info(),
+                to: ctx.accounts.owner_account_liquidity.to_account_info(),
+                authority: ctx.accounts.position.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        liquidity,
+        ctx.accounts.mint_liquidity.decimals,
+    )?;
+    token_interface::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.escrow.to_account_info(),
+            destination: ctx.accounts.owner.to_account_info(),
+            authority: ctx.accounts.position.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
+
+    burn_position_nft(
+        &ctx.accounts.token_metadata_program,
+        &ctx.accounts.position_metadata,
+        &ctx.accounts.position_edition,
+        &ctx.accounts.position_mint.to_account_info(),
+        &ctx.accounts.owner_account_nft.to_account_info(),
+        &ctx.accounts.owner.to_account_info(),
+        &ctx.accounts.token_program,
+    )?;
+
+    emit!(PositionClosedEvent {
+        pool: ctx.accounts.position.pool,
+        position_mint,
+        owner: ctx.accounts.owner.key(),
+        liquidity,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct OpenPosition<'info> {
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// CHECK: Read only authority
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.as_ref(),
+            pool.mint_b.as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+            AUTHORITY_SEED,
+        ],
+        bump,
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    #[account(
+        token::mint = pool.mint_a,
+        token::authority = pool_authority,
+    )]
+    pub pool_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        token::mint = pool.mint_b,
+        token::authority = pool_authority,
+    )]
+    pub pool_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.as_ref(),
+            pool.mint_b.as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+            LIQUIDITY_SEED,
+        ],
+        bump,
+    )]
+    pub mint_liquidity: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = owner,
+        mint::decimals = 0,
+        mint::authority = position,
+        mint::token_program = token_program,
+    )]
+    pub position_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = Position::LEN,
+        seeds = [
+            position_mint.key().as_ref(),
+            POSITION_SEED,
+        ],
+        bump,
+    )]
+    pub position: Box<Account<'info, Position>>,
+
+    #[account(
+        init,
+        payer = owner,
+        seeds = [
+            position.key().as_ref(),
+            ESCROW_SEED,
+        ],
+        bump,
+        token::mint = mint_liquidity,
+        token::authority = position,
+        token::token_program = token_program,
+    )]
+    pub escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The LP opening the position and paying for all rents
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = mint_liquidity,
+        token::authority = owner,
+    )]
+    pub owner_account_liquidity: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = owner,
+        associated_token::mint = position_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program,
+    )]
+    pub owner_account_nft: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// 头寸NFT的元数据账户，由元数据程序校验地址
+    /// CHECK: Initialized by the token metadata program
+    #[account(mut)]
+    pub position_metadata: UncheckedAccount<'info>,
+
+    /// 头寸NFT的主版本账户，由元数据程序校验地址
+    /// CHECK: Initialized by the token metadata program
+    #[account(mut)]
+    pub position_edition: UncheckedAccount<'info>,
+
+    /// Solana ecosystem accounts
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub token_metadata_program: Program<'info, Metadata>,
+}
+
+#[derive(Accounts)]
+pub struct ClosePosition<'info> {
+    #[account(
+        mut,
+        seeds = [
+            position_mint.key().as_ref(),
+            POSITION_SEED,
+        ],
+        bump,
+        close = owner,
+    )]
+    pub position: Box<Account<'info, Position>>,
+
+    #[account(
+        mut,
+        address = position.position_mint,
+    )]
+    pub position_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [
+            position.key().as_ref(),
+            ESCROW_SEED,
+        ],
+        bump,
+    )]
+    pub escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = escrow.mint)]
+    pub mint_liquidity: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The holder of the position NFT
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = position_mint,
+        token::authority = owner,
+        constraint = owner_account_nft.amount == 1 @ TutorialError::InvalidMint,
+    )]
+    pub owner_account_nft: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint_liquidity,
+        token::authority = owner,
+    )]
+    pub owner_account_liquidity: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// 头寸NFT的元数据账户，由元数据程序校验并关闭
+    /// CHECK: Checked and closed by the token metadata program
+    #[account(mut)]
+    pub position_metadata: UncheckedAccount<'info>,
+
+    /// 头寸NFT的主版本账户，由元数据程序校验并关闭
+    /// CHECK: Checked and closed by the token metadata program
+    #[account(mut)]
+    pub position_edition: UncheckedAccount<'info>,
+
+    /// Solana ecosystem accounts
+    pub token_program: Program<'info, Token>,
+    pub token_metadata_program: Program<'info, Metadata>,
+}
+
+#[derive(Accounts)]
+pub struct ModifyPosition<'info> {
+    #[account(
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+    )]
+    pub amm: Box<Account<'info, Amm>>,
+
+    #[account(has_one = amm)]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// CHECK: Read only authority
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.as_ref(),
+            pool.mint_b.as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+            AUTHORITY_SEED,
+        ],
+        bump,
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    #[account(
+        token::mint = pool.mint_a,
+        token::authority = pool_authority,
+    )]
+    pub pool_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        token::mint = pool.mint_b,
+        token::authority = pool_authority,
+    )]
+    pub pool_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.as_ref(),
+            pool.mint_b.as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+            LIQUIDITY_SEED,
+        ],
+        bump,
+    )]
+    pub mint_liquidity: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [
+            position.position_mint.as_ref(),
+            POSITION_SEED,
+        ],
+        bump,
+        has_one = pool,
+    )]
+    pub position: Box<Account<'info, Position>>,
+
+    #[account(
+        mut,
+        seeds = [
+            position.key().as_ref(),
+            ESCROW_SEED,
+        ],
+        bump,
+    )]
+    pub escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The holder of the position NFT
+    pub owner: Signer<'info>,
+
+    /// AMM的地址黑名单，未设置黑名单时可以省略，只有追加流动性时检查
+    #[account(address = amm.denylist @ TutorialError::DenylistRequired)]
+    pub denylist: Option<Box<Account<'info, Denylist>>>,
+
+    #[account(
+        token::mint = position.position_mint,
+        token::authority = owner,
+        constraint = owner_account_nft.amount == 1 @ TutorialError::InvalidMint,
+    )]
+    pub owner_account_nft: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint_liquidity,
+        token::authority = owner,
+    )]
+    pub owner_account_liquidity: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// 头寸NFT的元数据账户，由元数据程序校验并关闭
+    /// CHECK: Checked and closed by the token metadata program
+    #[account(mut)]
+    pub position_metadata: UncheckedAccount<'info>,
+
+    /// 头寸NFT的主版本账户，由元数据程序校验并关闭
+    /// CHECK: Checked and closed by the token metadata program
+    #[account(mut)]
+    pub position_edition: UncheckedAccount<'info>,
+
+    /// Solana ecosystem accounts
+    pub token_program: Program<'info, Token>,
+    pub token_metadata_program: Program<'info, Metadata>,
+}
+
+#[derive(Accounts)]
+pub struct CollectPositionFees<'info> {
+    #[account(
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+    )]
+    pub amm: Box<Account<'info, Amm>>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.key().as_ref(),
+            pool.mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+        ],
+        bump,
+        has_one = amm,
+        has_one = mint_a,
+        has_one = mint_b,
+        constraint = !pool.flash_loan.active @ TutorialError::FlashLoanActive,
+        constraint = !pool.locked @ TutorialError::PoolLocked,
+        constraint = !pool.status.is_frozen() @ TutorialError::PoolStatusRestricted,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// CHECK: Read only authority
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            mint_a.key().as_ref(),
+            mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+            AUTHORITY_SEED,
+        ],
+        bump,
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            position.position_mint.as_ref(),
+            POSITION_SEED,
+        ],
+        bump,
+        has_one = pool,
+    )]
+    pub position: Box<Account<'info, Position>>,
+
+    #[account(
+        mut,
+        seeds = [
+            position.key().as_ref(),
+            ESCROW_SEED,
+        ],
+        bump,
+    )]
+    pub escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The holder of the position NFT
+    pub owner: Signer<'info>,
+
+    #[account(
+        token::mint = position.position_mint,
+        token::authority = owner,
+        constraint = owner_account_nft.amount == 1 @ TutorialError::InvalidMint,
+    )]
+    pub owner_account_nft: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool.amm.as_ref(),
+            mint_a.key().as_ref(),
+            mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+            LIQUIDITY_SEED,
+        ],
+        bump,
+    )]
+    pub mint_liquidity: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_program_a)]
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_program_b)]
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_a,
+    )]
+    pub pool_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_b,
+        associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_b,
+    )]
+    pub pool_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint_a,
+        token::authority = owner,
+        token::token_program = token_program_a,
+    )]
+    pub owner_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint_b,
+        token::authority = owner,
+        token::token_program = token_program_b,
+    )]
+    pub owner_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Solana ecosystem accounts
+    pub token_program: Program<'info, Token>,
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,138 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+use crate::{
+    constants::{AUTHORITY_SEED, LIQUIDITY_SEED},
+    errors::TutorialError,
+    instructions::{deposit_amounts, minted_liquidity, withdraw_amounts},
+    models::quote::{DepositPreview, LpTokenValue, WithdrawPreview},
+    state::{Amm, DepositRecord, Pool},
+    utils::get_transfer_fee,
+};
+
+/// 预览存款：返回按池子比例调整后的存入数量和铸造的流动性代币，
+/// 实际执行时还会按存款人的余额截断提供的数量
+pub fn preview_deposit(ctx: Context<PreviewLiquidity>, amount_a: u64, amount_b: u64) -> Result<DepositPreview> {
+    let reserves = ctx.accounts.pool.reserves();
+    let pool_creation = ctx.accounts.mint_liquidity.supply == 0;
+    let (amount_a, amount_b) = deposit_amounts(amount_a, amount_b, reserves, pool_creation, None)?;
+
+    let received_a = amount_a - get_transfer_fee(&ctx.accounts.mint_a.to_account_info(), amount_a)?;
+    let received_b = amount_b - get_transfer_fee(&ctx.accounts.mint_b.to_account_info(), amount_b)?;
+    let liquidity = minted_liquidity(&ctx.accounts.pool, received_a, received_b, reserves, ctx.accounts.mint_liquidity.supply)?;
+
+    Ok(DepositPreview {
+        amount_a,
+        amount_b,
+        liquidity,
+    })
+}
+
+/// 预览提取：返回销毁lp_amount个流动性代币可以提取的数量，
+/// 提供存款记录时按持有时间计算提取费，否则按最高提取费计算
+pub fn preview_withdraw(ctx: Context<PreviewLiquidity>, lp_amount: u64) -> Result<WithdrawPreview> {
+    let reserves = ctx.accounts.pool.reserves();
+    let now = Clock::get()?.unix_timestamp;
+    let withdrawal_fee_bps = DepositRecord::withdrawal_fee_bps(
+        ctx.accounts.deposit_record.as_deref().map(|record| &**record),
+        &ctx.accounts.amm.withdrawal_fee_config,
+        lp_amount,
+        now,
+    );
+    let (amount_a, amount_b) =
+        withdraw_amounts(lp_amount, reserves, ctx.accounts.mint_liquidity.supply, withdrawal_fee_bps)?;
+
+    Ok(WithdrawPreview {
+        amount_a,
+        amount_b,
+        withdrawal_fee_bps,
+    })
+}
+
+/// 一个流动性代币按记录的储备可以赎回的代币数量，不扣除提取费
+pub fn get_lp_token_value(ctx: Context<PreviewLiquidity>) -> Result<LpTokenValue> {
+    let reserves = ctx.accounts.pool.reserves();
+    let lp_decimals = ctx.accounts.mint_liquidity.decimals;
+    let one_lp = 10u64.checked_pow(lp_decimals as u32).ok_or(TutorialError::MathOverflow)?;
+    let (amount_a, amount_b) = withdraw_amounts(one_lp, reserves, ctx.accounts.mint_liquidity.supply, 0)?;
+
+    Ok(LpTokenValue {
+        amount_a,
+        amount_b,
+        lp_decimals,
+    })
+}
+
+#[derive(Accounts)]
+pub struct PreviewLiquidity<'info> {
+    #[account(
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+    )]
+    pub amm: Box<Account<'info, Amm>>,
+
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.key().as_ref(),
+            pool.mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+        ],
+        bump,
+        has_one = amm,
+        has_one = mint_a,
+        has_one = mint_b,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// CHECK: Read only authority
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            mint_a.key().as_ref(),
+            mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+            AUTHORITY_SEED,
+        ],
+        bump,
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            mint_a.key().as_ref(),
+            mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+            LIQUIDITY_SEED,
+        ],
+        bump,
+    )]
+    pub mint_liquidity: Box<InterfaceAccount<'info, Mint>>,
+
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
+
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        associated_token::mint = mint_a,
+        associated_token::authority = pool_authority,
+        associated_token::token_program = mint_a.to_account_info().owner,
+    )]
+    pub pool_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        associated_token::mint = mint_b,
+        associated_token::authority = pool_authority,
+        associated_token::token_program = mint_b.to_account_info().owner,
+    )]
+    pub pool_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// LP的存款记录，预览提取时用于计算提取费
+    #[account(
+        constraint = deposit_record.pool == pool.key() @ TutorialError::NestedAccountMismatch,
+    )]
+    pub deposit_record: Option<Box<Account<'info, DepositRecord>>>,
+}
@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::PROGRAM_CONFIG_SEED,
+    errors::TutorialError,
+    events::ProgramConfigUpdatedEvent,
+    models::program_config::FeatureFlags,
+    program::AnchorSplAmm,
+    state::ProgramConfig,
+};
+
+/// 创建全局程序配置，只能由程序的升级权限调用一次
+pub fn create_program_config(ctx: Context<CreateProgramConfig>, features: FeatureFlags) -> Result<()> {
+    let program_config = &mut ctx.accounts.program_config;
+    program_config.authority = ctx.accounts.authority.key();
+    program_config.features = features;
+
+    emit!(ProgramConfigUpdatedEvent {
+        authority: program_config.authority,
+        features,
+    });
+
+    Ok(())
+}
+
+/// 修改功能开关
+pub fn update_program_config(ctx: Context<UpdateProgramConfig>, features: FeatureFlags) -> Result<()> {
+    let program_config = &mut ctx.accounts.program_config;
+    program_config.features = features;
+
+    emit!(ProgramConfigUpdatedEvent {
+        authority: program_config.authority,
+        features,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateProgramConfig<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = ProgramConfig::LEN,
+        seeds = [PROGRAM_CONFIG_SEED],
+        bump,
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, AnchorSplAmm>,
+
+    #[account(constraint = program_data.upgrade_authority_address == Some(authority.key()) @ TutorialError::NotUpgradeAuthority)]
+    pub program_data: Account<'info, ProgramData>,
+
+    /// The upgrade authority of the program
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Solana ecosystem accounts
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateProgramConfig<'info> {
+    #[account(
+        mut,
+        seeds = [PROGRAM_CONFIG_SEED],
+        bump,
+        has_one = authority,
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    pub authority: Signer<'info>,
+}
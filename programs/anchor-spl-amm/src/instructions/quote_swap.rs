@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+use crate::{
+    constants::{AUTHORITY_SEED, POOL_ORACLE_SEED},
+    instructions::quote_exact_input,
+    models::quote::SwapQuote,
+    state::{Amm, Pool, PoolOracle},
+};
+
+pub fn quote_swap(ctx: Context<QuoteSwap>, input_mint: Pubkey, input_amount: u64) -> Result<SwapQuote> {
+    let swap_a = ctx.accounts.pool.swap_direction(&input_mint)?;
+    // 与swap_exact_tokens_for_tokens共用计算路径，报价与实际执行结果一致
+    let (mint_in, mint_out) = if swap_a {
+        (&ctx.accounts.mint_a, &ctx.accounts.mint_b)
+    } else {
+        (&ctx.accounts.mint_b, &ctx.accounts.mint_a)
+    };
+
+    quote_exact_input(
+        &ctx.accounts.amm,
+        &ctx.accounts.pool,
+        ctx.accounts.pool_oracle.as_deref().map(|oracle| &**oracle),
+        (&mint_in.to_account_info(), &mint_out.to_account_info()),
+        input_amount,
+        0,
+        &Clock::get()?,
+    )
+}
+
+#[derive(Accounts)]
+pub struct QuoteSwap<'info> {
+    #[account(
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+    )]
+    pub amm: Box<Account<'info, Amm>>,
+
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.key().as_ref(),
+            pool.mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+        ],
+        bump,
+        has_one = amm,
+        has_one = mint_a,
+        has_one = mint_b,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// 池子的波动率预言机，启用波动率跟踪或动态费率时必须提供
+    #[account(
+        seeds = [
+            pool.key().as_ref(),
+            POOL_ORACLE_SEED,
+        ],
+        bump,
+    )]
+    pub pool_oracle: Option<Box<Account<'info, PoolOracle>>>,
+
+    /// CHECK: Read only authority
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            mint_a.key().as_ref(),
+            mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+            AUTHORITY_SEED,
+        ],
+        bump,
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
+
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        associated_token::mint = mint_a,
+        associated_token::authority = pool_authority,
+        associated_token::token_program = mint_a.to_account_info().owner,
+    )]
+    pub pool_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        associated_token::mint = mint_b,
+        associated_token::authority = pool_authority,
+        associated_token::token_program = mint_b.to_account_info().owner,
+    )]
+    pub pool_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+}
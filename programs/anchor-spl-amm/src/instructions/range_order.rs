@@ -0,0 +1,312 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    metadata::Metadata,
+    token::Token,
+    token_interface::{self, Burn, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    constants::{AUTHORITY_SEED, ESCROW_SEED, LIQUIDITY_SEED, MINIMUM_LIQUIDITY, POOL_ORACLE_SEED, POSITION_SEED},
+    errors::TutorialError,
+    events::RangeOrderFilledEvent,
+    instructions::{burn_position_nft, swap_fee_rate_bps},
+    models::{concentrated_liquidity::ConcentratedLiquidityPricing, curve::CurveCalculator},
+    state::{Amm, Pool, PoolOracle, Position},
+};
+
+/// 关闭已成交的区间订单：价格完全穿过区间后，提取头寸的全部流动性，
+/// 在池内把剩余的输入代币换成输出代币，所有者只收到输出代币
+pub fn close_filled_range_order(ctx: Context<CloseFilledRangeOrder>, min_out: u64) -> Result<()> {
+    Pool::lock(&mut ctx.accounts.pool)?;
+    let swap_a = ctx.accounts.position.range_order.ok_or(TutorialError::NotRangeOrder)?;
+    let (pool_a, pool_b) = ctx.accounts.pool.reserves();
+    if !ctx.accounts.position.is_range_order_filled(ConcentratedLiquidityPricing::price_x64(pool_a, pool_b)) {
+        return err!(TutorialError::RangeOrderNotFilled);
+    }
+
+    // 1. 校验接收账户的代币种类
+    let mint_out = if swap_a { &ctx.accounts.mint_b } else { &ctx.accounts.mint_a };
+    if ctx.accounts.owner_account.mint != mint_out.key() {
+        return err!(TutorialError::InvalidMint);
+    }
+
+    // 2. 按份额比例计算两侧可提取的数量
+    let liquidity = ctx.accounts.escrow.amount;
+    let total_liquidity = ctx.accounts.mint_liquidity.supply as u128 + MINIMUM_LIQUIDITY as u128;
+    let share = |reserve: u64| {
+        (liquidity as u128)
+            .checked_mul(reserve as u128)
+            .and_then(|v| v.checked_div(total_liquidity))
+            .and_then(|v| u64::try_from(v).ok())
+            .ok_or(TutorialError::MathOverflow)
+    };
+    let amount_a = share(pool_a)?;
+    let amount_b = share(pool_b)?;
+
+    // 3. 把剩余的输入代币按提取后的储备在池内交换
+    let (amount_keep, amount_swap, reserve_in, reserve_out) = if swap_a {
+        (amount_b, amount_a, pool_a - amount_a, pool_b - amount_b)
+    } else {
+        (amount_a, amount_b, pool_b - amount_b, pool_a - amount_a)
+    };
+    let fee_rate_bps = swap_fee_rate_bps(
+        &ctx.accounts.amm,
+        &ctx.accounts.pool,
+        ctx.accounts.pool_oracle.as_deref().map(|oracle| &**oracle),
+        swap_a,
+        amount_swap,
+        (reserve_in, reserve_out),
+    );
+    let fee_amount = (amount_swap as u128 * fee_rate_bps as u128 / 10000) as u64;
+    let swap_output = CurveCalculator::swap_output(
+        &ctx.accounts.pool.curve_type,
+        swap_a,
+        amount_swap - fee_amount,
+        reserve_in,
+        reserve_out,
+    )?;
+    let output = amount_keep + swap_output;
+    if output < min_out {
+        return err!(TutorialError::OutputTooSmall);
+    }
+
+    // 4. 转出输出代币
+    let authority_bump = ctx.bumps.pool_authority;
+    let authority_seeds = &[
+        &ctx.accounts.pool.amm.to_bytes(),
+        &ctx.accounts.mint_a.key().to_bytes(),
+        &ctx.accounts.mint_b.key().to_bytes(),
+        &ctx.accounts.pool.fee_tier.to_le_bytes()[..],
+        AUTHORITY_SEED,
+        &[authority_bump],
+    ];
+    let (token_program, pool_account) = if swap_a {
+        (&ctx.accounts.token_program_b, &ctx.accounts.pool_account_b)
+    } else {
+        (&ctx.accounts.token_program_a, &ctx.accounts.pool_account_a)
+    };
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: pool_account.to_account_info(),
+                mint: mint_out.to_account_info(),
+                to: ctx.accounts.owner_account.to_account_info(),
+                authority: ctx.accounts.pool_authority.to_account_info(),
+            },
+            &[&authority_seeds[..]],
+        ),
+        output,
+        mint_out.decimals,
+    )?;
+
+    // 5. 销毁头寸的流动性代币，关闭托管账户并销毁头寸NFT
+    let position_mint = ctx.accounts.position_mint.key();
+    let position_seeds = &[
+        position_mint.as_ref(),
+        POSITION_SEED,
+        &[ctx.bumps.position],
+    ];
+    let signer_seeds = &[&position_seeds[..]];
+    token_interface::burn(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.mint_liquidity.to_account_info(),
+                from: ctx.accounts.escrow.to_account_info(),
+                authority: ctx.accounts.position.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        liquidity,
+    )?;
+    token_interface::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.escrow.to_account_info(),
+            destination: ctx.accounts.owner.to_account_info(),
+            authority: ctx.accounts.position.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
+    burn_position_nft(
+        &ctx.accounts.token_metadata_program,
+        &ctx.accounts.position_metadata,
+        &ctx.accounts.position_edition,
+        &ctx.accounts.position_mint.to_account_info(),
+        &ctx.accounts.owner_account_nft.to_account_info(),
+        &ctx.accounts.owner.to_account_info(),
+        &ctx.accounts.token_program,
+    )?;
+
+    // 6. 单边提取会改变池子价格，交易后的价格不能超出聚合流动性区间，同步更新预言机
+    // 输入一侧的代币留在池内，只有输出一侧的储备减少
+    let (remove_a, remove_b) = if swap_a { (0, output) } else { (output, 0) };
+    let now = Clock::get()?.unix_timestamp;
+    let pool = &mut ctx.accounts.pool;
+    pool.remove_reserves(remove_a, remove_b)?;
+    let (reserve_a, reserve_b) = pool.reserves();
+    pool.price_range.check(reserve_a, reserve_b)?;
+    pool.oracle.update(reserve_a, reserve_b, now);
+    pool.stats.record_volume(swap_a, amount_swap, swap_output, fee_amount, now);
+    pool.stats.record_withdraw();
+
+    emit!(RangeOrderFilledEvent {
+        pool: ctx.accounts.pool.key(),
+        position_mint,
+        owner: ctx.accounts.owner.key(),
+        swap_a,
+        liquidity,
+        output,
+    });
+    ctx.accounts.pool.unlock();
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseFilledRangeOrder<'info> {
+    #[account(
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+    )]
+    pub amm: Box<Account<'info, Amm>>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.key().as_ref(),
+            pool.mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+        ],
+        bump,
+        has_one = amm,
+        has_one = mint_a,
+        has_one = mint_b,
+        constraint = !pool.flash_loan.active @ TutorialError::FlashLoanActive,
+        constraint = !pool.locked @ TutorialError::PoolLocked,
+        constraint = pool.status.allows_withdrawals() @ TutorialError::PoolStatusRestricted,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// 池子的波动率预言机，动态费率按其中的波动率计算，未提供时按无波动处理
+    #[account(
+        seeds = [
+            pool.key().as_ref(),
+            POOL_ORACLE_SEED,
+        ],
+        bump,
+    )]
+    pub pool_oracle: Option<Box<Account<'info, PoolOracle>>>,
+
+    /// CHECK: Read only authority
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            mint_a.key().as_ref(),
+            mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+            AUTHORITY_SEED,
+        ],
+        bump,
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            position_mint.key().as_ref(),
+            POSITION_SEED,
+        ],
+        bump,
+        has_one = pool,
+        close = owner,
+    )]
+    pub position: Box<Account<'info, Position>>,
+
+    #[account(
+        mut,
+        address = position.position_mint,
+    )]
+    pub position_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [
+            position.key().as_ref(),
+            ESCROW_SEED,
+        ],
+        bump,
+    )]
+    pub escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The holder of the position NFT
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = position_mint,
+        token::authority = owner,
+        constraint = owner_account_nft.amount == 1 @ TutorialError::InvalidMint,
+    )]
+    pub owner_account_nft: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool.amm.as_ref(),
+            mint_a.key().as_ref(),
+            mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+            LIQUIDITY_SEED,
+        ],
+        bump,
+    )]
+    pub mint_liquidity: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_program_a)]
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_program_b)]
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_a,
+    )]
+    pub pool_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_b,
+        associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_b,
+    )]
+    pub pool_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The owner's account receiving the output token
+    #[account(mut)]
+    pub owner_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// 头寸NFT的元数据账户，由元数据程序校验并关闭
+    /// CHECK: Checked and closed by the token metadata program
+    #[account(mut)]
+    pub position_metadata: UncheckedAccount<'info>,
+
+    /// 头寸NFT的主版本账户，由元数据程序校验并关闭
+    /// CHECK: Checked and closed by the token metadata program
+    #[account(mut)]
+    pub position_edition: UncheckedAccount<'info>,
+
+    /// Solana ecosystem accounts
+    pub token_program: Program<'info, Token>,
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
+    pub token_metadata_program: Program<'info, Metadata>,
+}
@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, TransferChecked};
+
+use crate::{
+    errors::TutorialError,
+    events::SwapRelayedEvent,
+    models::swap_options::SwapOptions,
+    utils::check_deadline,
+};
+
+use super::swap_exact_tokens_for_tokens::{swap_exact_tokens_for_tokens_process, SwapExactTokensForTokens};
+
+/// 中继交换：payer作为中继方支付交易费和全部租金，交易者只签名授权代币转移，
+/// 交换完成后从交易者收到的输出代币中向中继方支付小费，min_output_amount为扣除小费后交易者至少保留的数量
+pub fn swap_exact_tokens_for_tokens_relayed<'info>(
+    mut ctx: Context<'_, '_, 'info, 'info, SwapExactTokensForTokens<'info>>,
+    input_mint: Pubkey,
+    input_amount: u64,
+    min_output_amount: u64,
+    deadline: Option<i64>,
+    relayer_tip: u64,
+) -> Result<()> {
+    check_deadline(deadline)?;
+    let swap_a = ctx.accounts.pool.swap_direction(&input_mint)?;
+
+    let min_output_with_tip = min_output_amount.checked_add(relayer_tip).ok_or(TutorialError::MathOverflow)?;
+    swap_exact_tokens_for_tokens_process(&mut ctx, swap_a, input_amount, min_output_with_tip, SwapOptions::default())?;
+
+    if relayer_tip > 0 {
+        let accounts = &ctx.accounts;
+        let (trader_account, mint, token_program) = if swap_a {
+            (&accounts.trader_token_accounts.trader_account_b, &accounts.mint_b, &accounts.token_program_b)
+        } else {
+            (&accounts.trader_token_accounts.trader_account_a, &accounts.mint_a, &accounts.token_program_a)
+        };
+        let relayer_account = accounts.relayer_account.as_ref().ok_or(TutorialError::InvalidRelayerAccount)?;
+        if relayer_account.mint != mint.key() {
+            return err!(TutorialError::InvalidMint);
+        }
+        token_interface::transfer_checked(
+            CpiContext::new(
+                token_program.to_account_info(),
+                TransferChecked {
+                    from: trader_account.to_account_info(),
+                    mint: mint.to_account_info(),
+                    to: relayer_account.to_account_info(),
+                    authority: accounts.trader.to_account_info(),
+                },
+            ),
+            relayer_tip,
+            mint.decimals,
+        )?;
+    }
+
+    emit!(SwapRelayedEvent {
+        pool: ctx.accounts.pool.key(),
+        trader: ctx.accounts.trader.key(),
+        relayer: ctx.accounts.trader_token_accounts.payer.key(),
+        relayer_tip,
+    });
+
+    Ok(())
+}
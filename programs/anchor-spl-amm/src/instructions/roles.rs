@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::TutorialError, events::RoleUpdatedEvent, models::roles::Role, state::Amm};
+
+/// 授予角色，原持有者同时失去该角色；授予所有者即转移所有权
+pub fn grant_role(ctx: Context<UpdateRole>, role: Role, holder: Pubkey) -> Result<()> {
+    let amm = &mut ctx.accounts.amm;
+    amm.set_role(role, holder);
+
+    emit!(RoleUpdatedEvent {
+        amm: amm.key(),
+        role,
+        holder,
+    });
+
+    Ok(())
+}
+
+/// 撤销角色，之后由所有者代行该角色的操作
+pub fn revoke_role(ctx: Context<UpdateRole>, role: Role) -> Result<()> {
+    if role == Role::Owner {
+        return err!(TutorialError::CannotRevokeOwner);
+    }
+    let amm = &mut ctx.accounts.amm;
+    amm.set_role(role, Pubkey::default());
+
+    emit!(RoleUpdatedEvent {
+        amm: amm.key(),
+        role,
+        holder: Pubkey::default(),
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateRole<'info> {
+    #[account(
+        mut,
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+        has_one = admin,
+    )]
+    pub amm: Account<'info, Amm>,
+
+    /// The owner of the AMM
+    pub admin: Signer<'info>,
+}
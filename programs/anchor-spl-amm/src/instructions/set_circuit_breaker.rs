@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::TutorialError,
+    events::CircuitBreakerUpdatedEvent,
+    models::roles::Role,
+    state::{Amm, Pool},
+};
+
+/// 所有者或暂停角色手动控制熔断：设置为0立即恢复交换，设置为未来的时间则暂停交换直到该时间
+pub fn set_circuit_breaker(ctx: Context<SetCircuitBreaker>, circuit_breaker_until: i64) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    pool.circuit_breaker_until = circuit_breaker_until;
+
+    emit!(CircuitBreakerUpdatedEvent {
+        pool: pool.key(),
+        circuit_breaker_until,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetCircuitBreaker<'info> {
+    #[account(
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+        constraint = amm.has_role(Role::Pauser, &admin.key()) @ TutorialError::MissingRole,
+    )]
+    pub amm: Account<'info, Amm>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.key().as_ref(),
+            pool.mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+        ],
+        bump,
+        has_one = amm,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// The owner or the pauser of the AMM
+    pub admin: Signer<'info>,
+}
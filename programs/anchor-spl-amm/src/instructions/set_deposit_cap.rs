@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::TutorialError,
+    events::DepositCapUpdatedEvent,
+    models::roles::Role,
+    state::{Amm, Pool},
+};
+
+/// 池子创建者、AMM所有者或风控管理员设置存款后两侧储备的上限，设置为0时取消限制
+pub fn set_deposit_cap(ctx: Context<SetDepositCap>, max_reserve_a: u64, max_reserve_b: u64) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    pool.max_reserve_a = max_reserve_a;
+    pool.max_reserve_b = max_reserve_b;
+
+    emit!(DepositCapUpdatedEvent {
+        pool: pool.key(),
+        max_reserve_a,
+        max_reserve_b,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetDepositCap<'info> {
+    #[account(
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+    )]
+    pub amm: Account<'info, Amm>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.key().as_ref(),
+            pool.mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+        ],
+        bump,
+        has_one = amm,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// The creator of the pool, or the owner or the risk manager of the AMM
+    #[account(
+        constraint = authority.key() == pool.creator || amm.has_role(Role::RiskManager, &authority.key()) @ TutorialError::NotPoolCreatorOrAdmin,
+    )]
+    pub authority: Signer<'info>,
+}
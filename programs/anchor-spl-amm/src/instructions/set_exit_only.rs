@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::TutorialError, events::ExitOnlyUpdatedEvent, models::roles::Role, state::Amm};
+
+/// 所有者或暂停角色开关仅退出模式：开启后AMM下所有池子拒绝交换和存款，
+/// LP仍可以提取流动性和领取手续费，事故处理期间资金不会被困在池中
+pub fn set_exit_only(ctx: Context<SetExitOnly>, exit_only: bool) -> Result<()> {
+    let amm = &mut ctx.accounts.amm;
+    amm.exit_only = exit_only;
+
+    emit!(ExitOnlyUpdatedEvent {
+        amm: amm.key(),
+        exit_only,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetExitOnly<'info> {
+    #[account(
+        mut,
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+        constraint = amm.has_role(Role::Pauser, &admin.key()) @ TutorialError::MissingRole,
+    )]
+    pub amm: Account<'info, Amm>,
+
+    /// The owner or the pauser of the AMM
+    pub admin: Signer<'info>,
+}
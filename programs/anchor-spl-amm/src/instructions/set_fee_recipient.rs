@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::TutorialError, events::FeeRecipientUpdatedEvent, models::roles::Role, state::Amm};
+
+pub fn set_fee_recipient(ctx: Context<SetFeeRecipient>) -> Result<()> {
+    let amm = &mut ctx.accounts.amm;
+    let old_fee_recipient = amm.fee_recipient;
+    amm.fee_recipient = ctx.accounts.fee_recipient.key();
+
+    emit!(FeeRecipientUpdatedEvent {
+        amm: amm.key(),
+        old_fee_recipient,
+        new_fee_recipient: amm.fee_recipient,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetFeeRecipient<'info> {
+    #[account(
+        mut,
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+        constraint = amm.has_role(Role::FeeManager, &admin.key()) @ TutorialError::MissingRole,
+    )]
+    pub amm: Account<'info, Amm>,
+
+    /// The owner or the fee manager of the AMM
+    pub admin: Signer<'info>,
+
+    /// The new protocol fee recipient
+    /// CHECK: Read only, any account can receive fees
+    pub fee_recipient: AccountInfo<'info>,
+}
@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::*,
+    events::OracleGuardUpdatedEvent,
+    models::{oracle_guard::OracleGuardConfig, roles::Role},
+    state::{Amm, Pool},
+};
+
+pub fn set_oracle_guard(ctx: Context<SetOracleGuard>, oracle_guard: OracleGuardConfig) -> Result<()> {
+    // price_feed 为默认地址时关闭预言机保护
+    if !oracle_guard.is_valid() {
+        return err!(TutorialError::InvalidOracleGuard);
+    }
+
+    let pool = &mut ctx.accounts.pool;
+    pool.oracle_guard = oracle_guard;
+
+    emit!(OracleGuardUpdatedEvent {
+        pool: pool.key(),
+        oracle_guard,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetOracleGuard<'info> {
+    #[account(
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+        constraint = amm.has_role(Role::RiskManager, &admin.key()) @ TutorialError::MissingRole,
+    )]
+    pub amm: Account<'info, Amm>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.key().as_ref(),
+            pool.mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+        ],
+        bump,
+        has_one = amm,
+    )]
+    pub pool: Account<'info, Pool>,
+
+    /// The owner or the risk manager of the AMM
+    pub admin: Signer<'info>,
+}
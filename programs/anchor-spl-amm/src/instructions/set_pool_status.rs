@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::TutorialError,
+    events::PoolStatusUpdatedEvent,
+    models::{pool_status::PoolStatus, roles::Role},
+    state::{Amm, Pool},
+};
+
+/// 所有者或风控管理员设置单个池子的运行状态，只限制出问题的交易对，AMM下其他池子不受影响
+pub fn set_pool_status(ctx: Context<SetPoolStatus>, status: PoolStatus) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    pool.status = status;
+
+    emit!(PoolStatusUpdatedEvent {
+        pool: pool.key(),
+        status,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetPoolStatus<'info> {
+    #[account(
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+        constraint = amm.has_role(Role::RiskManager, &admin.key()) @ TutorialError::MissingRole,
+    )]
+    pub amm: Account<'info, Amm>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.key().as_ref(),
+            pool.mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+        ],
+        bump,
+        has_one = amm,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// The owner or the risk manager of the AMM
+    pub admin: Signer<'info>,
+}
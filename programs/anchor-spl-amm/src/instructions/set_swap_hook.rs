@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::TutorialError,
+    events::SwapHookUpdatedEvent,
+    models::roles::Role,
+    state::{Amm, Pool},
+};
+
+/// 管理员为池子设置交换钩子程序，设置为默认地址时关闭钩子
+pub fn set_swap_hook(ctx: Context<SetSwapHook>, swap_hook: Pubkey) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    pool.swap_hook = swap_hook;
+
+    emit!(SwapHookUpdatedEvent {
+        pool: pool.key(),
+        swap_hook,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetSwapHook<'info> {
+    #[account(
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+        constraint = amm.has_role(Role::RiskManager, &admin.key()) @ TutorialError::MissingRole,
+    )]
+    pub amm: Account<'info, Amm>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.key().as_ref(),
+            pool.mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+        ],
+        bump,
+        has_one = amm,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// The owner or the risk manager of the AMM
+    pub admin: Signer<'info>,
+}
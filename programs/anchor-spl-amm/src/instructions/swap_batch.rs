@@ -0,0 +1,98 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token::Token,
+    token_2022::Token2022,
+    token_interface::TokenAccount,
+};
+
+use crate::{
+    errors::*,
+    models::batch_swap::BatchSwapLeg,
+    state::{Amm, Denylist, Pool},
+    utils::{check_deadline, input_within_balance},
+};
+
+use super::swap_route::{execute_hop, HopAccounts, ROUTE_HOP_ACCOUNTS};
+
+/// 每笔交换在remaining_accounts中占用的账户数量：路由单跳的账户之后再加上交易者的输入账户
+pub const BATCH_LEG_ACCOUNTS: usize = ROUTE_HOP_ACCOUNTS + 1;
+
+/// 批量交换：在一笔交易中原子地执行多个池子的独立交换，供做市商同时再平衡多个池子，
+/// 所有交换的实际输出之和只检查一次滑点
+pub fn swap_batch<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SwapBatch<'info>>,
+    legs: Vec<BatchSwapLeg>,
+    min_total_output: u64,
+    deadline: Option<i64>,
+    clamp_to_balance: bool,
+) -> Result<()> {
+    // 拒绝已过期的交易，避免以过时的价格执行
+    check_deadline(deadline)?;
+    Denylist::check(&ctx.accounts.amm, ctx.accounts.denylist.as_deref().map(|denylist| &**denylist), &ctx.accounts.trader.key())?;
+
+    let remaining = ctx.remaining_accounts;
+    if legs.is_empty() || remaining.len() != legs.len() * BATCH_LEG_ACCOUNTS {
+        return err!(TutorialError::InvalidRoute);
+    }
+
+    let hop_accounts = HopAccounts {
+        program_id: ctx.program_id,
+        amm: &ctx.accounts.amm,
+        trader: ctx.accounts.trader.to_account_info(),
+        token_program: ctx.accounts.token_program.to_account_info(),
+        token_2022_program: ctx.accounts.token_2022_program.to_account_info(),
+    };
+    let mut total_output: u64 = 0;
+    for (leg, accounts) in legs.iter().zip(remaining.chunks_exact(BATCH_LEG_ACCOUNTS)) {
+        let (hop, trader_in) = accounts.split_at(ROUTE_HOP_ACCOUNTS);
+        let trader_in = &trader_in[0];
+
+        // 按参数中的池子和输入代币确定交换方向
+        if hop[0].key() != leg.pool {
+            return err!(TutorialError::InvalidRoute);
+        }
+        let pool = Account::<Pool>::try_from(&hop[0])?;
+        pool.swap_direction(&leg.input_mint)?;
+        let input_mint = leg.input_mint;
+        let trader_account_in = InterfaceAccount::<TokenAccount>::try_from(trader_in)?;
+        if trader_account_in.owner != ctx.accounts.trader.key() || trader_account_in.mint != input_mint {
+            return err!(TutorialError::InvalidRoute);
+        }
+
+        // Prevent swapping assets the trader does not own
+        // 超出余额时只有设置了clamp_to_balance才按余额成交，否则拒绝
+        let amount = input_within_balance(leg.amount, trader_account_in.amount, clamp_to_balance)?;
+        let (output, _, _) = execute_hop(&hop_accounts, hop, trader_in, input_mint, amount)?;
+        total_output = total_output.checked_add(output).ok_or(TutorialError::MathOverflow)?;
+    }
+
+    if total_output < min_total_output {
+        return err!(TutorialError::OutputTooSmall);
+    }
+
+    msg!("Executed {} swaps for {} in total", legs.len(), total_output);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SwapBatch<'info> {
+    #[account(
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+    )]
+    pub amm: Box<Account<'info, Amm>>,
+
+    /// The account doing the swaps
+    pub trader: Signer<'info>,
+
+    /// AMM的地址黑名单，未设置黑名单时可以省略
+    #[account(address = amm.denylist @ TutorialError::DenylistRequired)]
+    pub denylist: Option<Box<Account<'info, Denylist>>>,
+
+    /// Solana ecosystem accounts
+    pub token_program: Program<'info, Token>,
+    pub token_2022_program: Program<'info, Token2022>,
+}
@@ -1,92 +1,188 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{self, Mint, Token, TokenAccount, Transfer},
+    token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked},
 };
 use fixed::types::I64F64;
 
 use crate::{
-    constants::AUTHORITY_SEED,
+    constants::{
+        AUTHORITY_SEED, FEE_EXEMPTION_SEED, IL_COMPENSATION_SEED, POOL_ORACLE_SEED, PROTOCOL_FEE_SEED, SWAP_COMMITMENT_SEED,
+        TRADER_STATS_SEED,
+    },
     errors::*,
-    state::{Amm, Pool},
+    events::{PartialFillEvent, SwapEvent},
+    state::{Amm, Denylist, FeeExemption, Pool, PoolOracle, SwapCommitment, TraderStats},
+    models::curve::CurveCalculator,
     models::fee_strategy::{FeeCalculator, FeeStrategy},
+    models::oracle_guard::execution_price,
     models::price_impact::PriceImpactCalculator,
-    models::volatility::VolatilityTracker,
+    models::quote::SwapQuote,
+    models::swap_options::SwapOptions,
+    utils::{check_deadline, input_within_balance, transfer_fee_at},
 };
 
+use super::swap_hook::{invoke_swap_hook, SwapHookArgs};
+
+/// 部分成交时二分查找的最大次数
+const PARTIAL_FILL_MAX_ITERATIONS: usize = 16;
+
 // 将指令拆分为两部分
-pub fn swap_exact_tokens_for_tokens(
-    ctx: Context<SwapExactTokensForTokens>,
-    swap_a: bool, // true if swapping A for B, false if swapping B for A 
+pub fn swap_exact_tokens_for_tokens<'info>(
+    mut ctx: Context<'_, '_, 'info, 'info, SwapExactTokensForTokens<'info>>,
+    input_mint: Pubkey, // 输入代币，交换方向由它与池子代币的比较得出
     input_amount: u64,
     min_output_amount: u64,
+    deadline: Option<i64>,
+    options: SwapOptions,
 ) -> Result<()> {
+    // 拒绝已过期的交易，避免以过时的价格执行
+    check_deadline(deadline)?;
+    let swap_a = ctx.accounts.pool.swap_direction(&input_mint)?;
+
     // 调用处理函数
-    swap_exact_tokens_for_tokens_process(ctx, swap_a, input_amount, min_output_amount)
+    swap_exact_tokens_for_tokens_process(&mut ctx, swap_a, input_amount, min_output_amount, options)
 }
 
-// 处理交换逻辑
-fn swap_exact_tokens_for_tokens_process(
-    ctx: Context<SwapExactTokensForTokens>,
+// 获取当前适用的交易费率（基点）
+pub(crate) fn swap_fee_rate_bps(
+    amm: &Amm,
+    pool: &Pool,
+    pool_oracle: Option<&PoolOracle>,
     swap_a: bool,
-    input_amount: u64,
-    min_output_amount: u64,
-) -> Result<()> {
-    // 1. Prevent depositing assets the depositor does not own
-    let input = if swap_a && input_amount > ctx.accounts.trader_token_accounts.trader_account_a.amount {
-        ctx.accounts.trader_token_accounts.trader_account_a.amount
-    } else if !swap_a && input_amount > ctx.accounts.trader_token_accounts.trader_account_b.amount {
-        ctx.accounts.trader_token_accounts.trader_account_b.amount
-    } else {
-        input_amount
-    };
+    input: u64,
+    (reserve_in, reserve_out): (u64, u64),
+) -> u16 {
+    if let Some(fee_bps) = amm.fee_config.directional_fee_bps(swap_a) {
+        fee_bps // 该方向设置了费率覆盖
+    } else if amm.fee_config.strategy != FeeStrategy::Fixed {
+        // 获取当前波动率，用于调整费用；未提供池子预言机时按无波动处理
+        let volatility = pool_oracle.map_or(0, |oracle| oracle.volatility_tracker.get_volatility().to_num::<u16>());
 
-    // 2. Apply trading fee, used to compute the output
-    let amm = &ctx.accounts.amm;
-    
-    // 使用动态费用计算器获取当前适用的费率
-    let fee_rate_bps = if amm.fee_config.strategy != FeeStrategy::Fixed {
-        // 获取当前波动率，用于调整费用
-        let volatility = ctx.accounts.pool.volatility_tracker.get_volatility().to_num::<u16>();
-        
         // 基于当前市场状况计算动态费率
         FeeCalculator::get_fee_rate_bps(
-            &amm.fee_config, 
+            &amm.fee_config,
+            swap_a,
             input,
-            if swap_a { ctx.accounts.pool_token_accounts.pool_account_a.amount } else { ctx.accounts.pool_token_accounts.pool_account_b.amount },
-            if swap_a { ctx.accounts.pool_token_accounts.pool_account_b.amount } else { ctx.accounts.pool_token_accounts.pool_account_a.amount },
+            reserve_in,
+            reserve_out,
             Some(volatility)
         )
     } else {
-        amm.fee // 使用默认固定费率
-    };
-    
-    // 应用计算得到的费率
-    let taxed_input = input - input * fee_rate_bps as u64 / 10000;
-    
-    // 3. Compute the output amount and check price impact
-    let pool_a = &ctx.accounts.pool_token_accounts.pool_account_a;
-    let pool_b = &ctx.accounts.pool_token_accounts.pool_account_b;
-    
-    // 计算价格影响（滑点）
-    let price_impact = if swap_a {
-        PriceImpactCalculator::calculate_price_impact(
-            &amm.price_impact_config,
-            input,
-            0, // 暂时设为0，后面会计算实际输出
-            pool_a.amount, 
-            pool_b.amount
-        )
+        pool.fee_rate_bps(amm) // 使用池子费率档位对应的固定费率
+    }
+}
+
+// 池子启用外部预言机保护时，校验扣除手续费后的成交价格与预言机价格的偏差
+pub(crate) fn check_oracle_guard(
+    pool: &Pool,
+    price_feed: Option<&AccountInfo>,
+    swap_a: bool,
+    taxed_input: u64,
+    output: u64,
+    decimals_a: u8,
+    decimals_b: u8,
+) -> Result<()> {
+    if !pool.oracle_guard.is_enabled() {
+        return Ok(());
+    }
+    let price = execution_price(swap_a, taxed_input, output, decimals_a, decimals_b)?;
+    pool.oracle_guard.check(price_feed, price, Clock::get()?.unix_timestamp)
+}
+
+// 计算精确输入交换的报价：费用拆分、曲线输出、价格影响和转账费，供交换和报价指令以及链下适配器共用；
+// 按池子记录的储备定价，时间相关的判断使用传入的clock，链下报价时由调用方提供
+pub fn quote_exact_input(
+    amm: &Amm,
+    pool: &Pool,
+    pool_oracle: Option<&PoolOracle>,
+    (mint_in, mint_out): (&AccountInfo, &AccountInfo),
+    input: u64,
+    fee_discount_bps: u16,
+    clock: &Clock,
+) -> Result<SwapQuote> {
+    // 仅退出模式、池子状态不允许交换和熔断期间拒绝交换
+    amm.check_swaps_enabled()?;
+    pool.check_swaps_enabled()?;
+    pool.check_circuit_breaker(clock.unix_timestamp)?;
+    PoolOracle::check_required(amm, pool_oracle)?;
+
+    // 使用动态费用计算器获取当前适用的费率，再应用交易者的折扣
+    let swap_a = mint_in.key() == pool.mint_a;
+    let (reserve_a, reserve_b) = pool.reserves();
+    let (reserve_in, reserve_out) = if swap_a { (reserve_a, reserve_b) } else { (reserve_b, reserve_a) };
+    let fee_rate_bps = FeeCalculator::apply_discount(
+        swap_fee_rate_bps(amm, pool, pool_oracle, swap_a, input, (reserve_in, reserve_out)),
+        fee_discount_bps,
+    );
+
+    let (fee_amount, protocol_fee, il_compensation_fee, pool_received, taxed_input, output) = if amm.fee_on_output {
+        // 手续费以输出代币收取：输入全部进入池子，从曲线输出中扣除手续费
+        let pool_received = input
+            .checked_sub(transfer_fee_at(mint_in, input, clock.epoch)?)
+            .ok_or(TutorialError::MathOverflow)?;
+        if !PriceImpactCalculator::is_trade_size_acceptable(&amm.price_impact_config, pool_received, reserve_in) {
+            return err!(TutorialError::TradeTooLarge);
+        }
+        let gross_output = CurveCalculator::swap_output_at(&pool.curve_type, clock.unix_timestamp, swap_a, pool_received, reserve_in, reserve_out)?;
+        let fee_amount = (gross_output as u128 * fee_rate_bps as u128 / 10000) as u64;
+        let protocol_fee = FeeCalculator::calculate_protocol_fee(&amm.fee_config, fee_amount);
+        let il_compensation_fee = FeeCalculator::calculate_il_compensation_fee(&amm.fee_config, fee_amount);
+        let output = gross_output.checked_sub(fee_amount).ok_or(TutorialError::MathOverflow)?;
+        (fee_amount, protocol_fee, il_compensation_fee, pool_received, pool_received, output)
     } else {
-        PriceImpactCalculator::calculate_price_impact(
-            &amm.price_impact_config,
-            input,
-            0, // 暂时设为0，后面会计算实际输出
-            pool_b.amount, 
-            pool_a.amount
-        )
+        // 应用计算得到的费率
+        let fee_amount = (input as u128 * fee_rate_bps as u128 / 10000) as u64;
+
+        // 协议分成部分不进入池子，单独累积到协议费用账户
+        let protocol_fee = FeeCalculator::calculate_protocol_fee(&amm.fee_config, fee_amount);
+        // 补偿分成同样不进入池子，转入无常损失补偿金库
+        let il_compensation_fee = FeeCalculator::calculate_il_compensation_fee(&amm.fee_config, fee_amount);
+
+        // Token-2022 转账费：池子实际收到的数量可能少于转入数量
+        let pool_transfer = input
+            .checked_sub(protocol_fee + il_compensation_fee)
+            .ok_or(TutorialError::MathOverflow)?;
+        let pool_received = pool_transfer
+            .checked_sub(transfer_fee_at(mint_in, pool_transfer, clock.epoch)?)
+            .ok_or(TutorialError::MathOverflow)?;
+        let lp_fee = fee_amount
+            .checked_sub(protocol_fee + il_compensation_fee)
+            .ok_or(TutorialError::MathOverflow)?;
+        let taxed_input = pool_received.saturating_sub(lp_fee);
+
+        // 单笔交易不能消耗过多的输入端储备
+        if !PriceImpactCalculator::is_trade_size_acceptable(&amm.price_impact_config, pool_received, reserve_in) {
+            return err!(TutorialError::TradeTooLarge);
+        }
+
+        // 先按定价曲线计算输出金额
+        let output = CurveCalculator::swap_output_at(&pool.curve_type, clock.unix_timestamp, swap_a, taxed_input, reserve_in, reserve_out)?;
+        (fee_amount, protocol_fee, il_compensation_fee, pool_received, taxed_input, output)
     };
-    
+
+    // 交易后的价格不能超出池子的聚合流动性区间，输出端收取的协议分成和补偿分成同样离开池子
+    let output_side_fees = if amm.fee_on_output { protocol_fee + il_compensation_fee } else { 0 };
+    let reserve_in_after = reserve_in.checked_add(pool_received).ok_or(TutorialError::MathOverflow)?;
+    let reserve_out_after = reserve_out
+        .checked_sub(output)
+        .and_then(|reserve| reserve.checked_sub(output_side_fees))
+        .ok_or(TutorialError::MathOverflow)?;
+    if swap_a {
+        pool.price_range.check(reserve_in_after, reserve_out_after)?;
+    } else {
+        pool.price_range.check(reserve_out_after, reserve_in_after)?;
+    }
+
+    // 再用池子实际收到的输入和实际输出计算价格影响
+    let price_impact = PriceImpactCalculator::calculate_price_impact(
+        &amm.price_impact_config,
+        pool_received,
+        output,
+        reserve_in,
+        reserve_out,
+    );
+
     // 检查价格影响是否在可接受范围内
     if !PriceImpactCalculator::is_price_impact_acceptable(
         &amm.price_impact_config,
@@ -94,30 +190,6 @@ fn swap_exact_tokens_for_tokens_process(
     ) {
         return err!(TutorialError::PriceImpactTooHigh);
     }
-    
-    // 计算输出金额
-    let output = if swap_a {
-        I64F64::from_num(taxed_input)
-            .checked_mul(I64F64::from_num(pool_b.amount))
-            .unwrap()
-            .checked_div(
-                I64F64::from_num(pool_a.amount)
-                .checked_add(I64F64::from_num(taxed_input))
-                .unwrap(),
-            )
-            .unwrap()
-    } else {
-        I64F64::from_num(taxed_input)
-            .checked_mul(I64F64::from_num(pool_a.amount))
-            .unwrap()
-            .checked_div(
-                I64F64::from_num(pool_b.amount)
-                .checked_add(I64F64::from_num(taxed_input))
-                .unwrap(),
-            )
-            .unwrap()
-    }
-    .to_num::<u64>();
 
     // 应用滑点调整，确保输出不低于用户设定的最小值
     let adjusted_output = PriceImpactCalculator::adjust_output_for_slippage(
@@ -126,11 +198,6 @@ fn swap_exact_tokens_for_tokens_process(
         price_impact
     );
 
-    // 4. Slip point protection
-    if adjusted_output < min_output_amount {
-        return err!(TutorialError::OutputTooSmall);
-    }
-    
     // 检查交易是否对用户有利
     if !PriceImpactCalculator::is_trade_beneficial(
         I64F64::from_num(input),
@@ -140,100 +207,427 @@ fn swap_exact_tokens_for_tokens_process(
         return err!(TutorialError::TradeNotBeneficial);
     }
 
-    // 5. Compute the invariant before the trade
-    let invariant = pool_a.amount * pool_b.amount;
+    // 交易者实际收到的数量需扣除输出代币的转账费
+    let net_output = adjusted_output - transfer_fee_at(mint_out, adjusted_output, clock.epoch)?;
+
+    Ok(SwapQuote {
+        input_amount: input,
+        fee_amount,
+        protocol_fee,
+        il_compensation_fee,
+        taxed_input,
+        output_amount: adjusted_output,
+        net_output_amount: net_output,
+        price_impact_bps: PriceImpactCalculator::to_bps(price_impact),
+        fee_on_output: amm.fee_on_output,
+        pool_received,
+    })
+}
+
+// 部分成交：二分查找能通过价格影响和交易规模上限的最大输入。每次查找都是一次完整报价，稳定币和加权曲线
+// 的报价开销较大，因此查找次数有上限，成交数量与真实上限的差距不超过输入的 1/2^PARTIAL_FILL_MAX_ITERATIONS；
+// 其他原因的失败与输入规模无关，直接返回
+fn largest_fillable_quote(input: u64, quote: impl Fn(u64) -> Result<SwapQuote>) -> Result<Option<SwapQuote>> {
+    let (mut low, mut high) = (0, input);
+    let mut best = None;
+    for _ in 0..PARTIAL_FILL_MAX_ITERATIONS {
+        if high - low <= 1 {
+            break;
+        }
+        let mid = low + (high - low) / 2;
+        match quote(mid) {
+            Ok(filled) => {
+                low = mid;
+                best = Some(filled);
+            }
+            Err(e) if is_size_limit_error(&e) => high = mid,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(best)
+}
+
+// 是否是因交易规模超出上限而失败
+fn is_size_limit_error(e: &Error) -> bool {
+    *e == TutorialError::PriceImpactTooHigh.into() || *e == TutorialError::TradeTooLarge.into()
+}
+
+// 以输出代币收取手续费时，把协议分成和补偿分成从池子的输出端转到对应账户，fee_side_a表示输出代币为代币A
+pub(crate) fn transfer_output_side_fees<'info>(
+    accounts: &SwapExactTokensForTokens<'info>,
+    fee_side_a: bool,
+    protocol_fee: u64,
+    il_compensation_fee: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let pool_token_accounts = &accounts.pool_token_accounts;
+    let (pool_account, protocol_fee_account, il_compensation_account, mint, token_program) = if fee_side_a {
+        (
+            &pool_token_accounts.pool_account_a,
+            &pool_token_accounts.protocol_fee_account_a,
+            &pool_token_accounts.il_compensation_account_a,
+            &accounts.mint_a,
+            &accounts.token_program_a,
+        )
+    } else {
+        (
+            &pool_token_accounts.pool_account_b,
+            &pool_token_accounts.protocol_fee_account_b,
+            &pool_token_accounts.il_compensation_account_b,
+            &accounts.mint_b,
+            &accounts.token_program_b,
+        )
+    };
+    let transfer = |to: AccountInfo<'info>, amount: u64| {
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                TransferChecked {
+                    from: pool_account.to_account_info(),
+                    mint: mint.to_account_info(),
+                    to,
+                    authority: accounts.pool_authority.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+            mint.decimals,
+        )
+    };
+    if protocol_fee > 0 {
+        transfer(protocol_fee_account.to_account_info(), protocol_fee)?;
+    }
+    if il_compensation_fee > 0 {
+        let vault = il_compensation_account.as_ref().ok_or(TutorialError::MissingCompensationVault)?;
+        transfer(vault.to_account_info(), il_compensation_fee)?;
+    }
+    Ok(())
+}
+
+// 处理交换逻辑
+pub(crate) fn swap_exact_tokens_for_tokens_process<'info>(
+    ctx: &mut Context<'_, '_, 'info, 'info, SwapExactTokensForTokens<'info>>,
+    swap_a: bool,
+    input_amount: u64,
+    min_output_amount: u64,
+    options: SwapOptions,
+) -> Result<()> {
+    Denylist::check(&ctx.accounts.amm, ctx.accounts.denylist.as_deref().map(|denylist| &**denylist), &ctx.accounts.trader.key())?;
+    Pool::lock(&mut ctx.accounts.pool)?;
+
+    // 1. Prevent depositing assets the depositor does not own
+    //    默认拒绝超出余额的输入，只有显式要求时才把输入缩减为交易者的余额
+    let trader_balance = if swap_a {
+        ctx.accounts.trader_token_accounts.trader_account_a.amount
+    } else {
+        ctx.accounts.trader_token_accounts.trader_account_b.amount
+    };
+    let input = input_within_balance(input_amount, trader_balance, options.clamp_to_balance)?;
+
+    // 2. 按与报价相同的路径计算费用和输出
+    let clock = Clock::get()?;
+    let now = clock.unix_timestamp;
+    let fee_discount_bps = ctx.accounts.fee_discount_bps(now);
+    let (reserve_a, reserve_b) = ctx.accounts.pool.reserves();
+    let (reserve_in, reserve_out) = if swap_a {
+        (reserve_a, reserve_b)
+    } else {
+        (reserve_b, reserve_a)
+    };
+    let (mint_in, mint_out) = if swap_a {
+        (&ctx.accounts.mint_a, &ctx.accounts.mint_b)
+    } else {
+        (&ctx.accounts.mint_b, &ctx.accounts.mint_a)
+    };
+    let quote = |amount: u64| {
+        quote_exact_input(
+            &ctx.accounts.amm,
+            &ctx.accounts.pool,
+            ctx.accounts.pool_oracle.as_deref().map(|oracle| &**oracle),
+            (&mint_in.to_account_info(), &mint_out.to_account_info()),
+            amount,
+            fee_discount_bps,
+            &clock,
+        )
+    };
+    // 允许部分成交时，完整输入超出价格影响上限则只成交上限内的最大输入，剩余部分留在交易者账户
+    let (swap_quote, min_output_amount) = match quote(input) {
+        Ok(swap_quote) => (swap_quote, min_output_amount),
+        Err(e) if options.allow_partial_fill && is_size_limit_error(&e) => {
+            let swap_quote = largest_fillable_quote(input, quote)?.ok_or(e)?;
+            // 最小输出按成交比例缩减，保持交易者要求的最差价格不变
+            let min_output_filled = (min_output_amount as u128 * swap_quote.input_amount as u128 / input as u128) as u64;
+            emit!(PartialFillEvent {
+                pool: ctx.accounts.pool.key(),
+                trader: ctx.accounts.trader.key(),
+                requested_amount: input,
+                filled_amount: swap_quote.input_amount,
+            });
+            (swap_quote, min_output_filled)
+        }
+        Err(e) => return Err(e),
+    };
+    let SwapQuote {
+        input_amount: input,
+        fee_amount,
+        protocol_fee,
+        il_compensation_fee,
+        taxed_input,
+        output_amount: adjusted_output,
+        net_output_amount: net_output,
+        price_impact_bps,
+        fee_on_output,
+        ..
+    } = swap_quote;
+    // 手续费以输入代币收取时，协议分成和补偿分成由交易者直接转出，不经过池子
+    let (input_side_fees, output_side_fees) = if fee_on_output {
+        (0, protocol_fee + il_compensation_fee)
+    } else {
+        (protocol_fee + il_compensation_fee, 0)
+    };
+    let pool_transfer = input - input_side_fees;
+
+    // 3. Slip point protection
+    if net_output < min_output_amount {
+        return err!(TutorialError::OutputTooSmall);
+    }
+    // 按价格表达的限价保护，交易者无需随报价刷新重新计算最小输出
+    if !PriceImpactCalculator::is_within_price_limit(input, net_output, options.max_price_x64) {
+        return err!(TutorialError::PriceLimitExceeded);
+    }
+
+    // 外部预言机价格保护
+    check_oracle_guard(
+        &ctx.accounts.pool,
+        ctx.accounts.price_feed.as_deref(),
+        swap_a,
+        taxed_input,
+        adjusted_output,
+        ctx.accounts.mint_a.decimals,
+        ctx.accounts.mint_b.decimals,
+    )?;
 
-    // 6. Swap the tokens
+    // 4. Compute the invariant before the trade
+    let invariant = CurveCalculator::invariant(&ctx.accounts.pool.curve_type, reserve_a, reserve_b)?;
+
+    // 交换前调用池子的钩子，钩子可以拒绝这笔交换
+    let hook_args = SwapHookArgs {
+        pool: ctx.accounts.pool.key(),
+        trader: ctx.accounts.trader.key(),
+        swap_a,
+        input_amount: input,
+        output_amount: net_output,
+        reserve_a,
+        reserve_b,
+    };
+    ctx.accounts.invoke_swap_hook(ctx.remaining_accounts, "before_swap", &hook_args)?;
+
+    // 5. Swap the tokens
     let authority_bump = ctx.bumps.pool_authority;
     let authority_seeds = &[
         &ctx.accounts.pool.amm.to_bytes(),
         &ctx.accounts.mint_a.key().to_bytes(),
         &ctx.accounts.mint_b.key().to_bytes(),
+        &ctx.accounts.pool.fee_tier.to_le_bytes()[..],
         AUTHORITY_SEED,
         &[authority_bump],
     ];
     let signer_seeds = &[&authority_seeds[..]];
     if swap_a {
-        token::transfer(
+        token_interface::transfer_checked(
             CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
+                ctx.accounts.token_program_a.to_account_info(),
+                TransferChecked {
                     from: ctx.accounts.trader_token_accounts.trader_account_a.to_account_info(),
+                    mint: ctx.accounts.mint_a.to_account_info(),
                     to: ctx.accounts.pool_token_accounts.pool_account_a.to_account_info(),
                     authority: ctx.accounts.trader.to_account_info(),
                 },
-                
-            ), input,
+            ),
+            pool_transfer,
+            ctx.accounts.mint_a.decimals,
         )?;
-        token::transfer(
+        if protocol_fee > 0 && !fee_on_output {
+            token_interface::transfer_checked(
+                CpiContext::new(
+                    ctx.accounts.token_program_a.to_account_info(),
+                    TransferChecked {
+                        from: ctx.accounts.trader_token_accounts.trader_account_a.to_account_info(),
+                        mint: ctx.accounts.mint_a.to_account_info(),
+                        to: ctx.accounts.pool_token_accounts.protocol_fee_account_a.to_account_info(),
+                        authority: ctx.accounts.trader.to_account_info(),
+                    },
+                ),
+                protocol_fee,
+                ctx.accounts.mint_a.decimals,
+            )?;
+        }
+        token_interface::transfer_checked(
             CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
+                ctx.accounts.token_program_b.to_account_info(),
+                TransferChecked {
                     from: ctx.accounts.pool_token_accounts.pool_account_b.to_account_info(),
+                    mint: ctx.accounts.mint_b.to_account_info(),
                     to: ctx.accounts.trader_token_accounts.trader_account_b.to_account_info(),
                     authority: ctx.accounts.pool_authority.to_account_info(),
                 },
                 signer_seeds,
             ),
             adjusted_output,
+            ctx.accounts.mint_b.decimals,
         )?;
     } else {
-        token::transfer(
+        token_interface::transfer_checked(
             CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
+                ctx.accounts.token_program_b.to_account_info(),
+                TransferChecked {
                     from: ctx.accounts.trader_token_accounts.trader_account_b.to_account_info(),
+                    mint: ctx.accounts.mint_b.to_account_info(),
                     to: ctx.accounts.pool_token_accounts.pool_account_b.to_account_info(),
                     authority: ctx.accounts.trader.to_account_info(),
                 },
             ),
-            input,
+            pool_transfer,
+            ctx.accounts.mint_b.decimals,
         )?;
-        token::transfer(
+        if protocol_fee > 0 && !fee_on_output {
+            token_interface::transfer_checked(
+                CpiContext::new(
+                    ctx.accounts.token_program_b.to_account_info(),
+                    TransferChecked {
+                        from: ctx.accounts.trader_token_accounts.trader_account_b.to_account_info(),
+                        mint: ctx.accounts.mint_b.to_account_info(),
+                        to: ctx.accounts.pool_token_accounts.protocol_fee_account_b.to_account_info(),
+                        authority: ctx.accounts.trader.to_account_info(),
+                    },
+                ),
+                protocol_fee,
+                ctx.accounts.mint_b.decimals,
+            )?;
+        }
+        token_interface::transfer_checked(
             CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
+                ctx.accounts.token_program_a.to_account_info(),
+                TransferChecked {
                     from: ctx.accounts.pool_token_accounts.pool_account_a.to_account_info(),
+                    mint: ctx.accounts.mint_a.to_account_info(),
                     to: ctx.accounts.trader_token_accounts.trader_account_a.to_account_info(),
                     authority: ctx.accounts.pool_authority.to_account_info(),
                 },
                 signer_seeds,
             ),
             adjusted_output,
+            ctx.accounts.mint_a.decimals,
+        )?;
+    }
+
+    if il_compensation_fee > 0 && !fee_on_output {
+        let (trader_account, mint, token_program, vault) = if swap_a {
+            (
+                &ctx.accounts.trader_token_accounts.trader_account_a,
+                &ctx.accounts.mint_a,
+                &ctx.accounts.token_program_a,
+                &ctx.accounts.pool_token_accounts.il_compensation_account_a,
+            )
+        } else {
+            (
+                &ctx.accounts.trader_token_accounts.trader_account_b,
+                &ctx.accounts.mint_b,
+                &ctx.accounts.token_program_b,
+                &ctx.accounts.pool_token_accounts.il_compensation_account_b,
+            )
+        };
+        let vault = vault.as_ref().ok_or(TutorialError::MissingCompensationVault)?;
+        token_interface::transfer_checked(
+            CpiContext::new(
+                token_program.to_account_info(),
+                TransferChecked {
+                    from: trader_account.to_account_info(),
+                    mint: mint.to_account_info(),
+                    to: vault.to_account_info(),
+                    authority: ctx.accounts.trader.to_account_info(),
+                },
+            ),
+            il_compensation_fee,
+            mint.decimals,
         )?;
     }
 
+    // 手续费以输出代币收取时，协议分成和补偿分成从池子的输出端转出
+    if output_side_fees > 0 {
+        transfer_output_side_fees(ctx.accounts, !swap_a, protocol_fee, il_compensation_fee, signer_seeds)?;
+    }
+
     msg!(
-        "Traded {} tokens ({} after fees) for {} (Price impact: {} bps)",
+        "Traded {} tokens ({} after fees, {} protocol fee) for {} (Price impact: {} bps)",
         input,
         taxed_input,
-        adjusted_output,
-        price_impact
+        protocol_fee,
+        net_output,
+        price_impact_bps
     );
 
-    // 7. Verify the invariant still holds
+    // 6. Verify the invariant still holds, including the LP fee left in the pool
     // We tolerate if the new invariant is higher because it means a rounding error for LPs
-    ctx.accounts.pool_token_accounts.pool_account_a.reload()?;
-    ctx.accounts.pool_token_accounts.pool_account_b.reload()?;
-    if invariant > ctx.accounts.pool_token_accounts.pool_account_a.amount * ctx.accounts.pool_token_accounts.pool_account_b.amount {
-        return err!(TutorialError::InvariantViolated);
-    }
+    //    交易后的储备按实际转入转出的数量计算，不读取代币账户余额，直接转入池子的代币不会并入储备
+    let (reserve_in_after, reserve_out_after) = swap_quote.reserves_after(reserve_in, reserve_out);
+    let (reserve_a_after, reserve_b_after) =
+        if swap_a { (reserve_in_after, reserve_out_after) } else { (reserve_out_after, reserve_in_after) };
+    let fee_excluded = swap_quote.fee_excluded_reserves(reserve_in, reserve_out);
+    let invariant_delta = CurveCalculator::check_invariant_growth(
+        &ctx.accounts.pool.curve_type,
+        invariant,
+        if swap_a { fee_excluded } else { (fee_excluded.1, fee_excluded.0) },
+        (reserve_a_after, reserve_b_after),
+    )?;
+    ctx.accounts.invoke_swap_hook(
+        ctx.remaining_accounts,
+        "after_swap",
+        &SwapHookArgs {
+            reserve_a: reserve_a_after,
+            reserve_b: reserve_b_after,
+            ..hook_args
+        },
+    )?;
     
-    // 8. 更新波动率追踪器
+    emit!(SwapEvent {
+        pool: ctx.accounts.pool.key(),
+        trader: ctx.accounts.trader.key(),
+        swap_a,
+        input_amount: input,
+        output_amount: net_output,
+        fee_amount,
+        protocol_fee,
+        price_impact_bps,
+        reserve_a: reserve_a_after,
+        reserve_b: reserve_b_after,
+        invariant_delta,
+    });
+
+    // 7. 更新波动率追踪器
     let current_price = if swap_a {
-        I64F64::from_num(ctx.accounts.pool_token_accounts.pool_account_a.amount) / I64F64::from_num(ctx.accounts.pool_token_accounts.pool_account_b.amount)
+        ctx.accounts.pool.normalized_price(reserve_a_after, reserve_b_after, true)
     } else {
-        I64F64::from_num(ctx.accounts.pool_token_accounts.pool_account_b.amount) / I64F64::from_num(ctx.accounts.pool_token_accounts.pool_account_a.amount)
+        ctx.accounts.pool.normalized_price(reserve_b_after, reserve_a_after, false)
     };
     
+    // 按代币B计量记录交易者的成交量
+    if let Some(trader_stats) = ctx.accounts.trader_stats.as_mut() {
+        trader_stats.record(if swap_a { adjusted_output } else { input }, now);
+    }
+
     // 更新价格样本和计算波动率
-    let mut pool = &mut ctx.accounts.pool;
-    pool.volatility_tracker.update_price_sample(
-        current_price,
-        Clock::get()?.unix_timestamp,
-        &ctx.accounts.amm.volatility_config
-    );
-    
+    let pool = &mut ctx.accounts.pool;
+    pool.stats.record_swap(swap_a, input, adjusted_output, fee_amount, now);
+    if let Some(pool_oracle) = ctx.accounts.pool_oracle.as_mut() {
+        PoolOracle::record_price(pool_oracle, current_price, now, &ctx.accounts.amm.volatility_config)?;
+    }
+
+    // 8. 更新TWAP预言机
+    pool.oracle.update(reserve_a_after, reserve_b_after, now);
+    pool.swap_reserves(swap_a, swap_quote.pool_received, adjusted_output + output_side_fees)?;
+    pool.unlock();
+
     Ok(())
 }
 
@@ -254,20 +648,35 @@ pub struct SwapExactTokensForTokens<'info> {
             pool.amm.as_ref(),
             pool.mint_a.key().as_ref(),
             pool.mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
         ],
         bump,
         has_one = amm,
         has_one = mint_a,
         has_one = mint_b,
+        constraint = !pool.flash_loan.active @ TutorialError::FlashLoanActive,
+        constraint = !pool.locked @ TutorialError::PoolLocked,
     )]
     pub pool: Box<Account<'info, Pool>>,
 
+    /// 池子的波动率预言机，启用波动率跟踪或动态费率时必须提供
+    #[account(
+        mut,
+        seeds = [
+            pool.key().as_ref(),
+            POOL_ORACLE_SEED,
+        ],
+        bump,
+    )]
+    pub pool_oracle: Option<Box<Account<'info, PoolOracle>>>,
+
     /// CHECK: Read only authority
     #[account(
         seeds = [
             pool.amm.as_ref(),
             mint_a.key().as_ref(),
             mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
             AUTHORITY_SEED,
         ],
         bump,
@@ -277,21 +686,125 @@ pub struct SwapExactTokensForTokens<'info> {
     /// The account doing the swap
     pub trader: Signer<'info>,
 
-    pub mint_a: Box<Account<'info, Mint>>,
+    /// AMM的地址黑名单，未设置黑名单时可以省略
+    #[account(address = amm.denylist @ TutorialError::DenylistRequired)]
+    pub denylist: Option<Box<Account<'info, Denylist>>>,
 
-    pub mint_b: Box<Account<'info, Mint>>,
+    #[account(mint::token_program = token_program_a)]
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
 
-    // 分离池账户和交易者账户到单独的结构体中
+    #[account(mint::token_program = token_program_b)]
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
+
+    // 分离池账户和交易者账户到单独的结构体中，其中用于约束的账户必须与上面已校验的账户一致
+    #[account(
+        constraint = pool_token_accounts.mint_a.key() == mint_a.key() @ TutorialError::NestedAccountMismatch,
+        constraint = pool_token_accounts.mint_b.key() == mint_b.key() @ TutorialError::NestedAccountMismatch,
+        constraint = pool_token_accounts.pool_authority.key() == pool_authority.key() @ TutorialError::NestedAccountMismatch,
+        constraint = pool_token_accounts.token_program_a.key() == token_program_a.key() @ TutorialError::NestedAccountMismatch,
+        constraint = pool_token_accounts.token_program_b.key() == token_program_b.key() @ TutorialError::NestedAccountMismatch,
+    )]
     pub pool_token_accounts: PoolTokenAccounts<'info>,
     
+    #[account(
+        constraint = trader_token_accounts.mint_a.key() == mint_a.key() @ TutorialError::NestedAccountMismatch,
+        constraint = trader_token_accounts.mint_b.key() == mint_b.key() @ TutorialError::NestedAccountMismatch,
+        constraint = trader_token_accounts.trader.key() == trader.key() @ TutorialError::NestedAccountMismatch,
+        constraint = trader_token_accounts.token_program_a.key() == token_program_a.key() @ TutorialError::NestedAccountMismatch,
+        constraint = trader_token_accounts.token_program_b.key() == token_program_b.key() @ TutorialError::NestedAccountMismatch,
+    )]
     pub trader_token_accounts: TraderTokenAccounts<'info>,
 
+    /// 池子配置的Pyth价格账户，未启用预言机保护时可以省略
+    /// CHECK: 地址和数据在预言机保护检查中校验
+    pub price_feed: Option<UncheckedAccount<'info>>,
+
+    /// 交易者的成交量统计账户，提供时按30天成交量享受手续费折扣
+    #[account(
+        mut,
+        seeds = [
+            pool.key().as_ref(),
+            trader.key().as_ref(),
+            TRADER_STATS_SEED,
+        ],
+        bump,
+    )]
+    pub trader_stats: Option<Box<Account<'info, TraderStats>>>,
+
+    /// 池子配置的交换钩子程序，未配置钩子时可以省略，钩子需要的其他账户通过remaining_accounts传入
+    /// CHECK: 地址与池子配置的钩子程序比对
+    pub hook_program: Option<UncheckedAccount<'info>>,
+
+    /// 管理员为交易者设置的费用豁免，没有豁免时可以省略
+    #[account(
+        seeds = [
+            amm.key().as_ref(),
+            trader.key().as_ref(),
+            FEE_EXEMPTION_SEED,
+        ],
+        bump,
+    )]
+    pub fee_exemption: Option<Box<Account<'info, FeeExemption>>>,
+
+    /// 中继交换时接收小费的输出代币账户，必须属于支付租金的中继方
+    #[account(
+        mut,
+        constraint = relayer_account.owner == trader_token_accounts.payer.key() @ TutorialError::InvalidRelayerAccount,
+    )]
+    pub relayer_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// 交易者的交换承诺，只有揭示交换时需要提供
+    #[account(
+        mut,
+        seeds = [
+            pool.key().as_ref(),
+            trader.key().as_ref(),
+            SWAP_COMMITMENT_SEED,
+        ],
+        bump,
+    )]
+    pub swap_commitment: Option<Box<Account<'info, SwapCommitment>>>,
+
+    /// 前端托管方接收平台费的输入代币账户，只有带平台费的交换需要提供
+    #[account(mut)]
+    pub host_fee_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
     /// Solana ecosystem accounts
-    pub token_program: Program<'info, Token>,
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
+impl<'info> SwapExactTokensForTokens<'info> {
+    /// 以交易详情调用池子配置的交换钩子
+    pub(crate) fn invoke_swap_hook(
+        &self,
+        remaining_accounts: &[AccountInfo<'info>],
+        method: &str,
+        args: &SwapHookArgs,
+    ) -> Result<()> {
+        invoke_swap_hook(
+            &self.pool,
+            self.hook_program.as_deref(),
+            &self.trader.to_account_info(),
+            remaining_accounts,
+            method,
+            args,
+        )
+    }
+
+    /// 交易者可享受的手续费折扣（基点）：成交量折扣与费用豁免中较大的一个
+    pub(crate) fn fee_discount_bps(&self, timestamp: i64) -> u16 {
+        let volume_discount_bps = self
+            .trader_stats
+            .as_ref()
+            .map_or(0, |stats| FeeCalculator::volume_discount_bps(stats.discount_volume(timestamp)));
+        let exemption_discount_bps = self.fee_exemption.as_ref().map_or(0, |exemption| exemption.discount_bps);
+        volume_discount_bps.max(exemption_discount_bps)
+    }
+}
+
 // 池代币账户
 #[derive(Accounts)]
 pub struct PoolTokenAccounts<'info> {
@@ -299,15 +812,66 @@ pub struct PoolTokenAccounts<'info> {
         mut,
         associated_token::mint = mint_a,
         associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_a,
     )]
-    pub pool_account_a: Box<Account<'info, TokenAccount>>,
+    pub pool_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
         associated_token::mint = mint_b,
         associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_b,
     )]
-    pub pool_account_b: Box<Account<'info, TokenAccount>>,
+    pub pool_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool_authority.key().as_ref(),
+            mint_a.key().as_ref(),
+            PROTOCOL_FEE_SEED,
+        ],
+        bump,
+        token::token_program = token_program_a,
+    )]
+    pub protocol_fee_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool_authority.key().as_ref(),
+            mint_b.key().as_ref(),
+            PROTOCOL_FEE_SEED,
+        ],
+        bump,
+        token::token_program = token_program_b,
+    )]
+    pub protocol_fee_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// 无常损失补偿金库，补偿分成为0时可以省略
+    #[account(
+        mut,
+        seeds = [
+            pool_authority.key().as_ref(),
+            mint_a.key().as_ref(),
+            IL_COMPENSATION_SEED,
+        ],
+        bump,
+        token::token_program = token_program_a,
+    )]
+    pub il_compensation_account_a: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool_authority.key().as_ref(),
+            mint_b.key().as_ref(),
+            IL_COMPENSATION_SEED,
+        ],
+        bump,
+        token::token_program = token_program_b,
+    )]
+    pub il_compensation_account_b: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
     
     /// CHECK: Used in constraints
     pub mint_a: AccountInfo<'info>,
@@ -317,6 +881,9 @@ pub struct PoolTokenAccounts<'info> {
     
     /// CHECK: Used in constraints
     pub pool_authority: AccountInfo<'info>,
+
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
 }
 
 // 交易者代币账户
@@ -327,16 +894,18 @@ pub struct TraderTokenAccounts<'info> {
         payer = payer,
         associated_token::mint = mint_a,
         associated_token::authority = trader,
+        associated_token::token_program = token_program_a,
     )]
-    pub trader_account_a: Box<Account<'info, TokenAccount>>,
+    pub trader_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         init_if_needed,
         payer = payer,
         associated_token::mint = mint_b,
         associated_token::authority = trader,
+        associated_token::token_program = token_program_b,
     )]
-    pub trader_account_b: Box<Account<'info, TokenAccount>>,
+    pub trader_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
     
     /// CHECK: Used in constraints
     pub mint_a: AccountInfo<'info>,
@@ -352,7 +921,8 @@ pub struct TraderTokenAccounts<'info> {
     pub payer: Signer<'info>,
     
     // 必须添加这些程序账户以实现init_if_needed约束
-    pub token_program: Program<'info, Token>,
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
\ No newline at end of file
@@ -0,0 +1,64 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{hash::hash, instruction::Instruction, program::invoke},
+};
+
+use crate::{errors::TutorialError, state::Pool};
+
+/// 交换钩子收到的交易详情，before_swap中的储备为交换前，after_swap中为交换后
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct SwapHookArgs {
+    pub pool: Pubkey,
+    pub trader: Pubkey,
+    /// true表示用代币A换代币B
+    pub swap_a: bool,
+    pub input_amount: u64,
+    pub output_amount: u64,
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+}
+
+/// 调用池子配置的交换钩子程序，未配置钩子时不做任何事
+///
+/// 指令数据与Anchor程序兼容：8字节方法discriminator（"global:<method>"的哈希前缀）加上序列化的参数，
+/// 钩子可以返回错误拒绝交换；账户依次为池子、交易者和remaining_accounts，均不带签名权限
+pub(crate) fn invoke_swap_hook<'info>(
+    pool: &Account<'info, Pool>,
+    hook_program: Option<&AccountInfo<'info>>,
+    trader: &AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    method: &str,
+    args: &SwapHookArgs,
+) -> Result<()> {
+    if pool.swap_hook == Pubkey::default() {
+        return Ok(());
+    }
+    let hook_program = hook_program.ok_or(TutorialError::SwapHookRequired)?;
+    if hook_program.key() != pool.swap_hook {
+        return err!(TutorialError::SwapHookRequired);
+    }
+
+    let mut data = hash(format!("global:{}", method).as_bytes()).to_bytes()[..8].to_vec();
+    args.serialize(&mut data)?;
+
+    let mut accounts = vec![AccountMeta::new_readonly(pool.key(), false), AccountMeta::new_readonly(trader.key(), false)];
+    accounts.extend(remaining_accounts.iter().map(|account| AccountMeta {
+        pubkey: account.key(),
+        is_signer: false,
+        is_writable: account.is_writable,
+    }));
+    let mut account_infos = vec![pool.to_account_info(), trader.clone()];
+    account_infos.extend_from_slice(remaining_accounts);
+    account_infos.push(hook_program.clone());
+
+    invoke(
+        &Instruction {
+            program_id: hook_program.key(),
+            accounts,
+            data,
+        },
+        &account_infos,
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,371 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id,
+    token::Token,
+    token_2022::Token2022,
+    token_interface::{self, Mint, TokenAccount, TransferChecked},
+};
+
+use crate::{
+    constants::{AUTHORITY_SEED, POOL_ORACLE_SEED, PROTOCOL_FEE_SEED},
+    errors::*,
+    events::SwapEvent,
+    state::{Amm, Denylist, Pool, PoolOracle},
+    models::curve::CurveCalculator,
+    models::fee_strategy::FeeCalculator,
+    models::price_impact::PriceImpactCalculator,
+    utils::{check_deadline, get_transfer_fee, input_within_balance},
+};
+
+use super::swap_exact_tokens_for_tokens::{check_oracle_guard, swap_fee_rate_bps};
+
+/// 每一跳在remaining_accounts中占用的账户数量：
+/// pool, pool_authority, mint_in, mint_out, pool_account_in, pool_account_out,
+/// protocol_fee_account_in, trader_account_out, pool_oracle（传入程序ID表示不提供）
+pub const ROUTE_HOP_ACCOUNTS: usize = 9;
+
+pub fn swap_route<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SwapRoute<'info>>,
+    input_amount: u64,
+    min_output_amount: u64,
+    deadline: Option<i64>,
+    clamp_to_balance: bool,
+) -> Result<()> {
+    // 拒绝已过期的交易，避免以过时的价格执行
+    check_deadline(deadline)?;
+    Denylist::check(&ctx.accounts.amm, ctx.accounts.denylist.as_deref().map(|denylist| &**denylist), &ctx.accounts.trader.key())?;
+
+    let remaining = ctx.remaining_accounts;
+    let hops = remaining.chunks_exact(ROUTE_HOP_ACCOUNTS);
+    if remaining.is_empty() || !hops.remainder().is_empty() {
+        return err!(TutorialError::InvalidRoute);
+    }
+
+    // Prevent swapping assets the trader does not own
+    // 超出余额时只有设置了clamp_to_balance才按余额成交，否则拒绝
+    let mut amount = input_within_balance(input_amount, ctx.accounts.trader_source.amount, clamp_to_balance)?;
+    let mut trader_in = ctx.accounts.trader_source.to_account_info();
+    let mut expected_mint = ctx.accounts.trader_source.mint;
+
+    // 依次执行每一跳，上一跳的输出作为下一跳的输入
+    let hop_accounts = HopAccounts {
+        program_id: ctx.program_id,
+        amm: &ctx.accounts.amm,
+        trader: ctx.accounts.trader.to_account_info(),
+        token_program: ctx.accounts.token_program.to_account_info(),
+        token_2022_program: ctx.accounts.token_2022_program.to_account_info(),
+    };
+    for hop in hops {
+        let (output, trader_out, output_mint) = execute_hop(&hop_accounts, hop, &trader_in, expected_mint, amount)?;
+        amount = output;
+        trader_in = trader_out;
+        expected_mint = output_mint;
+    }
+
+    // 只在最后一跳检查一次滑点
+    if amount < min_output_amount {
+        return err!(TutorialError::OutputTooSmall);
+    }
+
+    msg!("Routed {} tokens through {} pools for {}", input_amount, remaining.len() / ROUTE_HOP_ACCOUNTS, amount);
+
+    Ok(())
+}
+
+/// 执行单跳交换需要的公共账户，路由交换和批量交换共用
+pub(crate) struct HopAccounts<'a, 'info> {
+    pub program_id: &'a Pubkey,
+    pub amm: &'a Account<'info, Amm>,
+    pub trader: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub token_2022_program: AccountInfo<'info>,
+}
+
+impl<'a, 'info> HopAccounts<'a, 'info> {
+    // 根据代币账户的所有者选择对应的代币程序
+    fn token_program_for(&self, mint: &AccountInfo<'info>) -> AccountInfo<'info> {
+        if *mint.owner == Token2022::id() {
+            self.token_2022_program.clone()
+        } else {
+            self.token_program.clone()
+        }
+    }
+}
+
+// 执行单跳交换，返回输出数量、交易者接收账户和输出代币
+pub(crate) fn execute_hop<'info>(
+    ctx: &HopAccounts<'_, 'info>,
+    hop: &'info [AccountInfo<'info>],
+    trader_in: &AccountInfo<'info>,
+    input_mint: Pubkey,
+    input: u64,
+) -> Result<(u64, AccountInfo<'info>, Pubkey)> {
+    let amm = ctx.amm;
+    // 路由每跳只携带输入端的协议费用账户，无法以输出代币收取手续费
+    if amm.fee_on_output {
+        return err!(TutorialError::FeeOnOutputNotSupported);
+    }
+
+    // 1. 校验池子及其权限账户
+    let mut pool = Box::new(Account::<Pool>::try_from(&hop[0])?);
+    if pool.amm != amm.key() {
+        return err!(TutorialError::InvalidRoute);
+    }
+    if pool.flash_loan.active {
+        return err!(TutorialError::FlashLoanActive);
+    }
+    amm.check_swaps_enabled()?;
+    pool.check_swaps_enabled()?;
+    pool.check_circuit_breaker(Clock::get()?.unix_timestamp)?;
+    // 路由每跳不携带钩子账户，配置了交换钩子的池子不能通过路由交换
+    if pool.swap_hook != Pubkey::default() {
+        return err!(TutorialError::SwapHookRequired);
+    }
+    Pool::lock(&mut pool)?;
+    let (pool_authority, authority_bump) = Pubkey::find_program_address(
+        &[
+            pool.amm.as_ref(),
+            pool.mint_a.as_ref(),
+            pool.mint_b.as_ref(),
+            &pool.fee_tier.to_le_bytes()[..],
+            AUTHORITY_SEED,
+        ],
+        ctx.program_id,
+    );
+    if hop[1].key() != pool_authority {
+        return err!(TutorialError::InvalidRoute);
+    }
+    let mut pool_oracle = if hop[8].key() == *ctx.program_id {
+        None
+    } else {
+        let (pool_oracle_key, _) = Pubkey::find_program_address(&[pool.key().as_ref(), POOL_ORACLE_SEED], ctx.program_id);
+        if hop[8].key() != pool_oracle_key {
+            return err!(TutorialError::InvalidRoute);
+        }
+        Some(Box::new(Account::<PoolOracle>::try_from(&hop[8])?))
+    };
+    PoolOracle::check_required(amm, pool_oracle.as_deref().map(|oracle| &**oracle))?;
+
+    // 2. 根据输入代币确定交换方向
+    let swap_a = if input_mint == pool.mint_a {
+        true
+    } else if input_mint == pool.mint_b {
+        false
+    } else {
+        return err!(TutorialError::InvalidRoute);
+    };
+    let output_mint = if swap_a { pool.mint_b } else { pool.mint_a };
+
+    // 3. 校验代币、池代币账户、协议费用账户和交易者接收账户
+    let mint_in = InterfaceAccount::<Mint>::try_from(&hop[2])?;
+    let mint_out = InterfaceAccount::<Mint>::try_from(&hop[3])?;
+    if mint_in.key() != input_mint || mint_out.key() != output_mint {
+        return err!(TutorialError::InvalidRoute);
+    }
+    let token_program_in = ctx.token_program_for(&hop[2]);
+    let token_program_out = ctx.token_program_for(&hop[3]);
+
+    let pool_account_in = InterfaceAccount::<TokenAccount>::try_from(&hop[4])?;
+    let pool_account_out = InterfaceAccount::<TokenAccount>::try_from(&hop[5])?;
+    let trader_account_out = InterfaceAccount::<TokenAccount>::try_from(&hop[7])?;
+    let (protocol_fee_account, _) = Pubkey::find_program_address(
+        &[pool_authority.as_ref(), input_mint.as_ref(), PROTOCOL_FEE_SEED],
+        ctx.program_id,
+    );
+    if pool_account_in.key()
+        != get_associated_token_address_with_program_id(&pool_authority, &input_mint, token_program_in.key)
+        || pool_account_out.key()
+            != get_associated_token_address_with_program_id(&pool_authority, &output_mint, token_program_out.key)
+        || hop[6].key() != protocol_fee_account
+        || trader_account_out.owner != ctx.trader.key()
+        || trader_account_out.mint != output_mint
+    {
+        return err!(TutorialError::InvalidRoute);
+    }
+
+    // 4. 计算费用和输出
+    let (reserve_a, reserve_b) = pool.reserves();
+    let (reserve_in, reserve_out) = if swap_a { (reserve_a, reserve_b) } else { (reserve_b, reserve_a) };
+    let fee_rate_bps = swap_fee_rate_bps(
+        amm,
+        &pool,
+        pool_oracle.as_deref().map(|oracle| &**oracle),
+        swap_a,
+        input,
+        (reserve_in, reserve_out),
+    );
+    let fee_amount = (input as u128 * fee_rate_bps as u128 / 10000) as u64;
+    // 路由每跳不携带补偿金库账户，补偿分成留在池子里归LP所有
+    let protocol_fee = FeeCalculator::calculate_protocol_fee(&amm.fee_config, fee_amount);
+
+    // Token-2022 转账费：按池子实际收到的数量计算输出
+    let pool_transfer = input.checked_sub(protocol_fee).ok_or(TutorialError::MathOverflow)?;
+    let pool_received = pool_transfer
+        .checked_sub(get_transfer_fee(&hop[2], pool_transfer)?)
+        .ok_or(TutorialError::MathOverflow)?;
+    let lp_fee = fee_amount.checked_sub(protocol_fee).ok_or(TutorialError::MathOverflow)?;
+    let taxed_input = pool_received.saturating_sub(lp_fee);
+
+    if !PriceImpactCalculator::is_trade_size_acceptable(&amm.price_impact_config, pool_received, reserve_in) {
+        return err!(TutorialError::TradeTooLarge);
+    }
+
+    let output = CurveCalculator::swap_output(&pool.curve_type, swap_a, taxed_input, reserve_in, reserve_out)?;
+    let price_impact = PriceImpactCalculator::calculate_price_impact(
+        &amm.price_impact_config,
+        pool_received,
+        output,
+        reserve_in,
+        reserve_out,
+    );
+    if !PriceImpactCalculator::is_price_impact_acceptable(
+        &amm.price_impact_config,
+        price_impact
+    ) {
+        return err!(TutorialError::PriceImpactTooHigh);
+    }
+    let output = PriceImpactCalculator::adjust_output_for_slippage(
+        &amm.price_impact_config,
+        output,
+        price_impact
+    );
+
+    // 路由每跳不携带价格账户，启用了预言机保护的池子不能通过路由交换
+    let (decimals_a, decimals_b) = if swap_a {
+        (mint_in.decimals, mint_out.decimals)
+    } else {
+        (mint_out.decimals, mint_in.decimals)
+    };
+    check_oracle_guard(&pool, None, swap_a, taxed_input, output, decimals_a, decimals_b)?;
+
+    // 5. 执行转账
+    let authority_seeds = &[
+        &pool.amm.to_bytes(),
+        &pool.mint_a.to_bytes(),
+        &pool.mint_b.to_bytes(),
+        &pool.fee_tier.to_le_bytes()[..],
+        AUTHORITY_SEED,
+        &[authority_bump],
+    ];
+    let signer_seeds = &[&authority_seeds[..]];
+    token_interface::transfer_checked(
+        CpiContext::new(
+            token_program_in.clone(),
+            TransferChecked {
+                from: trader_in.clone(),
+                mint: hop[2].clone(),
+                to: hop[4].clone(),
+                authority: ctx.trader.clone(),
+            },
+        ),
+        pool_transfer,
+        mint_in.decimals,
+    )?;
+    if protocol_fee > 0 {
+        token_interface::transfer_checked(
+            CpiContext::new(
+                token_program_in,
+                TransferChecked {
+                    from: trader_in.clone(),
+                    mint: hop[2].clone(),
+                    to: hop[6].clone(),
+                    authority: ctx.trader.clone(),
+                },
+            ),
+            protocol_fee,
+            mint_in.decimals,
+        )?;
+    }
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            token_program_out,
+            TransferChecked {
+                from: hop[5].clone(),
+                mint: hop[3].clone(),
+                to: hop[7].clone(),
+                authority: hop[1].clone(),
+            },
+            signer_seeds,
+        ),
+        output,
+        mint_out.decimals,
+    )?;
+
+    // 6. 校验不变量，更新该池的波动率追踪器并写回
+    let new_reserve_in = reserve_in.checked_add(pool_received).ok_or(TutorialError::MathOverflow)?;
+    let new_reserve_out = reserve_out.checked_sub(output).ok_or(TutorialError::MathOverflow)?;
+    // 按代币A、代币B的顺序排列储备
+    let orient = |reserve_in: u64, reserve_out: u64| if swap_a { (reserve_in, reserve_out) } else { (reserve_out, reserve_in) };
+    let (reserve_a_before, reserve_b_before) = orient(reserve_in, reserve_out);
+    let (reserve_a, reserve_b) = orient(new_reserve_in, new_reserve_out);
+    // 交易后的价格不能超出池子的聚合流动性区间
+    pool.price_range.check(reserve_a, reserve_b)?;
+    let invariant_delta = CurveCalculator::check_invariant_growth(
+        &pool.curve_type,
+        CurveCalculator::invariant(&pool.curve_type, reserve_a_before, reserve_b_before)?,
+        orient(
+            reserve_in.checked_add(taxed_input).ok_or(TutorialError::MathOverflow)?,
+            new_reserve_out,
+        ),
+        (reserve_a, reserve_b),
+    )?;
+    let current_price = pool.normalized_price(new_reserve_in, new_reserve_out, swap_a);
+    let now = Clock::get()?.unix_timestamp;
+    if let Some(pool_oracle) = pool_oracle.as_mut() {
+        PoolOracle::record_price(pool_oracle, current_price, now, &amm.volatility_config)?;
+        pool_oracle.exit(ctx.program_id)?;
+    }
+    pool.oracle.update(reserve_a, reserve_b, now);
+    pool.swap_reserves(swap_a, pool_received, output)?;
+    pool.stats.record_swap(swap_a, input, output, fee_amount, now);
+    pool.unlock();
+    pool.exit(ctx.program_id)?;
+
+    // 下一跳的输入为交易者实际收到的数量
+    let received = output - get_transfer_fee(&hop[3], output)?;
+
+    emit!(SwapEvent {
+        pool: pool.key(),
+        trader: ctx.trader.key(),
+        swap_a,
+        input_amount: input,
+        output_amount: received,
+        fee_amount,
+        protocol_fee,
+        price_impact_bps: PriceImpactCalculator::to_bps(price_impact),
+        reserve_a,
+        reserve_b,
+        invariant_delta,
+    });
+
+    Ok((received, hop[7].clone(), output_mint))
+}
+
+#[derive(Accounts)]
+pub struct SwapRoute<'info> {
+    #[account(
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+    )]
+    pub amm: Box<Account<'info, Amm>>,
+
+    /// The account doing the swap
+    pub trader: Signer<'info>,
+
+    /// AMM的地址黑名单，未设置黑名单时可以省略
+    #[account(address = amm.denylist @ TutorialError::DenylistRequired)]
+    pub denylist: Option<Box<Account<'info, Denylist>>>,
+
+    /// The trader account holding the first input token
+    #[account(
+        mut,
+        token::authority = trader,
+    )]
+    pub trader_source: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Solana ecosystem accounts
+    pub token_program: Program<'info, Token>,
+    pub token_2022_program: Program<'info, Token2022>,
+}
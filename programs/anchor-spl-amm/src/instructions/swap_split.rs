@@ -0,0 +1,76 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::*,
+    state::Denylist,
+    utils::{check_deadline, input_within_balance},
+};
+
+use super::swap_route::{execute_hop, HopAccounts, SwapRoute, ROUTE_HOP_ACCOUNTS};
+
+/// 拆单交换：把一笔输入按参数中的比例（基点）分配到同一交易对的多个费率档位池子，
+/// 每个池子占用ROUTE_HOP_ACCOUNTS个remaining_accounts，所有池子的输出之和共用一次滑点检查
+pub fn swap_split<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SwapRoute<'info>>,
+    input_amount: u64,
+    split_bps: Vec<u16>,
+    min_output_amount: u64,
+    deadline: Option<i64>,
+    clamp_to_balance: bool,
+) -> Result<()> {
+    // 拒绝已过期的交易，避免以过时的价格执行
+    check_deadline(deadline)?;
+    Denylist::check(&ctx.accounts.amm, ctx.accounts.denylist.as_deref().map(|denylist| &**denylist), &ctx.accounts.trader.key())?;
+
+    let remaining = ctx.remaining_accounts;
+    if split_bps.is_empty()
+        || remaining.len() != split_bps.len() * ROUTE_HOP_ACCOUNTS
+        || split_bps.iter().map(|bps| *bps as u32).sum::<u32>() != 10000
+    {
+        return err!(TutorialError::InvalidSplit);
+    }
+
+    // Prevent swapping assets the trader does not own
+    // 超出余额时只有设置了clamp_to_balance才按余额成交，否则拒绝
+    let input = input_within_balance(input_amount, ctx.accounts.trader_source.amount, clamp_to_balance)?;
+    let input_mint = ctx.accounts.trader_source.mint;
+    let trader_in = ctx.accounts.trader_source.to_account_info();
+
+    let hop_accounts = HopAccounts {
+        program_id: ctx.program_id,
+        amm: &ctx.accounts.amm,
+        trader: ctx.accounts.trader.to_account_info(),
+        token_program: ctx.accounts.token_program.to_account_info(),
+        token_2022_program: ctx.accounts.token_2022_program.to_account_info(),
+    };
+    let mut remaining_input = input;
+    let mut total_output: u64 = 0;
+    let mut pair_output_mint = None;
+    for (i, (bps, hop)) in split_bps.iter().zip(remaining.chunks_exact(ROUTE_HOP_ACCOUNTS)).enumerate() {
+        // 最后一个池子承接按比例分配后的余数
+        let amount = if i == split_bps.len() - 1 {
+            remaining_input
+        } else {
+            (input as u128 * *bps as u128 / 10000) as u64
+        };
+        remaining_input -= amount;
+        if amount == 0 {
+            continue;
+        }
+
+        let (output, _, output_mint) = execute_hop(&hop_accounts, hop, &trader_in, input_mint, amount)?;
+        // 所有池子必须是同一个交易对
+        if *pair_output_mint.get_or_insert(output_mint) != output_mint {
+            return err!(TutorialError::InvalidSplit);
+        }
+        total_output = total_output.checked_add(output).ok_or(TutorialError::MathOverflow)?;
+    }
+
+    if total_output < min_output_amount {
+        return err!(TutorialError::OutputTooSmall);
+    }
+
+    msg!("Split {} tokens across {} pools for {}", input, split_bps.len(), total_output);
+
+    Ok(())
+}
@@ -0,0 +1,365 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, TransferChecked};
+
+use crate::{
+    constants::AUTHORITY_SEED,
+    errors::*,
+    events::SwapEvent,
+    models::curve::CurveCalculator,
+    models::fee_strategy::FeeCalculator,
+    models::price_impact::PriceImpactCalculator,
+    state::{Denylist, Pool, PoolOracle},
+    utils::{check_deadline, get_transfer_inverse_fee},
+};
+
+use super::swap_exact_tokens_for_tokens::{
+    check_oracle_guard, swap_fee_rate_bps, transfer_output_side_fees, SwapExactTokensForTokens,
+};
+use super::swap_hook::SwapHookArgs;
+
+pub fn swap_tokens_for_exact_tokens<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SwapExactTokensForTokens<'info>>,
+    input_mint: Pubkey, // 输入代币，交换方向由它与池子代币的比较得出
+    output_amount: u64,
+    max_input_amount: u64,
+    deadline: Option<i64>,
+    max_price_x64: Option<u128>,
+) -> Result<()> {
+    // 拒绝已过期的交易，避免以过时的价格执行
+    check_deadline(deadline)?;
+    let swap_a = ctx.accounts.pool.swap_direction(&input_mint)?;
+
+    swap_tokens_for_exact_tokens_process(ctx, swap_a, output_amount, max_input_amount, max_price_x64)
+}
+
+// 处理精确输出的交换逻辑
+fn swap_tokens_for_exact_tokens_process<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SwapExactTokensForTokens<'info>>,
+    swap_a: bool,
+    output_amount: u64,
+    max_input_amount: u64,
+    max_price_x64: Option<u128>,
+) -> Result<()> {
+    Denylist::check(&ctx.accounts.amm, ctx.accounts.denylist.as_deref().map(|denylist| &**denylist), &ctx.accounts.trader.key())?;
+    Pool::lock(&mut ctx.accounts.pool)?;
+
+    let amm = &ctx.accounts.amm;
+    let (reserve_a, reserve_b) = ctx.accounts.pool.reserves();
+    let (reserve_in, reserve_out) = if swap_a {
+        (reserve_a, reserve_b)
+    } else {
+        (reserve_b, reserve_a)
+    };
+    let (mint_in, mint_out) = if swap_a {
+        (ctx.accounts.mint_a.to_account_info(), ctx.accounts.mint_b.to_account_info())
+    } else {
+        (ctx.accounts.mint_b.to_account_info(), ctx.accounts.mint_a.to_account_info())
+    };
+
+    // Token-2022 转账费：池子需多转出一部分，交易者才能实际收到目标数量
+    let pool_output = output_amount
+        .checked_add(get_transfer_inverse_fee(&mint_out, output_amount)?)
+        .ok_or(TutorialError::MathOverflow)?;
+
+    // 仅退出模式、池子状态不允许交换和熔断期间拒绝交换
+    let now = Clock::get()?.unix_timestamp;
+    amm.check_swaps_enabled()?;
+    ctx.accounts.pool.check_swaps_enabled()?;
+    ctx.accounts.pool.check_circuit_breaker(now)?;
+    let pool_oracle = ctx.accounts.pool_oracle.as_deref().map(|oracle| &**oracle);
+    PoolOracle::check_required(amm, pool_oracle)?;
+
+    // 1. 输出不能耗尽池子
+    if output_amount == 0 || pool_output >= reserve_out {
+        return err!(TutorialError::InsufficientLiquidity);
+    }
+
+    // 2. 按池子的定价曲线反推输入，向上取整以保证池子不吃亏
+    let fee_on_output = amm.fee_on_output;
+    let curve_input = CurveCalculator::swap_input(&ctx.accounts.pool.curve_type, swap_a, pool_output, reserve_in, reserve_out)?;
+    let fee_rate_bps = FeeCalculator::apply_discount(
+        swap_fee_rate_bps(amm, &ctx.accounts.pool, pool_oracle, swap_a, curve_input, (reserve_in, reserve_out)),
+        ctx.accounts.fee_discount_bps(now),
+    );
+
+    // 3. 加回手续费，费率应用交易者的折扣：
+    //    以输入代币收取时 input = taxed_input * 10000 / (10000 - fee)；
+    //    以输出代币收取时池子需按曲线付出 gross_output = pool_output * 10000 / (10000 - fee)，其中的手续费留在输出端
+    let (taxed_input, gross_output, fee_amount) = if fee_on_output {
+        let gross_output = (pool_output as u128 * 10000).div_ceil((10000 - fee_rate_bps) as u128) as u64;
+        if gross_output >= reserve_out {
+            return err!(TutorialError::InsufficientLiquidity);
+        }
+        let taxed_input = CurveCalculator::swap_input(&ctx.accounts.pool.curve_type, swap_a, gross_output, reserve_in, reserve_out)?;
+        (taxed_input, gross_output, gross_output - pool_output)
+    } else {
+        let input = (curve_input as u128 * 10000).div_ceil((10000 - fee_rate_bps) as u128) as u64;
+        (curve_input, pool_output, input - curve_input)
+    };
+    let protocol_fee = FeeCalculator::calculate_protocol_fee(&amm.fee_config, fee_amount);
+    let il_compensation_fee = FeeCalculator::calculate_il_compensation_fee(&amm.fee_config, fee_amount);
+    let (input_side_fees, output_side_fees) = if fee_on_output {
+        (0, protocol_fee + il_compensation_fee)
+    } else {
+        (protocol_fee + il_compensation_fee, 0)
+    };
+
+    // 转入池子的部分同样需要覆盖输入代币的转账费
+    let pool_received = if fee_on_output { taxed_input } else { taxed_input + fee_amount - input_side_fees };
+    let pool_transfer = pool_received
+        .checked_add(get_transfer_inverse_fee(&mint_in, pool_received)?)
+        .ok_or(TutorialError::MathOverflow)?;
+    let input = pool_transfer + input_side_fees;
+
+    // 4. Slip point protection
+    if input > max_input_amount {
+        return err!(TutorialError::InputTooLarge);
+    }
+    if !PriceImpactCalculator::is_within_price_limit(input, output_amount, max_price_x64) {
+        return err!(TutorialError::PriceLimitExceeded);
+    }
+    let trader_balance = if swap_a {
+        ctx.accounts.trader_token_accounts.trader_account_a.amount
+    } else {
+        ctx.accounts.trader_token_accounts.trader_account_b.amount
+    };
+    if input > trader_balance {
+        return err!(TutorialError::InsufficientInputBalance);
+    }
+
+    // 单笔交易不能消耗过多的输入端储备
+    if !PriceImpactCalculator::is_trade_size_acceptable(&amm.price_impact_config, pool_received, reserve_in) {
+        return err!(TutorialError::TradeTooLarge);
+    }
+
+    // 交易后的价格不能超出池子的聚合流动性区间
+    let reserve_in_after = reserve_in.checked_add(pool_received).ok_or(TutorialError::MathOverflow)?;
+    let reserve_out_after = reserve_out
+        .checked_sub(pool_output)
+        .and_then(|reserve| reserve.checked_sub(output_side_fees))
+        .ok_or(TutorialError::MathOverflow)?;
+    if swap_a {
+        ctx.accounts.pool.price_range.check(reserve_in_after, reserve_out_after)?;
+    } else {
+        ctx.accounts.pool.price_range.check(reserve_out_after, reserve_in_after)?;
+    }
+
+    // 检查价格影响是否在可接受范围内
+    let price_impact = PriceImpactCalculator::calculate_price_impact(
+        &amm.price_impact_config,
+        pool_received,
+        pool_output,
+        reserve_in,
+        reserve_out,
+    );
+    if !PriceImpactCalculator::is_price_impact_acceptable(
+        &amm.price_impact_config,
+        price_impact
+    ) {
+        return err!(TutorialError::PriceImpactTooHigh);
+    }
+
+    // 外部预言机价格保护
+    check_oracle_guard(
+        &ctx.accounts.pool,
+        ctx.accounts.price_feed.as_deref(),
+        swap_a,
+        taxed_input,
+        gross_output,
+        ctx.accounts.mint_a.decimals,
+        ctx.accounts.mint_b.decimals,
+    )?;
+
+    // 5. Compute the invariant before the trade
+    let invariant = CurveCalculator::invariant(&ctx.accounts.pool.curve_type, reserve_a, reserve_b)?;
+
+    // 交换前调用池子的钩子，钩子可以拒绝这笔交换
+    let hook_args = SwapHookArgs {
+        pool: ctx.accounts.pool.key(),
+        trader: ctx.accounts.trader.key(),
+        swap_a,
+        input_amount: input,
+        output_amount,
+        reserve_a,
+        reserve_b,
+    };
+    ctx.accounts.invoke_swap_hook(ctx.remaining_accounts, "before_swap", &hook_args)?;
+
+    // 6. Swap the tokens
+    let authority_bump = ctx.bumps.pool_authority;
+    let authority_seeds = &[
+        &ctx.accounts.pool.amm.to_bytes(),
+        &ctx.accounts.mint_a.key().to_bytes(),
+        &ctx.accounts.mint_b.key().to_bytes(),
+        &ctx.accounts.pool.fee_tier.to_le_bytes()[..],
+        AUTHORITY_SEED,
+        &[authority_bump],
+    ];
+    let signer_seeds = &[&authority_seeds[..]];
+    let (trader_in, trader_out, pool_in, pool_out, protocol_fee_account, il_compensation_account) = if swap_a {
+        (
+            ctx.accounts.trader_token_accounts.trader_account_a.to_account_info(),
+            ctx.accounts.trader_token_accounts.trader_account_b.to_account_info(),
+            ctx.accounts.pool_token_accounts.pool_account_a.to_account_info(),
+            ctx.accounts.pool_token_accounts.pool_account_b.to_account_info(),
+            ctx.accounts.pool_token_accounts.protocol_fee_account_a.to_account_info(),
+            ctx.accounts.pool_token_accounts.il_compensation_account_a.as_ref().map(|account| account.to_account_info()),
+        )
+    } else {
+        (
+            ctx.accounts.trader_token_accounts.trader_account_b.to_account_info(),
+            ctx.accounts.trader_token_accounts.trader_account_a.to_account_info(),
+            ctx.accounts.pool_token_accounts.pool_account_b.to_account_info(),
+            ctx.accounts.pool_token_accounts.pool_account_a.to_account_info(),
+            ctx.accounts.pool_token_accounts.protocol_fee_account_b.to_account_info(),
+            ctx.accounts.pool_token_accounts.il_compensation_account_b.as_ref().map(|account| account.to_account_info()),
+        )
+    };
+    let (token_program_in, token_program_out, decimals_in, decimals_out) = if swap_a {
+        (
+            ctx.accounts.token_program_a.to_account_info(),
+            ctx.accounts.token_program_b.to_account_info(),
+            ctx.accounts.mint_a.decimals,
+            ctx.accounts.mint_b.decimals,
+        )
+    } else {
+        (
+            ctx.accounts.token_program_b.to_account_info(),
+            ctx.accounts.token_program_a.to_account_info(),
+            ctx.accounts.mint_b.decimals,
+            ctx.accounts.mint_a.decimals,
+        )
+    };
+    token_interface::transfer_checked(
+        CpiContext::new(
+            token_program_in.clone(),
+            TransferChecked {
+                from: trader_in.clone(),
+                mint: mint_in.clone(),
+                to: pool_in,
+                authority: ctx.accounts.trader.to_account_info(),
+            },
+        ),
+        pool_transfer,
+        decimals_in,
+    )?;
+    if il_compensation_fee > 0 && !fee_on_output {
+        let vault = il_compensation_account.ok_or(TutorialError::MissingCompensationVault)?;
+        token_interface::transfer_checked(
+            CpiContext::new(
+                token_program_in.clone(),
+                TransferChecked {
+                    from: trader_in.clone(),
+                    mint: mint_in.clone(),
+                    to: vault,
+                    authority: ctx.accounts.trader.to_account_info(),
+                },
+            ),
+            il_compensation_fee,
+            decimals_in,
+        )?;
+    }
+    if protocol_fee > 0 && !fee_on_output {
+        token_interface::transfer_checked(
+            CpiContext::new(
+                token_program_in,
+                TransferChecked {
+                    from: trader_in,
+                    mint: mint_in,
+                    to: protocol_fee_account,
+                    authority: ctx.accounts.trader.to_account_info(),
+                },
+            ),
+            protocol_fee,
+            decimals_in,
+        )?;
+    }
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            token_program_out,
+            TransferChecked {
+                from: pool_out,
+                mint: mint_out,
+                to: trader_out,
+                authority: ctx.accounts.pool_authority.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        pool_output,
+        decimals_out,
+    )?;
+    if output_side_fees > 0 {
+        transfer_output_side_fees(ctx.accounts, !swap_a, protocol_fee, il_compensation_fee, signer_seeds)?;
+    }
+
+    msg!(
+        "Traded {} tokens ({} after fees, {} protocol fee) for {} (Price impact: {} bps)",
+        input,
+        taxed_input,
+        protocol_fee,
+        output_amount,
+        price_impact
+    );
+
+    // 7. Verify the invariant still holds, including the LP fee left in the pool
+    //    交易后的储备按实际转入转出的数量计算，直接转入池子的代币不会并入储备
+    let (reserve_a_after, reserve_b_after) =
+        if swap_a { (reserve_in_after, reserve_out_after) } else { (reserve_out_after, reserve_in_after) };
+    let fee_excluded = (
+        reserve_in.checked_add(taxed_input).ok_or(TutorialError::MathOverflow)?,
+        reserve_out.checked_sub(gross_output).ok_or(TutorialError::MathOverflow)?,
+    );
+    let invariant_delta = CurveCalculator::check_invariant_growth(
+        &ctx.accounts.pool.curve_type,
+        invariant,
+        if swap_a { fee_excluded } else { (fee_excluded.1, fee_excluded.0) },
+        (reserve_a_after, reserve_b_after),
+    )?;
+    ctx.accounts.invoke_swap_hook(
+        ctx.remaining_accounts,
+        "after_swap",
+        &SwapHookArgs {
+            reserve_a: reserve_a_after,
+            reserve_b: reserve_b_after,
+            ..hook_args
+        },
+    )?;
+
+    emit!(SwapEvent {
+        pool: ctx.accounts.pool.key(),
+        trader: ctx.accounts.trader.key(),
+        swap_a,
+        input_amount: input,
+        output_amount,
+        fee_amount,
+        protocol_fee,
+        price_impact_bps: PriceImpactCalculator::to_bps(price_impact),
+        reserve_a: reserve_a_after,
+        reserve_b: reserve_b_after,
+        invariant_delta,
+    });
+
+    // 8. 更新波动率追踪器
+    let current_price = if swap_a {
+        ctx.accounts.pool.normalized_price(reserve_a_after, reserve_b_after, true)
+    } else {
+        ctx.accounts.pool.normalized_price(reserve_b_after, reserve_a_after, false)
+    };
+    // 按代币B计量记录交易者的成交量
+    if let Some(trader_stats) = ctx.accounts.trader_stats.as_mut() {
+        trader_stats.record(if swap_a { output_amount } else { input }, now);
+    }
+
+    let pool = &mut ctx.accounts.pool;
+    pool.stats.record_swap(swap_a, input, pool_output, fee_amount, now);
+    if let Some(pool_oracle) = ctx.accounts.pool_oracle.as_mut() {
+        PoolOracle::record_price(pool_oracle, current_price, now, &ctx.accounts.amm.volatility_config)?;
+    }
+
+    // 9. 更新TWAP预言机
+    pool.oracle.update(reserve_a_after, reserve_b_after, now);
+    pool.swap_reserves(swap_a, pool_received, pool_output + output_side_fees)?;
+    pool.unlock();
+
+    Ok(())
+}
@@ -0,0 +1,310 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{MAX_TIMELOCK_DELAY, PENDING_CHANGE_SEED},
+    errors::TutorialError,
+    events::{
+        FeeConfigUpdatedEvent, FeeSideUpdatedEvent, ParameterChangeCancelledEvent, ParameterChangeExecutedEvent,
+        ParameterChangeProposedEvent, ParameterChangeQueuedEvent, TimelockDelayUpdatedEvent,
+        VolatilityConfigUpdatedEvent, WithdrawalFeeConfigUpdatedEvent,
+    },
+    models::timelock::ParameterChange,
+    state::{Amm, PendingChange, Pool},
+};
+
+/// 提议参数变更，提议者需要持有变更对应的角色
+pub fn propose_parameter_change(ctx: Context<ProposeParameterChange>, id: u64, change: ParameterChange) -> Result<()> {
+    let amm = &ctx.accounts.amm;
+    if !amm.has_role(change.required_role(), &ctx.accounts.proposer.key()) {
+        return err!(TutorialError::MissingRole);
+    }
+    match change {
+        ParameterChange::FeeConfig(fee_config) => {
+            if !fee_config.is_valid() {
+                return err!(TutorialError::InvalidFeeConfig);
+            }
+        }
+        ParameterChange::Curve { curve_type, .. } => {
+            if !curve_type.is_valid() {
+                return err!(TutorialError::InvalidCurveConfig);
+            }
+        }
+        ParameterChange::TimelockDelay(delay) => {
+            if !(0..amm.timelock_delay).contains(&delay) {
+                return err!(TutorialError::InvalidTimelockDelay);
+            }
+        }
+        ParameterChange::WithdrawalFeeConfig(withdrawal_fee_config) => {
+            if !withdrawal_fee_config.is_valid() {
+                return err!(TutorialError::InvalidWithdrawalFeeConfig);
+            }
+        }
+        ParameterChange::VolatilityConfig(volatility_config) => {
+            if !volatility_config.is_valid() {
+                return err!(TutorialError::InvalidVolatilityConfig);
+            }
+        }
+        ParameterChange::FeeSide(_) => {}
+    }
+
+    let pending_change = &mut ctx.accounts.pending_change;
+    pending_change.amm = amm.key();
+    pending_change.proposer = ctx.accounts.proposer.key();
+    pending_change.id = id;
+    pending_change.change = change;
+    pending_change.proposed_at = Clock::get()?.unix_timestamp;
+    pending_change.eta = 0;
+
+    emit!(ParameterChangeProposedEvent {
+        amm: amm.key(),
+        pending_change: pending_change.key(),
+        proposer: pending_change.proposer,
+        change,
+    });
+
+    Ok(())
+}
+
+/// 所有者批准提议并开始计时，按当前的延迟计算可执行时间
+pub fn queue_parameter_change(ctx: Context<QueueParameterChange>) -> Result<()> {
+    let pending_change = &mut ctx.accounts.pending_change;
+    if pending_change.is_queued() {
+        return err!(TutorialError::ChangeAlreadyQueued);
+    }
+    pending_change.eta = Clock::get()?.unix_timestamp + ctx.accounts.amm.timelock_delay;
+
+    emit!(ParameterChangeQueuedEvent {
+        amm: pending_change.amm,
+        pending_change: pending_change.key(),
+        eta: pending_change.eta,
+    });
+
+    Ok(())
+}
+
+/// 延迟结束后执行变更，任何人都可以调用，租金退还给提议者
+pub fn execute_parameter_change(ctx: Context<ExecuteParameterChange>) -> Result<()> {
+    let pending_change = &ctx.accounts.pending_change;
+    if !pending_change.is_queued() {
+        return err!(TutorialError::ChangeNotQueued);
+    }
+    if Clock::get()?.unix_timestamp < pending_change.eta {
+        return err!(TutorialError::TimelockNotElapsed);
+    }
+
+    let amm = &mut ctx.accounts.amm;
+    match pending_change.change {
+        ParameterChange::FeeConfig(fee_config) => {
+            amm.fee_config = fee_config;
+            // 保持固定费率与基础费率一致
+            amm.fee = fee_config.base_fee_bps;
+
+            emit!(FeeConfigUpdatedEvent {
+                amm: amm.key(),
+                fee_config,
+            });
+        }
+        ParameterChange::Curve { pool, curve_type } => {
+            let Some(pool_account) = ctx.accounts.pool.as_mut() else {
+                return Err(ErrorCode::AccountNotEnoughKeys.into());
+            };
+            if pool_account.key() != pool {
+                return Err(ErrorCode::ConstraintAddress.into());
+            }
+            pool_account.update_curve_params(curve_type)?;
+        }
+        ParameterChange::TimelockDelay(delay) => {
+            amm.timelock_delay = delay;
+
+            emit!(TimelockDelayUpdatedEvent {
+                amm: amm.key(),
+                delay,
+            });
+        }
+        ParameterChange::WithdrawalFeeConfig(withdrawal_fee_config) => {
+            amm.withdrawal_fee_config = withdrawal_fee_config;
+
+            emit!(WithdrawalFeeConfigUpdatedEvent {
+                amm: amm.key(),
+                withdrawal_fee_config,
+            });
+        }
+        ParameterChange::VolatilityConfig(volatility_config) => {
+            amm.volatility_config = volatility_config;
+
+            emit!(VolatilityConfigUpdatedEvent {
+                amm: amm.key(),
+                volatility_config,
+            });
+        }
+        ParameterChange::FeeSide(fee_on_output) => {
+            amm.fee_on_output = fee_on_output;
+
+            emit!(FeeSideUpdatedEvent {
+                amm: amm.key(),
+                fee_on_output,
+            });
+        }
+    }
+
+    emit!(ParameterChangeExecutedEvent {
+        amm: amm.key(),
+        pending_change: pending_change.key(),
+        change: pending_change.change,
+    });
+
+    Ok(())
+}
+
+/// 所有者或提议者取消尚未执行的变更
+pub fn cancel_parameter_change(ctx: Context<CancelParameterChange>) -> Result<()> {
+    emit!(ParameterChangeCancelledEvent {
+        amm: ctx.accounts.amm.key(),
+        pending_change: ctx.accounts.pending_change.key(),
+    });
+
+    Ok(())
+}
+
+/// 直接延长时间锁的延迟，缩短需要经过时间锁本身
+pub fn set_timelock_delay(ctx: Context<SetTimelockDelay>, delay: i64) -> Result<()> {
+    let amm = &mut ctx.accounts.amm;
+    if delay < amm.timelock_delay || delay > MAX_TIMELOCK_DELAY {
+        return err!(TutorialError::InvalidTimelockDelay);
+    }
+    amm.timelock_delay = delay;
+
+    emit!(TimelockDelayUpdatedEvent {
+        amm: amm.key(),
+        delay,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct ProposeParameterChange<'info> {
+    #[account(
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+    )]
+    pub amm: Box<Account<'info, Amm>>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = PendingChange::LEN,
+        seeds = [
+            amm.key().as_ref(),
+            proposer.key().as_ref(),
+            id.to_le_bytes().as_ref(),
+            PENDING_CHANGE_SEED,
+        ],
+        bump,
+    )]
+    pub pending_change: Box<Account<'info, PendingChange>>,
+
+    /// The holder of the role required by the change
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct QueueParameterChange<'info> {
+    #[account(
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+        has_one = admin,
+    )]
+    pub amm: Box<Account<'info, Amm>>,
+
+    #[account(
+        mut,
+        has_one = amm,
+    )]
+    pub pending_change: Box<Account<'info, PendingChange>>,
+
+    /// The owner of the AMM
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteParameterChange<'info> {
+    #[account(
+        mut,
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+    )]
+    pub amm: Box<Account<'info, Amm>>,
+
+    #[account(
+        mut,
+        has_one = amm,
+        has_one = proposer,
+        close = proposer,
+    )]
+    pub pending_change: Box<Account<'info, PendingChange>>,
+
+    /// 池子曲线变更的目标池子，其他变更可以省略
+    #[account(
+        mut,
+        has_one = amm,
+    )]
+    pub pool: Option<Box<Account<'info, Pool>>>,
+
+    /// CHECK: 与待执行变更记录的提议者一致，只接收退还的租金
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelParameterChange<'info> {
+    #[account(
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+    )]
+    pub amm: Box<Account<'info, Amm>>,
+
+    #[account(
+        mut,
+        has_one = amm,
+        has_one = proposer,
+        close = proposer,
+        constraint = authority.key() == proposer.key() || authority.key() == amm.admin @ TutorialError::MissingRole,
+    )]
+    pub pending_change: Box<Account<'info, PendingChange>>,
+
+    /// CHECK: 与待取消变更记录的提议者一致，只接收退还的租金
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+
+    /// The owner of the AMM or the proposer
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetTimelockDelay<'info> {
+    #[account(
+        mut,
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+        has_one = admin,
+    )]
+    pub amm: Box<Account<'info, Amm>>,
+
+    /// The owner of the AMM
+    pub admin: Signer<'info>,
+}
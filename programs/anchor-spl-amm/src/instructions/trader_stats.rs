@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::TRADER_STATS_SEED,
+    state::{Pool, TraderStats},
+};
+
+/// 创建交易者在池子中的成交量统计账户，之后的交换传入该账户即可累计成交量并享受折扣
+pub fn create_trader_stats(ctx: Context<CreateTraderStats>) -> Result<()> {
+    let trader_stats = &mut ctx.accounts.trader_stats;
+    trader_stats.pool = ctx.accounts.pool.key();
+    trader_stats.trader = ctx.accounts.trader.key();
+    trader_stats.period_start = Clock::get()?.unix_timestamp;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateTraderStats<'info> {
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        init,
+        payer = trader,
+        space = TraderStats::LEN,
+        seeds = [
+            pool.key().as_ref(),
+            trader.key().as_ref(),
+            TRADER_STATS_SEED,
+        ],
+        bump,
+    )]
+    pub trader_stats: Box<Account<'info, TraderStats>>,
+
+    /// 交易者，同时支付账户租金
+    #[account(mut)]
+    pub trader: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,124 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+use fixed::types::I64F64;
+
+use crate::{
+    constants::AUTHORITY_SEED,
+    errors::*,
+    events::{ConcentratedLiquidityConfigUpdatedEvent, PoolPriceRangeUpdatedEvent},
+    models::concentrated_liquidity::{ConcentratedLiquidityConfig, ConcentratedLiquidityPricing, PriceRange},
+    state::{Amm, Pool},
+};
+
+/// 更新聚合流动性配置，只影响之后创建的池子；已有池子保留原来的价格区间（未设置即全区间），
+/// 需要管理员逐个迁移
+pub fn update_concentrated_liquidity_config(
+    ctx: Context<UpdateConcentratedLiquidityConfig>,
+    config: ConcentratedLiquidityConfig,
+) -> Result<()> {
+    if !config.is_valid() {
+        return err!(TutorialError::InvalidConcentratedLiquidityConfig);
+    }
+
+    let amm = &mut ctx.accounts.amm;
+    amm.concentrated_liquidity_config = config;
+
+    emit!(ConcentratedLiquidityConfigUpdatedEvent {
+        amm: amm.key(),
+        config,
+    });
+
+    Ok(())
+}
+
+/// 按当前配置迁移已有池子的价格区间：开启时以池子当前价格为中心重新计算区间，关闭时恢复为全区间
+pub fn migrate_pool_price_range(ctx: Context<MigratePoolPriceRange>) -> Result<()> {
+    let config = &ctx.accounts.amm.concentrated_liquidity_config;
+    let price_x64 = ConcentratedLiquidityPricing::price_x64(
+        ctx.accounts.pool_account_a.amount,
+        ctx.accounts.pool_account_b.amount,
+    );
+
+    let pool = &mut ctx.accounts.pool;
+    pool.price_range = if config.enabled && price_x64 > 0 {
+        PriceRange::around(I64F64::from_bits(price_x64 as i128), config.range_percentage)
+    } else {
+        PriceRange::default()
+    };
+
+    emit!(PoolPriceRangeUpdatedEvent {
+        pool: pool.key(),
+        price_range: pool.price_range,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateConcentratedLiquidityConfig<'info> {
+    #[account(
+        mut,
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+        has_one = admin,
+    )]
+    pub amm: Account<'info, Amm>,
+
+    /// The admin of the AMM
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigratePoolPriceRange<'info> {
+    #[account(
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+        has_one = admin,
+    )]
+    pub amm: Account<'info, Amm>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.key().as_ref(),
+            pool.mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+        ],
+        bump,
+        has_one = amm,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// CHECK: Read only authority
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.as_ref(),
+            pool.mint_b.as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+            AUTHORITY_SEED,
+        ],
+        bump,
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    #[account(
+        token::mint = pool.mint_a,
+        token::authority = pool_authority,
+    )]
+    pub pool_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        token::mint = pool.mint_b,
+        token::authority = pool_authority,
+    )]
+    pub pool_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The admin of the AMM
+    pub admin: Signer<'info>,
+}
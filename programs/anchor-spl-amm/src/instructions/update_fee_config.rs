@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::*,
+    events::FeeConfigUpdatedEvent,
+    state::Amm,
+    models::{fee_strategy::FeeConfig, roles::Role},
+};
+
+pub fn update_fee_config(ctx: Context<UpdateFeeConfig>, fee_config: FeeConfig) -> Result<()> {
+    // 校验费率区间：min <= base <= max，且不超过100%
+    if !fee_config.is_valid() {
+        return err!(TutorialError::InvalidFeeConfig);
+    }
+
+    let amm = &mut ctx.accounts.amm;
+    // 启用时间锁后，提高费率和修改协议分成需要通过 propose_parameter_change
+    if amm.timelock_delay > 0 && amm.fee_config.requires_timelock(&fee_config) {
+        return err!(TutorialError::TimelockRequired);
+    }
+    amm.fee_config = fee_config;
+    // 保持固定费率与基础费率一致
+    amm.fee = fee_config.base_fee_bps;
+
+    emit!(FeeConfigUpdatedEvent {
+        amm: amm.key(),
+        fee_config,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateFeeConfig<'info> {
+    #[account(
+        mut,
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+        constraint = amm.has_role(Role::FeeManager, &admin.key()) @ TutorialError::MissingRole,
+    )]
+    pub amm: Account<'info, Amm>,
+
+    /// The owner or the fee manager of the AMM
+    pub admin: Signer<'info>,
+}
@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::TutorialError, events::FeeSideUpdatedEvent, models::roles::Role, state::Amm};
+
+/// 切换手续费的收取端：开启后手续费以输出代币收取，交易者输入的代币全部进入池子
+pub fn update_fee_side(ctx: Context<UpdateFeeSide>, fee_on_output: bool) -> Result<()> {
+    let amm = &mut ctx.accounts.amm;
+    // 启用时间锁后，需要通过 propose_parameter_change 修改
+    if amm.timelock_delay > 0 {
+        return err!(TutorialError::TimelockRequired);
+    }
+    amm.fee_on_output = fee_on_output;
+
+    emit!(FeeSideUpdatedEvent {
+        amm: amm.key(),
+        fee_on_output,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateFeeSide<'info> {
+    #[account(
+        mut,
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+        constraint = amm.has_role(Role::FeeManager, &admin.key()) @ TutorialError::MissingRole,
+    )]
+    pub amm: Account<'info, Amm>,
+
+    /// The owner or the fee manager of the AMM
+    pub admin: Signer<'info>,
+}
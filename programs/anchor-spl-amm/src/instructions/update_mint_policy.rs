@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::TutorialError,
+    events::MintPolicyUpdatedEvent,
+    models::{mint_policy::MintPolicy, roles::Role},
+    state::Amm,
+};
+
+/// 更新创建池子时的代币准入策略，只影响之后创建的池子
+pub fn update_mint_policy(ctx: Context<UpdateMintPolicy>, mint_policy: MintPolicy) -> Result<()> {
+    let amm = &mut ctx.accounts.amm;
+    amm.mint_policy = mint_policy;
+
+    emit!(MintPolicyUpdatedEvent {
+        amm: amm.key(),
+        mint_policy,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateMintPolicy<'info> {
+    #[account(
+        mut,
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+        constraint = amm.has_role(Role::RiskManager, &admin.key()) @ TutorialError::MissingRole,
+    )]
+    pub amm: Account<'info, Amm>,
+
+    /// The owner or the risk manager of the AMM
+    pub admin: Signer<'info>,
+}
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::*,
+    events::PriceImpactConfigUpdatedEvent,
+    models::{price_impact::PriceImpactConfig, roles::Role},
+    state::Amm,
+};
+
+/// 更新价格影响保护参数，单笔交易的最大比例保持不变
+pub fn update_price_impact_config(
+    ctx: Context<UpdatePriceImpactConfig>,
+    max_slippage_bps: u16,
+    dynamic_adjustment_factor: u16,
+    enabled: bool,
+) -> Result<()> {
+    let amm = &mut ctx.accounts.amm;
+    let price_impact_config = PriceImpactConfig {
+        enabled,
+        max_slippage_bps,
+        dynamic_adjustment_factor,
+        ..amm.price_impact_config
+    };
+    if !price_impact_config.is_valid() {
+        return err!(TutorialError::InvalidPriceImpactConfig);
+    }
+    amm.price_impact_config = price_impact_config;
+
+    emit!(PriceImpactConfigUpdatedEvent {
+        amm: amm.key(),
+        price_impact_config,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdatePriceImpactConfig<'info> {
+    #[account(
+        mut,
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+        constraint = amm.has_role(Role::RiskManager, &admin.key()) @ TutorialError::MissingRole,
+    )]
+    pub amm: Account<'info, Amm>,
+
+    /// The owner or the risk manager of the AMM
+    pub admin: Signer<'info>,
+}
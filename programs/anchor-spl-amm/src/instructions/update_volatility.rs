@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::POOL_ORACLE_SEED,
+    errors::TutorialError,
+    events::{CircuitBreakerUpdatedEvent, VolatilityUpdatedEvent},
+    state::{Amm, Pool, PoolOracle},
+};
+
+/// 任何人都可以调用的波动率更新指令，把重新计算的开销从交换路径中移出
+pub fn update_volatility(ctx: Context<UpdateVolatility>) -> Result<()> {
+    let config = &ctx.accounts.amm.volatility_config;
+    let now = Clock::get()?.unix_timestamp;
+    let pool_oracle = &mut ctx.accounts.pool_oracle;
+
+    // 每个间隔内最多重新计算一次
+    if !pool_oracle.volatility_tracker.is_update_due(config, now) {
+        return err!(TutorialError::VolatilityUpdateTooSoon);
+    }
+    let tripped = PoolOracle::refresh_volatility(pool_oracle, config, now)?;
+    let volatility_raw = pool_oracle.volatility_tracker.volatility_raw;
+
+    let pool = &mut ctx.accounts.pool;
+    emit!(VolatilityUpdatedEvent {
+        pool: pool.key(),
+        volatility_raw,
+    });
+    // 超过熔断阈值时暂停交换
+    if tripped {
+        pool.circuit_breaker_until = now + config.circuit_breaker_cooldown;
+        emit!(CircuitBreakerUpdatedEvent {
+            pool: pool.key(),
+            circuit_breaker_until: pool.circuit_breaker_until,
+        });
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateVolatility<'info> {
+    #[account(
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+    )]
+    pub amm: Box<Account<'info, Amm>>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.key().as_ref(),
+            pool.mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+        ],
+        bump,
+        has_one = amm,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// 池子的波动率预言机
+    #[account(
+        mut,
+        seeds = [
+            pool.key().as_ref(),
+            POOL_ORACLE_SEED,
+        ],
+        bump,
+    )]
+    pub pool_oracle: Box<Account<'info, PoolOracle>>,
+}
@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::*,
+    events::VolatilityConfigUpdatedEvent,
+    models::{volatility::VolatilityConfig, roles::Role},
+    state::Amm,
+};
+
+/// 更新波动率配置，启用后交换需要提供池子预言机以记录价格样本
+pub fn update_volatility_config(
+    ctx: Context<UpdateVolatilityConfig>,
+    volatility_config: VolatilityConfig,
+) -> Result<()> {
+    if !volatility_config.is_valid() {
+        return err!(TutorialError::InvalidVolatilityConfig);
+    }
+
+    let amm = &mut ctx.accounts.amm;
+    // 启用时间锁后，需要通过 propose_parameter_change 修改
+    if amm.timelock_delay > 0 {
+        return err!(TutorialError::TimelockRequired);
+    }
+    amm.volatility_config = volatility_config;
+
+    emit!(VolatilityConfigUpdatedEvent {
+        amm: amm.key(),
+        volatility_config,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateVolatilityConfig<'info> {
+    #[account(
+        mut,
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+        constraint = amm.has_role(Role::FeeManager, &admin.key()) @ TutorialError::MissingRole,
+    )]
+    pub amm: Account<'info, Amm>,
+
+    /// The owner or the fee manager of the AMM
+    pub admin: Signer<'info>,
+}
@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::*,
+    events::WithdrawalFeeConfigUpdatedEvent,
+    models::{withdrawal_fee::WithdrawalFeeConfig, roles::Role},
+    state::Amm,
+};
+
+pub fn update_withdrawal_fee_config(
+    ctx: Context<UpdateWithdrawalFeeConfig>,
+    withdrawal_fee_config: WithdrawalFeeConfig,
+) -> Result<()> {
+    if !withdrawal_fee_config.is_valid() {
+        return err!(TutorialError::InvalidWithdrawalFeeConfig);
+    }
+
+    let amm = &mut ctx.accounts.amm;
+    // 启用时间锁后，需要通过 propose_parameter_change 修改
+    if amm.timelock_delay > 0 {
+        return err!(TutorialError::TimelockRequired);
+    }
+    amm.withdrawal_fee_config = withdrawal_fee_config;
+
+    emit!(WithdrawalFeeConfigUpdatedEvent {
+        amm: amm.key(),
+        withdrawal_fee_config,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateWithdrawalFeeConfig<'info> {
+    #[account(
+        mut,
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+        constraint = amm.has_role(Role::FeeManager, &admin.key()) @ TutorialError::MissingRole,
+    )]
+    pub amm: Account<'info, Amm>,
+
+    /// The owner or the fee manager of the AMM
+    pub admin: Signer<'info>,
+}
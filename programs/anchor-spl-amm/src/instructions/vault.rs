@@ -0,0 +1,555 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::Token,
+    token_interface::{self, Burn, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    constants::{AUTHORITY_SEED, ESCROW_SEED, LIQUIDITY_SEED, VAULT_COMPOUND_INTERVAL, VAULT_SEED, VAULT_SHARE_SEED},
+    errors::TutorialError,
+    events::{VaultCompoundedEvent, VaultSharesUpdatedEvent},
+    instructions::minted_liquidity,
+    state::{Amm, Pool, Vault},
+    utils::get_transfer_fee,
+};
+
+/// 创建池子的自动复投金库
+pub fn create_vault(ctx: Context<CreateVault>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    vault.pool = ctx.accounts.pool.key();
+    vault.share_mint = ctx.accounts.share_mint.key();
+    vault.lp_vault = ctx.accounts.lp_vault.key();
+
+    Ok(())
+}
+
+/// 存入流动性代币，按金库当前持有的流动性铸造份额
+pub fn vault_deposit(ctx: Context<VaultDeposit>, liquidity: u64) -> Result<()> {
+    let share_supply = ctx.accounts.share_mint.supply;
+    let vault_liquidity = ctx.accounts.lp_vault.amount;
+    let shares = if share_supply == 0 || vault_liquidity == 0 {
+        liquidity
+    } else {
+        u64::try_from(liquidity as u128 * share_supply as u128 / vault_liquidity as u128)
+            .map_err(|_| error!(TutorialError::MathOverflow))?
+    };
+    if shares == 0 {
+        return err!(TutorialError::DepositTooSmall);
+    }
+
+    token_interface::transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.owner_account_liquidity.to_account_info(),
+                mint: ctx.accounts.mint_liquidity.to_account_info(),
+                to: ctx.accounts.lp_vault.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            },
+        ),
+        liquidity,
+        ctx.accounts.mint_liquidity.decimals,
+    )?;
+
+    let vault_seeds = &[
+        ctx.accounts.vault.pool.as_ref(),
+        VAULT_SEED,
+        &[ctx.bumps.vault],
+    ];
+    token_interface::mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.share_mint.to_account_info(),
+                to: ctx.accounts.owner_account_shares.to_account_info(),
+                authority: ctx.accounts.vault.to_account_info(),
+            },
+            &[&vault_seeds[..]],
+        ),
+        shares,
+    )?;
+
+    emit!(VaultSharesUpdatedEvent {
+        vault: ctx.accounts.vault.key(),
+        owner: ctx.accounts.owner.key(),
+        deposit: true,
+        liquidity,
+        shares,
+    });
+
+    Ok(())
+}
+
+/// 销毁份额，按比例取回金库持有的流动性代币（包含已复投的部分）
+pub fn vault_withdraw(ctx: Context<VaultWithdraw>, shares: u64) -> Result<()> {
+    let share_supply = ctx.accounts.share_mint.supply;
+    if shares == 0 || shares > share_supply {
+        return err!(TutorialError::InsufficientShares);
+    }
+    let liquidity = u64::try_from(shares as u128 * ctx.accounts.lp_vault.amount as u128 / share_supply as u128)
+        .map_err(|_| error!(TutorialError::MathOverflow))?;
+
+    token_interface::burn(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.share_mint.to_account_info(),
+                from: ctx.accounts.owner_account_shares.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            },
+        ),
+        shares,
+    )?;
+
+    let vault_seeds = &[
+        ctx.accounts.vault.pool.as_ref(),
+        VAULT_SEED,
+        &[ctx.bumps.vault],
+    ];
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.lp_vault.to_account_info(),
+                mint: ctx.accounts.mint_liquidity.to_account_info(),
+                to: ctx.accounts.owner_account_liquidity.to_account_info(),
+                authority: ctx.accounts.vault.to_account_info(),
+            },
+            &[&vault_seeds[..]],
+        ),
+        liquidity,
+        ctx.accounts.mint_liquidity.decimals,
+    )?;
+
+    emit!(VaultSharesUpdatedEvent {
+        vault: ctx.accounts.vault.key(),
+        owner: ctx.accounts.owner.key(),
+        deposit: false,
+        liquidity,
+        shares,
+    });
+
+    Ok(())
+}
+
+/// 复投（任何人都可以调用的crank）：交换手续费本身已留在储备中随流动性代币增值，
+/// 这里把金库收到的两种代币（如加成手续费、挖矿奖励）按池子比例重新存入，铸造的流动性代币归金库所有
+pub fn compound_vault(ctx: Context<CompoundVault>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    if now < ctx.accounts.vault.last_compound_at + VAULT_COMPOUND_INTERVAL {
+        return err!(TutorialError::CompoundTooSoon);
+    }
+    ctx.accounts.vault.last_compound_at = now;
+
+    // 1. 按池子比例计算可以存入的数量，多余的一侧留到下次复投
+    let (reserve_a, reserve_b) = ctx.accounts.pool.reserves();
+    let (reserve_a, reserve_b) = (reserve_a as u128, reserve_b as u128);
+    if reserve_a == 0 || reserve_b == 0 {
+        return err!(TutorialError::InsufficientLiquidity);
+    }
+    let balance_a = ctx.accounts.vault_account_a.amount as u128;
+    let balance_b = ctx.accounts.vault_account_b.amount as u128;
+    let amount_a = balance_a.min(balance_b * reserve_a / reserve_b);
+    let amount_b = amount_a * reserve_b / reserve_a;
+    let (amount_a, amount_b) = (amount_a as u64, amount_b as u64);
+    let received_a = amount_a - get_transfer_fee(&ctx.accounts.mint_a.to_account_info(), amount_a)?;
+    let received_b = amount_b - get_transfer_fee(&ctx.accounts.mint_b.to_account_info(), amount_b)?;
+    let liquidity = minted_liquidity(
+        &ctx.accounts.pool,
+        received_a,
+        received_b,
+        (reserve_a as u64, reserve_b as u64),
+        ctx.accounts.mint_liquidity.supply,
+    )?;
+    if liquidity == 0 {
+        return Ok(());
+    }
+    Pool::lock(&mut ctx.accounts.pool)?;
+
+    // 2. 金库把两种代币转入池子
+    let vault_seeds = &[
+        ctx.accounts.vault.pool.as_ref(),
+        VAULT_SEED,
+        &[ctx.bumps.vault],
+    ];
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program_a.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.vault_account_a.to_account_info(),
+                mint: ctx.accounts.mint_a.to_account_info(),
+                to: ctx.accounts.pool_account_a.to_account_info(),
+                authority: ctx.accounts.vault.to_account_info(),
+            },
+            &[&vault_seeds[..]],
+        ),
+        amount_a,
+        ctx.accounts.mint_a.decimals,
+    )?;
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program_b.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.vault_account_b.to_account_info(),
+                mint: ctx.accounts.mint_b.to_account_info(),
+                to: ctx.accounts.pool_account_b.to_account_info(),
+                authority: ctx.accounts.vault.to_account_info(),
+            },
+            &[&vault_seeds[..]],
+        ),
+        amount_b,
+        ctx.accounts.mint_b.decimals,
+    )?;
+
+    // 3. 铸造流动性代币到金库
+    let authority_bump = ctx.bumps.pool_authority;
+    let authority_seeds = &[
+        &ctx.accounts.pool.amm.to_bytes(),
+        &ctx.accounts.mint_a.key().to_bytes(),
+        &ctx.accounts.mint_b.key().to_bytes(),
+        &ctx.accounts.pool.fee_tier.to_le_bytes()[..],
+        AUTHORITY_SEED,
+        &[authority_bump],
+    ];
+    token_interface::mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.mint_liquidity.to_account_info(),
+                to: ctx.accounts.lp_vault.to_account_info(),
+                authority: ctx.accounts.pool_authority.to_account_info(),
+            },
+            &[&authority_seeds[..]],
+        ),
+        liquidity,
+    )?;
+
+    ctx.accounts.pool.stats.record_deposit();
+    ctx.accounts.pool.add_reserves(received_a, received_b)?;
+
+    ctx.accounts.lp_vault.reload()?;
+    emit!(VaultCompoundedEvent {
+        vault: ctx.accounts.vault.key(),
+        amount_a,
+        amount_b,
+        liquidity,
+        total_liquidity: ctx.accounts.lp_vault.amount,
+    });
+    ctx.accounts.pool.unlock();
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateVault<'info> {
+    #[account(
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+        has_one = admin,
+    )]
+    pub amm: Box<Account<'info, Amm>>,
+
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.key().as_ref(),
+            pool.mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+        ],
+        bump,
+        has_one = amm,
+        has_one = mint_a,
+        has_one = mint_b,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = Vault::LEN,
+        seeds = [
+            pool.key().as_ref(),
+            VAULT_SEED,
+        ],
+        bump,
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            mint_a.key().as_ref(),
+            mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+            LIQUIDITY_SEED,
+        ],
+        bump,
+    )]
+    pub mint_liquidity: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [
+            vault.key().as_ref(),
+            VAULT_SHARE_SEED,
+        ],
+        bump,
+        mint::decimals = mint_liquidity.decimals,
+        mint::authority = vault,
+        mint::token_program = token_program,
+    )]
+    pub share_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [
+            vault.key().as_ref(),
+            ESCROW_SEED,
+        ],
+        bump,
+        token::mint = mint_liquidity,
+        token::authority = vault,
+        token::token_program = token_program,
+    )]
+    pub lp_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mint::token_program = token_program_a)]
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_program_b)]
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
+
+    /// 金库收取待复投代币的账户，任何人都可以向其转入
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = mint_a,
+        associated_token::authority = vault,
+        associated_token::token_program = token_program_a,
+    )]
+    pub vault_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = mint_b,
+        associated_token::authority = vault,
+        associated_token::token_program = token_program_b,
+    )]
+    pub vault_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The admin of the AMM
+    pub admin: Signer<'info>,
+
+    /// The account paying for all rents
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Solana ecosystem accounts
+    pub token_program: Program<'info, Token>,
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VaultDeposit<'info> {
+    #[account(
+        seeds = [
+            vault.pool.as_ref(),
+            VAULT_SEED,
+        ],
+        bump,
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    #[account(
+        mut,
+        address = vault.share_mint,
+    )]
+    pub share_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        address = vault.lp_vault,
+    )]
+    pub lp_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = lp_vault.mint)]
+    pub mint_liquidity: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The LP depositing its liquidity and paying for all rents
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = mint_liquidity,
+        token::authority = owner,
+    )]
+    pub owner_account_liquidity: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = share_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program,
+    )]
+    pub owner_account_shares: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Solana ecosystem accounts
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VaultWithdraw<'info> {
+    #[account(
+        seeds = [
+            vault.pool.as_ref(),
+            VAULT_SEED,
+        ],
+        bump,
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    #[account(
+        mut,
+        address = vault.share_mint,
+    )]
+    pub share_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        address = vault.lp_vault,
+    )]
+    pub lp_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = lp_vault.mint)]
+    pub mint_liquidity: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The owner of the vault shares
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = mint_liquidity,
+        token::authority = owner,
+    )]
+    pub owner_account_liquidity: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = share_mint,
+        token::authority = owner,
+    )]
+    pub owner_account_shares: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Solana ecosystem accounts
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CompoundVault<'info> {
+    #[account(
+        mut,
+        seeds = [
+            vault.pool.as_ref(),
+            VAULT_SEED,
+        ],
+        bump,
+        has_one = pool,
+        has_one = lp_vault,
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    #[account(
+        mut,
+        has_one = mint_a,
+        has_one = mint_b,
+        constraint = !pool.flash_loan.active @ TutorialError::FlashLoanActive,
+        constraint = !pool.locked @ TutorialError::PoolLocked,
+        constraint = !pool.status.is_frozen() @ TutorialError::PoolStatusRestricted,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// CHECK: Read only authority
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            mint_a.key().as_ref(),
+            mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+            AUTHORITY_SEED,
+        ],
+        bump,
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool.amm.as_ref(),
+            mint_a.key().as_ref(),
+            mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+            LIQUIDITY_SEED,
+        ],
+        bump,
+    )]
+    pub mint_liquidity: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_program_a)]
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_program_b)]
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_a,
+    )]
+    pub pool_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_b,
+        associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_b,
+    )]
+    pub pool_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = vault,
+        associated_token::token_program = token_program_a,
+    )]
+    pub vault_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_b,
+        associated_token::authority = vault,
+        associated_token::token_program = token_program_b,
+    )]
+    pub vault_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub lp_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Solana ecosystem accounts
+    pub token_program: Program<'info, Token>,
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
+}
@@ -1,84 +1,173 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{self, Burn, Mint, Token, TokenAccount, Transfer},
+    token::Token,
+    token_interface::{self, Burn, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked},
 };
-use fixed::types::I64F64;
 
 use crate::{
-    constants::{AUTHORITY_SEED, LIQUIDITY_SEED, MINIMUM_LIQUIDITY},
-    state::{Amm, Pool},
+    constants::{AUTHORITY_SEED, DEPOSIT_RECORD_SEED, LIQUIDITY_SEED, MINIMUM_LIQUIDITY},
+    errors::TutorialError,
+    events::WithdrawEvent,
+    state::{Amm, DepositRecord, Pool},
+    models::withdrawal_fee::WithdrawalFeeConfig,
+    utils::check_deadline,
 };
 
 // 拆分指令，第一步：加载必要的账户
-pub fn withdraw_liquidity(ctx: Context<WithdrawLiquidity>, amount: u64) -> Result<()> {
+pub fn withdraw_liquidity(
+    mut ctx: Context<WithdrawLiquidity>,
+    amount: u64,
+    min_amount_a: u64,
+    min_amount_b: u64,
+    deadline: Option<i64>,
+) -> Result<()> {
+    // 拒绝已过期的交易
+    check_deadline(deadline)?;
+
     // 继续到第二步
-    withdraw_liquidity_process(ctx, amount)
+    withdraw_liquidity_process(&mut ctx, amount, min_amount_a, min_amount_b)
+}
+
+/// 按比例提取：在链上读取LP代币账户的实时余额，销毁其中bps对应的份额，
+/// 避免前端按过期余额构造“提取50%”之类的交易
+pub fn withdraw_liquidity_pct(
+    mut ctx: Context<WithdrawLiquidity>,
+    bps: u16,
+    min_amount_a: u64,
+    min_amount_b: u64,
+    deadline: Option<i64>,
+) -> Result<()> {
+    check_deadline(deadline)?;
+    if bps == 0 || bps > 10000 {
+        return err!(TutorialError::InvalidWithdrawPct);
+    }
+
+    let balance = ctx.accounts.depositor_token_accounts.depositor_account_liquidity.amount;
+    let amount = (balance as u128 * bps as u128 / 10000) as u64;
+    withdraw_liquidity_process(&mut ctx, amount, min_amount_a, min_amount_b)
+}
+
+/// 提取全部流动性并关闭已清空的LP代币账户，租金退还给存款人
+pub fn withdraw_all_and_close(
+    mut ctx: Context<WithdrawLiquidity>,
+    min_amount_a: u64,
+    min_amount_b: u64,
+    deadline: Option<i64>,
+) -> Result<()> {
+    check_deadline(deadline)?;
+
+    let amount = ctx.accounts.depositor_token_accounts.depositor_account_liquidity.amount;
+    withdraw_liquidity_process(&mut ctx, amount, min_amount_a, min_amount_b)?;
+
+    token_interface::close_account(CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.depositor_token_accounts.depositor_account_liquidity.to_account_info(),
+            destination: ctx.accounts.depositor.to_account_info(),
+            authority: ctx.accounts.depositor.to_account_info(),
+        },
+    ))
+}
+
+/// 销毁amount个流动性代币可以提取的数量，已扣除提取费
+pub(crate) fn withdraw_amounts(
+    amount: u64,
+    (reserve_a, reserve_b): (u64, u64),
+    supply: u64,
+    withdrawal_fee_bps: u16,
+) -> Result<(u64, u64)> {
+    // 使用u128计算份额，避免大额池子溢出
+    let total_liquidity = supply as u128 + MINIMUM_LIQUIDITY as u128;
+    let share = |reserve: u64| {
+        (amount as u128)
+            .checked_mul(reserve as u128)
+            .and_then(|v| v.checked_div(total_liquidity))
+            .and_then(|v| u64::try_from(v).ok())
+            .ok_or(TutorialError::MathOverflow)
+    };
+    Ok((
+        WithdrawalFeeConfig::apply(share(reserve_a)?, withdrawal_fee_bps),
+        WithdrawalFeeConfig::apply(share(reserve_b)?, withdrawal_fee_bps),
+    ))
 }
 
 // 处理流动性提取逻辑
-fn withdraw_liquidity_process(ctx: Context<WithdrawLiquidity>, amount: u64) -> Result<()> {
+pub(crate) fn withdraw_liquidity_process(
+    ctx: &mut Context<WithdrawLiquidity>,
+    amount: u64,
+    min_amount_a: u64,
+    min_amount_b: u64,
+) -> Result<()> {
+    Pool::lock(&mut ctx.accounts.pool)?;
+
+    // 按份额比例提取，与池子的定价曲线无关
     // 1. Calculate the seeds
     let authority_bump = ctx.bumps.pool_authority;
     let authority_seeds = &[
         &ctx.accounts.pool.amm.to_bytes(),
         &ctx.accounts.mint_a.key().to_bytes(),
         &ctx.accounts.mint_b.key().to_bytes(),
+        &ctx.accounts.pool.fee_tier.to_le_bytes()[..],
         AUTHORITY_SEED,
         &[authority_bump],
     ];
     let signer_seeds = &[&authority_seeds[..]];
 
     // Transfer tokens from the pool
-    let amount_a = I64F64::from_num(amount)
-    .checked_mul(I64F64::from_num(ctx.accounts.pool_token_accounts.pool_account_a.amount))
-    .unwrap()
-    .checked_div(I64F64::from_num(
-        ctx.accounts.mint_liquidity.supply + MINIMUM_LIQUIDITY,
-    ))
-    .unwrap()
-    .floor()
-    .to_num::<u64>();
+    let (reserve_a, reserve_b) = ctx.accounts.pool.reserves();
+    // 按持有时间收取提取费，提取费留在池子里
+    let now = Clock::get()?.unix_timestamp;
+    let withdrawal_fee_bps = DepositRecord::withdrawal_fee_bps(
+        ctx.accounts.deposit_record.as_deref().map(|record| &**record),
+        &ctx.accounts.amm.withdrawal_fee_config,
+        amount,
+        now,
+    );
+    let (amount_a, amount_b) =
+        withdraw_amounts(amount, (reserve_a, reserve_b), ctx.accounts.mint_liquidity.supply, withdrawal_fee_bps)?;
 
-    token::transfer(
+    // 滑点保护：提取数量低于用户设定的下限时拒绝
+    if amount_a < min_amount_a {
+        return err!(TutorialError::WithdrawAmountATooSmall);
+    }
+    if amount_b < min_amount_b {
+        return err!(TutorialError::WithdrawAmountBTooSmall);
+    }
+
+    token_interface::transfer_checked(
         CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            ctx.accounts.token_program_a.to_account_info(),
+            TransferChecked {
                 from: ctx.accounts.pool_token_accounts.pool_account_a.to_account_info(),
+                mint: ctx.accounts.mint_a.to_account_info(),
                 to: ctx.accounts.depositor_token_accounts.depositor_account_a.to_account_info(),
                 authority: ctx.accounts.pool_authority.to_account_info(),
             },
             signer_seeds,
         ),
         amount_a,
+        ctx.accounts.mint_a.decimals,
     )?;
 
-    let amount_b = I64F64::from_num(amount)
-    .checked_mul(I64F64::from_num(ctx.accounts.pool_token_accounts.pool_account_b.amount))
-    .unwrap()
-    .checked_div(I64F64::from_num(
-        ctx.accounts.mint_liquidity.supply + MINIMUM_LIQUIDITY,
-    ))
-    .unwrap()
-    .floor()
-    .to_num::<u64>();
-    
-    token::transfer(
+    token_interface::transfer_checked(
         CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            ctx.accounts.token_program_b.to_account_info(),
+            TransferChecked {
                 from: ctx.accounts.pool_token_accounts.pool_account_b.to_account_info(),
+                mint: ctx.accounts.mint_b.to_account_info(),
                 to: ctx.accounts.depositor_token_accounts.depositor_account_b.to_account_info(),
                 authority: ctx.accounts.pool_authority.to_account_info(),
             },
             signer_seeds,
         ),
         amount_b,
+        ctx.accounts.mint_b.decimals,
     )?;
 
     // Burn the liquidity tokens
     // It will fail if the amount is invalid
-    token::burn(
+    token_interface::burn(
         CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
             Burn {
@@ -90,6 +179,26 @@ fn withdraw_liquidity_process(ctx: Context<WithdrawLiquidity>, amount: u64) -> R
         amount,
     )?;
 
+    if let Some(deposit_record) = ctx.accounts.deposit_record.as_mut() {
+        deposit_record.record_withdrawal(amount);
+    }
+    ctx.accounts.pool.stats.record_withdraw();
+
+    // 储备只减少转出的数量，提取费留在储备中
+    ctx.accounts.pool.remove_reserves(amount_a, amount_b)?;
+    let (reserve_a, reserve_b) = ctx.accounts.pool.reserves();
+    emit!(WithdrawEvent {
+        pool: ctx.accounts.pool.key(),
+        depositor: ctx.accounts.depositor.key(),
+        amount_a,
+        amount_b,
+        liquidity: amount,
+        reserve_a,
+        reserve_b,
+        withdrawal_fee_bps,
+    });
+    ctx.accounts.pool.unlock();
+
     Ok(())
 }
 
@@ -106,14 +215,20 @@ pub struct WithdrawLiquidity<'info> {
     pub amm: Box<Account<'info, Amm>>,
 
     #[account(
+        mut,
         seeds = [
             pool.amm.as_ref(),
             pool.mint_a.key().as_ref(),
             pool.mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
         ],
         bump,
+        has_one = amm,
         has_one = mint_a,
         has_one = mint_b,
+        constraint = !pool.flash_loan.active @ TutorialError::FlashLoanActive,
+        constraint = !pool.locked @ TutorialError::PoolLocked,
+        constraint = pool.status.allows_withdrawals() @ TutorialError::PoolStatusRestricted,
     )]
     pub pool: Box<Account<'info, Pool>>,
 
@@ -123,13 +238,15 @@ pub struct WithdrawLiquidity<'info> {
             pool.amm.as_ref(),
             mint_a.key().as_ref(),
             mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
             AUTHORITY_SEED,
         ],
         bump,
     )]
     pub pool_authority: AccountInfo<'info>,
 
-    /// The account paying for all rents
+    /// The account paying for all rents, also receives the rent of a closed LP token account
+    #[account(mut)]
     pub depositor: Signer<'info>,
 
     #[account(
@@ -138,44 +255,78 @@ pub struct WithdrawLiquidity<'info> {
             pool.amm.as_ref(),
             mint_a.key().as_ref(),
             mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
             LIQUIDITY_SEED,
         ],
         bump,
     )]
-    pub mint_liquidity: Box<Account<'info, Mint>>,
+    pub mint_liquidity: Box<InterfaceAccount<'info, Mint>>,
 
-    pub mint_a: Box<Account<'info, Mint>>,
+    #[account(mint::token_program = token_program_a)]
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
 
-    pub mint_b: Box<Account<'info, Mint>>,
+    #[account(mint::token_program = token_program_b)]
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
 
-    // 分组池账户
-    pub pool_token_accounts: PoolTokenAccounts<'info>,
+    // 分组池账户，其中用于约束的账户必须与上面已校验的账户一致
+    #[account(
+        constraint = pool_token_accounts.mint_a.key() == mint_a.key() @ TutorialError::NestedAccountMismatch,
+        constraint = pool_token_accounts.mint_b.key() == mint_b.key() @ TutorialError::NestedAccountMismatch,
+        constraint = pool_token_accounts.pool_authority.key() == pool_authority.key() @ TutorialError::NestedAccountMismatch,
+        constraint = pool_token_accounts.token_program_a.key() == token_program_a.key() @ TutorialError::NestedAccountMismatch,
+        constraint = pool_token_accounts.token_program_b.key() == token_program_b.key() @ TutorialError::NestedAccountMismatch,
+    )]
+    pub pool_token_accounts: WithdrawPoolAccounts<'info>,
     
     // 分组用户账户
+    #[account(
+        constraint = depositor_token_accounts.mint_liquidity.key() == mint_liquidity.key() @ TutorialError::NestedAccountMismatch,
+        constraint = depositor_token_accounts.mint_a.key() == mint_a.key() @ TutorialError::NestedAccountMismatch,
+        constraint = depositor_token_accounts.mint_b.key() == mint_b.key() @ TutorialError::NestedAccountMismatch,
+        constraint = depositor_token_accounts.depositor.key() == depositor.key() @ TutorialError::NestedAccountMismatch,
+        constraint = depositor_token_accounts.token_program_a.key() == token_program_a.key() @ TutorialError::NestedAccountMismatch,
+        constraint = depositor_token_accounts.token_program_b.key() == token_program_b.key() @ TutorialError::NestedAccountMismatch,
+    )]
     pub depositor_token_accounts: DepositorTokenAccounts<'info>,
 
+    /// LP的存款记录，提供时记录按流动性加权的存入时间，用于计算提取费
+    #[account(
+        mut,
+        seeds = [
+            pool.key().as_ref(),
+            depositor.key().as_ref(),
+            DEPOSIT_RECORD_SEED,
+        ],
+        bump,
+    )]
+    pub deposit_record: Option<Box<Account<'info, DepositRecord>>>,
+
     /// Solana ecosystem accounts
     pub token_program: Program<'info, Token>,
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
 // 池代币账户
 #[derive(Accounts)]
-pub struct PoolTokenAccounts<'info> {
+pub struct WithdrawPoolAccounts<'info> {
     #[account(
         mut,
         associated_token::mint = mint_a,
         associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_a,
     )]
-    pub pool_account_a: Box<Account<'info, TokenAccount>>,
+    pub pool_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
         associated_token::mint = mint_b,
         associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_b,
     )]
-    pub pool_account_b: Box<Account<'info, TokenAccount>>,
+    pub pool_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
     
     /// CHECK: Used in constraints
     pub mint_a: AccountInfo<'info>,
@@ -185,6 +336,9 @@ pub struct PoolTokenAccounts<'info> {
     
     /// CHECK: Used in constraints
     pub pool_authority: AccountInfo<'info>,
+
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
 }
 
 // 存款人代币账户
@@ -194,24 +348,27 @@ pub struct DepositorTokenAccounts<'info> {
         mut,
         associated_token::mint = mint_liquidity,
         associated_token::authority = depositor,
+        associated_token::token_program = token_program,
     )]
-    pub depositor_account_liquidity: Box<Account<'info, TokenAccount>>,
+    pub depositor_account_liquidity: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         init_if_needed,
         payer = payer,
         associated_token::mint = mint_a,
         associated_token::authority = depositor,
+        associated_token::token_program = token_program_a,
     )]
-    pub depositor_account_a: Box<Account<'info, TokenAccount>>,
+    pub depositor_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         init_if_needed,
         payer = payer,
         associated_token::mint = mint_b,
         associated_token::authority = depositor,
+        associated_token::token_program = token_program_b,
     )]
-    pub depositor_account_b: Box<Account<'info, TokenAccount>>,
+    pub depositor_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
     
     /// CHECK: Used in constraints
     pub mint_liquidity: AccountInfo<'info>,
@@ -231,6 +388,8 @@ pub struct DepositorTokenAccounts<'info> {
     
     // 必须添加这些程序账户以实现init_if_needed约束
     pub token_program: Program<'info, Token>,
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
\ No newline at end of file
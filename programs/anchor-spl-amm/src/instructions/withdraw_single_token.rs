@@ -0,0 +1,281 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token::Token,
+    token_interface::{self, Burn, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    constants::{AUTHORITY_SEED, DEPOSIT_RECORD_SEED, LIQUIDITY_SEED, MINIMUM_LIQUIDITY, POOL_ORACLE_SEED},
+    errors::TutorialError,
+    events::WithdrawEvent,
+    instructions::swap_fee_rate_bps,
+    models::{curve::CurveCalculator, withdrawal_fee::WithdrawalFeeConfig},
+    state::{Amm, DepositRecord, Pool, PoolOracle},
+    utils::{check_deadline, get_transfer_fee},
+};
+
+/// 单币提取：按份额比例提取两种代币，再在池内把不需要的一侧换成需要的代币
+pub fn withdraw_single_token(
+    ctx: Context<WithdrawSingleToken>,
+    amount_lp: u64,
+    want_a: bool,
+    min_out: u64,
+    deadline: Option<i64>,
+) -> Result<()> {
+    check_deadline(deadline)?;
+    Pool::lock(&mut ctx.accounts.pool)?;
+
+    // 1. 校验接收账户的代币种类
+    let mint_out = if want_a { &ctx.accounts.mint_a } else { &ctx.accounts.mint_b };
+    if ctx.accounts.depositor_account.mint != mint_out.key() {
+        return err!(TutorialError::InvalidMint);
+    }
+
+    // 2. 按份额比例计算两侧可提取的数量
+    let (pool_a, pool_b) = ctx.accounts.pool.reserves();
+    let total_liquidity = ctx.accounts.mint_liquidity.supply as u128 + MINIMUM_LIQUIDITY as u128;
+    let share = |reserve: u64| {
+        (amount_lp as u128)
+            .checked_mul(reserve as u128)
+            .and_then(|v| v.checked_div(total_liquidity))
+            .and_then(|v| u64::try_from(v).ok())
+            .ok_or(TutorialError::MathOverflow)
+    };
+    // 按持有时间收取提取费，提取费留在池子里
+    let now = Clock::get()?.unix_timestamp;
+    let withdrawal_fee_bps = DepositRecord::withdrawal_fee_bps(
+        ctx.accounts.deposit_record.as_deref().map(|record| &**record),
+        &ctx.accounts.amm.withdrawal_fee_config,
+        amount_lp,
+        now,
+    );
+    let amount_a = WithdrawalFeeConfig::apply(share(pool_a)?, withdrawal_fee_bps);
+    let amount_b = WithdrawalFeeConfig::apply(share(pool_b)?, withdrawal_fee_bps);
+
+    // 3. 把另一侧按提取后的储备在池内交换，手续费留在池子里归剩余LP所有
+    let remaining_a = pool_a.checked_sub(amount_a).ok_or(TutorialError::MathOverflow)?;
+    let remaining_b = pool_b.checked_sub(amount_b).ok_or(TutorialError::MathOverflow)?;
+    let (amount_keep, amount_swap, reserve_in, reserve_out) = if want_a {
+        (amount_a, amount_b, remaining_b, remaining_a)
+    } else {
+        (amount_b, amount_a, remaining_a, remaining_b)
+    };
+    let fee_rate_bps = swap_fee_rate_bps(
+        &ctx.accounts.amm,
+        &ctx.accounts.pool,
+        ctx.accounts.pool_oracle.as_deref().map(|oracle| &**oracle),
+        !want_a,
+        amount_swap,
+        (reserve_in, reserve_out),
+    );
+    let fee_amount = (amount_swap as u128 * fee_rate_bps as u128 / 10000) as u64;
+    let swap_output = CurveCalculator::swap_output(
+        &ctx.accounts.pool.curve_type,
+        !want_a,
+        amount_swap - fee_amount,
+        reserve_in,
+        reserve_out,
+    )?;
+    let output = amount_keep.checked_add(swap_output).ok_or(TutorialError::MathOverflow)?;
+
+    // 4. 滑点保护：按用户实际收到的数量（扣除转账费）比较
+    let net_output = output - get_transfer_fee(&mint_out.to_account_info(), output)?;
+    if net_output < min_out {
+        return err!(TutorialError::OutputTooSmall);
+    }
+
+    // 5. 转出需要的代币
+    let authority_bump = ctx.bumps.pool_authority;
+    let authority_seeds = &[
+        &ctx.accounts.pool.amm.to_bytes(),
+        &ctx.accounts.mint_a.key().to_bytes(),
+        &ctx.accounts.mint_b.key().to_bytes(),
+        &ctx.accounts.pool.fee_tier.to_le_bytes()[..],
+        AUTHORITY_SEED,
+        &[authority_bump],
+    ];
+    let signer_seeds = &[&authority_seeds[..]];
+    let (token_program, pool_account) = if want_a {
+        (&ctx.accounts.token_program_a, &ctx.accounts.pool_account_a)
+    } else {
+        (&ctx.accounts.token_program_b, &ctx.accounts.pool_account_b)
+    };
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: pool_account.to_account_info(),
+                mint: mint_out.to_account_info(),
+                to: ctx.accounts.depositor_account.to_account_info(),
+                authority: ctx.accounts.pool_authority.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        output,
+        mint_out.decimals,
+    )?;
+
+    // 6. 销毁流动性代币
+    token_interface::burn(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.mint_liquidity.to_account_info(),
+                from: ctx.accounts.depositor_account_liquidity.to_account_info(),
+                authority: ctx.accounts.depositor.to_account_info(),
+            },
+        ),
+        amount_lp,
+    )?;
+
+    if let Some(deposit_record) = ctx.accounts.deposit_record.as_mut() {
+        deposit_record.record_withdrawal(amount_lp);
+    }
+
+    // 7. 单币提取会改变池子价格，交易后的价格不能超出聚合流动性区间，同步更新预言机
+    //    储备只减少转出的数量，直接转入池子的代币不会并入储备
+    let pool = &mut ctx.accounts.pool;
+    if want_a {
+        pool.remove_reserves(output, 0)?;
+    } else {
+        pool.remove_reserves(0, output)?;
+    }
+    let (reserve_a, reserve_b) = pool.reserves();
+    pool.price_range.check(reserve_a, reserve_b)?;
+    pool.oracle.update(reserve_a, reserve_b, now);
+    pool.stats.record_volume(!want_a, amount_swap, swap_output, fee_amount, now);
+    pool.stats.record_withdraw();
+
+    emit!(WithdrawEvent {
+        pool: ctx.accounts.pool.key(),
+        depositor: ctx.accounts.depositor.key(),
+        amount_a: if want_a { output } else { 0 },
+        amount_b: if want_a { 0 } else { output },
+        liquidity: amount_lp,
+        reserve_a,
+        reserve_b,
+        withdrawal_fee_bps,
+    });
+    ctx.accounts.pool.unlock();
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WithdrawSingleToken<'info> {
+    #[account(
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump,
+    )]
+    pub amm: Box<Account<'info, Amm>>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.key().as_ref(),
+            pool.mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+        ],
+        bump,
+        has_one = amm,
+        has_one = mint_a,
+        has_one = mint_b,
+        constraint = !pool.flash_loan.active @ TutorialError::FlashLoanActive,
+        constraint = !pool.locked @ TutorialError::PoolLocked,
+        constraint = pool.status.allows_withdrawals() @ TutorialError::PoolStatusRestricted,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// 池子的波动率预言机，动态费率按其中的波动率计算，未提供时按无波动处理
+    #[account(
+        seeds = [
+            pool.key().as_ref(),
+            POOL_ORACLE_SEED,
+        ],
+        bump,
+    )]
+    pub pool_oracle: Option<Box<Account<'info, PoolOracle>>>,
+
+    /// CHECK: Read only authority
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            mint_a.key().as_ref(),
+            mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+            AUTHORITY_SEED,
+        ],
+        bump,
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    /// The account burning its liquidity tokens
+    pub depositor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool.amm.as_ref(),
+            mint_a.key().as_ref(),
+            mint_b.key().as_ref(),
+            pool.fee_tier.to_le_bytes().as_ref(),
+            LIQUIDITY_SEED,
+        ],
+        bump,
+    )]
+    pub mint_liquidity: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_program_a)]
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_program_b)]
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_a,
+    )]
+    pub pool_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_b,
+        associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_b,
+    )]
+    pub pool_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_liquidity,
+        associated_token::authority = depositor,
+        associated_token::token_program = token_program,
+    )]
+    pub depositor_account_liquidity: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The depositor's account receiving the requested token
+    #[account(mut)]
+    pub depositor_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// LP的存款记录，提供时记录按流动性加权的存入时间，用于计算提取费
+    #[account(
+        mut,
+        seeds = [
+            pool.key().as_ref(),
+            depositor.key().as_ref(),
+            DEPOSIT_RECORD_SEED,
+        ],
+        bump,
+    )]
+    pub deposit_record: Option<Box<Account<'info, DepositRecord>>>,
+
+    /// Solana ecosystem accounts
+    pub token_program: Program<'info, Token>,
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
+}
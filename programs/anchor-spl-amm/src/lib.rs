@@ -2,13 +2,23 @@
 
 use anchor_lang::prelude::*;
 
-mod constants;
+pub mod constants;
 mod errors;
+pub mod events;
 mod instructions;
-mod models;
-mod state;
+pub mod models;
+pub mod state;
+mod utils;
 
 use instructions::*;
+pub use instructions::quote_exact_input;
+use models::{
+    apr::PoolApr, batch_swap::BatchSwapLeg, concentrated_liquidity::ConcentratedLiquidityConfig, curve::CurveType,
+    fee_strategy::FeeConfig, governance::GovernanceAction, liquidity_lock::LiquidityBalance, lp_metadata::LpMetadata, mint_policy::{MintListMode, MintPolicy},
+    oracle::TwapPrice, oracle_guard::OracleGuardConfig, pool_status::PoolStatus, program_config::FeatureFlags, quote::{DepositPreview, LpTokenValue, SwapQuote, WithdrawPreview}, roles::Role, swap_options::SwapOptions,
+    timelock::ParameterChange,
+    volatility::VolatilityConfig, withdrawal_fee::WithdrawalFeeConfig,
+};
 
 declare_id!("5pCZ4MZ1BU4FSx7zWxCtAQ5vyhxWLikoZpLV6biPG8Rj");
 
@@ -21,28 +31,605 @@ pub mod anchor_spl_amm {
         instructions::create_amm(ctx, id, fee)
     }
 
-    pub fn create_pool(ctx: Context<CreatePool>, initial_price: u64) -> Result<()> {
-        instructions::create_pool(ctx, initial_price)
+    pub fn create_pool(
+        ctx: Context<CreatePool>,
+        initial_price: u64,
+        curve_type: CurveType,
+        fee_tier: u16,
+        observation_count: u16,
+        lp_metadata: Option<LpMetadata>,
+    ) -> Result<()> {
+        instructions::create_pool(ctx, initial_price, curve_type, fee_tier, observation_count, lp_metadata)
     }
 
     pub fn deposit_liquidity(
         ctx: Context<DepositLiquidity>,
         amount_a: u64,
         amount_b: u64,
+        min_lp_tokens_out: u64,
+        deadline: Option<i64>,
+        max_ratio_deviation_bps: Option<u16>,
+        clamp_to_balance: bool,
     ) -> Result<()> {
-        instructions::deposit_liquidity(ctx, amount_a, amount_b)
+        instructions::deposit_liquidity(ctx, amount_a, amount_b, min_lp_tokens_out, deadline, max_ratio_deviation_bps, clamp_to_balance)
     }
 
-    pub fn withdraw_liquidity(ctx: Context<WithdrawLiquidity>, amount: u64) -> Result<()> {
-        instructions::withdraw_liquidity(ctx, amount)
+    pub fn deposit_single_sided(
+        ctx: Context<DepositSingleSided>,
+        deposit_a: bool,
+        amount: u64,
+        min_lp_tokens_out: u64,
+        deadline: Option<i64>,
+        clamp_to_balance: bool,
+    ) -> Result<()> {
+        instructions::deposit_single_sided(ctx, deposit_a, amount, min_lp_tokens_out, deadline, clamp_to_balance)
+    }
+
+    pub fn withdraw_liquidity(
+        ctx: Context<WithdrawLiquidity>,
+        amount: u64,
+        min_amount_a: u64,
+        min_amount_b: u64,
+        deadline: Option<i64>,
+    ) -> Result<()> {
+        instructions::withdraw_liquidity(ctx, amount, min_amount_a, min_amount_b, deadline)
     }
 
-    pub fn swap_exact_tokens_for_tokens(
-        ctx: Context<SwapExactTokensForTokens>,
-        swap_a: bool,
+    pub fn withdraw_liquidity_pct(
+        ctx: Context<WithdrawLiquidity>,
+        bps: u16,
+        min_amount_a: u64,
+        min_amount_b: u64,
+        deadline: Option<i64>,
+    ) -> Result<()> {
+        instructions::withdraw_liquidity_pct(ctx, bps, min_amount_a, min_amount_b, deadline)
+    }
+
+    pub fn withdraw_all_and_close(
+        ctx: Context<WithdrawLiquidity>,
+        min_amount_a: u64,
+        min_amount_b: u64,
+        deadline: Option<i64>,
+    ) -> Result<()> {
+        instructions::withdraw_all_and_close(ctx, min_amount_a, min_amount_b, deadline)
+    }
+
+    pub fn withdraw_single_token(
+        ctx: Context<WithdrawSingleToken>,
+        amount_lp: u64,
+        want_a: bool,
+        min_out: u64,
+        deadline: Option<i64>,
+    ) -> Result<()> {
+        instructions::withdraw_single_token(ctx, amount_lp, want_a, min_out, deadline)
+    }
+
+    pub fn swap_exact_tokens_for_tokens<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SwapExactTokensForTokens<'info>>,
+        input_mint: Pubkey,
         input_amount: u64,
         min_output_amount: u64,
+        deadline: Option<i64>,
+        options: SwapOptions,
+    ) -> Result<()> {
+        instructions::swap_exact_tokens_for_tokens(ctx, input_mint, input_amount, min_output_amount, deadline, options)
+    }
+
+    pub fn swap_tokens_for_exact_tokens<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SwapExactTokensForTokens<'info>>,
+        input_mint: Pubkey,
+        output_amount: u64,
+        max_input_amount: u64,
+        deadline: Option<i64>,
+        max_price_x64: Option<u128>,
     ) -> Result<()> {
-        instructions::swap_exact_tokens_for_tokens(ctx, swap_a, input_amount, min_output_amount)
+        instructions::swap_tokens_for_exact_tokens(ctx, input_mint, output_amount, max_input_amount, deadline, max_price_x64)
+    }
+
+    pub fn quote_swap(ctx: Context<QuoteSwap>, input_mint: Pubkey, input_amount: u64) -> Result<SwapQuote> {
+        instructions::quote_swap(ctx, input_mint, input_amount)
+    }
+
+    pub fn swap_route<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SwapRoute<'info>>,
+        input_amount: u64,
+        min_output_amount: u64,
+        deadline: Option<i64>,
+        clamp_to_balance: bool,
+    ) -> Result<()> {
+        instructions::swap_route(ctx, input_amount, min_output_amount, deadline, clamp_to_balance)
+    }
+
+    pub fn swap_exact_tokens_for_tokens_native<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SwapExactTokensForTokens<'info>>,
+        input_mint: Pubkey,
+        input_amount: u64,
+        min_output_amount: u64,
+        deadline: Option<i64>,
+    ) -> Result<()> {
+        instructions::swap_exact_tokens_for_tokens_native(ctx, input_mint, input_amount, min_output_amount, deadline)
+    }
+
+    pub fn deposit_liquidity_native(
+        ctx: Context<DepositLiquidity>,
+        amount_a: u64,
+        amount_b: u64,
+        min_lp_tokens_out: u64,
+        deadline: Option<i64>,
+        clamp_to_balance: bool,
+    ) -> Result<()> {
+        instructions::deposit_liquidity_native(ctx, amount_a, amount_b, min_lp_tokens_out, deadline, clamp_to_balance)
+    }
+
+    pub fn withdraw_liquidity_native(
+        ctx: Context<WithdrawLiquidity>,
+        amount: u64,
+        min_amount_a: u64,
+        min_amount_b: u64,
+        deadline: Option<i64>,
+    ) -> Result<()> {
+        instructions::withdraw_liquidity_native(ctx, amount, min_amount_a, min_amount_b, deadline)
+    }
+
+    pub fn update_fee_config(ctx: Context<UpdateFeeConfig>, fee_config: FeeConfig) -> Result<()> {
+        instructions::update_fee_config(ctx, fee_config)
+    }
+
+    pub fn set_fee_recipient(ctx: Context<SetFeeRecipient>) -> Result<()> {
+        instructions::set_fee_recipient(ctx)
+    }
+
+    pub fn collect_protocol_fees(ctx: Context<CollectProtocolFees>) -> Result<()> {
+        instructions::collect_protocol_fees(ctx)
+    }
+
+    pub fn close_pool(ctx: Context<ClosePool>) -> Result<()> {
+        instructions::close_pool(ctx)
+    }
+
+    pub fn flash_borrow(ctx: Context<FlashLoan>, amount_a: u64, amount_b: u64) -> Result<()> {
+        instructions::flash_borrow(ctx, amount_a, amount_b)
+    }
+
+    pub fn flash_repay(ctx: Context<FlashLoan>) -> Result<()> {
+        instructions::flash_repay(ctx)
+    }
+
+    pub fn create_il_compensation_vault(ctx: Context<CreateIlCompensationVault>) -> Result<()> {
+        instructions::create_il_compensation_vault(ctx)
+    }
+
+    pub fn register_il_position(ctx: Context<RegisterIlPosition>, amount: u64) -> Result<()> {
+        instructions::register_il_position(ctx, amount)
+    }
+
+    pub fn close_il_position(ctx: Context<CloseIlPosition>) -> Result<()> {
+        instructions::close_il_position(ctx)
+    }
+
+    pub fn claim_il_compensation(ctx: Context<ClaimIlCompensation>) -> Result<()> {
+        instructions::claim_il_compensation(ctx)
+    }
+
+    pub fn deposit_fee_position(ctx: Context<DepositFeePosition>, amount: u64) -> Result<()> {
+        instructions::deposit_fee_position(ctx, amount)
+    }
+
+    pub fn close_fee_position(ctx: Context<CloseFeePosition>) -> Result<()> {
+        instructions::close_fee_position(ctx)
+    }
+
+    pub fn claim_fees(ctx: Context<ClaimFees>) -> Result<()> {
+        instructions::claim_fees(ctx)
+    }
+
+    pub fn place_limit_order(
+        ctx: Context<PlaceLimitOrder>,
+        id: u64,
+        input_amount: u64,
+        min_output_amount: u64,
+    ) -> Result<()> {
+        instructions::place_limit_order(ctx, id, input_amount, min_output_amount)
+    }
+
+    pub fn cancel_limit_order(ctx: Context<CancelLimitOrder>) -> Result<()> {
+        instructions::cancel_limit_order(ctx)
+    }
+
+    pub fn fill_limit_order(ctx: Context<FillLimitOrder>) -> Result<()> {
+        instructions::fill_limit_order(ctx)
+    }
+
+    pub fn create_dca_schedule(
+        ctx: Context<CreateDcaSchedule>,
+        id: u64,
+        total_amount: u64,
+        amount_per_interval: u64,
+        interval: i64,
+        min_price: u128,
+    ) -> Result<()> {
+        instructions::create_dca_schedule(ctx, id, total_amount, amount_per_interval, interval, min_price)
+    }
+
+    pub fn execute_dca(ctx: Context<ExecuteDca>) -> Result<()> {
+        instructions::execute_dca(ctx)
+    }
+
+    pub fn cancel_dca_schedule(ctx: Context<CancelDcaSchedule>) -> Result<()> {
+        instructions::cancel_dca_schedule(ctx)
+    }
+
+    pub fn update_volatility(ctx: Context<UpdateVolatility>) -> Result<()> {
+        instructions::update_volatility(ctx)
+    }
+
+    pub fn get_twap(ctx: Context<GetTwap>, window_secs: u32) -> Result<TwapPrice> {
+        instructions::get_twap(ctx, window_secs)
+    }
+
+    pub fn set_oracle_guard(ctx: Context<SetOracleGuard>, oracle_guard: OracleGuardConfig) -> Result<()> {
+        instructions::set_oracle_guard(ctx, oracle_guard)
+    }
+
+    pub fn set_circuit_breaker(ctx: Context<SetCircuitBreaker>, circuit_breaker_until: i64) -> Result<()> {
+        instructions::set_circuit_breaker(ctx, circuit_breaker_until)
+    }
+
+    pub fn create_trader_stats(ctx: Context<CreateTraderStats>) -> Result<()> {
+        instructions::create_trader_stats(ctx)
+    }
+
+    pub fn set_fee_exemption(ctx: Context<SetFeeExemption>, discount_bps: u16) -> Result<()> {
+        instructions::set_fee_exemption(ctx, discount_bps)
+    }
+
+    pub fn remove_fee_exemption(ctx: Context<RemoveFeeExemption>) -> Result<()> {
+        instructions::remove_fee_exemption(ctx)
+    }
+
+    pub fn create_deposit_record(ctx: Context<CreateDepositRecord>) -> Result<()> {
+        instructions::create_deposit_record(ctx)
+    }
+
+    pub fn update_withdrawal_fee_config(
+        ctx: Context<UpdateWithdrawalFeeConfig>,
+        withdrawal_fee_config: WithdrawalFeeConfig,
+    ) -> Result<()> {
+        instructions::update_withdrawal_fee_config(ctx, withdrawal_fee_config)
+    }
+
+    pub fn lock_liquidity(ctx: Context<LockLiquidity>, amount: u64, duration: i64) -> Result<()> {
+        instructions::lock_liquidity(ctx, amount, duration)
+    }
+
+    pub fn unlock_liquidity(ctx: Context<UnlockLiquidity>) -> Result<()> {
+        instructions::unlock_liquidity(ctx)
+    }
+
+    pub fn claim_locked_fees(ctx: Context<ClaimLockedFees>) -> Result<()> {
+        instructions::claim_locked_fees(ctx)
+    }
+
+    pub fn get_liquidity_balance(ctx: Context<GetLiquidityBalance>) -> Result<LiquidityBalance> {
+        instructions::get_liquidity_balance(ctx)
+    }
+
+    pub fn create_farm(ctx: Context<CreateFarm>) -> Result<()> {
+        instructions::create_farm(ctx)
+    }
+
+    pub fn add_farm_reward(ctx: Context<AddFarmReward>) -> Result<()> {
+        instructions::add_farm_reward(ctx)
+    }
+
+    pub fn fund_farm(ctx: Context<FundFarm>, reward_index: u8, emission_rate: u64, duration: i64) -> Result<()> {
+        instructions::fund_farm(ctx, reward_index, emission_rate, duration)
+    }
+
+    pub fn stake_lp(ctx: Context<StakeLp>, amount: u64) -> Result<()> {
+        instructions::stake_lp(ctx, amount)
+    }
+
+    pub fn unstake_lp(ctx: Context<UnstakeLp>, amount: u64) -> Result<()> {
+        instructions::unstake_lp(ctx, amount)
+    }
+
+    pub fn refresh_stake_weight(ctx: Context<RefreshStakeWeight>) -> Result<()> {
+        instructions::refresh_stake_weight(ctx)
+    }
+
+    pub fn claim_rewards(ctx: Context<ClaimRewards>, reward_index: u8) -> Result<()> {
+        instructions::claim_rewards(ctx, reward_index)
+    }
+
+    pub fn create_vault(ctx: Context<CreateVault>) -> Result<()> {
+        instructions::create_vault(ctx)
+    }
+
+    pub fn vault_deposit(ctx: Context<VaultDeposit>, liquidity: u64) -> Result<()> {
+        instructions::vault_deposit(ctx, liquidity)
+    }
+
+    pub fn vault_withdraw(ctx: Context<VaultWithdraw>, shares: u64) -> Result<()> {
+        instructions::vault_withdraw(ctx, shares)
+    }
+
+    pub fn compound_vault(ctx: Context<CompoundVault>) -> Result<()> {
+        instructions::compound_vault(ctx)
+    }
+
+    pub fn open_position(
+        ctx: Context<OpenPosition>,
+        lower_price_x64: u128,
+        upper_price_x64: u128,
+        liquidity: u64,
+    ) -> Result<()> {
+        instructions::open_position(ctx, lower_price_x64, upper_price_x64, liquidity)
+    }
+
+    pub fn increase_liquidity(ctx: Context<ModifyPosition>, amount: u64) -> Result<()> {
+        instructions::increase_liquidity(ctx, amount)
+    }
+
+    pub fn decrease_liquidity(ctx: Context<ModifyPosition>, amount: u64) -> Result<()> {
+        instructions::decrease_liquidity(ctx, amount)
+    }
+
+    pub fn collect_position_fees(ctx: Context<CollectPositionFees>) -> Result<()> {
+        instructions::collect_position_fees(ctx)
+    }
+
+    pub fn close_position(ctx: Context<ClosePosition>) -> Result<()> {
+        instructions::close_position(ctx)
+    }
+
+    pub fn close_filled_range_order(ctx: Context<CloseFilledRangeOrder>, min_out: u64) -> Result<()> {
+        instructions::close_filled_range_order(ctx, min_out)
+    }
+
+    pub fn update_concentrated_liquidity_config(
+        ctx: Context<UpdateConcentratedLiquidityConfig>,
+        config: ConcentratedLiquidityConfig,
+    ) -> Result<()> {
+        instructions::update_concentrated_liquidity_config(ctx, config)
+    }
+
+    pub fn migrate_pool_price_range(ctx: Context<MigratePoolPriceRange>) -> Result<()> {
+        instructions::migrate_pool_price_range(ctx)
+    }
+
+    pub fn get_pool_apr(ctx: Context<GetPoolApr>) -> Result<PoolApr> {
+        instructions::get_pool_apr(ctx)
+    }
+
+    pub fn grow_pool_oracle(ctx: Context<GrowPoolOracle>) -> Result<()> {
+        instructions::grow_pool_oracle(ctx)
+    }
+
+    pub fn update_volatility_config(ctx: Context<UpdateVolatilityConfig>, volatility_config: VolatilityConfig) -> Result<()> {
+        instructions::update_volatility_config(ctx, volatility_config)
+    }
+
+    pub fn update_price_impact_config(
+        ctx: Context<UpdatePriceImpactConfig>,
+        max_slippage_bps: u16,
+        dynamic_adjustment_factor: u16,
+        enabled: bool,
+    ) -> Result<()> {
+        instructions::update_price_impact_config(ctx, max_slippage_bps, dynamic_adjustment_factor, enabled)
+    }
+
+    pub fn migrate_amm(ctx: Context<MigrateAmm>) -> Result<()> {
+        instructions::migrate_amm(ctx)
+    }
+
+    pub fn migrate_pool(ctx: Context<MigratePool>) -> Result<()> {
+        instructions::migrate_pool(ctx)
+    }
+
+    pub fn migrate_fee_position(ctx: Context<MigrateFeePosition>) -> Result<()> {
+        instructions::migrate_fee_position(ctx)
+    }
+
+    pub fn update_mint_policy(ctx: Context<UpdateMintPolicy>, mint_policy: MintPolicy) -> Result<()> {
+        instructions::update_mint_policy(ctx, mint_policy)
+    }
+
+    pub fn set_swap_hook(ctx: Context<SetSwapHook>, swap_hook: Pubkey) -> Result<()> {
+        instructions::set_swap_hook(ctx, swap_hook)
+    }
+
+    pub fn create_mint_list(ctx: Context<CreateMintList>, mode: MintListMode) -> Result<()> {
+        instructions::create_mint_list(ctx, mode)
+    }
+
+    pub fn close_mint_list(ctx: Context<CloseMintList>) -> Result<()> {
+        instructions::close_mint_list(ctx)
+    }
+
+    pub fn add_mint_to_list(ctx: Context<UpdateMintList>, mint: Pubkey) -> Result<()> {
+        instructions::add_mint_to_list(ctx, mint)
+    }
+
+    pub fn remove_mint_from_list(ctx: Context<UpdateMintList>, mint: Pubkey) -> Result<()> {
+        instructions::remove_mint_from_list(ctx, mint)
+    }
+
+    pub fn swap_exact_tokens_for_tokens_relayed<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SwapExactTokensForTokens<'info>>,
+        input_mint: Pubkey,
+        input_amount: u64,
+        min_output_amount: u64,
+        deadline: Option<i64>,
+        relayer_tip: u64,
+    ) -> Result<()> {
+        instructions::swap_exact_tokens_for_tokens_relayed(ctx, input_mint, input_amount, min_output_amount, deadline, relayer_tip)
+    }
+
+    pub fn swap_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SwapBatch<'info>>,
+        legs: Vec<BatchSwapLeg>,
+        min_total_output: u64,
+        deadline: Option<i64>,
+        clamp_to_balance: bool,
+    ) -> Result<()> {
+        instructions::swap_batch(ctx, legs, min_total_output, deadline, clamp_to_balance)
+    }
+
+    pub fn swap_split<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SwapRoute<'info>>,
+        input_amount: u64,
+        split_bps: Vec<u16>,
+        min_output_amount: u64,
+        deadline: Option<i64>,
+        clamp_to_balance: bool,
+    ) -> Result<()> {
+        instructions::swap_split(ctx, input_amount, split_bps, min_output_amount, deadline, clamp_to_balance)
+    }
+
+    pub fn commit_swap(ctx: Context<CommitSwap>, hash: [u8; 32]) -> Result<()> {
+        instructions::commit_swap(ctx, hash)
+    }
+
+    pub fn reveal_swap<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SwapExactTokensForTokens<'info>>,
+        input_mint: Pubkey,
+        input_amount: u64,
+        min_output_amount: u64,
+        salt: [u8; 32],
+    ) -> Result<()> {
+        instructions::reveal_swap(ctx, input_mint, input_amount, min_output_amount, salt)
+    }
+
+    pub fn cancel_swap_commitment(ctx: Context<CancelSwapCommitment>) -> Result<()> {
+        instructions::cancel_swap_commitment(ctx)
+    }
+
+    pub fn update_fee_side(ctx: Context<UpdateFeeSide>, fee_on_output: bool) -> Result<()> {
+        instructions::update_fee_side(ctx, fee_on_output)
+    }
+
+    pub fn update_max_host_fee(ctx: Context<UpdateMaxHostFee>, max_host_fee_bps: u16) -> Result<()> {
+        instructions::update_max_host_fee(ctx, max_host_fee_bps)
+    }
+
+    pub fn swap_exact_tokens_for_tokens_with_host_fee<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SwapExactTokensForTokens<'info>>,
+        input_mint: Pubkey,
+        input_amount: u64,
+        min_output_amount: u64,
+        deadline: Option<i64>,
+        host_fee_bps: u16,
+    ) -> Result<()> {
+        instructions::swap_exact_tokens_for_tokens_with_host_fee(ctx, input_mint, input_amount, min_output_amount, deadline, host_fee_bps)
+    }
+
+    pub fn sync(ctx: Context<SyncReserves>) -> Result<()> {
+        instructions::sync(ctx)
+    }
+
+    pub fn skim(ctx: Context<SkimReserves>) -> Result<()> {
+        instructions::skim(ctx)
+    }
+
+    pub fn migrate_liquidity(
+        ctx: Context<MigrateLiquidity>,
+        lp_amount: u64,
+        min_lp_out: u64,
+        deadline: Option<i64>,
+    ) -> Result<()> {
+        instructions::migrate_liquidity(ctx, lp_amount, min_lp_out, deadline)
+    }
+
+    pub fn set_deposit_cap(ctx: Context<SetDepositCap>, max_reserve_a: u64, max_reserve_b: u64) -> Result<()> {
+        instructions::set_deposit_cap(ctx, max_reserve_a, max_reserve_b)
+    }
+
+    pub fn preview_deposit(ctx: Context<PreviewLiquidity>, amount_a: u64, amount_b: u64) -> Result<DepositPreview> {
+        instructions::preview_deposit(ctx, amount_a, amount_b)
+    }
+
+    pub fn preview_withdraw(ctx: Context<PreviewLiquidity>, lp_amount: u64) -> Result<WithdrawPreview> {
+        instructions::preview_withdraw(ctx, lp_amount)
+    }
+
+    pub fn get_lp_token_value(ctx: Context<PreviewLiquidity>) -> Result<LpTokenValue> {
+        instructions::get_lp_token_value(ctx)
+    }
+
+    pub fn create_program_config(ctx: Context<CreateProgramConfig>, features: FeatureFlags) -> Result<()> {
+        instructions::create_program_config(ctx, features)
+    }
+
+    pub fn update_program_config(ctx: Context<UpdateProgramConfig>, features: FeatureFlags) -> Result<()> {
+        instructions::update_program_config(ctx, features)
+    }
+
+    pub fn grant_role(ctx: Context<UpdateRole>, role: Role, holder: Pubkey) -> Result<()> {
+        instructions::grant_role(ctx, role, holder)
+    }
+
+    pub fn revoke_role(ctx: Context<UpdateRole>, role: Role) -> Result<()> {
+        instructions::revoke_role(ctx, role)
+    }
+
+    pub fn set_timelock_delay(ctx: Context<SetTimelockDelay>, delay: i64) -> Result<()> {
+        instructions::set_timelock_delay(ctx, delay)
+    }
+
+    pub fn propose_parameter_change(ctx: Context<ProposeParameterChange>, id: u64, change: ParameterChange) -> Result<()> {
+        instructions::propose_parameter_change(ctx, id, change)
+    }
+
+    pub fn queue_parameter_change(ctx: Context<QueueParameterChange>) -> Result<()> {
+        instructions::queue_parameter_change(ctx)
+    }
+
+    pub fn execute_parameter_change(ctx: Context<ExecuteParameterChange>) -> Result<()> {
+        instructions::execute_parameter_change(ctx)
+    }
+
+    pub fn cancel_parameter_change(ctx: Context<CancelParameterChange>) -> Result<()> {
+        instructions::cancel_parameter_change(ctx)
+    }
+
+    pub fn create_governance_proposal(
+        ctx: Context<CreateGovernanceProposal>,
+        id: u64,
+        action: GovernanceAction,
+    ) -> Result<()> {
+        instructions::create_governance_proposal(ctx, id, action)
+    }
+
+    pub fn cast_vote(ctx: Context<CastVote>, support: bool) -> Result<()> {
+        instructions::cast_vote(ctx, support)
+    }
+
+    pub fn execute_governance_proposal(ctx: Context<ExecuteGovernanceProposal>) -> Result<()> {
+        instructions::execute_governance_proposal(ctx)
+    }
+
+    pub fn set_exit_only(ctx: Context<SetExitOnly>, exit_only: bool) -> Result<()> {
+        instructions::set_exit_only(ctx, exit_only)
+    }
+
+    pub fn set_pool_status(ctx: Context<SetPoolStatus>, status: PoolStatus) -> Result<()> {
+        instructions::set_pool_status(ctx, status)
+    }
+
+    pub fn create_denylist(ctx: Context<CreateDenylist>) -> Result<()> {
+        instructions::create_denylist(ctx)
+    }
+
+    pub fn close_denylist(ctx: Context<CloseDenylist>) -> Result<()> {
+        instructions::close_denylist(ctx)
+    }
+
+    pub fn add_to_denylist(ctx: Context<UpdateDenylist>, address: Pubkey) -> Result<()> {
+        instructions::add_to_denylist(ctx, address)
+    }
+
+    pub fn remove_from_denylist(ctx: Context<UpdateDenylist>, address: Pubkey) -> Result<()> {
+        instructions::remove_from_denylist(ctx, address)
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::TutorialError,
+    models::{
+        fee_strategy::{FeeCalculator, FeeConfig},
+        pool_stats::VolumeBucket,
+    },
+};
+
+/// 按最近24小时手续费估算的LP年化收益率，金额均以代币B计价
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct PoolApr {
+    /// 最近24小时归属LP的手续费（扣除协议分成和补偿分成）
+    pub lp_fees_24h: u64,
+    /// 按当前池子价格计算的总锁仓价值
+    pub tvl: u64,
+    /// 年化收益率（基点）
+    pub apr_bps: u64,
+}
+
+impl PoolApr {
+    /// 用池子当前价格把代币A的手续费和储备折算为代币B，再把日收益率年化
+    pub fn estimate(fee_config: &FeeConfig, rolling: &VolumeBucket, reserve_a: u64, reserve_b: u64) -> Result<Self> {
+        if reserve_a == 0 || reserve_b == 0 {
+            return Ok(Self::default());
+        }
+        let value_in_b = |amount_a: u64| amount_a as u128 * reserve_b as u128 / reserve_a as u128;
+        let lp_share = |fees: u64| {
+            fees - FeeCalculator::calculate_protocol_fee(fee_config, fees)
+                - FeeCalculator::calculate_il_compensation_fee(fee_config, fees)
+        };
+
+        let lp_fees_24h = value_in_b(lp_share(rolling.fees_a)) + lp_share(rolling.fees_b) as u128;
+        let tvl = value_in_b(reserve_a) + reserve_b as u128;
+        let apr_bps = lp_fees_24h * 365 * 10000 / tvl;
+
+        Ok(Self {
+            lp_fees_24h: u64::try_from(lp_fees_24h).map_err(|_| TutorialError::MathOverflow)?,
+            tvl: u64::try_from(tvl).map_err(|_| TutorialError::MathOverflow)?,
+            apr_bps: u64::try_from(apr_bps).map_err(|_| TutorialError::MathOverflow)?,
+        })
+    }
+}
@@ -0,0 +1,12 @@
+use anchor_lang::prelude::*;
+
+/// 批量交换中的一笔交换，账户按顺序通过remaining_accounts传入
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct BatchSwapLeg {
+    /// 交换的池子
+    pub pool: Pubkey,
+    /// 输入代币，必须是池子的两种代币之一
+    pub input_mint: Pubkey,
+    /// 输入数量，超出交易者余额时只有设置了clamp_to_balance才按余额成交，否则拒绝
+    pub amount: u64,
+}
@@ -1,8 +1,10 @@
 use anchor_lang::prelude::*;
 use fixed::types::I64F64;
 
+use crate::errors::TutorialError;
+
 /// 聚合流动性配置
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, PartialEq)]
 pub struct ConcentratedLiquidityConfig {
     /// 是否启用聚合流动性
     pub enabled: bool,
@@ -26,14 +28,63 @@ impl Default for ConcentratedLiquidityConfig {
 }
 
 impl ConcentratedLiquidityConfig {
-    // 计算结构体的大小：bool(1) + 2个i64(16)
-    pub const LEN: usize = 1 + 16;
+    /// 校验配置：开启时区间百分比必须在(0, 100)内，奖励系数不低于1倍，最小宽度不能为负
+    pub fn is_valid(&self) -> bool {
+        self.reward_multiplier >= 1000
+            && self.min_width >= 0
+            && (!self.enabled || (self.range_percentage > 0 && self.range_percentage < 100))
+    }
+}
+
+/// 池子的聚合流动性价格区间，代币A以代币B计价（Q64.64），上下限均为0表示不限制
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, Default, PartialEq)]
+pub struct PriceRange {
+    pub lower_price_x64: u128,
+    pub upper_price_x64: u128,
+}
+
+impl PriceRange {
+    /// 按参考价格和区间百分比计算价格区间
+    pub fn around(price: I64F64, range_percentage: u16) -> Self {
+        let range = I64F64::from_num(range_percentage) / I64F64::from_num(100);
+        let lower = (price * (I64F64::from_num(1) - range)).max(I64F64::from_num(0));
+        let upper = price * (I64F64::from_num(1) + range);
+        Self {
+            lower_price_x64: lower.to_bits() as u128,
+            upper_price_x64: upper.to_bits() as u128,
+        }
+    }
+
+    /// 是否设置了价格区间
+    pub fn is_set(&self) -> bool {
+        self.upper_price_x64 > 0
+    }
+
+    /// 交易后的储备对应的价格必须留在区间内
+    pub fn check(&self, reserve_a: u64, reserve_b: u64) -> Result<()> {
+        if !self.is_set() {
+            return Ok(());
+        }
+        let price_x64 = ConcentratedLiquidityPricing::price_x64(reserve_a, reserve_b);
+        if price_x64 < self.lower_price_x64 || price_x64 > self.upper_price_x64 {
+            return err!(TutorialError::PriceOutOfRange);
+        }
+        Ok(())
+    }
 }
 
 /// 聚合流动性价格计算
 pub struct ConcentratedLiquidityPricing;
 
 impl ConcentratedLiquidityPricing {
+    /// 按储备计算代币A以代币B计价的价格（Q64.64）
+    pub fn price_x64(reserve_a: u64, reserve_b: u64) -> u128 {
+        if reserve_a == 0 {
+            return 0;
+        }
+        ((reserve_b as u128) << 64) / reserve_a as u128
+    }
+
     /// 计算给定价格范围内的流动性价值
     pub fn calculate_concentrated_liquidity_value(
         config: &ConcentratedLiquidityConfig,
@@ -47,8 +98,8 @@ impl ConcentratedLiquidityPricing {
 
         // 计算流动性范围
         let range_percentage = I64F64::from_num(config.range_percentage as u64) / I64F64::from_num(100);
-        let lower_price = current_price * (I64F64::from_num(1) - range_percentage);
-        let upper_price = current_price * (I64F64::from_num(1) + range_percentage);
+        let _lower_price = current_price * (I64F64::from_num(1) - range_percentage);
+        let _upper_price = current_price * (I64F64::from_num(1) + range_percentage);
 
         // 计算聚合流动性值
         let token_a_value = I64F64::from_num(token_a_amount);
@@ -0,0 +1,139 @@
+use amm_math::curve::{self, Curve};
+use anchor_lang::prelude::*;
+
+use crate::errors::TutorialError;
+
+/// 池子使用的定价曲线
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum CurveType {
+    /// 恒定乘积曲线 x * y = k
+    #[default]
+    ConstantProduct,
+    /// Curve风格的稳定币曲线，适用于价值相近的交易对（如USDC/USDT）
+    StableSwap {
+        /// 放大系数，越大越接近恒定和曲线
+        amp: u64,
+    },
+    /// Balancer风格的加权曲线 x^w_a * y^w_b = k，适用于非50/50的资产配置
+    Weighted {
+        /// 代币A的权重（基点），代币B的权重为 10000 - weight_a
+        weight_a: u16,
+    },
+    /// 流动性引导池（LBP）：代币A的权重在时间窗口内从起始值线性变化到结束值，用于新代币的价格发现
+    Lbp {
+        /// 起始时代币A的权重（基点）
+        start_weight_a: u16,
+        /// 结束时代币A的权重（基点）
+        end_weight_a: u16,
+        /// 权重开始变化的时间（unix时间）
+        start_time: i64,
+        /// 权重停止变化的时间（unix时间）
+        end_time: i64,
+    },
+}
+
+impl CurveType {
+    /// 放大系数的上限
+    pub const MAX_AMP: u64 = 1_000_000;
+
+    /// 单个代币的最小权重（基点），避免幂运算的指数过大
+    pub const MIN_WEIGHT: u16 = 200;
+
+    /// 权重的总和（基点）
+    pub const TOTAL_WEIGHT: u16 = curve::TOTAL_WEIGHT;
+
+    /// 校验曲线参数
+    pub fn is_valid(&self) -> bool {
+        match self {
+            CurveType::ConstantProduct => true,
+            CurveType::StableSwap { amp } => *amp > 0 && *amp <= Self::MAX_AMP,
+            CurveType::Weighted { weight_a } => Self::is_valid_weight(*weight_a),
+            CurveType::Lbp { start_weight_a, end_weight_a, start_time, end_time } => {
+                Self::is_valid_weight(*start_weight_a) && Self::is_valid_weight(*end_weight_a) && end_time > start_time
+            }
+        }
+    }
+
+    fn is_valid_weight(weight_a: u16) -> bool {
+        (Self::MIN_WEIGHT..=Self::TOTAL_WEIGHT - Self::MIN_WEIGHT).contains(&weight_a)
+    }
+
+    /// 按给定时间线性插值LBP的权重，返回对应的加权曲线；其他曲线原样返回
+    pub fn at(&self, timestamp: i64) -> CurveType {
+        match *self {
+            CurveType::Lbp { start_weight_a, end_weight_a, start_time, end_time } => CurveType::Weighted {
+                weight_a: curve::lbp_weight_a(start_weight_a, end_weight_a, start_time, end_time, timestamp),
+            },
+            curve => curve,
+        }
+    }
+
+    /// 当前时间下生效的曲线
+    fn current(&self) -> Result<Curve> {
+        Ok(self.curve_at(Clock::get()?.unix_timestamp))
+    }
+
+    /// 给定时间下生效的曲线
+    fn curve_at(&self, timestamp: i64) -> Curve {
+        match self.at(timestamp) {
+            CurveType::StableSwap { amp } => Curve::StableSwap { amp },
+            CurveType::Weighted { weight_a } => Curve::Weighted { weight_a },
+            _ => Curve::ConstantProduct,
+        }
+    }
+}
+
+/// 曲线计算器，按当前时间生效的曲线调用共享的定价数学库
+pub struct CurveCalculator;
+
+impl CurveCalculator {
+    /// 给定扣费后的输入数量，计算输出数量（向下取整，对池子有利）
+    /// swap_a 表示输入为代币A，只有加权曲线需要区分方向
+    pub fn swap_output(curve: &CurveType, swap_a: bool, taxed_input: u64, reserve_in: u64, reserve_out: u64) -> Result<u64> {
+        Ok(curve.current()?.swap_output(swap_a, taxed_input, reserve_in, reserve_out).map_err(TutorialError::from)?)
+    }
+
+    /// 按给定时间生效的曲线计算输出数量，供链下报价使用调用方提供的时钟
+    pub fn swap_output_at(
+        curve: &CurveType,
+        timestamp: i64,
+        swap_a: bool,
+        taxed_input: u64,
+        reserve_in: u64,
+        reserve_out: u64,
+    ) -> Result<u64> {
+        Ok(curve
+            .curve_at(timestamp)
+            .swap_output(swap_a, taxed_input, reserve_in, reserve_out)
+            .map_err(TutorialError::from)?)
+    }
+
+    /// 给定输出数量，反推所需的扣费后输入数量（向上取整，对池子有利）
+    pub fn swap_input(curve: &CurveType, swap_a: bool, output: u64, reserve_in: u64, reserve_out: u64) -> Result<u64> {
+        Ok(curve.current()?.swap_input(swap_a, output, reserve_in, reserve_out).map_err(TutorialError::from)?)
+    }
+
+    /// 计算池子的不变量，交换后不变量不得减少
+    pub fn invariant(curve: &CurveType, reserve_a: u64, reserve_b: u64) -> Result<u128> {
+        Ok(curve.current()?.invariant(reserve_a, reserve_b).map_err(TutorialError::from)?)
+    }
+
+    /// 校验交换后不变量的增长并返回增量：只按扣除LP手续费后的输入计算的储备已不得使不变量减少
+    /// （向有利于池子的方向放宽计算误差），计入留在池中的手续费后的实际储备同样不得低于交换前
+    pub fn check_invariant_growth(
+        curve: &CurveType,
+        invariant_before: u128,
+        fee_excluded: (u64, u64),
+        reserves_after: (u64, u64),
+    ) -> Result<u128> {
+        Ok(curve
+            .current()?
+            .check_invariant_growth(invariant_before, fee_excluded, reserves_after)
+            .map_err(TutorialError::from)?)
+    }
+
+    /// 计算一笔存款对应的流动性数量
+    pub fn deposit_liquidity(curve: &CurveType, amount_a: u64, amount_b: u64) -> Result<u64> {
+        Ok(curve.current()?.deposit_liquidity(amount_a, amount_b).map_err(TutorialError::from)?)
+    }
+}
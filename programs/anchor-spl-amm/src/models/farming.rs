@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::TutorialError;
+
+/// 农场的奖励发放计划，按每份权重累计的奖励（reward-per-share）记账
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, Default, PartialEq)]
+pub struct FarmReward {
+    /// 奖励代币
+    pub mint: Pubkey,
+    /// 存放奖励代币的金库
+    pub vault: Pubkey,
+    /// 每秒发放的奖励数量
+    pub emission_rate: u64,
+    /// 本期发放结束时间
+    pub end_time: i64,
+    /// 上次累计奖励的时间
+    pub last_update: i64,
+    /// 每份质押权重累计的奖励（Q64.64）
+    pub reward_per_share: u128,
+}
+
+impl FarmReward {
+    /// 本期是否仍在发放
+    pub fn is_active(&self, now: i64) -> bool {
+        now < self.end_time
+    }
+
+    /// 累计自上次更新以来发放的奖励，没有质押时这段时间的奖励不发放
+    pub fn update(&mut self, total_weight: u64, now: i64) -> Result<()> {
+        let until = now.min(self.end_time);
+        if until <= self.last_update {
+            return Ok(());
+        }
+        if total_weight > 0 {
+            let emitted = self.emission_rate as u128 * (until - self.last_update) as u128;
+            let growth = emitted
+                .checked_shl(64)
+                .filter(|v| v >> 64 == emitted)
+                .ok_or(TutorialError::MathOverflow)?
+                / total_weight as u128;
+            self.reward_per_share = self
+                .reward_per_share
+                .checked_add(growth)
+                .ok_or(TutorialError::MathOverflow)?;
+        }
+        self.last_update = until;
+
+        Ok(())
+    }
+
+    /// 开始新一期发放
+    pub fn start(&mut self, emission_rate: u64, duration: i64, now: i64) {
+        self.emission_rate = emission_rate;
+        self.last_update = now;
+        self.end_time = now + duration;
+    }
+
+    /// 按质押权重计算自检查点以来应得的奖励
+    pub fn pending(&self, weight: u64, checkpoint: u128) -> Result<u64> {
+        let pending = (weight as u128)
+            .checked_mul(self.reward_per_share - checkpoint)
+            .ok_or(TutorialError::MathOverflow)?
+            >> 64;
+        u64::try_from(pending).map_err(|_| error!(TutorialError::MathOverflow))
+    }
+}
@@ -1,10 +1,13 @@
+use amm_math::fee;
 use anchor_lang::prelude::*;
-use fixed::types::I64F64;
+
+pub use amm_math::fee::VOLUME_DISCOUNT_TIERS;
 
 /// 费用策略枚举
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, Default, PartialEq)]
 pub enum FeeStrategy {
     /// 固定费用 - 始终使用相同的手续费率
+    #[default]
     Fixed,
     /// 动态费用 - 根据池子深度和交易量调整费用
     Dynamic,
@@ -14,14 +17,8 @@ pub enum FeeStrategy {
     VolatilityAdjusted,
 }
 
-impl Default for FeeStrategy {
-    fn default() -> Self {
-        FeeStrategy::Fixed
-    }
-}
-
 /// 费用配置
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug)]
 pub struct FeeConfig {
     /// 当前使用的费用策略
     pub strategy: FeeStrategy,
@@ -33,6 +30,14 @@ pub struct FeeConfig {
     pub base_fee_bps: u16,
     /// 费用调整系数（放大1000倍）
     pub adjustment_factor: u16,
+    /// 协议分成占交易费用的比例（基点 - 10000 = 100%）
+    pub protocol_fee_bps: u16,
+    /// 注入无常损失补偿金库的部分占交易费用的比例（基点 - 10000 = 100%）
+    pub il_compensation_fee_bps: u16,
+    /// A换B方向的费率覆盖（基点），设置后该方向不再按策略计算
+    pub fee_a_to_b_bps: Option<u16>,
+    /// B换A方向的费率覆盖（基点），设置后该方向不再按策略计算
+    pub fee_b_to_a_bps: Option<u16>,
 }
 
 impl Default for FeeConfig {
@@ -43,13 +48,45 @@ impl Default for FeeConfig {
             max_fee_bps: 100,   // 最高1%
             base_fee_bps: 30,   // 基础费率0.3%
             adjustment_factor: 1000, // 调整系数1.0
+            protocol_fee_bps: 0,     // 默认不收取协议费用
+            il_compensation_fee_bps: 0, // 默认不提取补偿资金
+            fee_a_to_b_bps: None,
+            fee_b_to_a_bps: None,
         }
     }
 }
 
 impl FeeConfig {
-    // 计算结构体的大小：枚举(1) + 4个u16(8)
-    pub const LEN: usize = 1 + 4 * 2;
+    /// 校验费率配置：min <= base <= max < 10000，协议分成与补偿分成之和不超过100%，方向费率低于100%
+    pub fn is_valid(&self) -> bool {
+        self.min_fee_bps <= self.base_fee_bps
+            && self.base_fee_bps <= self.max_fee_bps
+            && self.max_fee_bps < 10000
+            && self.protocol_fee_bps as u32 + self.il_compensation_fee_bps as u32 <= 10000
+            && !matches!(self.fee_a_to_b_bps, Some(fee) if fee >= 10000)
+            && !matches!(self.fee_b_to_a_bps, Some(fee) if fee >= 10000)
+    }
+
+    /// 交换方向对应的费率覆盖
+    pub fn directional_fee_bps(&self, swap_a: bool) -> Option<u16> {
+        if swap_a {
+            self.fee_a_to_b_bps
+        } else {
+            self.fee_b_to_a_bps
+        }
+    }
+
+    /// 替换为新配置是否需要经过时间锁：任一费率上限或基础费率提高，或协议分成变化。
+    /// 方向费率未设置时以最高费率为上限
+    pub fn requires_timelock(&self, new: &FeeConfig) -> bool {
+        let directional_cap = |config: &FeeConfig, swap_a: bool| config.directional_fee_bps(swap_a).unwrap_or(config.max_fee_bps);
+        new.min_fee_bps > self.min_fee_bps
+            || new.base_fee_bps > self.base_fee_bps
+            || new.max_fee_bps > self.max_fee_bps
+            || directional_cap(new, true) > directional_cap(self, true)
+            || directional_cap(new, false) > directional_cap(self, false)
+            || new.protocol_fee_bps != self.protocol_fee_bps
+    }
 }
 
 /// 费用计算器
@@ -59,105 +96,62 @@ impl FeeCalculator {
     /// 根据当前策略计算交易费用
     pub fn calculate_fee(
         config: &FeeConfig, 
+        swap_a: bool,
         input_amount: u64,
         reserve_in: u64,
         reserve_out: u64,
         volatility: Option<u16>,
     ) -> u64 {
         // 获取基点费率
-        let fee_bps = Self::get_fee_rate_bps(config, input_amount, reserve_in, reserve_out, volatility);
+        let fee_bps = Self::get_fee_rate_bps(config, swap_a, input_amount, reserve_in, reserve_out, volatility);
         
         // 计算费用金额
-        (I64F64::from_num(input_amount) * I64F64::from_num(fee_bps) / I64F64::from_num(10000)).to_num::<u64>()
+        fee::fee_amount(input_amount, fee_bps)
+    }
+
+    /// 计算交易费用中归属协议的部分
+    pub fn calculate_protocol_fee(config: &FeeConfig, fee_amount: u64) -> u64 {
+        fee::fee_share(fee_amount, config.protocol_fee_bps)
+    }
+
+    /// 计算交易费用中注入无常损失补偿金库的部分
+    pub fn calculate_il_compensation_fee(config: &FeeConfig, fee_amount: u64) -> u64 {
+        fee::fee_share(fee_amount, config.il_compensation_fee_bps)
     }
     
-    /// 获取按策略计算的费率（基点）
+    /// 交易者30天成交量对应的折扣档位（基点）
+    pub fn volume_discount_bps(volume: u64) -> u16 {
+        fee::volume_discount_bps(volume)
+    }
+
+    /// 对费率应用折扣，10000表示完全免除手续费
+    pub fn apply_discount(fee_rate_bps: u16, discount_bps: u16) -> u16 {
+        fee::apply_discount(fee_rate_bps, discount_bps)
+    }
+
+    /// 获取按策略计算的费率（基点），交换方向设置了费率覆盖时直接使用覆盖值
     pub fn get_fee_rate_bps(
         config: &FeeConfig, 
+        swap_a: bool,
         input_amount: u64,
         reserve_in: u64,
-        reserve_out: u64,
+        _reserve_out: u64,
         volatility: Option<u16>,
     ) -> u16 {
+        if let Some(fee_bps) = config.directional_fee_bps(swap_a) {
+            return fee_bps;
+        }
+
+        let range = (config.min_fee_bps, config.base_fee_bps, config.max_fee_bps);
         match config.strategy {
             FeeStrategy::Fixed => config.base_fee_bps,
-            FeeStrategy::Dynamic => Self::calculate_dynamic_fee_bps(config, input_amount, reserve_in),
-            FeeStrategy::Tiered => Self::calculate_tiered_fee_bps(config, input_amount),
-            FeeStrategy::VolatilityAdjusted => Self::calculate_volatility_adjusted_fee_bps(
-                config, 
+            FeeStrategy::Dynamic => fee::dynamic_fee_bps(range, config.adjustment_factor, input_amount, reserve_in),
+            FeeStrategy::Tiered => fee::tiered_fee_bps(range, input_amount),
+            FeeStrategy::VolatilityAdjusted => fee::volatility_adjusted_fee_bps(
+                config.min_fee_bps,
+                config.max_fee_bps,
                 volatility.unwrap_or(0)
             ),
         }
     }
-    
-    /// 计算动态费用（基于池子深度和交易量）
-    fn calculate_dynamic_fee_bps(
-        config: &FeeConfig, 
-        input_amount: u64,
-        reserve: u64,
-    ) -> u16 {
-        // 计算交易量占池子的比例
-        let ratio = if reserve == 0 {
-            I64F64::from_num(1) // 防止除以0
-        } else {
-            I64F64::from_num(input_amount) / I64F64::from_num(reserve)
-        };
-        
-        // 用二次曲线调整费率：base_fee + adjustment * (ratio)^2
-        let adjustment = I64F64::from_num(config.adjustment_factor) / I64F64::from_num(1000);
-        let base_fee = I64F64::from_num(config.base_fee_bps);
-        let fee_adjustment = adjustment * ratio * ratio;
-        
-        // 计算最终费率，确保在min和max之间
-        let calculated_fee = base_fee + fee_adjustment * I64F64::from_num(10000);
-        let fee_bps = calculated_fee.to_num::<u16>();
-        
-        fee_bps.clamp(config.min_fee_bps, config.max_fee_bps)
-    }
-    
-    /// 计算分层费用（基于交易量大小）
-    fn calculate_tiered_fee_bps(config: &FeeConfig, input_amount: u64) -> u16 {
-        // 定义几个交易量分层阈值
-        let tier1 = 1_000 * 10u64.pow(6); // 1,000 tokens (假设6位小数)
-        let tier2 = 10_000 * 10u64.pow(6); // 10,000 tokens
-        let tier3 = 100_000 * 10u64.pow(6); // 100,000 tokens
-        
-        // 根据交易量确定费率
-        let tier_fee = if input_amount < tier1 {
-            config.max_fee_bps // 小额交易，使用最高费率
-        } else if input_amount < tier2 {
-            // 线性插值第一层和第二层之间
-            let mid_fee = (config.max_fee_bps + config.base_fee_bps) / 2;
-            mid_fee
-        } else if input_amount < tier3 {
-            config.base_fee_bps // 中等交易，使用基础费率
-        } else {
-            config.min_fee_bps // 大额交易，使用最低费率
-        };
-        
-        tier_fee
-    }
-    
-    /// 计算基于波动率的费用
-    fn calculate_volatility_adjusted_fee_bps(config: &FeeConfig, volatility: u16) -> u16 {
-        // 波动率门槛
-        let low_threshold = 50; // 波动率低于5%
-        let high_threshold = 200; // 波动率高于20%
-        
-        // 根据波动率调整费率
-        let fee_bps = if volatility < low_threshold {
-            config.min_fee_bps // 低波动率，使用最低费率
-        } else if volatility > high_threshold {
-            config.max_fee_bps // 高波动率，使用最高费率
-        } else {
-            // 线性插值波动率与费率
-            let volatility_range = high_threshold - low_threshold;
-            let fee_range = config.max_fee_bps - config.min_fee_bps;
-            let vol_position = volatility - low_threshold;
-            
-            config.min_fee_bps + (vol_position * fee_range) / volatility_range
-        };
-        
-        fee_bps
-    }
-} 
\ No newline at end of file
+}
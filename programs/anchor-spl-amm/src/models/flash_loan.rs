@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::FLASH_LOAN_FEE_BPS;
+
+/// 闪电贷状态，借出到归还之间保持激活
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, Default, PartialEq)]
+pub struct FlashLoanState {
+    /// 是否有未归还的闪电贷
+    pub active: bool,
+    /// 借出的代币A数量
+    pub amount_a: u64,
+    /// 借出的代币B数量
+    pub amount_b: u64,
+    /// 借出前池子的代币A余额
+    pub balance_a_before: u64,
+    /// 借出前池子的代币B余额
+    pub balance_b_before: u64,
+}
+
+impl FlashLoanState {
+    /// 计算闪电贷手续费，向上取整
+    pub fn fee(amount: u64) -> u64 {
+        (amount as u128 * FLASH_LOAN_FEE_BPS as u128).div_ceil(10000) as u64
+    }
+}
@@ -0,0 +1,12 @@
+use anchor_lang::prelude::*;
+
+use super::curve::CurveType;
+
+/// LP治理提案可以执行的操作，只限于池子自身的配置
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug)]
+pub enum GovernanceAction {
+    /// 修改池子的曲线参数，曲线种类不变
+    Curve(CurveType),
+    /// 设置存款后两侧储备的上限，设置为0时取消限制
+    DepositCap { max_reserve_a: u64, max_reserve_b: u64 },
+}
@@ -0,0 +1,14 @@
+use anchor_lang::prelude::*;
+
+/// LP的流动性代币余额，区分可自由提取和锁定中的部分
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct LiquidityBalance {
+    /// 钱包中可自由提取的流动性代币
+    pub free: u64,
+    /// 锁定中的流动性代币
+    pub locked: u64,
+    /// 锁定到期时间，没有锁定时为0
+    pub unlock_at: i64,
+    /// 当前的ve加成倍数（基点），没有锁定时为10000
+    pub boost_bps: u16,
+}
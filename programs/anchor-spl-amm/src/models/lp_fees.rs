@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::TutorialError, models::curve::{CurveCalculator, CurveType}};
+
+/// LP手续费计算器（Uniswap V2风格）
+///
+/// 手续费留在储备中使不变量增长，每个流动性代币对应的不变量即为全局的手续费增长值；
+/// 首次之后的存款按两侧占储备的较小比例铸造，提取按份额比例赎回，取整都有利于池子，不会降低该值。
+pub struct LpFeeCalculator;
+
+impl LpFeeCalculator {
+    /// 计算每个流动性代币对应的不变量，Q64.64定点数
+    pub fn growth_per_share(curve: &CurveType, reserve_a: u64, reserve_b: u64, total_liquidity: u64) -> Result<u128> {
+        if total_liquidity == 0 {
+            return Ok(0);
+        }
+        let liquidity = CurveCalculator::deposit_liquidity(curve, reserve_a, reserve_b)?;
+        Ok(((liquidity as u128) << 64) / total_liquidity as u128)
+    }
+
+    /// 计算自检查点以来的手续费对应的流动性代币数量，本金（以不变量计）保持不变
+    pub fn fee_liquidity(liquidity: u64, checkpoint: u128, growth: u128) -> Result<u64> {
+        if checkpoint == 0 || growth <= checkpoint {
+            return Ok(0);
+        }
+        let fee_liquidity = (liquidity as u128)
+            .checked_mul(growth - checkpoint)
+            .ok_or(TutorialError::MathOverflow)?
+            / growth;
+        u64::try_from(fee_liquidity).map_err(|_| error!(TutorialError::MathOverflow))
+    }
+
+    /// 向已有头寸追加流动性时合并检查点，保持已累计但未领取的手续费不变
+    pub fn merge_checkpoint(liquidity: u64, checkpoint: u128, added: u64, growth: u128) -> Result<u128> {
+        let pending = Self::fee_liquidity(liquidity, checkpoint, growth)?;
+        let total = liquidity as u128 + added as u128;
+        if pending == 0 || total == 0 {
+            return Ok(growth);
+        }
+        let pending_growth = (pending as u128)
+            .checked_mul(growth)
+            .ok_or(TutorialError::MathOverflow)?
+            / total;
+        Ok(growth - pending_growth)
+    }
+}
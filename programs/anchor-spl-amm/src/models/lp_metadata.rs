@@ -0,0 +1,12 @@
+use anchor_lang::prelude::*;
+
+/// 流动性代币的Metaplex元数据，例如名称“AMM LP: SOL-USDC”
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct LpMetadata {
+    /// 代币名称，最长32字节
+    pub name: String,
+    /// 代币符号，最长10字节
+    pub symbol: String,
+    /// 链下元数据JSON的地址，最长200字节
+    pub uri: String,
+}
@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::{errors::TutorialError, utils::has_transfer_fee};
+
+/// 创建池子时对代币的准入策略：池代币账户被冻结后LP资金会被永久锁定，风控可以拒绝此类代币
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, Default, PartialEq)]
+pub struct MintPolicy {
+    /// 拒绝设置了冻结权限的代币
+    pub reject_freeze_authority: bool,
+    /// 拒绝当前收取转账费的Token-2022代币
+    pub reject_transfer_fee: bool,
+}
+
+impl MintPolicy {
+    /// 检查代币是否满足准入策略
+    pub fn check(&self, mint: &InterfaceAccount<Mint>) -> Result<()> {
+        if self.reject_freeze_authority && mint.freeze_authority.is_some() {
+            return err!(TutorialError::MintHasFreezeAuthority);
+        }
+        if self.reject_transfer_fee && has_transfer_fee(&mint.to_account_info())? {
+            return err!(TutorialError::MintHasTransferFee);
+        }
+
+        Ok(())
+    }
+}
+
+/// AMM代币名单的模式
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, Default, PartialEq)]
+pub enum MintListMode {
+    /// 只允许名单内的代币创建池子
+    #[default]
+    Allowlist,
+    /// 禁止名单内的代币创建池子
+    Denylist,
+}
@@ -1,4 +1,26 @@
+pub mod apr;
+pub mod batch_swap;
 pub mod concentrated_liquidity;
+pub mod curve;
+pub mod oracle;
+pub mod oracle_guard;
+pub mod pool_stats;
+pub mod pool_status;
 pub mod price_impact;
+pub mod program_config;
 pub mod volatility;
-pub mod fee_strategy; 
\ No newline at end of file
+pub mod fee_strategy;
+pub mod farming;
+pub mod flash_loan;
+pub mod governance;
+pub mod liquidity_lock;
+pub mod lp_metadata;
+pub mod lp_fees;
+pub use amm_math::math;
+pub mod mint_policy;
+pub mod quote;
+pub mod roles;
+pub mod swap_options;
+pub mod timelock;
+pub mod ve_boost;
+pub mod withdrawal_fee;
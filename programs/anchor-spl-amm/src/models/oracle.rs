@@ -0,0 +1,124 @@
+use anchor_lang::prelude::*;
+use fixed::types::I64F64;
+
+use crate::models::math;
+
+/// 最大观测点数量
+pub const MAX_OBSERVATIONS: usize = 24;
+
+/// TWAP查询结果，价格为Q64.64定点数（I64F64的原始位）
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct TwapPrice {
+    /// 以代币B计价的代币A的平均价格
+    pub price_a_x64: u128,
+    /// 以代币A计价的代币B的平均价格
+    pub price_b_x64: u128,
+    /// 以代币B计价的代币A的几何平均价格，对单个区块的价格尖刺更不敏感
+    pub geometric_price_a_x64: u128,
+    /// 以代币A计价的代币B的几何平均价格
+    pub geometric_price_b_x64: u128,
+    /// 实际使用的时间窗口（秒）
+    pub window_secs: u32,
+}
+
+/// 时间加权平均价格预言机
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Debug, Default)]
+pub struct PriceOracle {
+    /// 代币A价格的累积值（价格 * 秒，允许溢出回绕）
+    pub price_a_cumulative: u128,
+    /// 代币B价格的累积值（价格 * 秒，允许溢出回绕）
+    pub price_b_cumulative: u128,
+    /// 最近一次交易后的代币A价格
+    pub last_price_a: u128,
+    /// 最近一次交易后的代币B价格
+    pub last_price_b: u128,
+    /// 代币A价格自然对数的累积值（ln价格 * 秒，Q64.64，允许溢出回绕），代币B的对数价格为其相反数
+    pub log_price_cumulative: i128,
+    /// 最近一次交易后的代币A价格的自然对数（Q64.64）
+    pub last_log_price: i128,
+    /// 最近一次更新的时间戳
+    pub last_timestamp: i64,
+    /// 观测点的代币A累积值
+    pub observations_a: [u128; MAX_OBSERVATIONS],
+    /// 观测点的代币B累积值
+    pub observations_b: [u128; MAX_OBSERVATIONS],
+    /// 观测点的对数价格累积值
+    pub observations_log: [i128; MAX_OBSERVATIONS],
+    /// 观测点时间戳
+    pub observation_timestamps: [i64; MAX_OBSERVATIONS],
+    /// 下一个观测点的写入位置
+    pub observation_index: u8,
+}
+
+impl PriceOracle {
+    /// 每次交换后调用：先用上一次的价格累积经过的时间，再记录新的价格
+    pub fn update(&mut self, reserve_a: u64, reserve_b: u64, timestamp: i64) {
+        if self.last_timestamp > 0 && timestamp > self.last_timestamp {
+            let (price_a_cumulative, price_b_cumulative, log_price_cumulative) = self.cumulative_at(timestamp);
+            self.price_a_cumulative = price_a_cumulative;
+            self.price_b_cumulative = price_b_cumulative;
+            self.log_price_cumulative = log_price_cumulative;
+
+            // 每个时间戳只记录一个观测点
+            let index = self.observation_index as usize;
+            self.observations_a[index] = price_a_cumulative;
+            self.observations_b[index] = price_b_cumulative;
+            self.observations_log[index] = log_price_cumulative;
+            self.observation_timestamps[index] = timestamp;
+            self.observation_index = ((index + 1) % MAX_OBSERVATIONS) as u8;
+        }
+
+        if reserve_a > 0 && reserve_b > 0 {
+            let price_a = I64F64::from_num(reserve_b) / I64F64::from_num(reserve_a);
+            self.last_price_a = price_a.to_bits() as u128;
+            self.last_price_b = (I64F64::from_num(reserve_a) / I64F64::from_num(reserve_b)).to_bits() as u128;
+            if let Some(log_price) = math::ln(price_a) {
+                self.last_log_price = log_price.to_bits();
+            }
+        }
+        self.last_timestamp = timestamp;
+    }
+
+    /// 计算任意时刻的累积值
+    fn cumulative_at(&self, timestamp: i64) -> (u128, u128, i128) {
+        let elapsed = (timestamp - self.last_timestamp).max(0) as u128;
+        (
+            self.price_a_cumulative.wrapping_add(self.last_price_a.wrapping_mul(elapsed)),
+            self.price_b_cumulative.wrapping_add(self.last_price_b.wrapping_mul(elapsed)),
+            self.log_price_cumulative.wrapping_add(self.last_log_price.wrapping_mul(elapsed as i128)),
+        )
+    }
+
+    /// 获取至少覆盖window_secs的时间加权平均价格，历史不足时返回None
+    pub fn get_twap(&self, window_secs: u32, timestamp: i64) -> Option<TwapPrice> {
+        if window_secs == 0 || self.last_timestamp == 0 {
+            return None;
+        }
+        let target = timestamp - window_secs as i64;
+
+        // 找到不晚于目标时间的最近观测点
+        let mut best: Option<usize> = None;
+        let mut best_timestamp = 0;
+        for i in 0..MAX_OBSERVATIONS {
+            let observed_at = self.observation_timestamps[i];
+            if observed_at > best_timestamp && observed_at <= target {
+                best = Some(i);
+                best_timestamp = observed_at;
+            }
+        }
+        let index = best?;
+
+        let elapsed = (timestamp - self.observation_timestamps[index]) as u128;
+        let (price_a_cumulative, price_b_cumulative, log_price_cumulative) = self.cumulative_at(timestamp);
+
+        // 对数价格的时间平均取指数即为几何平均价格
+        let mean_log_price = I64F64::from_bits(log_price_cumulative.wrapping_sub(self.observations_log[index]) / elapsed as i128);
+        Some(TwapPrice {
+            price_a_x64: price_a_cumulative.wrapping_sub(self.observations_a[index]) / elapsed,
+            price_b_x64: price_b_cumulative.wrapping_sub(self.observations_b[index]) / elapsed,
+            geometric_price_a_x64: math::exp(mean_log_price)?.to_bits() as u128,
+            geometric_price_b_x64: math::exp(-mean_log_price)?.to_bits() as u128,
+            window_secs: elapsed.min(u32::MAX as u128) as u32,
+        })
+    }
+}
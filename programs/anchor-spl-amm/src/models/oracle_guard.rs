@@ -0,0 +1,110 @@
+use anchor_lang::prelude::*;
+use fixed::types::I64F64;
+use pyth_sdk_solana::load_price_feed_from_account_info;
+
+use crate::errors::TutorialError;
+
+/// 外部价格预言机（Pyth）保护配置，price_feed为默认地址时不启用
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, Default, PartialEq)]
+pub struct OracleGuardConfig {
+    /// Pyth价格账户
+    pub price_feed: Pubkey,
+    /// 成交价格与预言机价格的最大偏差（基点）
+    pub max_deviation_bps: u16,
+    /// 预言机价格的最大有效期（秒）
+    pub max_staleness_secs: u64,
+    /// false表示预言机报价为以代币B计价的代币A价格，true表示以代币A计价的代币B价格
+    pub invert: bool,
+}
+
+impl OracleGuardConfig {
+    /// 是否启用预言机保护
+    pub fn is_enabled(&self) -> bool {
+        self.price_feed != Pubkey::default()
+    }
+
+    /// 启用时偏差阈值必须在 (0, 10000] 内，且有效期不能为0
+    pub fn is_valid(&self) -> bool {
+        !self.is_enabled()
+            || (self.max_deviation_bps > 0 && self.max_deviation_bps <= 10000 && self.max_staleness_secs > 0)
+    }
+
+    /// 读取预言机价格，统一换算为以代币B计价的代币A价格（展示单位）
+    pub fn oracle_price(&self, price_feed: &AccountInfo, now: i64) -> Result<I64F64> {
+        if price_feed.key() != self.price_feed {
+            return err!(TutorialError::InvalidOracleAccount);
+        }
+        let feed = load_price_feed_from_account_info(price_feed)
+            .map_err(|_| error!(TutorialError::InvalidOracleAccount))?;
+        let price = feed
+            .get_price_no_older_than(now, self.max_staleness_secs)
+            .ok_or(TutorialError::StaleOraclePrice)?;
+        if price.price <= 0 {
+            return err!(TutorialError::InvalidOracleAccount);
+        }
+
+        let price = scale_by_pow10(I64F64::from_num(price.price), price.expo)?;
+        if self.invert {
+            I64F64::ONE.checked_div(price).ok_or(error!(TutorialError::MathOverflow))
+        } else {
+            Ok(price)
+        }
+    }
+
+    /// 未启用时直接通过；启用时要求传入价格账户，且成交价格偏离预言机价格不超过阈值
+    pub fn check(&self, price_feed: Option<&AccountInfo>, execution_price: I64F64, now: i64) -> Result<()> {
+        if !self.is_enabled() {
+            return Ok(());
+        }
+        let price_feed = price_feed.ok_or(TutorialError::OracleAccountRequired)?;
+        let oracle_price = self.oracle_price(price_feed, now)?;
+        if deviation_bps(execution_price, oracle_price) > self.max_deviation_bps as u64 {
+            return err!(TutorialError::OraclePriceDeviation);
+        }
+        Ok(())
+    }
+}
+
+/// 成交价格：以代币B计价的代币A价格，已按两种代币的小数位换算为展示单位
+///
+/// 传入的输入为扣除手续费后的数量，避免把手续费计入偏差
+pub fn execution_price(
+    swap_a: bool,
+    input: u64,
+    output: u64,
+    decimals_a: u8,
+    decimals_b: u8,
+) -> Result<I64F64> {
+    let (amount_a, amount_b) = if swap_a { (input, output) } else { (output, input) };
+    if amount_a == 0 {
+        return err!(TutorialError::InsufficientLiquidity);
+    }
+    let raw_price = I64F64::from_num(amount_b) / I64F64::from_num(amount_a);
+    scale_by_pow10(raw_price, decimals_a as i32 - decimals_b as i32)
+}
+
+/// 两个价格的相对偏差（基点）
+pub fn deviation_bps(price: I64F64, reference: I64F64) -> u64 {
+    if reference <= I64F64::ZERO {
+        return u64::MAX;
+    }
+    let diff = if price > reference { price - reference } else { reference - price };
+    diff.checked_div(reference)
+        .and_then(|ratio| ratio.checked_mul(I64F64::from_num(10000)))
+        .map(|bps| bps.to_num::<u64>())
+        .unwrap_or(u64::MAX)
+}
+
+/// value * 10^exponent
+fn scale_by_pow10(value: I64F64, exponent: i32) -> Result<I64F64> {
+    let ten = I64F64::from_num(10);
+    let mut result = value;
+    for _ in 0..exponent.unsigned_abs() {
+        result = if exponent > 0 {
+            result.checked_mul(ten).ok_or(TutorialError::MathOverflow)?
+        } else {
+            result / ten
+        };
+    }
+    Ok(result)
+}
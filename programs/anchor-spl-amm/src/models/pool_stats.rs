@@ -0,0 +1,100 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{VOLUME_BUCKETS, VOLUME_BUCKET_SECS};
+
+/// 一个小时内的成交量和手续费
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, Default, PartialEq)]
+pub struct VolumeBucket {
+    /// 桶对应的小时（unix时间戳 / 3600）
+    pub hour: i64,
+    pub volume_a: u64,
+    pub volume_b: u64,
+    pub fees_a: u64,
+    pub fees_b: u64,
+}
+
+impl VolumeBucket {
+    fn add(&mut self, other: &VolumeBucket) {
+        self.volume_a = self.volume_a.saturating_add(other.volume_a);
+        self.volume_b = self.volume_b.saturating_add(other.volume_b);
+        self.fees_a = self.fees_a.saturating_add(other.fees_a);
+        self.fees_b = self.fees_b.saturating_add(other.fees_b);
+    }
+}
+
+/// 池子的累计统计数据，随每笔交易、存款和提取更新，分析方无需回放全部历史
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, Default, PartialEq)]
+pub struct PoolStats {
+    /// 累计流经池子的代币A数量（流入和流出）
+    pub volume_a: u128,
+    /// 累计流经池子的代币B数量（流入和流出）
+    pub volume_b: u128,
+    /// 累计以代币A收取的交易手续费（含协议分成）
+    pub fees_a: u128,
+    /// 累计以代币B收取的交易手续费（含协议分成）
+    pub fees_b: u128,
+    /// 交易次数
+    pub swap_count: u64,
+    /// 存款次数
+    pub deposit_count: u64,
+    /// 提取次数
+    pub withdraw_count: u64,
+    /// 最近24小时按小时划分的成交量环形缓冲区，以小时对桶数取模定位
+    pub hourly: [VolumeBucket; VOLUME_BUCKETS],
+}
+
+impl PoolStats {
+    /// 记录一笔交易
+    pub fn record_swap(&mut self, swap_a: bool, input: u64, output: u64, fee: u64, now: i64) {
+        self.record_volume(swap_a, input, output, fee, now);
+        self.swap_count += 1;
+    }
+
+    /// 记录成交量和手续费，单币存取中的池内交换只计入成交量，不计交易次数
+    pub fn record_volume(&mut self, swap_a: bool, input: u64, output: u64, fee: u64, now: i64) {
+        let (volume_in, volume_out, fees_in) = if swap_a {
+            (&mut self.volume_a, &mut self.volume_b, &mut self.fees_a)
+        } else {
+            (&mut self.volume_b, &mut self.volume_a, &mut self.fees_b)
+        };
+        *volume_in = volume_in.saturating_add(input as u128);
+        *volume_out = volume_out.saturating_add(output as u128);
+        *fees_in = fees_in.saturating_add(fee as u128);
+
+        // 写入当前小时的桶，桶中是更早的数据时先清空
+        let hour = now.div_euclid(VOLUME_BUCKET_SECS);
+        let bucket = &mut self.hourly[hour.rem_euclid(VOLUME_BUCKETS as i64) as usize];
+        if bucket.hour != hour {
+            *bucket = VolumeBucket { hour, ..Default::default() };
+        }
+        let (bucket_a, bucket_b) = if swap_a { (input, output) } else { (output, input) };
+        bucket.add(&VolumeBucket {
+            hour,
+            volume_a: bucket_a,
+            volume_b: bucket_b,
+            fees_a: if swap_a { fee } else { 0 },
+            fees_b: if swap_a { 0 } else { fee },
+        });
+    }
+
+    /// 最近24小时（含当前小时）的成交量和手续费合计
+    pub fn rolling_volume(&self, now: i64) -> VolumeBucket {
+        let hour = now.div_euclid(VOLUME_BUCKET_SECS);
+        let mut total = VolumeBucket { hour, ..Default::default() };
+        self.hourly
+            .iter()
+            .filter(|bucket| bucket.hour > hour - VOLUME_BUCKETS as i64 && bucket.hour <= hour)
+            .for_each(|bucket| total.add(bucket));
+        total
+    }
+
+    /// 记录一次存款
+    pub fn record_deposit(&mut self) {
+        self.deposit_count += 1;
+    }
+
+    /// 记录一次提取
+    pub fn record_withdraw(&mut self) {
+        self.withdraw_count += 1;
+    }
+}
@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+/// 单个池子的运行状态，用于只限制出问题的交易对而不暂停整个AMM
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum PoolStatus {
+    /// 正常运行
+    #[default]
+    Active,
+    /// 只允许交换，拒绝存款和提取
+    SwapOnly,
+    /// 拒绝存款，交换和提取不受影响
+    DepositDisabled,
+    /// 冻结：拒绝交换、存款、提取和领取手续费
+    Frozen,
+}
+
+impl PoolStatus {
+    pub fn allows_swaps(&self) -> bool {
+        *self != PoolStatus::Frozen
+    }
+
+    pub fn allows_deposits(&self) -> bool {
+        *self == PoolStatus::Active
+    }
+
+    pub fn allows_withdrawals(&self) -> bool {
+        matches!(self, PoolStatus::Active | PoolStatus::DepositDisabled)
+    }
+
+    /// 领取手续费和同步储备等维护操作只在冻结时拒绝
+    pub fn is_frozen(&self) -> bool {
+        *self == PoolStatus::Frozen
+    }
+}
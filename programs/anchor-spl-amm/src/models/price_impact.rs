@@ -1,8 +1,9 @@
+use amm_math::price_impact;
 use anchor_lang::prelude::*;
 use fixed::types::I64F64;
 
 /// 价格影响配置
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, PartialEq)]
 pub struct PriceImpactConfig {
     /// 是否启用高级价格影响保护
     pub enabled: bool,
@@ -10,11 +11,15 @@ pub struct PriceImpactConfig {
     pub max_slippage_bps: u16,
     /// 动态滑点调整系数（放大1000倍）
     pub dynamic_adjustment_factor: u16,
+    /// 单笔交易输入占输入端储备的最大比例（基点），0表示不限制
+    pub max_trade_pct_bps: u16,
 }
 
 impl PriceImpactConfig {
-    // 计算结构体的大小：bool(1) + 2个u16(4)
-    pub const LEN: usize = 1 + 2 * 2;
+    /// 最大滑点和单笔交易比例不能超过100%
+    pub fn is_valid(&self) -> bool {
+        self.max_slippage_bps <= 10000 && self.max_trade_pct_bps <= 10000
+    }
 }
 
 impl Default for PriceImpactConfig {
@@ -23,6 +28,7 @@ impl Default for PriceImpactConfig {
             enabled: false,
             max_slippage_bps: 50, // 默认0.5%最大滑点
             dynamic_adjustment_factor: 1000, // 默认1.0
+            max_trade_pct_bps: 1000, // 默认单笔最多10%的储备
         }
     }
 }
@@ -33,38 +39,43 @@ pub struct PriceImpactCalculator;
 impl PriceImpactCalculator {
     /// 计算交易的价格影响
     pub fn calculate_price_impact(
-        config: &PriceImpactConfig,
+        _config: &PriceImpactConfig,
         input_amount: u64,
         output_amount: u64,
         reserve_in: u64,
         reserve_out: u64,
     ) -> I64F64 {
-        // 计算交易前后的价格变化
-        let price_before = I64F64::from_num(reserve_out) / I64F64::from_num(reserve_in);
-        let price_after = I64F64::from_num(reserve_out - output_amount) / I64F64::from_num(reserve_in + input_amount);
-        
-        // 计算价格影响百分比
-        let price_impact = I64F64::from_num(1) - (price_after / price_before);
-        
-        price_impact
+        price_impact::price_impact(input_amount, output_amount, reserve_in, reserve_out)
     }
     
+    /// 成交价格（每单位输出支付的输入，Q64.64）是否不高于交易者给出的最差价格，未给出时不限制
+    pub fn is_within_price_limit(input_amount: u64, output_amount: u64, max_price_x64: Option<u128>) -> bool {
+        price_impact::is_within_price_limit(input_amount, output_amount, max_price_x64)
+    }
+
+    /// 将价格影响转换为基点，用于日志和事件
+    pub fn to_bps(price_impact: I64F64) -> u16 {
+        price_impact::to_bps(price_impact)
+    }
+
     /// 检查交易是否超过最大允许的价格影响
     pub fn is_price_impact_acceptable(
         config: &PriceImpactConfig,
         price_impact: I64F64,
     ) -> bool {
-        if !config.enabled {
-            return true; // 如果未启用高级价格影响保护，默认接受任何价格影响
-        }
-        
-        // 将价格影响转换为基点值进行比较
-        let impact_bps = price_impact * I64F64::from_num(10000);
-        let max_slippage = I64F64::from_num(config.max_slippage_bps);
-        
-        impact_bps <= max_slippage
+        // 如果未启用高级价格影响保护，默认接受任何价格影响
+        !config.enabled || price_impact::is_within_slippage(price_impact, config.max_slippage_bps)
     }
     
+    /// 检查单笔交易的输入是否超过输入端储备的最大比例，防止大额交易瞬间掏空小池子
+    pub fn is_trade_size_acceptable(
+        config: &PriceImpactConfig,
+        input_amount: u64,
+        reserve_in: u64,
+    ) -> bool {
+        !config.enabled || price_impact::is_trade_size_acceptable(config.max_trade_pct_bps, input_amount, reserve_in)
+    }
+
     /// 根据价格影响动态调整输出金额
     pub fn adjust_output_for_slippage(
         config: &PriceImpactConfig,
@@ -74,21 +85,7 @@ impl PriceImpactCalculator {
         if !config.enabled {
             return output_amount; // 如果未启用，不调整输出
         }
-        
-        // 根据价格影响计算调整系数
-        let adjustment_factor = I64F64::from_num(1) - 
-            (price_impact * I64F64::from_num(config.dynamic_adjustment_factor) / I64F64::from_num(1000));
-        
-        // 确保调整系数不会低于某个阈值（例如0.9）
-        let min_adjustment = I64F64::from_num(0.9);
-        let final_adjustment = if adjustment_factor < min_adjustment {
-            min_adjustment
-        } else {
-            adjustment_factor
-        };
-        
-        // 计算调整后的输出金额
-        (I64F64::from_num(output_amount) * final_adjustment).to_num::<u64>()
+        price_impact::adjust_output_for_slippage(config.dynamic_adjustment_factor, output_amount, price_impact)
     }
     
     /// 检查交易是否有利
@@ -97,10 +94,6 @@ impl PriceImpactCalculator {
         output_value: I64F64,
         fee_percentage: I64F64,
     ) -> bool {
-        // 计算交易成本（包括费用）
-        let cost = input_value * (I64F64::from_num(1) + fee_percentage);
-        
-        // 如果输出价值大于输入价值加费用，则交易有利
-        output_value > cost
+        price_impact::is_trade_beneficial(input_value, output_value, fee_percentage)
     }
-} 
\ No newline at end of file
+}
@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+
+/// 全局功能开关，新功能可以先关闭上线，之后由配置权限打开，无需重新部署
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, PartialEq)]
+pub struct FeatureFlags {
+    /// 允许闪电贷
+    pub flash_loans_enabled: bool,
+    /// 允许使用Token-2022代币创建池子
+    pub token_2022_enabled: bool,
+    /// 任何人都可以创建AMM，关闭后只有配置权限可以创建
+    pub permissionless_amms: bool,
+    /// 新建AMM的默认协议分成（基点）
+    pub default_protocol_fee_bps: u16,
+}
+
+impl Default for FeatureFlags {
+    /// 创建配置账户之前的行为，与引入功能开关之前一致
+    fn default() -> Self {
+        Self {
+            flash_loans_enabled: true,
+            token_2022_enabled: true,
+            permissionless_amms: true,
+            default_protocol_fee_bps: 0,
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+
+/// 交换报价，与实际执行使用同一套计算
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct SwapQuote {
+    /// 交易者支付的输入数量
+    pub input_amount: u64,
+    /// 交易手续费总额
+    pub fee_amount: u64,
+    /// 手续费中归属协议的部分
+    pub protocol_fee: u64,
+    /// 手续费中注入无常损失补偿金库的部分
+    pub il_compensation_fee: u64,
+    /// 扣除手续费和转账费后参与定价的输入
+    pub taxed_input: u64,
+    /// 池子转出的数量
+    pub output_amount: u64,
+    /// 交易者扣除转账费后实际收到的数量
+    pub net_output_amount: u64,
+    /// 价格影响（基点）
+    pub price_impact_bps: u16,
+    /// 手续费是否以输出代币收取，是则三项费用均以输出代币计
+    pub fee_on_output: bool,
+    /// 池子实际收到的输入数量，包含留在池子里的LP手续费
+    pub pool_received: u64,
+}
+
+impl SwapQuote {
+    /// 不计手续费时交易后的储备（输入端、输出端），用于校验不变量的增长
+    pub fn fee_excluded_reserves(&self, reserve_in: u64, reserve_out: u64) -> (u64, u64) {
+        let output_fee = if self.fee_on_output { self.fee_amount } else { 0 };
+        (reserve_in + self.taxed_input, reserve_out - self.output_amount - output_fee)
+    }
+
+    /// 交易后记录的储备（输入端、输出端）：输入端加上池子实际收到的数量，
+    /// 输出端减去转出的数量以及以输出代币收取的协议分成和补偿分成
+    pub fn reserves_after(&self, reserve_in: u64, reserve_out: u64) -> (u64, u64) {
+        (reserve_in + self.pool_received, reserve_out - self.output_amount - self.output_side_fees())
+    }
+
+    /// 从池子输出端转出的协议分成和补偿分成，手续费以输入代币收取时为0
+    pub fn output_side_fees(&self) -> u64 {
+        if self.fee_on_output {
+            self.protocol_fee + self.il_compensation_fee
+        } else {
+            0
+        }
+    }
+}
+
+/// 存款预览，与deposit_liquidity使用同一套计算和舍入
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct DepositPreview {
+    /// 按池子比例调整后实际存入的代币A数量
+    pub amount_a: u64,
+    /// 按池子比例调整后实际存入的代币B数量
+    pub amount_b: u64,
+    /// 铸造的流动性代币数量
+    pub liquidity: u64,
+}
+
+/// 提取预览，与withdraw_liquidity使用同一套计算和舍入
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct WithdrawPreview {
+    /// 池子转出的代币A数量，已扣除提取费
+    pub amount_a: u64,
+    /// 池子转出的代币B数量，已扣除提取费
+    pub amount_b: u64,
+    /// 适用的提取费率（基点）
+    pub withdrawal_fee_bps: u16,
+}
+
+/// 一个流动性代币（10^小数位数个最小单位）背后的两种代币数量，随手续费积累而增长，
+/// 供借贷协议在链上为LP抵押品定价
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct LpTokenValue {
+    /// 每个流动性代币对应的代币A数量
+    pub amount_a: u64,
+    /// 每个流动性代币对应的代币B数量
+    pub amount_b: u64,
+    /// 流动性代币的小数位数
+    pub lp_decimals: u8,
+}
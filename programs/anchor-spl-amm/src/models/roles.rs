@@ -0,0 +1,15 @@
+use anchor_lang::prelude::*;
+
+/// AMM的运维角色，职责可以分给不同的密钥或多签。所有者（即admin）可以执行所有角色的操作，
+/// 其他角色未授予时为默认地址，只能由所有者执行
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Role {
+    /// 所有者，负责授予和撤销其他角色
+    Owner,
+    /// 费率、手续费接收方和费用豁免
+    FeeManager,
+    /// 熔断
+    Pauser,
+    /// 代币准入、价格保护、外部预言机和存款上限等风控参数
+    RiskManager,
+}
@@ -0,0 +1,12 @@
+use anchor_lang::prelude::*;
+
+/// 精确输入交换的可选行为，默认全部关闭
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct SwapOptions {
+    /// 完整输入超出价格影响或单笔交易上限时，只成交上限内的最大输入
+    pub allow_partial_fill: bool,
+    /// 可接受的最差成交价格（每单位输出的输入数量，Q64.64）
+    pub max_price_x64: Option<u128>,
+    /// 输入超出交易者余额时按余额成交，否则拒绝交换
+    pub clamp_to_balance: bool,
+}
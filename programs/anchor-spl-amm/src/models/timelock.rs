@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+use super::{
+    curve::CurveType, fee_strategy::FeeConfig, roles::Role, volatility::VolatilityConfig,
+    withdrawal_fee::WithdrawalFeeConfig,
+};
+
+/// 需要经过时间锁的参数变更
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug)]
+pub enum ParameterChange {
+    /// 替换AMM的费用配置，用于提高费率或修改协议分成
+    FeeConfig(FeeConfig),
+    /// 修改池子的曲线参数（如稳定币曲线的放大系数），曲线种类不变
+    Curve { pool: Pubkey, curve_type: CurveType },
+    /// 缩短时间锁的延迟，延长可以直接设置
+    TimelockDelay(i64),
+    /// 替换LP提取费配置
+    WithdrawalFeeConfig(WithdrawalFeeConfig),
+    /// 替换波动率配置
+    VolatilityConfig(VolatilityConfig),
+    /// 切换手续费的收取端
+    FeeSide(bool),
+}
+
+impl ParameterChange {
+    /// 提议该变更所需的角色
+    pub fn required_role(&self) -> Role {
+        match self {
+            ParameterChange::FeeConfig(_) => Role::FeeManager,
+            ParameterChange::Curve { .. } => Role::RiskManager,
+            ParameterChange::TimelockDelay(_) => Role::Owner,
+            ParameterChange::WithdrawalFeeConfig(_)
+            | ParameterChange::VolatilityConfig(_)
+            | ParameterChange::FeeSide(_) => Role::FeeManager,
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use crate::constants::{MAX_BOOST_BPS, MAX_LOCK_DURATION};
+
+/// ve（vote-escrow）加成计算器
+///
+/// 锁定的剩余时间越长加成越高：剩余时间达到最长期限时为最大加成，随到期临近线性衰减至1倍。
+/// 加成作用于锁定流动性的手续费分配和挖矿奖励。
+pub struct VeBoost;
+
+impl VeBoost {
+    /// 计算当前的加成倍数（基点），10000表示没有加成
+    pub fn boost_bps(unlock_at: i64, now: i64) -> u16 {
+        let remaining = unlock_at.saturating_sub(now).clamp(0, MAX_LOCK_DURATION);
+        let extra = (MAX_BOOST_BPS - 10000) as i128 * remaining as i128 / MAX_LOCK_DURATION as i128;
+        10000 + extra as u16
+    }
+
+    /// 按加成倍数放大后的数量，用于计算奖励权重
+    pub fn boosted_amount(amount: u64, boost_bps: u16) -> u64 {
+        let boosted = amount as u128 * boost_bps as u128 / 10000;
+        u64::try_from(boosted).unwrap_or(u64::MAX)
+    }
+
+    /// 加成带来的额外数量，不含基础部分
+    pub fn bonus(amount: u64, boost_bps: u16) -> u64 {
+        Self::boosted_amount(amount, boost_bps).saturating_sub(amount)
+    }
+}
@@ -1,12 +1,13 @@
 use anchor_lang::prelude::*;
 use fixed::types::I64F64;
-use std::f64;
 
-/// 最大价格样本数
-pub const MAX_SAMPLES: usize = 24;
+use crate::models::math::ln;
+
+/// 每个价格样本在账户数据中占用的字节数：i128价格 + i64时间戳
+pub const OBSERVATION_LEN: usize = 16 + 8;
 
 /// 波动率跟踪配置
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, PartialEq)]
 pub struct VolatilityConfig {
     /// 是否启用波动率跟踪和保护
     pub enabled: bool,
@@ -26,6 +27,14 @@ pub struct VolatilityConfig {
     pub compensation_factor: i64,
     /// 补偿周期（秒）
     pub compensation_period: i64,
+    /// 两次重新计算波动率之间的最小间隔（秒）
+    pub update_interval: i64,
+    /// 触发熔断的波动率阈值（放大1000倍），0表示不启用熔断
+    pub circuit_breaker_threshold: u16,
+    /// 熔断后暂停交换的时长（秒）
+    pub circuit_breaker_cooldown: i64,
+    /// 两次记录价格样本之间的最小间隔（秒），避免同一时段的大量交易挤占样本窗口
+    pub min_sample_interval_secs: i64,
 }
 
 impl Default for VolatilityConfig {
@@ -40,13 +49,31 @@ impl Default for VolatilityConfig {
             decay_lambda: 950,
             compensation_factor: 1000,
             compensation_period: 86400,
+            update_interval: 3600, // 与年化假设一致，每小时最多计算一次
+            circuit_breaker_threshold: 0,
+            circuit_breaker_cooldown: 3600,
+            min_sample_interval_secs: 60,
         }
     }
 }
 
 impl VolatilityConfig {
-    // 计算结构体的大小：2个u8(2) + 3个i64(24)
-    pub const LEN: usize = 2 + 3 * 8;
+    /// 校验配置：放大1000倍的系数不超过1，衰减系数必须为正，样本窗口至少包含一个收益率，时间参数不能为负
+    pub fn is_valid(&self) -> bool {
+        self.protection_factor <= 1000
+            && self.decay_factor > 0
+            && self.decay_factor <= 1000
+            && self.decay_lambda > 0
+            && self.decay_lambda <= 1000
+            && (0..=1000).contains(&self.compensation_factor)
+            && self.window_size >= 2
+            && self.min_samples >= 1
+            && self.min_samples <= self.window_size
+            && self.compensation_period > 0
+            && self.update_interval >= 0
+            && self.circuit_breaker_cooldown >= 0
+            && self.min_sample_interval_secs >= 0
+    }
 }
 
 /// 价格采样数据，用于跟踪历史价格
@@ -59,53 +86,57 @@ pub struct PriceSample {
 }
 
 /// 波动率监测器
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+///
+/// 价格样本不在结构体中，而是以环形缓冲区的形式存放在池子预言机账户的尾部，
+/// 样本数量在创建池子时选择，交换时不需要反序列化整个缓冲区
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Debug, Default)]
 pub struct VolatilityTracker {
-    /// 价格历史样本（使用i128存储I64F64值）
-    pub price_samples: [i128; MAX_SAMPLES],
-    /// 对应时间戳
-    pub timestamps: [i64; MAX_SAMPLES],
+    /// 价格样本缓冲区的容量
+    pub observation_count: u16,
     /// 当前样本索引
-    pub current_index: u8,
+    pub current_index: u16,
     /// 计算出的波动率（使用i128存储I64F64值）
     pub volatility_raw: i128,
     /// 最后更新时间
     pub last_updated: i64,
     /// 最后补偿时间
     pub last_compensated: i64,
+    /// 最后一次重新计算波动率的时间
+    pub last_computed: i64,
 }
 
 impl VolatilityTracker {
-    /// 计算结构体的大小：MAX_SAMPLES个i128(16*24) + MAX_SAMPLES个i64(8*24) + u8(1) + i128(16) + 2个i64(16)
-    pub const LEN: usize = MAX_SAMPLES * 16 + MAX_SAMPLES * 8 + 1 + 16 + 16;
-    
-    /// 添加新的价格样本并更新波动率
-    pub fn update_price_sample(&mut self, current_price: I64F64, timestamp: i64, config: &VolatilityConfig) {
-        if !config.enabled {
+    /// 读取缓冲区中的一个样本，返回价格和时间戳
+    fn sample(samples: &[u8], index: usize) -> (I64F64, i64) {
+        let offset = index * OBSERVATION_LEN;
+        let price = i128::from_le_bytes(samples[offset..offset + 16].try_into().unwrap());
+        let timestamp = i64::from_le_bytes(samples[offset + 16..offset + OBSERVATION_LEN].try_into().unwrap());
+        (I64F64::from_bits(price), timestamp)
+    }
+
+    /// 缓冲区是否已经按选择的容量分配完成
+    pub fn is_allocated(&self, samples: &[u8]) -> bool {
+        self.observation_count > 0 && samples.len() >= self.observation_count as usize * OBSERVATION_LEN
+    }
+
+    /// 添加新的价格样本，波动率由 update_volatility 单独重新计算，交换只读取缓存值
+    pub fn update_price_sample(&mut self, samples: &mut [u8], current_price: I64F64, timestamp: i64, config: &VolatilityConfig) {
+        if !config.enabled || !self.is_allocated(samples) {
             return;
         }
-        
-        // 存储前一个价格来计算收益率
-        let prev_index = if self.current_index == 0 {
-            MAX_SAMPLES - 1
-        } else {
-            (self.current_index - 1) as usize
-        };
-        
-        // 如果已经有样本，计算对数收益率并更新波动率
-        if self.timestamps[prev_index] > 0 {
-            // 计算对数收益率
-            let prev_price = I64F64::from_bits(self.price_samples[prev_index]);
-            // 更新当前波动率计算
-            self.calculate_volatility(config);
+
+        // 距离上一个样本太近时跳过，保留样本窗口中的历史
+        if self.last_updated > 0 && timestamp - self.last_updated < config.min_sample_interval_secs {
+            return;
         }
         
         // 存储新的价格样本
-        self.price_samples[self.current_index as usize] = current_price.to_bits();
-        self.timestamps[self.current_index as usize] = timestamp;
+        let offset = self.current_index as usize * OBSERVATION_LEN;
+        samples[offset..offset + 16].copy_from_slice(&current_price.to_bits().to_le_bytes());
+        samples[offset + 16..offset + OBSERVATION_LEN].copy_from_slice(&timestamp.to_le_bytes());
         
         // 更新索引
-        self.current_index = ((self.current_index as usize + 1) % MAX_SAMPLES) as u8;
+        self.current_index = ((self.current_index as usize + 1) % self.observation_count as usize) as u16;
         self.last_updated = timestamp;
     }
     
@@ -113,34 +144,48 @@ impl VolatilityTracker {
     pub fn get_volatility(&self) -> I64F64 {
         I64F64::from_bits(self.volatility_raw)
     }
+
+    /// 距离上次计算是否已经超过配置的间隔
+    pub fn is_update_due(&self, config: &VolatilityConfig, timestamp: i64) -> bool {
+        timestamp - self.last_computed >= config.update_interval
+    }
+
+    /// 根据已存储的价格样本重新计算波动率，返回是否超过了熔断阈值
+    pub fn update_volatility(&mut self, samples: &[u8], config: &VolatilityConfig, timestamp: i64) -> bool {
+        if self.is_allocated(samples) {
+            self.calculate_volatility(samples, config);
+        }
+        self.last_computed = timestamp;
+
+        let threshold = I64F64::from_num(config.circuit_breaker_threshold) / I64F64::from_num(1000);
+        config.circuit_breaker_threshold > 0 && self.get_volatility() > threshold
+    }
     
     /// 内部方法：计算波动率
-    fn calculate_volatility(&mut self, config: &VolatilityConfig) {
+    fn calculate_volatility(&mut self, samples: &[u8], config: &VolatilityConfig) {
         let mut sum_squared_returns = I64F64::from_num(0);
         let mut valid_samples = 0;
+        let count = self.observation_count as usize;
         
-        for i in 0..config.window_size as usize {
-            let idx = (self.current_index as usize + MAX_SAMPLES - 1 - i) % MAX_SAMPLES;
-            let prev_idx = (idx + MAX_SAMPLES - 1) % MAX_SAMPLES;
+        for i in 0..(config.window_size as usize).min(count) {
+            let idx = (self.current_index as usize + count - 1 - i) % count;
+            let prev_idx = (idx + count - 1) % count;
+            let (price, timestamp) = Self::sample(samples, idx);
+            let (prev_price, prev_timestamp) = Self::sample(samples, prev_idx);
             
             // 确保有两个有效的连续样本
-            if self.timestamps[idx] > 0 && self.timestamps[prev_idx] > 0 {
-                let price = I64F64::from_bits(self.price_samples[idx]);
-                let prev_price = I64F64::from_bits(self.price_samples[prev_idx]);
-                
-                // 计算对数收益率
-                let price_f64 = price.to_num::<f64>();
-                let prev_price_f64 = prev_price.to_num::<f64>();
+            if timestamp > 0 && prev_timestamp > 0 {
                 
-                if price_f64 > 0.0 && prev_price_f64 > 0.0 {
-                    let log_return = I64F64::from_num(f64::ln(price_f64 / prev_price_f64));
+                // 计算对数收益率：ln(P/P') = ln(P) - ln(P')，分开计算避免比值下溢
+                if let (Some(ln_price), Some(ln_prev_price)) = (ln(price), ln(prev_price)) {
+                    let log_return = ln_price - ln_prev_price;
                     
                     // 应用时间衰减
                     let decay = I64F64::from_num(config.decay_lambda) / I64F64::from_num(1000);
                     // 使用乘法代替powi
                     let mut weight = I64F64::from_num(1);
                     for _ in 0..i {
-                        weight = weight * decay;
+                        weight *= decay;
                     }
                     
                     // 累加加权平方收益率
@@ -161,7 +206,7 @@ impl VolatilityTracker {
         }
     }
     
-    /// 根据当前波动率计算非永久性损失补偿
+    /// 根据当前波动率计算非永久性损失补偿，补偿周期按头寸各自的上次领取时间计算
     pub fn calculate_il_compensation(
         &self,
         initial_price: I64F64, 
@@ -169,8 +214,9 @@ impl VolatilityTracker {
         liquidity_value: u64, 
         config: &VolatilityConfig,
         current_timestamp: i64,
+        last_compensated: i64,
     ) -> u64 {
-        if !config.enabled || current_timestamp - self.last_compensated < config.compensation_period {
+        if !config.enabled || current_timestamp - last_compensated < config.compensation_period {
             return 0;
         }
         
@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+
+/// LP提取费配置：持有时间越短费率越高，线性衰减到0，用于抑制只在大额交易前后短暂提供流动性的JIT行为
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, PartialEq)]
+pub struct WithdrawalFeeConfig {
+    /// 刚存入时的提取费率（基点），0表示不收取
+    pub fee_bps: u16,
+    /// 费率衰减到0所需的持有时间（秒）
+    pub decay_period: i64,
+}
+
+impl Default for WithdrawalFeeConfig {
+    fn default() -> Self {
+        Self {
+            fee_bps: 0,          // 默认不收取提取费
+            decay_period: 86400, // 默认持有1天后免费
+        }
+    }
+}
+
+impl WithdrawalFeeConfig {
+    /// 费率不超过100%，收取提取费时衰减周期必须为正
+    pub fn is_valid(&self) -> bool {
+        self.fee_bps <= 10000 && (self.fee_bps == 0 || self.decay_period > 0)
+    }
+
+    /// 提取amount个流动性代币的费率：存款记录覆盖的recorded_liquidity部分按持有时间衰减，
+    /// 超出记录的部分（如从其他地址转入的流动性）按刚存入计算；
+    /// 两部分按数量加权并向上取整，超出部分的提取费不会因取整而减少
+    pub fn fee_rate_bps(&self, held_secs: i64, recorded_liquidity: u64, amount: u64) -> u16 {
+        let decayed_bps = self.decayed_rate_bps(held_secs);
+        let covered = recorded_liquidity.min(amount);
+        if covered == amount {
+            return decayed_bps;
+        }
+
+        let excess = (amount - covered) as u128;
+        let weighted = covered as u128 * decayed_bps as u128 + excess * self.fee_bps as u128;
+        weighted.div_ceil(amount as u128) as u16
+    }
+
+    /// 按持有时间衰减后的费率
+    fn decayed_rate_bps(&self, held_secs: i64) -> u16 {
+        let held_secs = held_secs.max(0);
+        if self.fee_bps == 0 || held_secs >= self.decay_period {
+            return 0;
+        }
+
+        (self.fee_bps as i128 * (self.decay_period - held_secs) as i128 / self.decay_period as i128) as u16
+    }
+
+    /// 扣除提取费后的数量，提取费留在池子里归剩余LP所有
+    pub fn apply(amount: u64, fee_rate_bps: u16) -> u64 {
+        amount - (amount as u128 * fee_rate_bps as u128 / 10000) as u64
+    }
+}
@@ -1,15 +1,32 @@
 use anchor_lang::prelude::*;
 use fixed::types::I64F64;
 
+use crate::constants::{MAX_DENYLIST_LEN, MAX_FARM_REWARDS, MAX_MINT_LIST_LEN, REVEAL_WINDOW_SLOTS, VOLUME_PERIOD_SECS};
+use crate::errors::TutorialError;
 use crate::models::{
-    concentrated_liquidity::ConcentratedLiquidityConfig,
+    concentrated_liquidity::{ConcentratedLiquidityConfig, PriceRange},
+    curve::CurveType,
+    farming::FarmReward,
+    flash_loan::FlashLoanState,
+    governance::GovernanceAction,
+    oracle::PriceOracle,
+    oracle_guard::OracleGuardConfig,
+    pool_stats::PoolStats,
+    pool_status::PoolStatus,
     price_impact::PriceImpactConfig,
-    volatility::{VolatilityConfig, VolatilityTracker},
-    fee_strategy::{FeeStrategy, FeeConfig},
+    program_config::FeatureFlags,
+    roles::Role,
+    timelock::ParameterChange,
+    volatility::{VolatilityConfig, VolatilityTracker, OBSERVATION_LEN},
+    fee_strategy::{FeeConfig, FeeStrategy},
+    lp_fees::LpFeeCalculator,
+    mint_policy::{MintListMode, MintPolicy},
+    ve_boost::VeBoost,
+    withdrawal_fee::WithdrawalFeeConfig,
 };
 
 #[account]
-#[derive(Default)]
+#[derive(Default, InitSpace)]
 pub struct Amm {
     /// The primary key of the AMM
     pub id: Pubkey,
@@ -19,6 +36,9 @@ pub struct Amm {
 
     /// The LP fee taken on each trade, in basis points
     pub fee: u16,
+
+    /// 协议费用的接收账户
+    pub fee_recipient: Pubkey,
     
     /// 动态费用配置
     pub fee_config: FeeConfig,
@@ -31,14 +51,87 @@ pub struct Amm {
     
     /// 集中流动性配置
     pub concentrated_liquidity_config: ConcentratedLiquidityConfig,
+
+    /// LP提取费配置
+    pub withdrawal_fee_config: WithdrawalFeeConfig,
+
+    /// 已创建的池子数量，也是下一个池子在注册表中的序号
+    pub pool_count: u64,
+
+    /// 账户布局版本，新字段只能追加在末尾，旧账户通过migrate_amm扩容升级
+    pub version: u8,
+
+    /// 创建池子时的代币准入策略
+    pub mint_policy: MintPolicy,
+
+    /// 代币名单账户，未设置时为默认地址，设置后创建池子必须提供并通过名单检查
+    pub mint_list: Pubkey,
+
+    /// 以输出代币收取交换手续费，属于费用配置，因账户布局只能追加而单独存放
+    pub fee_on_output: bool,
+
+    /// 前端托管方（host）每笔交换可收取的平台费上限（基点）
+    pub max_host_fee_bps: u16,
+
+    /// 费用管理员，未授予时为默认地址
+    pub fee_manager: Pubkey,
+
+    /// 可以触发熔断的账户，未授予时为默认地址
+    pub pauser: Pubkey,
+
+    /// 风控管理员，未授予时为默认地址
+    pub risk_manager: Pubkey,
+
+    /// 敏感参数变更从排队到可以执行的延迟（秒），0表示不启用时间锁
+    pub timelock_delay: i64,
+
+    /// 仅退出模式：拒绝交换和存款，提取流动性和领取手续费不受影响
+    pub exit_only: bool,
+
+    /// 地址黑名单账户，未设置时为默认地址，设置后交换和存款必须提供并通过黑名单检查
+    pub denylist: Pubkey,
 }
 
 impl Amm {
-    // 8字节discriminator + id + admin + fee + fee_config + price_impact_config + volatility_config + concentrated_liquidity_config
-    pub const LEN: usize = 8 + 32 + 32 + 2 + 9 + 5 + 26 + 17;
+    // 8字节discriminator + 字段大小
+    pub const LEN: usize = 8 + Self::INIT_SPACE;
+
+    /// 角色当前的持有者，所有者即admin
+    pub fn role_holder(&self, role: Role) -> Pubkey {
+        match role {
+            Role::Owner => self.admin,
+            Role::FeeManager => self.fee_manager,
+            Role::Pauser => self.pauser,
+            Role::RiskManager => self.risk_manager,
+        }
+    }
+
+    /// 账户能否执行该角色的操作：所有者可以执行所有角色的操作
+    pub fn has_role(&self, role: Role, key: &Pubkey) -> bool {
+        *key == self.admin || (self.role_holder(role) == *key && *key != Pubkey::default())
+    }
+
+    /// 仅退出模式下拒绝交换
+    pub fn check_swaps_enabled(&self) -> Result<()> {
+        if self.exit_only {
+            return err!(TutorialError::ExitOnly);
+        }
+        Ok(())
+    }
+
+    /// 授予角色，授予所有者即转移所有权
+    pub fn set_role(&mut self, role: Role, holder: Pubkey) {
+        match role {
+            Role::Owner => self.admin = holder,
+            Role::FeeManager => self.fee_manager = holder,
+            Role::Pauser => self.pauser = holder,
+            Role::RiskManager => self.risk_manager = holder,
+        }
+    }
 }
 
 #[account]
+#[derive(Default, InitSpace)]
 pub struct Pool {
     /// Primary key of the AMM
     pub amm: Pubkey,
@@ -48,28 +141,1177 @@ pub struct Pool {
 
     /// Mint of token B
     pub mint_b: Pubkey,
+
+    /// 费率档位（基点），同一交易对可按不同档位创建多个池子，0表示使用AMM的默认费率
+    pub fee_tier: u16,
     
     /// 初始价格，用于价格参考
     pub initial_price: u64,
     
+    /// 熔断结束时间，在此之前拒绝交换
+    pub circuit_breaker_until: i64,
+
+    /// 定价曲线类型
+    pub curve_type: CurveType,
+
+    /// TWAP价格预言机
+    pub oracle: PriceOracle,
+
+    /// 闪电贷状态
+    pub flash_loan: FlashLoanState,
+
+    /// 外部价格预言机保护配置
+    pub oracle_guard: OracleGuardConfig,
+
+    /// 聚合流动性价格区间，交易不能把价格推出该区间
+    pub price_range: PriceRange,
+
+    /// 累计统计数据
+    pub stats: PoolStats,
+
+    /// 账户布局版本，新字段只能追加在末尾，旧账户通过migrate_pool扩容升级
+    pub version: u8,
+
+    /// 重入锁，移动池子储备的指令执行期间为true
+    pub locked: bool,
+
+    /// 管理员配置的交换钩子程序，交换前后以交易详情调用，为默认地址时不启用
+    pub swap_hook: Pubkey,
+
+    /// 移动储备的指令结束时记录的代币A储备，直接转入池子的代币在记录前属于盈余
+    pub reserve_a: u64,
+
+    /// 移动储备的指令结束时记录的代币B储备
+    pub reserve_b: u64,
+
+    /// 创建池子的账户，可以与AMM管理员一起设置存款上限
+    pub creator: Pubkey,
+
+    /// 存款后代币A储备的上限，0表示不限制
+    pub max_reserve_a: u64,
+
+    /// 存款后代币B储备的上限，0表示不限制
+    pub max_reserve_b: u64,
+
+    /// 代币A的小数位数，价格按两侧小数位数换算为整币之比，迁移前的池子为0表示不换算
+    pub decimals_a: u8,
+
+    /// 代币B的小数位数
+    pub decimals_b: u8,
+
+    /// 运行状态，用于单独限制这个池子的交换、存款和提取
+    pub status: PoolStatus,
+}
+
+impl Pool {
+    // 8字节discriminator + 字段大小
+    pub const LEN: usize = 8 + Self::INIT_SPACE;
+
+    /// 设置重入锁并立即写回账户数据，CPI期间重新进入本程序的指令加载池子时会看到锁并被拒绝
+    pub fn lock(pool: &mut Account<Pool>) -> Result<()> {
+        if pool.locked {
+            return err!(TutorialError::PoolLocked);
+        }
+        pool.locked = true;
+        pool.exit(&crate::ID)
+    }
+
+    /// 释放重入锁，随指令结束时的账户写回一起生效
+    pub fn unlock(&mut self) {
+        self.locked = false;
+    }
+
+    /// 池子状态不允许时拒绝交换
+    pub fn check_swaps_enabled(&self) -> Result<()> {
+        if !self.status.allows_swaps() {
+            return err!(TutorialError::PoolStatusRestricted);
+        }
+        Ok(())
+    }
+
+    /// 熔断期间拒绝交换
+    pub fn check_circuit_breaker(&self, timestamp: i64) -> Result<()> {
+        if timestamp < self.circuit_breaker_until {
+            return err!(TutorialError::ExcessiveVolatility);
+        }
+        Ok(())
+    }
+
+    /// 修改曲线参数，已有流动性的池子不能切换曲线种类
+    pub fn update_curve_params(&mut self, curve_type: CurveType) -> Result<()> {
+        if std::mem::discriminant(&self.curve_type) != std::mem::discriminant(&curve_type) {
+            return err!(TutorialError::CurveKindMismatch);
+        }
+        self.curve_type = curve_type;
+        Ok(())
+    }
+
+    /// 根据输入代币确定交换方向：输入为代币A时返回true，不属于池子时报错
+    pub fn swap_direction(&self, input_mint: &Pubkey) -> Result<bool> {
+        if *input_mint == self.mint_a {
+            Ok(true)
+        } else if *input_mint == self.mint_b {
+            Ok(false)
+        } else {
+            err!(TutorialError::InvalidInputMint)
+        }
+    }
+
+    /// 把池代币账户的当前余额记录为储备，只有sync使用，直接转入的盈余随之并入储备归LP所有
+    pub fn record_reserves(&mut self, reserve_a: u64, reserve_b: u64) {
+        self.reserve_a = reserve_a;
+        self.reserve_b = reserve_b;
+    }
+
+    /// 按指令实际转入池子的数量增加记录的储备，不读取代币账户余额，直接转入的代币仍属于盈余
+    pub fn add_reserves(&mut self, amount_a: u64, amount_b: u64) -> Result<()> {
+        self.reserve_a = self.reserve_a.checked_add(amount_a).ok_or(TutorialError::MathOverflow)?;
+        self.reserve_b = self.reserve_b.checked_add(amount_b).ok_or(TutorialError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// 按指令从池子转出的数量减少记录的储备，转出不能超过记录的储备
+    pub fn remove_reserves(&mut self, amount_a: u64, amount_b: u64) -> Result<()> {
+        self.reserve_a = self.reserve_a.checked_sub(amount_a).ok_or(TutorialError::InsufficientLiquidity)?;
+        self.reserve_b = self.reserve_b.checked_sub(amount_b).ok_or(TutorialError::InsufficientLiquidity)?;
+        Ok(())
+    }
+
+    /// 交换后更新记录的储备：输入端加上池子收到的数量，输出端减去转出的数量
+    pub fn swap_reserves(&mut self, swap_a: bool, amount_in: u64, amount_out: u64) -> Result<()> {
+        if swap_a {
+            self.add_reserves(amount_in, 0)?;
+            self.remove_reserves(0, amount_out)
+        } else {
+            self.add_reserves(0, amount_in)?;
+            self.remove_reserves(amount_out, 0)
+        }
+    }
+
+    /// 流动性代币的小数位数：首次存款按 sqrt(a * b) 铸造，其数量级为两种代币小数位数的平均值，
+    /// 按平均值设置小数位数后，一个流动性代币大致对应各一个单位的两种代币
+    pub fn lp_decimals(decimals_a: u8, decimals_b: u8) -> u8 {
+        ((decimals_a as u16 + decimals_b as u16) / 2) as u8
+    }
+
+    /// 归一化价格 numerator / denominator：按两种代币的小数位数把原始数量之比换算为整币之比，
+    /// 6位与9位小数的交易对不再得到相差三个数量级的价格。numerator_is_a 表示分子是否为代币A的数量
+    pub fn normalized_price(&self, numerator: u64, denominator: u64, numerator_is_a: bool) -> I64F64 {
+        self.normalize_price(I64F64::from_num(numerator) / I64F64::from_num(denominator), numerator_is_a)
+    }
+
+    /// 按小数位数归一化已算出的原始价格，如预言机记录的时间加权平均价格
+    pub fn normalize_price(&self, price: I64F64, numerator_is_a: bool) -> I64F64 {
+        let (decimals_num, decimals_den) = if numerator_is_a {
+            (self.decimals_a, self.decimals_b)
+        } else {
+            (self.decimals_b, self.decimals_a)
+        };
+        let scale = I64F64::saturating_from_num(
+            10u64.checked_pow(decimals_num.abs_diff(decimals_den) as u32).unwrap_or(u64::MAX),
+        );
+        if decimals_den >= decimals_num {
+            price.saturating_mul(scale)
+        } else {
+            price / scale
+        }
+    }
+
+    /// 存款后的储备超过上限时拒绝，用于有限额的试运行池子
+    pub fn check_deposit_cap(&self, reserve_a: u64, reserve_b: u64) -> Result<()> {
+        if (self.max_reserve_a > 0 && reserve_a > self.max_reserve_a)
+            || (self.max_reserve_b > 0 && reserve_b > self.max_reserve_b)
+        {
+            return err!(TutorialError::DepositCapExceeded);
+        }
+        Ok(())
+    }
+
+    /// 定价使用的储备，只来自记录的储备，直接转入池子的代币在sync之前不影响价格；
+    /// 迁移前的池子尚未记录储备，需要先调用一次sync
+    pub fn reserves(&self) -> (u64, u64) {
+        (self.reserve_a, self.reserve_b)
+    }
+
+    /// 池代币账户余额超出记录储备的盈余，即绕过存款流程直接转入池子的代币
+    pub fn surplus(&self, balance_a: u64, balance_b: u64) -> (u64, u64) {
+        (balance_a.saturating_sub(self.reserve_a), balance_b.saturating_sub(self.reserve_b))
+    }
+
+    /// 按规范顺序（mint_a < mint_b）排列交易对
+    pub fn sort_mints(mint_x: Pubkey, mint_y: Pubkey) -> (Pubkey, Pubkey) {
+        if mint_x < mint_y {
+            (mint_x, mint_y)
+        } else {
+            (mint_y, mint_x)
+        }
+    }
+
+    /// 计算交易对在指定费率档位下的池子地址，与传入的代币顺序无关
+    pub fn find_address(amm: &Pubkey, mint_x: &Pubkey, mint_y: &Pubkey, fee_tier: u16) -> (Pubkey, u8) {
+        let (mint_a, mint_b) = Self::sort_mints(*mint_x, *mint_y);
+        Pubkey::find_program_address(
+            &[amm.as_ref(), mint_a.as_ref(), mint_b.as_ref(), &fee_tier.to_le_bytes()],
+            &crate::ID,
+        )
+    }
+
+    /// 池子的固定费率：指定了档位时使用档位费率，否则使用AMM的默认费率
+    pub fn fee_rate_bps(&self, amm: &Amm) -> u16 {
+        if self.fee_tier > 0 {
+            self.fee_tier
+        } else {
+            amm.fee
+        }
+    }
+}
+
+/// 池子的波动率预言机，与池子分开存储：不需要波动率的交换可以不加载，样本数量也可以单独扩展
+///
+/// 价格样本存放在账户数据尾部（PoolOracle::LEN之后），账户大小随创建池子时选择的样本数量变化
+#[account]
+#[derive(Default, InitSpace)]
+pub struct PoolOracle {
+    /// 所属池子
+    pub pool: Pubkey,
+
     /// 波动率追踪器
     pub volatility_tracker: VolatilityTracker,
 }
 
-impl Pool {
-    // 8字节discriminator + amm + mint_a + mint_b + initial_price + volatility_tracker
-    pub const LEN: usize = 8 + 32 + 32 + 32 + 8 + 
-        (24 * 16 + 24 * 8 + 1 + 16 + 16); // VolatilityTracker的大小
+impl PoolOracle {
+    // 8字节discriminator + 字段大小，之后是价格样本缓冲区
+    pub const LEN: usize = 8 + Self::INIT_SPACE;
+
+    /// 按样本数量计算账户的完整大小
+    pub fn space(observation_count: u16) -> usize {
+        Self::LEN + observation_count as usize * OBSERVATION_LEN
+    }
+
+    /// 记录交换后的价格样本，缓冲区尚未分配完成时跳过
+    pub fn record_price(
+        pool_oracle: &mut Account<PoolOracle>,
+        price: I64F64,
+        timestamp: i64,
+        config: &VolatilityConfig,
+    ) -> Result<()> {
+        let info = pool_oracle.to_account_info();
+        let mut data = info.try_borrow_mut_data()?;
+        pool_oracle
+            .volatility_tracker
+            .update_price_sample(&mut data[Self::LEN..], price, timestamp, config);
+        Ok(())
+    }
+
+    /// 根据缓冲区中的样本重新计算波动率，返回是否超过了熔断阈值
+    pub fn refresh_volatility(pool_oracle: &mut Account<PoolOracle>, config: &VolatilityConfig, timestamp: i64) -> Result<bool> {
+        let info = pool_oracle.to_account_info();
+        let data = info.try_borrow_data()?;
+        Ok(pool_oracle.volatility_tracker.update_volatility(&data[Self::LEN..], config, timestamp))
+    }
+
+    /// 启用波动率跟踪时交换需要记录价格样本，动态费率需要读取波动率，此时交换必须提供池子预言机
+    pub fn check_required(amm: &Amm, pool_oracle: Option<&PoolOracle>) -> Result<()> {
+        let required = amm.volatility_config.enabled || amm.fee_config.strategy != FeeStrategy::Fixed;
+        if required && pool_oracle.is_none() {
+            return err!(TutorialError::MissingPoolOracle);
+        }
+        Ok(())
+    }
 }
 
-impl Default for Pool {
-    fn default() -> Self {
-        Self {
-            amm: Pubkey::default(),
-            mint_a: Pubkey::default(),
-            mint_b: Pubkey::default(),
-            initial_price: 0,
-            volatility_tracker: VolatilityTracker::default(),
+/// 池子注册表条目：以AMM和序号为种子，前端可以按序号0..pool_count分页枚举AMM下的所有池子，
+/// 无需通过getProgramAccounts扫描全部账户；池子关闭后条目仍保留，读取时需检查池子是否存在
+#[account]
+#[derive(Default, InitSpace)]
+pub struct PoolRef {
+    /// 所属AMM
+    pub amm: Pubkey,
+
+    /// 注册的池子
+    pub pool: Pubkey,
+
+    /// 在注册表中的序号
+    pub index: u64,
+}
+
+impl PoolRef {
+    // 8字节discriminator + 字段大小
+    pub const LEN: usize = 8 + Self::INIT_SPACE;
+}
+
+/// AMM的代币名单，由管理员维护，创建池子时检查两种代币
+#[account]
+#[derive(Default, InitSpace)]
+pub struct MintList {
+    /// 所属AMM
+    pub amm: Pubkey,
+
+    /// 名单模式
+    pub mode: MintListMode,
+
+    /// 名单中的代币
+    #[max_len(MAX_MINT_LIST_LEN)]
+    pub mints: Vec<Pubkey>,
+}
+
+impl MintList {
+    // 8字节discriminator + 字段大小
+    pub const LEN: usize = 8 + Self::INIT_SPACE;
+
+    /// 检查代币是否可以用于创建池子
+    pub fn check(&self, mint: &Pubkey) -> Result<()> {
+        let listed = self.mints.contains(mint);
+        let allowed = match self.mode {
+            MintListMode::Allowlist => listed,
+            MintListMode::Denylist => !listed,
+        };
+        if !allowed {
+            return err!(TutorialError::MintNotAllowed);
+        }
+        Ok(())
+    }
+}
+
+/// AMM的地址黑名单，由风控管理员维护，交换和存款时检查签名的交易者或存款人
+#[account]
+#[derive(Default, InitSpace)]
+pub struct Denylist {
+    /// 所属AMM
+    pub amm: Pubkey,
+
+    /// 被拒绝的地址
+    #[max_len(MAX_DENYLIST_LEN)]
+    pub addresses: Vec<Pubkey>,
+}
+
+impl Denylist {
+    // 8字节discriminator + 字段大小
+    pub const LEN: usize = 8 + Self::INIT_SPACE;
+
+    /// AMM设置了黑名单时必须提供名单账户（地址由账户约束校验），名单上的地址被拒绝
+    pub fn check(amm: &Amm, denylist: Option<&Denylist>, address: &Pubkey) -> Result<()> {
+        if amm.denylist == Pubkey::default() {
+            return Ok(());
+        }
+        let denylist = denylist.ok_or(TutorialError::DenylistRequired)?;
+        if denylist.addresses.contains(address) {
+            return err!(TutorialError::AddressDenied);
+        }
+        Ok(())
+    }
+}
+
+/// LP的无常损失补偿头寸，登记的流动性代币托管在头寸的托管账户中，记录登记时的价格和流动性
+#[account]
+#[derive(Default, InitSpace)]
+pub struct IlPosition {
+    /// 所属池子
+    pub pool: Pubkey,
+
+    /// 头寸所有者
+    pub owner: Pubkey,
+
+    /// 托管中的流动性代币数量
+    pub liquidity: u64,
+
+    /// 登记时以代币B计价的代币A时间加权平均价格，已按小数位数归一化（I64F64的原始位），
+    /// 追加登记时按流动性加权合并
+    pub entry_price: u128,
+
+    /// 最后一次领取补偿的时间
+    pub last_compensated: i64,
+}
+
+impl IlPosition {
+    // 8字节discriminator + 字段大小
+    pub const LEN: usize = 8 + Self::INIT_SPACE;
+
+    /// 追加登记amount个流动性代币，登记价格按流动性加权合并
+    pub fn register(&mut self, amount: u64, price: I64F64) -> Result<()> {
+        let total = self.liquidity.checked_add(amount).ok_or(TutorialError::MathOverflow)?;
+        if total == 0 {
+            return err!(TutorialError::EmptyIlPosition);
         }
+        let entry_price = I64F64::from_bits(self.entry_price as i128);
+        let weight = I64F64::from_num(amount) / I64F64::from_num(total);
+        let merged = price
+            .checked_sub(entry_price)
+            .and_then(|delta| delta.checked_mul(weight))
+            .and_then(|delta| entry_price.checked_add(delta))
+            .ok_or(TutorialError::MathOverflow)?;
+        self.entry_price = merged.to_bits() as u128;
+        self.liquidity = total;
+        Ok(())
+    }
+}
+
+/// LP的手续费头寸，托管流动性代币并记录上次领取时的手续费增长值
+///
+/// 手续费只按托管的数量计算，钱包中的流动性代币转入转出不影响已累计的手续费
+#[account]
+#[derive(Default, InitSpace)]
+pub struct FeePosition {
+    /// 所属池子
+    pub pool: Pubkey,
+
+    /// 头寸所有者
+    pub owner: Pubkey,
+
+    /// 上次领取时每个流动性代币对应的不变量（Q64.64）
+    pub fee_growth_checkpoint: u128,
+
+    /// 托管的流动性代币数量
+    pub liquidity: u64,
+}
+
+impl FeePosition {
+    // 8字节discriminator + 字段大小
+    pub const LEN: usize = 8 + Self::INIT_SPACE;
+
+    /// 追加托管的流动性，按流动性加权合并检查点，已累计但未领取的手续费保持不变
+    pub fn deposit(&mut self, amount: u64, growth: u128) -> Result<()> {
+        self.fee_growth_checkpoint =
+            LpFeeCalculator::merge_checkpoint(self.liquidity, self.fee_growth_checkpoint, amount, growth)?;
+        self.liquidity = self.liquidity.checked_add(amount).ok_or(TutorialError::MathOverflow)?;
+        Ok(())
+    }
+}
+
+/// 限价单：托管输入代币，池子价格达到限价后任何人都可以代为成交
+#[account]
+#[derive(Default, InitSpace)]
+pub struct LimitOrder {
+    /// 成交所用的池子
+    pub pool: Pubkey,
+
+    /// 下单者，接收输出代币和租金
+    pub owner: Pubkey,
+
+    /// 下单者自定的订单编号，用于区分同一池子的多个订单
+    pub id: u64,
+
+    /// true表示用代币A换代币B
+    pub swap_a: bool,
+
+    /// 托管的输入代币数量
+    pub input_amount: u64,
+
+    /// 最少获得的输出代币数量，即限价
+    pub min_output_amount: u64,
+}
+
+impl LimitOrder {
+    // 8字节discriminator + 字段大小
+    pub const LEN: usize = 8 + Self::INIT_SPACE;
+}
+
+/// 定投计划：托管输入代币，每个周期由任何人触发一次交换
+#[account]
+#[derive(Default, InitSpace)]
+pub struct DcaSchedule {
+    /// 交换所用的池子
+    pub pool: Pubkey,
+
+    /// 计划创建者，接收输出代币和租金
+    pub owner: Pubkey,
+
+    /// 创建者自定的计划编号
+    pub id: u64,
+
+    /// true表示用代币A换代币B
+    pub swap_a: bool,
+
+    /// 每个周期投入的输入代币数量
+    pub amount_per_interval: u64,
+
+    /// 执行周期（秒）
+    pub interval: i64,
+
+    /// 可接受的最低价格（每单位输入换得的输出，Q64.64定点数）
+    pub min_price: u128,
+
+    /// 下一次可以执行的时间
+    pub next_execution: i64,
+}
+
+impl DcaSchedule {
+    // 8字节discriminator + 字段大小
+    pub const LEN: usize = 8 + Self::INIT_SPACE;
+}
+
+/// LP的流动性锁：锁定的流动性代币托管在锁的代币账户中，到期前无法取回
+#[account]
+#[derive(Default, InitSpace)]
+pub struct LiquidityLock {
+    /// 所属池子
+    pub pool: Pubkey,
+
+    /// 锁的所有者，到期后取回流动性代币
+    pub owner: Pubkey,
+
+    /// 锁定的流动性代币数量
+    pub amount: u64,
+
+    /// 到期时间
+    pub unlock_at: i64,
+
+    /// 上次领取手续费时每个流动性代币对应的不变量（Q64.64）
+    pub fee_growth_checkpoint: u128,
+}
+
+impl LiquidityLock {
+    // 8字节discriminator + 字段大小
+    pub const LEN: usize = 8 + Self::INIT_SPACE;
+
+    /// 当前的ve加成倍数（基点）
+    pub fn boost_bps(&self, now: i64) -> u16 {
+        VeBoost::boost_bps(self.unlock_at, now)
     }
-}
\ No newline at end of file
+}
+
+/// LP在池子中的存款记录，按流动性加权的平均存入时间用于计算提取费的衰减
+#[account]
+#[derive(Default, InitSpace)]
+pub struct DepositRecord {
+    /// 所属池子
+    pub pool: Pubkey,
+
+    /// LP地址
+    pub owner: Pubkey,
+
+    /// 记录中的流动性代币数量
+    pub liquidity: u64,
+
+    /// 按流动性加权的平均存入时间
+    pub deposited_at: i64,
+}
+
+impl DepositRecord {
+    // 8字节discriminator + 字段大小
+    pub const LEN: usize = 8 + Self::INIT_SPACE;
+
+    /// 记录一笔存款，已有流动性按实际持有的数量参与加权，转走的流动性不能摊薄新存款的持有时间
+    pub fn record_deposit(&mut self, held_liquidity: u64, liquidity: u64, timestamp: i64) {
+        let held_liquidity = self.liquidity.min(held_liquidity);
+        let total = held_liquidity as u128 + liquidity as u128;
+        if total > 0 {
+            self.deposited_at = ((held_liquidity as i128 * self.deposited_at as i128
+                + liquidity as i128 * timestamp as i128)
+                / total as i128) as i64;
+        }
+        self.liquidity = total as u64;
+    }
+
+    /// 记录一笔提取
+    pub fn record_withdrawal(&mut self, liquidity: u64) {
+        self.liquidity = self.liquidity.saturating_sub(liquidity);
+    }
+
+    /// 已持有的时间（秒）
+    pub fn held_secs(&self, timestamp: i64) -> i64 {
+        timestamp - self.deposited_at
+    }
+
+    /// 启用提取费时存款必须提供存款记录，否则新存入的流动性提取时只能按刚存入计算
+    pub fn check_required(config: &WithdrawalFeeConfig, record: Option<&Self>) -> Result<()> {
+        if config.fee_bps > 0 && record.is_none() {
+            return err!(TutorialError::DepositRecordRequired);
+        }
+        Ok(())
+    }
+
+    /// 提取amount个流动性代币适用的提取费率，超出记录的流动性和没有存款记录时按刚存入计算
+    pub fn withdrawal_fee_bps(record: Option<&Self>, config: &WithdrawalFeeConfig, amount: u64, timestamp: i64) -> u16 {
+        match record {
+            Some(record) => config.fee_rate_bps(record.held_secs(timestamp), record.liquidity, amount),
+            None => config.fee_rate_bps(0, 0, amount),
+        }
+    }
+}
+
+/// 交易者提交的交换承诺，只包含交换参数的哈希，揭示前外部无法得知具体参数
+#[account]
+#[derive(Default, InitSpace)]
+pub struct SwapCommitment {
+    /// 交换的池子
+    pub pool: Pubkey,
+
+    /// 提交承诺的交易者
+    pub trader: Pubkey,
+
+    /// 交换参数和盐值的哈希
+    pub hash: [u8; 32],
+
+    /// 提交承诺时的slot
+    pub committed_slot: u64,
+}
+
+impl SwapCommitment {
+    // 8字节discriminator + 字段大小
+    pub const LEN: usize = 8 + Self::INIT_SPACE;
+
+    /// 交换参数的承诺哈希
+    pub fn hash_params(input_mint: &Pubkey, input_amount: u64, min_output_amount: u64, salt: &[u8; 32]) -> [u8; 32] {
+        anchor_lang::solana_program::hash::hashv(&[
+            input_mint.as_ref(),
+            &input_amount.to_le_bytes(),
+            &min_output_amount.to_le_bytes(),
+            salt,
+        ])
+        .to_bytes()
+    }
+
+    /// 揭示必须晚于提交所在的slot，并且不能超出揭示窗口
+    pub fn check_reveal_slot(&self, slot: u64) -> Result<()> {
+        if slot <= self.committed_slot {
+            return err!(TutorialError::RevealTooEarly);
+        }
+        if slot > self.committed_slot + REVEAL_WINDOW_SLOTS {
+            return err!(TutorialError::RevealWindowExpired);
+        }
+        Ok(())
+    }
+}
+
+/// 管理员为特定地址（如协议自有的套利机器人、合作路由）设置的手续费豁免
+#[account]
+#[derive(Default, InitSpace)]
+pub struct FeeExemption {
+    /// 所属AMM
+    pub amm: Pubkey,
+
+    /// 享受豁免的交易者地址
+    pub address: Pubkey,
+
+    /// 手续费折扣（基点），10000表示完全免除
+    pub discount_bps: u16,
+}
+
+impl FeeExemption {
+    // 8字节discriminator + 字段大小
+    pub const LEN: usize = 8 + Self::INIT_SPACE;
+}
+
+/// 交易者在某个池子的成交量统计，用于按30天成交量计算手续费折扣
+#[account]
+#[derive(Default, InitSpace)]
+pub struct TraderStats {
+    /// 所属池子
+    pub pool: Pubkey,
+
+    /// 交易者
+    pub trader: Pubkey,
+
+    /// 累计成交量（代币B的最小单位）
+    pub total_volume: u128,
+
+    /// 当前统计周期的成交量
+    pub period_volume: u64,
+
+    /// 上一个统计周期的成交量
+    pub previous_period_volume: u64,
+
+    /// 当前统计周期的开始时间
+    pub period_start: i64,
+}
+
+impl TraderStats {
+    // 8字节discriminator + 字段大小
+    pub const LEN: usize = 8 + Self::INIT_SPACE;
+
+    /// 用于计算折扣的成交量：上一个完整周期与当前周期中的较大值，超过两个周期没有交易则清零
+    pub fn discount_volume(&self, timestamp: i64) -> u64 {
+        let elapsed = timestamp - self.period_start;
+        if elapsed < VOLUME_PERIOD_SECS {
+            self.period_volume.max(self.previous_period_volume)
+        } else if elapsed < 2 * VOLUME_PERIOD_SECS {
+            self.period_volume
+        } else {
+            0
+        }
+    }
+
+    /// 记录一笔成交，必要时滚动到新的统计周期
+    pub fn record(&mut self, volume: u64, timestamp: i64) {
+        let elapsed = timestamp - self.period_start;
+        if elapsed >= VOLUME_PERIOD_SECS {
+            self.previous_period_volume = if elapsed < 2 * VOLUME_PERIOD_SECS { self.period_volume } else { 0 };
+            self.period_volume = 0;
+            self.period_start = timestamp;
+        }
+        self.period_volume = self.period_volume.saturating_add(volume);
+        self.total_volume += volume as u128;
+    }
+}
+
+/// 池子的流动性挖矿农场：LP质押流动性代币，按质押权重分享奖励，可同时发放多种奖励代币
+#[account]
+#[derive(Default, InitSpace)]
+pub struct Farm {
+    /// 所属池子
+    pub pool: Pubkey,
+
+    /// 托管质押流动性代币的账户
+    pub lp_vault: Pubkey,
+
+    /// 质押的流动性代币总量
+    pub total_staked: u64,
+
+    /// 质押权重总量，包含ve加成
+    pub total_weight: u64,
+
+    /// 各奖励代币的发放计划，未使用的位置mint为默认地址
+    pub rewards: [FarmReward; MAX_FARM_REWARDS],
+}
+
+impl Farm {
+    // 8字节discriminator + 字段大小
+    pub const LEN: usize = 8 + Self::INIT_SPACE;
+
+    /// 该位置是否已配置奖励代币
+    pub fn has_reward(&self, index: u8) -> bool {
+        matches!(self.rewards.get(index as usize), Some(reward) if reward.mint != Pubkey::default())
+    }
+
+    /// 第一个未使用的奖励位置
+    pub fn next_reward_index(&self) -> Option<usize> {
+        self.rewards.iter().position(|reward| reward.mint == Pubkey::default())
+    }
+
+    /// 按当前的质押权重总量累计所有奖励
+    pub fn update_rewards(&mut self, now: i64) -> Result<()> {
+        for reward in self.rewards.iter_mut() {
+            reward.update(self.total_weight, now)?;
+        }
+        Ok(())
+    }
+}
+
+/// LP在农场中的质押头寸
+#[account]
+#[derive(Default, InitSpace)]
+pub struct StakePosition {
+    /// 所属农场
+    pub farm: Pubkey,
+
+    /// 头寸所有者
+    pub owner: Pubkey,
+
+    /// 质押的流动性代币数量
+    pub amount: u64,
+
+    /// 质押权重：质押数量加上锁定流动性带来的ve加成
+    pub weight: u64,
+
+    /// 各奖励上次结算时每份权重累计的奖励（Q64.64）
+    pub reward_per_share_checkpoints: [u128; MAX_FARM_REWARDS],
+
+    /// 各奖励已结算但尚未领取的数量
+    pub pending_rewards: [u64; MAX_FARM_REWARDS],
+}
+
+impl StakePosition {
+    // 8字节discriminator + 字段大小
+    pub const LEN: usize = 8 + Self::INIT_SPACE;
+
+    /// 按最新的每份奖励结算所有奖励的待领取数量
+    pub fn settle(&mut self, rewards: &[FarmReward; MAX_FARM_REWARDS]) -> Result<()> {
+        for (i, reward) in rewards.iter().enumerate() {
+            let pending = reward.pending(self.weight, self.reward_per_share_checkpoints[i])?;
+            self.pending_rewards[i] = self.pending_rewards[i].saturating_add(pending);
+            self.reward_per_share_checkpoints[i] = reward.reward_per_share;
+        }
+        Ok(())
+    }
+
+    /// 质押权重：不超过锁定数量的部分按锁的ve加成放大
+    pub fn compute_weight(amount: u64, lock: Option<&LiquidityLock>, now: i64) -> u64 {
+        let bonus = lock.map_or(0, |lock| VeBoost::bonus(amount.min(lock.amount), lock.boost_bps(now)));
+        amount.saturating_add(bonus)
+    }
+}
+
+/// 池子的自动复投金库：接收流动性代币并铸造金库份额，复投时把金库持有的两种代币重新存入池子
+#[account]
+#[derive(Default, InitSpace)]
+pub struct Vault {
+    /// 所属池子
+    pub pool: Pubkey,
+
+    /// 金库份额代币
+    pub share_mint: Pubkey,
+
+    /// 托管流动性代币的账户
+    pub lp_vault: Pubkey,
+
+    /// 上次复投时间
+    pub last_compound_at: i64,
+}
+
+impl Vault {
+    // 8字节discriminator + 字段大小
+    pub const LEN: usize = 8 + Self::INIT_SPACE;
+}
+
+/// 聚合流动性头寸：托管流动性代币并记录价格区间，由头寸NFT的持有者所有
+///
+/// 池子的流动性覆盖全部价格，头寸与全价格区间的LP按相同的每份额手续费增长值累计手续费
+#[account]
+#[derive(Default, InitSpace)]
+pub struct Position {
+    /// 所属池子
+    pub pool: Pubkey,
+
+    /// 头寸NFT，持有者即头寸所有者
+    pub position_mint: Pubkey,
+
+    /// 头寸托管的流动性代币数量
+    pub liquidity: u64,
+
+    /// 价格区间下限，代币A以代币B计价（Q64.64）
+    pub lower_price_x64: u128,
+
+    /// 价格区间上限，代币A以代币B计价（Q64.64）
+    pub upper_price_x64: u128,
+
+    /// 上次结算时每个流动性代币对应的不变量（Q64.64）
+    pub fee_growth_checkpoint: u128,
+
+    /// 已结算但尚未领取的手续费，以流动性代币计，仍托管在头寸中
+    pub fees_owed: u64,
+
+    /// 开立时价格区间不包含当前价格的头寸作为区间订单：区间在价格之上为Some(true)，
+    /// 价格上穿区间后全部换成代币B；区间在价格之下为Some(false)，价格下穿区间后全部换成代币A
+    pub range_order: Option<bool>,
+}
+
+impl Position {
+    // 8字节discriminator + 字段大小
+    pub const LEN: usize = 8 + Self::INIT_SPACE;
+
+    /// 区间订单是否已成交：价格已完全穿过区间
+    pub fn is_range_order_filled(&self, price_x64: u128) -> bool {
+        match self.range_order {
+            Some(true) => price_x64 > self.upper_price_x64,
+            Some(false) => price_x64 < self.lower_price_x64,
+            None => false,
+        }
+    }
+
+    /// 结算自上次检查点以来的手续费：手续费对应的流动性代币从本金转入待领取部分
+    pub fn settle_fees(&mut self, growth: u128) -> Result<()> {
+        let fee_liquidity = LpFeeCalculator::fee_liquidity(self.liquidity, self.fee_growth_checkpoint, growth)?;
+        self.liquidity -= fee_liquidity;
+        self.fees_owed += fee_liquidity;
+        self.fee_growth_checkpoint = growth;
+        Ok(())
+    }
+}
+
+/// 全局程序配置，由程序的升级权限创建一次，保存功能开关
+#[account]
+#[derive(Default, InitSpace)]
+pub struct ProgramConfig {
+    /// 可以修改功能开关的账户，创建时为程序的升级权限
+    pub authority: Pubkey,
+
+    /// 功能开关
+    pub features: FeatureFlags,
+}
+
+impl ProgramConfig {
+    // 8字节discriminator + 字段大小
+    pub const LEN: usize = 8 + Self::INIT_SPACE;
+
+    /// 读取配置账户，尚未创建时使用默认配置
+    ///
+    /// 账户地址由调用方按种子约束，未创建的地址不属于本程序
+    pub fn load(info: &AccountInfo) -> Result<ProgramConfig> {
+        if info.owner != &crate::ID {
+            return Ok(ProgramConfig::default());
+        }
+        ProgramConfig::try_deserialize(&mut &info.try_borrow_data()?[..])
+    }
+}
+
+/// 等待时间锁的参数变更，由对应角色提议、所有者排队，延迟结束后任何人都可以执行
+#[account]
+#[derive(InitSpace)]
+pub struct PendingChange {
+    /// 所属的AMM
+    pub amm: Pubkey,
+
+    /// 提议者，执行或取消后收回租金
+    pub proposer: Pubkey,
+
+    /// 提议者自选的编号，用于推导地址
+    pub id: u64,
+
+    /// 参数变更
+    pub change: ParameterChange,
+
+    /// 提议时间（unix时间）
+    pub proposed_at: i64,
+
+    /// 可以执行的时间（unix时间），尚未排队时为0
+    pub eta: i64,
+}
+
+impl PendingChange {
+    // 8字节discriminator + 字段大小
+    pub const LEN: usize = 8 + Self::INIT_SPACE;
+
+    pub fn is_queued(&self) -> bool {
+        self.eta != 0
+    }
+}
+
+/// LP治理提案，锁定流动性代币的LP按锁定数量投票，通过后由程序执行
+#[account]
+#[derive(InitSpace)]
+pub struct GovernanceProposal {
+    /// 提案作用的池子，也是投票者锁定流动性的池子
+    pub pool: Pubkey,
+
+    /// 提议者
+    pub proposer: Pubkey,
+
+    /// 提议者自选的编号，用于推导地址
+    pub id: u64,
+
+    /// 通过后执行的操作
+    pub action: GovernanceAction,
+
+    /// 投票截止时间（unix时间）
+    pub voting_ends_at: i64,
+
+    /// 赞成票
+    pub votes_for: u64,
+
+    /// 反对票
+    pub votes_against: u64,
+
+    /// 通过所需的最少赞成票，按创建时的流动性代币供应量计算
+    pub quorum: u64,
+
+    /// 是否已执行
+    pub executed: bool,
+}
+
+impl GovernanceProposal {
+    // 8字节discriminator + 字段大小
+    pub const LEN: usize = 8 + Self::INIT_SPACE;
+
+    /// 赞成票达到法定票数且多于反对票
+    pub fn passed(&self) -> bool {
+        self.votes_for >= self.quorum && self.votes_for > self.votes_against
+    }
+}
+
+/// 一个流动性锁对一个提案的投票记录，存在即表示已投票
+#[account]
+#[derive(Default, InitSpace)]
+pub struct VoteRecord {
+    /// 投票的提案
+    pub proposal: Pubkey,
+
+    /// 投票使用的流动性锁
+    pub lock: Pubkey,
+
+    /// 是否赞成
+    pub support: bool,
+
+    /// 投票权重，即投票时锁定的流动性代币数量
+    pub weight: u64,
+}
+
+impl VoteRecord {
+    // 8字节discriminator + 字段大小
+    pub const LEN: usize = 8 + Self::INIT_SPACE;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::fee_strategy::FeeStrategy;
+
+    /// 序列化后的长度必须与InitSpace一致，并且可以反序列化回来
+    fn assert_space<T: AccountSerialize + AccountDeserialize>(account: &T, len: usize) {
+        let mut data = Vec::new();
+        account.try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), len);
+
+        let decoded = T::try_deserialize(&mut data.as_slice()).unwrap();
+        let mut reencoded = Vec::new();
+        decoded.try_serialize(&mut reencoded).unwrap();
+        assert_eq!(reencoded, data);
+    }
+
+    #[test]
+    fn amm_space() {
+        // 可选的方向费率都设置时占用的空间最大
+        let amm = Amm {
+            fee_config: FeeConfig {
+                strategy: FeeStrategy::VolatilityAdjusted,
+                fee_a_to_b_bps: Some(30),
+                fee_b_to_a_bps: Some(50),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert_space(&amm, Amm::LEN);
+    }
+
+    #[test]
+    fn pool_space() {
+        // Lbp是占用空间最大的曲线变体
+        let pool = Pool {
+            curve_type: CurveType::Lbp {
+                start_weight_a: 8000,
+                end_weight_a: 2000,
+                start_time: 1,
+                end_time: 2,
+            },
+            ..Default::default()
+        };
+        assert_space(&pool, Pool::LEN);
+    }
+
+    #[test]
+    fn position_space() {
+        let position = Position {
+            range_order: Some(true),
+            ..Default::default()
+        };
+        assert_space(&position, Position::LEN);
+    }
+
+    #[test]
+    fn pending_change_space() {
+        // 波动率配置变更是占用空间最大的变更
+        let pending_change = PendingChange {
+            amm: Pubkey::default(),
+            proposer: Pubkey::default(),
+            id: 0,
+            change: ParameterChange::VolatilityConfig(VolatilityConfig::default()),
+            proposed_at: 0,
+            eta: 0,
+        };
+        assert_space(&pending_change, PendingChange::LEN);
+    }
+
+    #[test]
+    fn governance_proposal_space() {
+        let proposal = GovernanceProposal {
+            pool: Pubkey::default(),
+            proposer: Pubkey::default(),
+            id: 0,
+            action: GovernanceAction::Curve(CurveType::Lbp {
+                start_weight_a: 8000,
+                end_weight_a: 2000,
+                start_time: 1,
+                end_time: 2,
+            }),
+            voting_ends_at: 0,
+            votes_for: 0,
+            votes_against: 0,
+            quorum: 0,
+            executed: false,
+        };
+        assert_space(&proposal, GovernanceProposal::LEN);
+    }
+
+    #[test]
+    fn fixed_size_account_space() {
+        assert_space(&PoolOracle::default(), PoolOracle::LEN);
+        assert_space(&PoolRef::default(), PoolRef::LEN);
+        assert_space(&IlPosition::default(), IlPosition::LEN);
+        assert_space(&FeePosition::default(), FeePosition::LEN);
+        assert_space(&LimitOrder::default(), LimitOrder::LEN);
+        assert_space(&DcaSchedule::default(), DcaSchedule::LEN);
+        assert_space(&LiquidityLock::default(), LiquidityLock::LEN);
+        assert_space(&DepositRecord::default(), DepositRecord::LEN);
+        assert_space(&FeeExemption::default(), FeeExemption::LEN);
+        assert_space(&TraderStats::default(), TraderStats::LEN);
+        assert_space(&Farm::default(), Farm::LEN);
+        assert_space(&StakePosition::default(), StakePosition::LEN);
+        assert_space(&Vault::default(), Vault::LEN);
+        assert_space(&ProgramConfig::default(), ProgramConfig::LEN);
+        assert_space(&VoteRecord::default(), VoteRecord::LEN);
+    }
+
+    #[test]
+    fn volatility_samples_respect_min_interval() {
+        let config = VolatilityConfig {
+            enabled: true,
+            min_sample_interval_secs: 60,
+            ..Default::default()
+        };
+        let mut tracker = VolatilityTracker {
+            observation_count: 4,
+            ..Default::default()
+        };
+        let mut samples = vec![0u8; 4 * OBSERVATION_LEN];
+
+        tracker.update_price_sample(&mut samples, I64F64::ONE, 1_000, &config);
+        assert_eq!((tracker.current_index, tracker.last_updated), (1, 1_000));
+
+        // 间隔不足时跳过，不覆盖窗口中的历史样本
+        tracker.update_price_sample(&mut samples, I64F64::from_num(2), 1_059, &config);
+        assert_eq!((tracker.current_index, tracker.last_updated), (1, 1_000));
+        assert!(samples[OBSERVATION_LEN..].iter().all(|&byte| byte == 0));
+
+        // 间隔达到配置值后记录
+        tracker.update_price_sample(&mut samples, I64F64::from_num(2), 1_060, &config);
+        assert_eq!((tracker.current_index, tracker.last_updated), (2, 1_060));
+
+        // 间隔为0时每次都记录
+        let config = VolatilityConfig { min_sample_interval_secs: 0, ..config };
+        tracker.update_price_sample(&mut samples, I64F64::from_num(3), 1_060, &config);
+        assert_eq!(tracker.current_index, 3);
+    }
+
+    #[test]
+    fn il_position_register_merges_entry_price() {
+        let mut position = IlPosition::default();
+        assert!(position.register(0, I64F64::from_num(1)).is_err());
+
+        position.register(100, I64F64::from_num(2)).unwrap();
+        assert_eq!((position.liquidity, I64F64::from_bits(position.entry_price as i128)), (100, I64F64::from_num(2)));
+
+        // 追加登记按流动性加权合并登记价格
+        position.register(300, I64F64::from_num(4)).unwrap();
+        assert_eq!((position.liquidity, I64F64::from_bits(position.entry_price as i128)), (400, I64F64::from_num(7) / I64F64::from_num(2)));
+    }
+
+    #[test]
+    fn fee_position_deposit_keeps_pending_fees() {
+        let one = 1u128 << 64;
+        let mut position = FeePosition::default();
+        position.deposit(1000, one).unwrap();
+        assert_eq!(LpFeeCalculator::fee_liquidity(position.liquidity, position.fee_growth_checkpoint, 2 * one).unwrap(), 500);
+
+        // 追加托管的流动性不分享此前的手续费增长，已累计的手续费保持不变
+        position.deposit(1000, 2 * one).unwrap();
+        assert_eq!(position.liquidity, 2000);
+        assert_eq!(LpFeeCalculator::fee_liquidity(position.liquidity, position.fee_growth_checkpoint, 2 * one).unwrap(), 500);
+    }
+
+    #[test]
+    fn stake_weight_decays_with_lock() {
+        let now = 1_000;
+        let lock = LiquidityLock {
+            amount: 100,
+            unlock_at: now + crate::constants::MAX_LOCK_DURATION,
+            ..Default::default()
+        };
+        assert_eq!(StakePosition::compute_weight(100, Some(&lock), now), 250);
+
+        // 剩余一半期限时加成减半，到期后没有加成
+        let halfway = now + crate::constants::MAX_LOCK_DURATION / 2;
+        assert_eq!(StakePosition::compute_weight(100, Some(&lock), halfway), 175);
+        assert_eq!(StakePosition::compute_weight(100, Some(&lock), lock.unlock_at), 100);
+        assert_eq!(StakePosition::compute_weight(100, None, now), 100);
+    }
+}
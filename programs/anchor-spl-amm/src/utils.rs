@@ -0,0 +1,113 @@
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::token_2022::{
+    spl_token_2022::{
+        extension::{transfer_fee::TransferFeeConfig, BaseStateWithExtensions, StateWithExtensions},
+        state::Mint,
+    },
+    Token2022,
+};
+
+use crate::errors::TutorialError;
+
+/// 检查交易是否已超过用户设定的截止时间
+pub fn check_deadline(deadline: Option<i64>) -> Result<()> {
+    if let Some(deadline) = deadline {
+        if Clock::get()?.unix_timestamp > deadline {
+            return err!(TutorialError::TransactionExpired);
+        }
+    }
+
+    Ok(())
+}
+
+/// 输入超出余额时，clamp_to_balance为true则按余额成交，否则拒绝
+pub fn input_within_balance(amount: u64, balance: u64, clamp_to_balance: bool) -> Result<u64> {
+    if amount <= balance {
+        Ok(amount)
+    } else if clamp_to_balance {
+        Ok(balance)
+    } else {
+        err!(TutorialError::InsufficientInputBalance)
+    }
+}
+
+/// 计算Token-2022 TransferFee扩展在转出 `amount` 时扣除的费用，旧版代币返回0
+pub fn get_transfer_fee(mint: &AccountInfo, amount: u64) -> Result<u64> {
+    transfer_fee_at(mint, amount, Clock::get()?.epoch)
+}
+
+/// 按指定epoch的费率计算转账费，供链下报价使用调用方提供的时钟
+pub fn transfer_fee_at(mint: &AccountInfo, amount: u64, epoch: u64) -> Result<u64> {
+    if *mint.owner != Token2022::id() {
+        return Ok(0);
+    }
+
+    let data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<Mint>::unpack(&data)?;
+    match mint_state.get_extension::<TransferFeeConfig>() {
+        Ok(config) => config
+            .calculate_epoch_fee(epoch, amount)
+            .ok_or_else(|| error!(TutorialError::MathOverflow)),
+        Err(_) => Ok(0),
+    }
+}
+
+/// 计算为使接收方实收 `amount` 需要额外转出的费用
+pub fn get_transfer_inverse_fee(mint: &AccountInfo, amount: u64) -> Result<u64> {
+    if *mint.owner != Token2022::id() {
+        return Ok(0);
+    }
+
+    let data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<Mint>::unpack(&data)?;
+    match mint_state.get_extension::<TransferFeeConfig>() {
+        Ok(config) => config
+            .calculate_inverse_epoch_fee(Clock::get()?.epoch, amount)
+            .ok_or_else(|| error!(TutorialError::MathOverflow)),
+        Err(_) => Ok(0),
+    }
+}
+
+/// 检查Token-2022代币在当前epoch是否收取转账费，旧版代币返回false
+pub fn has_transfer_fee(mint: &AccountInfo) -> Result<bool> {
+    if *mint.owner != Token2022::id() {
+        return Ok(false);
+    }
+
+    let data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<Mint>::unpack(&data)?;
+    match mint_state.get_extension::<TransferFeeConfig>() {
+        Ok(config) => Ok(u16::from(config.get_epoch_fee(Clock::get()?.epoch).transfer_fee_basis_points) > 0),
+        Err(_) => Ok(false),
+    }
+}
+
+/// 把程序拥有的账户扩容到 `new_len`，由 `payer` 补足新增的租金，新增部分以零填充；
+/// 账户已足够大时不做任何改变
+pub fn grow_account<'info>(
+    account: &AccountInfo<'info>,
+    new_len: usize,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    if account.data_len() >= new_len {
+        return Ok(());
+    }
+
+    let rent_due = Rent::get()?.minimum_balance(new_len).saturating_sub(account.lamports());
+    if rent_due > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                system_program::Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            rent_due,
+        )?;
+    }
+    account.realloc(new_len, true)?;
+
+    Ok(())
+}
@@ -0,0 +1,115 @@
+import * as anchor from '@coral-xyz/anchor';
+import { Program } from '@coral-xyz/anchor';
+import {
+  MINT_SIZE,
+  TOKEN_PROGRAM_ID,
+  createAssociatedTokenAccountIdempotentInstruction,
+  createInitializeMint2Instruction,
+  createMintToInstruction,
+  getAssociatedTokenAddressSync,
+  unpackAccount,
+} from '@solana/spl-token';
+import { type Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram, Transaction } from '@solana/web3.js';
+import { BankrunProvider, startAnchor } from 'anchor-bankrun';
+import { Clock, type ProgramTestContext } from 'solana-bankrun';
+import { type AnchorSplAmm, IDL } from '../../target/types/anchor_spl_amm';
+import type { TestValues } from '../utils';
+
+const METADATA_PROGRAM_ID = new PublicKey('metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s');
+
+export interface Bankrun {
+  context: ProgramTestContext;
+  provider: BankrunProvider;
+  program: Program<AnchorSplAmm>;
+}
+
+// Starts a fresh bank with the program from target/deploy (run `anchor build` first) and the
+// metadata program from genesis/, no validator needed
+export async function startBankrun(): Promise<Bankrun> {
+  // Extra programs are looked up in BPF_OUT_DIR
+  process.env.BPF_OUT_DIR = `${process.cwd()}/genesis`;
+  const context = await startAnchor('.', [{ name: 'metadata', programId: METADATA_PROGRAM_ID }], []);
+  const provider = new BankrunProvider(context);
+  // The shared test utils derive PDAs through anchor.workspace, which uses the global provider
+  anchor.setProvider(provider);
+  const program = new Program<AnchorSplAmm>(IDL, anchor.workspace.AnchorSplAmm.programId, provider);
+  return { context, provider, program };
+}
+
+// Moves the bank clock forward; the slot advances too so repeated transactions get a fresh blockhash
+export async function warp({ context }: Bankrun, seconds: number) {
+  const clock = await context.banksClient.getClock();
+  const slot = clock.slot + BigInt(1);
+  context.warpToSlot(slot);
+  context.setClock(new Clock(slot, clock.epochStartTimestamp, clock.epoch, clock.leaderScheduleEpoch, clock.unixTimestamp + BigInt(seconds)));
+}
+
+export function fund({ context }: Bankrun, address: PublicKey, sol = 10) {
+  context.setAccount(address, {
+    lamports: sol * LAMPORTS_PER_SOL,
+    data: Buffer.alloc(0),
+    owner: SystemProgram.programId,
+    executable: false,
+  });
+}
+
+export async function tokenBalance({ context }: Bankrun, address: PublicKey): Promise<bigint> {
+  const account = await context.banksClient.getAccount(address);
+  return unpackAccount(address, { ...account, data: Buffer.from(account.data) }).amount;
+}
+
+// Bankrun has no RPC for the spl-token helpers, so mints and holder accounts are created with raw instructions
+export async function mintTokens(bankrun: Bankrun, values: TestValues, decimals = 6) {
+  const { context, provider } = bankrun;
+  const payer = provider.wallet.publicKey;
+  const rent = await context.banksClient.getRent();
+  fund(bankrun, values.admin.publicKey);
+
+  const transaction = new Transaction();
+  for (const mintKeypair of [values.mintAKeypair, values.mintBKeypair]) {
+    const mint = mintKeypair.publicKey;
+    const holderAccount = getAssociatedTokenAddressSync(mint, values.admin.publicKey, true);
+    transaction.add(
+      SystemProgram.createAccount({
+        fromPubkey: payer,
+        newAccountPubkey: mint,
+        space: MINT_SIZE,
+        lamports: Number(rent.minimumBalance(BigInt(MINT_SIZE))),
+        programId: TOKEN_PROGRAM_ID,
+      }),
+      createInitializeMint2Instruction(mint, decimals, values.admin.publicKey, null),
+      createAssociatedTokenAccountIdempotentInstruction(payer, holderAccount, values.admin.publicKey, mint),
+      createMintToInstruction(mint, holderAccount, values.admin.publicKey, BigInt(values.defaultSupply.toString())),
+    );
+  }
+  const signers: Keypair[] = [values.mintAKeypair, values.mintBKeypair, values.admin];
+  await provider.sendAndConfirm(transaction, signers);
+}
+
+// Creates the AMM and a constant-product pool of the two test mints
+export async function createPool({ program }: Bankrun, values: TestValues) {
+  await program.methods
+    .createAmm(values.id, values.fee)
+    .accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig })
+    .rpc();
+  await program.methods
+    .createPool(new anchor.BN(0), { constantProduct: {} }, 0, 24, null)
+    .accounts({
+      amm: values.ammKey,
+      pool: values.poolKey,
+      poolRef: values.poolRef,
+      programConfig: values.programConfig,
+      poolOracle: values.poolOracle,
+      poolAuthority: values.poolAuthority,
+      mintLiquidity: values.mintLiquidity,
+      mintA: values.mintAKeypair.publicKey,
+      mintB: values.mintBKeypair.publicKey,
+      tokenProgramA: TOKEN_PROGRAM_ID,
+      tokenProgramB: TOKEN_PROGRAM_ID,
+      poolAccountA: values.poolAccountA,
+      poolAccountB: values.poolAccountB,
+      protocolFeeAccountA: values.protocolFeeAccountA,
+      protocolFeeAccountB: values.protocolFeeAccountB,
+    })
+    .rpc();
+}
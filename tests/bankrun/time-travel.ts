@@ -0,0 +1,292 @@
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { PublicKey } from '@solana/web3.js';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
+import { type TestValues, createValues, expectRevert, swapOptions } from '../utils';
+import { type Bankrun, createPool, mintTokens, startBankrun, tokenBalance, warp } from './setup';
+
+// Time-dependent behaviour, checked by moving the bank clock instead of sleeping on a validator
+describe('Bankrun: time travel', () => {
+  let bankrun: Bankrun;
+  let values: TestValues;
+
+  beforeEach(async () => {
+    bankrun = await startBankrun();
+    values = createValues();
+    await mintTokens(bankrun, values);
+    await createPool(bankrun, values);
+  });
+
+  const depositRecord = () =>
+    PublicKey.findProgramAddressSync(
+      [values.poolKey.toBuffer(), values.admin.publicKey.toBuffer(), Buffer.from('deposit_record')],
+      bankrun.program.programId,
+    )[0];
+
+  const liquidityAccounts = () => ({
+    pool: values.poolKey,
+    poolAuthority: values.poolAuthority,
+    depositor: values.admin.publicKey,
+    mintLiquidity: values.mintLiquidity,
+    mintA: values.mintAKeypair.publicKey,
+    mintB: values.mintBKeypair.publicKey,
+    tokenProgramA: TOKEN_PROGRAM_ID,
+    tokenProgramB: TOKEN_PROGRAM_ID,
+    poolAccountA: values.poolAccountA,
+    poolAccountB: values.poolAccountB,
+    depositorAccountLiquidity: values.liquidityAccount,
+    depositorAccountA: values.holderAccountA,
+    depositorAccountB: values.holderAccountB,
+    depositRecord: depositRecord(),
+  });
+
+  it('The withdrawal fee decays to zero after the decay period', async () => {
+    const { program } = bankrun;
+    await program.methods
+      .updateWithdrawalFeeConfig({ feeBps: 1000, decayPeriod: new BN(86400) })
+      .accounts({ amm: values.ammKey, admin: values.admin.publicKey })
+      .signers([values.admin])
+      .rpc();
+    await program.methods
+      .createDepositRecord()
+      .accounts({ pool: values.poolKey, depositRecord: depositRecord(), owner: values.admin.publicKey })
+      .signers([values.admin])
+      .rpc();
+    await program.methods
+      .depositLiquidity(values.depositAmountA, values.depositAmountB, new BN(0), null, null, false)
+      .accounts(liquidityAccounts())
+      .signers([values.admin])
+      .rpc();
+
+    await warp(bankrun, 86400);
+
+    // Half of the supply is worth exactly half of the 4 * 10^6 A reserve once the fee is gone
+    const before = await tokenBalance(bankrun, values.holderAccountA);
+    await program.methods
+      .withdrawLiquidity(new BN(10 ** 6), new BN(0), new BN(0), null)
+      .accounts({ amm: values.ammKey, ...liquidityAccounts() })
+      .signers([values.admin])
+      .rpc();
+    const after = await tokenBalance(bankrun, values.holderAccountA);
+    expect(Number(after - before)).to.equal(2 * 10 ** 6);
+  });
+
+  it('IL positions escrow the registered liquidity and price it from the TWAP', async () => {
+    const { program } = bankrun;
+    await program.methods
+      .depositLiquidity(values.depositAmountA, values.depositAmountB, new BN(0), null, null, false)
+      .accounts({ ...liquidityAccounts(), depositRecord: null })
+      .signers([values.admin])
+      .rpc();
+
+    const swap = () =>
+      program.methods
+        .swapExactTokensForTokens(values.mintBKeypair.publicKey, new BN(10 ** 4), new BN(1), null, swapOptions())
+        .accounts({
+          amm: values.ammKey,
+          pool: values.poolKey,
+          poolAuthority: values.poolAuthority,
+          trader: values.admin.publicKey,
+          mintA: values.mintAKeypair.publicKey,
+          mintB: values.mintBKeypair.publicKey,
+          tokenProgramA: TOKEN_PROGRAM_ID,
+          tokenProgramB: TOKEN_PROGRAM_ID,
+          poolAccountA: values.poolAccountA,
+          poolAccountB: values.poolAccountB,
+          protocolFeeAccountA: values.protocolFeeAccountA,
+          protocolFeeAccountB: values.protocolFeeAccountB,
+          traderAccountA: values.holderAccountA,
+          traderAccountB: values.holderAccountB,
+        })
+        .signers([values.admin])
+        .rpc();
+
+    const ilPosition = PublicKey.findProgramAddressSync(
+      [values.poolKey.toBuffer(), values.admin.publicKey.toBuffer(), Buffer.from('il_position')],
+      program.programId,
+    )[0];
+    const escrow = PublicKey.findProgramAddressSync([ilPosition.toBuffer(), Buffer.from('escrow')], program.programId)[0];
+    const register = (amount: number) =>
+      program.methods
+        .registerIlPosition(new BN(amount))
+        .accounts({
+          pool: values.poolKey,
+          poolAuthority: values.poolAuthority,
+          ilPosition,
+          escrow,
+          owner: values.admin.publicKey,
+          mintLiquidity: values.mintLiquidity,
+          mintA: values.mintAKeypair.publicKey,
+          mintB: values.mintBKeypair.publicKey,
+          poolAccountA: values.poolAccountA,
+          poolAccountB: values.poolAccountB,
+          ownerAccountLiquidity: values.liquidityAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          tokenProgramA: TOKEN_PROGRAM_ID,
+          tokenProgramB: TOKEN_PROGRAM_ID,
+        })
+        .signers([values.admin])
+        .rpc();
+
+    // The entry price is a 30 minute TWAP, so a swap in the same transaction cannot move it
+    await swap();
+    await warp(bankrun, 60);
+    await swap();
+    await expectRevert(register(10 ** 5));
+    await warp(bankrun, 30 * 60);
+
+    const before = await tokenBalance(bankrun, values.liquidityAccount);
+    await register(10 ** 5);
+    expect(before - (await tokenBalance(bankrun, values.liquidityAccount))).to.equal(BigInt(10 ** 5));
+    expect(await tokenBalance(bankrun, escrow)).to.equal(BigInt(10 ** 5));
+    expect((await program.account.ilPosition.fetch(ilPosition)).liquidity.toNumber()).to.equal(10 ** 5);
+
+    // Closing the position hands the escrowed liquidity back
+    await program.methods
+      .closeIlPosition()
+      .accounts({
+        ilPosition,
+        escrow,
+        mintLiquidity: values.mintLiquidity,
+        owner: values.admin.publicKey,
+        ownerAccountLiquidity: values.liquidityAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([values.admin])
+      .rpc();
+    expect(await tokenBalance(bankrun, values.liquidityAccount)).to.equal(before);
+  });
+
+  it('The stake boost decays with the lock and anyone can refresh it', async () => {
+    const { program } = bankrun;
+    await program.methods
+      .depositLiquidity(values.depositAmountA, values.depositAmountB, new BN(0), null, null, false)
+      .accounts({ ...liquidityAccounts(), depositRecord: null })
+      .signers([values.admin])
+      .rpc();
+
+    const farm = PublicKey.findProgramAddressSync([values.poolKey.toBuffer(), Buffer.from('farm')], program.programId)[0];
+    const lpVault = PublicKey.findProgramAddressSync([farm.toBuffer(), Buffer.from('escrow')], program.programId)[0];
+    const position = PublicKey.findProgramAddressSync(
+      [farm.toBuffer(), values.admin.publicKey.toBuffer(), Buffer.from('stake_position')],
+      program.programId,
+    )[0];
+    const lock = PublicKey.findProgramAddressSync(
+      [values.poolKey.toBuffer(), values.admin.publicKey.toBuffer(), Buffer.from('liquidity_lock')],
+      program.programId,
+    )[0];
+    const escrow = PublicKey.findProgramAddressSync([lock.toBuffer(), Buffer.from('escrow')], program.programId)[0];
+
+    await program.methods
+      .createFarm()
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        farm,
+        mintLiquidity: values.mintLiquidity,
+        lpVault,
+        rewardMint: values.mintAKeypair.publicKey,
+        rewardVault: PublicKey.findProgramAddressSync(
+          [farm.toBuffer(), values.mintAKeypair.publicKey.toBuffer(), Buffer.from('reward_vault')],
+          program.programId,
+        )[0],
+        admin: values.admin.publicKey,
+        tokenProgramReward: TOKEN_PROGRAM_ID,
+      })
+      .signers([values.admin])
+      .rpc();
+    await program.methods
+      .lockLiquidity(new BN(10 ** 5), new BN(365 * 86400))
+      .accounts({
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        mintLiquidity: values.mintLiquidity,
+        lock,
+        escrow,
+        owner: values.admin.publicKey,
+        ownerAccountLiquidity: values.liquidityAccount,
+      })
+      .signers([values.admin])
+      .rpc();
+    await program.methods
+      .stakeLp(new BN(10 ** 5))
+      .accounts({
+        farm,
+        lpVault,
+        mintLiquidity: values.mintLiquidity,
+        position,
+        owner: values.admin.publicKey,
+        ownerAccountLiquidity: values.liquidityAccount,
+        lock,
+      })
+      .signers([values.admin])
+      .rpc();
+
+    const weight = async () => (await program.account.stakePosition.fetch(position)).weight.toNumber();
+    // No owner signature needed, the lock is always read from its address
+    const refresh = () => program.methods.refreshStakeWeight().accounts({ farm, position, lock }).rpc();
+    const boosted = await weight();
+    expect(boosted).to.be.greaterThan(10 ** 5);
+
+    // The boost decays linearly as the expiry approaches
+    await warp(bankrun, 180 * 86400);
+    await refresh();
+    expect(await weight()).to.be.lessThan(boosted);
+    expect(await weight()).to.be.greaterThan(10 ** 5);
+
+    // Once the lock is gone only the staked amount counts
+    await warp(bankrun, 186 * 86400);
+    await program.methods
+      .unlockLiquidity()
+      .accounts({
+        lock,
+        escrow,
+        mintLiquidity: values.mintLiquidity,
+        owner: values.admin.publicKey,
+        ownerAccountLiquidity: values.liquidityAccount,
+      })
+      .signers([values.admin])
+      .rpc();
+    await refresh();
+    expect(await weight()).to.equal(10 ** 5);
+    expect((await program.account.farm.fetch(farm)).totalWeight.toNumber()).to.equal(10 ** 5);
+  });
+
+  it('Queued parameter changes execute once the delay has passed', async () => {
+    const { program } = bankrun;
+    await program.methods
+      .setTimelockDelay(new BN(86400))
+      .accounts({ amm: values.ammKey, admin: values.admin.publicKey })
+      .signers([values.admin])
+      .rpc();
+
+    const pendingChange = PublicKey.findProgramAddressSync(
+      [values.ammKey.toBuffer(), values.admin.publicKey.toBuffer(), new BN(0).toArrayLike(Buffer, 'le', 8), Buffer.from('pending_change')],
+      program.programId,
+    )[0];
+    await program.methods
+      .proposeParameterChange(new BN(0), { feeSide: [true] })
+      .accounts({ amm: values.ammKey, pendingChange, proposer: values.admin.publicKey })
+      .signers([values.admin])
+      .rpc();
+    await program.methods
+      .queueParameterChange()
+      .accounts({ amm: values.ammKey, pendingChange, admin: values.admin.publicKey })
+      .signers([values.admin])
+      .rpc();
+
+    const execute = () =>
+      program.methods
+        .executeParameterChange()
+        .accounts({ amm: values.ammKey, pendingChange, pool: null, proposer: values.admin.publicKey })
+        .rpc();
+    await warp(bankrun, 86000);
+    await expectRevert(execute());
+
+    await warp(bankrun, 400);
+    await execute();
+    expect((await program.account.amm.fetch(values.ammKey)).feeOnOutput).to.equal(true);
+  });
+});
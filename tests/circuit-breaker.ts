@@ -0,0 +1,120 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { Keypair } from '@solana/web3.js';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type TestValues, createValues, expectRevert, mintingTokens, swapOptions } from './utils';
+
+describe('Circuit breaker', () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+
+    await program.methods
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolRef: values.poolRef,
+        programConfig: values.programConfig,
+        poolOracle: values.poolOracle,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+      })
+      .rpc();
+
+    await program.methods
+      .depositLiquidity(values.depositAmountA, values.depositAmountB, new BN(0), null, null, false)
+      .accounts({
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc({ skipPreflight: true });
+  });
+
+  const setCircuitBreaker = (until: number, admin = values.admin) =>
+    program.methods
+      .setCircuitBreaker(new BN(until))
+      .accounts({ amm: values.ammKey, pool: values.poolKey, admin: admin.publicKey })
+      .signers([admin])
+      .rpc();
+
+  const swap = () =>
+    program.methods
+      .swapExactTokensForTokens(values.mintBKeypair.publicKey, new BN(10 ** 4), new BN(0), null, swapOptions())
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        trader: values.admin.publicKey,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+        traderAccountA: values.holderAccountA,
+        traderAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc();
+
+  it('Swaps are blocked while tripped', async () => {
+    await setCircuitBreaker(Math.floor(Date.now() / 1000) + 3600);
+
+    const pool = await program.account.pool.fetch(values.poolKey);
+    expect(pool.circuitBreakerUntil.toNumber()).to.be.greaterThan(0);
+    await expectRevert(swap());
+  });
+
+  it('Admin reset restores swaps', async () => {
+    await setCircuitBreaker(Math.floor(Date.now() / 1000) + 3600);
+    await setCircuitBreaker(0);
+
+    await swap();
+  });
+
+  it('Only the admin can override', async () => {
+    await expectRevert(setCircuitBreaker(0, Keypair.generate()));
+  });
+});
@@ -0,0 +1,242 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { PublicKey } from '@solana/web3.js';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type TestValues, createValues, mintingTokens, swapOptions } from './utils';
+
+describe('Claim fees', () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+
+    await program.methods
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolRef: values.poolRef,
+        programConfig: values.programConfig,
+        poolOracle: values.poolOracle,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+      })
+      .rpc();
+
+    await program.methods
+      .depositLiquidity(values.depositAmountA, values.depositAmountB, new BN(0), null, null, false)
+      .accounts({
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc({ skipPreflight: true });
+  });
+
+  const feePosition = () =>
+    PublicKey.findProgramAddressSync([values.poolKey.toBuffer(), values.admin.publicKey.toBuffer(), Buffer.from('fee_position')], program.programId)[0];
+
+  const escrow = () => PublicKey.findProgramAddressSync([feePosition().toBuffer(), Buffer.from('escrow')], program.programId)[0];
+
+  const depositFeePosition = (amount: BN) =>
+    program.methods
+      .depositFeePosition(amount)
+      .accounts({
+        pool: values.poolKey,
+        mintLiquidity: values.mintLiquidity,
+        feePosition: feePosition(),
+        escrow: escrow(),
+        owner: values.admin.publicKey,
+        ownerAccountLiquidity: values.liquidityAccount,
+        payer: values.admin.publicKey,
+      })
+      .signers([values.admin])
+      .rpc();
+
+  const claimFees = () =>
+    program.methods
+      .claimFees()
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        feePosition: feePosition(),
+        escrow: escrow(),
+        owner: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        ownerAccountA: values.holderAccountA,
+        ownerAccountB: values.holderAccountB,
+        depositRecord: null,
+      })
+      .signers([values.admin])
+      .rpc();
+
+  const swap = () =>
+    program.methods
+      .swapExactTokensForTokens(values.mintBKeypair.publicKey, new BN(10 ** 5), new BN(1), null, swapOptions())
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        trader: values.admin.publicKey,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+        traderAccountA: values.holderAccountA,
+        traderAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc();
+
+  const escrowBalance = async () => new BN((await connection.getTokenAccountBalance(escrow())).value.amount);
+
+  it('Depositing escrows the liquidity and records the checkpoint', async () => {
+    const before = await connection.getTokenAccountBalance(values.liquidityAccount);
+    await depositFeePosition(new BN(10 ** 6));
+
+    const after = await connection.getTokenAccountBalance(values.liquidityAccount);
+    expect(new BN(before.value.amount).sub(new BN(after.value.amount)).toNumber()).to.equal(10 ** 6);
+    expect((await escrowBalance()).toNumber()).to.equal(10 ** 6);
+    const position = await program.account.feePosition.fetch(feePosition());
+    expect(position.liquidity.toNumber()).to.equal(10 ** 6);
+    expect(position.feeGrowthCheckpoint.toString()).to.not.equal('0');
+
+    // Nothing has accrued yet
+    await claimFees();
+    expect((await escrowBalance()).toNumber()).to.equal(10 ** 6);
+  });
+
+  it('Claim fees earned by swaps', async () => {
+    await depositFeePosition(new BN(10 ** 6));
+    await swap();
+
+    const balanceBBefore = await connection.getTokenAccountBalance(values.holderAccountB);
+    await claimFees();
+
+    // Only the fee share of the escrowed liquidity is burned
+    const escrowed = await escrowBalance();
+    const balanceBAfter = await connection.getTokenAccountBalance(values.holderAccountB);
+    expect(escrowed.toNumber()).to.be.lessThan(10 ** 6);
+    expect(escrowed.toNumber()).to.be.greaterThan(10 ** 6 * 0.99);
+    expect(Number(balanceBAfter.value.amount)).to.be.greaterThan(Number(balanceBBefore.value.amount));
+    const position = await program.account.feePosition.fetch(feePosition());
+    expect(position.liquidity.toString()).to.equal(escrowed.toString());
+  });
+
+  // Invariant per liquidity token of the constant product pool
+  const growth = async () => {
+    const pool = await program.account.pool.fetch(values.poolKey);
+    const supply = Number((await connection.getTokenSupply(values.mintLiquidity)).value.amount) + values.minimumLiquidity.toNumber();
+    return Math.sqrt(pool.reserveA.toNumber() * pool.reserveB.toNumber()) / supply;
+  };
+
+  it('Liquidity added after fees accrued does not share them', async () => {
+    await depositFeePosition(new BN(5 * 10 ** 5));
+    const checkpoint = await growth();
+    await swap();
+
+    // The top-up merges the checkpoint, only the first deposit earned the swap fees
+    await depositFeePosition(new BN(5 * 10 ** 5));
+    const current = await growth();
+    await claimFees();
+
+    const burned = new BN(10 ** 6).sub(await escrowBalance()).toNumber();
+    const expected = (5 * 10 ** 5 * (current - checkpoint)) / current;
+    expect(burned).to.be.greaterThan(0);
+    expect(Math.abs(burned - expected)).to.be.at.most(2);
+  });
+
+  it('Closing returns the escrowed liquidity', async () => {
+    await depositFeePosition(new BN(10 ** 6));
+    const before = new BN((await connection.getTokenAccountBalance(values.liquidityAccount)).value.amount);
+
+    await program.methods
+      .closeFeePosition()
+      .accounts({
+        feePosition: feePosition(),
+        escrow: escrow(),
+        mintLiquidity: values.mintLiquidity,
+        owner: values.admin.publicKey,
+        ownerAccountLiquidity: values.liquidityAccount,
+      })
+      .signers([values.admin])
+      .rpc();
+
+    const after = new BN((await connection.getTokenAccountBalance(values.liquidityAccount)).value.amount);
+    expect(after.sub(before).toNumber()).to.equal(10 ** 6);
+    expect(await connection.getAccountInfo(feePosition())).to.equal(null);
+  });
+
+  it('Claimed fees pay the withdrawal fee', async () => {
+    await depositFeePosition(new BN(10 ** 6));
+    await swap();
+
+    // Burning LP to claim fees is charged like a withdrawal, the fee stays in the pool
+    await program.methods
+      .updateWithdrawalFeeConfig({ feeBps: 1000, decayPeriod: new BN(86400) })
+      .accounts({ amm: values.ammKey, admin: values.admin.publicKey })
+      .signers([values.admin])
+      .rpc();
+
+    const pool = await program.account.pool.fetch(values.poolKey);
+    const supply = (await connection.getTokenSupply(values.mintLiquidity)).value.amount;
+    const escrowBefore = await escrowBalance();
+    const balanceBBefore = await connection.getTokenAccountBalance(values.holderAccountB);
+    await claimFees();
+
+    const balanceBAfter = await connection.getTokenAccountBalance(values.holderAccountB);
+    const burned = escrowBefore.sub(await escrowBalance());
+    const share = burned.mul(pool.reserveB).div(new BN(supply).add(values.minimumLiquidity));
+    const received = new BN(balanceBAfter.value.amount).sub(new BN(balanceBBefore.value.amount));
+    expect(burned.toNumber()).to.be.greaterThan(0);
+    expect(received.toNumber()).to.be.at.most(share.muln(9).divn(10).toNumber());
+  });
+});
@@ -0,0 +1,143 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_PROGRAM_ID, transfer } from '@solana/spl-token';
+import { PublicKey } from '@solana/web3.js';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type TestValues, createValues, expectRevert, mintingTokens } from './utils';
+
+describe('Close pool', () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+
+  const compensationVault = (mint: PublicKey) =>
+    PublicKey.findProgramAddressSync([values.poolAuthority.toBuffer(), mint.toBuffer(), Buffer.from('il_compensation')], program.programId)[0];
+
+  const closePool = (withAdminAccounts = false) =>
+    program.methods
+      .closePool()
+      .accounts({
+        amm: values.ammKey,
+        admin: values.admin.publicKey,
+        pool: values.poolKey,
+        poolOracle: values.poolOracle,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+        ilCompensationAccountA: compensationVault(values.mintAKeypair.publicKey),
+        ilCompensationAccountB: compensationVault(values.mintBKeypair.publicKey),
+        adminAccountA: withAdminAccounts ? values.holderAccountA : null,
+        adminAccountB: withAdminAccounts ? values.holderAccountB : null,
+        receiver: values.admin.publicKey,
+      })
+      .signers([values.admin])
+      .rpc();
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+
+    await program.methods
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolRef: values.poolRef,
+        programConfig: values.programConfig,
+        poolOracle: values.poolOracle,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+      })
+      .rpc();
+  });
+
+  it('Close an empty pool', async () => {
+    await closePool();
+
+    expect(await connection.getAccountInfo(values.poolKey)).to.be.null;
+    expect(await connection.getAccountInfo(values.poolAccountA)).to.be.null;
+    expect(await connection.getAccountInfo(values.poolAccountB)).to.be.null;
+    expect(await connection.getAccountInfo(values.poolOracle)).to.be.null;
+  });
+
+  it('Compensation vault balances are swept to the admin', async () => {
+    const [vaultA, vaultB] = [values.mintAKeypair.publicKey, values.mintBKeypair.publicKey].map(compensationVault);
+    await program.methods
+      .createIlCompensationVault()
+      .accounts({
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        ilCompensationAccountA: vaultA,
+        ilCompensationAccountB: vaultB,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    await transfer(connection, values.admin, values.holderAccountA, vaultA, values.admin, 1000);
+    const before = await connection.getTokenAccountBalance(values.holderAccountA);
+
+    // The vault still holds funds, so the admin must pass an account to receive them
+    await expectRevert(closePool());
+    await closePool(true);
+
+    const after = await connection.getTokenAccountBalance(values.holderAccountA);
+    expect(new BN(after.value.amount).sub(new BN(before.value.amount)).toNumber()).to.equal(1000);
+    expect(await connection.getAccountInfo(vaultA)).to.be.null;
+    expect(await connection.getAccountInfo(vaultB)).to.be.null;
+  });
+
+  it('Pool with liquidity', async () => {
+    await program.methods
+      .depositLiquidity(values.depositAmountA, values.depositAmountB, new BN(0), null, null, false)
+      .accounts({
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc({ skipPreflight: true });
+
+    await expectRevert(closePool());
+  });
+});
@@ -0,0 +1,171 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_PROGRAM_ID, getAssociatedTokenAddressSync } from '@solana/spl-token';
+import { Keypair } from '@solana/web3.js';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type TestValues, createValues, expectRevert, mintingTokens, swapOptions } from './utils';
+
+describe('Collect protocol fees', () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+  let treasury: Keypair;
+
+  beforeEach(async () => {
+    values = createValues();
+    treasury = Keypair.generate();
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+
+    // Half of every swap fee goes to the protocol
+    await program.methods
+      .updateFeeConfig({
+        strategy: { fixed: {} },
+        minFeeBps: values.fee / 2,
+        maxFeeBps: values.fee * 2,
+        baseFeeBps: values.fee,
+        adjustmentFactor: 500,
+        protocolFeeBps: 5000,
+        ilCompensationFeeBps: 0,
+        feeAToBBps: null,
+        feeBToABps: null,
+      })
+      .accounts({ amm: values.ammKey, admin: values.admin.publicKey })
+      .signers([values.admin])
+      .rpc();
+
+    await program.methods
+      .setFeeRecipient()
+      .accounts({ amm: values.ammKey, admin: values.admin.publicKey, feeRecipient: treasury.publicKey })
+      .signers([values.admin])
+      .rpc();
+
+    await program.methods
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolRef: values.poolRef,
+        programConfig: values.programConfig,
+        poolOracle: values.poolOracle,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+      })
+      .rpc();
+
+    await program.methods
+      .depositLiquidity(values.depositAmountA, values.depositAmountB, new BN(0), null, null, false)
+      .accounts({
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc({ skipPreflight: true });
+  });
+
+  it('Collect after a swap', async () => {
+    const input = new BN(10 ** 6);
+    await program.methods
+      .swapExactTokensForTokens(values.mintAKeypair.publicKey, input, new BN(100), null, swapOptions())
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        trader: values.admin.publicKey,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+        traderAccountA: values.holderAccountA,
+        traderAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc({ skipPreflight: true });
+
+    const expectedFee = input.muln(values.fee).divn(10000).divn(2);
+    const accrued = await connection.getTokenAccountBalance(values.protocolFeeAccountA);
+    expect(accrued.value.amount).to.equal(expectedFee.toString());
+
+    const treasuryAccountA = getAssociatedTokenAddressSync(values.mintAKeypair.publicKey, treasury.publicKey, true);
+    const treasuryAccountB = getAssociatedTokenAddressSync(values.mintBKeypair.publicKey, treasury.publicKey, true);
+    await program.methods
+      .collectProtocolFees()
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+        feeRecipient: treasury.publicKey,
+        treasuryAccountA,
+        treasuryAccountB,
+      })
+      .rpc();
+
+    const collected = await connection.getTokenAccountBalance(treasuryAccountA);
+    expect(collected.value.amount).to.equal(expectedFee.toString());
+  });
+
+  it('Wrong recipient', async () => {
+    const stranger = Keypair.generate();
+    await expectRevert(
+      program.methods
+        .collectProtocolFees()
+        .accounts({
+          amm: values.ammKey,
+          pool: values.poolKey,
+          poolAuthority: values.poolAuthority,
+          mintA: values.mintAKeypair.publicKey,
+          mintB: values.mintBKeypair.publicKey,
+          tokenProgramA: TOKEN_PROGRAM_ID,
+          tokenProgramB: TOKEN_PROGRAM_ID,
+          protocolFeeAccountA: values.protocolFeeAccountA,
+          protocolFeeAccountB: values.protocolFeeAccountB,
+          feeRecipient: stranger.publicKey,
+          treasuryAccountA: getAssociatedTokenAddressSync(values.mintAKeypair.publicKey, stranger.publicKey, true),
+          treasuryAccountB: getAssociatedTokenAddressSync(values.mintBKeypair.publicKey, stranger.publicKey, true),
+        })
+        .rpc(),
+    );
+  });
+});
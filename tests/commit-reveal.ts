@@ -0,0 +1,153 @@
+import { createHash, randomBytes } from 'node:crypto';
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { PublicKey } from '@solana/web3.js';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type TestValues, createValues, expectRevert, mintingTokens } from './utils';
+
+describe('Commit-reveal swap', () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+  let swapCommitment: PublicKey;
+
+  beforeEach(async () => {
+    values = createValues();
+    swapCommitment = PublicKey.findProgramAddressSync(
+      [values.poolKey.toBuffer(), values.admin.publicKey.toBuffer(), Buffer.from('swap_commitment')],
+      program.programId,
+    )[0];
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+
+    await program.methods
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolRef: values.poolRef,
+        programConfig: values.programConfig,
+        poolOracle: values.poolOracle,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+      })
+      .rpc();
+
+    await program.methods
+      .depositLiquidity(values.depositAmountA, values.depositAmountB, new BN(0), null, null, false)
+      .accounts({
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc({ skipPreflight: true });
+  });
+
+  const wait = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+  // sha256(input_mint || input_amount || min_output_amount || salt), integers little-endian
+  const commitmentHash = (inputMint: PublicKey, input: anchor.BN, minOutput: anchor.BN, salt: Buffer) =>
+    [
+      ...createHash('sha256')
+        .update(Buffer.concat([inputMint.toBuffer(), input.toArrayLike(Buffer, 'le', 8), minOutput.toArrayLike(Buffer, 'le', 8), salt]))
+        .digest(),
+    ];
+
+  const commit = (hash: number[]) =>
+    program.methods
+      .commitSwap(hash)
+      .accounts({ pool: values.poolKey, swapCommitment, trader: values.admin.publicKey })
+      .signers([values.admin])
+      .rpc();
+
+  const reveal = (inputMint: PublicKey, input: anchor.BN, minOutput: anchor.BN, salt: Buffer) =>
+    program.methods
+      .revealSwap(inputMint, input, minOutput, [...salt])
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        trader: values.admin.publicKey,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+        traderAccountA: values.holderAccountA,
+        traderAccountB: values.holderAccountB,
+        swapCommitment,
+      })
+      .signers([values.admin])
+      .rpc();
+
+  it('Reveal executes the committed swap', async () => {
+    const input = new BN(10 ** 5);
+    const salt = randomBytes(32);
+    await commit(commitmentHash(values.mintAKeypair.publicKey, input, new BN(1), salt));
+
+    const commitment = await program.account.swapCommitment.fetch(swapCommitment);
+    expect(commitment.trader.toString()).to.equal(values.admin.publicKey.toString());
+
+    await wait(1000);
+    await reveal(values.mintAKeypair.publicKey, input, new BN(1), salt);
+
+    const traderTokenAccountA = await connection.getTokenAccountBalance(values.holderAccountA);
+    expect(traderTokenAccountA.value.amount).to.equal(values.defaultSupply.sub(values.depositAmountA).sub(input).toString());
+    // The commitment is consumed
+    expect(await connection.getAccountInfo(swapCommitment)).to.be.null;
+  });
+
+  it('Revealed parameters must match the commitment', async () => {
+    const salt = randomBytes(32);
+    await commit(commitmentHash(values.mintAKeypair.publicKey, new BN(10 ** 5), new BN(1), salt));
+    await wait(1000);
+
+    await expectRevert(reveal(values.mintAKeypair.publicKey, new BN(10 ** 6), new BN(1), salt));
+    await expectRevert(reveal(values.mintAKeypair.publicKey, new BN(10 ** 5), new BN(1), randomBytes(32)));
+    // The direction is part of the commitment
+    await expectRevert(reveal(values.mintBKeypair.publicKey, new BN(10 ** 5), new BN(1), salt));
+  });
+
+  it('Cancel returns the commitment rent', async () => {
+    await commit(commitmentHash(values.mintBKeypair.publicKey, new BN(10 ** 5), new BN(1), randomBytes(32)));
+
+    await program.methods.cancelSwapCommitment().accounts({ swapCommitment, trader: values.admin.publicKey }).signers([values.admin]).rpc();
+    expect(await connection.getAccountInfo(swapCommitment)).to.be.null;
+  });
+});
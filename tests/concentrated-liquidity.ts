@@ -0,0 +1,168 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { Keypair } from '@solana/web3.js';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type TestValues, createValues, expectRevert, mintingTokens, swapOptions } from './utils';
+
+describe('Concentrated liquidity config', () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+
+    await program.methods
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolRef: values.poolRef,
+        programConfig: values.programConfig,
+        poolOracle: values.poolOracle,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+      })
+      .rpc();
+
+    await program.methods
+      .depositLiquidity(values.depositAmountA, values.depositAmountB, new BN(0), null, null, false)
+      .accounts({
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc();
+  });
+
+  const config = (enabled: boolean, rangePercentage = 10) => ({
+    enabled,
+    rangePercentage,
+    rewardMultiplier: 1200,
+    minWidth: new BN(0),
+  });
+
+  const updateConfig = (enabled: boolean, rangePercentage = 10, admin = values.admin) =>
+    program.methods
+      .updateConcentratedLiquidityConfig(config(enabled, rangePercentage))
+      .accounts({ amm: values.ammKey, admin: admin.publicKey })
+      .signers([admin])
+      .rpc();
+
+  const migratePool = () =>
+    program.methods
+      .migratePoolPriceRange()
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        admin: values.admin.publicKey,
+      })
+      .signers([values.admin])
+      .rpc();
+
+  const swap = (amount: number) =>
+    program.methods
+      .swapExactTokensForTokens(values.mintBKeypair.publicKey, new BN(amount), new BN(1), null, swapOptions())
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        trader: values.admin.publicKey,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+        traderAccountA: values.holderAccountA,
+        traderAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc();
+
+  it('Update config', async () => {
+    await updateConfig(true, 20);
+
+    const amm = await program.account.amm.fetch(values.ammKey);
+    expect(amm.concentratedLiquidityConfig.enabled).to.equal(true);
+    expect(amm.concentratedLiquidityConfig.rangePercentage).to.equal(20);
+  });
+
+  it('Invalid config', async () => {
+    await expectRevert(updateConfig(true, 0));
+    await expectRevert(updateConfig(true, 100));
+  });
+
+  it('Only admin can update', async () => {
+    await expectRevert(updateConfig(true, 10, Keypair.generate()));
+  });
+
+  it('Existing pools keep full range', async () => {
+    await updateConfig(true);
+
+    const pool = await program.account.pool.fetch(values.poolKey);
+    expect(pool.priceRange.upperPriceX64.toNumber()).to.equal(0);
+
+    // Moves the price of A from 0.25 to ~0.29, outside ±10%
+    await swap(80_000);
+  });
+
+  it('Migrate pool price range', async () => {
+    await updateConfig(true);
+    await migratePool();
+
+    // Pool price of A is 0.25 B, the range is ±10%
+    const Q64 = new BN(2).pow(new BN(64));
+    const pool = await program.account.pool.fetch(values.poolKey);
+    expect(pool.priceRange.lowerPriceX64.muln(1_000_000).div(Q64).toNumber()).to.be.closeTo(225_000, 1);
+    expect(pool.priceRange.upperPriceX64.muln(1_000_000).div(Q64).toNumber()).to.be.closeTo(275_000, 1);
+
+    await expectRevert(swap(80_000));
+    await swap(10_000);
+
+    // Disabling restores the full range
+    await updateConfig(false);
+    await migratePool();
+    await swap(80_000);
+  });
+});
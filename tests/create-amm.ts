@@ -22,7 +22,7 @@ describe('Create AMM', () => {
     it('Creation', async () => {
       
       await program.methods.createAmm(values.id, values.fee)
-      .accounts({ amm: values.ammKey, admin: values.admin.publicKey }).rpc();
+      .accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
   
       const ammAccount = await program.account.amm.fetch(values.ammKey);
       expect(ammAccount.id.toString()).to.equal(values.id.toString());
@@ -33,6 +33,6 @@ describe('Create AMM', () => {
     it('Invalid fee', async () => {
       values.fee = 10000;
   
-      await expectRevert(program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey }).rpc());
+      await expectRevert(program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc());
     });
   });
\ No newline at end of file
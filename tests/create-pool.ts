@@ -1,8 +1,14 @@
 import * as anchor from '@coral-xyz/anchor';
 import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_PROGRAM_ID, getMint } from '@solana/spl-token';
 import { PublicKey } from '@solana/web3.js';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
 import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
-import { type TestValues, createValues, expectRevert, mintingTokens } from './utils';
+import { type TestValues, createValues, derivePoolKeys, expectRevert, mintingTokens, poolRefKey } from './utils';
+
+// Metaplex token metadata program, loaded into the local validator from genesis
+const TOKEN_METADATA_PROGRAM_ID = new PublicKey('metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s');
 
 describe('Create pool', () => {
   const provider = anchor.AnchorProvider.env();
@@ -16,7 +22,7 @@ describe('Create pool', () => {
   beforeEach(async () => {
     values = createValues();
 
-    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey }).rpc();
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
 
     await mintingTokens({
       connection,
@@ -28,20 +34,125 @@ describe('Create pool', () => {
 
   it('Creation', async () => {
     await program.methods
-      .createPool()
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
       .accounts({
         amm: values.ammKey,
         pool: values.poolKey,
+        poolRef: values.poolRef,
+        programConfig: values.programConfig,
+        poolOracle: values.poolOracle,
         poolAuthority: values.poolAuthority,
         mintLiquidity: values.mintLiquidity,
         mintA: values.mintAKeypair.publicKey,
         mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
         poolAccountA: values.poolAccountA,
         poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
       })
       .rpc({ skipPreflight: true });
   });
 
+  it('LP token metadata', async () => {
+    const lpMetadata = PublicKey.findProgramAddressSync(
+      [Buffer.from('metadata'), TOKEN_METADATA_PROGRAM_ID.toBuffer(), values.mintLiquidity.toBuffer()],
+      TOKEN_METADATA_PROGRAM_ID,
+    )[0];
+    await program.methods
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, { name: 'AMM LP: A-B', symbol: 'AMM-LP', uri: '' })
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolRef: values.poolRef,
+        programConfig: values.programConfig,
+        poolOracle: values.poolOracle,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+        lpMetadata,
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      })
+      .rpc();
+
+    const metadataAccount = await connection.getAccountInfo(lpMetadata);
+    expect(metadataAccount.owner.toString()).to.equal(TOKEN_METADATA_PROGRAM_ID.toString());
+  });
+
+  it('LP token metadata without the metadata accounts', async () => {
+    await expectRevert(
+      program.methods
+        .createPool(new BN(0), { constantProduct: {} }, 0, 24, { name: 'AMM LP: A-B', symbol: 'AMM-LP', uri: '' })
+        .accounts({
+          amm: values.ammKey,
+          pool: values.poolKey,
+          poolRef: values.poolRef,
+          programConfig: values.programConfig,
+          poolOracle: values.poolOracle,
+          poolAuthority: values.poolAuthority,
+          mintLiquidity: values.mintLiquidity,
+          mintA: values.mintAKeypair.publicKey,
+          mintB: values.mintBKeypair.publicKey,
+          tokenProgramA: TOKEN_PROGRAM_ID,
+          tokenProgramB: TOKEN_PROGRAM_ID,
+          poolAccountA: values.poolAccountA,
+          poolAccountB: values.poolAccountB,
+          protocolFeeAccountA: values.protocolFeeAccountA,
+          protocolFeeAccountB: values.protocolFeeAccountB,
+        })
+        .rpc(),
+    );
+  });
+
+  it('LP decimals follow the underlying mints', async () => {
+    values = createValues();
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+      decimals: 9,
+    });
+
+    await program.methods
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolRef: values.poolRef,
+        programConfig: values.programConfig,
+        poolOracle: values.poolOracle,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+      })
+      .rpc();
+
+    const mintLiquidity = await getMint(connection, values.mintLiquidity);
+    expect(mintLiquidity.decimals).to.equal(9);
+
+    // Prices are normalized with the decimals stored on the pool
+    const pool = await program.account.pool.fetch(values.poolKey);
+    expect(pool.decimalsA).to.equal(9);
+    expect(pool.decimalsB).to.equal(9);
+  });
+
   it('Invalid mints', async () => {
     values = createValues({
       mintBKeypair: values.mintAKeypair,
@@ -57,18 +168,96 @@ describe('Create pool', () => {
 
     await expectRevert(
       program.methods
-        .createPool()
+        .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
         .accounts({
           amm: values.ammKey,
           pool: values.poolKey,
+          poolRef: values.poolRef,
+          programConfig: values.programConfig,
+          poolOracle: values.poolOracle,
           poolAuthority: values.poolAuthority,
           mintLiquidity: values.mintLiquidity,
           mintA: values.mintAKeypair.publicKey,
           mintB: values.mintBKeypair.publicKey,
+          tokenProgramA: TOKEN_PROGRAM_ID,
+          tokenProgramB: TOKEN_PROGRAM_ID,
           poolAccountA: values.poolAccountA,
           poolAccountB: values.poolAccountB,
+          protocolFeeAccountA: values.protocolFeeAccountA,
+          protocolFeeAccountB: values.protocolFeeAccountB,
+        })
+        .rpc(),
+    );
+  });
+
+  it('Reversed mint order', async () => {
+    const keys = derivePoolKeys(values.ammKey, values.mintBKeypair.publicKey, values.mintAKeypair.publicKey);
+    await expectRevert(
+      program.methods
+        .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
+        .accounts({
+          amm: values.ammKey,
+          mintA: values.mintBKeypair.publicKey,
+          mintB: values.mintAKeypair.publicKey,
+          tokenProgramA: TOKEN_PROGRAM_ID,
+          tokenProgramB: TOKEN_PROGRAM_ID,
+          ...keys,
+          pool: keys.poolKey,
+          poolRef: values.poolRef,
+          programConfig: values.programConfig,
+        })
+        .rpc(),
+    );
+  });
+
+  it('Multiple fee tiers', async () => {
+    for (const [index, feeTier] of [0, 30].entries()) {
+      const keys = derivePoolKeys(values.ammKey, values.mintAKeypair.publicKey, values.mintBKeypair.publicKey, feeTier);
+      await program.methods
+        .createPool(new BN(0), { constantProduct: {} }, feeTier, 24, null)
+        .accounts({
+          amm: values.ammKey,
+          mintA: values.mintAKeypair.publicKey,
+          mintB: values.mintBKeypair.publicKey,
+          tokenProgramA: TOKEN_PROGRAM_ID,
+          tokenProgramB: TOKEN_PROGRAM_ID,
+          ...keys,
+          pool: keys.poolKey,
+          poolRef: poolRefKey(values.ammKey, index),
+          programConfig: values.programConfig,
+        })
+        .rpc();
+
+      const pool = await program.account.pool.fetch(keys.poolKey);
+      expect(pool.feeTier).to.equal(feeTier);
+
+      // Every pool is recorded in the AMM's registry
+      const poolRef = await program.account.poolRef.fetch(poolRefKey(values.ammKey, index));
+      expect(poolRef.pool.toString()).to.equal(keys.poolKey.toString());
+      expect(poolRef.index.toNumber()).to.equal(index);
+    }
+
+    const amm = await program.account.amm.fetch(values.ammKey);
+    expect(amm.poolCount.toNumber()).to.equal(2);
+  });
+
+  it('Unsupported fee tier', async () => {
+    const keys = derivePoolKeys(values.ammKey, values.mintAKeypair.publicKey, values.mintBKeypair.publicKey, 7);
+    await expectRevert(
+      program.methods
+        .createPool(new BN(0), { constantProduct: {} }, 7, 24, null)
+        .accounts({
+          amm: values.ammKey,
+          mintA: values.mintAKeypair.publicKey,
+          mintB: values.mintBKeypair.publicKey,
+          tokenProgramA: TOKEN_PROGRAM_ID,
+          tokenProgramB: TOKEN_PROGRAM_ID,
+          ...keys,
+          pool: keys.poolKey,
+          poolRef: values.poolRef,
+          programConfig: values.programConfig,
         })
         .rpc(),
     );
   });
-});
\ No newline at end of file
+});
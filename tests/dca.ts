@@ -0,0 +1,164 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { PublicKey } from '@solana/web3.js';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type TestValues, createValues, denyAddress, expectRevert, mintingTokens } from './utils';
+
+describe('DCA schedules', () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+
+    await program.methods
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolRef: values.poolRef,
+        programConfig: values.programConfig,
+        poolOracle: values.poolOracle,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+      })
+      .rpc();
+
+    await program.methods
+      .depositLiquidity(values.depositAmountA, values.depositAmountB, new BN(0), null, null, false)
+      .accounts({
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc({ skipPreflight: true });
+  });
+
+  const id = 1;
+  const schedule = () =>
+    PublicKey.findProgramAddressSync(
+      [values.poolKey.toBuffer(), values.admin.publicKey.toBuffer(), new BN(id).toArrayLike(Buffer, 'le', 8), Buffer.from('dca')],
+      program.programId,
+    )[0];
+  const escrow = () => PublicKey.findProgramAddressSync([schedule().toBuffer(), Buffer.from('escrow')], program.programId)[0];
+
+  // Sell 3 * 10^4 B for A in three hourly installments
+  const createSchedule = () =>
+    program.methods
+      .createDcaSchedule(new BN(id), new BN(3 * 10 ** 4), new BN(10 ** 4), new BN(3600), new BN(0))
+      .accounts({
+        pool: values.poolKey,
+        schedule: schedule(),
+        escrow: escrow(),
+        owner: values.admin.publicKey,
+        mintIn: values.mintBKeypair.publicKey,
+        ownerAccountIn: values.holderAccountB,
+        tokenProgramIn: TOKEN_PROGRAM_ID,
+      })
+      .signers([values.admin])
+      .rpc();
+
+  // The admin cranks its own schedule so the tip lands back in its B account
+  const executeDca = (denylist: PublicKey | null = null) =>
+    program.methods
+      .executeDca()
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        schedule: schedule(),
+        escrow: escrow(),
+        owner: values.admin.publicKey,
+        ownerAccountOut: values.holderAccountA,
+        keeper: values.admin.publicKey,
+        keeperAccount: values.holderAccountB,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        denylist,
+      })
+      .signers([values.admin])
+      .rpc();
+
+  it('Execute one installment per interval', async () => {
+    await createSchedule();
+
+    const before = await connection.getTokenAccountBalance(values.holderAccountA);
+    await executeDca();
+    const after = await connection.getTokenAccountBalance(values.holderAccountA);
+    expect(Number(after.value.amount)).to.be.greaterThan(Number(before.value.amount));
+    expect((await connection.getTokenAccountBalance(escrow())).value.amount).to.equal(String(2 * 10 ** 4));
+
+    await expectRevert(executeDca());
+  });
+
+  it('Cancel refunds the remaining escrow', async () => {
+    const before = await connection.getTokenAccountBalance(values.holderAccountB);
+    await createSchedule();
+
+    await program.methods
+      .cancelDcaSchedule()
+      .accounts({
+        schedule: schedule(),
+        escrow: escrow(),
+        owner: values.admin.publicKey,
+        mintIn: values.mintBKeypair.publicKey,
+        ownerAccountIn: values.holderAccountB,
+        tokenProgramIn: TOKEN_PROGRAM_ID,
+      })
+      .signers([values.admin])
+      .rpc();
+
+    const after = await connection.getTokenAccountBalance(values.holderAccountB);
+    expect(after.value.amount).to.equal(before.value.amount);
+    expect(await connection.getAccountInfo(schedule())).to.be.null;
+  });
+
+  it('Schedules of denied owners cannot be executed', async () => {
+    await createSchedule();
+    const denylist = await denyAddress(program, values, values.admin.publicKey);
+    await expectRevert(executeDca(denylist));
+    expect((await connection.getTokenAccountBalance(escrow())).value.amount).to.equal(String(3 * 10 ** 4));
+  });
+});
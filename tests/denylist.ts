@@ -0,0 +1,196 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { Keypair, PublicKey } from '@solana/web3.js';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type TestValues, createValues, expectRevert, mintingTokens, swapOptions } from './utils';
+
+describe('Denylist', () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+
+    await program.methods
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolRef: values.poolRef,
+        programConfig: values.programConfig,
+        poolOracle: values.poolOracle,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+      })
+      .rpc();
+
+    await program.methods
+      .depositLiquidity(values.depositAmountA, values.depositAmountB, new BN(0), null, null, false)
+      .accounts({
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc({ skipPreflight: true });
+  });
+
+  let denylistCreated: boolean;
+
+  const denylistKey = () => PublicKey.findProgramAddressSync([values.ammKey.toBuffer(), Buffer.from('denylist')], program.programId)[0];
+
+  // The denylist account is only passed once the AMM has one
+  const denylist = () => (denylistCreated ? denylistKey() : null);
+
+  const createDenylist = async () => {
+    await program.methods
+      .createDenylist()
+      .accounts({ amm: values.ammKey, denylist: denylistKey(), admin: values.admin.publicKey })
+      .signers([values.admin])
+      .rpc();
+    denylistCreated = true;
+  };
+
+  const updateDenylist = (denied: boolean, address: PublicKey, admin = values.admin) =>
+    (denied ? program.methods.addToDenylist(address) : program.methods.removeFromDenylist(address))
+      .accounts({ amm: values.ammKey, denylist: denylistKey(), admin: admin.publicKey })
+      .signers([admin])
+      .rpc();
+
+  const swap = () =>
+    program.methods
+      .swapExactTokensForTokens(values.mintBKeypair.publicKey, new BN(10 ** 4), new BN(0), null, swapOptions())
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        trader: values.admin.publicKey,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+        traderAccountA: values.holderAccountA,
+        traderAccountB: values.holderAccountB,
+        denylist: denylist(),
+      })
+      .signers([values.admin])
+      .rpc();
+
+  const liquidityAccounts = () => ({
+    amm: values.ammKey,
+    pool: values.poolKey,
+    poolAuthority: values.poolAuthority,
+    depositor: values.admin.publicKey,
+    mintLiquidity: values.mintLiquidity,
+    mintA: values.mintAKeypair.publicKey,
+    mintB: values.mintBKeypair.publicKey,
+    tokenProgramA: TOKEN_PROGRAM_ID,
+    tokenProgramB: TOKEN_PROGRAM_ID,
+    poolAccountA: values.poolAccountA,
+    poolAccountB: values.poolAccountB,
+    depositorAccountLiquidity: values.liquidityAccount,
+    depositorAccountA: values.holderAccountA,
+    depositorAccountB: values.holderAccountB,
+    denylist: denylist(),
+  });
+
+  const deposit = (amm = values.ammKey) =>
+    program.methods
+      .depositLiquidity(new BN(10 ** 5), new BN(10 ** 5), new BN(0), null, null, false)
+      .accounts({ ...liquidityAccounts(), amm })
+      .signers([values.admin])
+      .rpc();
+
+  const withdraw = () =>
+    program.methods
+      .withdrawLiquidity(new BN(10 ** 5), new BN(0), new BN(0), null)
+      .accounts(liquidityAccounts())
+      .signers([values.admin])
+      .rpc();
+
+  beforeEach(() => {
+    denylistCreated = false;
+  });
+
+  it('Denied addresses cannot swap or deposit', async () => {
+    await createDenylist();
+    await swap();
+
+    await updateDenylist(true, values.admin.publicKey);
+    expect((await program.account.denylist.fetch(denylistKey())).addresses.map((address) => address.toString())).to.deep.equal([
+      values.admin.publicKey.toString(),
+    ]);
+    await expectRevert(swap());
+    await expectRevert(deposit());
+
+    // Withdrawals are not checked
+    await withdraw();
+
+    await updateDenylist(false, values.admin.publicKey);
+    await swap();
+    await deposit();
+  });
+
+  it('Denied addresses cannot deposit through a foreign AMM', async () => {
+    await createDenylist();
+    await updateDenylist(true, values.admin.publicKey);
+
+    // Another AMM without a denylist cannot stand in for the pool's AMM
+    const foreign = createValues();
+    await program.methods.createAmm(foreign.id, 0).accounts({ amm: foreign.ammKey, admin: foreign.admin.publicKey, programConfig: foreign.programConfig }).rpc();
+    denylistCreated = false;
+
+    await expectRevert(deposit(foreign.ammKey));
+  });
+
+  it('The denylist must be provided once created', async () => {
+    await createDenylist();
+    denylistCreated = false;
+    await expectRevert(swap());
+  });
+
+  it('Only the owner or the risk manager can manage it', async () => {
+    await createDenylist();
+    await expectRevert(updateDenylist(true, Keypair.generate().publicKey, Keypair.generate()));
+    await expectRevert(updateDenylist(false, Keypair.generate().publicKey));
+  });
+});
@@ -0,0 +1,109 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { Keypair } from '@solana/web3.js';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type TestValues, createValues, expectRevert, mintingTokens } from './utils';
+
+describe('Deposit cap', () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+
+    // The provider wallet pays for the pool and becomes its creator
+    await program.methods
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolRef: values.poolRef,
+        programConfig: values.programConfig,
+        poolOracle: values.poolOracle,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+      })
+      .rpc();
+  });
+
+  const setDepositCap = (maxReserveA: BN, maxReserveB: BN, authority?: Keypair) =>
+    program.methods
+      .setDepositCap(maxReserveA, maxReserveB)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        authority: authority ? authority.publicKey : provider.wallet.publicKey,
+      })
+      .signers(authority ? [authority] : [])
+      .rpc();
+
+  const deposit = () =>
+    program.methods
+      .depositLiquidity(values.depositAmountA, values.depositAmountB, new BN(0), null, null, false)
+      .accounts({
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc();
+
+  it('Creator sets the cap', async () => {
+    await setDepositCap(values.depositAmountA, new BN(0));
+
+    const pool = await program.account.pool.fetch(values.poolKey);
+    expect(pool.creator.toString()).to.equal(provider.wallet.publicKey.toString());
+    expect(pool.maxReserveA.toString()).to.equal(values.depositAmountA.toString());
+
+    // The first deposit fills the cap exactly, the second one exceeds it
+    await deposit();
+    await expectRevert(deposit());
+  });
+
+  it('Admin lifts the cap', async () => {
+    await setDepositCap(new BN(1), new BN(1), values.admin);
+    await expectRevert(deposit());
+
+    await setDepositCap(new BN(0), new BN(0), values.admin);
+    await deposit();
+  });
+
+  it('Neither the creator nor the admin', async () => {
+    await expectRevert(setDepositCap(new BN(1), new BN(1), Keypair.generate()));
+  });
+});
@@ -1,8 +1,10 @@
 import * as anchor from '@coral-xyz/anchor';
 import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_PROGRAM_ID, transfer } from '@solana/spl-token';
+import { BN } from 'bn.js';
 import { expect } from 'chai';
 import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
-import { type TestValues, createValues, mintingTokens } from './utils';
+import { type TestValues, createValues, expectRevert, mintingTokens, swapOptions } from './utils';
 
 describe('Deposit liquidity', () => {
   const provider = anchor.AnchorProvider.env();
@@ -16,7 +18,7 @@ describe('Deposit liquidity', () => {
   beforeEach(async () => {
     values = createValues();
 
-    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey }).rpc();
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
 
     await mintingTokens({
       connection,
@@ -26,23 +28,30 @@ describe('Deposit liquidity', () => {
     });
 
     await program.methods
-      .createPool()
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
       .accounts({
         amm: values.ammKey,
         pool: values.poolKey,
+        poolRef: values.poolRef,
+        programConfig: values.programConfig,
+        poolOracle: values.poolOracle,
         poolAuthority: values.poolAuthority,
         mintLiquidity: values.mintLiquidity,
         mintA: values.mintAKeypair.publicKey,
         mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
         poolAccountA: values.poolAccountA,
         poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
       })
       .rpc();
   });
 
   it('Deposit equal amounts', async () => {
     await program.methods
-      .depositLiquidity(values.depositAmountA, values.depositAmountA)
+      .depositLiquidity(values.depositAmountA, values.depositAmountA, new BN(0), null, null, false)
       .accounts({
         pool: values.poolKey,
         poolAuthority: values.poolAuthority,
@@ -50,6 +59,8 @@ describe('Deposit liquidity', () => {
         mintLiquidity: values.mintLiquidity,
         mintA: values.mintAKeypair.publicKey,
         mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
         poolAccountA: values.poolAccountA,
         poolAccountB: values.poolAccountB,
         depositorAccountLiquidity: values.liquidityAccount,
@@ -66,4 +77,149 @@ describe('Deposit liquidity', () => {
     const depositTokenAccountB = await connection.getTokenAccountBalance(values.holderAccountB);
     expect(depositTokenAccountB.value.amount).to.equal(values.defaultSupply.sub(values.depositAmountA).toString());
   });
-});
\ No newline at end of file
+
+  it('Minimum liquidity output not met', async () => {
+    await expectRevert(
+      program.methods
+        .depositLiquidity(values.depositAmountA, values.depositAmountA, values.depositAmountA, null, null, false)
+        .accounts({
+          pool: values.poolKey,
+          poolAuthority: values.poolAuthority,
+          depositor: values.admin.publicKey,
+          mintLiquidity: values.mintLiquidity,
+          mintA: values.mintAKeypair.publicKey,
+          mintB: values.mintBKeypair.publicKey,
+          tokenProgramA: TOKEN_PROGRAM_ID,
+          tokenProgramB: TOKEN_PROGRAM_ID,
+          poolAccountA: values.poolAccountA,
+          poolAccountB: values.poolAccountB,
+          depositorAccountLiquidity: values.liquidityAccount,
+          depositorAccountA: values.holderAccountA,
+          depositorAccountB: values.holderAccountB,
+        })
+        .signers([values.admin])
+        .rpc(),
+    );
+  });
+
+  const deposit = (amountA: anchor.BN, amountB: anchor.BN, maxRatioDeviationBps: number | null = null, clampToBalance = false) =>
+    program.methods
+      .depositLiquidity(amountA, amountB, new BN(0), null, maxRatioDeviationBps, clampToBalance)
+      .accounts({
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc();
+
+  it('First deposit below the floor', async () => {
+    // sqrt(500 * 500) is below the minimum initial liquidity
+    await expectRevert(deposit(new BN(500), new BN(500)));
+  });
+
+  it('Donations do not skip the first deposit lock', async () => {
+    // Send dust straight to the pool before anyone deposits
+    await transfer(connection, values.admin, values.holderAccountA, values.poolAccountA, values.admin, 1);
+
+    await deposit(values.depositAmountA, values.depositAmountA);
+
+    const liquidity = await connection.getTokenAccountBalance(values.liquidityAccount);
+    expect(liquidity.value.amount).to.equal(values.depositAmountA.sub(values.minimumLiquidity).toString());
+  });
+
+  it('Ratio outside the tolerance', async () => {
+    await deposit(values.depositAmountA, values.depositAmountA);
+
+    // 2% off the 1:1 pool ratio
+    const skewed = values.depositAmountA.muln(102).divn(100);
+    await expectRevert(deposit(skewed, values.depositAmountA, 100));
+
+    await deposit(skewed, values.depositAmountA, 300);
+  });
+
+  it('Amounts above the balance are rejected unless clamping', async () => {
+    const aboveBalance = values.defaultSupply.muln(2);
+    try {
+      await deposit(aboveBalance, aboveBalance);
+      expect.fail('deposit above the balance should revert');
+    } catch (error) {
+      expect((error as anchor.AnchorError).error.errorCode.code).to.equal('InsufficientInputBalance');
+    }
+
+    await deposit(aboveBalance, aboveBalance, null, true);
+    const liquidity = await connection.getTokenAccountBalance(values.liquidityAccount);
+    expect(liquidity.value.amount).to.equal(values.defaultSupply.sub(values.minimumLiquidity).toString());
+  });
+
+  const balance = async (account: anchor.web3.PublicKey) => new BN((await connection.getTokenAccountBalance(account)).value.amount);
+
+  it('Depositing after fees accrue and withdrawing again is not profitable', async () => {
+    await deposit(values.depositAmountA, values.depositAmountB);
+
+    // Swaps leave their fees in the pool, growing the value of every LP token
+    await program.methods
+      .swapExactTokensForTokens(values.mintAKeypair.publicKey, new BN(10 ** 6), new BN(0), null, swapOptions())
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        trader: values.admin.publicKey,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+        traderAccountA: values.holderAccountA,
+        traderAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc();
+
+    const [beforeA, beforeB, beforeLiquidity] = await Promise.all([
+      balance(values.holderAccountA),
+      balance(values.holderAccountB),
+      balance(values.liquidityAccount),
+    ]);
+    await deposit(values.depositAmountA, values.depositAmountB);
+    const minted = (await balance(values.liquidityAccount)).sub(beforeLiquidity);
+
+    await program.methods
+      .withdrawLiquidity(minted, new BN(0), new BN(0), null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc();
+
+    // The fees earned before the deposit stay with the earlier LPs
+    expect((await balance(values.holderAccountA)).lte(beforeA)).to.be.true;
+    expect((await balance(values.holderAccountB)).lte(beforeB)).to.be.true;
+  });
+});
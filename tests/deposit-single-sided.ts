@@ -0,0 +1,131 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type TestValues, createValues, expectRevert, mintingTokens } from './utils';
+
+describe('Deposit single sided', () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+
+    await program.methods
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolRef: values.poolRef,
+        programConfig: values.programConfig,
+        poolOracle: values.poolOracle,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+      })
+      .rpc();
+
+    await program.methods
+      .depositLiquidity(values.depositAmountA, values.depositAmountB, new BN(0), null, null, false)
+      .accounts({
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc({ skipPreflight: true });
+  });
+
+  const depositSingleSided = (
+    depositA: boolean,
+    amount: anchor.BN,
+    depositorAccount: anchor.web3.PublicKey,
+    minLpTokensOut = new BN(0),
+    clampToBalance = false,
+  ) =>
+    program.methods
+      .depositSingleSided(depositA, amount, minLpTokensOut, null, clampToBalance)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccount,
+      })
+      .signers([values.admin])
+      .rpc();
+
+  it('Deposit only token A', async () => {
+    const amount = new BN(10 ** 6);
+    const liquidityBefore = await connection.getTokenAccountBalance(values.liquidityAccount);
+    await depositSingleSided(true, amount, values.holderAccountA);
+
+    const traderTokenAccountA = await connection.getTokenAccountBalance(values.holderAccountA);
+    const traderTokenAccountB = await connection.getTokenAccountBalance(values.holderAccountB);
+    const liquidityAfter = await connection.getTokenAccountBalance(values.liquidityAccount);
+    expect(traderTokenAccountA.value.amount).to.equal(values.defaultSupply.sub(values.depositAmountA).sub(amount).toString());
+    expect(traderTokenAccountB.value.amount).to.equal(values.defaultSupply.sub(values.depositAmountB).toString());
+    expect(Number(liquidityAfter.value.amount)).to.be.greaterThan(Number(liquidityBefore.value.amount));
+  });
+
+  it('Deposit only token B', async () => {
+    const amount = new BN(10 ** 6);
+    await depositSingleSided(false, amount, values.holderAccountB);
+
+    const traderTokenAccountB = await connection.getTokenAccountBalance(values.holderAccountB);
+    expect(traderTokenAccountB.value.amount).to.equal(values.defaultSupply.sub(values.depositAmountB).sub(amount).toString());
+  });
+
+  it('Source account does not match the deposited side', async () => {
+    await expectRevert(depositSingleSided(true, new BN(10 ** 6), values.holderAccountB));
+  });
+
+  it('Minimum liquidity output not met', async () => {
+    await expectRevert(depositSingleSided(true, new BN(10 ** 6), values.holderAccountA, new BN(10 ** 6)));
+  });
+
+  it('Amount above the balance is rejected without clamping', async () => {
+    await expectRevert(depositSingleSided(true, values.defaultSupply, values.holderAccountA));
+  });
+});
@@ -0,0 +1,175 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { Keypair } from '@solana/web3.js';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type TestValues, createValues, expectRevert, mintingTokens, swapOptions } from './utils';
+
+describe('Exit-only mode', () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+
+    await program.methods
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolRef: values.poolRef,
+        programConfig: values.programConfig,
+        poolOracle: values.poolOracle,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+      })
+      .rpc();
+
+    await program.methods
+      .depositLiquidity(values.depositAmountA, values.depositAmountB, new BN(0), null, null, false)
+      .accounts({
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc({ skipPreflight: true });
+  });
+
+  const setExitOnly = (exitOnly: boolean, admin = values.admin) =>
+    program.methods
+      .setExitOnly(exitOnly)
+      .accounts({ amm: values.ammKey, admin: admin.publicKey })
+      .signers([admin])
+      .rpc();
+
+  const swap = () =>
+    program.methods
+      .swapExactTokensForTokens(values.mintBKeypair.publicKey, new BN(10 ** 4), new BN(0), null, swapOptions())
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        trader: values.admin.publicKey,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+        traderAccountA: values.holderAccountA,
+        traderAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc();
+
+  const liquidityAccounts = () => ({
+    amm: values.ammKey,
+    pool: values.poolKey,
+    poolAuthority: values.poolAuthority,
+    depositor: values.admin.publicKey,
+    mintLiquidity: values.mintLiquidity,
+    mintA: values.mintAKeypair.publicKey,
+    mintB: values.mintBKeypair.publicKey,
+    tokenProgramA: TOKEN_PROGRAM_ID,
+    tokenProgramB: TOKEN_PROGRAM_ID,
+    poolAccountA: values.poolAccountA,
+    poolAccountB: values.poolAccountB,
+    depositorAccountLiquidity: values.liquidityAccount,
+    depositorAccountA: values.holderAccountA,
+    depositorAccountB: values.holderAccountB,
+  });
+
+  const deposit = (amm = values.ammKey) =>
+    program.methods
+      .depositLiquidity(new BN(10 ** 5), new BN(10 ** 5), new BN(0), null, null, false)
+      .accounts({ ...liquidityAccounts(), amm })
+      .signers([values.admin])
+      .rpc();
+
+  const withdraw = () =>
+    program.methods
+      .withdrawLiquidity(new BN(10 ** 5), new BN(0), new BN(0), null)
+      .accounts(liquidityAccounts())
+      .signers([values.admin])
+      .rpc();
+
+  it('Blocks swaps and deposits but not withdrawals', async () => {
+    await setExitOnly(true);
+    expect((await program.account.amm.fetch(values.ammKey)).exitOnly).to.equal(true);
+
+    await expectRevert(swap());
+    await expectRevert(deposit());
+
+    const before = await connection.getTokenAccountBalance(values.liquidityAccount);
+    await withdraw();
+    const after = await connection.getTokenAccountBalance(values.liquidityAccount);
+    expect(Number(after.value.amount)).to.equal(Number(before.value.amount) - 10 ** 5);
+  });
+
+  it('Deposits cannot pass a foreign AMM', async () => {
+    await setExitOnly(true);
+
+    // Another AMM that is not exit-only cannot stand in for the pool's AMM
+    const foreign = createValues();
+    await program.methods.createAmm(foreign.id, 0).accounts({ amm: foreign.ammKey, admin: foreign.admin.publicKey, programConfig: foreign.programConfig }).rpc();
+
+    await expectRevert(deposit(foreign.ammKey));
+  });
+
+  it('Turning it off restores trading', async () => {
+    await setExitOnly(true);
+    await setExitOnly(false);
+
+    await swap();
+    await deposit();
+  });
+
+  it('Only the owner or the pauser can switch it', async () => {
+    await expectRevert(setExitOnly(true, Keypair.generate()));
+
+    const pauser = Keypair.generate();
+    await program.methods
+      .grantRole({ pauser: {} }, pauser.publicKey)
+      .accounts({ amm: values.ammKey, admin: values.admin.publicKey })
+      .signers([values.admin])
+      .rpc();
+    await setExitOnly(true, pauser);
+  });
+});
@@ -0,0 +1,267 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { PublicKey } from '@solana/web3.js';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type TestValues, createValues, expectRevert, mintingTokens } from './utils';
+
+describe('Farming', () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+
+    await program.methods
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolRef: values.poolRef,
+        programConfig: values.programConfig,
+        poolOracle: values.poolOracle,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+      })
+      .rpc();
+
+    await program.methods
+      .depositLiquidity(values.depositAmountA, values.depositAmountB, new BN(0), null, null, false)
+      .accounts({
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc();
+  });
+
+  const farmKey = () =>
+    PublicKey.findProgramAddressSync([values.poolKey.toBuffer(), Buffer.from('farm')], program.programId)[0];
+
+  const lpVault = () =>
+    PublicKey.findProgramAddressSync([farmKey().toBuffer(), Buffer.from('escrow')], program.programId)[0];
+
+  const rewardVault = (mint = values.mintAKeypair.publicKey) =>
+    PublicKey.findProgramAddressSync(
+      [farmKey().toBuffer(), mint.toBuffer(), Buffer.from('reward_vault')],
+      program.programId,
+    )[0];
+
+  const positionKey = () =>
+    PublicKey.findProgramAddressSync(
+      [farmKey().toBuffer(), values.admin.publicKey.toBuffer(), Buffer.from('stake_position')],
+      program.programId,
+    )[0];
+
+  const wait = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+  // Token A doubles as the reward token
+  beforeEach(async () => {
+    await program.methods
+      .createFarm()
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        farm: farmKey(),
+        mintLiquidity: values.mintLiquidity,
+        lpVault: lpVault(),
+        rewardMint: values.mintAKeypair.publicKey,
+        rewardVault: rewardVault(),
+        admin: values.admin.publicKey,
+        tokenProgramReward: TOKEN_PROGRAM_ID,
+      })
+      .signers([values.admin])
+      .rpc();
+  });
+
+  const rewards = () => [
+    { mint: values.mintAKeypair.publicKey, account: values.holderAccountA },
+    { mint: values.mintBKeypair.publicKey, account: values.holderAccountB },
+  ];
+
+  const fundFarm = (emissionRate: number, duration: number, rewardIndex = 0) =>
+    program.methods
+      .fundFarm(rewardIndex, new BN(emissionRate), new BN(duration))
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        farm: farmKey(),
+        rewardMint: rewards()[rewardIndex].mint,
+        rewardVault: rewardVault(rewards()[rewardIndex].mint),
+        admin: values.admin.publicKey,
+        adminRewardAccount: rewards()[rewardIndex].account,
+        tokenProgramReward: TOKEN_PROGRAM_ID,
+      })
+      .signers([values.admin])
+      .rpc();
+
+  const stakeLp = (amount: number) =>
+    program.methods
+      .stakeLp(new BN(amount))
+      .accounts({
+        farm: farmKey(),
+        lpVault: lpVault(),
+        mintLiquidity: values.mintLiquidity,
+        position: positionKey(),
+        owner: values.admin.publicKey,
+        ownerAccountLiquidity: values.liquidityAccount,
+        lock: null,
+      })
+      .signers([values.admin])
+      .rpc();
+
+  const unstakeLp = (amount: number) =>
+    program.methods
+      .unstakeLp(new BN(amount))
+      .accounts({
+        farm: farmKey(),
+        lpVault: lpVault(),
+        mintLiquidity: values.mintLiquidity,
+        position: positionKey(),
+        owner: values.admin.publicKey,
+        ownerAccountLiquidity: values.liquidityAccount,
+        lock: null,
+      })
+      .signers([values.admin])
+      .rpc();
+
+  const claimRewards = (rewardIndex = 0) =>
+    program.methods
+      .claimRewards(rewardIndex)
+      .accounts({
+        farm: farmKey(),
+        position: positionKey(),
+        rewardMint: rewards()[rewardIndex].mint,
+        rewardVault: rewardVault(rewards()[rewardIndex].mint),
+        owner: values.admin.publicKey,
+        ownerRewardAccount: rewards()[rewardIndex].account,
+        tokenProgramReward: TOKEN_PROGRAM_ID,
+      })
+      .signers([values.admin])
+      .rpc();
+
+  it('Fund farm', async () => {
+    await fundFarm(100, 3600);
+
+    const vault = await connection.getTokenAccountBalance(rewardVault());
+    expect(vault.value.amount).to.equal(String(100 * 3600));
+
+    const farm = await program.account.farm.fetch(farmKey());
+    expect(farm.reward.emissionRate.toNumber()).to.equal(100);
+
+    // Cannot restart an emission that is still running
+    await expectRevert(fundFarm(100, 3600));
+  });
+
+  it('Invalid emission', async () => {
+    await expectRevert(fundFarm(0, 3600));
+    await expectRevert(fundFarm(100, 0));
+  });
+
+  it('Stake, claim and unstake', async () => {
+    await fundFarm(100, 3600);
+    await stakeLp(10 ** 6);
+
+    let farm = await program.account.farm.fetch(farmKey());
+    expect(farm.totalStaked.toNumber()).to.equal(10 ** 6);
+    expect(farm.totalWeight.toNumber()).to.equal(10 ** 6);
+
+    await wait(2000);
+
+    const before = await connection.getTokenAccountBalance(values.holderAccountA);
+    await claimRewards();
+    const after = await connection.getTokenAccountBalance(values.holderAccountA);
+    expect(Number(after.value.amount)).to.be.greaterThan(Number(before.value.amount));
+
+    await unstakeLp(10 ** 6);
+    farm = await program.account.farm.fetch(farmKey());
+    expect(farm.totalStaked.toNumber()).to.equal(0);
+
+    const position = await program.account.stakePosition.fetch(positionKey());
+    expect(position.amount.toNumber()).to.equal(0);
+  });
+
+  const addFarmReward = (mint: PublicKey) =>
+    program.methods
+      .addFarmReward()
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        farm: farmKey(),
+        rewardMint: mint,
+        rewardVault: rewardVault(mint),
+        admin: values.admin.publicKey,
+        tokenProgramReward: TOKEN_PROGRAM_ID,
+      })
+      .signers([values.admin])
+      .rpc();
+
+  it('Multiple reward tokens', async () => {
+    // Token B is streamed alongside token A with its own schedule
+    await addFarmReward(values.mintBKeypair.publicKey);
+    await fundFarm(100, 3600, 0);
+    await fundFarm(50, 7200, 1);
+
+    const farm = await program.account.farm.fetch(farmKey());
+    expect(farm.rewards[1].mint.toBase58()).to.equal(values.mintBKeypair.publicKey.toBase58());
+    expect(farm.rewards[1].emissionRate.toNumber()).to.equal(50);
+
+    await stakeLp(10 ** 6);
+    await wait(2000);
+
+    const beforeB = await connection.getTokenAccountBalance(values.holderAccountB);
+    await claimRewards(1);
+    const afterB = await connection.getTokenAccountBalance(values.holderAccountB);
+    expect(Number(afterB.value.amount)).to.be.greaterThan(Number(beforeB.value.amount));
+
+    // Claiming one reward leaves the other pending
+    const position = await program.account.stakePosition.fetch(positionKey());
+    expect(position.pendingRewards[1].toNumber()).to.equal(0);
+    expect(position.pendingRewards[0].toNumber()).to.be.greaterThan(0);
+  });
+
+  it('Duplicate or unknown reward', async () => {
+    await expectRevert(addFarmReward(values.mintAKeypair.publicKey));
+    await expectRevert(fundFarm(100, 3600, 1));
+  });
+
+  it('Unstake more than staked', async () => {
+    await stakeLp(10 ** 5);
+    await expectRevert(unstakeLp(10 ** 5 + 1));
+  });
+});
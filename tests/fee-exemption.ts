@@ -0,0 +1,150 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { PublicKey } from '@solana/web3.js';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type TestValues, createValues, expectRevert, mintingTokens, swapOptions } from './utils';
+
+describe('Fee exemption', () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+
+    await program.methods
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolRef: values.poolRef,
+        programConfig: values.programConfig,
+        poolOracle: values.poolOracle,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+      })
+      .rpc();
+
+    await program.methods
+      .depositLiquidity(values.depositAmountA, values.depositAmountB, new BN(0), null, null, false)
+      .accounts({
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc({ skipPreflight: true });
+  });
+
+  const feeExemption = () =>
+    PublicKey.findProgramAddressSync(
+      [values.ammKey.toBuffer(), values.admin.publicKey.toBuffer(), Buffer.from('fee_exemption')],
+      program.programId,
+    )[0];
+
+  const setFeeExemption = (discountBps: number) =>
+    program.methods
+      .setFeeExemption(discountBps)
+      .accounts({
+        amm: values.ammKey,
+        feeExemption: feeExemption(),
+        address: values.admin.publicKey,
+        admin: values.admin.publicKey,
+      })
+      .signers([values.admin])
+      .rpc();
+
+  it('Exempt trader swaps without fees', async () => {
+    await setFeeExemption(10000);
+
+    const input = new BN(10 ** 4);
+    const quote = await program.methods
+      .quoteSwap(values.mintBKeypair.publicKey, input)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+      })
+      .view();
+
+    const before = await connection.getTokenAccountBalance(values.holderAccountA);
+    await program.methods
+      .swapExactTokensForTokens(values.mintBKeypair.publicKey, input, new BN(0), null, swapOptions())
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        trader: values.admin.publicKey,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+        traderAccountA: values.holderAccountA,
+        traderAccountB: values.holderAccountB,
+        feeExemption: feeExemption(),
+      })
+      .signers([values.admin])
+      .rpc();
+    const after = await connection.getTokenAccountBalance(values.holderAccountA);
+
+    // The public quote still charges the pool fee
+    expect(Number(after.value.amount) - Number(before.value.amount)).to.be.greaterThan(quote.netOutputAmount.toNumber());
+  });
+
+  it('Invalid discount', async () => {
+    await expectRevert(setFeeExemption(10001));
+  });
+
+  it('Remove exemption', async () => {
+    await setFeeExemption(5000);
+    await program.methods
+      .removeFeeExemption()
+      .accounts({ amm: values.ammKey, feeExemption: feeExemption(), admin: values.admin.publicKey })
+      .signers([values.admin])
+      .rpc();
+
+    expect(await connection.getAccountInfo(feeExemption())).to.be.null;
+  });
+});
@@ -0,0 +1,145 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { Keypair } from '@solana/web3.js';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type TestValues, createValues, expectRevert, mintingTokens, swapOptions } from './utils';
+
+describe('Fee on output', () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+
+    await program.methods
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolRef: values.poolRef,
+        programConfig: values.programConfig,
+        poolOracle: values.poolOracle,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+      })
+      .rpc();
+
+    await program.methods
+      .depositLiquidity(values.depositAmountA, values.depositAmountB, new BN(0), null, null, false)
+      .accounts({
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc({ skipPreflight: true });
+  });
+
+  const updateFeeSide = (feeOnOutput: boolean, admin = values.admin) =>
+    program.methods.updateFeeSide(feeOnOutput).accounts({ amm: values.ammKey, admin: admin.publicKey }).signers([admin]).rpc();
+
+  const swapAccounts = () => ({
+    amm: values.ammKey,
+    pool: values.poolKey,
+    poolAuthority: values.poolAuthority,
+    trader: values.admin.publicKey,
+    mintA: values.mintAKeypair.publicKey,
+    mintB: values.mintBKeypair.publicKey,
+    tokenProgramA: TOKEN_PROGRAM_ID,
+    tokenProgramB: TOKEN_PROGRAM_ID,
+    poolAccountA: values.poolAccountA,
+    poolAccountB: values.poolAccountB,
+    protocolFeeAccountA: values.protocolFeeAccountA,
+    protocolFeeAccountB: values.protocolFeeAccountB,
+    traderAccountA: values.holderAccountA,
+    traderAccountB: values.holderAccountB,
+  });
+
+  const balance = async (account: anchor.web3.PublicKey) => new BN((await connection.getTokenAccountBalance(account)).value.amount);
+
+  it('Enable fee on output', async () => {
+    await updateFeeSide(true);
+
+    const amm = await program.account.amm.fetch(values.ammKey);
+    expect(amm.feeOnOutput).to.be.true;
+  });
+
+  it('Input goes to the pool in full', async () => {
+    await updateFeeSide(true);
+    const input = new BN(10 ** 5);
+
+    await program.methods
+      .swapExactTokensForTokens(values.mintAKeypair.publicKey, input, new BN(0), null, swapOptions())
+      .accounts(swapAccounts())
+      .signers([values.admin])
+      .rpc({ skipPreflight: true });
+
+    // The pool receives the whole input and the trader receives less than the fee-free curve output
+    const poolAccountA = await balance(values.poolAccountA);
+    expect(poolAccountA.toString()).to.equal(values.depositAmountA.add(input).toString());
+    const received = (await balance(values.holderAccountB)).sub(values.defaultSupply.sub(values.depositAmountB));
+    const curveOutput = values.depositAmountB.mul(input).div(values.depositAmountA.add(input));
+    expect(received.lt(curveOutput)).to.be.true;
+  });
+
+  it('Exact output keeps the fee in the output token', async () => {
+    await updateFeeSide(true);
+    const output = new BN(10 ** 5);
+    const traderABefore = await balance(values.holderAccountA);
+
+    await program.methods
+      .swapTokensForExactTokens(values.mintAKeypair.publicKey, output, values.depositAmountA, null, null)
+      .accounts(swapAccounts())
+      .signers([values.admin])
+      .rpc({ skipPreflight: true });
+
+    // The trader receives exactly the requested output and everything paid lands in the pool
+    const paid = traderABefore.sub(await balance(values.holderAccountA));
+    const poolAccountA = await balance(values.poolAccountA);
+    expect(poolAccountA.toString()).to.equal(values.depositAmountA.add(paid).toString());
+    const traderAccountB = await balance(values.holderAccountB);
+    expect(traderAccountB.toString()).to.equal(values.defaultSupply.sub(values.depositAmountB).add(output).toString());
+    // The pool pays out more than the trader receives, the difference stays as the fee
+    const poolAccountB = await balance(values.poolAccountB);
+    expect(values.depositAmountB.sub(poolAccountB).gte(output)).to.be.true;
+  });
+
+  it('Not the admin', async () => {
+    await expectRevert(updateFeeSide(true, Keypair.generate()));
+  });
+});
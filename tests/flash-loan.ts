@@ -0,0 +1,122 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { SYSVAR_INSTRUCTIONS_PUBKEY, Transaction } from '@solana/web3.js';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type TestValues, createValues, expectRevert, mintingTokens, setFeatureFlags } from './utils';
+
+describe('Flash loan', () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+
+    await program.methods
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolRef: values.poolRef,
+        programConfig: values.programConfig,
+        poolOracle: values.poolOracle,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+      })
+      .rpc();
+
+    await program.methods
+      .depositLiquidity(values.depositAmountA, values.depositAmountB, new BN(0), null, null, false)
+      .accounts({
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc({ skipPreflight: true });
+  });
+
+  const loanAccounts = () => ({
+    amm: values.ammKey,
+    pool: values.poolKey,
+    poolAuthority: values.poolAuthority,
+    mintA: values.mintAKeypair.publicKey,
+    mintB: values.mintBKeypair.publicKey,
+    poolAccountA: values.poolAccountA,
+    poolAccountB: values.poolAccountB,
+    borrower: values.admin.publicKey,
+    borrowerAccountA: values.holderAccountA,
+    borrowerAccountB: values.holderAccountB,
+    programConfig: values.programConfig,
+    instructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+    tokenProgramA: TOKEN_PROGRAM_ID,
+    tokenProgramB: TOKEN_PROGRAM_ID,
+  });
+
+  it('Borrow and repay in one transaction', async () => {
+    const amount = new BN(10 ** 6);
+    const borrow = await program.methods.flashBorrow(amount, new BN(0)).accounts(loanAccounts()).instruction();
+    const repay = await program.methods.flashRepay().accounts(loanAccounts()).instruction();
+    await provider.sendAndConfirm(new Transaction().add(borrow, repay), [values.admin]);
+
+    // The fee stays in the pool for the LPs
+    const fee = amount.muln(9).addn(9999).divn(10000);
+    const poolA = await connection.getTokenAccountBalance(values.poolAccountA);
+    expect(poolA.value.amount).to.equal(values.depositAmountA.add(fee).toString());
+  });
+
+  it('Borrow without repaying', async () => {
+    await expectRevert(
+      program.methods
+        .flashBorrow(new BN(10 ** 6), new BN(0))
+        .accounts(loanAccounts())
+        .signers([values.admin])
+        .rpc(),
+    );
+  });
+
+  it('Disabled by the program config', async () => {
+    await setFeatureFlags(program, { flashLoansEnabled: false });
+    try {
+      const borrow = await program.methods.flashBorrow(new BN(10 ** 6), new BN(0)).accounts(loanAccounts()).instruction();
+      const repay = await program.methods.flashRepay().accounts(loanAccounts()).instruction();
+      await expectRevert(provider.sendAndConfirm(new Transaction().add(borrow, repay), [values.admin]));
+    } finally {
+      await setFeatureFlags(program);
+    }
+  });
+});
@@ -0,0 +1,161 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { PublicKey } from '@solana/web3.js';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type TestValues, createValues, expectRevert, mintingTokens } from './utils';
+
+describe('Governance', () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+
+    await program.methods
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolRef: values.poolRef,
+        programConfig: values.programConfig,
+        poolOracle: values.poolOracle,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+      })
+      .rpc();
+
+    await program.methods
+      .depositLiquidity(values.depositAmountA, values.depositAmountB, new BN(0), null, null, false)
+      .accounts({
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc();
+  });
+
+  const DAY = 24 * 60 * 60;
+
+  const lockKey = () =>
+    PublicKey.findProgramAddressSync(
+      [values.poolKey.toBuffer(), values.admin.publicKey.toBuffer(), Buffer.from('liquidity_lock')],
+      program.programId,
+    )[0];
+
+  const escrowKey = () =>
+    PublicKey.findProgramAddressSync([lockKey().toBuffer(), Buffer.from('escrow')], program.programId)[0];
+
+  const proposalKey = (id: number) =>
+    PublicKey.findProgramAddressSync(
+      [values.poolKey.toBuffer(), values.admin.publicKey.toBuffer(), new BN(id).toArrayLike(Buffer, 'le', 8), Buffer.from('governance_proposal')],
+      program.programId,
+    )[0];
+
+  const voteRecordKey = (proposal: PublicKey) =>
+    PublicKey.findProgramAddressSync([proposal.toBuffer(), lockKey().toBuffer(), Buffer.from('vote_record')], program.programId)[0];
+
+  const lockLiquidity = (amount: number, duration: number) =>
+    program.methods
+      .lockLiquidity(new BN(amount), new BN(duration))
+      .accounts({
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        mintLiquidity: values.mintLiquidity,
+        lock: lockKey(),
+        escrow: escrowKey(),
+        owner: values.admin.publicKey,
+        ownerAccountLiquidity: values.liquidityAccount,
+      })
+      .signers([values.admin])
+      .rpc();
+
+  const propose = async (id: number, action: any) => {
+    const proposal = proposalKey(id);
+    await program.methods
+      .createGovernanceProposal(new BN(id), action)
+      .accounts({ pool: values.poolKey, mintLiquidity: values.mintLiquidity, lock: lockKey(), proposal, proposer: values.admin.publicKey })
+      .signers([values.admin])
+      .rpc();
+    return proposal;
+  };
+
+  const vote = (proposal: PublicKey, support: boolean) =>
+    program.methods
+      .castVote(support)
+      .accounts({ proposal, lock: lockKey(), voteRecord: voteRecordKey(proposal), voter: values.admin.publicKey })
+      .signers([values.admin])
+      .rpc();
+
+  const execute = (proposal: PublicKey) =>
+    program.methods.executeGovernanceProposal().accounts({ amm: values.ammKey, pool: values.poolKey, proposal }).rpc();
+
+  const depositCap = { depositCap: { maxReserveA: new BN(10 ** 9), maxReserveB: new BN(10 ** 9) } };
+
+  it('Locked liquidity votes once on a proposal', async () => {
+    await lockLiquidity(500_000, 7 * DAY);
+    const proposal = await propose(0, depositCap);
+
+    await vote(proposal, true);
+    await expectRevert(vote(proposal, false));
+
+    const account = await program.account.governanceProposal.fetch(proposal);
+    expect(account.votesFor.toNumber()).to.equal(500_000);
+    expect(account.votesAgainst.toNumber()).to.equal(0);
+    expect(account.quorum.toNumber()).to.be.greaterThan(0);
+
+    // Voting is still open
+    await expectRevert(execute(proposal));
+    expect((await program.account.pool.fetch(values.poolKey)).maxReserveA.toNumber()).to.equal(0);
+  });
+
+  it('Locks must outlast the vote', async () => {
+    await lockLiquidity(500_000, DAY);
+    const proposal = await propose(0, depositCap);
+    await expectRevert(vote(proposal, true));
+  });
+
+  it('Proposals need locked liquidity and a whitelisted action', async () => {
+    await expectRevert(propose(0, depositCap));
+
+    await lockLiquidity(500_000, 7 * DAY);
+    await expectRevert(propose(1, { curve: [{ stableSwap: { amp: new BN(0) } }] }));
+  });
+});
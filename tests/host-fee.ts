@@ -0,0 +1,137 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_PROGRAM_ID, getOrCreateAssociatedTokenAccount } from '@solana/spl-token';
+import { Keypair, type PublicKey } from '@solana/web3.js';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type TestValues, createValues, expectRevert, mintingTokens } from './utils';
+
+describe('Host fee', () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+  // The frontend hosting the swap
+  const host = Keypair.generate();
+  let hostAccountA: PublicKey;
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+
+    await program.methods
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolRef: values.poolRef,
+        programConfig: values.programConfig,
+        poolOracle: values.poolOracle,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+      })
+      .rpc();
+
+    await program.methods
+      .depositLiquidity(values.depositAmountA, values.depositAmountB, new BN(0), null, null, false)
+      .accounts({
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc({ skipPreflight: true });
+
+    hostAccountA = (await getOrCreateAssociatedTokenAccount(connection, values.admin, values.mintAKeypair.publicKey, host.publicKey)).address;
+  });
+
+  const updateMaxHostFee = (maxHostFeeBps: number, admin = values.admin) =>
+    program.methods.updateMaxHostFee(maxHostFeeBps).accounts({ amm: values.ammKey, admin: admin.publicKey }).signers([admin]).rpc();
+
+  const swapWithHostFee = (hostFeeBps: number, hostFeeAccount: PublicKey | null = hostAccountA) =>
+    program.methods
+      .swapExactTokensForTokensWithHostFee(values.mintAKeypair.publicKey, new BN(10 ** 6), new BN(100), null, hostFeeBps)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        trader: values.admin.publicKey,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+        traderAccountA: values.holderAccountA,
+        traderAccountB: values.holderAccountB,
+        hostFeeAccount,
+      })
+      .signers([values.admin])
+      .rpc();
+
+  it('Host receives the platform fee', async () => {
+    await updateMaxHostFee(50);
+    await swapWithHostFee(20);
+
+    // 0.2% of the input goes to the host, the rest is swapped
+    const hostBalance = await connection.getTokenAccountBalance(hostAccountA);
+    expect(hostBalance.value.amount).to.equal('2000');
+    const traderTokenAccountA = await connection.getTokenAccountBalance(values.holderAccountA);
+    expect(traderTokenAccountA.value.amount).to.equal(values.defaultSupply.sub(values.depositAmountA).sub(new BN(10 ** 6)).toString());
+  });
+
+  it('Fee above the cap', async () => {
+    await updateMaxHostFee(50);
+    await expectRevert(swapWithHostFee(51));
+  });
+
+  it('Host fees are disabled by default', async () => {
+    await expectRevert(swapWithHostFee(1));
+  });
+
+  it('Host fee account must hold the input token', async () => {
+    await updateMaxHostFee(50);
+    await expectRevert(swapWithHostFee(20, null));
+    await expectRevert(swapWithHostFee(20, values.holderAccountB));
+  });
+
+  it('Invalid cap', async () => {
+    await expectRevert(updateMaxHostFee(10000));
+  });
+
+  it('Not the admin', async () => {
+    await expectRevert(updateMaxHostFee(50, Keypair.generate()));
+  });
+});
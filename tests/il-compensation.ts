@@ -0,0 +1,176 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { PublicKey } from '@solana/web3.js';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type TestValues, createValues, expectRevert, mintingTokens, swapOptions } from './utils';
+
+describe('IL compensation', () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+  let ilCompensationAccountA: anchor.web3.PublicKey;
+  let ilCompensationAccountB: anchor.web3.PublicKey;
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+
+    // Half of every swap fee funds the compensation vault
+    await program.methods
+      .updateFeeConfig({
+        strategy: { fixed: {} },
+        minFeeBps: values.fee / 2,
+        maxFeeBps: values.fee * 2,
+        baseFeeBps: values.fee,
+        adjustmentFactor: 500,
+        protocolFeeBps: 0,
+        ilCompensationFeeBps: 5000,
+        feeAToBBps: null,
+        feeBToABps: null,
+      })
+      .accounts({ amm: values.ammKey, admin: values.admin.publicKey })
+      .signers([values.admin])
+      .rpc();
+
+    await program.methods
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolRef: values.poolRef,
+        programConfig: values.programConfig,
+        poolOracle: values.poolOracle,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+      })
+      .rpc();
+
+    await program.methods
+      .depositLiquidity(values.depositAmountA, values.depositAmountB, new BN(0), null, null, false)
+      .accounts({
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc({ skipPreflight: true });
+  
+    [ilCompensationAccountA, ilCompensationAccountB] = [values.mintAKeypair, values.mintBKeypair].map(
+      (mint) => PublicKey.findProgramAddressSync([values.poolAuthority.toBuffer(), mint.publicKey.toBuffer(), Buffer.from('il_compensation')], program.programId)[0],
+    );
+    await program.methods
+      .createIlCompensationVault()
+      .accounts({
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        ilCompensationAccountA,
+        ilCompensationAccountB,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+  });
+
+  const swapBForA = (input: anchor.BN, withVault = true) =>
+    program.methods
+      .swapExactTokensForTokens(values.mintBKeypair.publicKey, input, new BN(1), null, swapOptions())
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        trader: values.admin.publicKey,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+        ilCompensationAccountA: withVault ? ilCompensationAccountA : null,
+        ilCompensationAccountB: withVault ? ilCompensationAccountB : null,
+        traderAccountA: values.holderAccountA,
+        traderAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc();
+
+  it('Swap fees fund the vault', async () => {
+    const input = new BN(10 ** 5);
+    await swapBForA(input);
+
+    const expectedFee = input.muln(values.fee).divn(10000).divn(2);
+    const vault = await connection.getTokenAccountBalance(ilCompensationAccountB);
+    expect(vault.value.amount).to.equal(expectedFee.toString());
+  });
+
+  it('Swap without the vault', async () => {
+    await expectRevert(swapBForA(new BN(10 ** 5), false));
+  });
+
+  it('Registration needs 30 minutes of TWAP history', async () => {
+    await swapBForA(new BN(10 ** 5));
+
+    const ilPosition = PublicKey.findProgramAddressSync(
+      [values.poolKey.toBuffer(), values.admin.publicKey.toBuffer(), Buffer.from('il_position')],
+      program.programId,
+    )[0];
+    await expectRevert(
+      program.methods
+        .registerIlPosition(new BN(10 ** 5))
+        .accounts({
+          pool: values.poolKey,
+          poolAuthority: values.poolAuthority,
+          ilPosition,
+          escrow: PublicKey.findProgramAddressSync([ilPosition.toBuffer(), Buffer.from('escrow')], program.programId)[0],
+          owner: values.admin.publicKey,
+          mintLiquidity: values.mintLiquidity,
+          mintA: values.mintAKeypair.publicKey,
+          mintB: values.mintBKeypair.publicKey,
+          poolAccountA: values.poolAccountA,
+          poolAccountB: values.poolAccountB,
+          ownerAccountLiquidity: values.liquidityAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          tokenProgramA: TOKEN_PROGRAM_ID,
+          tokenProgramB: TOKEN_PROGRAM_ID,
+        })
+        .signers([values.admin])
+        .rpc(),
+    );
+  });
+});
@@ -0,0 +1,173 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { PublicKey } from '@solana/web3.js';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type TestValues, createValues, denyAddress, expectRevert, mintingTokens } from './utils';
+
+describe('Limit orders', () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+
+    await program.methods
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolRef: values.poolRef,
+        programConfig: values.programConfig,
+        poolOracle: values.poolOracle,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+      })
+      .rpc();
+
+    await program.methods
+      .depositLiquidity(values.depositAmountA, values.depositAmountB, new BN(0), null, null, false)
+      .accounts({
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc({ skipPreflight: true });
+  });
+
+  const orderKeys = (id: number) => {
+    const order = PublicKey.findProgramAddressSync(
+      [values.poolKey.toBuffer(), values.admin.publicKey.toBuffer(), new BN(id).toArrayLike(Buffer, 'le', 8), Buffer.from('limit_order')],
+      program.programId,
+    )[0];
+    const escrow = PublicKey.findProgramAddressSync([order.toBuffer(), Buffer.from('escrow')], program.programId)[0];
+    return { order, escrow };
+  };
+
+  // Sell B for A, the pool currently pays ~3.8 A per B after the 5% fee
+  const placeOrder = (id: number, minOutput: number) =>
+    program.methods
+      .placeLimitOrder(new BN(id), new BN(10 ** 4), new BN(minOutput))
+      .accounts({
+        pool: values.poolKey,
+        ...orderKeys(id),
+        owner: values.admin.publicKey,
+        mintIn: values.mintBKeypair.publicKey,
+        ownerAccountIn: values.holderAccountB,
+        tokenProgramIn: TOKEN_PROGRAM_ID,
+      })
+      .signers([values.admin])
+      .rpc();
+
+  const fillOrder = (id: number, denylist: PublicKey | null = null) =>
+    program.methods
+      .fillLimitOrder()
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        ...orderKeys(id),
+        owner: values.admin.publicKey,
+        ownerAccountOut: values.holderAccountA,
+        filler: provider.wallet.publicKey,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        denylist,
+      })
+      .rpc();
+
+  it('Place and cancel', async () => {
+    const before = await connection.getTokenAccountBalance(values.holderAccountB);
+    await placeOrder(1, 3 * 10 ** 4);
+
+    const { order, escrow } = orderKeys(1);
+    expect((await connection.getTokenAccountBalance(escrow)).value.amount).to.equal(String(10 ** 4));
+
+    await program.methods
+      .cancelLimitOrder()
+      .accounts({
+        order,
+        escrow,
+        owner: values.admin.publicKey,
+        mintIn: values.mintBKeypair.publicKey,
+        ownerAccountIn: values.holderAccountB,
+        tokenProgramIn: TOKEN_PROGRAM_ID,
+      })
+      .signers([values.admin])
+      .rpc();
+
+    const after = await connection.getTokenAccountBalance(values.holderAccountB);
+    expect(after.value.amount).to.equal(before.value.amount);
+    expect(await connection.getAccountInfo(order)).to.be.null;
+  });
+
+  it('Fill once the limit price is reached', async () => {
+    await placeOrder(2, 3 * 10 ** 4);
+
+    const before = await connection.getTokenAccountBalance(values.holderAccountA);
+    await fillOrder(2);
+
+    const after = await connection.getTokenAccountBalance(values.holderAccountA);
+    expect(Number(after.value.amount) - Number(before.value.amount)).to.be.greaterThanOrEqual(3 * 10 ** 4);
+    expect(await connection.getAccountInfo(orderKeys(2).order)).to.be.null;
+  });
+
+  it('Limit price not reached', async () => {
+    await placeOrder(3, 5 * 10 ** 4);
+    await expectRevert(fillOrder(3));
+  });
+
+  it('Orders of denied owners cannot be filled', async () => {
+    await placeOrder(4, 3 * 10 ** 4);
+    const denylist = await denyAddress(program, values, values.admin.publicKey);
+    await expectRevert(fillOrder(4, denylist));
+
+    await program.methods
+      .removeFromDenylist(values.admin.publicKey)
+      .accounts({ amm: values.ammKey, denylist, admin: values.admin.publicKey })
+      .signers([values.admin])
+      .rpc();
+    await fillOrder(4, denylist);
+  });
+});
@@ -0,0 +1,174 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { PublicKey } from '@solana/web3.js';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type TestValues, createValues, expectRevert, mintingTokens } from './utils';
+
+describe('Liquidity lock', () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+
+    await program.methods
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolRef: values.poolRef,
+        programConfig: values.programConfig,
+        poolOracle: values.poolOracle,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+      })
+      .rpc();
+
+    await program.methods
+      .depositLiquidity(values.depositAmountA, values.depositAmountB, new BN(0), null, null, false)
+      .accounts({
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc();
+  });
+
+  const MAX_LOCK_DURATION = 4 * 365 * 24 * 60 * 60;
+
+  const lockKey = () =>
+    PublicKey.findProgramAddressSync(
+      [values.poolKey.toBuffer(), values.admin.publicKey.toBuffer(), Buffer.from('liquidity_lock')],
+      program.programId,
+    )[0];
+
+  const escrowKey = () =>
+    PublicKey.findProgramAddressSync([lockKey().toBuffer(), Buffer.from('escrow')], program.programId)[0];
+
+  const lockLiquidity = (amount: number, duration: number) =>
+    program.methods
+      .lockLiquidity(new BN(amount), new BN(duration))
+      .accounts({
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        mintLiquidity: values.mintLiquidity,
+        lock: lockKey(),
+        escrow: escrowKey(),
+        owner: values.admin.publicKey,
+        ownerAccountLiquidity: values.liquidityAccount,
+      })
+      .signers([values.admin])
+      .rpc();
+
+  const getBalance = () =>
+    program.methods
+      .getLiquidityBalance()
+      .accounts({
+        pool: values.poolKey,
+        mintLiquidity: values.mintLiquidity,
+        owner: values.admin.publicKey,
+        ownerAccountLiquidity: values.liquidityAccount,
+        lock: lockKey(),
+      })
+      .view();
+
+  it('Lock liquidity', async () => {
+    const total = 2 * 10 ** 6 - values.minimumLiquidity.toNumber();
+    await lockLiquidity(10 ** 6, 3600);
+
+    const lock = await program.account.liquidityLock.fetch(lockKey());
+    expect(lock.amount.toNumber()).to.equal(10 ** 6);
+
+    const escrow = await connection.getTokenAccountBalance(escrowKey());
+    expect(escrow.value.amount).to.equal(String(10 ** 6));
+
+    const balance = await getBalance();
+    expect(balance.locked.toNumber()).to.equal(10 ** 6);
+    expect(balance.free.toNumber()).to.equal(total - 10 ** 6);
+    expect(balance.unlockAt.toNumber()).to.equal(lock.unlockAt.toNumber());
+  });
+
+  it('Relock adds to the lock', async () => {
+    await lockLiquidity(10 ** 5, 3600);
+    await lockLiquidity(10 ** 5, 60);
+
+    const lock = await program.account.liquidityLock.fetch(lockKey());
+    expect(lock.amount.toNumber()).to.equal(2 * 10 ** 5);
+  });
+
+  it('Invalid duration', async () => {
+    await expectRevert(lockLiquidity(10 ** 5, 0));
+    await expectRevert(lockLiquidity(10 ** 5, MAX_LOCK_DURATION + 1));
+  });
+
+  it('Longer locks earn a higher boost', async () => {
+    await lockLiquidity(10 ** 5, MAX_LOCK_DURATION);
+    const boosted = await getBalance();
+    // Close to the maximum 2.5x boost, decaying as expiry approaches
+    expect(boosted.boostBps).to.be.greaterThan(24990);
+    expect(boosted.boostBps).to.be.at.most(25000);
+  });
+
+  it('Short locks earn almost no boost', async () => {
+    await lockLiquidity(10 ** 5, 3600);
+    const balance = await getBalance();
+    expect(balance.boostBps).to.be.lessThan(10002);
+  });
+
+  it('Unlock before expiry', async () => {
+    await lockLiquidity(10 ** 5, 3600);
+
+    // The lock has not expired yet
+    await expectRevert(
+      program.methods
+        .unlockLiquidity()
+        .accounts({
+          lock: lockKey(),
+          escrow: escrowKey(),
+          mintLiquidity: values.mintLiquidity,
+          owner: values.admin.publicKey,
+          ownerAccountLiquidity: values.liquidityAccount,
+        })
+        .signers([values.admin])
+        .rpc(),
+    );
+  });
+});
@@ -0,0 +1,132 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_PROGRAM_ID, getAssociatedTokenAddressSync } from '@solana/spl-token';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type PoolKeys, type TestValues, createValues, derivePoolKeys, expectRevert, mintingTokens, poolRefKey } from './utils';
+
+describe('Migrate liquidity', () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+  let target: PoolKeys;
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+
+    // The old pool at the default fee tier and an empty pool at a new fee tier
+    target = derivePoolKeys(values.ammKey, values.mintAKeypair.publicKey, values.mintBKeypair.publicKey, 30);
+    const pools: [number, PoolKeys][] = [
+      [0, values],
+      [30, target],
+    ];
+    for (const [index, [feeTier, keys]] of pools.entries()) {
+      await program.methods
+        .createPool(new BN(0), { constantProduct: {} }, feeTier, 24, null)
+        .accounts({
+          amm: values.ammKey,
+          mintA: values.mintAKeypair.publicKey,
+          mintB: values.mintBKeypair.publicKey,
+          tokenProgramA: TOKEN_PROGRAM_ID,
+          tokenProgramB: TOKEN_PROGRAM_ID,
+          ...keys,
+          pool: keys.poolKey,
+          poolRef: poolRefKey(values.ammKey, index),
+          programConfig: values.programConfig,
+        })
+        .rpc();
+    }
+
+    await program.methods
+      .depositLiquidity(values.depositAmountA, values.depositAmountB, new BN(0), null, null, false)
+      .accounts({
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc({ skipPreflight: true });
+  });
+
+  const migrateLiquidity = (lpAmount: BN, minLpOut: BN, to: PoolKeys = target) =>
+    program.methods
+      .migrateLiquidity(lpAmount, minLpOut, null)
+      .accounts({
+        amm: values.ammKey,
+        fromPool: values.poolKey,
+        toPool: to.poolKey,
+        fromPoolAuthority: values.poolAuthority,
+        toPoolAuthority: to.poolAuthority,
+        depositor: values.admin.publicKey,
+        fromMintLiquidity: values.mintLiquidity,
+        toMintLiquidity: to.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        fromPoolAccountA: values.poolAccountA,
+        fromPoolAccountB: values.poolAccountB,
+        toPoolAccountA: to.poolAccountA,
+        toPoolAccountB: to.poolAccountB,
+        depositorAccountFromLiquidity: values.liquidityAccount,
+        depositorAccountToLiquidity: getAssociatedTokenAddressSync(to.mintLiquidity, values.admin.publicKey, true),
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+        fromDepositRecord: null,
+        toDepositRecord: null,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+      })
+      .signers([values.admin])
+      .rpc();
+
+  it('Moves liquidity into the new fee tier', async () => {
+    const before = await connection.getTokenAccountBalance(values.liquidityAccount);
+    const lpAmount = new BN(before.value.amount).divn(2);
+    await migrateLiquidity(lpAmount, new BN(1));
+
+    const after = await connection.getTokenAccountBalance(values.liquidityAccount);
+    expect(new BN(after.value.amount).eq(new BN(before.value.amount).sub(lpAmount))).to.be.true;
+
+    const migrated = await connection.getTokenAccountBalance(getAssociatedTokenAddressSync(target.mintLiquidity, values.admin.publicKey, true));
+    expect(Number(migrated.value.amount)).to.be.greaterThan(0);
+
+    // Both pools record their new reserves
+    const fromPool = await program.account.pool.fetch(values.poolKey);
+    const toPool = await program.account.pool.fetch(target.poolKey);
+    const fromBalance = await connection.getTokenAccountBalance(values.poolAccountA);
+    const toBalance = await connection.getTokenAccountBalance(target.poolAccountA);
+    expect(fromPool.reserveA.toString()).to.equal(fromBalance.value.amount);
+    expect(toPool.reserveA.toString()).to.equal(toBalance.value.amount);
+  });
+
+  it('Minimum liquidity out', async () => {
+    await expectRevert(migrateLiquidity(new BN(10 ** 5), new BN(10 ** 9)));
+  });
+
+  it('Same pool', async () => {
+    await expectRevert(migrateLiquidity(new BN(10 ** 5), new BN(0), values));
+  });
+});
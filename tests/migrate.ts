@@ -0,0 +1,82 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { Keypair } from '@solana/web3.js';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type TestValues, createValues, expectRevert, mintingTokens } from './utils';
+
+describe('Migrate', () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+
+    await program.methods
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolRef: values.poolRef,
+        programConfig: values.programConfig,
+        poolOracle: values.poolOracle,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+      })
+      .rpc();
+  });
+
+  it('New accounts are created at the current version', async () => {
+    const amm = await program.account.amm.fetch(values.ammKey);
+    expect(amm.version).to.equal(9);
+
+    const pool = await program.account.pool.fetch(values.poolKey);
+    expect(pool.version).to.equal(7);
+  });
+
+  it('Current accounts are already migrated', async () => {
+    await expectRevert(program.methods.migrateAmm().accounts({ amm: values.ammKey, admin: values.admin.publicKey }).rpc());
+    await expectRevert(
+      program.methods.migratePool().accounts({ amm: values.ammKey, pool: values.poolKey, admin: values.admin.publicKey }).rpc(),
+    );
+  });
+
+  it('Not the admin', async () => {
+    const notAdmin = Keypair.generate();
+
+    await expectRevert(
+      program.methods.migrateAmm().accounts({ amm: values.ammKey, admin: notAdmin.publicKey }).signers([notAdmin]).rpc(),
+    );
+    await expectRevert(
+      program.methods
+        .migratePool()
+        .accounts({ amm: values.ammKey, pool: values.poolKey, admin: notAdmin.publicKey })
+        .signers([notAdmin])
+        .rpc(),
+    );
+  });
+});
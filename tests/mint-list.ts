@@ -0,0 +1,122 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { Keypair, PublicKey } from '@solana/web3.js';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type TestValues, createValues, expectRevert, mintingTokens } from './utils';
+
+describe('Mint list', () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+  let mintList: PublicKey;
+
+  beforeEach(async () => {
+    values = createValues();
+    mintList = PublicKey.findProgramAddressSync([values.ammKey.toBuffer(), Buffer.from('mint_list')], program.programId)[0];
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+  });
+
+  const createMintList = (mode: { allowlist: {} } | { denylist: {} }, admin = values.admin) =>
+    program.methods
+      .createMintList(mode)
+      .accounts({ amm: values.ammKey, mintList, admin: admin.publicKey })
+      .signers([admin])
+      .rpc();
+
+  const addMint = (mint: PublicKey, admin = values.admin) =>
+    program.methods.addMintToList(mint).accounts({ amm: values.ammKey, mintList, admin: admin.publicKey }).signers([admin]).rpc();
+
+  const removeMint = (mint: PublicKey) =>
+    program.methods.removeMintFromList(mint).accounts({ amm: values.ammKey, mintList, admin: values.admin.publicKey }).signers([values.admin]).rpc();
+
+  const createPool = (list: PublicKey | null = mintList) =>
+    program.methods
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolRef: values.poolRef,
+        programConfig: values.programConfig,
+        poolOracle: values.poolOracle,
+        mintList: list,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+      })
+      .rpc();
+
+  it('Allowlist only accepts listed mints', async () => {
+    await createMintList({ allowlist: {} });
+    await addMint(values.mintAKeypair.publicKey);
+
+    const amm = await program.account.amm.fetch(values.ammKey);
+    expect(amm.mintList.toString()).to.equal(mintList.toString());
+    await expectRevert(createPool());
+
+    await addMint(values.mintBKeypair.publicKey);
+    const list = await program.account.mintList.fetch(mintList);
+    expect(list.mints.length).to.equal(2);
+    await createPool();
+  });
+
+  it('Denylist rejects listed mints', async () => {
+    await createMintList({ denylist: {} });
+    await addMint(values.mintBKeypair.publicKey);
+    await expectRevert(createPool());
+
+    await removeMint(values.mintBKeypair.publicKey);
+    await createPool();
+  });
+
+  it('Pools must provide the list once created', async () => {
+    await createMintList({ denylist: {} });
+
+    await expectRevert(createPool(null));
+  });
+
+  it('Closing the list lifts the restriction', async () => {
+    await createMintList({ allowlist: {} });
+    await program.methods.closeMintList().accounts({ amm: values.ammKey, mintList, admin: values.admin.publicKey }).signers([values.admin]).rpc();
+
+    const amm = await program.account.amm.fetch(values.ammKey);
+    expect(amm.mintList.toString()).to.equal(PublicKey.default.toString());
+    await createPool(null);
+  });
+
+  it('Duplicate and missing mints', async () => {
+    await createMintList({ allowlist: {} });
+    await addMint(values.mintAKeypair.publicKey);
+
+    await expectRevert(addMint(values.mintAKeypair.publicKey));
+    await expectRevert(removeMint(values.mintBKeypair.publicKey));
+  });
+
+  it('Not the admin', async () => {
+    await expectRevert(createMintList({ allowlist: {} }, Keypair.generate()));
+
+    await createMintList({ allowlist: {} });
+    await expectRevert(addMint(values.mintAKeypair.publicKey, Keypair.generate()));
+  });
+});
@@ -0,0 +1,84 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { Keypair } from '@solana/web3.js';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type TestValues, createValues, expectRevert, mintingTokens } from './utils';
+
+describe('Mint policy', () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+
+    // The test mints keep the creator as freeze authority
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+  });
+
+  const updateMintPolicy = (rejectFreezeAuthority: boolean, rejectTransferFee: boolean, admin = values.admin) =>
+    program.methods
+      .updateMintPolicy({ rejectFreezeAuthority, rejectTransferFee })
+      .accounts({ amm: values.ammKey, admin: admin.publicKey })
+      .signers([admin])
+      .rpc();
+
+  const createPool = () =>
+    program.methods
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolRef: values.poolRef,
+        programConfig: values.programConfig,
+        poolOracle: values.poolOracle,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+      })
+      .rpc();
+
+  it('Freeze authority allowed by default', async () => {
+    await createPool();
+  });
+
+  it('Reject mints with a freeze authority', async () => {
+    await updateMintPolicy(true, false);
+
+    const amm = await program.account.amm.fetch(values.ammKey);
+    expect(amm.mintPolicy.rejectFreezeAuthority).to.be.true;
+
+    await expectRevert(createPool());
+  });
+
+  it('Transfer fee policy ignores legacy mints', async () => {
+    await updateMintPolicy(false, true);
+
+    await createPool();
+  });
+
+  it('Not the admin', async () => {
+    await expectRevert(updateMintPolicy(true, true, Keypair.generate()));
+  });
+});
@@ -0,0 +1,93 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { NATIVE_MINT, TOKEN_PROGRAM_ID, createMint, getAssociatedTokenAddressSync, getOrCreateAssociatedTokenAccount, mintTo } from '@solana/spl-token';
+import { Keypair, LAMPORTS_PER_SOL, type PublicKey } from '@solana/web3.js';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type PoolKeys, type TestValues, createValues, derivePoolKeys, sortMints } from './utils';
+
+describe('Native SOL', () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+  let mintA: PublicKey;
+  let mintB: PublicKey;
+  let keys: PoolKeys;
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+
+    const signature = await connection.requestAirdrop(values.admin.publicKey, 10 * LAMPORTS_PER_SOL);
+    await connection.confirmTransaction({ signature, ...(await connection.getLatestBlockhash()) });
+
+    // Pair a fresh token with wrapped SOL, keeping the mints ordered
+    const token = await createMint(connection, values.admin, values.admin.publicKey, null, 9, Keypair.generate());
+    [mintA, mintB] = sortMints(token, NATIVE_MINT);
+    const tokenAccount = await getOrCreateAssociatedTokenAccount(connection, values.admin, token, values.admin.publicKey);
+    await mintTo(connection, values.admin, token, tokenAccount.address, values.admin, 100 * LAMPORTS_PER_SOL);
+    await getOrCreateAssociatedTokenAccount(connection, values.admin, NATIVE_MINT, values.admin.publicKey);
+
+    keys = derivePoolKeys(values.ammKey, mintA, mintB);
+    await program.methods
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
+      .accounts({ amm: values.ammKey, mintA, mintB, tokenProgramA: TOKEN_PROGRAM_ID, tokenProgramB: TOKEN_PROGRAM_ID, ...keys, pool: keys.poolKey, poolRef: values.poolRef, programConfig: values.programConfig })
+      .rpc();
+
+    await program.methods
+      .depositLiquidityNative(new BN(LAMPORTS_PER_SOL), new BN(LAMPORTS_PER_SOL), new BN(0), null, false)
+      .accounts({
+        pool: keys.poolKey,
+        poolAuthority: keys.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: keys.mintLiquidity,
+        mintA,
+        mintB,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: keys.poolAccountA,
+        poolAccountB: keys.poolAccountB,
+        depositorAccountLiquidity: getAssociatedTokenAddressSync(keys.mintLiquidity, values.admin.publicKey, true),
+        depositorAccountA: getAssociatedTokenAddressSync(mintA, values.admin.publicKey, true),
+        depositorAccountB: getAssociatedTokenAddressSync(mintB, values.admin.publicKey, true),
+      })
+      .signers([values.admin])
+      .rpc({ skipPreflight: true });
+  });
+
+  it('Swap native SOL for tokens', async () => {
+    const lamportsBefore = await connection.getBalance(values.admin.publicKey);
+
+    await program.methods
+      .swapExactTokensForTokensNative(NATIVE_MINT, new BN(LAMPORTS_PER_SOL / 10), new BN(1), null)
+      .accounts({
+        amm: values.ammKey,
+        pool: keys.poolKey,
+        poolAuthority: keys.poolAuthority,
+        trader: values.admin.publicKey,
+        mintA,
+        mintB,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: keys.poolAccountA,
+        poolAccountB: keys.poolAccountB,
+        protocolFeeAccountA: keys.protocolFeeAccountA,
+        protocolFeeAccountB: keys.protocolFeeAccountB,
+        traderAccountA: getAssociatedTokenAddressSync(mintA, values.admin.publicKey, true),
+        traderAccountB: getAssociatedTokenAddressSync(mintB, values.admin.publicKey, true),
+        payer: values.admin.publicKey,
+      })
+      .signers([values.admin])
+      .rpc({ skipPreflight: true });
+
+    // The wSOL account is closed and the trader paid in native lamports
+    expect(await connection.getAccountInfo(getAssociatedTokenAddressSync(NATIVE_MINT, values.admin.publicKey, true))).to.be.null;
+    expect(await connection.getBalance(values.admin.publicKey)).to.be.lessThan(lamportsBefore);
+  });
+});
@@ -0,0 +1,86 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type TestValues, createValues, expectRevert, mintingTokens } from './utils';
+
+// Size of the oracle header plus 24 bytes per observation
+const ORACLE_HEADER_LEN = 8 + 32 + 44;
+const OBSERVATION_LEN = 24;
+
+describe('Pool oracle', () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+  });
+
+  const createPool = (observationCount: number) =>
+    program.methods
+      .createPool(new BN(0), { constantProduct: {} }, 0, observationCount, null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolRef: values.poolRef,
+        programConfig: values.programConfig,
+        poolOracle: values.poolOracle,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+      })
+      .rpc();
+
+  const growPoolOracle = () => program.methods.growPoolOracle().accounts({ poolOracle: values.poolOracle }).rpc();
+
+  const oracleSize = async () => (await connection.getAccountInfo(values.poolOracle)).data.length;
+
+  it('Allocates the chosen window', async () => {
+    await createPool(128);
+
+    const poolOracle = await program.account.poolOracle.fetch(values.poolOracle);
+    expect(poolOracle.pool.toString()).to.equal(values.poolKey.toString());
+    expect(poolOracle.volatilityTracker.observationCount).to.equal(128);
+    expect(await oracleSize()).to.equal(ORACLE_HEADER_LEN + 128 * OBSERVATION_LEN);
+  });
+
+  it('Grows large windows past the allocation limit', async () => {
+    await createPool(1024);
+    expect(await oracleSize()).to.equal(10240);
+
+    await growPoolOracle();
+    await growPoolOracle();
+    expect(await oracleSize()).to.equal(ORACLE_HEADER_LEN + 1024 * OBSERVATION_LEN);
+
+    // Growing a fully allocated oracle is a no-op
+    await growPoolOracle();
+    expect(await oracleSize()).to.equal(ORACLE_HEADER_LEN + 1024 * OBSERVATION_LEN);
+  });
+
+  it('Unsupported window', async () => {
+    await expectRevert(createPool(50));
+  });
+});
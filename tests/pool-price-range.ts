@@ -0,0 +1,177 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type TestValues, createValues, expectRevert, mintingTokens, swapOptions } from './utils';
+
+describe('Pool price range', () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+
+  const Q64 = new BN(2).pow(new BN(64));
+
+  const createPool = (initialPrice: number) =>
+    program.methods
+      .createPool(new BN(initialPrice), { constantProduct: {} }, 0, 24, null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolRef: values.poolRef,
+        programConfig: values.programConfig,
+        poolOracle: values.poolOracle,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+      })
+      .rpc();
+
+  const deposit = () =>
+    // One A is worth 4 B, matching the initial price
+    program.methods
+      .depositLiquidity(new BN(10 ** 6), new BN(4 * 10 ** 6), new BN(0), null, null, false)
+      .accounts({
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc();
+
+  const swapAForB = (amount: number) =>
+    program.methods
+      .swapExactTokensForTokens(values.mintAKeypair.publicKey, new BN(amount), new BN(1), null, swapOptions())
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        trader: values.admin.publicKey,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+        traderAccountA: values.holderAccountA,
+        traderAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc();
+
+  const routeAForB = (amount: number) =>
+    program.methods
+      .swapRoute(new BN(amount), new BN(1), null, false)
+      .accounts({
+        amm: values.ammKey,
+        trader: values.admin.publicKey,
+        traderSource: values.holderAccountA,
+        token2022Program: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts(
+        [
+          values.poolKey,
+          values.poolAuthority,
+          values.mintAKeypair.publicKey,
+          values.mintBKeypair.publicKey,
+          values.poolAccountA,
+          values.poolAccountB,
+          values.protocolFeeAccountA,
+          values.holderAccountB,
+          values.poolOracle,
+        ].map((pubkey, i) => ({ pubkey, isSigner: false, isWritable: ![1, 2, 3].includes(i) })),
+      )
+      .signers([values.admin])
+      .rpc();
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+
+    // Pools created from now on concentrate liquidity within ±10% of their initial price
+    await program.methods
+      .updateConcentratedLiquidityConfig({ enabled: true, rangePercentage: 10, rewardMultiplier: 1200, minWidth: new BN(0) })
+      .accounts({ amm: values.ammKey, admin: values.admin.publicKey })
+      .signers([values.admin])
+      .rpc();
+  });
+
+  it('The range around the initial price is stored on the pool', async () => {
+    await createPool(4);
+
+    const pool = await program.account.pool.fetch(values.poolKey);
+    expect(pool.priceRange.lowerPriceX64.muln(1000).div(Q64).toNumber()).to.be.closeTo(3600, 1);
+    expect(pool.priceRange.upperPriceX64.muln(1000).div(Q64).toNumber()).to.be.closeTo(4400, 1);
+  });
+
+  it('Swaps must keep the price within the range', async () => {
+    await createPool(4);
+    await deposit();
+
+    // Selling 8% more A drops its price to about 3.43 B, below the 3.6 B lower bound
+    await expectRevert(swapAForB(80_000));
+
+    // Selling 3% more A keeps its price at about 3.78 B
+    await swapAForB(30_000);
+    const traderTokenAccountA = await connection.getTokenAccountBalance(values.holderAccountA);
+    expect(traderTokenAccountA.value.amount).to.equal(values.defaultSupply.subn(10 ** 6).subn(30_000).toString());
+  });
+
+  it('Routed swaps must keep the price within the range', async () => {
+    await createPool(4);
+    await deposit();
+
+    try {
+      await routeAForB(80_000);
+      expect.fail('Expected the route to revert');
+    } catch (error) {
+      expect(error).to.be.instanceOf(anchor.AnchorError);
+      expect((error as anchor.AnchorError).error.errorCode.code).to.equal('PriceOutOfRange');
+    }
+
+    await routeAForB(30_000);
+    const traderTokenAccountA = await connection.getTokenAccountBalance(values.holderAccountA);
+    expect(traderTokenAccountA.value.amount).to.equal(values.defaultSupply.subn(10 ** 6).subn(30_000).toString());
+  });
+
+  it('Pools without an initial price are not bounded', async () => {
+    await createPool(0);
+    await deposit();
+
+    const pool = await program.account.pool.fetch(values.poolKey);
+    expect(pool.priceRange.upperPriceX64.toNumber()).to.equal(0);
+    await swapAForB(80_000);
+  });
+});
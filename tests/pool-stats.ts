@@ -0,0 +1,160 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type TestValues, createValues, mintingTokens, swapOptions } from './utils';
+
+describe('Pool stats', () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+
+    await program.methods
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolRef: values.poolRef,
+        programConfig: values.programConfig,
+        poolOracle: values.poolOracle,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+      })
+      .rpc();
+
+    await program.methods
+      .depositLiquidity(values.depositAmountA, values.depositAmountB, new BN(0), null, null, false)
+      .accounts({
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc();
+  });
+
+  const swap = (amount: number) =>
+    program.methods
+      .swapExactTokensForTokens(values.mintBKeypair.publicKey, new BN(amount), new BN(1), null, swapOptions())
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        trader: values.admin.publicKey,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+        traderAccountA: values.holderAccountA,
+        traderAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc();
+
+  it('Track swaps, deposits and withdrawals', async () => {
+    await swap(10_000);
+
+    await program.methods
+      .withdrawLiquidity(new BN(1_000), new BN(0), new BN(0), null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc();
+
+    const { stats } = await program.account.pool.fetch(values.poolKey);
+    expect(stats.swapCount.toNumber()).to.equal(1);
+    expect(stats.depositCount.toNumber()).to.equal(1);
+    expect(stats.withdrawCount.toNumber()).to.equal(1);
+    // Swapped B for A: B flows in and pays the fee, A flows out
+    expect(stats.volumeB.toNumber()).to.equal(10_000);
+    expect(stats.volumeA.toNumber()).to.be.greaterThan(0);
+    expect(stats.feesB.toNumber()).to.be.greaterThan(0);
+    expect(stats.feesA.toNumber()).to.equal(0);
+
+    // The swap lands in the bucket of the current hour
+    const buckets = stats.hourly.filter((bucket) => bucket.hour.toNumber() > 0);
+    expect(buckets.length).to.equal(1);
+    expect(buckets[0].hour.toNumber()).to.be.closeTo(Math.floor(Date.now() / 1000 / 3600), 1);
+    expect(buckets[0].volumeB.toNumber()).to.equal(10_000);
+    expect(buckets[0].feesB.toNumber()).to.equal(stats.feesB.toNumber());
+  });
+
+  const getApr = () =>
+    program.methods
+      .getPoolApr()
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+      })
+      .view();
+
+  it('Fee APR', async () => {
+    // 4 A at 0.25 B each plus 1 B
+    let apr = await getApr();
+    expect(apr.tvl.toNumber()).to.equal(2_000_000);
+    expect(apr.aprBps.toNumber()).to.equal(0);
+
+    await swap(10_000);
+
+    apr = await getApr();
+    expect(apr.lpFees24h.toNumber()).to.be.greaterThan(0);
+    expect(apr.aprBps.toNumber()).to.equal(Math.floor((apr.lpFees24h.toNumber() * 365 * 10000) / apr.tvl.toNumber()));
+  });
+});
@@ -0,0 +1,165 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { Keypair } from '@solana/web3.js';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type TestValues, createValues, expectRevert, mintingTokens, swapOptions } from './utils';
+
+describe('Pool status', () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+
+    await program.methods
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolRef: values.poolRef,
+        programConfig: values.programConfig,
+        poolOracle: values.poolOracle,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+      })
+      .rpc();
+
+    await program.methods
+      .depositLiquidity(values.depositAmountA, values.depositAmountB, new BN(0), null, null, false)
+      .accounts({
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc({ skipPreflight: true });
+  });
+
+  const setPoolStatus = (status: any, admin = values.admin) =>
+    program.methods
+      .setPoolStatus(status)
+      .accounts({ amm: values.ammKey, pool: values.poolKey, admin: admin.publicKey })
+      .signers([admin])
+      .rpc();
+
+  const swap = () =>
+    program.methods
+      .swapExactTokensForTokens(values.mintBKeypair.publicKey, new BN(10 ** 4), new BN(0), null, swapOptions())
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        trader: values.admin.publicKey,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+        traderAccountA: values.holderAccountA,
+        traderAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc();
+
+  const liquidityAccounts = () => ({
+    amm: values.ammKey,
+    pool: values.poolKey,
+    poolAuthority: values.poolAuthority,
+    depositor: values.admin.publicKey,
+    mintLiquidity: values.mintLiquidity,
+    mintA: values.mintAKeypair.publicKey,
+    mintB: values.mintBKeypair.publicKey,
+    tokenProgramA: TOKEN_PROGRAM_ID,
+    tokenProgramB: TOKEN_PROGRAM_ID,
+    poolAccountA: values.poolAccountA,
+    poolAccountB: values.poolAccountB,
+    depositorAccountLiquidity: values.liquidityAccount,
+    depositorAccountA: values.holderAccountA,
+    depositorAccountB: values.holderAccountB,
+  });
+
+  const deposit = () =>
+    program.methods
+      .depositLiquidity(new BN(10 ** 5), new BN(10 ** 5), new BN(0), null, null, false)
+      .accounts(liquidityAccounts())
+      .signers([values.admin])
+      .rpc();
+
+  const withdraw = () =>
+    program.methods
+      .withdrawLiquidity(new BN(10 ** 5), new BN(0), new BN(0), null)
+      .accounts(liquidityAccounts())
+      .signers([values.admin])
+      .rpc();
+
+  it('Deposit disabled keeps swaps and withdrawals', async () => {
+    await setPoolStatus({ depositDisabled: {} });
+    expect((await program.account.pool.fetch(values.poolKey)).status).to.deep.equal({ depositDisabled: {} });
+
+    await expectRevert(deposit());
+    await swap();
+    await withdraw();
+  });
+
+  it('Swap only blocks liquidity changes', async () => {
+    await setPoolStatus({ swapOnly: {} });
+
+    await swap();
+    await expectRevert(deposit());
+    await expectRevert(withdraw());
+  });
+
+  it('Frozen pools reject everything until reactivated', async () => {
+    await setPoolStatus({ frozen: {} });
+
+    await expectRevert(swap());
+    await expectRevert(deposit());
+    await expectRevert(withdraw());
+
+    await setPoolStatus({ active: {} });
+    await swap();
+    await deposit();
+  });
+
+  it('Only the owner or the risk manager can change it', async () => {
+    await expectRevert(setPoolStatus({ frozen: {} }, Keypair.generate()));
+  });
+});
@@ -0,0 +1,302 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_PROGRAM_ID, getAssociatedTokenAddressSync, getMint } from '@solana/spl-token';
+import { Keypair, PublicKey } from '@solana/web3.js';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type TestValues, createValues, denyAddress, expectRevert, mintingTokens, swapOptions } from './utils';
+
+const TOKEN_METADATA_PROGRAM_ID = new PublicKey('metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s');
+
+describe('Positions', () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+
+    await program.methods
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolRef: values.poolRef,
+        programConfig: values.programConfig,
+        poolOracle: values.poolOracle,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+      })
+      .rpc();
+
+    await program.methods
+      .depositLiquidity(values.depositAmountA, values.depositAmountB, new BN(0), null, null, false)
+      .accounts({
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc();
+  });
+
+  const Q64 = new BN(2).pow(new BN(64));
+  // Pool price of A is 0.25 B
+  const lowerPrice = Q64.muln(2).divn(10);
+  const upperPrice = Q64.muln(3).divn(10);
+
+  const positionKey = (positionMint: PublicKey) =>
+    PublicKey.findProgramAddressSync([positionMint.toBuffer(), Buffer.from('position')], program.programId)[0];
+
+  const escrowKey = (positionMint: PublicKey) =>
+    PublicKey.findProgramAddressSync([positionKey(positionMint).toBuffer(), Buffer.from('escrow')], program.programId)[0];
+
+  const nftAccount = (positionMint: PublicKey) => getAssociatedTokenAddressSync(positionMint, values.admin.publicKey, true);
+
+  const metadataKey = (positionMint: PublicKey) =>
+    PublicKey.findProgramAddressSync([Buffer.from('metadata'), TOKEN_METADATA_PROGRAM_ID.toBuffer(), positionMint.toBuffer()], TOKEN_METADATA_PROGRAM_ID)[0];
+
+  const editionKey = (positionMint: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from('metadata'), TOKEN_METADATA_PROGRAM_ID.toBuffer(), positionMint.toBuffer(), Buffer.from('edition')],
+      TOKEN_METADATA_PROGRAM_ID,
+    )[0];
+
+  const openPosition = async (liquidity: number, lower = lowerPrice, upper = upperPrice) => {
+    const positionMint = Keypair.generate();
+    await program.methods
+      .openPosition(lower, upper, new BN(liquidity))
+      .accounts({
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        mintLiquidity: values.mintLiquidity,
+        positionMint: positionMint.publicKey,
+        position: positionKey(positionMint.publicKey),
+        escrow: escrowKey(positionMint.publicKey),
+        owner: values.admin.publicKey,
+        ownerAccountLiquidity: values.liquidityAccount,
+        ownerAccountNft: nftAccount(positionMint.publicKey),
+        positionMetadata: metadataKey(positionMint.publicKey),
+        positionEdition: editionKey(positionMint.publicKey),
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      })
+      .signers([values.admin, positionMint])
+      .rpc();
+    return positionMint.publicKey;
+  };
+
+  const closePosition = (positionMint: PublicKey) =>
+    program.methods
+      .closePosition()
+      .accounts({
+        position: positionKey(positionMint),
+        positionMint,
+        escrow: escrowKey(positionMint),
+        mintLiquidity: values.mintLiquidity,
+        owner: values.admin.publicKey,
+        ownerAccountNft: nftAccount(positionMint),
+        ownerAccountLiquidity: values.liquidityAccount,
+        positionMetadata: metadataKey(positionMint),
+        positionEdition: editionKey(positionMint),
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      })
+      .signers([values.admin])
+      .rpc();
+
+  it('Open position', async () => {
+    const positionMint = await openPosition(10 ** 6);
+
+    const position = await program.account.position.fetch(positionKey(positionMint));
+    expect(position.liquidity.toNumber()).to.equal(10 ** 6);
+    expect(position.positionMint.toBase58()).to.equal(positionMint.toBase58());
+
+    const nft = await connection.getTokenAccountBalance(nftAccount(positionMint));
+    expect(nft.value.amount).to.equal('1');
+    expect(nft.value.decimals).to.equal(0);
+
+    const escrow = await connection.getTokenAccountBalance(escrowKey(positionMint));
+    expect(escrow.value.amount).to.equal(String(10 ** 6));
+
+    // The NFT carries Metaplex metadata and a master edition that owns the mint authority
+    const metadata = await connection.getAccountInfo(metadataKey(positionMint));
+    expect(metadata.owner.toBase58()).to.equal(TOKEN_METADATA_PROGRAM_ID.toBase58());
+    const mint = await getMint(connection, positionMint);
+    expect(mint.supply.toString()).to.equal('1');
+    expect(mint.mintAuthority.toBase58()).to.equal(editionKey(positionMint).toBase58());
+  });
+
+  it('Close position', async () => {
+    const positionMint = await openPosition(10 ** 6);
+
+    const before = await connection.getTokenAccountBalance(values.liquidityAccount);
+    await closePosition(positionMint);
+    const after = await connection.getTokenAccountBalance(values.liquidityAccount);
+    expect(Number(after.value.amount) - Number(before.value.amount)).to.equal(10 ** 6);
+
+    // Burning the NFT closes its token account, metadata and master edition
+    expect(await connection.getAccountInfo(nftAccount(positionMint))).to.equal(null);
+    expect(await connection.getAccountInfo(metadataKey(positionMint))).to.equal(null);
+    expect(await connection.getAccountInfo(editionKey(positionMint))).to.equal(null);
+  });
+
+  const modifyAccounts = (positionMint: PublicKey) => ({
+    amm: values.ammKey,
+    pool: values.poolKey,
+    poolAuthority: values.poolAuthority,
+    poolAccountA: values.poolAccountA,
+    poolAccountB: values.poolAccountB,
+    mintLiquidity: values.mintLiquidity,
+    position: positionKey(positionMint),
+    escrow: escrowKey(positionMint),
+    owner: values.admin.publicKey,
+    ownerAccountNft: nftAccount(positionMint),
+    ownerAccountLiquidity: values.liquidityAccount,
+  });
+
+  it('Increase and decrease liquidity', async () => {
+    const positionMint = await openPosition(10 ** 6);
+
+    await program.methods
+      .increaseLiquidity(new BN(5 * 10 ** 5))
+      .accounts(modifyAccounts(positionMint))
+      .signers([values.admin])
+      .rpc();
+    let position = await program.account.position.fetch(positionKey(positionMint));
+    expect(position.liquidity.toNumber()).to.equal(15 * 10 ** 5);
+
+    await program.methods
+      .decreaseLiquidity(new BN(10 ** 6))
+      .accounts(modifyAccounts(positionMint))
+      .signers([values.admin])
+      .rpc();
+    position = await program.account.position.fetch(positionKey(positionMint));
+    expect(position.liquidity.toNumber()).to.equal(5 * 10 ** 5);
+
+    const escrow = await connection.getTokenAccountBalance(escrowKey(positionMint));
+    expect(escrow.value.amount).to.equal(String(5 * 10 ** 5));
+
+    // Cannot take out more than the position holds
+    await expectRevert(
+      program.methods
+        .decreaseLiquidity(new BN(5 * 10 ** 5 + 1))
+        .accounts(modifyAccounts(positionMint))
+        .signers([values.admin])
+        .rpc(),
+    );
+  });
+
+  it('Denied owners cannot increase liquidity', async () => {
+    const positionMint = await openPosition(10 ** 6);
+    const denylist = await denyAddress(program, values, values.admin.publicKey);
+
+    await expectRevert(
+      program.methods
+        .increaseLiquidity(new BN(5 * 10 ** 5))
+        .accounts({ ...modifyAccounts(positionMint), denylist })
+        .signers([values.admin])
+        .rpc(),
+    );
+
+    // Taking liquidity out stays open
+    await program.methods
+      .decreaseLiquidity(new BN(5 * 10 ** 5))
+      .accounts({ ...modifyAccounts(positionMint), denylist })
+      .signers([values.admin])
+      .rpc();
+  });
+
+  it('Collect position fees', async () => {
+    const positionMint = await openPosition(10 ** 6);
+
+    await program.methods
+      .swapExactTokensForTokens(values.mintBKeypair.publicKey, new BN(10 ** 5), new BN(1), null, swapOptions())
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        trader: values.admin.publicKey,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+        traderAccountA: values.holderAccountA,
+        traderAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc();
+
+    const balanceBefore = await connection.getTokenAccountBalance(values.holderAccountB);
+    await program.methods
+      .collectPositionFees()
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        position: positionKey(positionMint),
+        escrow: escrowKey(positionMint),
+        owner: values.admin.publicKey,
+        ownerAccountNft: nftAccount(positionMint),
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        ownerAccountA: values.holderAccountA,
+        ownerAccountB: values.holderAccountB,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+      })
+      .signers([values.admin])
+      .rpc();
+    const balanceAfter = await connection.getTokenAccountBalance(values.holderAccountB);
+    expect(Number(balanceAfter.value.amount)).to.be.greaterThan(Number(balanceBefore.value.amount));
+
+    // Only the fee share of the position is burned
+    const position = await program.account.position.fetch(positionKey(positionMint));
+    expect(position.feesOwed.toNumber()).to.equal(0);
+    expect(position.liquidity.toNumber()).to.be.lessThan(10 ** 6);
+    expect(position.liquidity.toNumber()).to.be.greaterThan(0.99 * 10 ** 6);
+  });
+
+  it('Invalid range', async () => {
+    await expectRevert(openPosition(10 ** 6, upperPrice, lowerPrice));
+  });
+});
@@ -0,0 +1,139 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type TestValues, createValues, mintingTokens } from './utils';
+
+describe('Preview liquidity', () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+
+    await program.methods
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolRef: values.poolRef,
+        programConfig: values.programConfig,
+        poolOracle: values.poolOracle,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+      })
+      .rpc();
+
+    await deposit(values.depositAmountA, values.depositAmountB);
+  });
+
+  const previewAccounts = () => ({
+    amm: values.ammKey,
+    pool: values.poolKey,
+    poolAuthority: values.poolAuthority,
+    mintLiquidity: values.mintLiquidity,
+    mintA: values.mintAKeypair.publicKey,
+    mintB: values.mintBKeypair.publicKey,
+    poolAccountA: values.poolAccountA,
+    poolAccountB: values.poolAccountB,
+    depositRecord: null,
+  });
+
+  const deposit = (amountA: BN, amountB: BN) =>
+    program.methods
+      .depositLiquidity(amountA, amountB, new BN(0), null, null, false)
+      .accounts({
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc({ skipPreflight: true });
+
+  const lpBalance = async () => new BN((await connection.getTokenAccountBalance(values.liquidityAccount)).value.amount);
+
+  it('Deposit preview matches the minted liquidity', async () => {
+    // Off-ratio amounts are scaled down to the pool ratio
+    const amountA = new BN(10 ** 6);
+    const amountB = new BN(10 ** 6);
+    const preview = await program.methods.previewDeposit(amountA, amountB).accounts(previewAccounts()).view();
+    expect(preview.amountA.lte(amountA)).to.be.true;
+    expect(preview.amountB.lte(amountB)).to.be.true;
+
+    const before = await lpBalance();
+    await deposit(amountA, amountB);
+    const after = await lpBalance();
+    expect(after.sub(before).toString()).to.equal(preview.liquidity.toString());
+  });
+
+  it('Withdraw preview matches the returned tokens', async () => {
+    const lpAmount = (await lpBalance()).divn(3);
+    const preview = await program.methods.previewWithdraw(lpAmount).accounts(previewAccounts()).view();
+
+    const before = await connection.getTokenAccountBalance(values.holderAccountA);
+    await program.methods
+      .withdrawLiquidity(lpAmount, preview.amountA, preview.amountB, null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc();
+    const after = await connection.getTokenAccountBalance(values.holderAccountA);
+    expect(new BN(after.value.amount).sub(new BN(before.value.amount)).toString()).to.equal(preview.amountA.toString());
+  });
+
+  it('LP token value', async () => {
+    // sqrt(4 * 1) = 2 LP tokens back 4 A and 1 B
+    const value = await program.methods.getLpTokenValue().accounts(previewAccounts()).view();
+    expect(value.lpDecimals).to.equal(6);
+    expect(value.amountA.toNumber()).to.equal(2 * 10 ** 6);
+    expect(value.amountB.toNumber()).to.equal(5 * 10 ** 5);
+  });
+});
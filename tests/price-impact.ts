@@ -0,0 +1,127 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type TestValues, createValues, expectRevert, mintingTokens, swapOptions } from './utils';
+
+describe('Price impact', () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+
+    await program.methods
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolRef: values.poolRef,
+        programConfig: values.programConfig,
+        poolOracle: values.poolOracle,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+      })
+      .rpc();
+
+    await program.methods
+      .depositLiquidity(values.depositAmountA, values.depositAmountB, new BN(0), null, null, false)
+      .accounts({
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc({ skipPreflight: true });
+
+    // At most 1% price impact, without adjusting the output
+    await program.methods.updatePriceImpactConfig(100, 0, true).accounts({ amm: values.ammKey, admin: values.admin.publicKey }).signers([values.admin]).rpc();
+  });
+
+  const swapBForA = (input: number) =>
+    program.methods
+      .swapExactTokensForTokens(values.mintBKeypair.publicKey, new BN(input), new BN(1), null, swapOptions())
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        trader: values.admin.publicKey,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+        traderAccountA: values.holderAccountA,
+        traderAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc();
+
+  it('Swaps within the price impact limit go through', async () => {
+    // 3000 B into the 1:4 pool moves the price by about 0.6%
+    await swapBForA(3000);
+
+    const traderTokenAccountB = await connection.getTokenAccountBalance(values.holderAccountB);
+    expect(traderTokenAccountB.value.amount).to.equal(values.defaultSupply.sub(values.depositAmountB).subn(3000).toString());
+  });
+
+  it('Price impact includes the output leaving the pool', async () => {
+    // The input alone moves the price by about 0.6%, input and output together by about 1.2%
+    await expectRevert(swapBForA(6000));
+
+    const traderTokenAccountB = await connection.getTokenAccountBalance(values.holderAccountB);
+    expect(traderTokenAccountB.value.amount).to.equal(values.defaultSupply.sub(values.depositAmountB).toString());
+  });
+
+  it('Trades above the size cap are rejected', async () => {
+    // Lift the price impact limit so only the default 10% trade size cap applies
+    await program.methods.updatePriceImpactConfig(10000, 0, true).accounts({ amm: values.ammKey, admin: values.admin.publicKey }).signers([values.admin]).rpc();
+    const amm = await program.account.amm.fetch(values.ammKey);
+    expect(amm.priceImpactConfig.maxTradePctBps).to.equal(1000);
+
+    // More than 10% of the 10^6 B reserve reaches the pool even after fees
+    await expectRevert(swapBForA(120_000));
+
+    // Exactly 10% of the reserve is allowed
+    await swapBForA(100_000);
+    const traderTokenAccountB = await connection.getTokenAccountBalance(values.holderAccountB);
+    expect(traderTokenAccountB.value.amount).to.equal(values.defaultSupply.sub(values.depositAmountB).subn(100_000).toString());
+  });
+});
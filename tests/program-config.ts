@@ -0,0 +1,70 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { Keypair } from '@solana/web3.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type TestValues, createValues, expectRevert, featureFlags, setFeatureFlags } from './utils';
+
+describe('Program config', () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+
+  beforeEach(async () => {
+    values = createValues();
+    await setFeatureFlags(program);
+  });
+
+  // The config is shared with the other suites
+  after(() => setFeatureFlags(program));
+
+  const createAmm = (payer?: Keypair) =>
+    program.methods
+      .createAmm(values.id, values.fee)
+      .accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig, ...(payer && { payer: payer.publicKey }) })
+      .signers(payer ? [payer] : [])
+      .rpc();
+
+  const fundedKeypair = async () => {
+    const keypair = Keypair.generate();
+    const signature = await connection.requestAirdrop(keypair.publicKey, anchor.web3.LAMPORTS_PER_SOL);
+    await connection.confirmTransaction({ signature, ...(await connection.getLatestBlockhash()) });
+    return keypair;
+  };
+
+  it('Created by the upgrade authority', async () => {
+    const config = await program.account.programConfig.fetch(values.programConfig);
+    expect(config.authority.toString()).to.equal(provider.wallet.publicKey.toString());
+    expect(config.features).to.deep.equal(featureFlags());
+  });
+
+  it('Only the authority updates the flags', async () => {
+    const other = Keypair.generate();
+    await expectRevert(
+      program.methods
+        .updateProgramConfig(featureFlags({ flashLoansEnabled: false }))
+        .accounts({ programConfig: values.programConfig, authority: other.publicKey })
+        .signers([other])
+        .rpc(),
+    );
+  });
+
+  it('Default protocol fee of new AMMs', async () => {
+    await setFeatureFlags(program, { defaultProtocolFeeBps: 1000 });
+    await createAmm();
+
+    const amm = await program.account.amm.fetch(values.ammKey);
+    expect(amm.feeConfig.protocolFeeBps).to.equal(1000);
+  });
+
+  it('Restricted AMM creation', async () => {
+    await setFeatureFlags(program, { permissionlessAmms: false });
+
+    await expectRevert(createAmm(await fundedKeypair()));
+    await createAmm();
+  });
+});
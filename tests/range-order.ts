@@ -0,0 +1,223 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_PROGRAM_ID, getAssociatedTokenAddressSync } from '@solana/spl-token';
+import { Keypair, PublicKey } from '@solana/web3.js';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type TestValues, createValues, expectRevert, mintingTokens, swapOptions } from './utils';
+
+const TOKEN_METADATA_PROGRAM_ID = new PublicKey('metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s');
+
+describe('Range orders', () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+
+    await program.methods
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolRef: values.poolRef,
+        programConfig: values.programConfig,
+        poolOracle: values.poolOracle,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+      })
+      .rpc();
+
+    await program.methods
+      .depositLiquidity(values.depositAmountA, values.depositAmountB, new BN(0), null, null, false)
+      .accounts({
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc();
+  });
+
+  const Q64 = new BN(2).pow(new BN(64));
+  // Pool price of A is 0.25 B, so this range sits entirely above the current price
+  const lowerPrice = Q64.muln(26).divn(100);
+  const upperPrice = Q64.muln(27).divn(100);
+
+  const positionKey = (positionMint: PublicKey) =>
+    PublicKey.findProgramAddressSync([positionMint.toBuffer(), Buffer.from('position')], program.programId)[0];
+
+  const escrowKey = (positionMint: PublicKey) =>
+    PublicKey.findProgramAddressSync([positionKey(positionMint).toBuffer(), Buffer.from('escrow')], program.programId)[0];
+
+  const nftAccount = (positionMint: PublicKey) => getAssociatedTokenAddressSync(positionMint, values.admin.publicKey, true);
+
+  const metadataKey = (positionMint: PublicKey) =>
+    PublicKey.findProgramAddressSync([Buffer.from('metadata'), TOKEN_METADATA_PROGRAM_ID.toBuffer(), positionMint.toBuffer()], TOKEN_METADATA_PROGRAM_ID)[0];
+
+  const editionKey = (positionMint: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from('metadata'), TOKEN_METADATA_PROGRAM_ID.toBuffer(), positionMint.toBuffer(), Buffer.from('edition')],
+      TOKEN_METADATA_PROGRAM_ID,
+    )[0];
+
+  const openPosition = async (liquidity: number, lower = lowerPrice, upper = upperPrice) => {
+    const positionMint = Keypair.generate();
+    await program.methods
+      .openPosition(lower, upper, new BN(liquidity))
+      .accounts({
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        mintLiquidity: values.mintLiquidity,
+        positionMint: positionMint.publicKey,
+        position: positionKey(positionMint.publicKey),
+        escrow: escrowKey(positionMint.publicKey),
+        owner: values.admin.publicKey,
+        ownerAccountLiquidity: values.liquidityAccount,
+        ownerAccountNft: nftAccount(positionMint.publicKey),
+        positionMetadata: metadataKey(positionMint.publicKey),
+        positionEdition: editionKey(positionMint.publicKey),
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      })
+      .signers([values.admin, positionMint])
+      .rpc();
+    return positionMint.publicKey;
+  };
+
+  const closePosition = (positionMint: PublicKey) =>
+    program.methods
+      .closePosition()
+      .accounts({
+        position: positionKey(positionMint),
+        positionMint,
+        escrow: escrowKey(positionMint),
+        mintLiquidity: values.mintLiquidity,
+        owner: values.admin.publicKey,
+        ownerAccountNft: nftAccount(positionMint),
+        ownerAccountLiquidity: values.liquidityAccount,
+        positionMetadata: metadataKey(positionMint),
+        positionEdition: editionKey(positionMint),
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      })
+      .signers([values.admin])
+      .rpc();
+
+  const swapBForA = (amount: number) =>
+    program.methods
+      .swapExactTokensForTokens(values.mintBKeypair.publicKey, new BN(amount), new BN(1), null, swapOptions())
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        trader: values.admin.publicKey,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+        traderAccountA: values.holderAccountA,
+        traderAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc();
+
+  const closeFilledRangeOrder = (positionMint: PublicKey, ownerAccount: PublicKey) =>
+    program.methods
+      .closeFilledRangeOrder(new BN(0))
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        position: positionKey(positionMint),
+        positionMint,
+        escrow: escrowKey(positionMint),
+        owner: values.admin.publicKey,
+        ownerAccountNft: nftAccount(positionMint),
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        ownerAccount,
+        positionMetadata: metadataKey(positionMint),
+        positionEdition: editionKey(positionMint),
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      })
+      .signers([values.admin])
+      .rpc();
+
+  it('Ranges outside the price are range orders', async () => {
+    const above = await openPosition(10 ** 5);
+    expect((await program.account.position.fetch(positionKey(above))).rangeOrder).to.equal(true);
+
+    const below = await openPosition(10 ** 5, Q64.muln(1).divn(10), Q64.muln(2).divn(10));
+    expect((await program.account.position.fetch(positionKey(below))).rangeOrder).to.equal(false);
+
+    const inRange = await openPosition(10 ** 5, Q64.muln(2).divn(10), Q64.muln(3).divn(10));
+    expect((await program.account.position.fetch(positionKey(inRange))).rangeOrder).to.equal(null);
+    await expectRevert(closeFilledRangeOrder(inRange, values.holderAccountB));
+  });
+
+  it('Close a filled range order', async () => {
+    const positionMint = await openPosition(10 ** 6);
+
+    // The price has not crossed the range yet
+    await expectRevert(closeFilledRangeOrder(positionMint, values.holderAccountB));
+
+    // Buying A pushes its price through the range
+    await swapBForA(10 ** 5);
+
+    const balanceA = await connection.getTokenAccountBalance(values.holderAccountA);
+    const balanceB = await connection.getTokenAccountBalance(values.holderAccountB);
+    await closeFilledRangeOrder(positionMint, values.holderAccountB);
+
+    // The order is paid out entirely in B
+    const balanceAAfter = await connection.getTokenAccountBalance(values.holderAccountA);
+    const balanceBAfter = await connection.getTokenAccountBalance(values.holderAccountB);
+    expect(balanceAAfter.value.amount).to.equal(balanceA.value.amount);
+    expect(Number(balanceBAfter.value.amount)).to.be.greaterThan(Number(balanceB.value.amount));
+
+    const nft = await connection.getTokenAccountBalance(nftAccount(positionMint));
+    expect(nft.value.amount).to.equal('0');
+  });
+});
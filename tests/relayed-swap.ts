@@ -0,0 +1,131 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_PROGRAM_ID, getOrCreateAssociatedTokenAccount } from '@solana/spl-token';
+import type { PublicKey } from '@solana/web3.js';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type TestValues, createValues, expectRevert, mintingTokens } from './utils';
+
+describe('Relayed swap', () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+  // The provider wallet acts as the relayer paying fees and rent
+  const relayer = provider.wallet.publicKey;
+  let relayerAccountB: PublicKey;
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+
+    await program.methods
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolRef: values.poolRef,
+        programConfig: values.programConfig,
+        poolOracle: values.poolOracle,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+      })
+      .rpc();
+
+    await program.methods
+      .depositLiquidity(values.depositAmountA, values.depositAmountB, new BN(0), null, null, false)
+      .accounts({
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc({ skipPreflight: true });
+
+    relayerAccountB = (await getOrCreateAssociatedTokenAccount(connection, values.admin, values.mintBKeypair.publicKey, relayer)).address;
+  });
+
+  const swapRelayed = (tip: number, relayerAccount: PublicKey | null = relayerAccountB) =>
+    program.methods
+      .swapExactTokensForTokensRelayed(values.mintAKeypair.publicKey, new BN(10 ** 6), new BN(100), null, new BN(tip))
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        trader: values.admin.publicKey,
+        payer: relayer,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+        traderAccountA: values.holderAccountA,
+        traderAccountB: values.holderAccountB,
+        relayerAccount,
+      })
+      .signers([values.admin])
+      .rpc();
+
+  it('Relayer pays the fees and receives the tip', async () => {
+    const traderLamports = await connection.getBalance(values.admin.publicKey);
+
+    await swapRelayed(1000);
+
+    // The trader only signed the token movement
+    expect(await connection.getBalance(values.admin.publicKey)).to.equal(traderLamports);
+    const relayerBalance = await connection.getTokenAccountBalance(relayerAccountB);
+    expect(relayerBalance.value.amount).to.equal('1000');
+  });
+
+  it('Relayed swap without a tip', async () => {
+    await swapRelayed(0, null);
+
+    const relayerBalance = await connection.getTokenAccountBalance(relayerAccountB);
+    expect(relayerBalance.value.amount).to.equal('0');
+  });
+
+  it('Tip requires a relayer account', async () => {
+    await expectRevert(swapRelayed(1000, null));
+  });
+
+  it('Tip account must belong to the relayer', async () => {
+    await expectRevert(swapRelayed(1000, values.holderAccountB));
+  });
+
+  it('Minimum output is checked after the tip', async () => {
+    await expectRevert(swapRelayed(10 ** 12));
+  });
+});
@@ -0,0 +1,77 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { Keypair, PublicKey } from '@solana/web3.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type TestValues, createValues, expectRevert } from './utils';
+
+describe('Roles', () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+  });
+
+  const grantRole = (role: any, holder: PublicKey, owner = values.admin) =>
+    program.methods.grantRole(role, holder).accounts({ amm: values.ammKey, admin: owner.publicKey }).signers([owner]).rpc();
+
+  const revokeRole = (role: any, owner = values.admin) =>
+    program.methods.revokeRole(role).accounts({ amm: values.ammKey, admin: owner.publicKey }).signers([owner]).rpc();
+
+  const updateFeeSide = (signer: Keypair) =>
+    program.methods.updateFeeSide(true).accounts({ amm: values.ammKey, admin: signer.publicKey }).signers([signer]).rpc();
+
+  const updateMintPolicy = (signer: Keypair) =>
+    program.methods
+      .updateMintPolicy({ rejectFreezeAuthority: true, rejectTransferFee: false })
+      .accounts({ amm: values.ammKey, admin: signer.publicKey })
+      .signers([signer])
+      .rpc();
+
+  it('Fee manager updates fee settings only', async () => {
+    const feeManager = Keypair.generate();
+    await grantRole({ feeManager: {} }, feeManager.publicKey);
+
+    const amm = await program.account.amm.fetch(values.ammKey);
+    expect(amm.feeManager.toString()).to.equal(feeManager.publicKey.toString());
+
+    await updateFeeSide(feeManager);
+    expect((await program.account.amm.fetch(values.ammKey)).feeOnOutput).to.equal(true);
+
+    // Other duties stay with the owner and their own role
+    await expectRevert(updateMintPolicy(feeManager));
+    await expectRevert(grantRole({ pauser: {} }, feeManager.publicKey, feeManager));
+  });
+
+  it('Revoked roles fall back to the owner', async () => {
+    const riskManager = Keypair.generate();
+    await grantRole({ riskManager: {} }, riskManager.publicKey);
+    await updateMintPolicy(riskManager);
+
+    await revokeRole({ riskManager: {} });
+    const amm = await program.account.amm.fetch(values.ammKey);
+    expect(amm.riskManager.toString()).to.equal(PublicKey.default.toString());
+
+    await expectRevert(updateMintPolicy(riskManager));
+    await updateMintPolicy(values.admin);
+  });
+
+  it('Owner is transferred, not revoked', async () => {
+    await expectRevert(revokeRole({ owner: {} }));
+
+    const newOwner = Keypair.generate();
+    await grantRole({ owner: {} }, newOwner.publicKey);
+
+    const amm = await program.account.amm.fetch(values.ammKey);
+    expect(amm.admin.toString()).to.equal(newOwner.publicKey.toString());
+    await expectRevert(updateFeeSide(values.admin));
+    await updateFeeSide(newOwner);
+  });
+});
@@ -0,0 +1,214 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_PROGRAM_ID, transfer } from '@solana/spl-token';
+import { Keypair } from '@solana/web3.js';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type TestValues, createValues, expectRevert, mintingTokens, swapOptions } from './utils';
+
+describe('Skim and sync', () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+
+  const donation = 10 ** 5;
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+
+    await program.methods
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolRef: values.poolRef,
+        programConfig: values.programConfig,
+        poolOracle: values.poolOracle,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+      })
+      .rpc();
+
+    await program.methods
+      .depositLiquidity(values.depositAmountA, values.depositAmountB, new BN(0), null, null, false)
+      .accounts({
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc({ skipPreflight: true });
+
+    // Donate token A directly to the pool, bypassing the deposit flow
+    await transfer(connection, values.admin, values.holderAccountA, values.poolAccountA, values.admin, donation);
+  });
+
+  const sync = () =>
+    program.methods
+      .sync()
+      .accounts({
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+  const skim = (admin = values.admin) =>
+    program.methods
+      .skim()
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+        admin: admin.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+      })
+      .signers([admin])
+      .rpc();
+
+  it('Deposits record the pool reserves', async () => {
+    const pool = await program.account.pool.fetch(values.poolKey);
+    expect(pool.reserveA.toString()).to.equal(values.depositAmountA.toString());
+    expect(pool.reserveB.toString()).to.equal(values.depositAmountB.toString());
+  });
+
+  it('Skim sends the surplus to the protocol fee account', async () => {
+    await skim();
+
+    const poolBalance = await connection.getTokenAccountBalance(values.poolAccountA);
+    expect(poolBalance.value.amount).to.equal(values.depositAmountA.toString());
+    const feeBalance = await connection.getTokenAccountBalance(values.protocolFeeAccountA);
+    expect(feeBalance.value.amount).to.equal(donation.toString());
+  });
+
+  it('Sync folds the surplus into the reserves', async () => {
+    await sync();
+
+    const pool = await program.account.pool.fetch(values.poolKey);
+    expect(pool.reserveA.toString()).to.equal(values.depositAmountA.add(new BN(donation)).toString());
+
+    // Nothing is left to skim once the surplus belongs to the pool
+    await skim();
+    const feeBalance = await connection.getTokenAccountBalance(values.protocolFeeAccountA);
+    expect(feeBalance.value.amount).to.equal('0');
+  });
+
+  const quote = () =>
+    program.methods
+      .quoteSwap(values.mintBKeypair.publicKey, new BN(10 ** 4))
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+      })
+      .view();
+
+  it('Donations do not move the price until synced', async () => {
+    const donated = await quote();
+    await skim();
+    const skimmed = await quote();
+    expect(donated.netOutputAmount.toString()).to.equal(skimmed.netOutputAmount.toString());
+
+    // Synced donations deepen the pool and raise the output
+    await transfer(connection, values.admin, values.holderAccountA, values.poolAccountA, values.admin, donation);
+    await sync();
+    const synced = await quote();
+    expect(synced.netOutputAmount.gt(skimmed.netOutputAmount)).to.be.true;
+  });
+
+  const swap = (input: BN) =>
+    program.methods
+      .swapExactTokensForTokens(values.mintAKeypair.publicKey, input, new BN(0), null, swapOptions())
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        trader: values.admin.publicKey,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+        traderAccountA: values.holderAccountA,
+        traderAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc({ skipPreflight: true });
+
+  it('Swaps after a donation do not fold it into the reserves', async () => {
+    await swap(new BN(10 ** 5));
+
+    // The recorded reserves only moved by the swap, the donation is still surplus
+    const pool = await program.account.pool.fetch(values.poolKey);
+    const poolBalance = await connection.getTokenAccountBalance(values.poolAccountA);
+    expect(new BN(poolBalance.value.amount).sub(pool.reserveA).toString()).to.equal(donation.toString());
+
+    // Removing the donation leaves the quote unchanged
+    const donated = await quote();
+    await skim();
+    const skimmed = await quote();
+    expect(donated.netOutputAmount.toString()).to.equal(skimmed.netOutputAmount.toString());
+  });
+
+  it('The fee manager can skim', async () => {
+    const feeManager = Keypair.generate();
+    await program.methods.grantRole({ feeManager: {} }, feeManager.publicKey).accounts({ amm: values.ammKey, admin: values.admin.publicKey }).signers([values.admin]).rpc();
+
+    await skim(feeManager);
+    const feeBalance = await connection.getTokenAccountBalance(values.protocolFeeAccountA);
+    expect(feeBalance.value.amount).to.equal(donation.toString());
+  });
+
+  it('Not the owner or the fee manager', async () => {
+    await expectRevert(skim(Keypair.generate()));
+  });
+});
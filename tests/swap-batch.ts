@@ -0,0 +1,139 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID, createMint, getAssociatedTokenAddressSync, getOrCreateAssociatedTokenAccount, mintTo } from '@solana/spl-token';
+import { Keypair, type PublicKey } from '@solana/web3.js';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type PoolKeys, type TestValues, createValues, derivePoolKeys, expectRevert, mintingTokens, poolRefKey } from './utils';
+
+describe('Swap batch', () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+  let mintC: PublicKey;
+  let secondPool: PoolKeys;
+  let holderAccountC: PublicKey;
+
+  const createAndFund = async (mintA: PublicKey, mintB: PublicKey, keys: PoolKeys, index: number) => {
+    await program.methods
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
+      .accounts({ amm: values.ammKey, mintA, mintB, tokenProgramA: TOKEN_PROGRAM_ID, tokenProgramB: TOKEN_PROGRAM_ID, ...keys, pool: keys.poolKey, poolRef: poolRefKey(values.ammKey, index), programConfig: values.programConfig })
+      .rpc();
+    await program.methods
+      .depositLiquidity(values.depositAmountA, values.depositAmountA, new BN(0), null, null, false)
+      .accounts({
+        pool: keys.poolKey,
+        poolAuthority: keys.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: keys.mintLiquidity,
+        mintA,
+        mintB,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: keys.poolAccountA,
+        poolAccountB: keys.poolAccountB,
+        depositorAccountLiquidity: getAssociatedTokenAddressSync(keys.mintLiquidity, values.admin.publicKey, true),
+        depositorAccountA: getAssociatedTokenAddressSync(mintA, values.admin.publicKey, true),
+        depositorAccountB: getAssociatedTokenAddressSync(mintB, values.admin.publicKey, true),
+      })
+      .signers([values.admin])
+      .rpc({ skipPreflight: true });
+  };
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+
+    // Third token, paired with B in a second pool, sorting after B
+    let mintCKeypair = Keypair.generate();
+    while (new BN(mintCKeypair.publicKey.toBytes()).lt(new BN(values.mintBKeypair.publicKey.toBytes()))) {
+      mintCKeypair = Keypair.generate();
+    }
+    mintC = await createMint(connection, values.admin, values.admin.publicKey, null, 6, mintCKeypair);
+    holderAccountC = (await getOrCreateAssociatedTokenAccount(connection, values.admin, mintC, values.admin.publicKey)).address;
+    await mintTo(connection, values.admin, mintC, holderAccountC, values.admin, values.defaultSupply.toNumber());
+
+    await createAndFund(values.mintAKeypair.publicKey, values.mintBKeypair.publicKey, derivePoolKeys(values.ammKey, values.mintAKeypair.publicKey, values.mintBKeypair.publicKey), 0);
+    secondPool = derivePoolKeys(values.ammKey, values.mintBKeypair.publicKey, mintC);
+    await createAndFund(values.mintBKeypair.publicKey, mintC, secondPool, 1);
+  });
+
+  // Sell A into the A/B pool and C into the B/C pool, both for B
+  const batch = (amount: anchor.BN, minTotalOutput: anchor.BN, clampToBalance = false) =>
+    program.methods
+      .swapBatch(
+        [
+          { pool: values.poolKey, inputMint: values.mintAKeypair.publicKey, amount },
+          { pool: secondPool.poolKey, inputMint: mintC, amount },
+        ],
+        minTotalOutput,
+        null,
+        clampToBalance,
+      )
+      .accounts({
+        amm: values.ammKey,
+        trader: values.admin.publicKey,
+        token2022Program: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts(
+        [
+          values.poolKey,
+          values.poolAuthority,
+          values.mintAKeypair.publicKey,
+          values.mintBKeypair.publicKey,
+          values.poolAccountA,
+          values.poolAccountB,
+          values.protocolFeeAccountA,
+          values.holderAccountB,
+          values.poolOracle,
+          values.holderAccountA,
+          secondPool.poolKey,
+          secondPool.poolAuthority,
+          mintC,
+          values.mintBKeypair.publicKey,
+          secondPool.poolAccountB,
+          secondPool.poolAccountA,
+          secondPool.protocolFeeAccountB,
+          values.holderAccountB,
+          secondPool.poolOracle,
+          holderAccountC,
+        ].map((pubkey, i) => ({ pubkey, isSigner: false, isWritable: ![1, 2, 3].includes(i % 10) })),
+      )
+      .signers([values.admin])
+      .rpc({ skipPreflight: true });
+
+  it('Swaps into B from two pools at once', async () => {
+    const amount = new BN(10 ** 5);
+    const balanceA = await connection.getTokenAccountBalance(values.holderAccountA);
+    const balanceB = await connection.getTokenAccountBalance(values.holderAccountB);
+    const balanceC = await connection.getTokenAccountBalance(holderAccountC);
+
+    await batch(amount, new BN(1));
+
+    expect((await connection.getTokenAccountBalance(values.holderAccountA)).value.amount).to.equal(new BN(balanceA.value.amount).sub(amount).toString());
+    expect((await connection.getTokenAccountBalance(holderAccountC)).value.amount).to.equal(new BN(balanceC.value.amount).sub(amount).toString());
+    expect(Number((await connection.getTokenAccountBalance(values.holderAccountB)).value.amount)).to.be.greaterThan(Number(balanceB.value.amount));
+  });
+
+  it('Aggregate output too small', async () => {
+    // Both legs together return slightly less than the combined input
+    await expectRevert(batch(new BN(10 ** 5), new BN(2 * 10 ** 5)));
+  });
+
+  it('Leg amounts above the balance are rejected without clamping', async () => {
+    await expectRevert(batch(values.defaultSupply, new BN(1)));
+  });
+});
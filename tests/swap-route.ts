@@ -0,0 +1,127 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID, createMint, getAssociatedTokenAddressSync, getOrCreateAssociatedTokenAccount, mintTo } from '@solana/spl-token';
+import { Keypair, type PublicKey } from '@solana/web3.js';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type PoolKeys, type TestValues, createValues, derivePoolKeys, expectRevert, mintingTokens, poolRefKey } from './utils';
+
+describe('Swap route', () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+  let mintC: PublicKey;
+  let secondPool: PoolKeys;
+  let holderAccountC: PublicKey;
+
+  const createAndFund = async (mintA: PublicKey, mintB: PublicKey, keys: PoolKeys, index: number) => {
+    await program.methods
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
+      .accounts({ amm: values.ammKey, mintA, mintB, tokenProgramA: TOKEN_PROGRAM_ID, tokenProgramB: TOKEN_PROGRAM_ID, ...keys, pool: keys.poolKey, poolRef: poolRefKey(values.ammKey, index), programConfig: values.programConfig })
+      .rpc();
+    await program.methods
+      .depositLiquidity(values.depositAmountA, values.depositAmountA, new BN(0), null, null, false)
+      .accounts({
+        pool: keys.poolKey,
+        poolAuthority: keys.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: keys.mintLiquidity,
+        mintA,
+        mintB,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: keys.poolAccountA,
+        poolAccountB: keys.poolAccountB,
+        depositorAccountLiquidity: getAssociatedTokenAddressSync(keys.mintLiquidity, values.admin.publicKey, true),
+        depositorAccountA: getAssociatedTokenAddressSync(mintA, values.admin.publicKey, true),
+        depositorAccountB: getAssociatedTokenAddressSync(mintB, values.admin.publicKey, true),
+      })
+      .signers([values.admin])
+      .rpc({ skipPreflight: true });
+  };
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+
+    // Third token, paired with B in a second pool, sorting after B
+    let mintCKeypair = Keypair.generate();
+    while (new BN(mintCKeypair.publicKey.toBytes()).lt(new BN(values.mintBKeypair.publicKey.toBytes()))) {
+      mintCKeypair = Keypair.generate();
+    }
+    mintC = await createMint(connection, values.admin, values.admin.publicKey, null, 6, mintCKeypair);
+    holderAccountC = (await getOrCreateAssociatedTokenAccount(connection, values.admin, mintC, values.admin.publicKey)).address;
+    await mintTo(connection, values.admin, mintC, holderAccountC, values.admin, values.defaultSupply.toNumber());
+
+    await createAndFund(values.mintAKeypair.publicKey, values.mintBKeypair.publicKey, derivePoolKeys(values.ammKey, values.mintAKeypair.publicKey, values.mintBKeypair.publicKey), 0);
+    secondPool = derivePoolKeys(values.ammKey, values.mintBKeypair.publicKey, mintC);
+    await createAndFund(values.mintBKeypair.publicKey, mintC, secondPool, 1);
+  });
+
+  const route = (input: anchor.BN, minOutput: anchor.BN, clampToBalance = false) =>
+    program.methods
+      .swapRoute(input, minOutput, null, clampToBalance)
+      .accounts({
+        amm: values.ammKey,
+        trader: values.admin.publicKey,
+        traderSource: values.holderAccountA,
+        token2022Program: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts(
+        [
+          values.poolKey,
+          values.poolAuthority,
+          values.mintAKeypair.publicKey,
+          values.mintBKeypair.publicKey,
+          values.poolAccountA,
+          values.poolAccountB,
+          values.protocolFeeAccountA,
+          values.holderAccountB,
+          values.poolOracle,
+          secondPool.poolKey,
+          secondPool.poolAuthority,
+          values.mintBKeypair.publicKey,
+          mintC,
+          secondPool.poolAccountA,
+          secondPool.poolAccountB,
+          secondPool.protocolFeeAccountA,
+          holderAccountC,
+          secondPool.poolOracle,
+        ].map((pubkey, i) => ({ pubkey, isSigner: false, isWritable: ![1, 2, 3].includes(i % 9) })),
+      )
+      .signers([values.admin])
+      .rpc({ skipPreflight: true });
+
+  it('Route A to C through B', async () => {
+    const input = new BN(10 ** 5);
+    const balanceB = await connection.getTokenAccountBalance(values.holderAccountB);
+    const balanceC = await connection.getTokenAccountBalance(holderAccountC);
+
+    await route(input, new BN(1));
+
+    // The intermediate token balance is untouched, the final token balance grows
+    expect((await connection.getTokenAccountBalance(values.holderAccountB)).value.amount).to.equal(balanceB.value.amount);
+    expect(Number((await connection.getTokenAccountBalance(holderAccountC)).value.amount)).to.be.greaterThan(Number(balanceC.value.amount));
+  });
+
+  it('Final output too small', async () => {
+    await expectRevert(route(new BN(10 ** 5), new BN(10 ** 5)));
+  });
+
+  it('Input above the balance is rejected without clamping', async () => {
+    await expectRevert(route(values.defaultSupply, new BN(1)));
+  });
+});
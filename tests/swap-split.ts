@@ -0,0 +1,132 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID, getAssociatedTokenAddressSync } from '@solana/spl-token';
+import { PublicKey } from '@solana/web3.js';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type PoolKeys, type TestValues, createValues, denyAddress, derivePoolKeys, expectRevert, mintingTokens, poolRefKey } from './utils';
+
+describe('Swap split', () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+  let pools: PoolKeys[];
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+
+    // The same pair at two fee tiers
+    pools = [];
+    for (const [index, feeTier] of [0, 30].entries()) {
+      const keys = derivePoolKeys(values.ammKey, values.mintAKeypair.publicKey, values.mintBKeypair.publicKey, feeTier);
+      await program.methods
+        .createPool(new BN(0), { constantProduct: {} }, feeTier, 24, null)
+        .accounts({
+          amm: values.ammKey,
+          mintA: values.mintAKeypair.publicKey,
+          mintB: values.mintBKeypair.publicKey,
+          tokenProgramA: TOKEN_PROGRAM_ID,
+          tokenProgramB: TOKEN_PROGRAM_ID,
+          ...keys,
+          pool: keys.poolKey,
+          poolRef: poolRefKey(values.ammKey, index),
+          programConfig: values.programConfig,
+        })
+        .rpc();
+      await program.methods
+        .depositLiquidity(values.depositAmountA, values.depositAmountB, new BN(0), null, null, false)
+        .accounts({
+          pool: keys.poolKey,
+          poolAuthority: keys.poolAuthority,
+          depositor: values.admin.publicKey,
+          mintLiquidity: keys.mintLiquidity,
+          mintA: values.mintAKeypair.publicKey,
+          mintB: values.mintBKeypair.publicKey,
+          tokenProgramA: TOKEN_PROGRAM_ID,
+          tokenProgramB: TOKEN_PROGRAM_ID,
+          poolAccountA: keys.poolAccountA,
+          poolAccountB: keys.poolAccountB,
+          depositorAccountLiquidity: getAssociatedTokenAddressSync(keys.mintLiquidity, values.admin.publicKey, true),
+          depositorAccountA: values.holderAccountA,
+          depositorAccountB: values.holderAccountB,
+        })
+        .signers([values.admin])
+        .rpc({ skipPreflight: true });
+      pools.push(keys);
+    }
+  });
+
+  const split = (input: anchor.BN, splitBps: number[], minOutput: anchor.BN, denylist: PublicKey | null = null, clampToBalance = false) =>
+    program.methods
+      .swapSplit(input, splitBps, minOutput, null, clampToBalance)
+      .accounts({
+        amm: values.ammKey,
+        trader: values.admin.publicKey,
+        traderSource: values.holderAccountA,
+        token2022Program: TOKEN_2022_PROGRAM_ID,
+        denylist,
+      })
+      .remainingAccounts(
+        pools
+          .flatMap((keys) => [
+            keys.poolKey,
+            keys.poolAuthority,
+            values.mintAKeypair.publicKey,
+            values.mintBKeypair.publicKey,
+            keys.poolAccountA,
+            keys.poolAccountB,
+            keys.protocolFeeAccountA,
+            values.holderAccountB,
+            keys.poolOracle,
+          ])
+          .map((pubkey, i) => ({ pubkey, isSigner: false, isWritable: ![1, 2, 3].includes(i % 9) })),
+      )
+      .signers([values.admin])
+      .rpc({ skipPreflight: true });
+
+  it('Splits the input across both fee tiers', async () => {
+    const input = new BN(10 ** 5);
+    const balanceB = await connection.getTokenAccountBalance(values.holderAccountB);
+
+    await split(input, [7000, 3000], new BN(1));
+
+    const first = await connection.getTokenAccountBalance(pools[0].poolAccountA);
+    const second = await connection.getTokenAccountBalance(pools[1].poolAccountA);
+    expect(first.value.amount).to.equal(values.depositAmountA.addn(70000).toString());
+    expect(second.value.amount).to.equal(values.depositAmountA.addn(30000).toString());
+    expect(Number((await connection.getTokenAccountBalance(values.holderAccountB)).value.amount)).to.be.greaterThan(Number(balanceB.value.amount));
+  });
+
+  it('Proportions must sum to 100%', async () => {
+    await expectRevert(split(new BN(10 ** 5), [7000, 2000], new BN(1)));
+  });
+
+  it('Shared output too small', async () => {
+    await expectRevert(split(new BN(10 ** 5), [5000, 5000], new BN(10 ** 5)));
+  });
+
+  it('Denied traders cannot split', async () => {
+    const denylist = await denyAddress(program, values, values.admin.publicKey);
+    await expectRevert(split(new BN(10 ** 5), [5000, 5000], new BN(1), denylist));
+    // The denylist cannot be left out once the AMM has one
+    await expectRevert(split(new BN(10 ** 5), [5000, 5000], new BN(1)));
+  });
+
+  it('Input above the balance is rejected without clamping', async () => {
+    await expectRevert(split(values.defaultSupply, [5000, 5000], new BN(1)));
+  });
+});
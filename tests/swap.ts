@@ -1,9 +1,11 @@
 import * as anchor from '@coral-xyz/anchor';
 import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { Keypair, Transaction } from '@solana/web3.js';
 import { BN } from 'bn.js';
 import { expect } from 'chai';
 import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
-import { type TestValues, createValues, mintingTokens } from './utils';
+import { type TestValues, createValues, expectRevert, mintingTokens, swapOptions } from './utils';
 
 describe('Swap', () => {
   const provider = anchor.AnchorProvider.env();
@@ -17,7 +19,7 @@ describe('Swap', () => {
   beforeEach(async () => {
     values = createValues();
 
-    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey }).rpc();
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
 
     await mintingTokens({
       connection,
@@ -27,21 +29,28 @@ describe('Swap', () => {
     });
 
     await program.methods
-      .createPool()
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
       .accounts({
         amm: values.ammKey,
         pool: values.poolKey,
+        poolRef: values.poolRef,
+        programConfig: values.programConfig,
+        poolOracle: values.poolOracle,
         poolAuthority: values.poolAuthority,
         mintLiquidity: values.mintLiquidity,
         mintA: values.mintAKeypair.publicKey,
         mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
         poolAccountA: values.poolAccountA,
         poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
       })
       .rpc();
 
     await program.methods
-      .depositLiquidity(values.depositAmountA, values.depositAmountB)
+      .depositLiquidity(values.depositAmountA, values.depositAmountB, new BN(0), null, null, false)
       .accounts({
         pool: values.poolKey,
         poolAuthority: values.poolAuthority,
@@ -49,6 +58,8 @@ describe('Swap', () => {
         mintLiquidity: values.mintLiquidity,
         mintA: values.mintAKeypair.publicKey,
         mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
         poolAccountA: values.poolAccountA,
         poolAccountB: values.poolAccountB,
         depositorAccountLiquidity: values.liquidityAccount,
@@ -62,7 +73,7 @@ describe('Swap', () => {
   it('Swap from A to B', async () => {
     const input = new BN(10 ** 6);
     await program.methods
-      .swapExactTokensForTokens(true, input, new BN(100))
+      .swapExactTokensForTokens(values.mintAKeypair.publicKey, input, new BN(100), null, swapOptions())
       .accounts({
         amm: values.ammKey,
         pool: values.poolKey,
@@ -70,8 +81,12 @@ describe('Swap', () => {
         trader: values.admin.publicKey,
         mintA: values.mintAKeypair.publicKey,
         mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
         poolAccountA: values.poolAccountA,
         poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
         traderAccountA: values.holderAccountA,
         traderAccountB: values.holderAccountB,
       })
@@ -83,5 +98,198 @@ describe('Swap', () => {
     expect(traderTokenAccountA.value.amount).to.equal(values.defaultSupply.sub(values.depositAmountA).sub(input).toString());
     expect(Number(traderTokenAccountB.value.amount)).to.be.greaterThan(values.defaultSupply.sub(values.depositAmountB).toNumber());
     expect(Number(traderTokenAccountB.value.amount)).to.be.lessThan(values.defaultSupply.sub(values.depositAmountB).add(input).toNumber());
+
+    // The reentrancy lock is released once the swap completes
+    const pool = await program.account.pool.fetch(values.poolKey);
+    expect(pool.locked).to.be.false;
+  });
+
+  it('Emits a swap event', async () => {
+    const input = new BN(10 ** 6);
+    const signature = await program.methods
+      .swapExactTokensForTokens(values.mintAKeypair.publicKey, input, new BN(100), null, swapOptions())
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        trader: values.admin.publicKey,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+        traderAccountA: values.holderAccountA,
+        traderAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc({ commitment: 'confirmed' });
+
+    const tx = await connection.getTransaction(signature, { commitment: 'confirmed', maxSupportedTransactionVersion: 0 });
+    const parser = new anchor.EventParser(program.programId, program.coder);
+    const events = [...parser.parseLogs(tx.meta.logMessages)];
+    const swapEvent = events.find((event) => event.name === 'SwapEvent');
+    expect(swapEvent).to.not.be.undefined;
+    expect(swapEvent.data.pool.toBase58()).to.equal(values.poolKey.toBase58());
+    expect(swapEvent.data.inputAmount.toString()).to.equal(input.toString());
+    expect(swapEvent.data.reserveA.toString()).to.equal(values.depositAmountA.add(input).toString());
+    // The LP fee left in the pool strictly grows the invariant
+    expect(swapEvent.data.invariantDelta.gtn(0)).to.be.true;
+  });
+
+  it('Expired deadline', async () => {
+    const expired = new BN(Math.floor(Date.now() / 1000) - 60);
+    await expectRevert(
+      program.methods
+        .swapExactTokensForTokens(values.mintAKeypair.publicKey, new BN(10 ** 6), new BN(100), expired, swapOptions())
+        .accounts({
+          amm: values.ammKey,
+          pool: values.poolKey,
+          poolAuthority: values.poolAuthority,
+          trader: values.admin.publicKey,
+          mintA: values.mintAKeypair.publicKey,
+          mintB: values.mintBKeypair.publicKey,
+          tokenProgramA: TOKEN_PROGRAM_ID,
+          tokenProgramB: TOKEN_PROGRAM_ID,
+          poolAccountA: values.poolAccountA,
+          poolAccountB: values.poolAccountB,
+          protocolFeeAccountA: values.protocolFeeAccountA,
+          protocolFeeAccountB: values.protocolFeeAccountB,
+          traderAccountA: values.holderAccountA,
+          traderAccountB: values.holderAccountB,
+        })
+        .signers([values.admin])
+        .rpc(),
+    );
+  });
+
+  const swapAboveBalance = (clampToBalance: boolean) =>
+    program.methods
+      .swapExactTokensForTokens(values.mintAKeypair.publicKey, values.defaultSupply, new BN(100), null, swapOptions({ clampToBalance }))
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        trader: values.admin.publicKey,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+        traderAccountA: values.holderAccountA,
+        traderAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc();
+
+  it('Input above the balance is rejected unless clamping', async () => {
+    await expectRevert(swapAboveBalance(false));
+
+    // Clamping swaps the whole remaining balance
+    await swapAboveBalance(true);
+    const traderTokenAccountA = await connection.getTokenAccountBalance(values.holderAccountA);
+    expect(traderTokenAccountA.value.amount).to.equal('0');
+  });
+
+  it('Input mint must belong to the pool', async () => {
+    await expectRevert(
+      program.methods
+        .swapExactTokensForTokens(values.mintLiquidity, new BN(10 ** 6), new BN(100), null, swapOptions())
+        .accounts({
+          amm: values.ammKey,
+          pool: values.poolKey,
+          poolAuthority: values.poolAuthority,
+          trader: values.admin.publicKey,
+          mintA: values.mintAKeypair.publicKey,
+          mintB: values.mintBKeypair.publicKey,
+          tokenProgramA: TOKEN_PROGRAM_ID,
+          tokenProgramB: TOKEN_PROGRAM_ID,
+          poolAccountA: values.poolAccountA,
+          poolAccountB: values.poolAccountB,
+          protocolFeeAccountA: values.protocolFeeAccountA,
+          protocolFeeAccountB: values.protocolFeeAccountB,
+          traderAccountA: values.holderAccountA,
+          traderAccountB: values.holderAccountB,
+        })
+        .signers([values.admin])
+        .rpc(),
+    );
+  });
+
+  it('Nested accounts must match the top-level accounts', async () => {
+    const ix = await program.methods
+      .swapExactTokensForTokens(values.mintAKeypair.publicKey, new BN(10 ** 6), new BN(100), null, swapOptions())
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        trader: values.admin.publicKey,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+        traderAccountA: values.holderAccountA,
+        traderAccountB: values.holderAccountB,
+      })
+      .instruction();
+
+    // Point the pool authority of the nested pool token accounts somewhere else
+    const nestedAuthority = ix.keys.filter((key) => key.pubkey.equals(values.poolAuthority))[1];
+    nestedAuthority.pubkey = Keypair.generate().publicKey;
+
+    await expectRevert(provider.sendAndConfirm(new Transaction().add(ix), [values.admin]));
+  });
+
+  const swapLarge = (allowPartialFill: boolean) =>
+    program.methods
+      .swapExactTokensForTokens(values.mintBKeypair.publicKey, new BN(10 ** 6), new BN(100), null, swapOptions({ allowPartialFill }))
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        trader: values.admin.publicKey,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+        traderAccountA: values.holderAccountA,
+        traderAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc({ commitment: 'confirmed' });
+
+  it('Partial fill stops at the trade size cap', async () => {
+    // Cap single trades at 10% of the input reserve
+    await program.methods.updatePriceImpactConfig(10000, 0, true).accounts({ amm: values.ammKey, admin: values.admin.publicKey }).signers([values.admin]).rpc();
+
+    await expectRevert(swapLarge(false));
+
+    const signature = await swapLarge(true);
+    const tx = await connection.getTransaction(signature, { commitment: 'confirmed', maxSupportedTransactionVersion: 0 });
+    const parser = new anchor.EventParser(program.programId, program.coder);
+    const partialFill = [...parser.parseLogs(tx.meta.logMessages)].find((event) => event.name === 'PartialFillEvent');
+    expect(partialFill.data.requestedAmount.toNumber()).to.equal(10 ** 6);
+    // The search is bounded to 16 steps, so the fill is within 10^6 / 2^16 of the 10% cap on the 10^6 B reserve
+    const cap = values.depositAmountB.toNumber() / 10;
+    expect(partialFill.data.filledAmount.toNumber()).to.be.at.least(cap - 16);
+    // Protocol fees are taken before the input reaches the pool
+    expect(partialFill.data.filledAmount.toNumber()).to.be.at.most(cap * 1.05);
+
+    // Only the filled amount left the trader account
+    const traderTokenAccountB = await connection.getTokenAccountBalance(values.holderAccountB);
+    expect(traderTokenAccountB.value.amount).to.equal(values.defaultSupply.sub(values.depositAmountB).sub(partialFill.data.filledAmount).toString());
   });
-});
\ No newline at end of file
+});
@@ -0,0 +1,180 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { BN } from 'bn.js';
+import { Keypair, PublicKey } from '@solana/web3.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type TestValues, createValues, expectRevert } from './utils';
+
+describe('Timelock', () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+
+  const wait = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+  // Proposers pay the rent of the pending change
+  const fund = async (key: PublicKey) =>
+    provider.connection.confirmTransaction(await provider.connection.requestAirdrop(key, 10 ** 9));
+
+  const feeConfig = (overrides = {}) => ({
+    strategy: { fixed: {} },
+    minFeeBps: 10,
+    maxFeeBps: 100,
+    baseFeeBps: 30,
+    adjustmentFactor: 1000,
+    protocolFeeBps: 0,
+    ilCompensationFeeBps: 0,
+    feeAToBBps: null,
+    feeBToABps: null,
+    ...overrides,
+  });
+
+  const pendingChangeKey = (proposer: PublicKey, id: number) =>
+    PublicKey.findProgramAddressSync(
+      [values.ammKey.toBuffer(), proposer.toBuffer(), new BN(id).toArrayLike(Buffer, 'le', 8), Buffer.from('pending_change')],
+      program.programId,
+    )[0];
+
+  const setDelay = (delay: number) =>
+    program.methods
+      .setTimelockDelay(new BN(delay))
+      .accounts({ amm: values.ammKey, admin: values.admin.publicKey })
+      .signers([values.admin])
+      .rpc();
+
+  const updateFeeConfig = (config: ReturnType<typeof feeConfig>) =>
+    program.methods
+      .updateFeeConfig(config)
+      .accounts({ amm: values.ammKey, admin: values.admin.publicKey })
+      .signers([values.admin])
+      .rpc();
+
+  const propose = async (id: number, change: any, proposer = values.admin) => {
+    const pendingChange = pendingChangeKey(proposer.publicKey, id);
+    await program.methods
+      .proposeParameterChange(new BN(id), change)
+      .accounts({ amm: values.ammKey, pendingChange, proposer: proposer.publicKey })
+      .signers([proposer])
+      .rpc();
+    return pendingChange;
+  };
+
+  const queue = (pendingChange: PublicKey) =>
+    program.methods
+      .queueParameterChange()
+      .accounts({ amm: values.ammKey, pendingChange, admin: values.admin.publicKey })
+      .signers([values.admin])
+      .rpc();
+
+  const execute = (pendingChange: PublicKey, proposer = values.admin.publicKey) =>
+    program.methods
+      .executeParameterChange()
+      .accounts({ amm: values.ammKey, pendingChange, pool: null, proposer })
+      .rpc();
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+    await fund(values.admin.publicKey);
+  });
+
+  it('Fee increases wait for the delay', async () => {
+    await setDelay(2);
+
+    // Lowering fees stays immediate, raising them or touching the protocol share does not
+    await updateFeeConfig(feeConfig({ baseFeeBps: 20 }));
+    await expectRevert(updateFeeConfig(feeConfig({ baseFeeBps: 50 })));
+    await expectRevert(updateFeeConfig(feeConfig({ baseFeeBps: 20, protocolFeeBps: 1000 })));
+
+    const pendingChange = await propose(0, { feeConfig: [feeConfig({ baseFeeBps: 50 })] });
+    await expectRevert(execute(pendingChange));
+
+    await queue(pendingChange);
+    const queued = await program.account.pendingChange.fetch(pendingChange);
+    expect(queued.eta.toNumber()).to.be.greaterThan(queued.proposedAt.toNumber());
+    await expectRevert(execute(pendingChange));
+
+    await wait(3000);
+    await execute(pendingChange);
+    const amm = await program.account.amm.fetch(values.ammKey);
+    expect(amm.feeConfig.baseFeeBps).to.equal(50);
+    expect(amm.fee).to.equal(50);
+    expect(await provider.connection.getAccountInfo(pendingChange)).to.equal(null);
+  });
+
+  it('Proposals need the matching role', async () => {
+    const feeManager = Keypair.generate();
+    await program.methods
+      .grantRole({ feeManager: {} }, feeManager.publicKey)
+      .accounts({ amm: values.ammKey, admin: values.admin.publicKey })
+      .signers([values.admin])
+      .rpc();
+    await fund(feeManager.publicKey);
+
+    await propose(0, { feeConfig: [feeConfig({ maxFeeBps: 200 })] }, feeManager);
+    await expectRevert(propose(1, { timelockDelay: [new BN(0)] }, feeManager));
+  });
+
+  it('Shortening the delay goes through the timelock', async () => {
+    await setDelay(60);
+    await expectRevert(setDelay(30));
+
+    const pendingChange = await propose(0, { timelockDelay: [new BN(30)] });
+    await queue(pendingChange);
+    await expectRevert(execute(pendingChange));
+
+    // The owner can drop a queued change
+    await program.methods
+      .cancelParameterChange()
+      .accounts({ amm: values.ammKey, pendingChange, proposer: values.admin.publicKey, authority: values.admin.publicKey })
+      .signers([values.admin])
+      .rpc();
+    expect(await provider.connection.getAccountInfo(pendingChange)).to.equal(null);
+    expect((await program.account.amm.fetch(values.ammKey)).timelockDelay.toNumber()).to.equal(60);
+  });
+
+  it('Withdrawal fee, volatility and fee side changes go through the timelock', async () => {
+    await setDelay(2);
+    const amm = await program.account.amm.fetch(values.ammKey);
+    const withdrawalFeeConfig = { feeBps: 100, decayPeriod: new BN(3600) };
+
+    await expectRevert(
+      program.methods
+        .updateWithdrawalFeeConfig(withdrawalFeeConfig)
+        .accounts({ amm: values.ammKey, admin: values.admin.publicKey })
+        .signers([values.admin])
+        .rpc(),
+    );
+    await expectRevert(
+      program.methods
+        .updateVolatilityConfig(amm.volatilityConfig)
+        .accounts({ amm: values.ammKey, admin: values.admin.publicKey })
+        .signers([values.admin])
+        .rpc(),
+    );
+    await expectRevert(
+      program.methods
+        .updateFeeSide(true)
+        .accounts({ amm: values.ammKey, admin: values.admin.publicKey })
+        .signers([values.admin])
+        .rpc(),
+    );
+
+    const withdrawalFeeChange = await propose(0, { withdrawalFeeConfig: [withdrawalFeeConfig] });
+    const feeSideChange = await propose(1, { feeSide: [true] });
+    await queue(withdrawalFeeChange);
+    await queue(feeSideChange);
+    await wait(3000);
+    await execute(withdrawalFeeChange);
+    await execute(feeSideChange);
+
+    const updated = await program.account.amm.fetch(values.ammKey);
+    expect(updated.withdrawalFeeConfig.feeBps).to.equal(100);
+    expect(updated.feeOnOutput).to.equal(true);
+  });
+});
@@ -0,0 +1,134 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import {
+  ExtensionType,
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  createAssociatedTokenAccountIdempotent,
+  createInitializeMintInstruction,
+  createInitializeTransferFeeConfigInstruction,
+  createMint,
+  getAssociatedTokenAddressSync,
+  getMintLen,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from '@solana/spl-token';
+import { SystemProgram, Transaction, sendAndConfirmTransaction } from '@solana/web3.js';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type PoolKeys, type TestValues, createValues, derivePoolKeys, swapOptions } from './utils';
+
+describe('Token-2022', () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  // 1% transfer fee on token B
+  const transferFeeBps = 100;
+
+  let values: TestValues;
+  let keys: PoolKeys;
+  let holderAccountB: anchor.web3.PublicKey;
+
+  beforeEach(async () => {
+    values = createValues();
+    const mintA = values.mintAKeypair.publicKey;
+    const mintB = values.mintBKeypair.publicKey;
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+
+    const signature = await connection.requestAirdrop(values.admin.publicKey, 10 * anchor.web3.LAMPORTS_PER_SOL);
+    await connection.confirmTransaction({ signature, ...(await connection.getLatestBlockhash()) });
+
+    // Token A is a legacy SPL token
+    await createMint(connection, values.admin, values.admin.publicKey, null, 6, values.mintAKeypair);
+    await getOrCreateAssociatedTokenAccount(connection, values.admin, mintA, values.admin.publicKey);
+    await mintTo(connection, values.admin, mintA, values.holderAccountA, values.admin, values.defaultSupply.toNumber());
+
+    // Token B is a Token-2022 mint with the transfer fee extension
+    const mintLen = getMintLen([ExtensionType.TransferFeeConfig]);
+    await sendAndConfirmTransaction(
+      connection,
+      new Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: values.admin.publicKey,
+          newAccountPubkey: mintB,
+          space: mintLen,
+          lamports: await connection.getMinimumBalanceForRentExemption(mintLen),
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeTransferFeeConfigInstruction(mintB, values.admin.publicKey, values.admin.publicKey, transferFeeBps, BigInt(10 ** 12), TOKEN_2022_PROGRAM_ID),
+        createInitializeMintInstruction(mintB, 6, values.admin.publicKey, null, TOKEN_2022_PROGRAM_ID),
+      ),
+      [values.admin, values.mintBKeypair],
+    );
+    holderAccountB = await createAssociatedTokenAccountIdempotent(connection, values.admin, mintB, values.admin.publicKey, {}, TOKEN_2022_PROGRAM_ID);
+    await mintTo(connection, values.admin, mintB, holderAccountB, values.admin, values.defaultSupply.toNumber(), [], {}, TOKEN_2022_PROGRAM_ID);
+
+    keys = derivePoolKeys(values.ammKey, mintA, mintB);
+    keys.poolAccountB = getAssociatedTokenAddressSync(mintB, keys.poolAuthority, true, TOKEN_2022_PROGRAM_ID);
+
+    await program.methods
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
+      .accounts({ amm: values.ammKey, mintA, mintB, tokenProgramA: TOKEN_PROGRAM_ID, tokenProgramB: TOKEN_2022_PROGRAM_ID, ...keys, pool: keys.poolKey, poolRef: values.poolRef, programConfig: values.programConfig })
+      .rpc();
+
+    await program.methods
+      .depositLiquidity(values.depositAmountA, values.depositAmountA, new BN(0), null, null, false)
+      .accounts({
+        pool: keys.poolKey,
+        poolAuthority: keys.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: keys.mintLiquidity,
+        mintA,
+        mintB,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_2022_PROGRAM_ID,
+        poolAccountA: keys.poolAccountA,
+        poolAccountB: keys.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc({ skipPreflight: true });
+  });
+
+  it('Deposit credits the amount received after transfer fees', async () => {
+    const poolB = await connection.getTokenAccountBalance(keys.poolAccountB);
+    const expected = values.depositAmountA.muln(10000 - transferFeeBps).divn(10000);
+    expect(poolB.value.amount).to.equal(expected.toString());
+  });
+
+  it('Swap into a transfer fee token', async () => {
+    const input = new BN(10 ** 5);
+    const before = await connection.getTokenAccountBalance(holderAccountB);
+
+    await program.methods
+      .swapExactTokensForTokens(values.mintAKeypair.publicKey, input, new BN(1), null, swapOptions())
+      .accounts({
+        amm: values.ammKey,
+        pool: keys.poolKey,
+        poolAuthority: keys.poolAuthority,
+        trader: values.admin.publicKey,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_2022_PROGRAM_ID,
+        poolAccountA: keys.poolAccountA,
+        poolAccountB: keys.poolAccountB,
+        protocolFeeAccountA: keys.protocolFeeAccountA,
+        protocolFeeAccountB: keys.protocolFeeAccountB,
+        traderAccountA: values.holderAccountA,
+        traderAccountB: holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc({ skipPreflight: true });
+
+    const after = await connection.getTokenAccountBalance(holderAccountB);
+    expect(Number(after.value.amount)).to.be.greaterThan(Number(before.value.amount));
+  });
+});
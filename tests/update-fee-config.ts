@@ -0,0 +1,89 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { Keypair } from '@solana/web3.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type TestValues, createValues, expectRevert } from './utils';
+
+describe('Update fee config', () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+
+  const dynamicConfig = {
+    strategy: { dynamic: {} },
+    minFeeBps: 10,
+    maxFeeBps: 100,
+    baseFeeBps: 30,
+    adjustmentFactor: 1000,
+    protocolFeeBps: 0,
+    ilCompensationFeeBps: 0,
+    feeAToBBps: null,
+    feeBToABps: null,
+  };
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+  });
+
+  it('Switch to dynamic fees', async () => {
+    await program.methods
+      .updateFeeConfig(dynamicConfig)
+      .accounts({ amm: values.ammKey, admin: values.admin.publicKey })
+      .signers([values.admin])
+      .rpc();
+
+    const ammAccount = await program.account.amm.fetch(values.ammKey);
+    expect(ammAccount.feeConfig.strategy).to.deep.equal({ dynamic: {} });
+    expect(ammAccount.feeConfig.baseFeeBps).to.equal(30);
+    expect(ammAccount.fee).to.equal(30);
+  });
+
+  it('Invalid range', async () => {
+    await expectRevert(
+      program.methods
+        .updateFeeConfig({ ...dynamicConfig, minFeeBps: 200 })
+        .accounts({ amm: values.ammKey, admin: values.admin.publicKey })
+        .signers([values.admin])
+        .rpc(),
+    );
+  });
+
+  it('Directional fee override', async () => {
+    await program.methods
+      .updateFeeConfig({ ...dynamicConfig, feeBToABps: 200 })
+      .accounts({ amm: values.ammKey, admin: values.admin.publicKey })
+      .signers([values.admin])
+      .rpc();
+
+    const ammAccount = await program.account.amm.fetch(values.ammKey);
+    expect(ammAccount.feeConfig.feeAToBBps).to.be.null;
+    expect(ammAccount.feeConfig.feeBToABps).to.equal(200);
+  });
+
+  it('Invalid directional fee', async () => {
+    await expectRevert(
+      program.methods
+        .updateFeeConfig({ ...dynamicConfig, feeAToBBps: 10000 })
+        .accounts({ amm: values.ammKey, admin: values.admin.publicKey })
+        .signers([values.admin])
+        .rpc(),
+    );
+  });
+
+  it('Not the admin', async () => {
+    const stranger = Keypair.generate();
+    await expectRevert(
+      program.methods
+        .updateFeeConfig(dynamicConfig)
+        .accounts({ amm: values.ammKey, admin: stranger.publicKey })
+        .signers([stranger])
+        .rpc(),
+    );
+  });
+});
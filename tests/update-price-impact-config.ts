@@ -0,0 +1,47 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { Keypair } from '@solana/web3.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type TestValues, createValues, expectRevert } from './utils';
+
+describe('Update price impact config', () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+  });
+
+  const updatePriceImpactConfig = (maxSlippageBps: number, dynamicAdjustmentFactor: number, enabled: boolean, admin = values.admin) =>
+    program.methods
+      .updatePriceImpactConfig(maxSlippageBps, dynamicAdjustmentFactor, enabled)
+      .accounts({ amm: values.ammKey, admin: admin.publicKey })
+      .signers([admin])
+      .rpc();
+
+  it('Enable price impact protection', async () => {
+    await updatePriceImpactConfig(100, 500, true);
+
+    const amm = await program.account.amm.fetch(values.ammKey);
+    expect(amm.priceImpactConfig.enabled).to.be.true;
+    expect(amm.priceImpactConfig.maxSlippageBps).to.equal(100);
+    expect(amm.priceImpactConfig.dynamicAdjustmentFactor).to.equal(500);
+    // The trade size limit is left untouched
+    expect(amm.priceImpactConfig.maxTradePctBps).to.equal(1000);
+  });
+
+  it('Invalid slippage', async () => {
+    await expectRevert(updatePriceImpactConfig(10001, 1000, true));
+  });
+
+  it('Not the admin', async () => {
+    await expectRevert(updatePriceImpactConfig(100, 1000, true, Keypair.generate()));
+  });
+});
@@ -0,0 +1,151 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { Keypair } from '@solana/web3.js';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type TestValues, createValues, expectRevert, mintingTokens, swapOptions } from './utils';
+
+describe('Update volatility config', () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+
+  const enabledConfig = {
+    enabled: true,
+    protectionFactor: 500,
+    decayFactor: 950,
+    minVolatilityThreshold: 100,
+    windowSize: 24,
+    minSamples: 2,
+    decayLambda: new BN(950),
+    compensationFactor: new BN(1000),
+    compensationPeriod: new BN(86400),
+    updateInterval: new BN(3600),
+    circuitBreakerThreshold: 0,
+    circuitBreakerCooldown: new BN(3600),
+    minSampleIntervalSecs: new BN(0),
+  };
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+
+    await program.methods
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolRef: values.poolRef,
+        programConfig: values.programConfig,
+        poolOracle: values.poolOracle,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+      })
+      .rpc();
+
+    await program.methods
+      .depositLiquidity(values.depositAmountA, values.depositAmountB, new BN(0), null, null, false)
+      .accounts({
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc({ skipPreflight: true });
+  });
+
+  const updateVolatilityConfig = (config: typeof enabledConfig, admin = values.admin) =>
+    program.methods
+      .updateVolatilityConfig(config)
+      .accounts({ amm: values.ammKey, admin: admin.publicKey })
+      .signers([admin])
+      .rpc();
+
+  const swap = (poolOracle = values.poolOracle) =>
+    program.methods
+      .swapExactTokensForTokens(values.mintBKeypair.publicKey, new BN(10 ** 4), new BN(0), null, swapOptions())
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolOracle,
+        poolAuthority: values.poolAuthority,
+        trader: values.admin.publicKey,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+        traderAccountA: values.holderAccountA,
+        traderAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc();
+
+  it('Enable volatility tracking', async () => {
+    await updateVolatilityConfig(enabledConfig);
+
+    const amm = await program.account.amm.fetch(values.ammKey);
+    expect(amm.volatilityConfig.enabled).to.be.true;
+    expect(amm.volatilityConfig.minSampleIntervalSecs.toNumber()).to.equal(0);
+
+    // Swaps now record price samples in the pool oracle
+    await swap();
+    const poolOracle = await program.account.poolOracle.fetch(values.poolOracle);
+    expect(poolOracle.volatilityTracker.currentIndex).to.equal(1);
+    expect(poolOracle.volatilityTracker.lastUpdated.toNumber()).to.be.greaterThan(0);
+  });
+
+  it('Swaps must provide the pool oracle once enabled', async () => {
+    await updateVolatilityConfig(enabledConfig);
+
+    await expectRevert(swap(null));
+  });
+
+  it('Invalid decay', async () => {
+    await expectRevert(updateVolatilityConfig({ ...enabledConfig, decayLambda: new BN(0) }));
+    await expectRevert(updateVolatilityConfig({ ...enabledConfig, decayFactor: 1001 }));
+  });
+
+  it('Invalid sample window', async () => {
+    await expectRevert(updateVolatilityConfig({ ...enabledConfig, minSamples: 30 }));
+  });
+
+  it('Not the admin', async () => {
+    await expectRevert(updateVolatilityConfig(enabledConfig, Keypair.generate()));
+  });
+});
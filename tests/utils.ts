@@ -4,7 +4,7 @@ import { type Connection, Keypair, PublicKey, type Signer } from '@solana/web3.j
 import { BN } from 'bn.js';
 
 export async function sleep(seconds: number) {
-  new Promise((resolve) => setTimeout(resolve, seconds * 1000));
+  return new Promise((resolve) => setTimeout(resolve, seconds * 1000));
 }
 
 export const generateSeededKeypair = (seed: string) => {
@@ -20,6 +20,14 @@ export const expectRevert = async (promise: Promise<any>) => {
   }
 };
 
+// Options of exact-input swaps, every behaviour is off unless overridden
+export const swapOptions = (options: { allowPartialFill?: boolean; maxPriceX64?: anchor.BN | null; clampToBalance?: boolean } = {}) => ({
+  allowPartialFill: false,
+  maxPriceX64: null,
+  clampToBalance: false,
+  ...options,
+});
+
 
 export const mintingTokens = async ({
   connection,
@@ -77,6 +85,9 @@ export interface TestValues {
   ammKey: PublicKey;
   minimumLiquidity: anchor.BN;
   poolKey: PublicKey;
+  poolRef: PublicKey;
+  programConfig: PublicKey;
+  poolOracle: PublicKey;
   poolAuthority: PublicKey;
   mintLiquidity: PublicKey;
   depositAmountA: anchor.BN;
@@ -84,6 +95,8 @@ export interface TestValues {
   liquidityAccount: PublicKey;
   poolAccountA: PublicKey;
   poolAccountB: PublicKey;
+  protocolFeeAccountA: PublicKey;
+  protocolFeeAccountB: PublicKey;
   holderAccountA: PublicKey;
   holderAccountB: PublicKey;
 }
@@ -104,15 +117,23 @@ export function createValues(defaults?: TestValuesDefaults): TestValues {
   }
 
   const poolAuthority = PublicKey.findProgramAddressSync(
-    [ammKey.toBuffer(), mintAKeypair.publicKey.toBuffer(), mintBKeypair.publicKey.toBuffer(), Buffer.from('authority')],
+    [ammKey.toBuffer(), mintAKeypair.publicKey.toBuffer(), mintBKeypair.publicKey.toBuffer(), feeTierSeed(0), Buffer.from('authority')],
     anchor.workspace.AnchorSplAmm.programId,
   )[0];
   const mintLiquidity = PublicKey.findProgramAddressSync(
-    [ammKey.toBuffer(), mintAKeypair.publicKey.toBuffer(), mintBKeypair.publicKey.toBuffer(), Buffer.from('liquidity')],
+    [ammKey.toBuffer(), mintAKeypair.publicKey.toBuffer(), mintBKeypair.publicKey.toBuffer(), feeTierSeed(0), Buffer.from('liquidity')],
     anchor.workspace.AnchorSplAmm.programId,
   )[0];
   const poolKey = PublicKey.findProgramAddressSync(
-    [ammKey.toBuffer(), mintAKeypair.publicKey.toBuffer(), mintBKeypair.publicKey.toBuffer()],
+    [ammKey.toBuffer(), mintAKeypair.publicKey.toBuffer(), mintBKeypair.publicKey.toBuffer(), feeTierSeed(0)],
+    anchor.workspace.AnchorSplAmm.programId,
+  )[0];
+  const protocolFeeAccountA = PublicKey.findProgramAddressSync(
+    [poolAuthority.toBuffer(), mintAKeypair.publicKey.toBuffer(), Buffer.from('protocol_fee')],
+    anchor.workspace.AnchorSplAmm.programId,
+  )[0];
+  const protocolFeeAccountB = PublicKey.findProgramAddressSync(
+    [poolAuthority.toBuffer(), mintBKeypair.publicKey.toBuffer(), Buffer.from('protocol_fee')],
     anchor.workspace.AnchorSplAmm.programId,
   )[0];
   return {
@@ -124,9 +145,14 @@ export function createValues(defaults?: TestValuesDefaults): TestValues {
     mintBKeypair,
     mintLiquidity,
     poolKey,
+    poolRef: poolRefKey(ammKey, 0),
+    programConfig: programConfigKey(),
+    poolOracle: poolOracleKey(poolKey),
     poolAuthority,
     poolAccountA: getAssociatedTokenAddressSync(mintAKeypair.publicKey, poolAuthority, true),
     poolAccountB: getAssociatedTokenAddressSync(mintBKeypair.publicKey, poolAuthority, true),
+    protocolFeeAccountA,
+    protocolFeeAccountB,
     liquidityAccount: getAssociatedTokenAddressSync(mintLiquidity, admin.publicKey, true),
     holderAccountA: getAssociatedTokenAddressSync(mintAKeypair.publicKey, admin.publicKey, true),
     holderAccountB: getAssociatedTokenAddressSync(mintBKeypair.publicKey, admin.publicKey, true),
@@ -135,4 +161,104 @@ export function createValues(defaults?: TestValuesDefaults): TestValues {
     minimumLiquidity: new BN(100),
     defaultSupply: new BN(100 * 10 ** 6),
   };
-}
\ No newline at end of file
+}
+
+// Registry entry of the pool created at the given index of an AMM
+export function poolRefKey(ammKey: PublicKey, index: number): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [ammKey.toBuffer(), new BN(index).toArrayLike(Buffer, 'le', 8), Buffer.from('pool_ref')],
+    anchor.workspace.AnchorSplAmm.programId,
+  )[0];
+}
+
+// Global program config holding the feature flags
+export function programConfigKey(): PublicKey {
+  return PublicKey.findProgramAddressSync([Buffer.from('program_config')], anchor.workspace.AnchorSplAmm.programId)[0];
+}
+
+const BPF_LOADER_UPGRADEABLE = new PublicKey('BPFLoaderUpgradeab1e11111111111111111111111');
+
+// Feature flags of the program config, everything enabled unless overridden
+export const featureFlags = (
+  overrides: { flashLoansEnabled?: boolean; token2022Enabled?: boolean; permissionlessAmms?: boolean; defaultProtocolFeeBps?: number } = {},
+) => ({
+  flashLoansEnabled: true,
+  token2022Enabled: true,
+  permissionlessAmms: true,
+  defaultProtocolFeeBps: 0,
+  ...overrides,
+});
+
+// Sets the global feature flags, creating the config with the provider wallet (the upgrade authority) on first use.
+// The config is shared by every suite, so callers restore the defaults when done
+export async function setFeatureFlags(program: anchor.Program<any>, overrides: Parameters<typeof featureFlags>[0] = {}) {
+  const programConfig = programConfigKey();
+  const authority = program.provider.publicKey;
+  if (await program.account.programConfig.fetchNullable(programConfig)) {
+    await program.methods.updateProgramConfig(featureFlags(overrides)).accounts({ programConfig, authority }).rpc();
+    return;
+  }
+  const programData = PublicKey.findProgramAddressSync([program.programId.toBuffer()], BPF_LOADER_UPGRADEABLE)[0];
+  await program.methods
+    .createProgramConfig(featureFlags(overrides))
+    .accounts({ programConfig, program: program.programId, programData, authority })
+    .rpc();
+}
+
+// Creates the AMM denylist with the given address on it, returning the denylist account to pass to checked instructions
+export async function denyAddress(program: anchor.Program<any>, values: TestValues, address: PublicKey): Promise<PublicKey> {
+  const denylist = PublicKey.findProgramAddressSync([values.ammKey.toBuffer(), Buffer.from('denylist')], program.programId)[0];
+  const accounts = { amm: values.ammKey, denylist, admin: values.admin.publicKey };
+  await program.methods.createDenylist().accounts(accounts).signers([values.admin]).rpc();
+  await program.methods.addToDenylist(address).accounts(accounts).signers([values.admin]).rpc();
+  return denylist;
+}
+
+// Volatility oracle stored alongside each pool
+export function poolOracleKey(poolKey: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync([poolKey.toBuffer(), Buffer.from('pool_oracle')], anchor.workspace.AnchorSplAmm.programId)[0];
+}
+
+// Pools only accept mints in canonical order (mint A < mint B)
+export function sortMints(mintX: PublicKey, mintY: PublicKey): [PublicKey, PublicKey] {
+  return new BN(mintX.toBytes()).lt(new BN(mintY.toBytes())) ? [mintX, mintY] : [mintY, mintX];
+}
+
+export interface PoolKeys {
+  poolKey: PublicKey;
+  poolOracle: PublicKey;
+  poolAuthority: PublicKey;
+  mintLiquidity: PublicKey;
+  poolAccountA: PublicKey;
+  poolAccountB: PublicKey;
+  protocolFeeAccountA: PublicKey;
+  protocolFeeAccountB: PublicKey;
+}
+
+// Pool PDAs include the fee tier as a little-endian u16
+export function feeTierSeed(feeTier: number): Buffer {
+  return new BN(feeTier).toArrayLike(Buffer, 'le', 2);
+}
+
+export function derivePoolKeys(ammKey: PublicKey, mintA: PublicKey, mintB: PublicKey, feeTier = 0): PoolKeys {
+  const programId = anchor.workspace.AnchorSplAmm.programId;
+  const poolKey = PublicKey.findProgramAddressSync([ammKey.toBuffer(), mintA.toBuffer(), mintB.toBuffer(), feeTierSeed(feeTier)], programId)[0];
+  const poolAuthority = PublicKey.findProgramAddressSync(
+    [ammKey.toBuffer(), mintA.toBuffer(), mintB.toBuffer(), feeTierSeed(feeTier), Buffer.from('authority')],
+    programId,
+  )[0];
+  const mintLiquidity = PublicKey.findProgramAddressSync(
+    [ammKey.toBuffer(), mintA.toBuffer(), mintB.toBuffer(), feeTierSeed(feeTier), Buffer.from('liquidity')],
+    programId,
+  )[0];
+  return {
+    poolKey,
+    poolOracle: poolOracleKey(poolKey),
+    poolAuthority,
+    mintLiquidity,
+    poolAccountA: getAssociatedTokenAddressSync(mintA, poolAuthority, true),
+    poolAccountB: getAssociatedTokenAddressSync(mintB, poolAuthority, true),
+    protocolFeeAccountA: PublicKey.findProgramAddressSync([poolAuthority.toBuffer(), mintA.toBuffer(), Buffer.from('protocol_fee')], programId)[0],
+    protocolFeeAccountB: PublicKey.findProgramAddressSync([poolAuthority.toBuffer(), mintB.toBuffer(), Buffer.from('protocol_fee')], programId)[0],
+  };
+}
@@ -0,0 +1,205 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_PROGRAM_ID, getAssociatedTokenAddressSync, transfer } from '@solana/spl-token';
+import { PublicKey } from '@solana/web3.js';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type TestValues, createValues, expectRevert, mintingTokens } from './utils';
+
+describe('Auto-compounding vault', () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+
+    await program.methods
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolRef: values.poolRef,
+        programConfig: values.programConfig,
+        poolOracle: values.poolOracle,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+      })
+      .rpc();
+
+    await program.methods
+      .depositLiquidity(values.depositAmountA, values.depositAmountB, new BN(0), null, null, false)
+      .accounts({
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc();
+  });
+
+  const vaultKey = () =>
+    PublicKey.findProgramAddressSync([values.poolKey.toBuffer(), Buffer.from('vault')], program.programId)[0];
+
+  const shareMint = () =>
+    PublicKey.findProgramAddressSync([vaultKey().toBuffer(), Buffer.from('vault_share')], program.programId)[0];
+
+  const lpVault = () =>
+    PublicKey.findProgramAddressSync([vaultKey().toBuffer(), Buffer.from('escrow')], program.programId)[0];
+
+  const vaultAccount = (mint: PublicKey) => getAssociatedTokenAddressSync(mint, vaultKey(), true);
+
+  const sharesAccount = () => getAssociatedTokenAddressSync(shareMint(), values.admin.publicKey, true);
+
+  beforeEach(async () => {
+    await program.methods
+      .createVault()
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        vault: vaultKey(),
+        mintLiquidity: values.mintLiquidity,
+        shareMint: shareMint(),
+        lpVault: lpVault(),
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        vaultAccountA: vaultAccount(values.mintAKeypair.publicKey),
+        vaultAccountB: vaultAccount(values.mintBKeypair.publicKey),
+        admin: values.admin.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+      })
+      .signers([values.admin])
+      .rpc();
+  });
+
+  const vaultDeposit = (liquidity: number) =>
+    program.methods
+      .vaultDeposit(new BN(liquidity))
+      .accounts({
+        vault: vaultKey(),
+        shareMint: shareMint(),
+        lpVault: lpVault(),
+        mintLiquidity: values.mintLiquidity,
+        owner: values.admin.publicKey,
+        ownerAccountLiquidity: values.liquidityAccount,
+        ownerAccountShares: sharesAccount(),
+      })
+      .signers([values.admin])
+      .rpc();
+
+  const vaultWithdraw = (shares: number) =>
+    program.methods
+      .vaultWithdraw(new BN(shares))
+      .accounts({
+        vault: vaultKey(),
+        shareMint: shareMint(),
+        lpVault: lpVault(),
+        mintLiquidity: values.mintLiquidity,
+        owner: values.admin.publicKey,
+        ownerAccountLiquidity: values.liquidityAccount,
+        ownerAccountShares: sharesAccount(),
+      })
+      .signers([values.admin])
+      .rpc();
+
+  const compoundVault = () =>
+    program.methods
+      .compoundVault()
+      .accounts({
+        vault: vaultKey(),
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        vaultAccountA: vaultAccount(values.mintAKeypair.publicKey),
+        vaultAccountB: vaultAccount(values.mintBKeypair.publicKey),
+        lpVault: lpVault(),
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+  it('Deposit and withdraw', async () => {
+    await vaultDeposit(10 ** 6);
+
+    const shares = await connection.getTokenAccountBalance(sharesAccount());
+    expect(shares.value.amount).to.equal(String(10 ** 6));
+
+    await vaultWithdraw(10 ** 6);
+    const lp = await connection.getTokenAccountBalance(lpVault());
+    expect(lp.value.amount).to.equal('0');
+
+    await expectRevert(vaultWithdraw(1));
+  });
+
+  it('Compound idle tokens into liquidity', async () => {
+    await vaultDeposit(10 ** 6);
+
+    // Tokens sent to the vault (e.g. rewards) are redeposited at the pool ratio
+    await transfer(
+      connection,
+      values.admin,
+      values.holderAccountA,
+      vaultAccount(values.mintAKeypair.publicKey),
+      values.admin,
+      4 * 10 ** 5,
+    );
+    await transfer(
+      connection,
+      values.admin,
+      values.holderAccountB,
+      vaultAccount(values.mintBKeypair.publicKey),
+      values.admin,
+      10 ** 5,
+    );
+
+    await compoundVault();
+    const lp = await connection.getTokenAccountBalance(lpVault());
+    expect(Number(lp.value.amount)).to.be.greaterThan(10 ** 6);
+
+    // The crank can only run once per interval
+    await expectRevert(compoundVault());
+
+    // Shares now redeem for more liquidity than was deposited
+    const before = await connection.getTokenAccountBalance(values.liquidityAccount);
+    await vaultWithdraw(10 ** 6);
+    const after = await connection.getTokenAccountBalance(values.liquidityAccount);
+    expect(Number(after.value.amount) - Number(before.value.amount)).to.be.greaterThan(10 ** 6);
+  });
+});
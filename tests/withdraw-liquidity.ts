@@ -1,8 +1,10 @@
 import * as anchor from '@coral-xyz/anchor';
 import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { BN } from 'bn.js';
 import { expect } from 'chai';
 import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
-import { type TestValues, createValues, mintingTokens } from './utils';
+import { type TestValues, createValues, expectRevert, mintingTokens } from './utils';
 
 describe('Withdraw liquidity', () => {
   const provider = anchor.AnchorProvider.env();
@@ -16,7 +18,7 @@ describe('Withdraw liquidity', () => {
   beforeEach(async () => {
     values = createValues();
 
-    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey }).rpc();
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
 
     await mintingTokens({
       connection,
@@ -26,21 +28,28 @@ describe('Withdraw liquidity', () => {
     });
 
     await program.methods
-      .createPool()
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
       .accounts({
         amm: values.ammKey,
         pool: values.poolKey,
+        poolRef: values.poolRef,
+        programConfig: values.programConfig,
+        poolOracle: values.poolOracle,
         poolAuthority: values.poolAuthority,
         mintLiquidity: values.mintLiquidity,
         mintA: values.mintAKeypair.publicKey,
         mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
         poolAccountA: values.poolAccountA,
         poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
       })
       .rpc();
 
     await program.methods
-      .depositLiquidity(values.depositAmountA, values.depositAmountA)
+      .depositLiquidity(values.depositAmountA, values.depositAmountA, new BN(0), null, null, false)
       .accounts({
         pool: values.poolKey,
         poolAuthority: values.poolAuthority,
@@ -48,6 +57,8 @@ describe('Withdraw liquidity', () => {
         mintLiquidity: values.mintLiquidity,
         mintA: values.mintAKeypair.publicKey,
         mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
         poolAccountA: values.poolAccountA,
         poolAccountB: values.poolAccountB,
         depositorAccountLiquidity: values.liquidityAccount,
@@ -60,7 +71,7 @@ describe('Withdraw liquidity', () => {
 
   it('Withdraw everything', async () => {
     await program.methods
-      .withdrawLiquidity(values.depositAmountA.sub(values.minimumLiquidity))
+      .withdrawLiquidity(values.depositAmountA.sub(values.minimumLiquidity), new BN(0), new BN(0), null)
       .accounts({
         amm: values.ammKey,
         pool: values.poolKey,
@@ -69,6 +80,8 @@ describe('Withdraw liquidity', () => {
         mintLiquidity: values.mintLiquidity,
         mintA: values.mintAKeypair.publicKey,
         mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
         poolAccountA: values.poolAccountA,
         poolAccountB: values.poolAccountB,
         depositorAccountLiquidity: values.liquidityAccount,
@@ -87,4 +100,92 @@ describe('Withdraw liquidity', () => {
     expect(Number(depositTokenAccountB.value.amount)).to.be.lessThan(values.defaultSupply.toNumber());
     expect(Number(depositTokenAccountB.value.amount)).to.be.greaterThan(values.defaultSupply.sub(values.depositAmountA).toNumber());
   });
-});
\ No newline at end of file
+
+  it('Minimum withdrawal amount not met', async () => {
+    await expectRevert(
+      program.methods
+        .withdrawLiquidity(values.depositAmountA.sub(values.minimumLiquidity), values.depositAmountA, new BN(0), null)
+        .accounts({
+          amm: values.ammKey,
+          pool: values.poolKey,
+          poolAuthority: values.poolAuthority,
+          depositor: values.admin.publicKey,
+          mintLiquidity: values.mintLiquidity,
+          mintA: values.mintAKeypair.publicKey,
+          mintB: values.mintBKeypair.publicKey,
+          tokenProgramA: TOKEN_PROGRAM_ID,
+          tokenProgramB: TOKEN_PROGRAM_ID,
+          poolAccountA: values.poolAccountA,
+          poolAccountB: values.poolAccountB,
+          depositorAccountLiquidity: values.liquidityAccount,
+          depositorAccountA: values.holderAccountA,
+          depositorAccountB: values.holderAccountB,
+        })
+        .signers([values.admin])
+        .rpc(),
+    );
+  });
+
+  const withdrawPct = (bps: number) =>
+    program.methods
+      .withdrawLiquidityPct(bps, new BN(0), new BN(0), null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc();
+
+  it('Withdraw a percentage of the balance', async () => {
+    const balance = values.depositAmountA.sub(values.minimumLiquidity);
+    await withdrawPct(5000);
+
+    // Half of the live balance is burned
+    const liquidityTokenAccount = await connection.getTokenAccountBalance(values.liquidityAccount);
+    expect(liquidityTokenAccount.value.amount).to.equal(balance.sub(balance.divn(2)).toString());
+  });
+
+  it('Invalid percentage', async () => {
+    await expectRevert(withdrawPct(0));
+    await expectRevert(withdrawPct(10001));
+  });
+
+  it('Withdraw everything and close the LP account', async () => {
+    await program.methods
+      .withdrawAllAndClose(new BN(0), new BN(0), null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+      })
+      .signers([values.admin])
+      .rpc();
+
+    expect(await connection.getAccountInfo(values.liquidityAccount)).to.be.null;
+    const depositTokenAccountA = await connection.getTokenAccountBalance(values.holderAccountA);
+    expect(Number(depositTokenAccountA.value.amount)).to.be.greaterThan(values.defaultSupply.sub(values.depositAmountA).toNumber());
+  });
+});
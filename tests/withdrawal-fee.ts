@@ -0,0 +1,241 @@
+import * as anchor from '@coral-xyz/anchor';
+import type { Program } from '@coral-xyz/anchor';
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { PublicKey } from '@solana/web3.js';
+import { BN } from 'bn.js';
+import { expect } from 'chai';
+import type { AnchorSplAmm } from '../target/types/anchor_spl_amm';
+import { type TestValues, createValues, expectRevert, mintingTokens, sleep } from './utils';
+
+describe('Withdrawal fee', () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AnchorSplAmm as Program<AnchorSplAmm>;
+
+  let values: TestValues;
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods.createAmm(values.id, values.fee).accounts({ amm: values.ammKey, admin: values.admin.publicKey, programConfig: values.programConfig }).rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+
+    await program.methods
+      .createPool(new BN(0), { constantProduct: {} }, 0, 24, null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolRef: values.poolRef,
+        programConfig: values.programConfig,
+        poolOracle: values.poolOracle,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        protocolFeeAccountA: values.protocolFeeAccountA,
+        protocolFeeAccountB: values.protocolFeeAccountB,
+      })
+      .rpc();
+  });
+
+  const depositRecord = () =>
+    PublicKey.findProgramAddressSync(
+      [values.poolKey.toBuffer(), values.admin.publicKey.toBuffer(), Buffer.from('deposit_record')],
+      program.programId,
+    )[0];
+
+  const updateWithdrawalFeeConfig = (feeBps: number, decayPeriod = 86400) =>
+    program.methods
+      .updateWithdrawalFeeConfig({ feeBps, decayPeriod: new BN(decayPeriod) })
+      .accounts({ amm: values.ammKey, admin: values.admin.publicKey })
+      .signers([values.admin])
+      .rpc();
+
+  it('Fresh deposits pay the withdrawal fee', async () => {
+    await updateWithdrawalFeeConfig(1000);
+    await program.methods
+      .createDepositRecord()
+      .accounts({ pool: values.poolKey, depositRecord: depositRecord(), owner: values.admin.publicKey })
+      .signers([values.admin])
+      .rpc();
+
+    await program.methods
+      .depositLiquidity(values.depositAmountA, values.depositAmountB, new BN(0), null, null, false)
+      .accounts({
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+        depositRecord: depositRecord(),
+      })
+      .signers([values.admin])
+      .rpc();
+
+    const record = await program.account.depositRecord.fetch(depositRecord());
+    expect(record.liquidity.toNumber()).to.equal(2 * 10 ** 6 - values.minimumLiquidity.toNumber());
+
+    // Half of the supply is worth 2 * 10^6 A before the ~10% fee
+    const before = await connection.getTokenAccountBalance(values.holderAccountA);
+    await program.methods
+      .withdrawLiquidity(new BN(10 ** 6), new BN(0), new BN(0), null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+        depositRecord: depositRecord(),
+      })
+      .signers([values.admin])
+      .rpc();
+    const after = await connection.getTokenAccountBalance(values.holderAccountA);
+
+    const received = Number(after.value.amount) - Number(before.value.amount);
+    expect(received).to.be.lessThan(1.9 * 10 ** 6);
+    expect(received).to.be.greaterThan(1.7 * 10 ** 6);
+  });
+
+  const createDepositRecord = () =>
+    program.methods
+      .createDepositRecord()
+      .accounts({ pool: values.poolKey, depositRecord: depositRecord(), owner: values.admin.publicKey })
+      .signers([values.admin])
+      .rpc();
+
+  const deposit = (record: PublicKey | null) =>
+    program.methods
+      .depositLiquidity(values.depositAmountA, values.depositAmountB, new BN(0), null, null, false)
+      .accounts({
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+        depositRecord: record,
+      })
+      .signers([values.admin])
+      .rpc();
+
+  it('Deposits need a record while the fee is enabled', async () => {
+    await updateWithdrawalFeeConfig(1000);
+    await expectRevert(deposit(null));
+
+    await createDepositRecord();
+    await deposit(depositRecord());
+  });
+
+  it('Liquidity outside the record pays the full fee', async () => {
+    // Liquidity deposited before the fee existed has no record
+    await deposit(null);
+
+    // An empty record that has aged past the decay period must not waive the fee on that liquidity
+    await createDepositRecord();
+    await updateWithdrawalFeeConfig(1000, 1);
+    await sleep(3);
+
+    const before = await connection.getTokenAccountBalance(values.holderAccountA);
+    await program.methods
+      .withdrawLiquidity(new BN(10 ** 6), new BN(0), new BN(0), null)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+        depositRecord: depositRecord(),
+      })
+      .signers([values.admin])
+      .rpc();
+    const after = await connection.getTokenAccountBalance(values.holderAccountA);
+
+    // Half of the supply is worth 2 * 10^6 A, the full 10% fee applies
+    const received = Number(after.value.amount) - Number(before.value.amount);
+    expect(received).to.be.lessThanOrEqual(1.8 * 10 ** 6);
+  });
+
+  it('Withdrawals use the fee config of the pool AMM', async () => {
+    await createDepositRecord();
+    await updateWithdrawalFeeConfig(1000);
+    await deposit(depositRecord());
+
+    // Another AMM without a withdrawal fee cannot stand in for the pool's AMM
+    const foreign = createValues();
+    await program.methods.createAmm(foreign.id, 0).accounts({ amm: foreign.ammKey, admin: foreign.admin.publicKey, programConfig: foreign.programConfig }).rpc();
+
+    const withdraw = (amm: PublicKey) =>
+      program.methods
+        .withdrawLiquidity(new BN(10 ** 6), new BN(0), new BN(0), null)
+        .accounts({
+          amm,
+          pool: values.poolKey,
+          poolAuthority: values.poolAuthority,
+          depositor: values.admin.publicKey,
+          mintLiquidity: values.mintLiquidity,
+          mintA: values.mintAKeypair.publicKey,
+          mintB: values.mintBKeypair.publicKey,
+          tokenProgramA: TOKEN_PROGRAM_ID,
+          tokenProgramB: TOKEN_PROGRAM_ID,
+          poolAccountA: values.poolAccountA,
+          poolAccountB: values.poolAccountB,
+          depositorAccountLiquidity: values.liquidityAccount,
+          depositorAccountA: values.holderAccountA,
+          depositorAccountB: values.holderAccountB,
+          depositRecord: depositRecord(),
+        })
+        .signers([values.admin])
+        .rpc();
+    await expectRevert(withdraw(foreign.ammKey));
+    await withdraw(values.ammKey);
+  });
+
+  it('Invalid config', async () => {
+    await expectRevert(updateWithdrawalFeeConfig(10001));
+  });
+});